@@ -297,7 +297,9 @@ fn should_send(
         return false;
     }
     match notification.kind {
-        NotificationKind::DirectMessage => settings.direct_messages,
+        NotificationKind::DirectMessage | NotificationKind::GroupDirectMessage => {
+            settings.direct_messages
+        }
         NotificationKind::Mention | NotificationKind::Reply => settings.mentions_and_replies,
         NotificationKind::Followed | NotificationKind::Repost | NotificationKind::QuoteRepost => {
             settings.follows_and_reposts
@@ -310,6 +312,7 @@ fn should_send(
 fn notification_title(kind: &NotificationKind) -> &'static str {
     match kind {
         NotificationKind::DirectMessage => "Direct message",
+        NotificationKind::GroupDirectMessage => "Group message",
         NotificationKind::Mention => "Mention",
         NotificationKind::Reply => "Reply",
         NotificationKind::Followed => "New follower",
@@ -327,7 +330,10 @@ fn notification_body(
 ) -> Option<String> {
     if !preview_body {
         return Some(
-            if matches!(kind, NotificationKind::DirectMessage) {
+            if matches!(
+                kind,
+                NotificationKind::DirectMessage | NotificationKind::GroupDirectMessage
+            ) {
                 "Open kukuri to read this message."
             } else {
                 "Open kukuri to view this activity."
@@ -8,8 +8,9 @@ use kukuri_desktop_runtime::{
     FreezePrivateChannelRequest, ImportChannelAccessTokenRequest, ImportFriendOnlyGrantRequest,
     ImportFriendPlusShareRequest, ImportPeerTicketRequest, ImportPrivateChannelInviteRequest,
     IndexQueryResponse, LeavePrivateChannelRequest, ListJoinedPrivateChannelsRequest,
-    PreviewChannelAccessTokenRequest, RotatePrivateChannelRequest, SetChannelGossipEnabledRequest,
-    SetCommunityNodeConfigRequest, SetCommunityNodeInviteCodeRequest, SetDiscoverySeedsRequest,
+    PreviewChannelAccessTokenRequest, RebuildTopicProjectionsRequest, RotatePrivateChannelRequest, SetChannelGossipEnabledRequest,
+    SetCommunityNodeConfigRequest, SetCommunityNodeInviteCodeRequest,
+    SetCommunityNodeModerationPolicyRequest, SetDiscoverySeedsRequest,
     SetTopicGossipEnabledRequest,
     SubmitCommunityNodeReportRequest, SubmitCommunityNodeReportResult,
     SubmitIndexingRequestResponse, TrustUserReadResponse, RelationReadResponse,
@@ -193,6 +194,24 @@ pub async fn get_sync_status(
     state.runtime.get_sync_status().await.map_err(map_error)
 }
 
+#[tauri::command]
+pub async fn get_storage_usage(
+    state: tauri::State<'_, DesktopState>,
+) -> Result<kukuri_app_api::StorageUsageView, CommandError> {
+    state.runtime.get_storage_usage().await.map_err(map_error)
+}
+
+#[tauri::command]
+pub async fn collect_blob_garbage(
+    state: tauri::State<'_, DesktopState>,
+) -> Result<kukuri_app_api::BlobGarbageCollectionView, CommandError> {
+    state
+        .runtime
+        .collect_blob_garbage()
+        .await
+        .map_err(map_error)
+}
+
 #[tauri::command]
 pub async fn get_discovery_config(
     state: tauri::State<'_, DesktopState>,
@@ -240,6 +259,18 @@ pub async fn unsubscribe_topic(
         .map_err(map_error)
 }
 
+#[tauri::command]
+pub async fn rebuild_topic_projections(
+    state: tauri::State<'_, DesktopState>,
+    request: RebuildTopicProjectionsRequest,
+) -> Result<usize, CommandError> {
+    state
+        .runtime
+        .rebuild_topic_projections(request)
+        .await
+        .map_err(map_error)
+}
+
 #[tauri::command]
 pub async fn set_topic_gossip_enabled(
     state: tauri::State<'_, DesktopState>,
@@ -400,6 +431,29 @@ pub async fn fetch_community_node_manifest(
         .map_err(map_error)
 }
 
+#[tauri::command]
+pub async fn set_community_node_moderation_policy(
+    state: tauri::State<'_, DesktopState>,
+    request: SetCommunityNodeModerationPolicyRequest,
+) -> Result<kukuri_app_api::CommunityModerationPolicyView, CommandError> {
+    state
+        .runtime
+        .set_community_node_moderation_policy(request)
+        .await
+        .map_err(map_error)
+}
+
+#[tauri::command]
+pub async fn list_community_node_moderation_policies(
+    state: tauri::State<'_, DesktopState>,
+) -> Result<Vec<kukuri_app_api::CommunityModerationPolicyView>, CommandError> {
+    state
+        .runtime
+        .list_community_node_moderation_policies()
+        .await
+        .map_err(map_error)
+}
+
 #[tauri::command]
 pub async fn submit_community_node_report(
     state: tauri::State<'_, DesktopState>,
@@ -460,6 +514,30 @@ pub async fn recommend_community_node_index(
         .map_err(CommandError::from)
 }
 
+#[tauri::command]
+pub async fn search_community_node_people(
+    state: tauri::State<'_, DesktopState>,
+    request: CommunityNodeIndexQueryRequest,
+) -> Result<IndexQueryResponse, CommandError> {
+    state
+        .runtime
+        .search_community_node_people(request)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn list_community_node_live_now(
+    state: tauri::State<'_, DesktopState>,
+    request: CommunityNodeIndexQueryRequest,
+) -> Result<IndexQueryResponse, CommandError> {
+    state
+        .runtime
+        .list_community_node_live_now(request)
+        .await
+        .map_err(CommandError::from)
+}
+
 #[tauri::command]
 pub async fn read_community_node_trust_user(
     state: tauri::State<'_, DesktopState>,
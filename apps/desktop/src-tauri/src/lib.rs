@@ -179,6 +179,8 @@ pub fn run() {
             commands::direct_messages::clear_direct_message,
             commands::direct_messages::get_direct_message_status,
             commands::community_node::get_sync_status,
+            commands::community_node::get_storage_usage,
+            commands::community_node::collect_blob_garbage,
             commands::community_node::get_discovery_config,
             commands::live_game::list_live_sessions,
             commands::live_game::create_live_session,
@@ -196,6 +198,7 @@ pub fn run() {
             commands::community_node::import_peer_ticket,
             commands::community_node::set_discovery_seeds,
             commands::community_node::unsubscribe_topic,
+            commands::community_node::rebuild_topic_projections,
             commands::community_node::set_topic_gossip_enabled,
             commands::community_node::set_channel_gossip_enabled,
             commands::community_node::get_local_peer_ticket,
@@ -212,11 +215,15 @@ pub fn run() {
             commands::community_node::accept_community_node_consents,
             commands::community_node::refresh_community_node_metadata,
             commands::community_node::fetch_community_node_manifest,
+            commands::community_node::set_community_node_moderation_policy,
+            commands::community_node::list_community_node_moderation_policies,
             commands::community_node::submit_community_node_report,
             commands::community_node::submit_community_node_indexing_request,
             commands::community_node::search_community_node_index,
             commands::community_node::discover_community_node_index,
             commands::community_node::recommend_community_node_index,
+            commands::community_node::search_community_node_people,
+            commands::community_node::list_community_node_live_now,
             commands::community_node::read_community_node_trust_user,
             commands::community_node::read_community_node_relation_user,
             commands::community_node::list_community_node_relation_neighbors,
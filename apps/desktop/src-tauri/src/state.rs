@@ -246,6 +246,7 @@ pub(crate) fn build_desktop_state(
 
     spawn_runtime_event_bridge(app_handle, &runtime);
     tauri::async_runtime::block_on(runtime.start_sync_status_observer());
+    tauri::async_runtime::block_on(runtime.start_linked_device_sync());
 
     Ok(DesktopState { runtime })
 }
@@ -531,3 +531,43 @@ test('post card renders bookmark as an icon-only action with an accessible label
   await user.click(bookmarkButton);
   expect(onToggleBookmark).toHaveBeenCalledWith(createView().post);
 });
+
+test('post card keeps blurred moderation content behind an explicit reveal', async () => {
+  const user = userEvent.setup();
+  const base = createView();
+  render(
+    <PostCard
+      view={{
+        ...base,
+        post: {
+          ...base.post,
+          content: 'flagged body',
+          moderation: {
+            treatment: 'blur',
+            labels: ['spam'],
+            sources: [
+              {
+                node_base_url: 'https://node.example',
+                event_id: 'event-1',
+                target_kind: 'post',
+                action: 'risk_label',
+                reason_code: 'general_moderation',
+                labels: ['spam'],
+              },
+            ],
+          },
+        },
+      }}
+      onOpenAuthor={() => undefined}
+      onOpenThread={() => undefined}
+      onReply={() => undefined}
+    />
+  );
+
+  expect(screen.getByText('Community node advisory: spam')).toBeInTheDocument();
+  expect(screen.queryByText('flagged body')).not.toBeInTheDocument();
+
+  await user.click(screen.getByRole('button', { name: 'Show post' }));
+
+  expect(screen.getByText('flagged body')).toBeInTheDocument();
+});
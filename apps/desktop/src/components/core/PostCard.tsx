@@ -178,6 +178,11 @@ export function PostCard({
         : localState === 'failed'
           ? t('feed.localFailed')
           : null;
+  // community node の moderation event は端末内 policy 適用済み(hide は一覧に来ない)。
+  // blur は利用者が明示的に開くまで本文を出さず、label は注意表示のみ。
+  const moderation = post.moderation ?? null;
+  const [moderationRevealed, setModerationRevealed] = useState(false);
+  const moderationBlurred = moderation?.treatment === 'blur' && !moderationRevealed;
   const primaryContent = showRepostAsPrimary && repostSource ? repostSource.content : post.content;
   const hasPrimaryContent = isPendingText || primaryContent.trim().length > 0;
   const reactionSummary = post.reaction_summary ?? [];
@@ -501,7 +506,25 @@ export function PostCard({
         />
       ) : null}
 
-      {readOnly ? (
+      {moderation ? (
+        <div
+          className='topic-diagnostic topic-diagnostic-secondary'
+          data-testid={`moderation-notice-${post.object_id}`}
+        >
+          <span>
+            {t('feed.moderationAdvisory', {
+              labels: moderation.labels.length > 0 ? moderation.labels.join(', ') : '-',
+            })}
+          </span>
+          {moderationBlurred ? (
+            <Button variant='secondary' type='button' onClick={() => setModerationRevealed(true)}>
+              {t('feed.moderationReveal')}
+            </Button>
+          ) : null}
+        </div>
+      ) : null}
+
+      {moderationBlurred ? null : readOnly ? (
         <div className='post-link post-layout-safe'>{contentBlock}</div>
       ) : (
         <div
@@ -73,6 +73,7 @@ const syncStatus: SyncStatus = {
     configured_seed_peer_ids: [],
     bootstrap_seed_peer_ids: ['community-node'],
     manual_ticket_peer_ids: [],
+    lan_peer_ids: [],
     connected_peer_ids: [],
     docs_assist_peer_ids: [],
     blob_assist_peer_ids: [],
@@ -80,6 +81,7 @@ const syncStatus: SyncStatus = {
   },
   gossip_disabled_topics: [],
   gossip_disabled_channels: [],
+  blob_downloads: { queued: 0, active: 0, failed: 0 },
 };
 
 function StoryFrame({ children }: { children: React.ReactNode }) {
@@ -183,6 +183,7 @@ function createSyncStatus(): SyncStatus {
       configured_seed_peer_ids: [],
       bootstrap_seed_peer_ids: [],
       manual_ticket_peer_ids: [],
+      lan_peer_ids: [],
       connected_peer_ids: [],
       docs_assist_peer_ids: [],
       blob_assist_peer_ids: [],
@@ -190,6 +191,7 @@ function createSyncStatus(): SyncStatus {
     },
     gossip_disabled_topics: [],
     gossip_disabled_channels: [],
+    blob_downloads: { queued: 0, active: 0, failed: 0 },
   };
 }
 
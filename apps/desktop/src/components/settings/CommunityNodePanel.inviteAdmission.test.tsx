@@ -85,6 +85,8 @@ test('provides Japanese reasons and next steps for every stable admission code',
     'INVITE_REVOKED',
     'NOT_ALLOWLISTED',
     'BANNED',
+    'SUSPENDED',
+    'VOUCH_REQUIRED',
   ];
 
   for (const code of codes) {
@@ -121,6 +121,7 @@ export function createDiscoveryPanelFixture(): DiscoveryPanelView {
       { label: i18n.t('settings:discovery.diagnostics.connectedPeers'), value: 'peer-a', monospace: true },
       { label: i18n.t('settings:discovery.diagnostics.relayAssistedPeers'), value: 'relay-peer', monospace: true },
       { label: i18n.t('settings:discovery.diagnostics.manualTicketPeers'), value: 'peer-ticket-1', monospace: true },
+      { label: i18n.t('settings:discovery.diagnostics.lanPeers'), value: 'lan-peer-1', monospace: true },
       { label: i18n.t('settings:discovery.diagnostics.communityBootstrapPeers'), value: 'bootstrap-peer-1', monospace: true },
       { label: i18n.t('settings:discovery.diagnostics.configuredSeedIds'), value: 'seed-peer-1', monospace: true },
       { label: i18n.t('settings:discovery.diagnostics.discoveryError'), value: i18n.t('common:fallbacks.none') },
@@ -335,6 +335,9 @@ export const syncStatusFull = {
     "manual_ticket_peer_ids": [
       "manual-1"
     ],
+    "lan_peer_ids": [
+      "lan-1"
+    ],
     "connected_peer_ids": [
       "peer-a"
     ],
@@ -350,7 +353,12 @@ export const syncStatusFull = {
   ],
   "gossip_disabled_channels": [
     "chan-quiet"
-  ]
+  ],
+  "blob_downloads": {
+    "queued": 3,
+    "active": 1,
+    "failed": 1
+  }
 } satisfies SyncStatus;
 
 // sync_status.minimal.json
@@ -377,6 +385,7 @@ export const syncStatusMinimal = {
     "configured_seed_peer_ids": [],
     "bootstrap_seed_peer_ids": [],
     "manual_ticket_peer_ids": [],
+    "lan_peer_ids": [],
     "connected_peer_ids": [],
     "docs_assist_peer_ids": [],
     "blob_assist_peer_ids": [],
@@ -384,7 +393,12 @@ export const syncStatusMinimal = {
     "last_discovery_error": null
   },
   "gossip_disabled_topics": [],
-  "gossip_disabled_channels": []
+  "gossip_disabled_channels": [],
+  "blob_downloads": {
+    "queued": 0,
+    "active": 0,
+    "failed": 0
+  }
 } satisfies SyncStatus;
 
 // notification_view.json
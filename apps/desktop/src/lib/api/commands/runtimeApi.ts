@@ -5,6 +5,7 @@ import type {
   BookmarkedPostView,
   ChannelAccessTokenExport,
   ChannelAccessTokenPreview,
+  CommunityModerationPolicyView,
   CommunityNodeConfig,
   CommunityNodeIndexingRequest,
   CommunityNodeIndexQueryRequest,
@@ -95,6 +96,7 @@ import type {
   SetChannelGossipEnabledRequest,
   SetCommunityNodeConfigRequest,
   SetCommunityNodeInviteCodeRequest,
+  SetCommunityNodeModerationPolicyRequest,
   SetDiscoverySeedsRequest,
   SetTopicGossipEnabledRequest,
   ToggleReactionRequest,
@@ -658,6 +660,22 @@ export const runtimeApi: DesktopApi = {
       } satisfies CommunityNodeTargetRequest,
     });
   }),
+  setCommunityNodeModerationPolicy: command(
+    'setCommunityNodeModerationPolicy',
+    async (baseUrl, policy) => {
+      return invokeDesktop<CommunityModerationPolicyView>('set_community_node_moderation_policy', {
+        request: {
+          base_url: baseUrl,
+          policy,
+        } satisfies SetCommunityNodeModerationPolicyRequest,
+      });
+    }
+  ),
+  listCommunityNodeModerationPolicies: command('listCommunityNodeModerationPolicies', async () => {
+    return invokeDesktop<CommunityModerationPolicyView[]>(
+      'list_community_node_moderation_policies'
+    );
+  }),
   readCommunityNodeTrustUser: command('readCommunityNodeTrustUser', async (request) => {
     return invokeDesktop<TrustUserReadResponse>('read_community_node_trust_user', {
       request: request satisfies CommunityNodeUserAdvisoryRequest,
@@ -706,6 +724,16 @@ export const runtimeApi: DesktopApi = {
       request: request satisfies CommunityNodeIndexQueryRequest,
     });
   }),
+  searchCommunityNodePeople: command('searchCommunityNodePeople', async (request) => {
+    return invokeDesktop<IndexQueryResponse>('search_community_node_people', {
+      request: request satisfies CommunityNodeIndexQueryRequest,
+    });
+  }),
+  listCommunityNodeLiveNow: command('listCommunityNodeLiveNow', async (request) => {
+    return invokeDesktop<IndexQueryResponse>('list_community_node_live_now', {
+      request: request satisfies CommunityNodeIndexQueryRequest,
+    });
+  }),
   submitCommunityNodeIndexingRequest: command(
     'submitCommunityNodeIndexingRequest',
     async (request) => {
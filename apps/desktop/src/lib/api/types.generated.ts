@@ -7,7 +7,7 @@ export type ChannelAudienceKind = "invite_only" | "friend_only" | "friend_plus";
 
 export type ChannelSharingState = "open" | "frozen";
 
-export type DiscoveryMode = "static_peer" | "seeded_dht";
+export type DiscoveryMode = "static_peer" | "seeded_dht" | "lan";
 
 export type ConnectMode = "direct_only" | "direct_or_relay";
 
@@ -19,7 +19,9 @@ export type BlobViewStatus = "Missing" | "Available" | "Pinned";
 
 export type DeliveryState = "Live" | "DurableRecovering" | "DurableReady" | "Offline";
 
-export type NotificationKind = "mention" | "reply" | "repost" | "quote_repost" | "direct_message" | "followed";
+export type NotificationKind = "mention" | "reply" | "repost" | "quote_repost" | "direct_message" | "followed" | "group_direct_message";
+
+export type CommunityModerationPolicy = "hide" | "blur" | "label" | "ignore";
 
 export type ChannelAccessTokenKind = "invite" | "grant" | "share";
 
@@ -49,7 +51,13 @@ export type ContentObservationView = { node_base_url: string, capability: string
 
 export type ContentProvenanceView = { canonical_source: string, observed_via: Array<ContentObservationView>, };
 
-export type PostView = { object_id: string, envelope_id: string, author_pubkey: string, author_name?: string | null, author_display_name?: string | null, author_picture?: string | null, author_picture_asset?: ProfileAssetView | null, following: boolean, followed_by: boolean, mutual: boolean, friend_of_friend: boolean, provenance?: ContentProvenanceView | null, content: string, content_status: BlobViewStatus, attachments: Array<AttachmentView>, created_at: number, reply_to?: string | null, reply_preview?: ReplyPreviewView | null, root_id?: string | null, object_kind: string, published_topic_id?: string | null, origin_topic_id?: string | null, repost_of?: RepostSourceView | null, repost_commentary?: string | null, is_threadable: boolean, channel_id?: string | null, audience_label: string, reaction_summary?: Array<ReactionSummaryView> | null, my_reactions?: Array<ReactionKeyView> | null, };
+export type ContentModerationSourceView = { node_base_url: string, event_id: string, target_kind: string, action: string, reason_code: string, labels: Array<string>, };
+
+export type ContentModerationView = { treatment: CommunityModerationPolicy, labels: Array<string>, sources: Array<ContentModerationSourceView>, };
+
+export type CommunityModerationPolicyView = { node_base_url: string, policy: CommunityModerationPolicy, updated_at: number | null, };
+
+export type PostView = { object_id: string, envelope_id: string, author_pubkey: string, author_name?: string | null, author_display_name?: string | null, author_picture?: string | null, author_picture_asset?: ProfileAssetView | null, following: boolean, followed_by: boolean, mutual: boolean, friend_of_friend: boolean, provenance?: ContentProvenanceView | null, moderation?: ContentModerationView | null, content: string, content_status: BlobViewStatus, attachments: Array<AttachmentView>, created_at: number, reply_to?: string | null, reply_preview?: ReplyPreviewView | null, root_id?: string | null, object_kind: string, published_topic_id?: string | null, origin_topic_id?: string | null, repost_of?: RepostSourceView | null, repost_commentary?: string | null, is_threadable: boolean, channel_id?: string | null, audience_label: string, reaction_summary?: Array<ReactionSummaryView> | null, my_reactions?: Array<ReactionKeyView> | null, };
 
 export type BookmarkedPostView = { bookmarked_at: number, post: PostView, };
 
@@ -71,6 +79,12 @@ export type TimelineView = { items: Array<PostView>, next_cursor?: TimelineCurso
 
 export type DirectMessageTimelineView = { items: Array<DirectMessageMessageView>, next_cursor?: TimelineCursor | null, };
 
+export type LinkedDeviceView = { device_id: string, device_label: string, authorized_by_device_id?: string | null, authorized_at: number, is_current_device: boolean, };
+
+export type DevicePairingView = { device_id: string, device_label: string, ticket: string, pairing_code: string, expires_at: number, };
+
+export type IdentityBackupImportView = { author_pubkey: string, created_at: number, private_channel_count: number, direct_message_count: number, };
+
 export type JoinedPrivateChannelView = { topic_id: string, channel_id: string, label: string, creator_pubkey: string, owner_pubkey: string, joined_via_pubkey?: string | null, audience_kind: ChannelAudienceKind, is_owner: boolean, current_epoch_id: string, archived_epoch_ids: Array<string>, sharing_state: ChannelSharingState, rotation_required: boolean, participant_count: number, stale_participant_count: number, };
 
 export type PrivateChannelEpochCapability = { epoch_id: string, namespace_secret_hex: string, };
@@ -83,9 +97,15 @@ export type ChannelAccessTokenPreview = { kind: ChannelAccessTokenKind, topic_id
 
 export type TopicSyncStatus = { topic: string, joined: boolean, delivery_state: DeliveryState, peer_count: number, connected_peers: Array<string>, docs_assist_peer_ids: Array<string>, configured_peer_ids: Array<string>, missing_peer_ids: Array<string>, active_path: ConnectionPath, rendezvous_peer_ids: Array<string>, fallback_peer_ids: Array<string>, last_received_at?: number | null, last_docs_activity_at?: number | null, status_detail: string, last_error?: string | null, };
 
-export type DiscoveryStatus = { mode: DiscoveryMode, connect_mode: ConnectMode, active_path: ConnectionPath, fallback_peer_ids: Array<string>, env_locked: boolean, configured_seed_peer_ids: Array<string>, bootstrap_seed_peer_ids: Array<string>, manual_ticket_peer_ids: Array<string>, connected_peer_ids: Array<string>, docs_assist_peer_ids: Array<string>, blob_assist_peer_ids: Array<string>, local_endpoint_id: string, last_discovery_error?: string | null, };
+export type DiscoveryStatus = { mode: DiscoveryMode, connect_mode: ConnectMode, active_path: ConnectionPath, fallback_peer_ids: Array<string>, env_locked: boolean, configured_seed_peer_ids: Array<string>, bootstrap_seed_peer_ids: Array<string>, manual_ticket_peer_ids: Array<string>, lan_peer_ids: Array<string>, connected_peer_ids: Array<string>, docs_assist_peer_ids: Array<string>, blob_assist_peer_ids: Array<string>, local_endpoint_id: string, last_discovery_error?: string | null, };
+
+export type BlobDownloadQueueStatus = { queued: number, active: number, failed: number, };
+
+export type SyncStatus = { connected: boolean, delivery_state: DeliveryState, last_sync_ts?: number | null, peer_count: number, pending_events: number, status_detail: string, last_error?: string | null, configured_peers: Array<string>, subscribed_topics: Array<string>, active_path: ConnectionPath, fallback_peer_ids: Array<string>, topic_diagnostics: Array<TopicSyncStatus>, local_author_pubkey: string, discovery: DiscoveryStatus, gossip_disabled_topics: Array<string>, gossip_disabled_channels: Array<string>, blob_downloads: BlobDownloadQueueStatus, };
+
+export type StorageUsageView = { pinned_blobs: number, pinned_bytes: number, cached_blobs: number, cached_bytes: number, unreferenced_blobs: number, cache_quota_bytes: number, };
 
-export type SyncStatus = { connected: boolean, delivery_state: DeliveryState, last_sync_ts?: number | null, peer_count: number, pending_events: number, status_detail: string, last_error?: string | null, configured_peers: Array<string>, subscribed_topics: Array<string>, active_path: ConnectionPath, fallback_peer_ids: Array<string>, topic_diagnostics: Array<TopicSyncStatus>, local_author_pubkey: string, discovery: DiscoveryStatus, gossip_disabled_topics: Array<string>, gossip_disabled_channels: Array<string>, };
+export type BlobGarbageCollectionView = { evicted_blobs: number, evicted_bytes: number, usage: StorageUsageView, };
 
 export type LiveSessionStatus = "Scheduled" | "Live" | "Paused" | "Ended";
 
@@ -166,7 +186,7 @@ previously_accepted_version?: number | null, };
 
 export type CommunityNodeConsentStatus = { all_required_accepted: boolean, items: Array<CommunityNodeConsentItem>, };
 
-export type CommunityNodeAdmissionRejectionCode = "INVITE_REQUIRED" | "INVITE_INVALID" | "INVITE_EXPIRED" | "INVITE_EXHAUSTED" | "INVITE_REVOKED" | "NOT_ALLOWLISTED" | "BANNED";
+export type CommunityNodeAdmissionRejectionCode = "INVITE_REQUIRED" | "INVITE_INVALID" | "INVITE_EXPIRED" | "INVITE_EXHAUSTED" | "INVITE_REVOKED" | "NOT_ALLOWLISTED" | "BANNED" | "SUSPENDED" | "VOUCH_REQUIRED";
 
 export type CommunityNodeAdmissionRejection = { code: CommunityNodeAdmissionRejectionCode, message: string, };
 
@@ -196,7 +216,23 @@ export type CommunityNodeIndexQueryRequest = { base_url: string, query?: string
  * scope_kind が private_channel のとき必須。所属証明(channel secret)を参加中
  * チャンネルの capability から引くために使う(#711)。
  */
-topic_id?: string | null, limit?: number | null, };
+topic_id?: string | null, limit?: number | null, 
+/**
+ * 直前 page の `next_cursor`。同じ条件のまま渡すと続きの page を返す(無限スクロール)。
+ */
+cursor?: string | null, author_pubkey?: string | null, 
+/**
+ * `created_at` の下限(unix 秒、含む)。
+ */
+since?: number | null, 
+/**
+ * `created_at` の上限(unix 秒、含まない)。
+ */
+until?: number | null, 
+/**
+ * search / discovery の並び順。recommendation は順位付けのため指定できない。
+ */
+order?: IndexSortOrder | null, };
 
 export type CommunityNodeIndexQueryError = { code: string, message: string, status?: number | null, retry_after_seconds?: number | null, };
 
@@ -218,11 +254,37 @@ export type CommunityNodeIndexingRequestError = { code: string, message: string,
 
 export type IndexScopeKind = "public_topic" | "private_channel";
 
+export type IndexSortOrder = "newest" | "oldest";
+
 export type IndexingRequestStatus = "pending" | "approved" | "rejected";
 
-export type IndexEntryView = { scope_kind: IndexScopeKind, scope_id: string, object_id: string, author_pubkey: string, text: string, created_at: number, };
+export type RecommendationReason = "recent" | "engaged" | "trusted_author" | "close_to_viewer";
 
-export type IndexQueryResponse = { entries: Array<IndexEntryView>, };
+export type IndexEntryKind = "post" | "profile" | "session";
+
+export type IndexProfileView = { name?: string | null, display_name?: string | null, about?: string | null, };
+
+export type IndexSessionKind = "live_session" | "game_room" | "metaverse_room";
+
+export type IndexSessionStatus = "scheduled" | "live" | "waiting" | "running" | "paused";
+
+export type IndexSessionView = { session_kind: IndexSessionKind, session_id: string, topic_id: string, status: IndexSessionStatus, title: string, description?: string | null, participant_count?: number | null, };
+
+export type IndexEntryView = { scope_kind: IndexScopeKind, scope_id: string, object_id: string, author_pubkey: string, text: string, created_at: number, reasons?: Array<RecommendationReason> | null, 
+/**
+ * Omitted for posts, so the post wire shape is unchanged.
+ */
+entry_kind?: IndexEntryKind | null, 
+/**
+ * Signed profile fields, present only on `profile` entries.
+ */
+profile?: IndexProfileView | null, 
+/**
+ * Session state, present only on `session` entries.
+ */
+session?: IndexSessionView | null, };
+
+export type IndexQueryResponse = { entries: Array<IndexEntryView>, next_cursor?: string | null, };
 
 export type SubmitIndexingRequestResponse = { request_id: string, status: IndexingRequestStatus, };
 
@@ -317,7 +379,9 @@ reference_id?: string | null,
  */
 disputed_risk_signal_id?: string | null, };
 
-export type RuntimeEvent = { "type": "notification_status_changed" } | { "type": "sync_status_changed", sync_status?: SyncStatus | null, community_node_statuses?: Array<CommunityNodeNodeStatus> | null, };
+export type ContentEvent = { "kind": "post_changed", topic_id: string, channel_id?: string | null, object_id: string, author_pubkey: string, } | { "kind": "thread_reply", topic_id: string, channel_id?: string | null, root_object_id: string, object_id: string, author_pubkey: string, } | { "kind": "reaction_changed", topic_id: string, target_object_id: string, } | { "kind": "direct_message_received", peer_pubkey: string, dm_id: string, message_id: string, } | { "kind": "direct_message_acked", peer_pubkey: string, dm_id: string, message_id: string, } | { "kind": "live_session_changed", topic_id: string, session_id: string, } | { "kind": "game_room_changed", topic_id: string, room_id: string, } | { "kind": "profile_updated", author_pubkey: string, };
+
+export type RuntimeEvent = { "type": "notification_status_changed" } | { "type": "sync_status_changed", sync_status?: SyncStatus | null, community_node_statuses?: Array<CommunityNodeNodeStatus> | null, } | { "type": "content_changed", event: ContentEvent, };
 
 export type CreatePostRequest = { topic: string, content: string, reply_to?: string | null, channel_ref: ChannelRef, attachments: Array<CreateAttachmentRequest>, };
 
@@ -353,6 +417,8 @@ export type ImportPeerTicketRequest = { ticket: string, };
 
 export type UnsubscribeTopicRequest = { topic: string, };
 
+export type RebuildTopicProjectionsRequest = { topic: string, };
+
 export type SetTopicGossipEnabledRequest = { topic: string, enabled: boolean, };
 
 export type SetChannelGossipEnabledRequest = { topic: string, channel: string, enabled: boolean, };
@@ -375,6 +441,14 @@ export type SendDirectMessageRequest = { pubkey: string, text?: string | null, r
 
 export type DeleteDirectMessageMessageRequest = { pubkey: string, message_id: string, };
 
+export type CreateDevicePairingRequest = { device_label: string, };
+
+export type AcceptDevicePairingRequest = { ticket: string, pairing_code: string, };
+
+export type ExportIdentityBackupRequest = { passphrase: string, };
+
+export type ImportIdentityBackupRequest = { bundle: string, passphrase: string, };
+
 export type SetMyProfileRequest = { name?: string | null, display_name?: string | null, about?: string | null, picture?: string | null, picture_upload?: CreateAttachmentRequest | null, clear_picture: boolean, };
 
 export type ListLiveSessionsRequest = { topic: string, scope: TimelineScope, };
@@ -433,6 +507,8 @@ export type SetCommunityNodeConfigRequest = { nodes: Array<SetCommunityNodeConfi
 
 export type SetCommunityNodeInviteCodeRequest = { base_url: string, invite_code?: string | null, };
 
+export type SetCommunityNodeModerationPolicyRequest = { base_url: string, policy: CommunityModerationPolicy, };
+
 export type CommunityNodeTargetRequest = { base_url: string, };
 
 export type AcceptCommunityNodeConsentsRequest = { base_url: string, policy_slugs: Array<string>, };
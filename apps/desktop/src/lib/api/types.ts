@@ -10,6 +10,8 @@ import type {
   ChannelAccessTokenPreview,
   ChannelAudienceKind,
   ChannelRef,
+  CommunityModerationPolicy,
+  CommunityModerationPolicyView,
   CommunityNodeConfig,
   CommunityNodeIndexingRequest,
   CommunityNodeIndexQueryRequest,
@@ -358,6 +360,11 @@ export interface DesktopApi {
   ): Promise<CommunityNodeNodeStatus>;
   refreshCommunityNodeMetadata(baseUrl: string): Promise<CommunityNodeNodeStatus>;
   fetchCommunityNodeManifest(baseUrl: string): Promise<CommunityNodeManifestFetch>;
+  setCommunityNodeModerationPolicy(
+    baseUrl: string,
+    policy: CommunityModerationPolicy
+  ): Promise<CommunityModerationPolicyView>;
+  listCommunityNodeModerationPolicies(): Promise<CommunityModerationPolicyView[]>;
   readCommunityNodeTrustUser(
     request: CommunityNodeUserAdvisoryRequest
   ): Promise<TrustUserReadResponse>;
@@ -379,6 +386,12 @@ export interface DesktopApi {
   recommendCommunityNodeIndex(
     request: CommunityNodeIndexQueryRequest
   ): Promise<IndexQueryResponse>;
+  searchCommunityNodePeople(
+    request: CommunityNodeIndexQueryRequest
+  ): Promise<IndexQueryResponse>;
+  listCommunityNodeLiveNow(
+    request: CommunityNodeIndexQueryRequest
+  ): Promise<IndexQueryResponse>;
   submitCommunityNodeIndexingRequest(
     request: CommunityNodeIndexingRequest
   ): Promise<SubmitIndexingRequestResponse>;
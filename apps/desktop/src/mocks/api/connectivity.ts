@@ -1,14 +1,36 @@
 import {
   type BlobMediaPayload,
+  type CommunityModerationPolicyView,
   type CommunityNodeIndexQueryRequest,
   type DesktopApi,
+  type GameRoomStatus,
   type IndexQueryResponse,
+  type IndexSessionStatus,
+  type LiveSessionStatus,
   type SubmitIndexingRequestResponse,
 } from '@/lib/api';
 
 import { cloneSyncStatus } from '../desktopMockModel';
 import { type MockRuntime } from '../mockRuntime';
 
+// live now は終了した session を載せないため、Ended は対応表に無い。
+const LIVE_SESSION_INDEX_STATUS: Record<
+  Exclude<LiveSessionStatus, 'Ended'>,
+  IndexSessionStatus
+> = {
+  Scheduled: 'scheduled',
+  Live: 'live',
+  Paused: 'paused',
+};
+
+const GAME_ROOM_INDEX_STATUS: Record<Exclude<GameRoomStatus, 'Ended'>, IndexSessionStatus> = {
+  Waiting: 'waiting',
+  Running: 'running',
+  Paused: 'paused',
+};
+
+type LiveNowEntry = Omit<IndexQueryResponse['entries'][number], 'scope_id' | 'entry_kind'>;
+
 type ConnectivityMock = Pick<
   DesktopApi,
   | 'getSyncStatus'
@@ -24,6 +46,8 @@ type ConnectivityMock = Pick<
   | 'acceptCommunityNodeConsents'
   | 'refreshCommunityNodeMetadata'
   | 'fetchCommunityNodeManifest'
+  | 'setCommunityNodeModerationPolicy'
+  | 'listCommunityNodeModerationPolicies'
   | 'readCommunityNodeTrustUser'
   | 'readCommunityNodeRelationUser'
   | 'listCommunityNodeRelationNeighbors'
@@ -33,6 +57,8 @@ type ConnectivityMock = Pick<
   | 'searchCommunityNodeIndex'
   | 'discoverCommunityNodeIndex'
   | 'recommendCommunityNodeIndex'
+  | 'searchCommunityNodePeople'
+  | 'listCommunityNodeLiveNow'
   | 'submitCommunityNodeIndexingRequest'
   | 'submitCommunityNodeReport'
   | 'importPeerTicket'
@@ -55,6 +81,7 @@ export function createConnectivityMock(runtime: MockRuntime): ConnectivityMock {
     metaverseAssetPayloads,
     mockConsentItems,
   } = runtime;
+  const moderationPolicies = new Map<string, CommunityModerationPolicyView>();
 
   function queryIndex(request: CommunityNodeIndexQueryRequest): IndexQueryResponse {
     const query = request.query?.trim().toLocaleLowerCase() ?? '';
@@ -80,6 +107,107 @@ export function createConnectivityMock(runtime: MockRuntime): ConnectivityMock {
     return { entries };
   }
 
+  function queryPeople(request: CommunityNodeIndexQueryRequest): IndexQueryResponse {
+    const query = request.query?.trim().toLocaleLowerCase() ?? '';
+    const entries = new Map<string, IndexQueryResponse['entries'][number]>();
+    for (const [topic, posts] of Object.entries(postsByTopic)) {
+      if (
+        request.scope_kind &&
+        (request.scope_kind !== 'public_topic' || request.scope_id !== topic)
+      ) {
+        continue;
+      }
+      for (const post of posts) {
+        if (post.channel_id || entries.has(post.author_pubkey)) {
+          continue;
+        }
+        const text = [post.author_name, post.author_display_name]
+          .filter((value): value is string => Boolean(value))
+          .join('\n');
+        if (!text || (query && !text.toLocaleLowerCase().includes(query))) {
+          continue;
+        }
+        entries.set(post.author_pubkey, {
+          scope_kind: 'public_topic',
+          scope_id: topic,
+          object_id: `profile:${post.author_pubkey}`,
+          author_pubkey: post.author_pubkey,
+          text,
+          created_at: post.created_at,
+          entry_kind: 'profile',
+          profile: {
+            name: post.author_name ?? null,
+            display_name: post.author_display_name ?? null,
+          },
+        });
+      }
+    }
+    return { entries: [...entries.values()].slice(0, request.limit ?? 20) };
+  }
+
+  function queryLiveNow(request: CommunityNodeIndexQueryRequest): IndexQueryResponse {
+    const query = request.query?.trim().toLocaleLowerCase() ?? '';
+    const entries: IndexQueryResponse['entries'] = [];
+    const push = (topic: string, channelId: string | null | undefined, entry: LiveNowEntry) => {
+      const scopeId = channelId ?? topic;
+      if (
+        (request.scope_kind &&
+          (entry.scope_kind !== request.scope_kind || scopeId !== request.scope_id)) ||
+        (query && !entry.text.toLocaleLowerCase().includes(query))
+      ) {
+        return;
+      }
+      entries.push({ ...entry, scope_id: scopeId, entry_kind: 'session' });
+    };
+    for (const [topic, sessions] of Object.entries(liveSessionsByTopic)) {
+      for (const session of sessions) {
+        if (session.status === 'Ended') {
+          continue;
+        }
+        push(topic, session.channel_id, {
+          scope_kind: session.channel_id ? 'private_channel' : 'public_topic',
+          object_id: `live:${session.session_id}`,
+          author_pubkey: session.host_pubkey,
+          text: [session.title, session.description].filter(Boolean).join('\n'),
+          created_at: session.started_at,
+          session: {
+            session_kind: 'live_session',
+            session_id: session.session_id,
+            topic_id: topic,
+            status: LIVE_SESSION_INDEX_STATUS[session.status],
+            title: session.title,
+            description: session.description || null,
+          },
+        });
+      }
+    }
+    for (const [topic, rooms] of Object.entries(gameRoomsByTopic)) {
+      for (const room of rooms) {
+        if (room.status === 'Ended') {
+          continue;
+        }
+        push(topic, room.channel_id, {
+          scope_kind: room.channel_id ? 'private_channel' : 'public_topic',
+          object_id: `room:${room.room_id}`,
+          author_pubkey: room.host_pubkey,
+          text: [room.title, room.description].filter(Boolean).join('\n'),
+          created_at: room.updated_at,
+          session: {
+            session_kind: room.room_kind === 'metaverse_room' ? 'metaverse_room' : 'game_room',
+            session_id: room.room_id,
+            topic_id: topic,
+            status: GAME_ROOM_INDEX_STATUS[room.status],
+            title: room.title,
+            description: room.description || null,
+            participant_count: room.scores.length,
+          },
+        });
+      }
+    }
+    entries.sort((left, right) => right.created_at - left.created_at);
+    return { entries: entries.slice(0, request.limit ?? 20) };
+  }
+
   const relationOptoutNodes = new Set<string>();
 
   return {
@@ -208,6 +336,20 @@ export function createConnectivityMock(runtime: MockRuntime): ConnectivityMock {
       };
       return runtime.communityNodeStatuses.find((status) => status.base_url === baseUrl)!;
     },
+    async setCommunityNodeModerationPolicy(baseUrl, policy) {
+      const view: CommunityModerationPolicyView = {
+        node_base_url: baseUrl,
+        policy,
+        updated_at: Date.now(),
+      };
+      moderationPolicies.set(baseUrl, view);
+      return view;
+    },
+    async listCommunityNodeModerationPolicies() {
+      return [...moderationPolicies.values()].sort((left, right) =>
+        left.node_base_url.localeCompare(right.node_base_url)
+      );
+    },
     async fetchCommunityNodeManifest(baseUrl) {
       return {
         status: 'ok',
@@ -325,6 +467,12 @@ export function createConnectivityMock(runtime: MockRuntime): ConnectivityMock {
     async recommendCommunityNodeIndex(request) {
       return queryIndex(request);
     },
+    async searchCommunityNodePeople(request) {
+      return queryPeople(request);
+    },
+    async listCommunityNodeLiveNow(request) {
+      return queryLiveNow(request);
+    },
     async submitCommunityNodeIndexingRequest(request) {
       return {
         request_id: `mock-indexing-${request.scope_kind}-${request.channel_id ?? request.topic_id}`,
@@ -338,6 +338,7 @@ export function cloneSyncStatus(syncStatus: SyncStatus): SyncStatus {
       configured_seed_peer_ids: [...syncStatus.discovery.configured_seed_peer_ids],
       bootstrap_seed_peer_ids: [...syncStatus.discovery.bootstrap_seed_peer_ids],
       manual_ticket_peer_ids: [...syncStatus.discovery.manual_ticket_peer_ids],
+      lan_peer_ids: [...syncStatus.discovery.lan_peer_ids],
       connected_peer_ids: [...syncStatus.discovery.connected_peer_ids],
       docs_assist_peer_ids: [...syncStatus.discovery.docs_assist_peer_ids],
       blob_assist_peer_ids: [...syncStatus.discovery.blob_assist_peer_ids],
@@ -193,6 +193,7 @@ export function createMockRuntime(options?: DesktopMockApiOptions): MockRuntime
       configured_seed_peer_ids: [],
       bootstrap_seed_peer_ids: [],
       manual_ticket_peer_ids: [],
+      lan_peer_ids: [],
       connected_peer_ids: ['peer-a'],
       docs_assist_peer_ids: assistPeerIds,
       blob_assist_peer_ids: assistPeerIds,
@@ -201,6 +202,7 @@ export function createMockRuntime(options?: DesktopMockApiOptions): MockRuntime
     },
     gossip_disabled_topics: [],
     gossip_disabled_channels: [],
+    blob_downloads: { queued: 0, active: 0, failed: 0 },
   };
   const authorSocialViews: Record<string, AuthorSocialView> = Object.fromEntries(
     Object.entries(options?.authorSocialViews ?? {}).map(([pubkey, view]) => [
@@ -541,7 +541,9 @@ export function DesktopShellPage({
               label={
                 syncStatus.discovery.mode === 'seeded_dht'
                   ? t('shell:navigation.seededDht')
-                  : t('shell:navigation.staticPeers')
+                  : syncStatus.discovery.mode === 'lan'
+                    ? t('shell:navigation.lanDiscovery')
+                    : t('shell:navigation.staticPeers')
               }
             />
             {syncStatus.pending_events > 0 ? (
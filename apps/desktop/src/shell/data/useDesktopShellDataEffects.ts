@@ -9,6 +9,7 @@ import { reconcileCommunityIndexNodeSelection } from '@/lib/api/communityIndex';
 import type {
   AttachmentView,
   CommunityNodeNodeStatus,
+  ContentEvent,
   DesktopApi,
   SyncStatus,
 } from '@/lib/api';
@@ -209,7 +210,55 @@ export function useDesktopShellDataEffects({
     [setCommunityNodeStatuses, setSyncStatus]
   );
 
-  useRuntimeEventBridge(refreshNotificationStatus, applySyncStatusChange);
+  // 購読経路の変更通知。表示中の topic / DM / author に関係するものだけ取り直し、
+  // それ以外は interval の refresh に任せる。
+  const applyContentChange = useCallback(
+    (event: ContentEvent) => {
+      switch (event.kind) {
+        case 'post_changed':
+        case 'thread_reply':
+        case 'reaction_changed':
+        case 'live_session_changed':
+        case 'game_room_changed': {
+          if (event.topic_id !== activeTopic || visibleRefreshInFlightRef.current) {
+            return;
+          }
+          visibleRefreshInFlightRef.current = true;
+          void refreshVisibleShellData(activeTopic, selectedThread, 'buffer').finally(() => {
+            visibleRefreshInFlightRef.current = false;
+          });
+          return;
+        }
+        case 'direct_message_received':
+        case 'direct_message_acked':
+          if (
+            shellChromeState.activePrimarySection === 'messages' ||
+            storeApi.getState().directMessagePaneOpen
+          ) {
+            void loadMessagesSection().catch(() => undefined);
+          }
+          return;
+        case 'profile_updated':
+          if (event.author_pubkey === selectedAuthorPubkey) {
+            void loadAuthorSection(event.author_pubkey).catch(() => undefined);
+          }
+          return;
+      }
+    },
+    [
+      activeTopic,
+      loadAuthorSection,
+      loadMessagesSection,
+      refreshVisibleShellData,
+      selectedAuthorPubkey,
+      selectedThread,
+      shellChromeState.activePrimarySection,
+      storeApi,
+      visibleRefreshInFlightRef,
+    ]
+  );
+
+  useRuntimeEventBridge(refreshNotificationStatus, applySyncStatusChange, applyContentChange);
 
   useEffect(() => {
     void refreshConnectivityStatus()
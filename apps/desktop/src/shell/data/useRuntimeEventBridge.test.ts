@@ -69,6 +69,40 @@ describe('useRuntimeEventBridge', () => {
     expect(onSyncStatusChanged).toHaveBeenNthCalledWith(2, null, communityNodeStatuses);
   });
 
+  test('forwards content change events with their ids', async () => {
+    let capturedCallback: EventCallback | undefined;
+    listenMock.mockImplementation(async (_event: string, callback: EventCallback) => {
+      capturedCallback = callback;
+      return () => undefined;
+    });
+    const onContentChanged = vi.fn();
+
+    renderHook(() => useRuntimeEventBridge(vi.fn(), vi.fn(), onContentChanged));
+    await vi.waitFor(() => expect(capturedCallback).toBeDefined());
+
+    capturedCallback?.({
+      payload: {
+        type: 'content_changed',
+        event: {
+          kind: 'thread_reply',
+          topic_id: 'kukuri:topic:demo',
+          channel_id: null,
+          root_object_id: 'root-1',
+          object_id: 'reply-1',
+          author_pubkey: 'a'.repeat(64),
+        },
+      },
+    });
+    expect(onContentChanged).toHaveBeenCalledWith({
+      kind: 'thread_reply',
+      topic_id: 'kukuri:topic:demo',
+      channel_id: null,
+      root_object_id: 'root-1',
+      object_id: 'reply-1',
+      author_pubkey: 'a'.repeat(64),
+    });
+  });
+
   test('ignores unknown runtime event types', async () => {
     let capturedCallback: EventCallback | undefined;
     listenMock.mockImplementation(async (_event: string, callback: EventCallback) => {
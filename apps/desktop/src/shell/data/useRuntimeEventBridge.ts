@@ -1,7 +1,12 @@
 import { useEffect, useRef } from 'react';
 import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 
-import type { CommunityNodeNodeStatus, RuntimeEvent, SyncStatus } from '@/lib/api';
+import type {
+  CommunityNodeNodeStatus,
+  ContentEvent,
+  RuntimeEvent,
+  SyncStatus,
+} from '@/lib/api';
 import { isTauriRuntime } from '@/lib/releaseReadiness';
 
 export function useRuntimeEventBridge(
@@ -9,10 +14,12 @@ export function useRuntimeEventBridge(
   onSyncStatusChanged: (
     syncStatus: SyncStatus | null,
     communityNodeStatuses: CommunityNodeNodeStatus[] | null
-  ) => void
+  ) => void,
+  onContentChanged?: (event: ContentEvent) => void
 ): void {
   const notificationCallbackRef = useRef(onNotificationStatusChanged);
   const syncStatusCallbackRef = useRef(onSyncStatusChanged);
+  const contentCallbackRef = useRef(onContentChanged);
 
   useEffect(() => {
     notificationCallbackRef.current = onNotificationStatusChanged;
@@ -22,6 +29,10 @@ export function useRuntimeEventBridge(
     syncStatusCallbackRef.current = onSyncStatusChanged;
   }, [onSyncStatusChanged]);
 
+  useEffect(() => {
+    contentCallbackRef.current = onContentChanged;
+  }, [onContentChanged]);
+
   useEffect(() => {
     if (!isTauriRuntime()) {
       return;
@@ -44,6 +55,9 @@ export function useRuntimeEventBridge(
                 event.payload.community_node_statuses ?? null
               );
               break;
+            case 'content_changed':
+              contentCallbackRef.current?.(event.payload.event);
+              break;
           }
         }
       );
@@ -71,6 +71,7 @@ export const DEFAULT_SYNC_STATUS: SyncStatus = {
     configured_seed_peer_ids: [],
     bootstrap_seed_peer_ids: [],
     manual_ticket_peer_ids: [],
+    lan_peer_ids: [],
     connected_peer_ids: [],
     docs_assist_peer_ids: [],
     blob_assist_peer_ids: [],
@@ -79,6 +80,7 @@ export const DEFAULT_SYNC_STATUS: SyncStatus = {
   },
   gossip_disabled_topics: [],
   gossip_disabled_channels: [],
+  blob_downloads: { queued: 0, active: 0, failed: 0 },
 };
 
 export function createInitialConnectivitySlice(): ConnectivitySliceState {
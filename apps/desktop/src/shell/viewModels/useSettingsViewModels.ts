@@ -262,6 +262,11 @@ export function useSettingsViewModels({
           value: formatListLabel(syncStatus.discovery.manual_ticket_peer_ids),
           monospace: true,
         },
+        {
+          label: t('settings:discovery.diagnostics.lanPeers'),
+          value: formatListLabel(syncStatus.discovery.lan_peer_ids),
+          monospace: true,
+        },
         {
           label: t('settings:discovery.diagnostics.communityBootstrapPeers'),
           value: formatListLabel(syncStatus.discovery.bootstrap_seed_peer_ids),
@@ -299,6 +304,7 @@ export function useSettingsViewModels({
       syncStatus.discovery.connect_mode,
       syncStatus.discovery.connected_peer_ids,
       syncStatus.discovery.docs_assist_peer_ids,
+      syncStatus.discovery.lan_peer_ids,
       syncStatus.discovery.last_discovery_error,
       syncStatus.discovery.local_endpoint_id,
       syncStatus.discovery.manual_ticket_peer_ids,
@@ -0,0 +1,92 @@
+use crate::service::*;
+
+const DEVICE_PAIRING_TTL_MS: i64 = 10 * 60 * 1000;
+const DEFAULT_LOCAL_DEVICE_LABEL: &str = "primary";
+
+impl AppService {
+    /// 新しい端末向けの device 登録を発行し、pairing ticket と pairing code を返す。
+    /// 新端末は ticket と code の両方で author 鍵を受け取り、発行済みの device id で起動する。
+    pub async fn create_device_pairing(&self, device_label: &str) -> Result<DevicePairingView> {
+        let local_device_id = self.require_local_device_id()?.to_string();
+        self.ensure_local_device_authorization().await?;
+        let device_id = generate_device_id();
+        let envelope = build_device_authorization_envelope(
+            self.keys(),
+            device_id.as_str(),
+            device_label,
+            Some(local_device_id.as_str()),
+        )?;
+        let authorization = persist_device_authorization(self.docs_sync(), &envelope).await?;
+        let pairing_code = generate_device_pairing_code();
+        let expires_at = Utc::now().timestamp_millis() + DEVICE_PAIRING_TTL_MS;
+        let ticket =
+            build_device_pairing_ticket(self.keys(), &envelope, pairing_code.as_str(), expires_at)?;
+        Ok(DevicePairingView {
+            device_id: authorization.device_id,
+            device_label: authorization.device_label,
+            ticket,
+            pairing_code,
+            expires_at,
+        })
+    }
+
+    pub async fn list_linked_devices(&self) -> Result<Vec<LinkedDeviceView>> {
+        let local_device_id = self.local_device_id();
+        Ok(load_device_authorizations(
+            self.docs_sync(),
+            self.current_author_pubkey().as_str(),
+            DocFetchPolicy::LocalThenRemote,
+        )
+        .await?
+        .into_iter()
+        .map(|authorization| LinkedDeviceView {
+            is_current_device: local_device_id == Some(authorization.device_id.as_str()),
+            device_id: authorization.device_id,
+            device_label: authorization.device_label,
+            authorized_by_device_id: authorization.authorized_by_device_id,
+            authorized_at: authorization.authorized_at,
+        })
+        .collect())
+    }
+
+    /// 他端末の device replica からブックマーク / ミュート / 参加中の非公開チャンネル /
+    /// DM の削除 / 通知の既読を取り込む。適用した entry 数を返す。
+    pub async fn sync_linked_devices(&self) -> Result<usize> {
+        self.require_local_device_id()?;
+        self.ensure_local_device_authorization().await?;
+        self.merge_linked_device_state(DocFetchPolicy::LocalThenRemote)
+            .await
+    }
+
+    fn require_local_device_id(&self) -> Result<&str> {
+        self.local_device_id()
+            .ok_or_else(|| anyhow::anyhow!("device linking requires a local device id"))
+    }
+
+    /// 最初の端末は自分自身の登録を持たないため、pairing / 同期の前に自己署名で登録する。
+    async fn ensure_local_device_authorization(&self) -> Result<()> {
+        let local_device_id = self.require_local_device_id()?;
+        let replica = author_replica_id(self.current_author_pubkey().as_str());
+        self.docs_sync().open_replica(&replica).await?;
+        // pairing で参加した端末の登録は発行元が書いているので、remote も確認してから判断する。
+        if !query_replica_with_fetch_policy(
+            self.docs_sync(),
+            &replica,
+            DocQuery::Exact(device_authorization_key(local_device_id)),
+            DocFetchPolicy::LocalThenRemote,
+        )
+        .await?
+        .is_empty()
+        {
+            return Ok(());
+        }
+        let envelope = build_device_authorization_envelope(
+            self.keys(),
+            local_device_id,
+            DEFAULT_LOCAL_DEVICE_LABEL,
+            None,
+        )?;
+        persist_device_authorization(self.docs_sync(), &envelope).await?;
+        Ok(())
+    }
+}
@@ -30,6 +30,7 @@ impl AppService {
                 .await?;
         }
         self.rebuild_author_relationships().await?;
+        self.reconcile_group_direct_message_subscriptions().await?;
         Ok(())
     }
 
@@ -163,12 +164,13 @@ impl AppService {
             &Pubkey::from(self.current_author_pubkey()),
             &Pubkey::from(peer_pubkey.as_str()),
         );
+        let deleted_at = Utc::now().timestamp_millis();
         self.services
             .projection_store
             .put_direct_message_tombstone(DirectMessageTombstoneRow {
                 dm_id: dm_id.clone(),
                 message_id: message_id.to_string(),
-                deleted_at: Utc::now().timestamp_millis(),
+                deleted_at,
             })
             .await?;
         self.services
@@ -177,6 +179,20 @@ impl AppService {
             .await?;
         self.refresh_direct_message_conversation(peer_pubkey.as_str())
             .await?;
+        self.record_device_state(
+            stable_key(
+                DEVICE_STATE_DM_DELETIONS_PREFIX,
+                format!("{dm_id}/{message_id}").as_str(),
+            )
+            .as_str(),
+            &DeviceDirectMessageDeletionV1 {
+                peer_pubkey: peer_pubkey.clone(),
+                message_id: message_id.to_string(),
+                deleted_at,
+            },
+            false,
+        )
+        .await;
         Ok(())
     }
 
@@ -213,6 +229,15 @@ impl AppService {
             .projection_store
             .clear_direct_message_local(dm_id.as_str())
             .await?;
+        self.record_device_state(
+            stable_key(DEVICE_STATE_DM_CLEARS_PREFIX, dm_id.as_str()).as_str(),
+            &DeviceDirectMessageClearV1 {
+                peer_pubkey: peer_pubkey.clone(),
+                cleared_at: deleted_at,
+            },
+            false,
+        )
+        .await;
         Ok(())
     }
 
@@ -261,4 +286,90 @@ impl AppService {
                 last_error: diagnostic.last_error,
             }))
     }
+
+    /// identity backup 用に DM の会話 metadata を書き出す。
+    ///
+    /// ratchet state・session prekey の秘密鍵・グループ鍵は含めない。backup に残すと漏洩時に
+    /// 過去の frame まで読めてしまい、古い snapshot を戻すと使用済みの counter を再利用する。
+    pub async fn export_direct_message_backup(&self) -> Result<DirectMessageBackupV1> {
+        let store = &self.services.projection_store;
+        let conversations = store.list_direct_message_conversations().await?;
+        let mut sessions = Vec::new();
+        for conversation in &conversations {
+            if let Some(session) = store
+                .get_direct_message_session(conversation.dm_id.as_str())
+                .await?
+            {
+                sessions.push(reset_direct_message_session(session));
+            }
+        }
+        let group_conversations = store.list_group_direct_message_conversations().await?;
+        Ok(DirectMessageBackupV1 {
+            conversations,
+            sessions,
+            group_conversations,
+        })
+    }
+
+    /// backup の DM state を取り込み、取り込んだ会話数を返す。
+    ///
+    /// 端末に既にある会話・session は上書きしない。復元した session は ratchet を持たず、
+    /// 次の送信で session-init からやり直す。グループ鍵は owner の次の rekey で受け取る。
+    pub async fn restore_direct_message_backup(
+        &self,
+        backup: DirectMessageBackupV1,
+    ) -> Result<usize> {
+        let store = &self.services.projection_store;
+        let mut restored = 0;
+        for conversation in backup.conversations {
+            normalize_author_pubkey(conversation.peer_pubkey.as_str())?;
+            if store
+                .get_direct_message_conversation_by_dm_id(conversation.dm_id.as_str())
+                .await?
+                .is_some()
+            {
+                continue;
+            }
+            store
+                .upsert_direct_message_conversation(conversation)
+                .await?;
+            restored += 1;
+        }
+        for session in backup.sessions {
+            if store
+                .get_direct_message_session(session.dm_id.as_str())
+                .await?
+                .is_none()
+            {
+                store
+                    .put_direct_message_session(reset_direct_message_session(session))
+                    .await?;
+            }
+        }
+        for group in backup.group_conversations {
+            if store
+                .get_group_direct_message_conversation(group.group_id.as_str())
+                .await?
+                .is_some()
+            {
+                continue;
+            }
+            store
+                .upsert_group_direct_message_conversation(group)
+                .await?;
+            restored += 1;
+        }
+        self.resume_direct_message_state().await?;
+        Ok(restored)
+    }
+}
+
+/// 交渉済みの frame version だけを残し、ratchet state と session prekey を捨てる。
+fn reset_direct_message_session(session: DirectMessageSessionRow) -> DirectMessageSessionRow {
+    DirectMessageSessionRow {
+        ratchet_state_json: None,
+        local_prekey_secret_hex: None,
+        peer_prekey_pubkey: None,
+        ..session
+    }
 }
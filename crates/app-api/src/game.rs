@@ -579,6 +579,7 @@ impl AppService {
             .blob_service
             .put_blob(input.bytes, input.mime_type.as_str())
             .await?;
+        self.pin_blobs([&stored.hash]).await?;
         self.services
             .projection_store
             .mark_blob_status(&stored.hash, BlobCacheStatus::Available)
@@ -0,0 +1,234 @@
+use crate::service::*;
+
+impl AppService {
+    pub async fn create_group_direct_message(
+        &self,
+        title: Option<&str>,
+        member_pubkeys: &[String],
+    ) -> Result<GroupDirectMessageConversationView> {
+        let local_author_pubkey = self.current_author_pubkey();
+        let members = self
+            .normalize_group_direct_message_member_input(member_pubkeys)
+            .await?;
+        let members = normalize_group_direct_message_members(
+            &Pubkey::from(local_author_pubkey.as_str()),
+            &members.into_iter().map(Pubkey::from).collect::<Vec<_>>(),
+        )?;
+        let now = Utc::now().timestamp_millis();
+        let group = GroupDirectMessageConversationRow {
+            group_id: generate_group_direct_message_id(),
+            owner_pubkey: local_author_pubkey,
+            title: normalize_optional_text(title.map(str::to_string)),
+            epoch: 1,
+            members: members
+                .into_iter()
+                .map(|member| member.as_str().to_string())
+                .collect(),
+            removed_at: None,
+            updated_at: now,
+            last_message_at: None,
+            last_message_id: None,
+            last_message_preview: None,
+        };
+        self.distribute_group_direct_message_epoch(&group, &[])
+            .await?;
+        self.group_direct_message_conversation_view(group).await
+    }
+
+    pub async fn list_group_direct_messages(
+        &self,
+    ) -> Result<Vec<GroupDirectMessageConversationView>> {
+        self.reconcile_group_direct_message_subscriptions().await?;
+        let mut rows = self
+            .services
+            .projection_store
+            .list_group_direct_message_conversations()
+            .await?;
+        rows.sort_by(|left, right| {
+            right
+                .updated_at
+                .cmp(&left.updated_at)
+                .then_with(|| left.group_id.cmp(&right.group_id))
+        });
+        let mut items = Vec::with_capacity(rows.len());
+        for row in rows {
+            items.push(self.group_direct_message_conversation_view(row).await?);
+        }
+        Ok(items)
+    }
+
+    pub async fn open_group_direct_message(
+        &self,
+        group_id: &str,
+    ) -> Result<GroupDirectMessageConversationView> {
+        let group = self.load_group_direct_message(group_id).await?;
+        self.reconcile_group_direct_message_subscriptions().await?;
+        self.group_direct_message_conversation_view(group).await
+    }
+
+    pub async fn list_group_direct_message_messages(
+        &self,
+        group_id: &str,
+        cursor: Option<TimelineCursor>,
+        limit: usize,
+    ) -> Result<DirectMessageTimelineView> {
+        let group = self.load_group_direct_message(group_id).await?;
+        let page = self
+            .services
+            .projection_store
+            .list_direct_message_messages(group.group_id.as_str(), cursor, limit)
+            .await?;
+        let mut items = Vec::with_capacity(page.items.len());
+        for row in page.items {
+            items.push(self.direct_message_message_view(row).await?);
+        }
+        Ok(DirectMessageTimelineView {
+            items,
+            next_cursor: page.next_cursor,
+        })
+    }
+
+    pub async fn send_group_direct_message(
+        &self,
+        group_id: &str,
+        text: Option<&str>,
+        reply_to_message_id: Option<&str>,
+        attachments: Vec<PendingAttachment>,
+    ) -> Result<String> {
+        let group = self.load_group_direct_message(group_id).await?;
+        if group.removed_at.is_some()
+            || !group
+                .members
+                .iter()
+                .any(|member| member == &self.current_author_pubkey())
+        {
+            anyhow::bail!("group direct message requires membership");
+        }
+        self.reconcile_group_direct_message_subscriptions().await?;
+        self.send_group_direct_message_internal(group, text, reply_to_message_id, attachments)
+            .await
+    }
+
+    /// メンバーを追加して rekey する。追加されたメンバーは追加後の epoch からだけ読める。
+    pub async fn add_group_direct_message_members(
+        &self,
+        group_id: &str,
+        member_pubkeys: &[String],
+    ) -> Result<GroupDirectMessageConversationView> {
+        let group = self.load_owned_group_direct_message(group_id).await?;
+        let added = self
+            .normalize_group_direct_message_member_input(member_pubkeys)
+            .await?;
+        let members = normalize_group_direct_message_members(
+            &Pubkey::from(group.owner_pubkey.as_str()),
+            &group
+                .members
+                .iter()
+                .chain(added.iter())
+                .cloned()
+                .map(Pubkey::from)
+                .collect::<Vec<_>>(),
+        )?;
+        self.rekey_group_direct_message(group, members, Vec::new())
+            .await
+    }
+
+    /// メンバーを外して rekey する。外されたメンバーは以後の epoch の鍵と topic を得られない。
+    pub async fn remove_group_direct_message_members(
+        &self,
+        group_id: &str,
+        member_pubkeys: &[String],
+    ) -> Result<GroupDirectMessageConversationView> {
+        let group = self.load_owned_group_direct_message(group_id).await?;
+        let mut removed = Vec::new();
+        for member_pubkey in member_pubkeys {
+            let member_pubkey = normalize_author_pubkey(member_pubkey)?;
+            if member_pubkey == group.owner_pubkey {
+                anyhow::bail!("group direct message owner cannot be removed");
+            }
+            if group.members.contains(&member_pubkey) && !removed.contains(&member_pubkey) {
+                removed.push(member_pubkey);
+            }
+        }
+        if removed.is_empty() {
+            anyhow::bail!("group direct message member was not found");
+        }
+        let members = normalize_group_direct_message_members(
+            &Pubkey::from(group.owner_pubkey.as_str()),
+            &group
+                .members
+                .iter()
+                .filter(|member| !removed.contains(member))
+                .cloned()
+                .map(Pubkey::from)
+                .collect::<Vec<_>>(),
+        )?;
+        self.rekey_group_direct_message(group, members, removed)
+            .await
+    }
+
+    async fn rekey_group_direct_message(
+        &self,
+        group: GroupDirectMessageConversationRow,
+        members: Vec<Pubkey>,
+        removed_members: Vec<String>,
+    ) -> Result<GroupDirectMessageConversationView> {
+        let group = GroupDirectMessageConversationRow {
+            epoch: group.epoch + 1,
+            members: members
+                .into_iter()
+                .map(|member| member.as_str().to_string())
+                .collect(),
+            updated_at: Utc::now().timestamp_millis(),
+            ..group
+        };
+        self.distribute_group_direct_message_epoch(&group, &removed_members)
+            .await?;
+        self.group_direct_message_conversation_view(group).await
+    }
+
+    async fn load_group_direct_message(
+        &self,
+        group_id: &str,
+    ) -> Result<GroupDirectMessageConversationRow> {
+        self.services
+            .projection_store
+            .get_group_direct_message_conversation(group_id.trim())
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("group direct message was not found"))
+    }
+
+    async fn load_owned_group_direct_message(
+        &self,
+        group_id: &str,
+    ) -> Result<GroupDirectMessageConversationRow> {
+        let group = self.load_group_direct_message(group_id).await?;
+        if group.owner_pubkey != self.current_author_pubkey() {
+            anyhow::bail!("only the group direct message owner can change members");
+        }
+        Ok(group)
+    }
+
+    /// 鍵更新は pairwise DM で届けるため、追加するメンバーとは相互フォローが必要。
+    async fn normalize_group_direct_message_member_input(
+        &self,
+        member_pubkeys: &[String],
+    ) -> Result<Vec<String>> {
+        let local_author_pubkey = self.current_author_pubkey();
+        let mut members = Vec::with_capacity(member_pubkeys.len());
+        for member_pubkey in member_pubkeys {
+            let member_pubkey = normalize_author_pubkey(member_pubkey)?;
+            if member_pubkey == local_author_pubkey || members.contains(&member_pubkey) {
+                continue;
+            }
+            if !self
+                .direct_message_send_enabled(member_pubkey.as_str())
+                .await?
+            {
+                anyhow::bail!("group direct message members require a mutual relationship");
+            }
+            members.push(member_pubkey);
+        }
+        Ok(members)
+    }
+}
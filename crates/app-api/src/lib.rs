@@ -14,22 +14,26 @@
 //! ※ `private_channels.rs` ↔ `service/private_channels_support.rs` は公開 / 内部の
 //! 正当な分割であり、同名を理由に統合しない(REFACTORING.md 地雷リスト)。
 
+mod devices;
 mod direct_messages;
 mod game;
+mod group_direct_messages;
 mod live;
 mod media;
+mod moderation;
 mod notifications;
 mod private_channel_indexing;
 mod private_channel_rendezvous;
 mod private_channels;
 mod reactions;
+mod search;
 mod service;
 mod social;
 mod sync;
 mod timeline;
 mod views;
 
-pub use kukuri_store::NotificationKind;
+pub use kukuri_store::{CommunityModerationPolicy, NotificationKind};
 pub use private_channels::{
     is_retryable_friend_only_grant_import_error, is_retryable_friend_plus_share_import_error,
 };
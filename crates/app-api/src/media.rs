@@ -56,4 +56,38 @@ impl AppService {
             payload.mime, payload.bytes_base64
         )))
     }
+
+    /// ローカル blob の容量(pin 済み / cache)と cache quota を返す。
+    pub async fn get_storage_usage(&self) -> Result<StorageUsageView> {
+        let usage = summarize_blob_storage(self.services.projection_store.as_ref()).await?;
+        Ok(StorageUsageView {
+            pinned_blobs: usage.pinned_blobs,
+            pinned_bytes: usage.pinned_bytes,
+            cached_blobs: usage.cached_blobs,
+            cached_bytes: usage.cached_bytes,
+            unreferenced_blobs: usage.unreferenced_blobs,
+            cache_quota_bytes: self.services.blob_retention.cache_quota_bytes,
+        })
+    }
+
+    /// 定期 GC を待たずに retention を 1 回回す。手放した blob は表示時に取り直す。
+    pub async fn collect_blob_garbage(&self) -> Result<BlobGarbageCollectionView> {
+        let report = collect_blob_garbage(
+            self.services.projection_store.as_ref(),
+            self.services.blob_service.as_ref(),
+            &self.services.blob_retention,
+            Utc::now().timestamp_millis(),
+        )
+        .await?;
+        info!(
+            evicted_blobs = report.evicted_blobs,
+            evicted_bytes = report.evicted_bytes,
+            "blob garbage collection requested"
+        );
+        Ok(BlobGarbageCollectionView {
+            evicted_blobs: report.evicted_blobs,
+            evicted_bytes: report.evicted_bytes,
+            usage: self.get_storage_usage().await?,
+        })
+    }
 }
@@ -0,0 +1,49 @@
+use crate::service::*;
+
+impl AppService {
+    /// community node ごとの moderation event の扱い(hide / blur / label / ignore)を設定する。
+    ///
+    /// `node_base_url` は呼び出し側で正規化済みであること(desktop-runtime の構成済みノード URL)。
+    pub async fn set_community_moderation_policy(
+        &self,
+        node_base_url: &str,
+        policy: CommunityModerationPolicy,
+    ) -> Result<CommunityModerationPolicyView> {
+        let node_base_url = node_base_url.trim();
+        if node_base_url.is_empty() {
+            anyhow::bail!("community node base url is required");
+        }
+        let row = CommunityModerationPolicyRow {
+            node_base_url: node_base_url.to_string(),
+            policy,
+            updated_at: Utc::now().timestamp_millis(),
+        };
+        self.services
+            .projection_store
+            .put_community_moderation_policy(row.clone())
+            .await?;
+        Ok(CommunityModerationPolicyView {
+            node_base_url: row.node_base_url,
+            policy: row.policy,
+            updated_at: Some(row.updated_at),
+        })
+    }
+
+    /// 明示設定済みの node policy を返す。未設定の node は既定の `label` として扱われる。
+    pub async fn list_community_moderation_policies(
+        &self,
+    ) -> Result<Vec<CommunityModerationPolicyView>> {
+        Ok(self
+            .services
+            .projection_store
+            .list_community_moderation_policies()
+            .await?
+            .into_iter()
+            .map(|row| CommunityModerationPolicyView {
+                node_base_url: row.node_base_url,
+                policy: row.policy,
+                updated_at: Some(row.updated_at),
+            })
+            .collect())
+    }
+}
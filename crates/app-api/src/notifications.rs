@@ -13,18 +13,28 @@ impl AppService {
         &self,
         notification_id: &str,
     ) -> Result<NotificationStatusView> {
+        let read_at = Utc::now().timestamp_millis();
         self.services
             .projection_store
-            .mark_notification_read(notification_id, Utc::now().timestamp_millis())
+            .mark_notification_read(notification_id, read_at)
             .await?;
+        self.record_device_state(
+            stable_key(DEVICE_STATE_NOTIFICATION_READS_PREFIX, notification_id).as_str(),
+            &read_at,
+            false,
+        )
+        .await;
         self.notification_status_view().await
     }
 
     pub async fn mark_all_notifications_read(&self) -> Result<NotificationStatusView> {
+        let read_at = Utc::now().timestamp_millis();
         self.services
             .projection_store
-            .mark_all_notifications_read(Utc::now().timestamp_millis())
+            .mark_all_notifications_read(read_at)
             .await?;
+        self.record_device_state(DEVICE_STATE_NOTIFICATIONS_ALL_READ_KEY, &read_at, false)
+            .await;
         self.notification_status_view().await
     }
 
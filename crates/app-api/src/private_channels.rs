@@ -954,6 +954,8 @@ impl AppService {
                 }
             }
         }
+        self.unpin_own_channel_blobs(topic_id, &state.channel_id)
+            .await?;
         self.remove_joined_private_channel(topic_id, channel_id)
             .await?;
         Ok(())
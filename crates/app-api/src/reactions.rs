@@ -125,6 +125,7 @@ impl AppService {
         persist_custom_reaction_asset_doc(self.services.docs_sync.as_ref(), &asset, &envelope)
             .await?;
         self.services.store.put_envelope(envelope).await?;
+        self.pin_blobs([&stored_blob.hash]).await?;
         self.services
             .projection_store
             .mark_blob_status(&stored_blob.hash, BlobCacheStatus::Available)
@@ -212,6 +213,16 @@ impl AppService {
             .projection_store
             .put_bookmarked_custom_reaction(row.clone())
             .await?;
+        self.record_device_state(
+            stable_key(
+                DEVICE_STATE_BOOKMARKED_REACTIONS_PREFIX,
+                row.asset_id.as_str(),
+            )
+            .as_str(),
+            &row,
+            false,
+        )
+        .await;
         Ok(bookmarked_custom_reaction_view_from_row(row))
     }
 
@@ -219,6 +230,13 @@ impl AppService {
         self.services
             .projection_store
             .remove_bookmarked_custom_reaction(asset_id)
-            .await
+            .await?;
+        self.record_device_state(
+            stable_key(DEVICE_STATE_BOOKMARKED_REACTIONS_PREFIX, asset_id).as_str(),
+            &asset_id,
+            true,
+        )
+        .await;
+        Ok(())
     }
 }
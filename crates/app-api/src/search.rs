@@ -0,0 +1,140 @@
+use crate::service::*;
+
+const LOCAL_SEARCH_DEFAULT_LIMIT: usize = 50;
+const LOCAL_SEARCH_MAX_LIMIT: usize = 200;
+
+impl AppService {
+    /// 端末内に hydrate 済みの post 本文と DM 平文を全文検索する。
+    ///
+    /// community node の `INDEX_SEARCH_PATH` と違い、参加中の private channel と DM も
+    /// 対象になる。topic / channel を指定した場合は DM を含めない。
+    pub async fn search_local(&self, input: LocalSearchInput) -> Result<LocalSearchView> {
+        let text = input.query.trim().to_string();
+        if text.is_empty() {
+            return Ok(LocalSearchView {
+                posts: Vec::new(),
+                direct_messages: Vec::new(),
+            });
+        }
+        let limit = match input.limit {
+            0 => LOCAL_SEARCH_DEFAULT_LIMIT,
+            limit => limit.min(LOCAL_SEARCH_MAX_LIMIT),
+        };
+        let topic_id = normalize_optional_text(input.topic_id);
+        let author_pubkey = input
+            .author_pubkey
+            .as_deref()
+            .map(normalize_author_pubkey)
+            .transpose()?;
+        if let Some(channel_id) = input.channel_ref.as_ref().and_then(ChannelRef::channel_id) {
+            let Some(topic_id) = topic_id.as_deref() else {
+                anyhow::bail!("private channel search requires topic_id");
+            };
+            self.ensure_private_channel_access(topic_id, channel_id)
+                .await?;
+        }
+        let scoped = topic_id.is_some() || input.channel_ref.is_some();
+        let mut subject_kinds = BTreeSet::from([LOCAL_SEARCH_POST_KIND.to_string()]);
+        if input.include_direct_messages && !scoped {
+            subject_kinds.insert(LOCAL_SEARCH_DIRECT_MESSAGE_KIND.to_string());
+        }
+        let mut query = LocalSearchQuery {
+            text,
+            subject_kinds,
+            topic_id,
+            channel_id: input
+                .channel_ref
+                .as_ref()
+                .map(|channel_ref| channel_storage_id(channel_ref.channel_id())),
+            author_pubkey,
+            created_after: input.created_after,
+            created_before: input.created_before,
+            before: None,
+            limit,
+        };
+
+        let muted_author_pubkeys = self.current_muted_author_pubkeys().await?;
+        let mut allowed_channels_by_topic = HashMap::<String, BTreeSet<String>>::new();
+        let mut post_rows = Vec::new();
+        let mut direct_messages = Vec::new();
+        // mute / retract / 脱退済み channel の hit は索引の LIMIT の後で落ちるので、
+        // page が埋まるか索引を読み切るまで続きの位置から読み直す。
+        'fill: loop {
+            let documents = self
+                .services
+                .projection_store
+                .search_local_documents(&query)
+                .await?;
+            let exhausted = documents.len() < limit;
+            query.before = documents
+                .last()
+                .map(|document| (document.created_at, document.subject_id.clone()));
+            for document in documents {
+                if post_rows.len() + direct_messages.len() >= limit {
+                    break 'fill;
+                }
+                match document.subject_kind.as_str() {
+                    LOCAL_SEARCH_POST_KIND => {
+                        let Some(row) = self
+                            .services
+                            .projection_store
+                            .get_object_projection(&EnvelopeId::from(document.subject_id.as_str()))
+                            .await?
+                        else {
+                            continue;
+                        };
+                        if object_projection_row_is_retracted(&row)
+                            || object_projection_row_is_muted(&row, &muted_author_pubkeys)
+                        {
+                            continue;
+                        }
+                        // 脱退した private channel の post は索引に残っていても返さない。
+                        if !allowed_channels_by_topic.contains_key(row.topic_id.as_str()) {
+                            let allowed = self
+                                .allowed_channel_ids_for_scope(
+                                    row.topic_id.as_str(),
+                                    &TimelineScope::AllJoined,
+                                )
+                                .await?;
+                            allowed_channels_by_topic.insert(row.topic_id.clone(), allowed);
+                        }
+                        if allowed_channels_by_topic
+                            .get(row.topic_id.as_str())
+                            .is_some_and(|allowed| allowed.contains(row.channel_id.as_str()))
+                        {
+                            post_rows.push(row);
+                        }
+                    }
+                    LOCAL_SEARCH_DIRECT_MESSAGE_KIND => {
+                        let Some(dm_id) = document.dm_id.as_deref() else {
+                            continue;
+                        };
+                        if let Some(row) = self
+                            .services
+                            .projection_store
+                            .get_direct_message_message(dm_id, document.subject_id.as_str())
+                            .await?
+                        {
+                            direct_messages.push(self.direct_message_message_view(row).await?);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if exhausted || post_rows.len() + direct_messages.len() >= limit {
+                break;
+            }
+        }
+        let posts = self
+            .page_to_view(Page {
+                items: post_rows,
+                next_cursor: None,
+            })
+            .await?
+            .items;
+        Ok(LocalSearchView {
+            posts,
+            direct_messages,
+        })
+    }
+}
@@ -152,6 +152,7 @@ pub(crate) async fn materialize_direct_message_manifest(
     keys: &KukuriKeys,
     sender_pubkey: &Pubkey,
     message_id: &str,
+    attachment_key_hex: Option<&str>,
     manifest: Option<&DirectMessageAttachmentManifestV1>,
 ) -> Result<Option<DirectMessageAttachmentManifestV1>> {
     let Some(manifest) = manifest else {
@@ -162,6 +163,7 @@ pub(crate) async fn materialize_direct_message_manifest(
         keys,
         sender_pubkey,
         message_id,
+        attachment_key_hex,
         &manifest.original,
     )
     .await?;
@@ -172,6 +174,7 @@ pub(crate) async fn materialize_direct_message_manifest(
                 keys,
                 sender_pubkey,
                 message_id,
+                attachment_key_hex,
                 poster,
             )
             .await?,
@@ -191,6 +194,7 @@ pub(crate) async fn materialize_direct_message_blob_ref(
     keys: &KukuriKeys,
     sender_pubkey: &Pubkey,
     message_id: &str,
+    attachment_key_hex: Option<&str>,
     encrypted_ref: &DirectMessageEncryptedBlobRefV1,
 ) -> Result<DirectMessageEncryptedBlobRefV1> {
     let Some(bytes) = blob_service.fetch_blob(&encrypted_ref.hash).await? else {
@@ -198,7 +202,13 @@ pub(crate) async fn materialize_direct_message_blob_ref(
     };
     let encrypted: DirectMessageEncryptedAttachmentV1 = serde_json::from_slice(bytes.as_slice())
         .context("failed to decode direct message attachment blob")?;
-    let decrypted = decrypt_direct_message_attachment(keys, sender_pubkey, message_id, &encrypted)?;
+    // v2 frame の添付は payload に載った使い捨て鍵で、v1 は static 鍵で暗号化されている。
+    let decrypted = match attachment_key_hex {
+        Some(attachment_key_hex) => {
+            decrypt_direct_message_attachment_with_key(attachment_key_hex, message_id, &encrypted)?
+        }
+        None => decrypt_direct_message_attachment(keys, sender_pubkey, message_id, &encrypted)?,
+    };
     let local = blob_service
         .put_blob(decrypted, encrypted_ref.mime.as_str())
         .await?;
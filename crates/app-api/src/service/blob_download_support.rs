@@ -0,0 +1,77 @@
+use super::*;
+
+/// 未取得の blob を download queue に積む(実際の取得は blob-service の scheduler)。
+pub(crate) async fn queue_blob_downloads(
+    projection_store: &dyn ProjectionStore,
+    blobs: Vec<(kukuri_core::BlobHash, DownloadJobKind)>,
+    priority: i64,
+) -> Result<()> {
+    let now = Utc::now().timestamp_millis();
+    for (hash, kind) in blobs {
+        projection_store
+            .enqueue_download_job(&hash, kind, priority, now)
+            .await?;
+    }
+    Ok(())
+}
+
+/// live session / game room の manifest が取れなかったときに積む。取れた後の projection は
+/// 次の hydration で反映される。
+pub(crate) async fn queue_manifest_download(
+    projection_store: &dyn ProjectionStore,
+    manifest: &ManifestBlobRef,
+) -> Result<()> {
+    queue_blob_downloads(
+        projection_store,
+        vec![(manifest.hash.clone(), DownloadJobKind::Manifest)],
+        DOWNLOAD_PRIORITY_BACKGROUND,
+    )
+    .await
+}
+
+pub(crate) fn missing_attachment_downloads(
+    attachments: &[AttachmentView],
+) -> Vec<(kukuri_core::BlobHash, DownloadJobKind)> {
+    attachments
+        .iter()
+        .filter(|attachment| attachment.status == BlobViewStatus::Missing)
+        .map(|attachment| {
+            (
+                kukuri_core::BlobHash::new(attachment.hash.clone()),
+                DownloadJobKind::Attachment,
+            )
+        })
+        .collect()
+}
+
+impl AppService {
+    /// 表示中の item の未取得 blob を queue の先頭側へ寄せる。打ち切った job もここで再開する
+    /// (再開は打ち切り時の cooldown 後。表示のたびに転送を叩き直しはしない)。
+    pub(crate) async fn prioritize_visible_blob_downloads(
+        &self,
+        blobs: Vec<(kukuri_core::BlobHash, DownloadJobKind)>,
+    ) -> Result<()> {
+        let projection_store = self.services.projection_store.as_ref();
+        let now = Utc::now().timestamp_millis();
+        for (hash, kind) in blobs {
+            projection_store
+                .enqueue_download_job(&hash, kind, DOWNLOAD_PRIORITY_VISIBLE, now)
+                .await?;
+            projection_store.retry_download_job(&hash, now).await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn blob_download_queue_status(&self) -> Result<BlobDownloadQueueStatus> {
+        let summary = self
+            .services
+            .projection_store
+            .summarize_download_jobs()
+            .await?;
+        Ok(BlobDownloadQueueStatus {
+            queued: summary.queued,
+            active: summary.active,
+            failed: summary.failed,
+        })
+    }
+}
@@ -0,0 +1,108 @@
+use super::*;
+
+/// 退出した channel の post を走査するときの 1 page の件数。
+const CHANNEL_BLOB_UNPIN_PAGE_SIZE: usize = 200;
+
+fn post_payload_blob_hash(payload_ref: &PayloadRef) -> Option<&kukuri_core::BlobHash> {
+    match payload_ref {
+        PayloadRef::BlobText { hash, .. } => Some(hash),
+        PayloadRef::InlineText { .. } => None,
+    }
+}
+
+fn post_projection_blob_hashes(projection: &ObjectProjectionRow) -> Vec<kukuri_core::BlobHash> {
+    post_payload_blob_hash(&projection.payload_ref)
+        .into_iter()
+        .chain(
+            projection
+                .attachments
+                .iter()
+                .map(|attachment| &attachment.hash),
+        )
+        .cloned()
+        .collect()
+}
+
+impl AppService {
+    /// 自分が配る blob(投稿本文・添付・profile 画像・カスタムリアクション等)を retention の
+    /// 対象から外す。取り消し / 差し替え時に `unpin_blobs` で戻す。
+    pub(crate) async fn pin_blobs<'a>(
+        &self,
+        hashes: impl IntoIterator<Item = &'a kukuri_core::BlobHash>,
+    ) -> Result<()> {
+        for hash in hashes {
+            self.services.blob_service.pin_blob(hash).await?;
+        }
+        Ok(())
+    }
+
+    /// pin を外すだけで削除はしない。参照が残っていれば cache として quota 内で残る。
+    pub(crate) async fn unpin_blobs<'a>(
+        &self,
+        hashes: impl IntoIterator<Item = &'a kukuri_core::BlobHash>,
+    ) -> Result<()> {
+        for hash in hashes {
+            self.services.blob_service.unpin_blob(hash).await?;
+        }
+        Ok(())
+    }
+
+    /// 取り消した自分の post の本文(編集前の版を含む)と添付の pin を外す。
+    pub(crate) async fn unpin_post_blobs(&self, projection: &ObjectProjectionRow) -> Result<()> {
+        let mut hashes = post_projection_blob_hashes(projection);
+        let history = load_post_history_envelopes(
+            self.services.docs_sync.as_ref(),
+            &projection.source_replica_id,
+            &projection.object_id,
+        )
+        .await?;
+        for envelope in history {
+            let payload_ref = if let Some(revision) = envelope.post_revision_content()? {
+                revision.payload_ref
+            } else {
+                envelope.post_content()?.map(|content| content.payload_ref)
+            };
+            hashes.extend(
+                payload_ref
+                    .as_ref()
+                    .and_then(post_payload_blob_hash)
+                    .cloned(),
+            );
+        }
+        self.unpin_blobs(hashes.iter()).await
+    }
+
+    /// 退出した private channel に自分が書いた post の blob の pin を外す。
+    pub(crate) async fn unpin_own_channel_blobs(
+        &self,
+        topic_id: &str,
+        channel_id: &ChannelId,
+    ) -> Result<()> {
+        let local_author = self.current_author_pubkey();
+        let allowed_channels = BTreeSet::from([channel_id.as_str().to_string()]);
+        let mut cursor = None;
+        loop {
+            let page = self
+                .services
+                .projection_store
+                .list_topic_timeline_filtered(
+                    topic_id,
+                    &allowed_channels,
+                    cursor,
+                    CHANNEL_BLOB_UNPIN_PAGE_SIZE,
+                )
+                .await?;
+            let hashes = page
+                .items
+                .iter()
+                .filter(|projection| projection.author_pubkey == local_author)
+                .flat_map(post_projection_blob_hashes)
+                .collect::<Vec<_>>();
+            self.unpin_blobs(hashes.iter()).await?;
+            let Some(next_cursor) = page.next_cursor else {
+                return Ok(());
+            };
+            cursor = Some(next_cursor);
+        }
+    }
+}
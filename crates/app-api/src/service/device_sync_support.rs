@@ -0,0 +1,432 @@
+use serde::Deserialize;
+
+use super::*;
+
+pub(crate) const DEVICE_STATE_BOOKMARKED_POSTS_PREFIX: &str = "bookmarks/posts";
+pub(crate) const DEVICE_STATE_BOOKMARKED_REACTIONS_PREFIX: &str = "bookmarks/reactions";
+pub(crate) const DEVICE_STATE_MUTES_PREFIX: &str = "mutes";
+pub(crate) const DEVICE_STATE_PRIVATE_CHANNELS_PREFIX: &str = "private-channels";
+pub(crate) const DEVICE_STATE_DM_DELETIONS_PREFIX: &str = "dm/deleted";
+pub(crate) const DEVICE_STATE_DM_CLEARS_PREFIX: &str = "dm/cleared";
+pub(crate) const DEVICE_STATE_NOTIFICATION_READS_PREFIX: &str = "notifications/read";
+pub(crate) const DEVICE_STATE_NOTIFICATIONS_ALL_READ_KEY: &str = "notifications/all-read";
+
+/// device replica の 1 key 分の状態。key 単位の last-writer-wins で merge する。
+/// 削除も `removed` 付きの entry として残し、他端末へ伝播させる。
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct DeviceStateEntryV1 {
+    pub(crate) updated_at: i64,
+    #[serde(default)]
+    pub(crate) removed: bool,
+    #[serde(default)]
+    pub(crate) value: serde_json::Value,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct DeviceDirectMessageDeletionV1 {
+    pub(crate) peer_pubkey: String,
+    pub(crate) message_id: String,
+    pub(crate) deleted_at: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct DeviceDirectMessageClearV1 {
+    pub(crate) peer_pubkey: String,
+    pub(crate) cleared_at: i64,
+}
+
+pub(crate) fn device_authorization_key(device_id: &str) -> String {
+    stable_key("devices", format!("{device_id}/authorization").as_str())
+}
+
+pub(crate) async fn persist_device_authorization(
+    docs_sync: &dyn DocsSync,
+    envelope: &KukuriEnvelope,
+) -> Result<DeviceAuthorization> {
+    let authorization = parse_device_authorization(envelope)?;
+    let replica = author_replica_id(authorization.author_pubkey.as_str());
+    docs_sync.open_replica(&replica).await?;
+    docs_sync
+        .apply_doc_op(
+            &replica,
+            DocOp::SetJson {
+                key: device_authorization_key(authorization.device_id.as_str()),
+                value: serde_json::to_value(envelope)?,
+            },
+        )
+        .await?;
+    Ok(authorization)
+}
+
+pub(crate) async fn load_device_authorizations(
+    docs_sync: &dyn DocsSync,
+    author_pubkey: &str,
+    policy: DocFetchPolicy,
+) -> Result<Vec<DeviceAuthorization>> {
+    let replica = author_replica_id(author_pubkey);
+    docs_sync.open_replica(&replica).await?;
+    let mut items = Vec::new();
+    for record in query_replica_with_fetch_policy(
+        docs_sync,
+        &replica,
+        DocQuery::Prefix(stable_key("devices", "")),
+        policy,
+    )
+    .await?
+    {
+        if !record.key.ends_with("/authorization") {
+            continue;
+        }
+        let parsed = serde_json::from_slice::<KukuriEnvelope>(record.value.as_slice())
+            .map_err(anyhow::Error::from)
+            .and_then(|envelope| parse_device_authorization(&envelope));
+        match parsed {
+            Ok(authorization)
+                if authorization.author_pubkey.as_str() == author_pubkey
+                    && record.key == device_authorization_key(authorization.device_id.as_str()) =>
+            {
+                items.push(authorization);
+            }
+            Ok(_) => {}
+            Err(error) => {
+                warn!(key = %record.key, error = %error, "ignoring invalid device authorization");
+            }
+        }
+    }
+    items.sort_by(|left, right| {
+        left.authorized_at
+            .cmp(&right.authorized_at)
+            .then_with(|| left.device_id.cmp(&right.device_id))
+    });
+    Ok(items)
+}
+
+/// device replica は公開 namespace のため、復号できない(= 同じ author 鍵で書かれていない)
+/// entry は読み飛ばす。
+pub(crate) async fn load_device_state_entries(
+    docs_sync: &dyn DocsSync,
+    keys: &KukuriKeys,
+    replica: &ReplicaId,
+    policy: DocFetchPolicy,
+) -> Result<BTreeMap<String, DeviceStateEntryV1>> {
+    docs_sync.open_replica(replica).await?;
+    let mut entries = BTreeMap::new();
+    for record in query_replica_with_fetch_policy(docs_sync, replica, DocQuery::All, policy).await?
+    {
+        match decode_device_state_entry(keys, &record) {
+            Ok(entry) => {
+                entries.insert(record.key, entry);
+            }
+            Err(error) => {
+                warn!(
+                    replica_id = %replica.as_str(),
+                    key = %record.key,
+                    error = %error,
+                    "ignoring unreadable device state entry"
+                );
+            }
+        }
+    }
+    Ok(entries)
+}
+
+fn decode_device_state_entry(keys: &KukuriKeys, record: &DocRecord) -> Result<DeviceStateEntryV1> {
+    let doc: DeviceStateDocV1 = serde_json::from_slice(record.value.as_slice())?;
+    decrypt_device_state_doc(keys, record.key.as_str(), &doc)
+}
+
+async fn write_device_state_entry(
+    docs_sync: &dyn DocsSync,
+    keys: &KukuriKeys,
+    replica: &ReplicaId,
+    key: &str,
+    entry: &DeviceStateEntryV1,
+) -> Result<()> {
+    docs_sync.open_replica(replica).await?;
+    docs_sync
+        .apply_doc_op(
+            replica,
+            DocOp::SetJson {
+                key: key.to_string(),
+                value: serde_json::to_value(encrypt_device_state_doc(keys, key, entry)?)?,
+            },
+        )
+        .await
+}
+
+impl AppService {
+    pub(crate) fn local_device_replica_id(&self) -> Option<ReplicaId> {
+        self.local_device_id()
+            .map(|device_id| device_replica_id(self.current_author_pubkey().as_str(), device_id))
+    }
+
+    /// ローカルでの変更を自端末の device replica へ写す。端末間同期は補助的なので、
+    /// 失敗しても元の操作は失敗させない。`removed` の場合も `value` には対象を識別
+    /// できる値を渡す。
+    pub(crate) async fn record_device_state<T: Serialize>(
+        &self,
+        key: &str,
+        value: &T,
+        removed: bool,
+    ) {
+        if let Err(error) = self.try_record_device_state(key, value, removed).await {
+            warn!(key = %key, error = %error, "failed to record device state");
+        }
+    }
+
+    async fn try_record_device_state<T: Serialize>(
+        &self,
+        key: &str,
+        value: &T,
+        removed: bool,
+    ) -> Result<()> {
+        let Some(replica) = self.local_device_replica_id() else {
+            return Ok(());
+        };
+        let value = serde_json::to_value(value)?;
+        self.docs_sync().open_replica(&replica).await?;
+        // 同じ内容の書き直しは updated_at だけが進み、他端末で無駄な再適用を招くため省く。
+        if let Some(record) =
+            query_replica_local_only(self.docs_sync(), &replica, DocQuery::Exact(key.to_string()))
+                .await?
+                .into_iter()
+                .next()
+            && let Ok(existing) = decode_device_state_entry(self.keys(), &record)
+            && existing.removed == removed
+            && existing.value == value
+        {
+            return Ok(());
+        }
+        write_device_state_entry(
+            self.docs_sync(),
+            self.keys(),
+            &replica,
+            key,
+            &DeviceStateEntryV1 {
+                updated_at: Utc::now().timestamp_millis(),
+                removed,
+                value,
+            },
+        )
+        .await
+    }
+
+    /// 同じ author の他端末の device replica を読み、自端末より新しい entry を
+    /// ローカルへ適用して自端末の replica にも取り込む。適用した entry 数を返す。
+    pub(crate) async fn merge_linked_device_state(&self, policy: DocFetchPolicy) -> Result<usize> {
+        let Some(local_device_id) = self.local_device_id() else {
+            return Ok(0);
+        };
+        let author_pubkey = self.current_author_pubkey();
+        let own_replica = device_replica_id(author_pubkey.as_str(), local_device_id);
+        let mut own_entries = load_device_state_entries(
+            self.docs_sync(),
+            self.keys(),
+            &own_replica,
+            DocFetchPolicy::LocalOnly,
+        )
+        .await?;
+        let mut applied = 0;
+        for authorization in
+            load_device_authorizations(self.docs_sync(), author_pubkey.as_str(), policy).await?
+        {
+            if authorization.device_id == local_device_id {
+                continue;
+            }
+            let replica =
+                device_replica_id(author_pubkey.as_str(), authorization.device_id.as_str());
+            let entries =
+                match load_device_state_entries(self.docs_sync(), self.keys(), &replica, policy)
+                    .await
+                {
+                    Ok(entries) => entries,
+                    Err(error) => {
+                        warn!(
+                            device_id = %authorization.device_id,
+                            error = %error,
+                            "failed to load linked device state"
+                        );
+                        continue;
+                    }
+                };
+            for (key, entry) in entries {
+                if own_entries
+                    .get(&key)
+                    .is_some_and(|own| own.updated_at >= entry.updated_at)
+                {
+                    continue;
+                }
+                if let Err(error) = self.apply_device_state_entry(key.as_str(), &entry).await {
+                    warn!(
+                        device_id = %authorization.device_id,
+                        key = %key,
+                        error = %error,
+                        "failed to apply linked device state"
+                    );
+                    continue;
+                }
+                write_device_state_entry(
+                    self.docs_sync(),
+                    self.keys(),
+                    &own_replica,
+                    key.as_str(),
+                    &entry,
+                )
+                .await?;
+                own_entries.insert(key, entry);
+                applied += 1;
+            }
+        }
+        Ok(applied)
+    }
+
+    async fn apply_device_state_entry(&self, key: &str, entry: &DeviceStateEntryV1) -> Result<()> {
+        let projection_store = self.services.projection_store.as_ref();
+        if let Some(source_object_id) =
+            device_state_key_suffix(key, DEVICE_STATE_BOOKMARKED_POSTS_PREFIX)
+        {
+            if entry.removed {
+                return projection_store
+                    .remove_bookmarked_post(&EnvelopeId::from(source_object_id))
+                    .await;
+            }
+            let row: BookmarkedPostRow = serde_json::from_value(entry.value.clone())?;
+            return projection_store.put_bookmarked_post(row).await;
+        }
+        if let Some(asset_id) =
+            device_state_key_suffix(key, DEVICE_STATE_BOOKMARKED_REACTIONS_PREFIX)
+        {
+            if entry.removed {
+                return projection_store
+                    .remove_bookmarked_custom_reaction(asset_id)
+                    .await;
+            }
+            let row: BookmarkedCustomReactionRow = serde_json::from_value(entry.value.clone())?;
+            return projection_store.put_bookmarked_custom_reaction(row).await;
+        }
+        if let Some(author_pubkey) = device_state_key_suffix(key, DEVICE_STATE_MUTES_PREFIX) {
+            if entry.removed {
+                return projection_store.remove_muted_author(author_pubkey).await;
+            }
+            let row: MutedAuthorRow = serde_json::from_value(entry.value.clone())?;
+            return projection_store.put_muted_author(row).await;
+        }
+        if device_state_key_suffix(key, DEVICE_STATE_PRIVATE_CHANNELS_PREFIX).is_some() {
+            let capability: PrivateChannelCapability = serde_json::from_value(entry.value.clone())?;
+            if entry.removed {
+                self.remove_joined_private_channel(
+                    capability.topic_id.as_str(),
+                    capability.channel_id.as_str(),
+                )
+                .await?;
+                return Ok(());
+            }
+            return self.restore_private_channel_capability(capability).await;
+        }
+        if device_state_key_suffix(key, DEVICE_STATE_DM_DELETIONS_PREFIX).is_some() {
+            let deletion: DeviceDirectMessageDeletionV1 =
+                serde_json::from_value(entry.value.clone())?;
+            // 未着の message でも tombstone を先に置いておけば、後から届いた時に弾かれる。
+            return self
+                .delete_direct_message_messages_locally(
+                    deletion.peer_pubkey.as_str(),
+                    vec![deletion.message_id],
+                    deletion.deleted_at,
+                )
+                .await;
+        }
+        if device_state_key_suffix(key, DEVICE_STATE_DM_CLEARS_PREFIX).is_some() {
+            let clear: DeviceDirectMessageClearV1 = serde_json::from_value(entry.value.clone())?;
+            let message_ids = self
+                .direct_message_message_ids_until(clear.peer_pubkey.as_str(), clear.cleared_at)
+                .await?;
+            return self
+                .delete_direct_message_messages_locally(
+                    clear.peer_pubkey.as_str(),
+                    message_ids,
+                    clear.cleared_at,
+                )
+                .await;
+        }
+        if key == DEVICE_STATE_NOTIFICATIONS_ALL_READ_KEY {
+            let read_at: i64 = serde_json::from_value(entry.value.clone())?;
+            return projection_store.mark_all_notifications_read(read_at).await;
+        }
+        if let Some(notification_id) =
+            device_state_key_suffix(key, DEVICE_STATE_NOTIFICATION_READS_PREFIX)
+        {
+            let read_at: i64 = serde_json::from_value(entry.value.clone())?;
+            return projection_store
+                .mark_notification_read(notification_id, read_at)
+                .await;
+        }
+        // 新しい版の端末が書いた未知の key は無視する。
+        Ok(())
+    }
+
+    async fn direct_message_message_ids_until(
+        &self,
+        peer_pubkey: &str,
+        until: i64,
+    ) -> Result<Vec<String>> {
+        let dm_id = direct_message_id_for_participants(
+            &Pubkey::from(self.current_author_pubkey()),
+            &Pubkey::from(normalize_author_pubkey(peer_pubkey)?.as_str()),
+        );
+        let mut message_ids = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = self
+                .services
+                .projection_store
+                .list_direct_message_messages(dm_id.as_str(), cursor.clone(), 500)
+                .await?;
+            message_ids.extend(
+                page.items
+                    .iter()
+                    .filter(|message| message.created_at <= until)
+                    .map(|message| message.message_id.clone()),
+            );
+            if page.next_cursor.is_none() {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+        Ok(message_ids)
+    }
+
+    async fn delete_direct_message_messages_locally(
+        &self,
+        peer_pubkey: &str,
+        message_ids: Vec<String>,
+        deleted_at: i64,
+    ) -> Result<()> {
+        let peer_pubkey = normalize_author_pubkey(peer_pubkey)?;
+        let dm_id = direct_message_id_for_participants(
+            &Pubkey::from(self.current_author_pubkey()),
+            &Pubkey::from(peer_pubkey.as_str()),
+        );
+        for message_id in message_ids {
+            self.services
+                .projection_store
+                .put_direct_message_tombstone(DirectMessageTombstoneRow {
+                    dm_id: dm_id.clone(),
+                    message_id: message_id.clone(),
+                    deleted_at,
+                })
+                .await?;
+            self.services
+                .projection_store
+                .delete_direct_message_message_local(dm_id.as_str(), message_id.as_str())
+                .await?;
+        }
+        self.refresh_direct_message_conversation(peer_pubkey.as_str())
+            .await
+    }
+}
+
+fn device_state_key_suffix<'a>(key: &'a str, prefix: &str) -> Option<&'a str> {
+    key.strip_prefix(prefix)?
+        .strip_prefix('/')
+        .filter(|suffix| !suffix.is_empty())
+}
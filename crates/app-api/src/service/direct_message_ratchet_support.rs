@@ -0,0 +1,230 @@
+use super::*;
+
+/// 1 会話あたりに保持する skipped message key の上限。超えた分は古い順に捨てる
+/// (捨てた key の frame は復号できなくなるが、無制限に溜めると forward secrecy が痩せる)。
+const DIRECT_MESSAGE_SKIPPED_KEY_LIMIT: usize = 2000;
+
+/// 相手が広告した frame version。未交渉の会話は v1。
+pub(crate) async fn direct_message_peer_frame_version(
+    projection_store: &dyn ProjectionStore,
+    dm_id: &str,
+) -> Result<u8> {
+    Ok(projection_store
+        .get_direct_message_session(dm_id)
+        .await?
+        .map(|session| session.peer_frame_version)
+        .unwrap_or(DIRECT_MESSAGE_FRAME_VERSION_V1))
+}
+
+/// 送信 frame を暗号化して blob 用の bytes を返す。payload には対応 version と自分の session
+/// prekey を載せる。v2 では ratchet session を進め、frame を outbox に載せる前に state を保存する
+/// (再送は同じ blob を使うため二重に進めない)。
+pub(crate) async fn seal_direct_message_frame(
+    services: &ServiceHandles,
+    frame_version: u8,
+    peer_pubkey: &str,
+    dm_id: &str,
+    message_id: &str,
+    created_at: i64,
+    payload: &DirectMessagePayloadV1,
+) -> Result<Vec<u8>> {
+    let keys = services.keys.as_ref();
+    let peer = Pubkey::from(peer_pubkey);
+    let _guard = services.direct_message_session_lock.lock().await;
+    let projection_store = services.projection_store.as_ref();
+    let session = projection_store.get_direct_message_session(dm_id).await?;
+    let mut row = session_row(session.as_ref(), dm_id, peer_pubkey, frame_version);
+    let mut payload = payload.clone();
+    payload.supported_frame_versions = supported_direct_message_frame_versions();
+    payload.session_prekey = Some(ensure_session_prekey(&mut row)?.public_key());
+
+    let frame_bytes = if frame_version < DIRECT_MESSAGE_FRAME_VERSION_V2 {
+        let frame =
+            encrypt_direct_message_frame(keys, &peer, dm_id, message_id, created_at, &payload)?;
+        serde_json::to_vec(&frame).context("failed to encode direct message frame blob")?
+    } else {
+        let state = decode_ratchet_state(session.as_ref())?;
+        let peer_prekey = row.peer_prekey_pubkey.as_deref().map(Pubkey::from);
+        let encrypted = encrypt_direct_message_frame_v2(
+            keys,
+            state.as_ref(),
+            &peer,
+            peer_prekey.as_ref(),
+            dm_id,
+            message_id,
+            created_at,
+            &payload,
+        )?;
+        row.ratchet_state_json = Some(encode_ratchet_state(&encrypted.state)?);
+        serde_json::to_vec(&encrypted.frame)
+            .context("failed to encode direct message frame blob")?
+    };
+    put_direct_message_session(projection_store, session.as_ref(), row).await?;
+    Ok(frame_bytes)
+}
+
+/// 受信 frame を復号する。v1 payload の広告や v2 frame の受信で相手の対応 version と
+/// session prekey を記録し、以後の送信を v2 へ切り替える。自分の prekey に対する init を
+/// 採用したら、その prekey の秘密鍵を捨てて新しい prekey に差し替える。
+pub(crate) async fn open_direct_message_frame(
+    services: &ServiceHandles,
+    frame: &DirectMessageFrame,
+) -> Result<DirectMessagePayloadV1> {
+    let keys = services.keys.as_ref();
+    let projection_store = services.projection_store.as_ref();
+    let peer_pubkey = frame.sender().as_str();
+    let frame = match frame {
+        DirectMessageFrame::V1(frame) => {
+            let payload = decrypt_direct_message_frame(keys, frame)?;
+            let supports_v2 = payload
+                .supported_frame_versions
+                .contains(&DIRECT_MESSAGE_FRAME_VERSION_V2);
+            if supports_v2 || payload.session_prekey.is_some() {
+                let _guard = services.direct_message_session_lock.lock().await;
+                let session = projection_store
+                    .get_direct_message_session(frame.dm_id.as_str())
+                    .await?;
+                let mut row = session_row(
+                    session.as_ref(),
+                    frame.dm_id.as_str(),
+                    peer_pubkey,
+                    DIRECT_MESSAGE_FRAME_VERSION_V1,
+                );
+                if supports_v2 {
+                    row.peer_frame_version =
+                        row.peer_frame_version.max(DIRECT_MESSAGE_FRAME_VERSION_V2);
+                }
+                remember_peer_prekey(&mut row, &payload);
+                put_direct_message_session(projection_store, session.as_ref(), row).await?;
+            }
+            return Ok(payload);
+        }
+        DirectMessageFrame::V2(frame) => frame,
+    };
+
+    // skipped key は取り出すと消えるため、署名を確かめてから引く。
+    frame.verify()?;
+    let _guard = services.direct_message_session_lock.lock().await;
+    let dm_id = frame.dm_id.as_str();
+    let session = projection_store.get_direct_message_session(dm_id).await?;
+    let state = decode_ratchet_state(session.as_ref())?;
+    let mut row = session_row(
+        session.as_ref(),
+        dm_id,
+        peer_pubkey,
+        DIRECT_MESSAGE_FRAME_VERSION_V2,
+    );
+    row.peer_frame_version = DIRECT_MESSAGE_FRAME_VERSION_V2;
+    let session_prekey = row
+        .local_prekey_secret_hex
+        .as_deref()
+        .map(KukuriKeys::parse)
+        .transpose()
+        .context("invalid direct message session prekey")?;
+    let skipped_message_key = projection_store
+        .take_direct_message_skipped_key(
+            dm_id,
+            frame.header.ratchet_pubkey.as_str(),
+            frame.header.message_number,
+        )
+        .await?;
+    let decrypted = decrypt_direct_message_frame_v2(
+        keys,
+        session_prekey.as_ref(),
+        state.as_ref(),
+        frame,
+        skipped_message_key.as_deref(),
+    )?;
+    if !decrypted.skipped_keys.is_empty() {
+        let created_at = Utc::now().timestamp_millis();
+        projection_store
+            .put_direct_message_skipped_keys(
+                decrypted
+                    .skipped_keys
+                    .into_iter()
+                    .map(|key| DirectMessageSkippedKeyRow {
+                        dm_id: dm_id.to_string(),
+                        ratchet_pubkey: key.ratchet_pubkey.as_str().to_string(),
+                        message_number: key.message_number,
+                        message_key_hex: key.message_key_hex,
+                        created_at,
+                    })
+                    .collect(),
+            )
+            .await?;
+        projection_store
+            .prune_direct_message_skipped_keys(dm_id, DIRECT_MESSAGE_SKIPPED_KEY_LIMIT)
+            .await?;
+    }
+    if let Some(state) = decrypted.state.as_ref() {
+        row.ratchet_state_json = Some(encode_ratchet_state(state)?);
+    }
+    if decrypted.session_prekey_consumed {
+        row.local_prekey_secret_hex = None;
+        ensure_session_prekey(&mut row)?;
+    }
+    remember_peer_prekey(&mut row, &decrypted.payload);
+    put_direct_message_session(projection_store, session.as_ref(), row).await?;
+    Ok(decrypted.payload)
+}
+
+/// 既存の session 行を複製する。無ければ `peer_frame_version` で新しい行を作る。
+fn session_row(
+    session: Option<&DirectMessageSessionRow>,
+    dm_id: &str,
+    peer_pubkey: &str,
+    peer_frame_version: u8,
+) -> DirectMessageSessionRow {
+    session.cloned().unwrap_or_else(|| DirectMessageSessionRow {
+        dm_id: dm_id.to_string(),
+        peer_pubkey: peer_pubkey.to_string(),
+        peer_frame_version,
+        ratchet_state_json: None,
+        local_prekey_secret_hex: None,
+        peer_prekey_pubkey: None,
+        updated_at: 0,
+    })
+}
+
+/// 広告中の session prekey を返す。未生成なら作って行に載せる。
+fn ensure_session_prekey(row: &mut DirectMessageSessionRow) -> Result<KukuriKeys> {
+    if let Some(secret_hex) = row.local_prekey_secret_hex.as_deref() {
+        return KukuriKeys::parse(secret_hex).context("invalid direct message session prekey");
+    }
+    let prekey = KukuriKeys::generate();
+    row.local_prekey_secret_hex = Some(prekey.export_secret_hex());
+    Ok(prekey)
+}
+
+fn remember_peer_prekey(row: &mut DirectMessageSessionRow, payload: &DirectMessagePayloadV1) {
+    if let Some(prekey) = payload.session_prekey.as_ref() {
+        row.peer_prekey_pubkey = Some(prekey.as_str().to_string());
+    }
+}
+
+/// 変更があった時だけ session 行を保存する。
+async fn put_direct_message_session(
+    projection_store: &dyn ProjectionStore,
+    previous: Option<&DirectMessageSessionRow>,
+    mut row: DirectMessageSessionRow,
+) -> Result<()> {
+    if previous == Some(&row) {
+        return Ok(());
+    }
+    row.updated_at = Utc::now().timestamp_millis();
+    projection_store.put_direct_message_session(row).await
+}
+
+fn encode_ratchet_state(state: &DirectMessageRatchetStateV2) -> Result<String> {
+    serde_json::to_string(state).context("failed to encode direct message ratchet state")
+}
+
+fn decode_ratchet_state(
+    session: Option<&DirectMessageSessionRow>,
+) -> Result<Option<DirectMessageRatchetStateV2>> {
+    session
+        .and_then(|session| session.ratchet_state_json.as_deref())
+        .map(serde_json::from_str)
+        .transpose()
+        .context("failed to decode direct message ratchet state")
+}
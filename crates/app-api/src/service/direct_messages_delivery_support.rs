@@ -26,7 +26,7 @@ impl AppService {
                 frame_hash,
                 ..
             } => {
-                AppService::ingest_direct_message_frame(
+                let ingested = AppService::ingest_direct_message_frame(
                     services,
                     local_author_pubkey,
                     peer_pubkey,
@@ -35,7 +35,15 @@ impl AppService {
                     message_id.as_str(),
                     frame_hash,
                 )
-                .await
+                .await?;
+                if ingested {
+                    services.emit_content_event(ContentEvent::DirectMessageReceived {
+                        peer_pubkey: peer_pubkey.to_string(),
+                        dm_id: dm_id.clone(),
+                        message_id: message_id.clone(),
+                    });
+                }
+                Ok(ingested)
             }
             GossipHint::DirectMessageAck { ack, .. } => {
                 ack.verify()?;
@@ -54,6 +62,11 @@ impl AppService {
                 projection_store
                     .remove_direct_message_outbox(ack.dm_id.as_str(), ack.message_id.as_str())
                     .await?;
+                services.emit_content_event(ContentEvent::DirectMessageAcked {
+                    peer_pubkey: peer_pubkey.to_string(),
+                    dm_id: ack.dm_id.clone(),
+                    message_id: ack.message_id.clone(),
+                });
                 Ok(true)
             }
             _ => Ok(false),
@@ -172,22 +185,26 @@ impl AppService {
         let Some(frame_bytes) = blob_service.fetch_blob(frame_hash).await? else {
             return Ok(false);
         };
-        let frame: DirectMessageFrameV1 = serde_json::from_slice(frame_bytes.as_slice())
+        let frame: DirectMessageFrame = serde_json::from_slice(frame_bytes.as_slice())
             .context("failed to decode direct message frame blob")?;
-        if frame.message_id != message_id || frame.dm_id != dm_id {
+        if frame.message_id() != message_id || frame.dm_id() != dm_id {
             return Ok(false);
         }
-        if frame.sender.as_str() != peer_pubkey || frame.recipient.as_str() != local_author_pubkey {
+        if frame.sender().as_str() != peer_pubkey
+            || frame.recipient().as_str() != local_author_pubkey
+        {
             return Ok(false);
         }
-        let payload = decrypt_direct_message_frame(keys, &frame)?;
+        frame.verify()?;
         let ack = build_direct_message_ack(
             keys,
             dm_id,
             message_id,
-            &frame.sender,
+            frame.sender(),
             Utc::now().timestamp_millis(),
         )?;
+        // outbox の再送で同じ frame が届いても ratchet を二重に進めないよう、
+        // 受信済み/削除済みの判定は復号より先に行う。
         if projection_store
             .has_direct_message_tombstone(dm_id, message_id)
             .await?
@@ -219,20 +236,49 @@ impl AppService {
                 .await?;
             return Ok(false);
         }
+        let payload = open_direct_message_frame(services, &frame).await?;
+        if let Some(update) = payload.group_key_update.as_ref() {
+            // グループ鍵更新は会話に表示しない。再送は tombstone で ack だけ返す。
+            let applied = apply_group_direct_message_key_update(
+                projection_store,
+                local_author_pubkey,
+                peer_pubkey,
+                update,
+            )
+            .await?;
+            projection_store
+                .put_direct_message_tombstone(DirectMessageTombstoneRow {
+                    dm_id: dm_id.to_string(),
+                    message_id: message_id.to_string(),
+                    deleted_at: Utc::now().timestamp_millis(),
+                })
+                .await?;
+            hint_transport
+                .publish_hint(
+                    topic,
+                    GossipHint::DirectMessageAck {
+                        topic_id: topic.clone(),
+                        ack,
+                    },
+                )
+                .await?;
+            return Ok(applied);
+        }
         let local_manifest = materialize_direct_message_manifest(
             blob_service,
             keys,
-            &frame.sender,
-            frame.message_id.as_str(),
+            frame.sender(),
+            message_id,
+            payload.attachment_key_hex.as_deref(),
             payload.attachment_manifest.as_ref(),
         )
         .await?;
         let message_row = DirectMessageMessageRow {
             dm_id: dm_id.to_string(),
             message_id: message_id.to_string(),
-            sender_pubkey: frame.sender.as_str().to_string(),
-            recipient_pubkey: frame.recipient.as_str().to_string(),
-            created_at: frame.created_at,
+            sender_pubkey: frame.sender().as_str().to_string(),
+            recipient_pubkey: frame.recipient().as_str().to_string(),
+            created_at: frame.created_at(),
             text: payload.text,
             reply_to_message_id: payload.reply_to,
             attachment_manifest: local_manifest,
@@ -240,6 +286,7 @@ impl AppService {
             acked_at: None,
         };
         let preview_text = notification_preview_text(Some(direct_message_preview(&message_row)));
+        index_direct_message_for_search(projection_store, &message_row).await?;
         projection_store
             .put_direct_message_message(message_row)
             .await?;
@@ -247,8 +294,8 @@ impl AppService {
             .upsert_direct_message_conversation(DirectMessageConversationRow {
                 dm_id: dm_id.to_string(),
                 peer_pubkey: peer_pubkey.to_string(),
-                updated_at: frame.created_at,
-                last_message_at: Some(frame.created_at),
+                updated_at: frame.created_at(),
+                last_message_at: Some(frame.created_at()),
                 last_message_id: Some(message_id.to_string()),
                 last_message_preview: preview_text.clone(),
             })
@@ -267,7 +314,7 @@ impl AppService {
                 dm_id: Some(dm_id.to_string()),
                 message_id: Some(message_id.to_string()),
                 preview_text,
-                created_at: frame.created_at,
+                created_at: frame.created_at(),
                 received_at: Utc::now().timestamp_millis(),
             },
         )
@@ -375,13 +422,25 @@ impl AppService {
         {
             anyhow::bail!("direct message reply target was not found");
         }
+        let frame_version =
+            direct_message_peer_frame_version(self.services.projection_store.as_ref(), &dm_id)
+                .await?;
+        let attachment_key_hex = (frame_version >= DIRECT_MESSAGE_FRAME_VERSION_V2
+            && !attachments.is_empty())
+        .then(generate_direct_message_attachment_key);
         let (local_manifest, encrypted_manifest) = self
-            .prepare_direct_message_manifests(peer_pubkey, message_id.as_str(), attachments)
+            .prepare_direct_message_manifests(
+                peer_pubkey,
+                message_id.as_str(),
+                attachment_key_hex.as_deref(),
+                attachments,
+            )
             .await?;
         let created_at = Utc::now().timestamp_millis();
-        let frame = encrypt_direct_message_frame(
-            self.services.keys.as_ref(),
-            &Pubkey::from(peer_pubkey),
+        let frame_bytes = seal_direct_message_frame(
+            &self.services,
+            frame_version,
+            peer_pubkey,
             dm_id.as_str(),
             message_id.as_str(),
             created_at,
@@ -389,31 +448,35 @@ impl AppService {
                 text: text.clone(),
                 reply_to: normalize_optional_text(reply_to_message_id.map(str::to_string)),
                 attachment_manifest: encrypted_manifest,
+                supported_frame_versions: Vec::new(),
+                attachment_key_hex,
+                group_key_update: None,
+                session_prekey: None,
             },
-        )?;
-        let frame_bytes =
-            serde_json::to_vec(&frame).context("failed to encode direct message frame blob")?;
+        )
+        .await?;
         let frame_blob = self
             .services
             .blob_service
             .put_blob(frame_bytes, DIRECT_MESSAGE_FRAME_MIME)
             .await?;
+        let message_row = DirectMessageMessageRow {
+            dm_id: dm_id.clone(),
+            message_id: message_id.clone(),
+            sender_pubkey: self.current_author_pubkey(),
+            recipient_pubkey: peer_pubkey.to_string(),
+            created_at,
+            text,
+            reply_to_message_id: normalize_optional_text(reply_to_message_id.map(str::to_string)),
+            attachment_manifest: local_manifest,
+            outgoing: true,
+            acked_at: None,
+        };
+        index_direct_message_for_search(self.services.projection_store.as_ref(), &message_row)
+            .await?;
         self.services
             .projection_store
-            .put_direct_message_message(DirectMessageMessageRow {
-                dm_id: dm_id.clone(),
-                message_id: message_id.clone(),
-                sender_pubkey: self.current_author_pubkey(),
-                recipient_pubkey: peer_pubkey.to_string(),
-                created_at,
-                text,
-                reply_to_message_id: normalize_optional_text(
-                    reply_to_message_id.map(str::to_string),
-                ),
-                attachment_manifest: local_manifest,
-                outgoing: true,
-                acked_at: None,
-            })
+            .put_direct_message_message(message_row)
             .await?;
         self.services
             .projection_store
@@ -441,6 +504,7 @@ impl AppService {
         &self,
         peer_pubkey: &str,
         message_id: &str,
+        attachment_key_hex: Option<&str>,
         attachments: Vec<PendingAttachment>,
     ) -> Result<(
         Option<DirectMessageAttachmentManifestV1>,
@@ -470,9 +534,10 @@ impl AppService {
                     .blob_service
                     .put_blob(image.bytes.clone(), image.mime.as_str())
                     .await?;
-                let encrypted = encrypt_direct_message_attachment(
+                let encrypted = encrypt_direct_message_attachment_for(
                     self.services.keys.as_ref(),
                     &Pubkey::from(peer_pubkey),
+                    attachment_key_hex,
                     message_id,
                     "original",
                     image.bytes.as_slice(),
@@ -532,16 +597,18 @@ impl AppService {
                     .blob_service
                     .put_blob(poster.bytes.clone(), poster.mime.as_str())
                     .await?;
-                let encrypted_video = encrypt_direct_message_attachment(
+                let encrypted_video = encrypt_direct_message_attachment_for(
                     self.services.keys.as_ref(),
                     &Pubkey::from(peer_pubkey),
+                    attachment_key_hex,
                     message_id,
                     "original",
                     video.bytes.as_slice(),
                 )?;
-                let encrypted_poster = encrypt_direct_message_attachment(
+                let encrypted_poster = encrypt_direct_message_attachment_for(
                     self.services.keys.as_ref(),
                     &Pubkey::from(peer_pubkey),
+                    attachment_key_hex,
                     message_id,
                     "poster",
                     poster.bytes.as_slice(),
@@ -609,3 +676,24 @@ impl AppService {
         }
     }
 }
+
+fn encrypt_direct_message_attachment_for(
+    keys: &KukuriKeys,
+    peer_pubkey: &Pubkey,
+    attachment_key_hex: Option<&str>,
+    message_id: &str,
+    blob_id: &str,
+    plaintext: &[u8],
+) -> Result<DirectMessageEncryptedAttachmentV1> {
+    match attachment_key_hex {
+        Some(attachment_key_hex) => encrypt_direct_message_attachment_with_key(
+            attachment_key_hex,
+            message_id,
+            blob_id,
+            plaintext,
+        ),
+        None => {
+            encrypt_direct_message_attachment(keys, peer_pubkey, message_id, blob_id, plaintext)
+        }
+    }
+}
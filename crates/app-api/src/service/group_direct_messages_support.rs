@@ -0,0 +1,857 @@
+use super::*;
+
+/// owner から届いた鍵更新を反映する。自分が残るなら epoch の鍵を保存し、外されたなら
+/// 会話を removed として残す(履歴は古い epoch の鍵で引き続き読める)。
+pub(crate) async fn apply_group_direct_message_key_update(
+    projection_store: &dyn ProjectionStore,
+    local_author_pubkey: &str,
+    sender_pubkey: &str,
+    update: &GroupDirectMessageKeyUpdateV1,
+) -> Result<bool> {
+    update.validate()?;
+    if update.owner.as_str() != sender_pubkey {
+        anyhow::bail!("group direct message key update must come from the owner");
+    }
+    let existing = projection_store
+        .get_group_direct_message_conversation(update.group_id.as_str())
+        .await?;
+    if let Some(existing) = existing.as_ref() {
+        if existing.owner_pubkey != update.owner.as_str() {
+            anyhow::bail!("group direct message owner mismatch");
+        }
+        if existing.epoch >= update.epoch {
+            return Ok(false);
+        }
+    }
+    let removed_at = if update.is_member(&Pubkey::from(local_author_pubkey)) {
+        let epoch_secret_hex = update
+            .epoch_secret_hex
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("group direct message key update is missing secret"))?;
+        projection_store
+            .put_group_direct_message_epoch(GroupDirectMessageEpochRow {
+                group_id: update.group_id.clone(),
+                epoch: update.epoch,
+                epoch_secret_hex: epoch_secret_hex.to_string(),
+                members: update
+                    .members
+                    .iter()
+                    .map(|member| member.as_str().to_string())
+                    .collect(),
+                created_at: update.created_at,
+            })
+            .await?;
+        None
+    } else if existing.is_some() {
+        Some(update.created_at)
+    } else {
+        return Ok(false);
+    };
+    projection_store
+        .upsert_group_direct_message_conversation(GroupDirectMessageConversationRow {
+            group_id: update.group_id.clone(),
+            owner_pubkey: update.owner.as_str().to_string(),
+            title: update.title.clone(),
+            epoch: update.epoch,
+            members: update
+                .members
+                .iter()
+                .map(|member| member.as_str().to_string())
+                .collect(),
+            removed_at,
+            updated_at: existing
+                .as_ref()
+                .map(|row| row.updated_at.max(update.created_at))
+                .unwrap_or(update.created_at),
+            last_message_at: existing.as_ref().and_then(|row| row.last_message_at),
+            last_message_id: existing
+                .as_ref()
+                .and_then(|row| row.last_message_id.clone()),
+            last_message_preview: existing.and_then(|row| row.last_message_preview),
+        })
+        .await?;
+    Ok(true)
+}
+
+/// 現在の epoch の topic。自分が外された会話や鍵の無い epoch は None。
+async fn current_group_direct_message_topic(
+    projection_store: &dyn ProjectionStore,
+    local_author_pubkey: &str,
+    row: &GroupDirectMessageConversationRow,
+) -> Result<Option<TopicId>> {
+    if row.removed_at.is_some()
+        || !row
+            .members
+            .iter()
+            .any(|member| member == local_author_pubkey)
+    {
+        return Ok(None);
+    }
+    let Some(epoch) = projection_store
+        .get_group_direct_message_epoch(row.group_id.as_str(), row.epoch)
+        .await?
+    else {
+        return Ok(None);
+    };
+    derive_group_direct_message_topic(
+        row.group_id.as_str(),
+        row.epoch,
+        epoch.epoch_secret_hex.as_str(),
+    )
+    .map(Some)
+}
+
+impl AppService {
+    /// 画面に出さない pairwise DM(グループ鍵更新)を送る。message 行は作らず outbox だけ積む。
+    pub(crate) async fn queue_direct_message_control_payload(
+        &self,
+        peer_pubkey: &str,
+        message_id: &str,
+        payload: DirectMessagePayloadV1,
+    ) -> Result<()> {
+        let dm_id = direct_message_id_for_participants(
+            &Pubkey::from(self.current_author_pubkey()),
+            &Pubkey::from(peer_pubkey),
+        );
+        let frame_version =
+            direct_message_peer_frame_version(self.services.projection_store.as_ref(), &dm_id)
+                .await?;
+        let created_at = Utc::now().timestamp_millis();
+        let frame_bytes = seal_direct_message_frame(
+            &self.services,
+            frame_version,
+            peer_pubkey,
+            dm_id.as_str(),
+            message_id,
+            created_at,
+            &payload,
+        )
+        .await?;
+        let frame_blob = self
+            .services
+            .blob_service
+            .put_blob(frame_bytes, DIRECT_MESSAGE_FRAME_MIME)
+            .await?;
+        self.services
+            .projection_store
+            .put_direct_message_outbox(DirectMessageOutboxRow {
+                dm_id,
+                message_id: message_id.to_string(),
+                peer_pubkey: peer_pubkey.to_string(),
+                frame_blob_hash: frame_blob.hash,
+                created_at,
+                last_attempt_at: None,
+            })
+            .await?;
+        self.ensure_direct_message_subscription(peer_pubkey).await?;
+        let _ = Self::flush_direct_message_outbox_for_peer(
+            &self.services,
+            self.current_author_pubkey().as_str(),
+            peer_pubkey,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// owner として新しい epoch の鍵を保存し、残るメンバーには鍵を、外したメンバーには
+    /// 鍵無しの更新を配る。
+    pub(crate) async fn distribute_group_direct_message_epoch(
+        &self,
+        group: &GroupDirectMessageConversationRow,
+        removed_members: &[String],
+    ) -> Result<()> {
+        let local_author_pubkey = self.current_author_pubkey();
+        let created_at = Utc::now().timestamp_millis();
+        let epoch_secret_hex = generate_group_direct_message_epoch_secret();
+        self.services
+            .projection_store
+            .put_group_direct_message_epoch(GroupDirectMessageEpochRow {
+                group_id: group.group_id.clone(),
+                epoch: group.epoch,
+                epoch_secret_hex: epoch_secret_hex.clone(),
+                members: group.members.clone(),
+                created_at,
+            })
+            .await?;
+        self.services
+            .projection_store
+            .upsert_group_direct_message_conversation(group.clone())
+            .await?;
+        let update = GroupDirectMessageKeyUpdateV1 {
+            group_id: group.group_id.clone(),
+            owner: Pubkey::from(local_author_pubkey.as_str()),
+            title: group.title.clone(),
+            epoch: group.epoch,
+            members: group.members.iter().cloned().map(Pubkey::from).collect(),
+            epoch_secret_hex: Some(epoch_secret_hex),
+            created_at,
+        };
+        let message_id = format!("dm-group-key-{}-{}", group.group_id, group.epoch);
+        for member in &group.members {
+            if member == &local_author_pubkey {
+                continue;
+            }
+            self.queue_direct_message_control_payload(
+                member.as_str(),
+                message_id.as_str(),
+                DirectMessagePayloadV1 {
+                    group_key_update: Some(update.clone()),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+        for member in removed_members {
+            self.queue_direct_message_control_payload(
+                member.as_str(),
+                message_id.as_str(),
+                DirectMessagePayloadV1 {
+                    group_key_update: Some(GroupDirectMessageKeyUpdateV1 {
+                        epoch_secret_hex: None,
+                        ..update.clone()
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+        self.reconcile_group_direct_message_subscriptions().await
+    }
+
+    pub(crate) async fn group_direct_message_conversation_view(
+        &self,
+        row: GroupDirectMessageConversationRow,
+    ) -> Result<GroupDirectMessageConversationView> {
+        let pending_outbox_count = self
+            .services
+            .projection_store
+            .list_group_direct_message_outbox(row.group_id.as_str())
+            .await?
+            .len();
+        Ok(GroupDirectMessageConversationView {
+            is_owner: row.owner_pubkey == self.current_author_pubkey(),
+            removed: row.removed_at.is_some(),
+            group_id: row.group_id,
+            owner_pubkey: row.owner_pubkey,
+            title: row.title,
+            epoch: row.epoch,
+            member_pubkeys: row.members,
+            updated_at: row.updated_at,
+            last_message_at: row.last_message_at,
+            last_message_id: row.last_message_id,
+            last_message_preview: row.last_message_preview,
+            pending_outbox_count,
+        })
+    }
+
+    pub(crate) async fn reconcile_group_direct_message_subscriptions(&self) -> Result<()> {
+        reconcile_group_direct_message_subscriptions(
+            &self.services,
+            &self.last_sync_ts,
+            &self
+                .subscription_registry
+                .group_direct_message_subscriptions,
+            &self.notification_inserted_notify,
+            self.current_author_pubkey().as_str(),
+        )
+        .await?;
+        self.ensure_group_direct_message_supervisor().await;
+        Ok(())
+    }
+
+    async fn ensure_group_direct_message_supervisor(&self) {
+        let mut supervisor = self
+            .subscription_registry
+            .group_direct_message_supervisor
+            .lock()
+            .await;
+        if supervisor
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+        {
+            return;
+        }
+        let services = self.services.clone();
+        let last_sync = Arc::clone(&self.last_sync_ts);
+        let subscriptions = Arc::clone(
+            &self
+                .subscription_registry
+                .group_direct_message_subscriptions,
+        );
+        let notification_inserted = Arc::clone(&self.notification_inserted_notify);
+        let local_author_pubkey = self.current_author_pubkey();
+        *supervisor = Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_millis(
+                DIRECT_MESSAGE_RETRY_INTERVAL_MS,
+            ));
+            loop {
+                interval.tick().await;
+                if let Err(error) = reconcile_group_direct_message_subscriptions(
+                    &services,
+                    &last_sync,
+                    &subscriptions,
+                    &notification_inserted,
+                    local_author_pubkey.as_str(),
+                )
+                .await
+                {
+                    warn!(error = %error, "failed to reconcile group direct message subscriptions");
+                }
+                let groups = match services
+                    .projection_store
+                    .list_group_direct_message_conversations()
+                    .await
+                {
+                    Ok(groups) => groups,
+                    Err(error) => {
+                        warn!(error = %error, "failed to list group direct messages");
+                        continue;
+                    }
+                };
+                for group in groups {
+                    if let Err(error) = AppService::flush_group_direct_message_outbox(
+                        &services,
+                        local_author_pubkey.as_str(),
+                        group.group_id.as_str(),
+                    )
+                    .await
+                    {
+                        warn!(
+                            group_id = %group.group_id,
+                            error = %error,
+                            "failed to flush group direct message outbox"
+                        );
+                    }
+                }
+            }
+        }));
+    }
+
+    pub(crate) async fn handle_group_direct_message_hint(
+        services: &ServiceHandles,
+        local_author_pubkey: &str,
+        group_id: &str,
+        topic: &TopicId,
+        hint: &GossipHint,
+    ) -> Result<bool> {
+        match hint {
+            GossipHint::DirectMessageFrame {
+                dm_id,
+                message_id,
+                frame_hash,
+                ..
+            } if dm_id == group_id => {
+                Self::ingest_group_direct_message_frame(
+                    services,
+                    local_author_pubkey,
+                    topic,
+                    group_id,
+                    message_id.as_str(),
+                    frame_hash,
+                )
+                .await
+            }
+            GossipHint::DirectMessageAck { ack, .. } if ack.dm_id == group_id => {
+                ack.verify()?;
+                if ack.recipient.as_str() != local_author_pubkey {
+                    return Ok(false);
+                }
+                Self::record_group_direct_message_ack(
+                    services.projection_store.as_ref(),
+                    local_author_pubkey,
+                    group_id,
+                    ack.message_id.as_str(),
+                    ack.sender.as_str(),
+                    ack.acked_at,
+                )
+                .await
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// 自分以外の全メンバーから ack が揃ったら outbox から外して配達済みにする。
+    pub(crate) async fn record_group_direct_message_ack(
+        projection_store: &dyn ProjectionStore,
+        local_author_pubkey: &str,
+        group_id: &str,
+        message_id: &str,
+        member_pubkey: &str,
+        acked_at: i64,
+    ) -> Result<bool> {
+        let Some(group) = projection_store
+            .get_group_direct_message_conversation(group_id)
+            .await?
+        else {
+            return Ok(false);
+        };
+        if member_pubkey == local_author_pubkey
+            || !group.members.iter().any(|member| member == member_pubkey)
+        {
+            return Ok(false);
+        }
+        let acked_members = projection_store
+            .put_group_direct_message_ack(group_id, message_id, member_pubkey, acked_at)
+            .await?;
+        let fully_acked = group
+            .members
+            .iter()
+            .filter(|member| member.as_str() != local_author_pubkey)
+            .all(|member| acked_members.contains(member));
+        if fully_acked {
+            projection_store
+                .set_direct_message_acked_at(group_id, message_id, acked_at)
+                .await?;
+            projection_store
+                .remove_group_direct_message_outbox(group_id, message_id)
+                .await?;
+        }
+        Ok(fully_acked)
+    }
+
+    pub(crate) async fn ingest_group_direct_message_frame(
+        services: &ServiceHandles,
+        local_author_pubkey: &str,
+        topic: &TopicId,
+        group_id: &str,
+        message_id: &str,
+        frame_hash: &kukuri_core::BlobHash,
+    ) -> Result<bool> {
+        let projection_store = services.projection_store.as_ref();
+        let blob_service = services.blob_service.as_ref();
+        let keys = services.keys.as_ref();
+        let Some(group) = projection_store
+            .get_group_direct_message_conversation(group_id)
+            .await?
+        else {
+            return Ok(false);
+        };
+        let Some(frame_bytes) = blob_service.fetch_blob(frame_hash).await? else {
+            return Ok(false);
+        };
+        let frame: GroupDirectMessageFrameV1 = serde_json::from_slice(frame_bytes.as_slice())
+            .context("failed to decode group direct message frame blob")?;
+        if frame.group_id != group_id
+            || frame.message_id != message_id
+            || frame.sender.as_str() == local_author_pubkey
+        {
+            return Ok(false);
+        }
+        // 鍵更新より先に frame が届いた場合は ack せず、送信側の再送を待つ。
+        let Some(epoch) = projection_store
+            .get_group_direct_message_epoch(group_id, frame.epoch)
+            .await?
+        else {
+            return Ok(false);
+        };
+        // 送信者は frame の epoch 時点のメンバーで確かめる。メンバー記録の無い古い epoch 行
+        // だけは現在のメンバーで代用する。
+        let epoch_members = if epoch.members.is_empty() {
+            &group.members
+        } else {
+            &epoch.members
+        };
+        if !epoch_members
+            .iter()
+            .any(|member| member == frame.sender.as_str())
+        {
+            return Ok(false);
+        }
+        frame.verify()?;
+        let ack = build_direct_message_ack(
+            keys,
+            group_id,
+            message_id,
+            &frame.sender,
+            Utc::now().timestamp_millis(),
+        )?;
+        let already_received = projection_store
+            .has_direct_message_tombstone(group_id, message_id)
+            .await?
+            || projection_store
+                .get_direct_message_message(group_id, message_id)
+                .await?
+                .is_some();
+        if already_received {
+            services
+                .hint_transport
+                .publish_hint(
+                    topic,
+                    GossipHint::DirectMessageAck {
+                        topic_id: topic.clone(),
+                        ack,
+                    },
+                )
+                .await?;
+            return Ok(false);
+        }
+        let payload = decrypt_group_direct_message_frame(epoch.epoch_secret_hex.as_str(), &frame)?;
+        let local_manifest = materialize_direct_message_manifest(
+            blob_service,
+            keys,
+            &frame.sender,
+            message_id,
+            payload.attachment_key_hex.as_deref(),
+            payload.attachment_manifest.as_ref(),
+        )
+        .await?;
+        let message_row = DirectMessageMessageRow {
+            dm_id: group_id.to_string(),
+            message_id: message_id.to_string(),
+            sender_pubkey: frame.sender.as_str().to_string(),
+            recipient_pubkey: group_id.to_string(),
+            created_at: frame.created_at,
+            text: payload.text,
+            reply_to_message_id: payload.reply_to,
+            attachment_manifest: local_manifest,
+            outgoing: false,
+            acked_at: None,
+        };
+        let preview_text = notification_preview_text(Some(direct_message_preview(&message_row)));
+        index_direct_message_for_search(projection_store, &message_row).await?;
+        projection_store
+            .put_direct_message_message(message_row)
+            .await?;
+        Self::touch_group_direct_message_conversation(
+            projection_store,
+            group,
+            message_id,
+            frame.created_at,
+            preview_text.clone(),
+        )
+        .await?;
+        Self::put_notification_candidate(
+            projection_store,
+            local_author_pubkey,
+            NotificationCandidate {
+                kind: NotificationKind::GroupDirectMessage,
+                actor_pubkey: frame.sender.as_str().to_string(),
+                source_envelope_id: None,
+                source_replica_id: None,
+                topic_id: None,
+                channel_id: None,
+                object_id: None,
+                dm_id: Some(group_id.to_string()),
+                message_id: Some(message_id.to_string()),
+                preview_text,
+                created_at: frame.created_at,
+                received_at: Utc::now().timestamp_millis(),
+            },
+        )
+        .await?;
+        services
+            .hint_transport
+            .publish_hint(
+                topic,
+                GossipHint::DirectMessageAck {
+                    topic_id: topic.clone(),
+                    ack,
+                },
+            )
+            .await?;
+        Ok(true)
+    }
+
+    pub(crate) async fn touch_group_direct_message_conversation(
+        projection_store: &dyn ProjectionStore,
+        group: GroupDirectMessageConversationRow,
+        message_id: &str,
+        created_at: i64,
+        preview_text: Option<String>,
+    ) -> Result<()> {
+        if group
+            .last_message_at
+            .is_some_and(|last_message_at| last_message_at > created_at)
+        {
+            return Ok(());
+        }
+        projection_store
+            .upsert_group_direct_message_conversation(GroupDirectMessageConversationRow {
+                updated_at: group.updated_at.max(created_at),
+                last_message_at: Some(created_at),
+                last_message_id: Some(message_id.to_string()),
+                last_message_preview: preview_text,
+                ..group
+            })
+            .await
+    }
+
+    /// 未 ack の frame を現在 epoch の topic へ再送する。rekey 前に積んだ frame も古い epoch
+    /// のまま流す(受信側は古い epoch の鍵を保持している)。
+    pub(crate) async fn flush_group_direct_message_outbox(
+        services: &ServiceHandles,
+        local_author_pubkey: &str,
+        group_id: &str,
+    ) -> Result<usize> {
+        let projection_store = services.projection_store.as_ref();
+        let hint_transport = services.hint_transport.as_ref();
+        let Some(group) = projection_store
+            .get_group_direct_message_conversation(group_id)
+            .await?
+        else {
+            return Ok(0);
+        };
+        let Some(topic) =
+            current_group_direct_message_topic(projection_store, local_author_pubkey, &group)
+                .await?
+        else {
+            return Ok(0);
+        };
+        let topic_has_connected_peer =
+            direct_message_topic_peer_count(services.transport.as_ref(), &topic).await? > 0;
+        let attempted_at = Utc::now().timestamp_millis();
+        let mut published = 0usize;
+        for row in projection_store
+            .list_group_direct_message_outbox(group_id)
+            .await?
+        {
+            if topic_has_connected_peer {
+                projection_store
+                    .touch_group_direct_message_outbox_attempt(
+                        group_id,
+                        row.message_id.as_str(),
+                        attempted_at,
+                    )
+                    .await?;
+            }
+            let publish_result = hint_transport
+                .publish_hint(
+                    &topic,
+                    GossipHint::DirectMessageFrame {
+                        topic_id: topic.clone(),
+                        dm_id: group_id.to_string(),
+                        message_id: row.message_id.clone(),
+                        frame_hash: row.frame_blob_hash.clone(),
+                    },
+                )
+                .await;
+            if let Err(error) = publish_result {
+                if topic_has_connected_peer {
+                    return Err(error);
+                }
+                continue;
+            }
+            published += 1;
+        }
+        Ok(published)
+    }
+
+    pub(crate) async fn send_group_direct_message_internal(
+        &self,
+        group: GroupDirectMessageConversationRow,
+        text: Option<&str>,
+        reply_to_message_id: Option<&str>,
+        attachments: Vec<PendingAttachment>,
+    ) -> Result<String> {
+        let local_author_pubkey = self.current_author_pubkey();
+        let projection_store = self.services.projection_store.as_ref();
+        let text = normalize_optional_text(text.map(str::to_string));
+        if text.is_none() && attachments.is_empty() {
+            anyhow::bail!("group direct message text or attachment is required");
+        }
+        let reply_to = normalize_optional_text(reply_to_message_id.map(str::to_string));
+        if let Some(reply_to) = reply_to.as_deref()
+            && projection_store
+                .get_direct_message_message(group.group_id.as_str(), reply_to)
+                .await?
+                .is_none()
+        {
+            anyhow::bail!("group direct message reply target was not found");
+        }
+        let Some(epoch) = projection_store
+            .get_group_direct_message_epoch(group.group_id.as_str(), group.epoch)
+            .await?
+        else {
+            anyhow::bail!("group direct message epoch key is missing");
+        };
+        let message_id = format!(
+            "dm-message-{}-{}",
+            Utc::now().timestamp_millis(),
+            short_id_suffix(local_author_pubkey.as_str())
+        );
+        // グループでは添付を常に使い捨て鍵で暗号化するため、peer 鍵は使われない。
+        let attachment_key_hex =
+            (!attachments.is_empty()).then(generate_direct_message_attachment_key);
+        let (local_manifest, encrypted_manifest) = self
+            .prepare_direct_message_manifests(
+                local_author_pubkey.as_str(),
+                message_id.as_str(),
+                attachment_key_hex.as_deref(),
+                attachments,
+            )
+            .await?;
+        let created_at = Utc::now().timestamp_millis();
+        let frame = encrypt_group_direct_message_frame(
+            self.services.keys.as_ref(),
+            group.group_id.as_str(),
+            group.epoch,
+            epoch.epoch_secret_hex.as_str(),
+            message_id.as_str(),
+            created_at,
+            &DirectMessagePayloadV1 {
+                text: text.clone(),
+                reply_to: reply_to.clone(),
+                attachment_manifest: encrypted_manifest,
+                attachment_key_hex,
+                ..Default::default()
+            },
+        )?;
+        let frame_blob = self
+            .services
+            .blob_service
+            .put_blob(
+                serde_json::to_vec(&frame)
+                    .context("failed to encode group direct message frame blob")?,
+                DIRECT_MESSAGE_FRAME_MIME,
+            )
+            .await?;
+        let message_row = DirectMessageMessageRow {
+            dm_id: group.group_id.clone(),
+            message_id: message_id.clone(),
+            sender_pubkey: local_author_pubkey.clone(),
+            recipient_pubkey: group.group_id.clone(),
+            created_at,
+            text,
+            reply_to_message_id: reply_to,
+            attachment_manifest: local_manifest,
+            outgoing: true,
+            acked_at: None,
+        };
+        let preview_text = notification_preview_text(Some(direct_message_preview(&message_row)));
+        index_direct_message_for_search(projection_store, &message_row).await?;
+        projection_store
+            .put_direct_message_message(message_row)
+            .await?;
+        projection_store
+            .put_group_direct_message_outbox(GroupDirectMessageOutboxRow {
+                group_id: group.group_id.clone(),
+                message_id: message_id.clone(),
+                frame_blob_hash: frame_blob.hash,
+                created_at,
+                last_attempt_at: None,
+            })
+            .await?;
+        let group_id = group.group_id.clone();
+        Self::touch_group_direct_message_conversation(
+            projection_store,
+            group,
+            message_id.as_str(),
+            created_at,
+            preview_text,
+        )
+        .await?;
+        let _ = Self::flush_group_direct_message_outbox(
+            &self.services,
+            local_author_pubkey.as_str(),
+            group_id.as_str(),
+        )
+        .await?;
+        Ok(message_id)
+    }
+}
+
+/// 参加中グループの現在 epoch の topic だけを購読し、rekey や除名で古くなった購読を外す。
+pub(crate) async fn reconcile_group_direct_message_subscriptions(
+    services: &ServiceHandles,
+    last_sync: &Arc<Mutex<Option<i64>>>,
+    group_direct_message_subscriptions: &Mutex<HashMap<String, JoinHandle<()>>>,
+    notification_inserted: &Arc<tokio::sync::Notify>,
+    local_author_pubkey: &str,
+) -> Result<()> {
+    let projection_store = services.projection_store.as_ref();
+    let mut desired = BTreeMap::new();
+    for row in projection_store
+        .list_group_direct_message_conversations()
+        .await?
+    {
+        if let Some(topic) =
+            current_group_direct_message_topic(projection_store, local_author_pubkey, &row).await?
+        {
+            desired.insert(topic.as_str().to_string(), row.group_id);
+        }
+    }
+    let mut subscriptions = group_direct_message_subscriptions.lock().await;
+    let stale = subscriptions
+        .iter()
+        .filter(|(topic, handle)| !desired.contains_key(*topic) || handle.is_finished())
+        .map(|(topic, _)| topic.clone())
+        .collect::<Vec<_>>();
+    for topic in stale {
+        if let Some(handle) = subscriptions.remove(topic.as_str()) {
+            handle.abort();
+            let _ = services
+                .hint_transport
+                .unsubscribe_hints(&TopicId::new(topic))
+                .await;
+        }
+    }
+    for (topic, group_id) in desired {
+        if subscriptions.contains_key(topic.as_str()) {
+            continue;
+        }
+        let handle = spawn_group_direct_message_subscription(
+            services.clone(),
+            Arc::clone(last_sync),
+            Arc::clone(notification_inserted),
+            local_author_pubkey.to_string(),
+            TopicId::new(topic.as_str()),
+            group_id,
+        )
+        .await?;
+        subscriptions.insert(topic, handle);
+    }
+    Ok(())
+}
+
+async fn spawn_group_direct_message_subscription(
+    services: ServiceHandles,
+    last_sync: Arc<Mutex<Option<i64>>>,
+    notification_inserted: Arc<tokio::sync::Notify>,
+    local_author_pubkey: String,
+    topic: TopicId,
+    group_id: String,
+) -> Result<JoinHandle<()>> {
+    let mut hint_stream = services.hint_transport.subscribe_hints(&topic).await?;
+    Ok(tokio::spawn(async move {
+        while let Some(event) = hint_stream.next().await {
+            if !hint_targets_topic(&event.hint, topic.as_str()) {
+                continue;
+            }
+            if let Err(error) = services
+                .blob_service
+                .learn_peer(event.source_peer.as_str())
+                .await
+            {
+                warn!(
+                    group_id = %group_id,
+                    source_peer = %event.source_peer,
+                    error = %error,
+                    "failed to learn group direct message blob peer"
+                );
+            }
+            match AppService::handle_group_direct_message_hint(
+                &services,
+                local_author_pubkey.as_str(),
+                group_id.as_str(),
+                &topic,
+                &event.hint,
+            )
+            .await
+            {
+                Ok(true) => {
+                    *last_sync.lock().await = Some(Utc::now().timestamp_millis());
+                    notification_inserted.notify_waiters();
+                }
+                Ok(false) => {}
+                Err(error) => {
+                    warn!(
+                        group_id = %group_id,
+                        error = %error,
+                        "failed to handle group direct message hint"
+                    );
+                }
+            }
+        }
+        let _ = services.hint_transport.unsubscribe_hints(&topic).await;
+    }))
+}
@@ -1,31 +1,95 @@
 use super::*;
 
+/// 1 回の hydration で進める replica cursor。`since` の watermark 以下の entry は replica から
+/// 取り直さない。本体 blob などが揃わず次回も作り直す entry があれば、その author の watermark は
+/// その entry の手前で止める。
+///
+/// 同期で後から届いた entry の timestamp が watermark 以下だった場合は、docs event 経由の
+/// 個別 hydration か `rebuild_topic_projections` で拾う。
+pub(crate) struct ReplicaCursorPass {
+    since: ReplicaCursor,
+    projected: ReplicaCursor,
+    pending: BTreeMap<String, u64>,
+}
+
+impl ReplicaCursorPass {
+    pub(crate) fn new(since: ReplicaCursor) -> Self {
+        Self {
+            since,
+            projected: ReplicaCursor::default(),
+            pending: BTreeMap::new(),
+        }
+    }
+
+    fn watermarks(&self) -> &BTreeMap<String, u64> {
+        &self.since.watermarks
+    }
+
+    fn project(&mut self, record: &DocRecord) {
+        self.projected
+            .advance(record.author.as_str(), record.timestamp);
+    }
+
+    fn defer(&mut self, record: &DocRecord) {
+        let pending = self
+            .pending
+            .entry(record.author.clone())
+            .or_insert(record.timestamp);
+        *pending = (*pending).min(record.timestamp);
+    }
+
+    /// `since` から進んだ author の watermark だけを返す。
+    pub(crate) fn finish(self) -> ReplicaCursor {
+        let mut advanced = ReplicaCursor::default();
+        for (author, projected) in self.projected.watermarks {
+            let watermark = match self.pending.get(author.as_str()) {
+                Some(pending) => projected.min(pending.saturating_sub(1)),
+                None => projected,
+            };
+            if !self.since.is_current(author.as_str(), watermark) {
+                advanced.advance(author.as_str(), watermark);
+            }
+        }
+        advanced
+    }
+}
+
 pub(crate) async fn hydrate_object_projection_from_replica(
     docs_sync: &dyn DocsSync,
     blob_service: &dyn BlobService,
     projection_store: &dyn ProjectionStore,
     replica: &ReplicaId,
     policy: DocFetchPolicy,
+    cursor: &mut ReplicaCursorPass,
 ) -> Result<usize> {
-    let records = query_replica_with_fetch_policy(
-        docs_sync,
-        replica,
-        DocQuery::Prefix("objects/".into()),
-        policy,
-    )
-    .await?;
-    let mut hydrated = 0usize;
+    let since = docs_sync
+        .query_replica_since(
+            replica,
+            DocQuery::Prefix("objects/".into()),
+            policy,
+            cursor.watermarks(),
+        )
+        .await?;
+    let mut hydrated = count_state_keys(&since.skipped_keys);
     let mut blob_statuses = Vec::new();
+    let mut downloads = Vec::new();
     let mut projections = Vec::new();
-    for record in records {
+    for record in since.records {
         if !record.key.ends_with("/state") {
             continue;
         }
         let header: CanonicalPostHeader = serde_json::from_slice(&record.value)?;
+        // 本文 blob が未取得の projection は本文なしになるので、cursor を進めず次回も作り直す。
+        // 添付の取得状態は download queue が更新する。
+        let mut payload_ready = true;
         let content = match &header.payload_ref {
             PayloadRef::InlineText { text } => Some(text.clone()),
             PayloadRef::BlobText { hash, .. } => {
                 let payload = fetch_projection_blob_text(blob_service, hash).await;
+                if payload.is_none() {
+                    payload_ready = false;
+                    downloads.push((hash.clone(), DownloadJobKind::Payload));
+                }
                 blob_statuses.push((
                     hash.clone(),
                     match payload {
@@ -38,12 +102,22 @@ pub(crate) async fn hydrate_object_projection_from_replica(
         };
         for attachment in &header.attachments {
             let status = best_effort_blob_cache_status(blob_service, &attachment.hash).await;
+            if status == BlobCacheStatus::Missing {
+                downloads.push((attachment.hash.clone(), DownloadJobKind::Attachment));
+            }
             blob_statuses.push((attachment.hash.clone(), status));
         }
         projections.push(projection_row_from_header(&header, content, replica));
+        if payload_ready {
+            cursor.project(&record);
+        } else {
+            cursor.defer(&record);
+        }
         hydrated += 1;
     }
     projection_store.mark_blob_statuses(blob_statuses).await?;
+    queue_blob_downloads(projection_store, downloads, DOWNLOAD_PRIORITY_BACKGROUND).await?;
+    index_object_projections_for_search(projection_store, &projections).await?;
     projection_store.put_object_projections(projections).await?;
     Ok(hydrated)
 }
@@ -53,12 +127,16 @@ pub(crate) async fn hydrate_object_projection_from_record(
     projection_store: &dyn ProjectionStore,
     replica: &ReplicaId,
     record: DocRecord,
-) -> Result<bool> {
+) -> Result<ObjectProjectionRow> {
     let header: CanonicalPostHeader = serde_json::from_slice(&record.value)?;
+    let mut downloads = Vec::new();
     let content = match &header.payload_ref {
         PayloadRef::InlineText { text } => Some(text.clone()),
         PayloadRef::BlobText { hash, .. } => {
             let payload = fetch_projection_blob_text(blob_service, hash).await;
+            if payload.is_none() {
+                downloads.push((hash.clone(), DownloadJobKind::Payload));
+            }
             projection_store
                 .mark_blob_status(
                     hash,
@@ -73,14 +151,18 @@ pub(crate) async fn hydrate_object_projection_from_record(
     };
     for attachment in &header.attachments {
         let status = best_effort_blob_cache_status(blob_service, &attachment.hash).await;
+        if status == BlobCacheStatus::Missing {
+            downloads.push((attachment.hash.clone(), DownloadJobKind::Attachment));
+        }
         projection_store
             .mark_blob_status(&attachment.hash, status)
             .await?;
     }
-    projection_store
-        .put_object_projection(projection_row_from_header(&header, content, replica))
-        .await?;
-    Ok(true)
+    queue_blob_downloads(projection_store, downloads, DOWNLOAD_PRIORITY_BACKGROUND).await?;
+    let row = projection_row_from_header(&header, content, replica);
+    index_object_projections_for_search(projection_store, std::slice::from_ref(&row)).await?;
+    projection_store.put_object_projection(row.clone()).await?;
+    Ok(row)
 }
 
 pub(crate) async fn hydrate_object_projection_from_key(
@@ -89,16 +171,18 @@ pub(crate) async fn hydrate_object_projection_from_key(
     projection_store: &dyn ProjectionStore,
     replica: &ReplicaId,
     key: &str,
-) -> Result<bool> {
+) -> Result<Option<ObjectProjectionRow>> {
     let Some(record) = docs_sync
         .query_replica(replica, DocQuery::Exact(key.to_string()))
         .await?
         .into_iter()
         .next()
     else {
-        return Ok(false);
+        return Ok(None);
     };
-    hydrate_object_projection_from_record(blob_service, projection_store, replica, record).await
+    hydrate_object_projection_from_record(blob_service, projection_store, replica, record)
+        .await
+        .map(Some)
 }
 
 pub(crate) async fn hydrate_reaction_cache_from_replica(
@@ -106,16 +190,18 @@ pub(crate) async fn hydrate_reaction_cache_from_replica(
     projection_store: &dyn ProjectionStore,
     replica: &ReplicaId,
     policy: DocFetchPolicy,
+    cursor: &mut ReplicaCursorPass,
 ) -> Result<usize> {
-    let records = query_replica_with_fetch_policy(
-        docs_sync,
-        replica,
-        DocQuery::Prefix("reactions/".into()),
-        policy,
-    )
-    .await?;
-    let mut hydrated = 0usize;
-    for record in records {
+    let since = docs_sync
+        .query_replica_since(
+            replica,
+            DocQuery::Prefix("reactions/".into()),
+            policy,
+            cursor.watermarks(),
+        )
+        .await?;
+    let mut hydrated = count_state_keys(&since.skipped_keys);
+    for record in since.records {
         if !record.key.ends_with("/state") {
             continue;
         }
@@ -123,6 +209,7 @@ pub(crate) async fn hydrate_reaction_cache_from_replica(
         projection_store
             .upsert_reaction_cache(reaction_projection_row_from_doc(&reaction, replica))
             .await?;
+        cursor.project(&record);
         hydrated += 1;
     }
     Ok(hydrated)
@@ -132,12 +219,11 @@ pub(crate) async fn hydrate_reaction_cache_from_record(
     projection_store: &dyn ProjectionStore,
     replica: &ReplicaId,
     record: DocRecord,
-) -> Result<bool> {
+) -> Result<ReactionProjectionRow> {
     let reaction: ReactionDocV1 = serde_json::from_slice(record.value.as_slice())?;
-    projection_store
-        .upsert_reaction_cache(reaction_projection_row_from_doc(&reaction, replica))
-        .await?;
-    Ok(true)
+    let row = reaction_projection_row_from_doc(&reaction, replica);
+    projection_store.upsert_reaction_cache(row.clone()).await?;
+    Ok(row)
 }
 
 pub(crate) async fn hydrate_reaction_cache_from_key(
@@ -145,16 +231,18 @@ pub(crate) async fn hydrate_reaction_cache_from_key(
     projection_store: &dyn ProjectionStore,
     replica: &ReplicaId,
     key: &str,
-) -> Result<bool> {
+) -> Result<Option<ReactionProjectionRow>> {
     let Some(record) = docs_sync
         .query_replica(replica, DocQuery::Exact(key.to_string()))
         .await?
         .into_iter()
         .next()
     else {
-        return Ok(false);
+        return Ok(None);
     };
-    hydrate_reaction_cache_from_record(projection_store, replica, record).await
+    hydrate_reaction_cache_from_record(projection_store, replica, record)
+        .await
+        .map(Some)
 }
 
 pub(crate) async fn hydrate_reaction_cache_for_target(
@@ -174,8 +262,8 @@ pub(crate) async fn hydrate_reaction_cache_for_target(
         if !record.key.ends_with("/state") {
             continue;
         }
-        hydrated +=
-            hydrate_reaction_cache_from_record(projection_store, replica, record).await? as usize;
+        hydrate_reaction_cache_from_record(projection_store, replica, record).await?;
+        hydrated += 1;
     }
     Ok(hydrated)
 }
@@ -188,6 +276,9 @@ pub(crate) async fn hydrate_topic_state(
     hydrate_subscription_state(services, topic_id, &topic_replica_id(topic_id), policy).await
 }
 
+/// replica の state を projection へ反映する。`replica_cursors` に記録した author ごとの
+/// watermark 以下の entry は取得も作り直しもしない(再起動・再購読で replica 全体を歩き直さないため)。
+/// 戻り値は projection に反映済みの state 数で、cursor で飛ばした entry も含む。
 pub(crate) async fn hydrate_subscription_state(
     services: &ServiceHandles,
     topic_id: &str,
@@ -197,16 +288,29 @@ pub(crate) async fn hydrate_subscription_state(
     let docs_sync = services.docs_sync.as_ref();
     let blob_service = services.blob_service.as_ref();
     let projection_store = services.projection_store.as_ref();
+    let mut cursor = ReplicaCursorPass::new(
+        projection_store
+            .get_replica_cursor(replica)
+            .await?
+            .unwrap_or_default(),
+    );
     let post_count = hydrate_object_projection_from_replica(
         docs_sync,
         blob_service,
         projection_store,
         replica,
         policy,
+        &mut cursor,
+    )
+    .await?;
+    let reaction_count = hydrate_reaction_cache_from_replica(
+        docs_sync,
+        projection_store,
+        replica,
+        policy,
+        &mut cursor,
     )
     .await?;
-    let reaction_count =
-        hydrate_reaction_cache_from_replica(docs_sync, projection_store, replica, policy).await?;
     let live_count = hydrate_live_sessions_from_replica(
         docs_sync,
         blob_service,
@@ -214,6 +318,7 @@ pub(crate) async fn hydrate_subscription_state(
         topic_id,
         replica,
         policy,
+        &mut cursor,
     )
     .await?;
     let game_count = hydrate_game_rooms_from_replica(
@@ -223,8 +328,15 @@ pub(crate) async fn hydrate_subscription_state(
         topic_id,
         replica,
         policy,
+        &mut cursor,
     )
     .await?;
+    let advanced = cursor.finish();
+    if !advanced.watermarks.is_empty() {
+        projection_store
+            .put_replica_cursor(replica, &advanced)
+            .await?;
+    }
     Ok(post_count + reaction_count + live_count + game_count)
 }
 
@@ -235,16 +347,18 @@ pub(crate) async fn hydrate_live_sessions_from_replica(
     topic_id: &str,
     replica: &ReplicaId,
     policy: DocFetchPolicy,
+    cursor: &mut ReplicaCursorPass,
 ) -> Result<usize> {
-    let records = query_replica_with_fetch_policy(
-        docs_sync,
-        replica,
-        DocQuery::Prefix("sessions/live/".into()),
-        policy,
-    )
-    .await?;
-    let mut hydrated = 0usize;
-    for record in records {
+    let since = docs_sync
+        .query_replica_since(
+            replica,
+            DocQuery::Prefix("sessions/live/".into()),
+            policy,
+            cursor.watermarks(),
+        )
+        .await?;
+    let mut hydrated = since.skipped_keys.len();
+    for record in since.records {
         let state: LiveSessionStateDocV1 = serde_json::from_slice(&record.value)?;
         projection_store
             .mark_blob_status(
@@ -260,6 +374,8 @@ pub(crate) async fn hydrate_live_sessions_from_replica(
             fetch_manifest_blob::<LiveSessionManifestBlobV1>(blob_service, &state.current_manifest)
                 .await?
         else {
+            queue_manifest_download(projection_store, &state.current_manifest).await?;
+            cursor.defer(&record);
             continue;
         };
         projection_store
@@ -267,6 +383,7 @@ pub(crate) async fn hydrate_live_sessions_from_replica(
                 &state, &manifest, topic_id, replica,
             ))
             .await?;
+        cursor.project(&record);
         hydrated += 1;
     }
     Ok(hydrated)
@@ -294,6 +411,7 @@ pub(crate) async fn hydrate_live_session_from_record(
         fetch_manifest_blob::<LiveSessionManifestBlobV1>(blob_service, &state.current_manifest)
             .await?
     else {
+        queue_manifest_download(projection_store, &state.current_manifest).await?;
         return Ok(false);
     };
     projection_store
@@ -359,16 +477,18 @@ pub(crate) async fn hydrate_game_rooms_from_replica(
     topic_id: &str,
     replica: &ReplicaId,
     policy: DocFetchPolicy,
+    cursor: &mut ReplicaCursorPass,
 ) -> Result<usize> {
-    let records = query_replica_with_fetch_policy(
-        docs_sync,
-        replica,
-        DocQuery::Prefix("sessions/game/".into()),
-        policy,
-    )
-    .await?;
-    let mut hydrated = 0usize;
-    for record in records {
+    let since = docs_sync
+        .query_replica_since(
+            replica,
+            DocQuery::Prefix("sessions/game/".into()),
+            policy,
+            cursor.watermarks(),
+        )
+        .await?;
+    let mut hydrated = since.skipped_keys.len();
+    for record in since.records {
         let state: GameRoomStateDocV1 = serde_json::from_slice(&record.value)?;
         projection_store
             .mark_blob_status(
@@ -384,6 +504,8 @@ pub(crate) async fn hydrate_game_rooms_from_replica(
             fetch_manifest_blob::<GameRoomManifestBlobV1>(blob_service, &state.current_manifest)
                 .await?
         else {
+            queue_manifest_download(projection_store, &state.current_manifest).await?;
+            cursor.defer(&record);
             continue;
         };
         projection_store
@@ -391,6 +513,7 @@ pub(crate) async fn hydrate_game_rooms_from_replica(
                 &state, &manifest, topic_id, replica,
             ))
             .await?;
+        cursor.project(&record);
         hydrated += 1;
     }
     Ok(hydrated)
@@ -418,6 +541,7 @@ pub(crate) async fn hydrate_game_room_from_record(
         fetch_manifest_blob::<GameRoomManifestBlobV1>(blob_service, &state.current_manifest)
             .await?
     else {
+        queue_manifest_download(projection_store, &state.current_manifest).await?;
         return Ok(false);
     };
     projection_store
@@ -485,23 +609,34 @@ pub(crate) async fn hydrate_subscription_event(
     let blob_service = services.blob_service.as_ref();
     let projection_store = services.projection_store.as_ref();
     if key.starts_with("objects/") && key.ends_with("/state") {
-        return Ok(hydrate_object_projection_from_key(
+        let Some(row) = hydrate_object_projection_from_key(
             docs_sync,
             blob_service,
             projection_store,
             replica,
             key,
         )
-        .await? as usize);
+        .await?
+        else {
+            return Ok(0);
+        };
+        services.emit_content_event(object_content_event(&row));
+        return Ok(1);
     }
     if key.starts_with("reactions/") && key.ends_with("/state") {
-        return Ok(
+        let Some(row) =
             hydrate_reaction_cache_from_key(docs_sync, projection_store, replica, key).await?
-                as usize,
-        );
+        else {
+            return Ok(0);
+        };
+        services.emit_content_event(ContentEvent::ReactionChanged {
+            topic_id: topic_id.to_string(),
+            target_object_id: row.target_object_id.as_str().to_string(),
+        });
+        return Ok(1);
     }
-    if key.starts_with("sessions/live/") && key.ends_with("/state") {
-        return hydrate_live_session_from_key_with_retry(
+    if let Some(session_id) = session_id_from_state_key("sessions/live/", key) {
+        let hydrated = hydrate_live_session_from_key_with_retry(
             docs_sync,
             blob_service,
             projection_store,
@@ -509,10 +644,17 @@ pub(crate) async fn hydrate_subscription_event(
             replica,
             key,
         )
-        .await;
+        .await?;
+        if hydrated > 0 {
+            services.emit_content_event(ContentEvent::LiveSessionChanged {
+                topic_id: topic_id.to_string(),
+                session_id: session_id.to_string(),
+            });
+        }
+        return Ok(hydrated);
     }
-    if key.starts_with("sessions/game/") && key.ends_with("/state") {
-        return hydrate_game_room_from_key_with_retry(
+    if let Some(room_id) = session_id_from_state_key("sessions/game/", key) {
+        let hydrated = hydrate_game_room_from_key_with_retry(
             docs_sync,
             blob_service,
             projection_store,
@@ -520,7 +662,14 @@ pub(crate) async fn hydrate_subscription_event(
             replica,
             key,
         )
-        .await;
+        .await?;
+        if hydrated > 0 {
+            services.emit_content_event(ContentEvent::GameRoomChanged {
+                topic_id: topic_id.to_string(),
+                room_id: room_id.to_string(),
+            });
+        }
+        return Ok(hydrated);
     }
     Ok(0)
 }
@@ -539,37 +688,52 @@ pub(crate) async fn hydrate_subscription_hint(
             let mut hydrated = 0usize;
             for object in objects {
                 if object.object_kind == "reaction" {
-                    hydrated += hydrate_reaction_cache_for_target(
+                    let reactions = hydrate_reaction_cache_for_target(
                         docs_sync,
                         projection_store,
                         replica,
                         object.object_id.as_str(),
                     )
                     .await?;
+                    if reactions > 0 {
+                        services.emit_content_event(ContentEvent::ReactionChanged {
+                            topic_id: topic_id.to_string(),
+                            target_object_id: object.object_id.clone(),
+                        });
+                    }
+                    hydrated += reactions;
                     continue;
                 }
-                hydrated += hydrate_object_projection_from_key(
+                if let Some(row) = hydrate_object_projection_from_key(
                     docs_sync,
                     blob_service,
                     projection_store,
                     replica,
                     stable_key("objects", &format!("{}/state", object.object_id)).as_str(),
                 )
-                .await? as usize;
+                .await?
+                {
+                    services.emit_content_event(object_content_event(&row));
+                    hydrated += 1;
+                }
             }
             Ok(hydrated)
         }
         GossipHint::ThreadUpdated { object_ids, .. } => {
             let mut hydrated = 0usize;
             for object_id in object_ids {
-                hydrated += hydrate_object_projection_from_key(
+                if let Some(row) = hydrate_object_projection_from_key(
                     docs_sync,
                     blob_service,
                     projection_store,
                     replica,
                     stable_key("objects", &format!("{}/state", object_id.as_str())).as_str(),
                 )
-                .await? as usize;
+                .await?
+                {
+                    services.emit_content_event(object_content_event(&row));
+                    hydrated += 1;
+                }
             }
             Ok(hydrated)
         }
@@ -579,7 +743,7 @@ pub(crate) async fn hydrate_subscription_hint(
             ..
         } => match object_kind.as_str() {
             "live-session" => {
-                hydrate_live_session_from_key_with_retry(
+                let hydrated = hydrate_live_session_from_key_with_retry(
                     docs_sync,
                     blob_service,
                     projection_store,
@@ -587,10 +751,17 @@ pub(crate) async fn hydrate_subscription_hint(
                     replica,
                     stable_key("sessions/live", &format!("{session_id}/state")).as_str(),
                 )
-                .await
+                .await?;
+                if hydrated > 0 {
+                    services.emit_content_event(ContentEvent::LiveSessionChanged {
+                        topic_id: topic_id.to_string(),
+                        session_id: session_id.to_string(),
+                    });
+                }
+                Ok(hydrated)
             }
             "game-session" => {
-                hydrate_game_room_from_key_with_retry(
+                let hydrated = hydrate_game_room_from_key_with_retry(
                     docs_sync,
                     blob_service,
                     projection_store,
@@ -598,12 +769,26 @@ pub(crate) async fn hydrate_subscription_hint(
                     replica,
                     stable_key("sessions/game", &format!("{session_id}/state")).as_str(),
                 )
-                .await
+                .await?;
+                if hydrated > 0 {
+                    services.emit_content_event(ContentEvent::GameRoomChanged {
+                        topic_id: topic_id.to_string(),
+                        room_id: session_id.to_string(),
+                    });
+                }
+                Ok(hydrated)
             }
             _ => Ok(0),
         },
-        GossipHint::ProfileUpdated { .. }
-        | GossipHint::Presence { .. }
+        GossipHint::ProfileUpdated { author } => {
+            // profile は author replica 側で同期されるため、ここでは UI への通知だけを行う。
+            // topic の projection は増えないので 0 を返し、呼び出し側の再同期判定に影響させない。
+            services.emit_content_event(ContentEvent::ProfileUpdated {
+                author_pubkey: author.as_str().to_string(),
+            });
+            Ok(0)
+        }
+        GossipHint::Presence { .. }
         | GossipHint::Typing { .. }
         | GossipHint::LivePresence { .. }
         | GossipHint::MetaverseRoomEvent { .. }
@@ -612,6 +797,41 @@ pub(crate) async fn hydrate_subscription_hint(
     }
 }
 
+/// hydrate した投稿行を event にする。`reply_to` を持つ行は thread 返信として扱う。
+fn object_content_event(row: &ObjectProjectionRow) -> ContentEvent {
+    let topic_id = row.topic_id.clone();
+    let channel_id = channel_id_for_view(row.channel_id.as_str());
+    let object_id = row.object_id.as_str().to_string();
+    let author_pubkey = row.author_pubkey.clone();
+    match row.reply_to_object_id.as_ref() {
+        Some(reply_to) => ContentEvent::ThreadReply {
+            topic_id,
+            channel_id,
+            root_object_id: row
+                .root_object_id
+                .as_ref()
+                .unwrap_or(reply_to)
+                .as_str()
+                .to_string(),
+            object_id,
+            author_pubkey,
+        },
+        None => ContentEvent::PostChanged {
+            topic_id,
+            channel_id,
+            object_id,
+            author_pubkey,
+        },
+    }
+}
+
+/// `sessions/{kind}/{session_id}/state` から session id を取り出す。
+fn session_id_from_state_key<'a>(prefix: &str, key: &'a str) -> Option<&'a str> {
+    key.strip_prefix(prefix)?
+        .strip_suffix("/state")
+        .filter(|session_id| !session_id.is_empty())
+}
+
 pub(crate) fn hint_targets_topic(hint: &GossipHint, topic: &str) -> bool {
     match hint {
         GossipHint::TopicObjectsChanged { topic_id, .. }
@@ -665,3 +885,7 @@ pub(crate) fn profile_timeline_page(
 
     Page { items, next_cursor }
 }
+
+fn count_state_keys(keys: &[String]) -> usize {
+    keys.iter().filter(|key| key.ends_with("/state")).count()
+}
@@ -0,0 +1,72 @@
+use super::*;
+
+pub(crate) const LOCAL_SEARCH_POST_KIND: &str = "post";
+pub(crate) const LOCAL_SEARCH_DIRECT_MESSAGE_KIND: &str = "direct_message";
+
+/// projection 行を端末内検索文書へ写す。
+///
+/// 取り消し済みの object は空本文(= 削除)として返す。本文 blob が未取得の行は
+/// 既存文書を壊さないよう None を返し、取得後の再 hydrate で索引する。
+pub(crate) fn local_search_document_from_projection(
+    row: &ObjectProjectionRow,
+) -> Option<LocalSearchDocumentRow> {
+    let body = if object_projection_row_is_retracted(row) {
+        String::new()
+    } else {
+        row.content.clone()?
+    };
+    Some(LocalSearchDocumentRow {
+        subject_kind: LOCAL_SEARCH_POST_KIND.to_string(),
+        subject_id: row.object_id.as_str().to_string(),
+        topic_id: Some(row.topic_id.clone()),
+        channel_id: Some(row.channel_id.clone()),
+        dm_id: None,
+        author_pubkey: row.author_pubkey.clone(),
+        // envelope の created_at は秒、DM は millis。検索文書は millis に揃える。
+        created_at: row.created_at.saturating_mul(1000),
+        body,
+    })
+}
+
+pub(crate) fn local_search_document_from_direct_message(
+    row: &DirectMessageMessageRow,
+) -> Option<LocalSearchDocumentRow> {
+    let body = row.text.as_deref()?.trim();
+    if body.is_empty() {
+        return None;
+    }
+    Some(LocalSearchDocumentRow {
+        subject_kind: LOCAL_SEARCH_DIRECT_MESSAGE_KIND.to_string(),
+        subject_id: row.message_id.clone(),
+        topic_id: None,
+        channel_id: None,
+        dm_id: Some(row.dm_id.clone()),
+        author_pubkey: row.sender_pubkey.clone(),
+        created_at: row.created_at,
+        body: body.to_string(),
+    })
+}
+
+/// projection 書き込みの直後に呼び、検索索引を同じ行で更新する。
+pub(crate) async fn index_object_projections_for_search(
+    projection_store: &dyn ProjectionStore,
+    rows: &[ObjectProjectionRow],
+) -> Result<()> {
+    let documents = rows
+        .iter()
+        .filter_map(local_search_document_from_projection)
+        .collect::<Vec<_>>();
+    projection_store.put_local_search_documents(documents).await
+}
+
+pub(crate) async fn index_direct_message_for_search(
+    projection_store: &dyn ProjectionStore,
+    row: &DirectMessageMessageRow,
+) -> Result<()> {
+    let Some(document) = local_search_document_from_direct_message(row) else {
+        return Ok(());
+    };
+    projection_store
+        .put_local_search_documents(vec![document])
+        .await
+}
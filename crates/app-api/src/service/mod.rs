@@ -6,17 +6,23 @@ pub(crate) use base64::Engine;
 pub(crate) use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 pub(crate) use chrono::Utc;
 pub(crate) use futures_util::StreamExt;
-pub(crate) use kukuri_blob_service::{BlobService, BlobStatus, MemoryBlobService, StoredBlob};
+pub(crate) use kukuri_blob_service::{
+    BlobRetentionConfig, BlobService, BlobStatus, DOWNLOAD_PRIORITY_BACKGROUND,
+    DOWNLOAD_PRIORITY_VISIBLE, MemoryBlobService, StoredBlob, collect_blob_garbage,
+    summarize_blob_storage,
+};
 pub(crate) use kukuri_core::{
     AssetRole, AuthorProfileDocV1, AuthorProfilePostDocV1, AuthorProfileRepostDocV1,
     CanonicalPostHeader, ChannelAudienceKind, ChannelId, ChannelRef, ChannelSharingState,
     CreatePrivateChannelInput, CustomReactionAssetDocV1, CustomReactionAssetSnapshotV1,
-    DirectMessageAttachmentKind, DirectMessageAttachmentManifestV1,
-    DirectMessageEncryptedAttachmentV1, DirectMessageEncryptedBlobRefV1, DirectMessageFrameV1,
-    DirectMessagePayloadV1, EnvelopeId, FollowEdge, FollowEdgeDocV1, FollowEdgeStatus,
-    FriendOnlyGrantPreview, FriendPlusSharePreview, GAME_MANIFEST_MIME, GameParticipant,
-    GameRoomKind, GameRoomManifestBlobV1, GameRoomStateDocV1, GameRoomStatus, GameScoreEntry,
-    GossipHint, HintObjectRef, KukuriEnvelope, KukuriKeys, KukuriMediaManifestV1,
+    DIRECT_MESSAGE_FRAME_VERSION_V1, DIRECT_MESSAGE_FRAME_VERSION_V2, DeviceAuthorization,
+    DeviceStateDocV1, DirectMessageAttachmentKind, DirectMessageAttachmentManifestV1,
+    DirectMessageEncryptedAttachmentV1, DirectMessageEncryptedBlobRefV1, DirectMessageFrame,
+    DirectMessagePayloadV1, DirectMessageRatchetStateV2, EnvelopeId, FollowEdge, FollowEdgeDocV1,
+    FollowEdgeStatus, FriendOnlyGrantPreview, FriendPlusSharePreview, GAME_MANIFEST_MIME,
+    GameParticipant, GameRoomKind, GameRoomManifestBlobV1, GameRoomStateDocV1, GameRoomStatus,
+    GameScoreEntry, GossipHint, GroupDirectMessageFrameV1, GroupDirectMessageKeyUpdateV1,
+    HintObjectRef, KukuriEnvelope, KukuriKeys, KukuriMediaManifestV1,
     KukuriProfileEnvelopeContentV1, KukuriProfilePostEnvelopeContentV1,
     KukuriProfileRepostEnvelopeContentV1, LIVE_MANIFEST_MIME, LiveSessionManifestBlobV1,
     LiveSessionStateDocV1, LiveSessionStatus, ManifestBlobRef, MediaManifestItem,
@@ -27,36 +33,49 @@ pub(crate) use kukuri_core::{
     PrivateChannelInviteTokenParams, PrivateChannelJoinMode, PrivateChannelMetadataDocV1,
     PrivateChannelParticipantDocV1, PrivateChannelPolicyDocV1, Profile, ProfilePost, ProfileRepost,
     Pubkey, ReactionDocV1, ReactionKeyKind, ReactionKeyV1, ReplicaId, RepostSourceSnapshotV1,
-    SharedRoomObjectV1, TimelineScope, TopicId, author_profile_topic_id,
-    build_custom_reaction_asset_envelope, build_direct_message_ack, build_follow_edge_envelope,
+    SharedRoomObjectV1, TimelineScope, TopicId, apply_post_revision, author_profile_topic_id,
+    build_custom_reaction_asset_envelope, build_device_authorization_envelope,
+    build_device_pairing_ticket, build_direct_message_ack, build_follow_edge_envelope,
     build_friend_only_grant_token, build_friend_plus_share_token, build_game_session_envelope,
     build_live_session_envelope, build_media_manifest_envelope,
-    build_metaverse_room_event_envelope, build_post_envelope_with_payload_in_channel,
+    build_metaverse_room_event_envelope, build_post_edit_envelope,
+    build_post_envelope_with_payload_in_channel, build_post_tombstone_envelope,
     build_private_channel_epoch_handoff_grant_envelope, build_private_channel_invite_token,
     build_private_channel_participant_envelope, build_private_channel_policy_envelope,
     build_profile_envelope, build_profile_post_envelope, build_profile_repost_envelope,
-    build_reaction_envelope, build_repost_envelope, decrypt_direct_message_attachment,
-    decrypt_direct_message_frame, decrypt_private_channel_epoch_handoff_grant,
-    derive_direct_message_topic, deterministic_reaction_id, direct_message_id_for_participants,
-    encrypt_direct_message_attachment, encrypt_direct_message_frame,
-    encrypt_private_channel_epoch_handoff_grant, generate_keys, parse_custom_reaction_asset,
-    parse_follow_edge, parse_friend_only_grant_token, parse_friend_plus_share_token,
-    parse_private_channel_epoch_handoff_grant, parse_private_channel_invite_token,
-    parse_private_channel_participant, parse_private_channel_policy, parse_profile,
-    parse_profile_post, parse_profile_repost, parse_reaction, timeline_sort_key,
+    build_reaction_envelope, build_repost_envelope, decrypt_device_state_doc,
+    decrypt_direct_message_attachment, decrypt_direct_message_attachment_with_key,
+    decrypt_direct_message_frame, decrypt_direct_message_frame_v2,
+    decrypt_group_direct_message_frame, decrypt_private_channel_epoch_handoff_grant,
+    derive_direct_message_topic, derive_group_direct_message_topic, deterministic_reaction_id,
+    direct_message_id_for_participants, encrypt_device_state_doc,
+    encrypt_direct_message_attachment, encrypt_direct_message_attachment_with_key,
+    encrypt_direct_message_frame, encrypt_direct_message_frame_v2,
+    encrypt_group_direct_message_frame, encrypt_private_channel_epoch_handoff_grant,
+    generate_device_id, generate_device_pairing_code, generate_direct_message_attachment_key,
+    generate_group_direct_message_epoch_secret, generate_group_direct_message_id, generate_keys,
+    normalize_group_direct_message_members, parse_custom_reaction_asset,
+    parse_device_authorization, parse_follow_edge, parse_friend_only_grant_token,
+    parse_friend_plus_share_token, parse_private_channel_epoch_handoff_grant,
+    parse_private_channel_invite_token, parse_private_channel_participant,
+    parse_private_channel_policy, parse_profile, parse_profile_post, parse_profile_repost,
+    parse_reaction, supported_direct_message_frame_versions, timeline_sort_key,
 };
 pub(crate) use kukuri_docs_sync::{
     DocEvent, DocFetchPolicy, DocOp, DocQuery, DocRecord, DocsSync, MemoryDocsSync,
-    author_replica_id, private_channel_epoch_replica_id, private_channel_hint_topic,
-    private_channel_replica_id, stable_key, topic_replica_id,
+    author_replica_id, device_replica_id, private_channel_epoch_replica_id,
+    private_channel_hint_topic, private_channel_replica_id, stable_key, topic_replica_id,
 };
 pub(crate) use kukuri_store::{
     AuthorRelationshipProjectionRow, BlobCacheStatus, BlobCacheStore, BookmarkedCustomReactionRow,
-    BookmarkedPostRow, DirectMessageConversationRow, DirectMessageMessageRow,
-    DirectMessageOutboxRow, DirectMessageTombstoneRow, GameRoomProjectionRow,
-    LiveSessionProjectionRow, MutedAuthorRow, NotificationKind, NotificationRow,
+    BookmarkedPostRow, CommunityModerationPolicy, CommunityModerationPolicyRow,
+    DirectMessageConversationRow, DirectMessageMessageRow, DirectMessageOutboxRow,
+    DirectMessageSessionRow, DirectMessageSkippedKeyRow, DirectMessageTombstoneRow,
+    DownloadJobKind, GameRoomProjectionRow, GroupDirectMessageConversationRow,
+    GroupDirectMessageEpochRow, GroupDirectMessageOutboxRow, LiveSessionProjectionRow,
+    LocalSearchDocumentRow, LocalSearchQuery, MutedAuthorRow, NotificationKind, NotificationRow,
     ObjectProjectionRow, ObjectProjectionStore, Page, ProjectionStore, ReactionProjectionRow,
-    Store, TimelineCursor,
+    ReplicaCursor, Store, TimelineCursor,
 };
 pub(crate) use kukuri_transport::{
     ConnectionPath, DiscoveryMode, DiscoverySnapshot, HintTransport, PeerSnapshot, SeedPeer,
@@ -78,31 +97,41 @@ pub(crate) const DIRECT_MESSAGE_ATTACHMENT_MIME: &str =
     "application/vnd.kukuri.direct-message-attachment+json";
 pub(crate) const DIRECT_MESSAGE_RETRY_INTERVAL_MS: u64 = 2_000;
 pub(crate) const NOTIFICATION_PREVIEW_LIMIT: usize = 80;
+/// content event の broadcast 容量。溢れた購読側は Lagged を受けて全体を取り直す。
+pub(crate) const CONTENT_EVENT_CHANNEL_CAPACITY: usize = 256;
 
 pub(crate) use crate::views::{
-    AttachmentView, AuthorSocialView, BlobMediaPayload, BlobViewStatus,
-    BookmarkedCustomReactionView, BookmarkedPostView, ChannelAccessTokenExport,
-    ChannelAccessTokenKind, ChannelAccessTokenPreview, CreateCustomReactionAssetInput,
+    AttachmentView, AuthorSocialView, BlobDownloadQueueStatus, BlobGarbageCollectionView,
+    BlobMediaPayload, BlobViewStatus, BookmarkedCustomReactionView, BookmarkedPostView,
+    ChannelAccessTokenExport, ChannelAccessTokenKind, ChannelAccessTokenPreview,
+    CommunityModerationPolicyView, ContentEvent, CreateCustomReactionAssetInput,
     CreateGameRoomInput, CreateLiveSessionInput, CreateMetaverseRoomInput, CustomReactionAssetView,
-    DeliveryState, DirectMessageConversationView, DirectMessageMessageView,
-    DirectMessageStatusView, DirectMessageTimelineView, DirectMessageTopicStatusView,
-    DiscoveryStatus, GameRoomView, GameScoreView, ImportMetaverseRoomAssetInput,
-    JoinedPrivateChannelView, LiveSessionView, MetaverseAssetRefView, MetaverseRoomEventView,
-    NotificationStatusView, NotificationView, PendingAttachment, PostView,
-    PrivateChannelCapability, PrivateChannelEpochCapability, ProfileAssetView, ProfileInput,
-    PublishMetaverseRoomEventInput, ReactionKeyView, ReactionStateView, ReactionSummaryView,
-    RecentReactionView, ReplyPreviewAuthorView, ReplyPreviewView, RepostSourceView,
-    SocialConnectionKind, SyncStatus, TimelineView, TopicSyncStatus, UpdateGameRoomInput,
-    UpdateMetaverseRoomInput,
+    DeliveryState, DevicePairingView, DirectMessageBackupV1, DirectMessageConversationView,
+    DirectMessageMessageView, DirectMessageStatusView, DirectMessageTimelineView,
+    DirectMessageTopicStatusView, DiscoveryStatus, GameRoomView, GameScoreView,
+    GroupDirectMessageConversationView, ImportMetaverseRoomAssetInput, JoinedPrivateChannelView,
+    LinkedDeviceView, LiveSessionView, LocalSearchInput, LocalSearchView, MetaverseAssetRefView,
+    MetaverseRoomEventView, NotificationStatusView, NotificationView, PendingAttachment,
+    PostRevisionView, PostView, PrivateChannelCapability, PrivateChannelEpochCapability,
+    ProfileAssetView, ProfileInput, PublishMetaverseRoomEventInput, ReactionKeyView,
+    ReactionStateView, ReactionSummaryView, RecentReactionView, ReplyPreviewAuthorView,
+    ReplyPreviewView, RepostSourceView, SocialConnectionKind, StorageUsageView, SyncStatus,
+    TimelineView, TopicSyncStatus, UpdateGameRoomInput, UpdateMetaverseRoomInput,
 };
 
 mod attachment_support;
+mod blob_download_support;
+mod blob_retention_support;
+mod device_sync_support;
+mod direct_message_ratchet_support;
 mod direct_messages_delivery_support;
 mod direct_messages_subscription_support;
 mod errors;
 mod gossip_subscription_support;
+mod group_direct_messages_support;
 mod hydration_support;
 mod live_game_support;
+mod local_search_support;
 mod metaverse_room_event_support;
 mod notifications_support;
 mod object_persistence_support;
@@ -131,12 +160,31 @@ pub(crate) use attachment_support::{
     register_private_channel_replica_secrets, sanitize_game_participants, short_id_suffix,
     subscription_replicas_for_topic, validate_game_room_scores, validate_game_room_transition,
 };
+pub(crate) use blob_download_support::{
+    missing_attachment_downloads, queue_blob_downloads, queue_manifest_download,
+};
+pub(crate) use device_sync_support::{
+    DEVICE_STATE_BOOKMARKED_POSTS_PREFIX, DEVICE_STATE_BOOKMARKED_REACTIONS_PREFIX,
+    DEVICE_STATE_DM_CLEARS_PREFIX, DEVICE_STATE_DM_DELETIONS_PREFIX, DEVICE_STATE_MUTES_PREFIX,
+    DEVICE_STATE_NOTIFICATION_READS_PREFIX, DEVICE_STATE_NOTIFICATIONS_ALL_READ_KEY,
+    DEVICE_STATE_PRIVATE_CHANNELS_PREFIX, DeviceDirectMessageClearV1,
+    DeviceDirectMessageDeletionV1, device_authorization_key, load_device_authorizations,
+    persist_device_authorization,
+};
+pub(crate) use direct_message_ratchet_support::{
+    direct_message_peer_frame_version, open_direct_message_frame, seal_direct_message_frame,
+};
 pub(crate) use gossip_subscription_support::gossip_disabled_channel_key;
+pub(crate) use group_direct_messages_support::apply_group_direct_message_key_update;
 pub(crate) use hydration_support::{
     hint_targets_topic, hydrate_subscription_event, hydrate_subscription_hint,
     hydrate_subscription_state, hydrate_topic_state, profile_timeline_page,
     projection_page_needs_hydration,
 };
+pub(crate) use local_search_support::{
+    LOCAL_SEARCH_DIRECT_MESSAGE_KIND, LOCAL_SEARCH_POST_KIND, index_direct_message_for_search,
+    index_object_projections_for_search,
+};
 pub(crate) use metaverse_room_event_support::{
     metaverse_room_event_buffer_key, parse_metaverse_room_event_envelope,
     push_metaverse_room_event_buffer,
@@ -154,12 +202,13 @@ pub(crate) use object_persistence_support::{
     fetch_private_channel_epoch_handoff_grant_from_replica,
     fetch_private_channel_participants_from_replica, fetch_private_channel_policy_from_replica,
     fetch_projection_blob_text, game_projection_row_from_state, live_projection_row_from_state,
-    persist_game_room_state, persist_live_session_state, persist_media_manifest,
-    persist_post_object, persist_private_channel_epoch_handoff_grant,
-    persist_private_channel_metadata, persist_private_channel_participant,
-    persist_private_channel_policy, private_channel_rotation_is_pending,
-    projection_row_from_header, reaction_cache_key, reaction_projection_row_from_doc,
-    reaction_state_view_from_rows, recent_reaction_view_from_projection, search_key_or_asset_id,
+    load_post_history_envelopes, persist_game_room_state, persist_live_session_state,
+    persist_media_manifest, persist_post_object, persist_post_revision,
+    persist_private_channel_epoch_handoff_grant, persist_private_channel_metadata,
+    persist_private_channel_participant, persist_private_channel_policy,
+    private_channel_rotation_is_pending, projection_row_from_header, reaction_cache_key,
+    reaction_projection_row_from_doc, reaction_state_view_from_rows,
+    recent_reaction_view_from_projection, search_key_or_asset_id,
     session_projection_retry_attempts, session_projection_retry_delay, store_manifest_blob,
     wait_for_private_channel_epoch_snapshot,
 };
@@ -168,8 +217,8 @@ pub(crate) use profile_docs_support::{
     load_custom_reaction_assets_from_author_replica, load_profile_posts_from_author_replica,
     load_profile_reposts_from_author_replica, merge_seed_peers, persist_custom_reaction_asset_doc,
     persist_follow_edge_doc, persist_profile_doc, persist_profile_post_doc,
-    persist_profile_repost_doc, persist_reaction_doc, snapshot_follow_notification_baseline,
-    snapshot_object_notification_baseline,
+    persist_profile_repost_doc, persist_reaction_doc, remove_profile_post_doc,
+    snapshot_follow_notification_baseline, snapshot_object_notification_baseline,
 };
 pub(crate) use projection_support::{
     active_private_channel_participants, archive_private_channel_epoch,
@@ -177,7 +226,8 @@ pub(crate) use projection_support::{
     fetch_post_object_for_projection, filter_channel_rows, filtered_thread_page,
     filtered_timeline_page, initial_private_channel_epoch_id,
     joined_private_channel_state_from_capability, merged_private_channel_state_from_epoch_join,
-    next_private_channel_epoch_id, private_channel_epoch_capabilities,
+    next_private_channel_epoch_id, object_projection_row_is_muted,
+    object_projection_row_is_retracted, private_channel_epoch_capabilities,
     private_channel_is_epoch_aware, private_channel_replica_for_epoch,
     profile_timeline_item_is_muted,
 };
@@ -319,6 +369,16 @@ pub struct ServiceHandles {
     pub(crate) docs_sync: Arc<dyn DocsSync>,
     pub(crate) blob_service: Arc<dyn BlobService>,
     pub(crate) keys: Arc<KukuriKeys>,
+    /// DM ratchet state の read-modify-write を直列化する。送信と受信が同じ session を
+    /// 同時に進めると chain key が巻き戻るため。
+    pub(crate) direct_message_session_lock: Arc<Mutex<()>>,
+    /// device replica(`device::{author}::{device_id}`)へ書く端末 id。未設定なら
+    /// 端末間同期は no-op。
+    pub(crate) local_device_id: Option<String>,
+    /// 手動 GC(`collect_blob_garbage`)と容量表示に使う retention 設定。
+    pub(crate) blob_retention: BlobRetentionConfig,
+    /// hydration / DM 受信で projection に反映した変更の通知先。購読者がいなければ捨てる。
+    pub(crate) content_events: tokio::sync::broadcast::Sender<ContentEvent>,
 }
 
 impl ServiceHandles {
@@ -339,8 +399,27 @@ impl ServiceHandles {
             docs_sync,
             blob_service,
             keys: Arc::new(keys),
+            direct_message_session_lock: Arc::new(Mutex::new(())),
+            local_device_id: None,
+            blob_retention: BlobRetentionConfig::default(),
+            content_events: tokio::sync::broadcast::channel(CONTENT_EVENT_CHANNEL_CAPACITY).0,
         }
     }
+
+    pub fn with_local_device_id(mut self, device_id: impl Into<String>) -> Self {
+        self.local_device_id = Some(device_id.into());
+        self
+    }
+
+    pub fn with_blob_retention(mut self, config: BlobRetentionConfig) -> Self {
+        self.blob_retention = config;
+        self
+    }
+
+    pub(crate) fn emit_content_event(&self, event: ContentEvent) {
+        // 受信者 0 件の send error は想定内(UI / daemon 未接続)。
+        let _ = self.content_events.send(event);
+    }
 }
 
 pub struct AppService {
@@ -473,6 +552,10 @@ impl AppService {
         self.services.keys.as_ref()
     }
 
+    pub(crate) fn local_device_id(&self) -> Option<&str> {
+        self.services.local_device_id.as_deref()
+    }
+
     pub fn new<S, T>(store: Arc<S>, transport: Arc<T>) -> Self
     where
         S: Store + ProjectionStore + 'static,
@@ -521,6 +604,11 @@ impl AppService {
         Arc::clone(&self.notification_inserted_notify)
     }
 
+    /// 購読経路で反映された投稿・reaction・DM・session・profile の変更を受け取る。
+    pub fn subscribe_content_events(&self) -> tokio::sync::broadcast::Receiver<ContentEvent> {
+        self.services.content_events.subscribe()
+    }
+
     pub(crate) async fn resolve_repost_source(
         &self,
         source_topic_id: &str,
@@ -815,6 +903,33 @@ impl AppService {
                 let _ = self.services.hint_transport.unsubscribe_hints(&topic).await;
             }
         }
+        if let Some(handle) = self
+            .subscription_registry
+            .group_direct_message_supervisor
+            .lock()
+            .await
+            .take()
+        {
+            handle.abort();
+            let _ = tokio::time::timeout(std::time::Duration::from_secs(2), handle).await;
+        }
+        let group_handles = {
+            let mut subscriptions = self
+                .subscription_registry
+                .group_direct_message_subscriptions
+                .lock()
+                .await;
+            subscriptions.drain().collect::<Vec<_>>()
+        };
+        for (topic, handle) in group_handles {
+            handle.abort();
+            let _ = tokio::time::timeout(std::time::Duration::from_secs(2), handle).await;
+            let _ = self
+                .services
+                .hint_transport
+                .unsubscribe_hints(&TopicId::new(topic))
+                .await;
+        }
         let author_handles = {
             let mut subscriptions = self.subscription_registry.author_subscriptions.lock().await;
             subscriptions
@@ -25,6 +25,10 @@ pub(crate) async fn notification_candidate_from_object_event(
     if header.author.as_str() == local_author_pubkey {
         return Ok(None);
     }
+    // 編集・取り消しによる state の上書きは新規通知にしない(元投稿の通知で足りる)。
+    if header.status != ObjectStatus::Active {
+        return Ok(None);
+    }
     let content = notification_text_from_payload_ref(blob_service, &header.payload_ref).await;
     let repost_commentary = if header.object_kind == "repost" {
         normalize_repost_commentary(content.clone())
@@ -198,6 +202,7 @@ pub(crate) fn notification_kind_key(kind: &NotificationKind) -> &'static str {
         NotificationKind::QuoteRepost => "quote_repost",
         NotificationKind::DirectMessage => "direct_message",
         NotificationKind::Followed => "followed",
+        NotificationKind::GroupDirectMessage => "group_direct_message",
     }
 }
 
@@ -73,6 +73,108 @@ pub(crate) async fn persist_post_object(
     Ok(())
 }
 
+/// 編集 / 取り消し revision を object ごとの履歴 key に積み、object state を上書きする。
+///
+/// 元投稿の envelope(`objects/{id}/envelope`)は書き換えない。revision を先に書くことで、
+/// state が参照する revision envelope が replica に欠けた状態を作らない。
+pub(crate) async fn persist_post_revision(
+    docs_sync: &dyn DocsSync,
+    replica: &ReplicaId,
+    object: &CanonicalPostHeader,
+    revision: &KukuriEnvelope,
+) -> Result<()> {
+    docs_sync.open_replica(replica).await?;
+    docs_sync
+        .apply_doc_op(
+            replica,
+            DocOp::SetJson {
+                key: stable_key(
+                    "objects",
+                    &format!(
+                        "{}/revisions/{}",
+                        object.object_id.as_str(),
+                        timeline_sort_key(revision.created_at, &revision.id)
+                    ),
+                ),
+                value: serde_json::to_value(revision)?,
+            },
+        )
+        .await?;
+    docs_sync
+        .apply_doc_op(
+            replica,
+            DocOp::SetJson {
+                key: stable_key("objects", &format!("{}/state", object.object_id.as_str())),
+                value: serde_json::to_value(object)?,
+            },
+        )
+        .await?;
+    Ok(())
+}
+
+/// object の元 envelope と revision envelope を古い順に返す。
+///
+/// 署名検証に失敗したもの、別 object / 別作者を指す revision は履歴に含めない。
+pub(crate) async fn load_post_history_envelopes(
+    docs_sync: &dyn DocsSync,
+    replica: &ReplicaId,
+    object_id: &EnvelopeId,
+) -> Result<Vec<KukuriEnvelope>> {
+    let Some(original) = query_replica_local_only(
+        docs_sync,
+        replica,
+        DocQuery::Exact(stable_key(
+            "objects",
+            &format!("{}/envelope", object_id.as_str()),
+        )),
+    )
+    .await?
+    .into_iter()
+    .next() else {
+        return Ok(Vec::new());
+    };
+    let original: KukuriEnvelope = serde_json::from_slice(&original.value)?;
+    let mut revisions = Vec::new();
+    for record in query_replica_local_only(
+        docs_sync,
+        replica,
+        DocQuery::Prefix(stable_key(
+            "objects",
+            &format!("{}/revisions/", object_id.as_str()),
+        )),
+    )
+    .await?
+    {
+        let Ok(envelope) = serde_json::from_slice::<KukuriEnvelope>(&record.value) else {
+            continue;
+        };
+        if envelope.verify().is_err() || envelope.pubkey != original.pubkey {
+            continue;
+        }
+        if let Some(content) = envelope.post_revision_content().ok().flatten()
+            && content.object_id == *object_id
+        {
+            revisions.push((content.previous_envelope_id, envelope));
+        }
+    }
+    revisions.sort_by(|(_, left), (_, right)| {
+        left.created_at
+            .cmp(&right.created_at)
+            .then_with(|| left.id.cmp(&right.id))
+    });
+    // created_at は秒精度なので、同一秒の revision も previous_envelope_id の連鎖で適用順に並べる。
+    // 連鎖から外れた revision(別端末からの並行編集)は created_at 順で末尾に残す。
+    let mut history = vec![original];
+    while let Some(position) = revisions
+        .iter()
+        .position(|(previous, _)| history.last().is_some_and(|last| last.id == *previous))
+    {
+        history.push(revisions.remove(position).1);
+    }
+    history.extend(revisions.into_iter().map(|(_, envelope)| envelope));
+    Ok(history)
+}
+
 pub(crate) async fn persist_media_manifest(
     replica: &ReplicaId,
     envelope: &KukuriEnvelope,
@@ -623,6 +725,7 @@ pub(crate) fn projection_row_from_header(
         content,
         attachments: header.attachments.clone(),
         repost_of: header.repost_of.clone(),
+        status: header.status.clone(),
         source_replica_id: source_replica_id.clone(),
         source_key: stable_key("objects", &format!("{}/state", header.object_id.as_str())),
         source_envelope_id: header.envelope_id.clone(),
@@ -433,6 +433,17 @@ impl AppService {
         );
         self.persist_private_channel_capabilities_if_configured()
             .await?;
+        self.record_device_state(
+            stable_key(
+                DEVICE_STATE_PRIVATE_CHANNELS_PREFIX,
+                joined_private_channel_key(state.topic_id.as_str(), state.channel_id.as_str())
+                    .as_str(),
+            )
+            .as_str(),
+            &private_channel_capability_snapshot(&state),
+            false,
+        )
+        .await;
         self.ensure_private_channel_subscription(
             state.topic_id.as_str(),
             state.channel_id.as_str(),
@@ -451,9 +462,19 @@ impl AppService {
             .lock()
             .await
             .remove(joined_private_channel_key(topic_id, channel_id).as_str());
-        if removed.is_some() {
+        if let Some(state) = removed.as_ref() {
             self.persist_private_channel_capabilities_if_configured()
                 .await?;
+            self.record_device_state(
+                stable_key(
+                    DEVICE_STATE_PRIVATE_CHANNELS_PREFIX,
+                    joined_private_channel_key(topic_id, channel_id).as_str(),
+                )
+                .as_str(),
+                &private_channel_capability_snapshot(state),
+                true,
+            )
+            .await;
         }
         let prefix = joined_private_channel_subscription_prefix(topic_id, channel_id);
         let keys = self
@@ -75,6 +75,24 @@ pub(crate) async fn persist_profile_post_doc(
         .await
 }
 
+/// 取り消した投稿を author replica の profile post から外す。
+pub(crate) async fn remove_profile_post_doc(
+    docs_sync: &dyn DocsSync,
+    author_pubkey: &str,
+    object_id: &EnvelopeId,
+) -> Result<()> {
+    let replica = author_replica_id(author_pubkey);
+    docs_sync.open_replica(&replica).await?;
+    docs_sync
+        .apply_doc_op(
+            &replica,
+            DocOp::DeletePrefix {
+                prefix: stable_key("profile/posts", object_id.as_str()),
+            },
+        )
+        .await
+}
+
 pub(crate) async fn persist_profile_repost_doc(
     docs_sync: &dyn DocsSync,
     profile_repost: &ProfileRepost,
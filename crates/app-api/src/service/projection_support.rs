@@ -28,7 +28,9 @@ pub(crate) async fn filtered_timeline_page(
         .await?;
         let next_cursor = page.next_cursor.clone();
         for row in page.items {
-            if !object_projection_row_is_muted(&row, muted_author_pubkeys) {
+            if !object_projection_row_is_retracted(&row)
+                && !object_projection_row_is_muted(&row, muted_author_pubkeys)
+            {
                 items.push(row);
                 if items.len() >= limit {
                     return Ok(Page { items, next_cursor });
@@ -72,7 +74,9 @@ pub(crate) async fn filtered_thread_page(
         .await?;
         let next_cursor = page.next_cursor.clone();
         for row in page.items {
-            if !object_projection_row_is_muted(&row, muted_author_pubkeys) {
+            if !object_projection_row_is_retracted(&row)
+                && !object_projection_row_is_muted(&row, muted_author_pubkeys)
+            {
                 items.push(row);
                 if items.len() >= limit {
                     return Ok(Page { items, next_cursor });
@@ -106,6 +110,11 @@ pub(crate) fn object_projection_row_is_muted(
         })
 }
 
+/// 作者が取り消した(deleted / tombstoned)object は timeline / thread に出さない。
+pub(crate) fn object_projection_row_is_retracted(row: &ObjectProjectionRow) -> bool {
+    matches!(row.status, ObjectStatus::Deleted | ObjectStatus::Tombstoned)
+}
+
 pub(crate) fn bookmarked_post_row_is_muted(
     row: &BookmarkedPostRow,
     muted_author_pubkeys: &BTreeSet<String>,
@@ -16,6 +16,11 @@ pub(crate) struct SubscriptionRegistry {
     pub(crate) subscriptions: Arc<Mutex<HashMap<String, JoinHandle<()>>>>,
     /// DM の購読 task(key = dm topic)。
     pub(crate) direct_message_subscriptions: Arc<Mutex<HashMap<String, JoinHandle<()>>>>,
+    /// グループ DM の購読 task(key = 現 epoch の group topic)。
+    pub(crate) group_direct_message_subscriptions: Arc<Mutex<HashMap<String, JoinHandle<()>>>>,
+    /// グループ DM の購読張り替えと outbox 再送を回す task。鍵更新は pairwise DM の
+    /// task で反映されるため、新しい epoch の購読はこちらが拾う。
+    pub(crate) group_direct_message_supervisor: Arc<Mutex<Option<JoinHandle<()>>>>,
     /// private channel の購読 task(key = channel 購読キー)。
     pub(crate) private_channel_subscriptions: Arc<Mutex<HashMap<String, JoinHandle<()>>>>,
     /// 作者(プロフィール)購読 task(key = author pubkey)。
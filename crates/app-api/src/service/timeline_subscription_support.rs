@@ -121,11 +121,14 @@ impl AppService {
                 "failed to restart replica sync after local timeline write"
             );
         }
-        ObjectProjectionStore::put_object_projection(
+        let row = projection_row_from_header(&object, content, replica);
+        index_object_projections_for_search(
             self.services.projection_store.as_ref(),
-            projection_row_from_header(&object, content, replica),
+            std::slice::from_ref(&row),
         )
         .await?;
+        ObjectProjectionStore::put_object_projection(self.services.projection_store.as_ref(), row)
+            .await?;
         if let PayloadRef::BlobText { hash, .. } = &object.payload_ref {
             BlobCacheStore::mark_blob_status(
                 self.services.projection_store.as_ref(),
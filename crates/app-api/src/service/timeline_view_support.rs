@@ -2,7 +2,10 @@
 //! WP-H5 PR2 で timeline_runtime_support.rs から分割。購読・復旧は timeline_subscription_support.rs。
 
 use super::*;
-use crate::{ContentObservationView, ContentProvenanceView};
+use crate::{
+    ContentModerationSourceView, ContentModerationView, ContentObservationView,
+    ContentProvenanceView,
+};
 
 fn inherit_post_observation_for_attachments(
     attachments: &mut [AttachmentView],
@@ -19,6 +22,26 @@ fn inherit_post_observation_for_attachments(
     }
 }
 
+fn moderation_treatment_rank(policy: CommunityModerationPolicy) -> u8 {
+    match policy {
+        CommunityModerationPolicy::Hide => 3,
+        CommunityModerationPolicy::Blur => 2,
+        CommunityModerationPolicy::Label => 1,
+        CommunityModerationPolicy::Ignore => 0,
+    }
+}
+
+fn stronger_moderation_treatment(
+    left: CommunityModerationPolicy,
+    right: CommunityModerationPolicy,
+) -> CommunityModerationPolicy {
+    if moderation_treatment_rank(right) > moderation_treatment_rank(left) {
+        right
+    } else {
+        left
+    }
+}
+
 impl AppService {
     pub(crate) async fn content_provenance_view(
         &self,
@@ -47,6 +70,73 @@ impl AppService {
         }))
     }
 
+    /// 投稿とその作者に向けた moderation event へ node ごとの端末内 policy を適用する。
+    ///
+    /// policy 未設定の node は `label`(advisory 表示のみ)。`ignore` の node の event は
+    /// 無視し、複数 node が効く場合は最も強い扱い(hide > blur > label)を採る。
+    pub(crate) async fn content_moderation_view(
+        &self,
+        object_id: &str,
+        author_pubkey: &str,
+    ) -> Result<Option<ContentModerationView>> {
+        let mut events = self
+            .services
+            .projection_store
+            .list_community_moderation_events_for_targets("post", &[object_id.to_string()])
+            .await?;
+        events.extend(
+            self.services
+                .projection_store
+                .list_community_moderation_events_for_targets("user", &[author_pubkey.to_string()])
+                .await?,
+        );
+        if events.is_empty() {
+            return Ok(None);
+        }
+        let policies = self
+            .services
+            .projection_store
+            .list_community_moderation_policies()
+            .await?
+            .into_iter()
+            .map(|row| (row.node_base_url, row.policy))
+            .collect::<HashMap<_, _>>();
+        let mut treatment = None;
+        let mut labels = Vec::new();
+        let mut sources = Vec::new();
+        for event in events {
+            let policy = policies
+                .get(event.node_base_url.as_str())
+                .copied()
+                .unwrap_or_default();
+            if policy == CommunityModerationPolicy::Ignore {
+                continue;
+            }
+            treatment = Some(match treatment {
+                Some(current) => stronger_moderation_treatment(current, policy),
+                None => policy,
+            });
+            for label in &event.labels {
+                if !labels.contains(label) {
+                    labels.push(label.clone());
+                }
+            }
+            sources.push(ContentModerationSourceView {
+                node_base_url: event.node_base_url,
+                event_id: event.event_id,
+                target_kind: event.target_kind,
+                action: event.action,
+                reason_code: event.reason_code,
+                labels: event.labels,
+            });
+        }
+        Ok(treatment.map(|treatment| ContentModerationView {
+            treatment,
+            labels,
+            sources,
+        }))
+    }
+
     pub(crate) async fn page_to_view(
         &self,
         page: Page<ObjectProjectionRow>,
@@ -86,10 +176,18 @@ impl AppService {
 
         let mut items = Vec::with_capacity(page.items.len());
         for row in page.items {
-            items.push(
-                self.row_to_view_with_cache(row, &profiles, &relationships, &reactions_by_target)
-                    .await?,
-            );
+            let view = self
+                .row_to_view_with_cache(row, &profiles, &relationships, &reactions_by_target)
+                .await?;
+            // hide policy の投稿はタイムラインから外す(cursor はページ単位のまま進める)。
+            if view
+                .moderation
+                .as_ref()
+                .is_some_and(|moderation| moderation.treatment == CommunityModerationPolicy::Hide)
+            {
+                continue;
+            }
+            items.push(view);
         }
         Ok(TimelineView {
             items,
@@ -116,8 +214,19 @@ impl AppService {
         let provenance = self
             .content_provenance_view("post", row.object_id.as_str(), "author_docs")
             .await?;
+        let moderation = self
+            .content_moderation_view(row.object_id.as_str(), row.author_pubkey.as_str())
+            .await?;
         let mut attachments = self.attachment_views_for_projection_row(&row).await?;
         inherit_post_observation_for_attachments(&mut attachments, provenance.as_ref());
+        let mut visible_downloads = missing_attachment_downloads(&attachments);
+        if content_status == BlobViewStatus::Missing
+            && let PayloadRef::BlobText { hash, .. } = &row.payload_ref
+        {
+            visible_downloads.push((hash.clone(), DownloadJobKind::Payload));
+        }
+        self.prioritize_visible_blob_downloads(visible_downloads)
+            .await?;
         let repost_of = match row.repost_of.clone() {
             Some(snapshot) => Some(
                 self.repost_snapshot_to_view_with_profiles(snapshot, profiles)
@@ -168,6 +277,7 @@ impl AppService {
             mutual,
             friend_of_friend,
             provenance,
+            moderation,
             content: row.content.unwrap_or_else(|| "[blob pending]".to_string()),
             content_status,
             attachments,
@@ -225,6 +335,11 @@ impl AppService {
             .projection_store
             .put_object_projection(row.clone())
             .await?;
+        index_object_projections_for_search(
+            self.services.projection_store.as_ref(),
+            std::slice::from_ref(&row),
+        )
+        .await?;
         Ok(Some(row))
     }
 
@@ -357,6 +472,9 @@ impl AppService {
             .content_provenance_view("post", row.source_object_id.as_str(), "author_docs")
             .await?;
         inherit_post_observation_for_attachments(&mut attachments, provenance.as_ref());
+        let moderation = self
+            .content_moderation_view(row.source_object_id.as_str(), row.author_pubkey.as_str())
+            .await?;
 
         let AuthorViewParts {
             author_name,
@@ -383,6 +501,7 @@ impl AppService {
                 mutual,
                 friend_of_friend,
                 provenance,
+                moderation,
                 object_kind: row.object_kind.clone(),
                 content: row.content.unwrap_or_else(|| "[blob pending]".to_string()),
                 content_status,
@@ -430,6 +549,12 @@ impl AppService {
         let provenance = self
             .content_provenance_view("post", profile_post.object_id.as_str(), "author_docs")
             .await?;
+        let moderation = self
+            .content_moderation_view(
+                profile_post.object_id.as_str(),
+                profile_post.author_pubkey.as_str(),
+            )
+            .await?;
 
         let AuthorViewParts {
             author_name,
@@ -460,6 +585,7 @@ impl AppService {
             mutual,
             friend_of_friend,
             provenance,
+            moderation,
             object_kind: profile_post.object_kind,
             content: profile_post.content,
             content_status: BlobViewStatus::Available,
@@ -511,6 +637,12 @@ impl AppService {
         let provenance = self
             .content_provenance_view("post", profile_repost.object_id.as_str(), "author_docs")
             .await?;
+        let moderation = self
+            .content_moderation_view(
+                profile_repost.object_id.as_str(),
+                profile_repost.author_pubkey.as_str(),
+            )
+            .await?;
         Ok(PostView {
             object_id: profile_repost.object_id.0.clone(),
             envelope_id: profile_repost.envelope_id.0.clone(),
@@ -524,6 +656,7 @@ impl AppService {
             mutual,
             friend_of_friend,
             provenance,
+            moderation,
             object_kind: "repost".into(),
             content: profile_repost.commentary.clone().unwrap_or_default(),
             content_status: BlobViewStatus::Available,
@@ -76,6 +76,13 @@ impl AppService {
         } else {
             current_profile.picture_asset.clone()
         };
+        let replaced_picture = current_profile
+            .picture_asset
+            .as_ref()
+            .map(|asset| &asset.hash)
+            .filter(|hash| picture_asset.as_ref().map(|asset| &asset.hash) != Some(*hash));
+        self.pin_blobs(picture_asset.as_ref().map(|asset| &asset.hash))
+            .await?;
         let envelope = build_profile_envelope(
             self.services.keys.as_ref(),
             &KukuriProfileEnvelopeContentV1 {
@@ -95,6 +102,7 @@ impl AppService {
             .upsert_profile_cache(profile.clone())
             .await?;
         persist_profile_doc(self.services.docs_sync.as_ref(), &profile, &envelope).await?;
+        self.unpin_blobs(replaced_picture).await?;
         self.rebuild_author_relationships().await?;
         *self.last_sync_ts.lock().await = Some(Utc::now().timestamp_millis());
         Ok(profile)
@@ -150,13 +158,20 @@ impl AppService {
         let author_pubkey = normalize_author_pubkey(pubkey)?;
         self.ensure_author_subscription(author_pubkey.as_str())
             .await?;
+        let row = MutedAuthorRow {
+            author_pubkey: author_pubkey.clone(),
+            muted_at: Utc::now().timestamp_millis(),
+        };
         self.services
             .projection_store
-            .put_muted_author(MutedAuthorRow {
-                author_pubkey: author_pubkey.clone(),
-                muted_at: Utc::now().timestamp_millis(),
-            })
+            .put_muted_author(row.clone())
             .await?;
+        self.record_device_state(
+            stable_key(DEVICE_STATE_MUTES_PREFIX, author_pubkey.as_str()).as_str(),
+            &row,
+            false,
+        )
+        .await;
         self.build_author_social_view(author_pubkey.as_str()).await
     }
 
@@ -168,6 +183,12 @@ impl AppService {
             .projection_store
             .remove_muted_author(author_pubkey.as_str())
             .await?;
+        self.record_device_state(
+            stable_key(DEVICE_STATE_MUTES_PREFIX, author_pubkey.as_str()).as_str(),
+            &author_pubkey,
+            true,
+        )
+        .await;
         self.build_author_social_view(author_pubkey.as_str()).await
     }
 
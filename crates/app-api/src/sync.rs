@@ -97,6 +97,7 @@ impl AppService {
             discovery,
             gossip_disabled_topics: self.list_gossip_disabled_topics().await,
             gossip_disabled_channels: self.list_gossip_disabled_channels().await,
+            blob_downloads: self.blob_download_queue_status().await?,
         })
     }
 
@@ -110,6 +111,7 @@ impl AppService {
             configured_seed_peer_ids,
             bootstrap_seed_peer_ids,
             manual_ticket_peer_ids,
+            lan_peer_ids,
             connected_peer_ids,
             local_endpoint_id,
             last_discovery_error,
@@ -125,6 +127,7 @@ impl AppService {
             configured_seed_peer_ids,
             bootstrap_seed_peer_ids,
             manual_ticket_peer_ids,
+            lan_peer_ids,
             connected_peer_ids,
             docs_assist_peer_ids,
             blob_assist_peer_ids,
@@ -236,6 +239,27 @@ impl AppService {
             .await
     }
 
+    /// topic(と参加中の private channel)の replica cursor を捨てて replica 全体を歩き直し、
+    /// projection を作り直す。反映した state 数を返す。
+    pub async fn rebuild_topic_projections(&self, topic_id: &str) -> Result<usize> {
+        let projection_store = self.services.projection_store.as_ref();
+        projection_store
+            .clear_replica_cursor(&topic_replica_id(topic_id))
+            .await?;
+        for state in self.joined_private_channel_states_for_topic(topic_id).await {
+            for epoch in private_channel_epoch_capabilities(&state) {
+                projection_store
+                    .clear_replica_cursor(&private_channel_replica_for_epoch(
+                        state.channel_id.as_str(),
+                        epoch.epoch_id.as_str(),
+                    ))
+                    .await?;
+            }
+        }
+        self.hydrate_scope_projection(topic_id, &TimelineScope::AllJoined)
+            .await
+    }
+
     pub async fn peer_ticket(&self) -> Result<Option<String>> {
         self.services.transport.export_ticket().await
     }
@@ -0,0 +1,110 @@
+use super::*;
+
+fn linked_device_app(
+    docs_sync: Arc<MemoryDocsSync>,
+    keys: KukuriKeys,
+    device_id: Option<&str>,
+) -> AppService {
+    let store = Arc::new(MemoryStore::default());
+    let transport = Arc::new(StaticTransport::new(PeerSnapshot::default()));
+    let services = ServiceHandles::new(
+        store.clone(),
+        store,
+        transport,
+        Arc::new(NoopHintTransport),
+        docs_sync,
+        Arc::new(MemoryBlobService::default()),
+        keys,
+    );
+    AppService::from_handles(match device_id {
+        Some(device_id) => services.with_local_device_id(device_id),
+        None => services,
+    })
+}
+
+async fn muted_pubkeys(app: &AppService) -> Vec<String> {
+    app.list_social_connections(SocialConnectionKind::Muted)
+        .await
+        .expect("muted authors")
+        .into_iter()
+        .map(|author| author.author_pubkey)
+        .collect()
+}
+
+#[tokio::test]
+async fn device_pairing_registers_new_device_under_author() {
+    let docs_sync = Arc::new(MemoryDocsSync::default());
+    let keys = generate_keys();
+    let desktop = linked_device_app(docs_sync.clone(), keys.clone(), Some("desktop"));
+
+    let pairing = desktop
+        .create_device_pairing("laptop")
+        .await
+        .expect("create device pairing");
+    let opened = kukuri_core::open_device_pairing_ticket(
+        pairing.ticket.as_str(),
+        pairing.pairing_code.as_str(),
+        Utc::now().timestamp_millis(),
+    )
+    .expect("open pairing ticket");
+    assert_eq!(opened.keys.public_key(), keys.public_key());
+    assert_eq!(opened.authorization.device_id, pairing.device_id);
+    assert_eq!(
+        opened.authorization.authorized_by_device_id.as_deref(),
+        Some("desktop")
+    );
+
+    let laptop = linked_device_app(
+        docs_sync.clone(),
+        opened.keys,
+        Some(pairing.device_id.as_str()),
+    );
+    let devices = laptop.list_linked_devices().await.expect("linked devices");
+    assert_eq!(
+        devices
+            .iter()
+            .map(|device| (device.device_id.as_str(), device.is_current_device))
+            .collect::<BTreeSet<_>>(),
+        BTreeSet::from([("desktop", false), (pairing.device_id.as_str(), true)])
+    );
+
+    let unlinked = linked_device_app(docs_sync, keys, None);
+    assert!(unlinked.create_device_pairing("tablet").await.is_err());
+}
+
+#[tokio::test]
+async fn linked_devices_merge_mutes_with_last_writer_wins() {
+    let docs_sync = Arc::new(MemoryDocsSync::default());
+    let keys = generate_keys();
+    let desktop = linked_device_app(docs_sync.clone(), keys.clone(), Some("desktop"));
+    let pairing = desktop
+        .create_device_pairing("laptop")
+        .await
+        .expect("create device pairing");
+    let laptop = linked_device_app(docs_sync, keys, Some(pairing.device_id.as_str()));
+    let muted_author = generate_keys().public_key_hex();
+
+    desktop
+        .mute_author(muted_author.as_str())
+        .await
+        .expect("mute on desktop");
+    assert!(muted_pubkeys(&laptop).await.is_empty());
+    assert_eq!(laptop.sync_linked_devices().await.expect("laptop sync"), 1);
+    assert_eq!(muted_pubkeys(&laptop).await, vec![muted_author.clone()]);
+    assert_eq!(
+        laptop.sync_linked_devices().await.expect("laptop resync"),
+        0,
+        "already merged entries must not be re-applied"
+    );
+
+    sleep(Duration::from_millis(2)).await;
+    laptop
+        .unmute_author(muted_author.as_str())
+        .await
+        .expect("unmute on laptop");
+    assert_eq!(
+        desktop.sync_linked_devices().await.expect("desktop sync"),
+        1
+    );
+    assert!(muted_pubkeys(&desktop).await.is_empty());
+}
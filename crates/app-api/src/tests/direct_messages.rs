@@ -1,4 +1,6 @@
 mod access;
 mod delivery;
+mod group;
+mod ratchet;
 mod restart;
 mod subscription_status;
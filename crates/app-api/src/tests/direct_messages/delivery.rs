@@ -125,6 +125,37 @@ async fn dm_first_message_appears_in_recipient_conversation_list_without_opening
             .any(|message| message.message_id == message_id),
         "recipient should see the delivered message after the conversation appears",
     );
+
+    let search_input = LocalSearchInput {
+        query: "FROM A".to_string(),
+        include_direct_messages: true,
+        limit: 10,
+        ..LocalSearchInput::default()
+    };
+    let found = app_b
+        .search_local(search_input.clone())
+        .await
+        .expect("search delivered direct message");
+    assert_eq!(
+        found
+            .direct_messages
+            .iter()
+            .map(|message| message.message_id.as_str())
+            .collect::<Vec<_>>(),
+        vec![message_id.as_str()]
+    );
+    app_b
+        .delete_direct_message_message(a_pubkey.as_str(), message_id.as_str())
+        .await
+        .expect("delete delivered direct message");
+    assert!(
+        app_b
+            .search_local(search_input)
+            .await
+            .expect("search deleted direct message")
+            .direct_messages
+            .is_empty()
+    );
 }
 
 #[tokio::test]
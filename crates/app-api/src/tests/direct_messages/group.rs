@@ -0,0 +1,333 @@
+use super::super::*;
+
+struct GroupMember {
+    app: AppService,
+    store: Arc<MemoryStore>,
+    pubkey: String,
+}
+
+async fn mutual_follow(stores: &[&Arc<MemoryStore>], left: &KukuriKeys, right: &KukuriKeys) {
+    for (follower, followee) in [(left, right), (right, left)] {
+        let edge = parse_follow_edge(
+            &build_follow_edge_envelope(
+                follower,
+                &Pubkey::from(followee.public_key_hex().as_str()),
+                FollowEdgeStatus::Active,
+            )
+            .expect("build follow edge"),
+        )
+        .expect("parse follow edge")
+        .expect("follow edge");
+        for store in stores {
+            store
+                .upsert_follow_edge(edge.clone())
+                .await
+                .expect("seed follow edge");
+        }
+    }
+}
+
+/// owner と各メンバーだけが相互フォローしている 4 人。hint は流さず配送はテスト側で行う。
+async fn group_members() -> (Vec<GroupMember>, Arc<MemoryBlobService>) {
+    let transport = Arc::new(StaticTransport::new(PeerSnapshot::default()));
+    let docs_sync = Arc::new(MemoryDocsSync::default());
+    let blob_service = Arc::new(MemoryBlobService::default());
+    let keys = (0..4).map(|_| generate_keys()).collect::<Vec<_>>();
+    let stores = (0..4)
+        .map(|_| Arc::new(MemoryStore::default()))
+        .collect::<Vec<_>>();
+    let store_refs = stores.iter().collect::<Vec<_>>();
+    for member_keys in &keys[1..] {
+        mutual_follow(&store_refs, &keys[0], member_keys).await;
+    }
+    let mut members = Vec::new();
+    for (keys, store) in keys.into_iter().zip(stores) {
+        let pubkey = keys.public_key_hex();
+        let app = app_service_from_dependencies(
+            store.clone(),
+            store.clone(),
+            transport.clone(),
+            Arc::new(NoopHintTransport),
+            docs_sync.clone(),
+            blob_service.clone(),
+            keys,
+        );
+        app.rebuild_author_relationships()
+            .await
+            .expect("rebuild relationships");
+        members.push(GroupMember { app, store, pubkey });
+    }
+    (members, blob_service)
+}
+
+async fn deliver_key_update(owner: &GroupMember, member: &GroupMember, group_id: &str, epoch: u64) {
+    let message_id = format!("dm-group-key-{group_id}-{epoch}");
+    let row = owner
+        .store
+        .list_direct_message_outbox()
+        .await
+        .expect("list outbox")
+        .into_iter()
+        .find(|row| row.message_id == message_id && row.peer_pubkey == member.pubkey)
+        .expect("queued key update");
+    let topic = derive_direct_message_topic(
+        member.app.services.keys.as_ref(),
+        &Pubkey::from(owner.pubkey.as_str()),
+    )
+    .expect("derive dm topic");
+    assert!(
+        AppService::ingest_direct_message_frame(
+            &member.app.services,
+            member.pubkey.as_str(),
+            owner.pubkey.as_str(),
+            &topic,
+            row.dm_id.as_str(),
+            message_id.as_str(),
+            &row.frame_blob_hash,
+        )
+        .await
+        .expect("ingest key update")
+    );
+}
+
+async fn deliver_group_frame(
+    sender: &GroupMember,
+    recipient: &GroupMember,
+    group_id: &str,
+    message_id: &str,
+) -> Result<bool> {
+    let row = sender
+        .store
+        .list_group_direct_message_outbox(group_id)
+        .await
+        .expect("list group outbox")
+        .into_iter()
+        .find(|row| row.message_id == message_id)
+        .expect("queued group frame");
+    AppService::ingest_group_direct_message_frame(
+        &recipient.app.services,
+        recipient.pubkey.as_str(),
+        &TopicId::new("kukuri:dm:test"),
+        group_id,
+        message_id,
+        &row.frame_blob_hash,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn group_dm_members_receive_messages_and_removed_member_is_rekeyed_out() {
+    let (members, _blob_service) = group_members().await;
+    let [owner, bob, carol, dave] = members.as_slice() else {
+        panic!("expected four members");
+    };
+    let group = owner
+        .app
+        .create_group_direct_message(Some("weekend"), &[bob.pubkey.clone(), carol.pubkey.clone()])
+        .await
+        .expect("create group");
+    assert_eq!(group.epoch, 1);
+    assert_eq!(group.member_pubkeys.len(), 3);
+    let group_id = group.group_id.as_str();
+    for member in [bob, carol] {
+        deliver_key_update(owner, member, group_id, 1).await;
+    }
+    assert!(
+        owner
+            .app
+            .create_group_direct_message(None, std::slice::from_ref(&bob.pubkey))
+            .await
+            .is_err(),
+        "a group needs at least three members"
+    );
+    let bob_view = bob
+        .app
+        .open_group_direct_message(group_id)
+        .await
+        .expect("bob opens group");
+    assert_eq!(bob_view.title.as_deref(), Some("weekend"));
+    assert!(!bob_view.is_owner);
+
+    let hello = bob
+        .app
+        .send_group_direct_message(group_id, Some("hello group"), None, Vec::new())
+        .await
+        .expect("bob sends group message");
+    for recipient in [owner, carol] {
+        assert!(
+            deliver_group_frame(bob, recipient, group_id, hello.as_str())
+                .await
+                .expect("deliver group message")
+        );
+    }
+    let carol_messages = carol
+        .app
+        .list_group_direct_message_messages(group_id, None, 20)
+        .await
+        .expect("carol lists group messages");
+    assert_eq!(carol_messages.items.len(), 1);
+    assert_eq!(carol_messages.items[0].text, "hello group");
+    assert_eq!(carol_messages.items[0].sender_pubkey, bob.pubkey);
+    assert!(
+        carol
+            .app
+            .list_notifications()
+            .await
+            .expect("list notifications")
+            .iter()
+            .any(|row| row.kind == NotificationKind::GroupDirectMessage)
+    );
+
+    // 全メンバーの ack が揃うまで outbox に残る。
+    let acked_at = Utc::now().timestamp_millis();
+    assert!(
+        !AppService::record_group_direct_message_ack(
+            bob.store.as_ref(),
+            bob.pubkey.as_str(),
+            group_id,
+            hello.as_str(),
+            owner.pubkey.as_str(),
+            acked_at,
+        )
+        .await
+        .expect("record owner ack")
+    );
+    assert!(
+        AppService::record_group_direct_message_ack(
+            bob.store.as_ref(),
+            bob.pubkey.as_str(),
+            group_id,
+            hello.as_str(),
+            carol.pubkey.as_str(),
+            acked_at,
+        )
+        .await
+        .expect("record carol ack")
+    );
+    assert!(
+        bob.store
+            .list_group_direct_message_outbox(group_id)
+            .await
+            .expect("list outbox")
+            .is_empty()
+    );
+
+    let rekeyed = owner
+        .app
+        .add_group_direct_message_members(group_id, std::slice::from_ref(&dave.pubkey))
+        .await
+        .expect("add dave");
+    assert_eq!(rekeyed.epoch, 2);
+    let rekeyed = owner
+        .app
+        .remove_group_direct_message_members(group_id, std::slice::from_ref(&carol.pubkey))
+        .await
+        .expect("remove carol");
+    assert_eq!(rekeyed.epoch, 3);
+    for member in [bob, dave] {
+        deliver_key_update(owner, member, group_id, 3).await;
+    }
+    deliver_key_update(owner, carol, group_id, 3).await;
+    assert!(
+        carol
+            .app
+            .open_group_direct_message(group_id)
+            .await
+            .expect("carol keeps history")
+            .removed
+    );
+    assert!(
+        carol
+            .app
+            .send_group_direct_message(group_id, Some("still here?"), None, Vec::new())
+            .await
+            .is_err()
+    );
+
+    let secret = owner
+        .app
+        .send_group_direct_message(group_id, Some("after rekey"), None, Vec::new())
+        .await
+        .expect("owner sends after rekey");
+    assert!(
+        deliver_group_frame(owner, dave, group_id, secret.as_str())
+            .await
+            .expect("dave reads new epoch")
+    );
+    assert!(
+        !deliver_group_frame(owner, carol, group_id, secret.as_str())
+            .await
+            .expect("removed member ignores new epoch frame")
+    );
+    assert!(
+        carol
+            .store
+            .get_group_direct_message_epoch(group_id, 3)
+            .await
+            .expect("load epoch")
+            .is_none()
+    );
+    let dave_texts = dave
+        .app
+        .list_group_direct_message_messages(group_id, None, 20)
+        .await
+        .expect("dave lists group messages")
+        .items
+        .into_iter()
+        .map(|message| message.text)
+        .collect::<Vec<_>>();
+    assert_eq!(dave_texts, vec!["after rekey"]);
+}
+
+#[tokio::test]
+async fn group_dm_frames_are_checked_against_epoch_members() {
+    let (members, _blob_service) = group_members().await;
+    let [owner, bob, carol, _dave] = members.as_slice() else {
+        panic!("expected four members");
+    };
+    let group = owner
+        .app
+        .create_group_direct_message(None, &[bob.pubkey.clone(), carol.pubkey.clone()])
+        .await
+        .expect("create group");
+    let group_id = group.group_id.as_str();
+    for member in [bob, carol] {
+        deliver_key_update(owner, member, group_id, 1).await;
+    }
+    let late = carol
+        .app
+        .send_group_direct_message(group_id, Some("sent before removal"), None, Vec::new())
+        .await
+        .expect("carol sends in epoch 1");
+
+    owner
+        .app
+        .remove_group_direct_message_members(group_id, std::slice::from_ref(&carol.pubkey))
+        .await
+        .expect("remove carol");
+    deliver_key_update(owner, bob, group_id, 2).await;
+    let epoch = bob
+        .store
+        .get_group_direct_message_epoch(group_id, 2)
+        .await
+        .expect("load epoch")
+        .expect("epoch 2");
+    assert!(!epoch.members.contains(&carol.pubkey));
+
+    // epoch 1 の時点では carol はメンバーだったので、遅れて届いた frame も受け取る。
+    assert!(
+        deliver_group_frame(carol, bob, group_id, late.as_str())
+            .await
+            .expect("deliver late frame")
+    );
+    let texts = bob
+        .app
+        .list_group_direct_message_messages(group_id, None, 20)
+        .await
+        .expect("bob lists group messages")
+        .items
+        .into_iter()
+        .map(|message| message.text)
+        .collect::<Vec<_>>();
+    assert_eq!(texts, vec!["sent before removal"]);
+}
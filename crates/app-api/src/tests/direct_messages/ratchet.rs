@@ -0,0 +1,495 @@
+use super::super::*;
+
+struct RatchetPair {
+    app_a: AppService,
+    app_b: AppService,
+    store_a: Arc<MemoryStore>,
+    store_b: Arc<MemoryStore>,
+    blob_service: Arc<MemoryBlobService>,
+    a_pubkey: String,
+    b_pubkey: String,
+}
+
+async fn mutual_dm_pair() -> RatchetPair {
+    let transport = Arc::new(StaticTransport::new(PeerSnapshot::default()));
+    let docs_sync = Arc::new(MemoryDocsSync::default());
+    let blob_service = Arc::new(MemoryBlobService::default());
+    let store_a = Arc::new(MemoryStore::default());
+    let store_b = Arc::new(MemoryStore::default());
+    let keys_a = generate_keys();
+    let keys_b = generate_keys();
+    let a_pubkey = keys_a.public_key_hex();
+    let b_pubkey = keys_b.public_key_hex();
+    let follow_a_to_b = parse_follow_edge(
+        &build_follow_edge_envelope(
+            &keys_a,
+            &Pubkey::from(b_pubkey.as_str()),
+            FollowEdgeStatus::Active,
+        )
+        .expect("build follow edge a->b"),
+    )
+    .expect("parse follow edge a->b")
+    .expect("follow edge a->b");
+    let follow_b_to_a = parse_follow_edge(
+        &build_follow_edge_envelope(
+            &keys_b,
+            &Pubkey::from(a_pubkey.as_str()),
+            FollowEdgeStatus::Active,
+        )
+        .expect("build follow edge b->a"),
+    )
+    .expect("parse follow edge b->a")
+    .expect("follow edge b->a");
+    for store in [&store_a, &store_b] {
+        store
+            .upsert_follow_edge(follow_a_to_b.clone())
+            .await
+            .expect("seed follow edge a->b");
+        store
+            .upsert_follow_edge(follow_b_to_a.clone())
+            .await
+            .expect("seed follow edge b->a");
+    }
+
+    // hint は流さず、frame の配送順をテスト側で制御する。
+    let app_a = app_service_from_dependencies(
+        store_a.clone(),
+        store_a.clone(),
+        transport.clone(),
+        Arc::new(NoopHintTransport),
+        docs_sync.clone(),
+        blob_service.clone(),
+        keys_a,
+    );
+    let app_b = app_service_from_dependencies(
+        store_b.clone(),
+        store_b.clone(),
+        transport,
+        Arc::new(NoopHintTransport),
+        docs_sync,
+        blob_service.clone(),
+        keys_b,
+    );
+    app_a
+        .rebuild_author_relationships()
+        .await
+        .expect("rebuild relationships for app a");
+    app_b
+        .rebuild_author_relationships()
+        .await
+        .expect("rebuild relationships for app b");
+    RatchetPair {
+        app_a,
+        app_b,
+        store_a,
+        store_b,
+        blob_service,
+        a_pubkey,
+        b_pubkey,
+    }
+}
+
+async fn queued_frame_hash(store: &MemoryStore, message_id: &str) -> kukuri_core::BlobHash {
+    store
+        .list_direct_message_outbox()
+        .await
+        .expect("list outbox")
+        .into_iter()
+        .find(|row| row.message_id == message_id)
+        .expect("queued outbox row")
+        .frame_blob_hash
+}
+
+async fn queued_frame(
+    store: &MemoryStore,
+    blob_service: &MemoryBlobService,
+    message_id: &str,
+) -> DirectMessageFrame {
+    let hash = queued_frame_hash(store, message_id).await;
+    let bytes = blob_service
+        .fetch_blob(&hash)
+        .await
+        .expect("fetch frame blob")
+        .expect("frame blob");
+    serde_json::from_slice(bytes.as_slice()).expect("decode frame blob")
+}
+
+async fn deliver(
+    sender_store: &MemoryStore,
+    recipient: &AppService,
+    sender_pubkey: &str,
+    recipient_pubkey: &str,
+    message_id: &str,
+) -> Result<bool> {
+    let hash = queued_frame_hash(sender_store, message_id).await;
+    let topic = derive_direct_message_topic(
+        recipient.services.keys.as_ref(),
+        &Pubkey::from(sender_pubkey),
+    )
+    .expect("derive dm topic");
+    AppService::ingest_direct_message_frame(
+        &recipient.services,
+        recipient_pubkey,
+        sender_pubkey,
+        &topic,
+        direct_message_id_for_participants(
+            &Pubkey::from(sender_pubkey),
+            &Pubkey::from(recipient_pubkey),
+        )
+        .as_str(),
+        message_id,
+        &hash,
+    )
+    .await
+}
+
+async fn received_texts(app: &AppService, peer_pubkey: &str) -> Vec<String> {
+    let mut messages = app
+        .list_direct_message_messages(peer_pubkey, None, 50)
+        .await
+        .expect("list direct messages")
+        .items
+        .into_iter()
+        .filter(|message| !message.outgoing)
+        .collect::<Vec<_>>();
+    messages.sort_by_key(|message| message.created_at);
+    messages.into_iter().map(|message| message.text).collect()
+}
+
+/// v1 の初回メッセージで v2 対応を広告し、返信以降は ratchet frame に切り替わる。
+/// 返信(相手の session prekey に対する init)の message_id を返す。
+async fn negotiate_v2(pair: &RatchetPair) -> String {
+    let hello = pair
+        .app_a
+        .send_direct_message(pair.b_pubkey.as_str(), Some("hello"), None, Vec::new())
+        .await
+        .expect("send first message");
+    assert!(matches!(
+        queued_frame(&pair.store_a, &pair.blob_service, hello.as_str()).await,
+        DirectMessageFrame::V1(_)
+    ));
+    assert!(
+        deliver(
+            &pair.store_a,
+            &pair.app_b,
+            pair.a_pubkey.as_str(),
+            pair.b_pubkey.as_str(),
+            hello.as_str(),
+        )
+        .await
+        .expect("deliver first message")
+    );
+
+    let reply = pair
+        .app_b
+        .send_direct_message(pair.a_pubkey.as_str(), Some("hi"), None, Vec::new())
+        .await
+        .expect("send reply");
+    assert!(matches!(
+        queued_frame(&pair.store_b, &pair.blob_service, reply.as_str()).await,
+        DirectMessageFrame::V2(_)
+    ));
+    assert!(
+        deliver(
+            &pair.store_b,
+            &pair.app_a,
+            pair.b_pubkey.as_str(),
+            pair.a_pubkey.as_str(),
+            reply.as_str(),
+        )
+        .await
+        .expect("deliver reply")
+    );
+    reply
+}
+
+async fn local_session_prekey(store: &MemoryStore, dm_id: &str) -> Pubkey {
+    let secret_hex = store
+        .get_direct_message_session(dm_id)
+        .await
+        .expect("get dm session")
+        .and_then(|session| session.local_prekey_secret_hex)
+        .expect("local session prekey");
+    KukuriKeys::parse(secret_hex.as_str())
+        .expect("parse session prekey")
+        .public_key()
+}
+
+#[tokio::test]
+async fn dm_v2_session_init_targets_the_advertised_prekey_and_retires_it() {
+    let pair = mutual_dm_pair().await;
+    let dm_id = direct_message_id_for_participants(
+        &Pubkey::from(pair.a_pubkey.as_str()),
+        &Pubkey::from(pair.b_pubkey.as_str()),
+    );
+    let reply = negotiate_v2(&pair).await;
+    let DirectMessageFrame::V2(init) =
+        queued_frame(&pair.store_b, &pair.blob_service, reply.as_str()).await
+    else {
+        panic!("reply must be a v2 frame");
+    };
+    assert!(init.header.session_init);
+    let advertised = init
+        .header
+        .recipient_prekey
+        .clone()
+        .expect("init is keyed to the advertised prekey");
+
+    // 採用後は prekey が差し替わり、identity 鍵だけでは init frame を開けない。
+    assert_ne!(
+        local_session_prekey(&pair.store_a, dm_id.as_str()).await,
+        advertised
+    );
+    assert!(
+        decrypt_direct_message_frame_v2(pair.app_a.services.keys.as_ref(), None, None, &init, None)
+            .is_err()
+    );
+    assert_eq!(
+        received_texts(&pair.app_a, pair.b_pubkey.as_str()).await,
+        vec!["hi"]
+    );
+}
+
+/// backup には ratchet state も session prekey も入れない。復元した端末は古い counter を
+/// 再利用せず、次の送信で session-init から張り直す。
+#[tokio::test]
+async fn dm_backup_restore_reinitializes_the_ratchet_session() {
+    let pair = mutual_dm_pair().await;
+    negotiate_v2(&pair).await;
+    let before = pair
+        .app_a
+        .send_direct_message(pair.b_pubkey.as_str(), Some("before"), None, Vec::new())
+        .await
+        .expect("send before backup");
+    let DirectMessageFrame::V2(before_frame) =
+        queued_frame(&pair.store_a, &pair.blob_service, before.as_str()).await
+    else {
+        panic!("message before backup must be a v2 frame");
+    };
+    assert!(!before_frame.header.session_init);
+
+    let backup = pair
+        .app_a
+        .export_direct_message_backup()
+        .await
+        .expect("export dm backup");
+    assert_eq!(backup.sessions.len(), 1);
+    let session = &backup.sessions[0];
+    assert_eq!(session.peer_frame_version, DIRECT_MESSAGE_FRAME_VERSION_V2);
+    assert!(session.ratchet_state_json.is_none());
+    assert!(session.local_prekey_secret_hex.is_none());
+    assert!(session.peer_prekey_pubkey.is_none());
+
+    let store_restored = Arc::new(MemoryStore::default());
+    for edge in pair
+        .store_a
+        .list_follow_edges_by_subject(pair.a_pubkey.as_str())
+        .await
+        .expect("list follow edges by subject")
+        .into_iter()
+        .chain(
+            pair.store_a
+                .list_follow_edges_by_target(pair.a_pubkey.as_str())
+                .await
+                .expect("list follow edges by target"),
+        )
+    {
+        store_restored
+            .upsert_follow_edge(edge)
+            .await
+            .expect("seed follow edge");
+    }
+    let app_restored = app_service_from_dependencies(
+        store_restored.clone(),
+        store_restored.clone(),
+        Arc::new(StaticTransport::new(PeerSnapshot::default())),
+        Arc::new(NoopHintTransport),
+        Arc::new(MemoryDocsSync::default()),
+        pair.blob_service.clone(),
+        pair.app_a.services.keys.as_ref().clone(),
+    );
+    assert_eq!(
+        app_restored
+            .restore_direct_message_backup(backup)
+            .await
+            .expect("restore dm backup"),
+        1
+    );
+
+    let after = app_restored
+        .send_direct_message(pair.b_pubkey.as_str(), Some("after"), None, Vec::new())
+        .await
+        .expect("send after restore");
+    let DirectMessageFrame::V2(after_frame) =
+        queued_frame(&store_restored, &pair.blob_service, after.as_str()).await
+    else {
+        panic!("restored session keeps the negotiated v2 frame version");
+    };
+    assert!(after_frame.header.session_init);
+    assert_eq!(after_frame.header.message_number, 0);
+    assert_ne!(
+        after_frame.header.ratchet_pubkey,
+        before_frame.header.ratchet_pubkey
+    );
+    assert!(
+        deliver(
+            &store_restored,
+            &pair.app_b,
+            pair.a_pubkey.as_str(),
+            pair.b_pubkey.as_str(),
+            after.as_str(),
+        )
+        .await
+        .expect("deliver message after restore")
+    );
+    assert_eq!(
+        received_texts(&pair.app_b, pair.a_pubkey.as_str()).await,
+        vec!["hello", "after"]
+    );
+}
+
+#[tokio::test]
+async fn dm_v2_upgrade_is_negotiated_and_out_of_order_frames_decrypt() {
+    let pair = mutual_dm_pair().await;
+    negotiate_v2(&pair).await;
+
+    let mut message_ids = Vec::new();
+    for text in ["one", "two", "three"] {
+        let message_id = pair
+            .app_a
+            .send_direct_message(pair.b_pubkey.as_str(), Some(text), None, Vec::new())
+            .await
+            .expect("send ratcheted message");
+        assert!(matches!(
+            queued_frame(&pair.store_a, &pair.blob_service, message_id.as_str()).await,
+            DirectMessageFrame::V2(_)
+        ));
+        message_ids.push(message_id);
+        sleep(Duration::from_millis(2)).await;
+    }
+
+    for index in [2, 0, 1] {
+        assert!(
+            deliver(
+                &pair.store_a,
+                &pair.app_b,
+                pair.a_pubkey.as_str(),
+                pair.b_pubkey.as_str(),
+                message_ids[index].as_str(),
+            )
+            .await
+            .expect("deliver out-of-order frame")
+        );
+    }
+    assert_eq!(
+        received_texts(&pair.app_b, pair.a_pubkey.as_str()).await,
+        vec!["hello", "one", "two", "three"]
+    );
+    assert!(
+        !deliver(
+            &pair.store_a,
+            &pair.app_b,
+            pair.a_pubkey.as_str(),
+            pair.b_pubkey.as_str(),
+            message_ids[0].as_str(),
+        )
+        .await
+        .expect("redelivered frame is acknowledged without decrypting again")
+    );
+}
+
+#[tokio::test]
+async fn dm_v2_outbox_frames_decrypt_after_ratchet_advances() {
+    let pair = mutual_dm_pair().await;
+    negotiate_v2(&pair).await;
+
+    let queued_before_reply = pair
+        .app_a
+        .send_direct_message(
+            pair.b_pubkey.as_str(),
+            Some("queued before reply"),
+            None,
+            Vec::new(),
+        )
+        .await
+        .expect("queue message while peer is offline");
+    let queued_hash = queued_frame_hash(&pair.store_a, queued_before_reply.as_str()).await;
+
+    // 相手からの返信で送信側の DH ratchet が一段進んでも、outbox の frame は作り直さない。
+    let reply = pair
+        .app_b
+        .send_direct_message(
+            pair.a_pubkey.as_str(),
+            Some("are you there"),
+            None,
+            Vec::new(),
+        )
+        .await
+        .expect("send second reply");
+    assert!(
+        deliver(
+            &pair.store_b,
+            &pair.app_a,
+            pair.b_pubkey.as_str(),
+            pair.a_pubkey.as_str(),
+            reply.as_str(),
+        )
+        .await
+        .expect("deliver second reply")
+    );
+    sleep(Duration::from_millis(2)).await;
+    let queued_after_reply = pair
+        .app_a
+        .send_direct_message(
+            pair.b_pubkey.as_str(),
+            Some("queued after reply"),
+            None,
+            Vec::new(),
+        )
+        .await
+        .expect("queue message on the next ratchet chain");
+
+    AppService::flush_direct_message_outbox_for_peer(
+        &pair.app_a.services,
+        pair.a_pubkey.as_str(),
+        pair.b_pubkey.as_str(),
+    )
+    .await
+    .expect("flush outbox");
+    assert_eq!(
+        queued_frame_hash(&pair.store_a, queued_before_reply.as_str()).await,
+        queued_hash,
+        "outbox retry must republish the already sealed frame",
+    );
+
+    for message_id in [&queued_after_reply, &queued_before_reply] {
+        assert!(
+            deliver(
+                &pair.store_a,
+                &pair.app_b,
+                pair.a_pubkey.as_str(),
+                pair.b_pubkey.as_str(),
+                message_id.as_str(),
+            )
+            .await
+            .expect("deliver flushed outbox frame")
+        );
+    }
+    assert_eq!(
+        received_texts(&pair.app_b, pair.a_pubkey.as_str()).await,
+        vec!["hello", "queued before reply", "queued after reply"]
+    );
+    assert!(
+        pair.store_b
+            .get_direct_message_session(
+                direct_message_id_for_participants(
+                    &Pubkey::from(pair.a_pubkey.as_str()),
+                    &Pubkey::from(pair.b_pubkey.as_str()),
+                )
+                .as_str(),
+            )
+            .await
+            .expect("load session")
+            .is_some_and(|session| session.peer_frame_version == DIRECT_MESSAGE_FRAME_VERSION_V2)
+    );
+}
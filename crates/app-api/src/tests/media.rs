@@ -33,7 +33,8 @@ async fn create_post_with_image_attachment_surfaces_attachment_metadata() {
     assert_eq!(post.attachments.len(), 1);
     assert_eq!(post.attachments[0].mime, "image/png");
     assert_eq!(post.attachments[0].role, "image_original");
-    assert_eq!(post.attachments[0].status, BlobViewStatus::Available);
+    // 自分の投稿の添付は retention の対象外(pin)として配り続ける。
+    assert_eq!(post.attachments[0].status, BlobViewStatus::Pinned);
 }
 
 #[tokio::test]
@@ -983,3 +984,135 @@ async fn image_reply_thread_syncs() {
     assert_eq!(reply.attachments[0].mime, "image/jpeg");
     assert_eq!(reply.reply_to.as_deref(), Some(root_id.as_str()));
 }
+
+#[tokio::test]
+async fn deleted_post_blobs_are_unpinned_and_collected() {
+    let store = Arc::new(MemoryStore::default());
+    let transport = Arc::new(FakeTransport::new("app", FakeNetwork::default()));
+    let blob_service = Arc::new(kukuri_blob_service::RetainedBlobService::new(
+        Arc::new(MemoryBlobService::default()),
+        store.clone(),
+    ));
+    let app = AppService::from_handles(
+        ServiceHandles::new(
+            store.clone(),
+            store.clone(),
+            transport.clone(),
+            transport,
+            Arc::new(MemoryDocsSync::default()),
+            blob_service.clone(),
+            generate_keys(),
+        )
+        .with_blob_retention(kukuri_blob_service::BlobRetentionConfig {
+            unreferenced_grace: std::time::Duration::ZERO,
+            ..Default::default()
+        }),
+    );
+    let object_id = app
+        .create_post_with_attachments(
+            "kukuri:topic:retention",
+            "caption",
+            None,
+            vec![pending_image_attachment("image/png", b"retained-image")],
+        )
+        .await
+        .expect("create image post");
+
+    let usage = app.get_storage_usage().await.expect("usage after post");
+    assert_eq!(usage.pinned_blobs, 2);
+    assert_eq!(usage.cached_blobs, 0);
+    let collected = app.collect_blob_garbage().await.expect("gc while pinned");
+    assert_eq!(collected.evicted_blobs, 0);
+
+    app.delete_post("kukuri:topic:retention", object_id.as_str())
+        .await
+        .expect("delete post");
+    let usage = app.get_storage_usage().await.expect("usage after delete");
+    assert_eq!(usage.pinned_blobs, 0);
+    assert_eq!(usage.unreferenced_blobs, 2);
+
+    let collected = app.collect_blob_garbage().await.expect("gc after delete");
+    assert_eq!(collected.evicted_blobs, 2);
+    assert_eq!(
+        collected.usage,
+        StorageUsageView {
+            cache_quota_bytes: kukuri_blob_service::DEFAULT_BLOB_CACHE_QUOTA_BYTES,
+            ..StorageUsageView::default()
+        }
+    );
+}
+
+#[tokio::test]
+async fn edited_remote_post_keeps_revision_blobs_through_gc() {
+    let store = Arc::new(MemoryStore::default());
+    let transport = Arc::new(FakeTransport::new("app", FakeNetwork::default()));
+    let blob_service = Arc::new(kukuri_blob_service::RetainedBlobService::new(
+        Arc::new(MemoryBlobService::default()),
+        store.clone(),
+    ));
+    let app = AppService::from_handles(
+        ServiceHandles::new(
+            store.clone(),
+            store.clone(),
+            transport.clone(),
+            transport,
+            Arc::new(MemoryDocsSync::default()),
+            blob_service.clone(),
+            generate_keys(),
+        )
+        .with_blob_retention(kukuri_blob_service::BlobRetentionConfig {
+            unreferenced_grace: std::time::Duration::ZERO,
+            ..Default::default()
+        }),
+    );
+    // 他人の post を取得済みの cache として置き、本文を差し替える編集を projection に反映する。
+    let original = blob_service
+        .put_blob(b"original remote body".to_vec(), "text/plain")
+        .await
+        .expect("put original body");
+    let edited = blob_service
+        .put_blob(b"edited remote body".to_vec(), "text/plain")
+        .await
+        .expect("put edited body");
+    let projection = |body: &StoredBlob, status: ObjectStatus| ObjectProjectionRow {
+        object_id: EnvelopeId::from("remote-post"),
+        topic_id: "kukuri:topic:retention".to_string(),
+        channel_id: "public".to_string(),
+        author_pubkey: "f".repeat(64),
+        created_at: 1,
+        object_kind: "post".to_string(),
+        root_object_id: None,
+        reply_to_object_id: None,
+        payload_ref: PayloadRef::BlobText {
+            hash: body.hash.clone(),
+            mime: body.mime.clone(),
+            bytes: body.bytes,
+        },
+        content: None,
+        attachments: Vec::new(),
+        repost_of: None,
+        status,
+        source_replica_id: topic_replica_id("kukuri:topic:retention"),
+        source_key: "objects/remote-post/state".to_string(),
+        source_envelope_id: EnvelopeId::from("remote-post"),
+        source_blob_hash: None,
+        derived_at: 1,
+        projection_version: 2,
+    };
+    for row in [
+        projection(&original, ObjectStatus::Active),
+        projection(&edited, ObjectStatus::Edited),
+    ] {
+        ObjectProjectionStore::put_object_projection(store.as_ref(), row)
+            .await
+            .expect("put projection");
+    }
+
+    let collected = app.collect_blob_garbage().await.expect("gc after edit");
+    assert_eq!(
+        collected.evicted_blobs, 0,
+        "編集前の本文は revision 履歴が参照しているので手放さない"
+    );
+    assert_eq!(collected.usage.cached_blobs, 2);
+    assert_eq!(collected.usage.unreferenced_blobs, 0);
+}
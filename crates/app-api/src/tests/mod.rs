@@ -15,9 +15,9 @@ use kukuri_docs_sync::IrohDocsSync;
 #[cfg(feature = "iroh-integration-tests")]
 use kukuri_iroh_node::IrohDocsNode;
 use kukuri_store::{
-    BookmarkedCustomReactionRow, ContentObservationRow, ContentObservationStore,
-    DirectMessageStore, LiveGameProjectionStore, MemoryStore, ReactionBookmarkStore,
-    SocialProjectionStore, SqliteStore,
+    BookmarkedCustomReactionRow, CommunityModerationEventRow, CommunityModerationStore,
+    ContentObservationRow, ContentObservationStore, DirectMessageStore, LiveGameProjectionStore,
+    MemoryStore, ReactionBookmarkStore, ReplicaCursorStore, SocialProjectionStore, SqliteStore,
 };
 #[cfg(feature = "iroh-integration-tests")]
 use kukuri_transport::{DhtDiscoveryOptions, IrohGossipTransport, TransportRelayConfig};
@@ -33,10 +33,12 @@ use tokio::time::{Duration, sleep, timeout};
 use tokio_stream::wrappers::BroadcastStream;
 
 mod capability_registry_snapshot;
+mod devices;
 mod direct_messages;
 mod game;
 mod live;
 mod media;
+mod moderation;
 mod notifications;
 mod private_channels;
 mod reactions;
@@ -0,0 +1,151 @@
+use super::*;
+
+fn moderation_event_row(
+    node_base_url: &str,
+    event_id: &str,
+    target_kind: &str,
+    target_id: &str,
+    labels: &[&str],
+) -> CommunityModerationEventRow {
+    CommunityModerationEventRow {
+        node_base_url: node_base_url.to_string(),
+        event_id: event_id.to_string(),
+        issuer_node_id: "issuer".to_string(),
+        target_kind: target_kind.to_string(),
+        target_id: target_id.to_string(),
+        action: "risk_label".to_string(),
+        labels: labels.iter().map(|label| label.to_string()).collect(),
+        reason_code: "general_moderation".to_string(),
+        signed_event_json: "{}".to_string(),
+        received_at: 1,
+    }
+}
+
+#[tokio::test]
+async fn community_moderation_policy_controls_timeline_treatment() {
+    let (app, store, _docs_sync, _blob_service) = local_app_with_memory_services();
+    let topic = "kukuri:topic:moderation-policy";
+    let labeled_id = app
+        .create_post(topic, "labeled post", None)
+        .await
+        .expect("create labeled post");
+    let hidden_id = app
+        .create_post(topic, "hidden post", None)
+        .await
+        .expect("create hidden post");
+    for row in [
+        moderation_event_row(
+            "https://label.example",
+            "event-label",
+            "post",
+            labeled_id.as_str(),
+            &["spam"],
+        ),
+        moderation_event_row(
+            "https://ignored.example",
+            "event-ignored",
+            "post",
+            labeled_id.as_str(),
+            &["harassment"],
+        ),
+        moderation_event_row(
+            "https://hide.example",
+            "event-hide",
+            "post",
+            hidden_id.as_str(),
+            &["scam"],
+        ),
+    ] {
+        assert!(
+            store
+                .put_community_moderation_event(row)
+                .await
+                .expect("store moderation event")
+        );
+    }
+
+    // policy 未設定の node は label 扱いになり、hide 対象も一覧には残る。
+    let timeline = app
+        .list_timeline(topic, None, 20)
+        .await
+        .expect("list timeline");
+    let hidden = timeline
+        .items
+        .iter()
+        .find(|post| post.object_id == hidden_id)
+        .expect("hidden post before policy");
+    assert_eq!(
+        hidden
+            .moderation
+            .as_ref()
+            .map(|moderation| moderation.treatment),
+        Some(CommunityModerationPolicy::Label)
+    );
+
+    app.set_community_moderation_policy("https://hide.example", CommunityModerationPolicy::Hide)
+        .await
+        .expect("set hide policy");
+    app.set_community_moderation_policy(
+        "https://ignored.example",
+        CommunityModerationPolicy::Ignore,
+    )
+    .await
+    .expect("set ignore policy");
+
+    let timeline = app
+        .list_timeline(topic, None, 20)
+        .await
+        .expect("list timeline");
+    assert!(
+        timeline
+            .items
+            .iter()
+            .all(|post| post.object_id != hidden_id)
+    );
+    let labeled = timeline
+        .items
+        .iter()
+        .find(|post| post.object_id == labeled_id)
+        .expect("labeled post");
+    let moderation = labeled.moderation.as_ref().expect("labeled moderation");
+    assert_eq!(moderation.treatment, CommunityModerationPolicy::Label);
+    assert_eq!(moderation.labels, vec!["spam".to_string()]);
+    assert_eq!(moderation.sources.len(), 1);
+    assert_eq!(moderation.sources[0].node_base_url, "https://label.example");
+
+    app.set_community_moderation_policy("https://label.example", CommunityModerationPolicy::Blur)
+        .await
+        .expect("set blur policy");
+    let timeline = app
+        .list_timeline(topic, None, 20)
+        .await
+        .expect("list timeline");
+    let labeled = timeline
+        .items
+        .iter()
+        .find(|post| post.object_id == labeled_id)
+        .expect("blurred post");
+    assert_eq!(
+        labeled
+            .moderation
+            .as_ref()
+            .map(|moderation| moderation.treatment),
+        Some(CommunityModerationPolicy::Blur)
+    );
+
+    let policies = app
+        .list_community_moderation_policies()
+        .await
+        .expect("list policies");
+    assert_eq!(
+        policies
+            .iter()
+            .map(|policy| (policy.node_base_url.as_str(), policy.policy))
+            .collect::<Vec<_>>(),
+        vec![
+            ("https://hide.example", CommunityModerationPolicy::Hide),
+            ("https://ignored.example", CommunityModerationPolicy::Ignore),
+            ("https://label.example", CommunityModerationPolicy::Blur),
+        ]
+    );
+}
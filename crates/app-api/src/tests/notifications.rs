@@ -490,6 +490,10 @@ async fn incoming_dm_frame_creates_single_direct_message_notification_after_stor
             text: Some("hello from remote".into()),
             reply_to: None,
             attachment_manifest: None,
+            supported_frame_versions: Vec::new(),
+            attachment_key_hex: None,
+            group_key_update: None,
+            session_prekey: None,
         },
     )
     .expect("encrypt dm frame");
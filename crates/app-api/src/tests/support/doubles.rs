@@ -159,6 +159,10 @@ impl BlobService for AssistedBlobService {
         Ok(())
     }
 
+    async fn unpin_blob(&self, _hash: &kukuri_core::BlobHash) -> Result<()> {
+        Ok(())
+    }
+
     async fn blob_status(&self, _hash: &kukuri_core::BlobHash) -> Result<BlobStatus> {
         Ok(BlobStatus::Missing)
     }
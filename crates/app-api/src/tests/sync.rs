@@ -170,6 +170,10 @@ impl BlobService for DelayedBlobService {
         self.inner.pin_blob(hash).await
     }
 
+    async fn unpin_blob(&self, hash: &BlobHash) -> Result<()> {
+        self.inner.unpin_blob(hash).await
+    }
+
     async fn blob_status(&self, hash: &BlobHash) -> Result<BlobStatus> {
         if self
             .remaining_misses
@@ -284,6 +288,7 @@ fn app_with_hanging_remote_docs(
 mod diagnostics;
 mod gossip_toggle;
 mod hint_rehydration;
+mod projection_cursors;
 mod subscription_restarts;
 #[cfg(feature = "iroh-integration-tests")]
 mod transport_replication;
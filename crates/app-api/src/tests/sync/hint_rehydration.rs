@@ -496,3 +496,98 @@ async fn topic_session_hints_retry_until_manifest_blob_is_available() {
         "expected live session projection after retry"
     );
 }
+
+#[tokio::test]
+async fn thread_and_profile_hints_emit_content_events_with_ids() {
+    let docs_sync = Arc::new(kukuri_docs_sync::MemoryDocsSync::default());
+    let store = Arc::new(MemoryStore::default());
+    let transport = Arc::new(StaticTransport::new(PeerSnapshot::default()));
+    let services = ServiceHandles::new(
+        store.clone(),
+        store,
+        transport,
+        Arc::new(NoopHintTransport),
+        docs_sync.clone(),
+        Arc::new(MemoryBlobService::default()),
+        generate_keys(),
+    );
+    let mut events = services.content_events.subscribe();
+    let keys = generate_keys();
+    let topic = TopicId::new("kukuri:topic:content-events");
+    let replica = topic_replica_id(topic.as_str());
+    let root = persist_test_post(
+        docs_sync.as_ref(),
+        None,
+        &keys,
+        &topic,
+        PayloadRef::InlineText {
+            text: "root".into(),
+        },
+        Vec::new(),
+        None,
+    )
+    .await;
+    let reply = persist_test_post(
+        docs_sync.as_ref(),
+        None,
+        &keys,
+        &topic,
+        PayloadRef::InlineText {
+            text: "reply".into(),
+        },
+        Vec::new(),
+        Some(&root),
+    )
+    .await;
+
+    let hydrated = hydrate_subscription_hint(
+        &services,
+        topic.as_str(),
+        &replica,
+        &GossipHint::ThreadUpdated {
+            root_id: root.id.clone(),
+            object_ids: vec![root.id.clone(), reply.id.clone()],
+        },
+    )
+    .await
+    .expect("hydrate thread hint");
+    assert_eq!(hydrated, 2);
+    let profile_hydrated = hydrate_subscription_hint(
+        &services,
+        topic.as_str(),
+        &replica,
+        &GossipHint::ProfileUpdated {
+            author: keys.public_key(),
+        },
+    )
+    .await
+    .expect("hydrate profile hint");
+    assert_eq!(profile_hydrated, 0);
+
+    assert_eq!(
+        events.try_recv().expect("root event"),
+        ContentEvent::PostChanged {
+            topic_id: topic.as_str().to_string(),
+            channel_id: None,
+            object_id: root.id.as_str().to_string(),
+            author_pubkey: keys.public_key_hex(),
+        }
+    );
+    assert_eq!(
+        events.try_recv().expect("reply event"),
+        ContentEvent::ThreadReply {
+            topic_id: topic.as_str().to_string(),
+            channel_id: None,
+            root_object_id: root.id.as_str().to_string(),
+            object_id: reply.id.as_str().to_string(),
+            author_pubkey: keys.public_key_hex(),
+        }
+    );
+    assert_eq!(
+        events.try_recv().expect("profile event"),
+        ContentEvent::ProfileUpdated {
+            author_pubkey: keys.public_key_hex(),
+        }
+    );
+    assert!(events.try_recv().is_err());
+}
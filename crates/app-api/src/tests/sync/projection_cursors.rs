@@ -0,0 +1,68 @@
+use super::*;
+
+async fn projected_content(store: &MemoryStore, object_id: &str) -> Option<String> {
+    ObjectProjectionStore::get_object_projection(store, &EnvelopeId::from(object_id))
+        .await
+        .expect("projection lookup")
+        .expect("projection")
+        .content
+}
+
+#[tokio::test]
+async fn hydration_skips_unchanged_entries_until_topic_rebuild() {
+    let (app, store, _, _) = local_app_with_memory_services();
+    let topic = "kukuri:topic:projection-cursor";
+    let replica = topic_replica_id(topic);
+    let object_id = app
+        .create_post(topic, "original body", None)
+        .await
+        .expect("create post");
+
+    assert_eq!(
+        hydrate_subscription_state(&app.services, topic, &replica, DocFetchPolicy::LocalOnly)
+            .await
+            .expect("first hydrate"),
+        1
+    );
+    let cursor = store
+        .get_replica_cursor(&replica)
+        .await
+        .expect("cursor lookup")
+        .expect("cursor recorded");
+    assert_eq!(cursor.watermarks.len(), 1);
+
+    // projection だけを書き換え、cursor が進んだ entry は作り直されないことを確かめる。
+    let mut row = ObjectProjectionStore::get_object_projection(
+        store.as_ref(),
+        &EnvelopeId::from(object_id.clone()),
+    )
+    .await
+    .expect("projection lookup")
+    .expect("projection");
+    row.content = Some("stale body".into());
+    ObjectProjectionStore::put_object_projection(store.as_ref(), row)
+        .await
+        .expect("overwrite projection");
+    assert_eq!(
+        hydrate_subscription_state(&app.services, topic, &replica, DocFetchPolicy::LocalOnly)
+            .await
+            .expect("second hydrate"),
+        1,
+        "cursor で飛ばした entry も反映済みとして数える"
+    );
+    assert_eq!(
+        projected_content(store.as_ref(), object_id.as_str()).await,
+        Some("stale body".into())
+    );
+
+    assert_eq!(
+        app.rebuild_topic_projections(topic)
+            .await
+            .expect("rebuild topic projections"),
+        1
+    );
+    assert_eq!(
+        projected_content(store.as_ref(), object_id.as_str()).await,
+        Some("original body".into())
+    );
+}
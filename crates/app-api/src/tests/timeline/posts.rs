@@ -19,3 +19,190 @@ async fn create_post_and_list_timeline() {
     assert_eq!(timeline.items[0].object_id, object_id);
     assert_eq!(timeline.items[0].content, "hello app");
 }
+
+#[tokio::test]
+async fn local_search_follows_post_edits_and_deletes() {
+    let store = Arc::new(MemoryStore::default());
+    let transport = Arc::new(FakeTransport::new("app", FakeNetwork::default()));
+    let app = AppService::new(store, transport);
+
+    let object_id = app
+        .create_post("kukuri:topic:api", "searchable kukuri post", None)
+        .await
+        .expect("create post");
+    app.create_post("kukuri:topic:other", "kukuri elsewhere", None)
+        .await
+        .expect("create other topic post");
+    let search = |query: &str, topic_id: Option<&str>| LocalSearchInput {
+        query: query.to_string(),
+        topic_id: topic_id.map(str::to_string),
+        limit: 10,
+        ..LocalSearchInput::default()
+    };
+
+    let found = app
+        .search_local(search("KUKURI post", None))
+        .await
+        .expect("search before edit");
+    assert_eq!(
+        found
+            .posts
+            .iter()
+            .map(|post| post.object_id.as_str())
+            .collect::<Vec<_>>(),
+        vec![object_id.as_str()]
+    );
+    assert!(found.direct_messages.is_empty());
+    let scoped = app
+        .search_local(search("kukuri", Some("kukuri:topic:other")))
+        .await
+        .expect("topic scoped search");
+    assert_eq!(scoped.posts.len(), 1);
+    assert_eq!(scoped.posts[0].content, "kukuri elsewhere");
+
+    app.edit_post("kukuri:topic:api", object_id.as_str(), "rewritten body")
+        .await
+        .expect("edit post");
+    assert!(
+        app.search_local(search("searchable", None))
+            .await
+            .expect("search old text")
+            .posts
+            .is_empty()
+    );
+    let edited = app
+        .search_local(search("rewritten", None))
+        .await
+        .expect("search edited text");
+    assert_eq!(edited.posts.len(), 1);
+    assert_eq!(edited.posts[0].content, "rewritten body");
+
+    app.delete_post("kukuri:topic:api", object_id.as_str())
+        .await
+        .expect("delete post");
+    assert!(
+        app.search_local(search("rewritten", None))
+            .await
+            .expect("search deleted post")
+            .posts
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn local_search_fills_page_past_filtered_hits() {
+    let store = Arc::new(MemoryStore::default());
+    let transport = Arc::new(FakeTransport::new("app", FakeNetwork::default()));
+    let app = AppService::new(store.clone(), transport);
+
+    let mut object_ids = Vec::new();
+    for index in 0..3 {
+        object_ids.push(
+            app.create_post("kukuri:topic:api", &format!("kukuri visible {index}"), None)
+                .await
+                .expect("create post"),
+        );
+    }
+    // projection を持たない新しい文書が索引の先頭 limit 件を占める。
+    let newest = Utc::now().timestamp_millis() + 60_000;
+    store
+        .put_local_search_documents(
+            (0..3)
+                .map(|index| LocalSearchDocumentRow {
+                    subject_kind: "post".to_string(),
+                    subject_id: format!("stale-post-{index}"),
+                    topic_id: Some("kukuri:topic:api".to_string()),
+                    channel_id: Some("public".to_string()),
+                    dm_id: None,
+                    author_pubkey: "a".repeat(64),
+                    created_at: newest + index,
+                    body: "kukuri stale".to_string(),
+                })
+                .collect(),
+        )
+        .await
+        .expect("put stale documents");
+
+    let found = app
+        .search_local(LocalSearchInput {
+            query: "kukuri".to_string(),
+            limit: 2,
+            ..LocalSearchInput::default()
+        })
+        .await
+        .expect("search");
+    assert_eq!(found.posts.len(), 2, "除外された hit の分も続きから埋める");
+    assert!(found.posts.iter().all(|post| {
+        object_ids
+            .iter()
+            .any(|id| id.as_str() == post.object_id.as_str())
+    }));
+
+    let all = app
+        .search_local(LocalSearchInput {
+            query: "kukuri".to_string(),
+            limit: 10,
+            ..LocalSearchInput::default()
+        })
+        .await
+        .expect("search all");
+    assert_eq!(all.posts.len(), 3);
+}
+
+#[tokio::test]
+async fn edit_and_delete_post_keep_revision_history() {
+    let store = Arc::new(MemoryStore::default());
+    let transport = Arc::new(FakeTransport::new("app", FakeNetwork::default()));
+    let app = AppService::new(store, transport);
+
+    let object_id = app
+        .create_post("kukuri:topic:api", "helo app", None)
+        .await
+        .expect("create post");
+    let edit_id = app
+        .edit_post("kukuri:topic:api", object_id.as_str(), "hello app")
+        .await
+        .expect("edit post");
+    let timeline = app
+        .list_timeline("kukuri:topic:api", None, 10)
+        .await
+        .expect("timeline after edit");
+    assert_eq!(timeline.items.len(), 1);
+    assert_eq!(timeline.items[0].object_id, object_id);
+    assert_eq!(timeline.items[0].envelope_id, edit_id);
+    assert_eq!(timeline.items[0].content, "hello app");
+
+    let delete_id = app
+        .delete_post("kukuri:topic:api", object_id.as_str())
+        .await
+        .expect("delete post");
+    let timeline = app
+        .list_timeline("kukuri:topic:api", None, 10)
+        .await
+        .expect("timeline after delete");
+    assert!(timeline.items.is_empty());
+    assert!(
+        app.edit_post("kukuri:topic:api", object_id.as_str(), "again")
+            .await
+            .is_err()
+    );
+
+    let revisions = app
+        .list_post_revisions("kukuri:topic:api", object_id.as_str())
+        .await
+        .expect("post revisions");
+    assert_eq!(
+        revisions
+            .iter()
+            .map(|revision| (revision.envelope_id.as_str(), revision.status.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (object_id.as_str(), "active"),
+            (edit_id.as_str(), "edited"),
+            (delete_id.as_str(), "deleted"),
+        ]
+    );
+    assert_eq!(revisions[0].content.as_deref(), Some("helo app"));
+    assert_eq!(revisions[1].content.as_deref(), Some("hello app"));
+    assert_eq!(revisions[2].content, None);
+}
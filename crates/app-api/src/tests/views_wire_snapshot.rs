@@ -24,13 +24,14 @@ use kukuri_store::{NotificationKind, TimelineCursor};
 use kukuri_transport::{ConnectMode, ConnectionPath, DiscoveryMode};
 
 use crate::views::{
-    AttachmentView, AuthorSocialView, BlobViewStatus, BookmarkedPostView, ChannelAccessTokenExport,
-    ChannelAccessTokenKind, ChannelAccessTokenPreview, ContentObservationView,
-    ContentProvenanceView, CustomReactionAssetView, DeliveryState, DirectMessageConversationView,
-    DirectMessageMessageView, DirectMessageStatusView, DirectMessageTimelineView, DiscoveryStatus,
-    GameRoomView, GameScoreView, JoinedPrivateChannelView, NotificationView, PostView,
-    ProfileAssetView, ReactionKeyView, ReactionSummaryView, ReplyPreviewAuthorView,
-    ReplyPreviewView, RepostSourceView, SyncStatus, TimelineView, TopicSyncStatus,
+    AttachmentView, AuthorSocialView, BlobDownloadQueueStatus, BlobViewStatus, BookmarkedPostView,
+    ChannelAccessTokenExport, ChannelAccessTokenKind, ChannelAccessTokenPreview,
+    ContentObservationView, ContentProvenanceView, CustomReactionAssetView, DeliveryState,
+    DirectMessageConversationView, DirectMessageMessageView, DirectMessageStatusView,
+    DirectMessageTimelineView, DiscoveryStatus, GameRoomView, GameScoreView,
+    JoinedPrivateChannelView, NotificationView, PostView, ProfileAssetView, ReactionKeyView,
+    ReactionSummaryView, ReplyPreviewAuthorView, ReplyPreviewView, RepostSourceView, SyncStatus,
+    TimelineView, TopicSyncStatus,
 };
 
 const PUBKEY_A: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
@@ -136,6 +137,7 @@ fn post_view_full() -> PostView {
                 observed_at: 1_700_000_003_000,
             }],
         }),
+        moderation: None,
         content: "hello ipc contract".to_string(),
         content_status: BlobViewStatus::Available,
         attachments: vec![observed_attachment()],
@@ -208,6 +210,7 @@ fn post_view_minimal() -> PostView {
         mutual: false,
         friend_of_friend: false,
         provenance: None,
+        moderation: None,
         content: "minimal".to_string(),
         content_status: BlobViewStatus::Missing,
         attachments: vec![],
@@ -303,6 +306,7 @@ fn sync_status_full() -> SyncStatus {
             configured_seed_peer_ids: vec!["seed-1".to_string()],
             bootstrap_seed_peer_ids: vec!["seed-2".to_string()],
             manual_ticket_peer_ids: vec!["manual-1".to_string()],
+            lan_peer_ids: vec!["lan-1".to_string()],
             connected_peer_ids: vec!["peer-a".to_string()],
             docs_assist_peer_ids: vec!["peer-b".to_string()],
             blob_assist_peer_ids: vec![],
@@ -311,6 +315,11 @@ fn sync_status_full() -> SyncStatus {
         },
         gossip_disabled_topics: vec!["kukuri:topic:quiet".to_string()],
         gossip_disabled_channels: vec!["chan-quiet".to_string()],
+        blob_downloads: BlobDownloadQueueStatus {
+            queued: 3,
+            active: 1,
+            failed: 1,
+        },
     }
 }
 
@@ -340,6 +349,7 @@ fn views_wire_sync_status_minimal() {
             discovery: DiscoveryStatus::default(),
             gossip_disabled_topics: vec![],
             gossip_disabled_channels: vec![],
+            blob_downloads: BlobDownloadQueueStatus::default(),
         },
     );
 }
@@ -241,6 +241,16 @@ impl AppService {
             .projection_store
             .put_bookmarked_post(row.clone())
             .await?;
+        self.record_device_state(
+            stable_key(
+                DEVICE_STATE_BOOKMARKED_POSTS_PREFIX,
+                row.source_object_id.as_str(),
+            )
+            .as_str(),
+            &row,
+            false,
+        )
+        .await;
         self.bookmarked_post_view_from_row(row).await
     }
 
@@ -248,7 +258,14 @@ impl AppService {
         self.services
             .projection_store
             .remove_bookmarked_post(&EnvelopeId::from(source_object_id))
-            .await
+            .await?;
+        self.record_device_state(
+            stable_key(DEVICE_STATE_BOOKMARKED_POSTS_PREFIX, source_object_id).as_str(),
+            &source_object_id,
+            true,
+        )
+        .await;
+        Ok(())
     }
 
     pub async fn create_post(
@@ -433,6 +450,14 @@ impl AppService {
         let post_object = envelope
             .to_post_object()?
             .ok_or_else(|| anyhow::anyhow!("failed to parse post object for profile topic"))?;
+        let owned_blob_hashes = std::iter::once(stored_blob.hash.clone())
+            .chain(
+                stored_attachments
+                    .iter()
+                    .map(|(_, stored)| stored.hash.clone()),
+            )
+            .collect::<Vec<_>>();
+        self.pin_blobs(&owned_blob_hashes).await?;
         self.ingest_event(
             &write_replica,
             envelope.clone(),
@@ -441,30 +466,7 @@ impl AppService {
         )
         .await?;
         if effective_channel_id.is_none() {
-            let local_author_pubkey = self.current_author_pubkey();
-            let profile_post_envelope = build_profile_post_envelope(
-                self.services.keys.as_ref(),
-                &KukuriProfilePostEnvelopeContentV1 {
-                    author_pubkey: Pubkey::from(local_author_pubkey.as_str()),
-                    profile_topic_id: author_profile_topic_id(local_author_pubkey.as_str()),
-                    published_topic_id: topic.clone(),
-                    object_id: post_object.object_id.clone(),
-                    created_at: post_object.created_at,
-                    object_kind: post_object.object_kind.clone(),
-                    content: content.to_string(),
-                    attachments: post_object.attachments.clone(),
-                    reply_to_object_id: post_object.reply_to.clone(),
-                    root_id: post_object.root.clone(),
-                },
-            )?;
-            let profile_post = parse_profile_post(&profile_post_envelope)?
-                .ok_or_else(|| anyhow::anyhow!("failed to parse profile post envelope"))?;
-            persist_profile_post_doc(
-                self.services.docs_sync.as_ref(),
-                &profile_post,
-                &profile_post_envelope,
-            )
-            .await?;
+            self.persist_own_profile_post(&post_object, content).await?;
         }
         let hint_topic = channel_hint_topic_for(topic_id, effective_channel_id.as_ref());
         let hint = GossipHint::TopicObjectsChanged {
@@ -534,6 +536,242 @@ impl AppService {
         Ok(envelope.id.0)
     }
 
+    /// 自分の post / comment の本文を編集する。編集 revision の envelope id を返す。
+    pub async fn edit_post(
+        &self,
+        topic_id: &str,
+        object_id: &str,
+        content: &str,
+    ) -> Result<String> {
+        ensure_text_within_limit("post content", content, MAX_POST_CONTENT_CHARS)?;
+        let (projection, current) = self
+            .own_post_object_for_revision(topic_id, object_id)
+            .await?;
+        let stored_blob = self
+            .services
+            .blob_service
+            .put_blob(content.as_bytes().to_vec(), "text/plain")
+            .await?;
+        let revision = build_post_edit_envelope(
+            self.services.keys.as_ref(),
+            &current,
+            PayloadRef::BlobText {
+                hash: stored_blob.hash.clone(),
+                mime: stored_blob.mime.clone(),
+                bytes: stored_blob.bytes,
+            },
+            current.attachments.clone(),
+            current.media_manifest_refs.clone(),
+        )?;
+        let next = apply_post_revision(&current, &revision)?;
+        // 編集前の版も履歴として配り続けるため、旧本文の pin は取り消し時まで残す。
+        self.pin_blobs([&stored_blob.hash]).await?;
+        BlobCacheStore::mark_blob_status(
+            self.services.projection_store.as_ref(),
+            &stored_blob.hash,
+            BlobCacheStatus::Available,
+        )
+        .await?;
+        self.publish_post_revision(&projection, &next, &revision, Some(content.to_string()))
+            .await?;
+        if next.channel_id.is_none() {
+            self.persist_own_profile_post(&next, content).await?;
+        }
+        Ok(revision.id.0)
+    }
+
+    /// 自分の post / comment を取り消す。tombstone revision の envelope id を返す。
+    pub async fn delete_post(&self, topic_id: &str, object_id: &str) -> Result<String> {
+        let (projection, current) = self
+            .own_post_object_for_revision(topic_id, object_id)
+            .await?;
+        let revision = build_post_tombstone_envelope(self.services.keys.as_ref(), &current)?;
+        let next = apply_post_revision(&current, &revision)?;
+        self.publish_post_revision(&projection, &next, &revision, None)
+            .await?;
+        self.unpin_post_blobs(&projection).await?;
+        if next.channel_id.is_none() {
+            remove_profile_post_doc(
+                self.services.docs_sync.as_ref(),
+                self.current_author_pubkey().as_str(),
+                &next.object_id,
+            )
+            .await?;
+        }
+        Ok(revision.id.0)
+    }
+
+    /// post の版履歴(元投稿 + 編集 / 取り消し revision)を古い順に返す。
+    pub async fn list_post_revisions(
+        &self,
+        topic_id: &str,
+        object_id: &str,
+    ) -> Result<Vec<PostRevisionView>> {
+        self.ensure_topic_subscription(topic_id).await?;
+        let object_id = EnvelopeId::from(object_id);
+        let projection = self
+            .services
+            .projection_store
+            .get_object_projection(&object_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("post was not found"))?;
+        if projection.topic_id != topic_id {
+            anyhow::bail!("post topic does not match");
+        }
+        let history = load_post_history_envelopes(
+            self.services.docs_sync.as_ref(),
+            &projection.source_replica_id,
+            &object_id,
+        )
+        .await?;
+        let mut items = Vec::with_capacity(history.len());
+        for envelope in history {
+            let (status, payload_ref) = if let Some(revision) = envelope.post_revision_content()? {
+                (revision.status, revision.payload_ref)
+            } else {
+                let content = envelope
+                    .post_content()?
+                    .ok_or_else(|| anyhow::anyhow!("post history envelope is not a post"))?;
+                (ObjectStatus::Active, Some(content.payload_ref))
+            };
+            let (content, content_status) = match payload_ref.as_ref() {
+                Some(PayloadRef::InlineText { text }) => {
+                    (Some(text.clone()), BlobViewStatus::Available)
+                }
+                Some(payload_ref @ PayloadRef::BlobText { hash, .. }) => (
+                    fetch_projection_blob_text(self.services.blob_service.as_ref(), hash).await,
+                    blob_view_status_for_payload(self.services.blob_service.as_ref(), payload_ref)
+                        .await?,
+                ),
+                None => (None, BlobViewStatus::Available),
+            };
+            items.push(PostRevisionView {
+                object_id: object_id.as_str().to_string(),
+                envelope_id: envelope.id.as_str().to_string(),
+                status: match status {
+                    ObjectStatus::Active => "active",
+                    ObjectStatus::Edited => "edited",
+                    ObjectStatus::Deleted => "deleted",
+                    ObjectStatus::Tombstoned => "tombstoned",
+                }
+                .to_string(),
+                content,
+                content_status,
+                created_at: envelope.created_at,
+            });
+        }
+        Ok(items)
+    }
+
+    async fn own_post_object_for_revision(
+        &self,
+        topic_id: &str,
+        object_id: &str,
+    ) -> Result<(ObjectProjectionRow, CanonicalPostHeader)> {
+        self.ensure_topic_subscription(topic_id).await?;
+        let object_id = EnvelopeId::from(object_id);
+        let projection = self
+            .services
+            .projection_store
+            .get_object_projection(&object_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("post was not found"))?;
+        if projection.topic_id != topic_id {
+            anyhow::bail!("post topic does not match");
+        }
+        if projection.author_pubkey != self.current_author_pubkey() {
+            anyhow::bail!("only the author can edit or delete a post");
+        }
+        let current = fetch_post_object_for_projection(
+            self.services.docs_sync.as_ref(),
+            &projection.source_replica_id,
+            projection.source_key.as_str(),
+        )
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("post object state was not found"))?;
+        Ok((projection, current))
+    }
+
+    async fn publish_post_revision(
+        &self,
+        projection: &ObjectProjectionRow,
+        next: &CanonicalPostHeader,
+        revision: &KukuriEnvelope,
+        content: Option<String>,
+    ) -> Result<()> {
+        persist_post_revision(
+            self.services.docs_sync.as_ref(),
+            &projection.source_replica_id,
+            next,
+            revision,
+        )
+        .await?;
+        self.services.store.put_envelope(revision.clone()).await?;
+        let row = projection_row_from_header(next, content, &projection.source_replica_id);
+        index_object_projections_for_search(
+            self.services.projection_store.as_ref(),
+            std::slice::from_ref(&row),
+        )
+        .await?;
+        ObjectProjectionStore::put_object_projection(self.services.projection_store.as_ref(), row)
+            .await?;
+        if let Err(error) = self
+            .services
+            .hint_transport
+            .publish_hint(
+                &channel_hint_topic_for(next.topic_id.as_str(), next.channel_id.as_ref()),
+                GossipHint::TopicObjectsChanged {
+                    topic_id: next.topic_id.clone(),
+                    objects: vec![HintObjectRef {
+                        object_id: next.object_id.as_str().to_string(),
+                        object_kind: next.object_kind.clone(),
+                    }],
+                },
+            )
+            .await
+        {
+            warn!(
+                topic = %next.topic_id.as_str(),
+                object_id = %next.object_id.as_str(),
+                error = %error,
+                "failed to publish post revision hint; durable docs state was already persisted"
+            );
+        }
+        *self.last_sync_ts.lock().await = Some(Utc::now().timestamp_millis());
+        Ok(())
+    }
+
+    async fn persist_own_profile_post(
+        &self,
+        post_object: &CanonicalPostHeader,
+        content: &str,
+    ) -> Result<()> {
+        let local_author_pubkey = self.current_author_pubkey();
+        let profile_post_envelope = build_profile_post_envelope(
+            self.services.keys.as_ref(),
+            &KukuriProfilePostEnvelopeContentV1 {
+                author_pubkey: Pubkey::from(local_author_pubkey.as_str()),
+                profile_topic_id: author_profile_topic_id(local_author_pubkey.as_str()),
+                published_topic_id: post_object.topic_id.clone(),
+                object_id: post_object.object_id.clone(),
+                created_at: post_object.created_at,
+                object_kind: post_object.object_kind.clone(),
+                content: content.to_string(),
+                attachments: post_object.attachments.clone(),
+                reply_to_object_id: post_object.reply_to.clone(),
+                root_id: post_object.root.clone(),
+            },
+        )?;
+        let profile_post = parse_profile_post(&profile_post_envelope)?
+            .ok_or_else(|| anyhow::anyhow!("failed to parse profile post envelope"))?;
+        persist_profile_post_doc(
+            self.services.docs_sync.as_ref(),
+            &profile_post,
+            &profile_post_envelope,
+        )
+        .await
+    }
+
     pub async fn list_timeline(
         &self,
         topic_id: &str,
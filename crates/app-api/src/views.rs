@@ -1,9 +1,12 @@
 use kukuri_core::{
-    AssetRole, ChannelAudienceKind, ChannelSharingState, GameRoomKind, GameRoomStatus,
+    AssetRole, ChannelAudienceKind, ChannelRef, ChannelSharingState, GameRoomKind, GameRoomStatus,
     KukuriEnvelope, LiveSessionStatus, MetaverseAssetKind, MetaverseAssetRef,
     MetaverseRoomEventEnvelopeContentV1, MetaverseRoomEventV1, MetaverseRoomStateV1,
 };
-use kukuri_store::{NotificationKind, TimelineCursor};
+use kukuri_store::{
+    CommunityModerationPolicy, DirectMessageConversationRow, DirectMessageSessionRow,
+    GroupDirectMessageConversationRow, NotificationKind, TimelineCursor,
+};
 use kukuri_transport::{ConnectMode, ConnectionPath, DiscoveryMode};
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +25,35 @@ pub struct ContentProvenanceView {
     pub observed_via: Vec<ContentObservationView>,
 }
 
+/// 投稿に効いている moderation event 1 件(発行 node と内容)。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ContentModerationSourceView {
+    pub node_base_url: String,
+    pub event_id: String,
+    pub target_kind: String,
+    pub action: String,
+    pub reason_code: String,
+    pub labels: Vec<String>,
+}
+
+/// node ごとの端末内 policy を適用した結果。`treatment` は効いている最も強い扱い。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ContentModerationView {
+    pub treatment: CommunityModerationPolicy,
+    pub labels: Vec<String>,
+    pub sources: Vec<ContentModerationSourceView>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct CommunityModerationPolicyView {
+    pub node_base_url: String,
+    pub policy: CommunityModerationPolicy,
+    pub updated_at: Option<i64>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
@@ -39,6 +71,8 @@ pub struct PostView {
     pub friend_of_friend: bool,
     #[serde(default)]
     pub provenance: Option<ContentProvenanceView>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moderation: Option<ContentModerationView>,
     pub content: String,
     pub content_status: BlobViewStatus,
     pub attachments: Vec<AttachmentView>,
@@ -120,6 +154,19 @@ pub struct ReactionStateView {
     pub my_reactions: Vec<ReactionKeyView>,
 }
 
+/// post の版履歴 1 件。最初の要素は元投稿、以降は編集 / 取り消し revision(古い順)。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
+pub struct PostRevisionView {
+    pub object_id: String,
+    pub envelope_id: String,
+    pub status: String,
+    pub content: Option<String>,
+    pub content_status: BlobViewStatus,
+    pub created_at: i64,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
@@ -329,6 +376,39 @@ pub struct DirectMessageConversationView {
     pub status: DirectMessageStatusView,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
+pub struct GroupDirectMessageConversationView {
+    pub group_id: String,
+    pub owner_pubkey: String,
+    pub title: Option<String>,
+    pub epoch: u64,
+    pub member_pubkeys: Vec<String>,
+    pub is_owner: bool,
+    /// owner にメンバーから外された会話。履歴は読めるが送受信はしない。
+    pub removed: bool,
+    pub updated_at: i64,
+    pub last_message_at: Option<i64>,
+    pub last_message_id: Option<String>,
+    pub last_message_preview: Option<String>,
+    pub pending_outbox_count: usize,
+}
+
+/// identity backup に含める DM の端末内 state(会話一覧と交渉済みの frame version)。
+///
+/// メッセージ本文・ratchet state・session prekey・グループ鍵は含めない。復元先では
+/// session-init からやり直し、以後の送受信だけを引き継ぐ。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectMessageBackupV1 {
+    #[serde(default)]
+    pub conversations: Vec<DirectMessageConversationRow>,
+    #[serde(default)]
+    pub sessions: Vec<DirectMessageSessionRow>,
+    #[serde(default)]
+    pub group_conversations: Vec<GroupDirectMessageConversationRow>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
@@ -406,6 +486,19 @@ pub struct GameScoreView {
     pub score: i64,
 }
 
+/// 端末内全文検索の入力。日付は unix millis(`created_after` は含む、`created_before` は含まない)。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LocalSearchInput {
+    pub query: String,
+    pub topic_id: Option<String>,
+    pub channel_ref: Option<ChannelRef>,
+    pub author_pubkey: Option<String>,
+    pub created_after: Option<i64>,
+    pub created_before: Option<i64>,
+    pub include_direct_messages: bool,
+    pub limit: usize,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CreateLiveSessionInput {
     pub title: String,
@@ -480,6 +573,50 @@ pub struct TimelineView {
     pub next_cursor: Option<TimelineCursor>,
 }
 
+/// 端末内全文検索の結果。いずれも新しい順。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
+pub struct LocalSearchView {
+    pub posts: Vec<PostView>,
+    pub direct_messages: Vec<DirectMessageMessageView>,
+}
+
+/// 同じ author 鍵で署名された device 登録。`is_current_device` はこの端末自身。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
+pub struct LinkedDeviceView {
+    pub device_id: String,
+    pub device_label: String,
+    pub authorized_by_device_id: Option<String>,
+    pub authorized_at: i64,
+    pub is_current_device: bool,
+}
+
+/// 新 device へ渡す pairing 情報。ticket と pairing code は別経路で渡す想定。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
+pub struct DevicePairingView {
+    pub device_id: String,
+    pub device_label: String,
+    pub ticket: String,
+    pub pairing_code: String,
+    pub expires_at: i64,
+}
+
+/// identity backup を新しい profile へ取り込んだ結果。DM state は次回起動時に反映される。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
+pub struct IdentityBackupImportView {
+    pub author_pubkey: String,
+    pub created_at: i64,
+    pub private_channel_count: usize,
+    pub direct_message_count: usize,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
@@ -597,6 +734,36 @@ pub struct SyncStatus {
     pub discovery: DiscoveryStatus,
     pub gossip_disabled_topics: Vec<String>,
     pub gossip_disabled_channels: Vec<String>,
+    pub blob_downloads: BlobDownloadQueueStatus,
+}
+
+/// 未取得 blob の download queue の件数。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct BlobDownloadQueueStatus {
+    pub queued: usize,
+    pub active: usize,
+    pub failed: usize,
+}
+
+/// ローカル blob の容量。pin 済み(自分が配るもの)と cache(quota で手放しうるもの)に分ける。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct StorageUsageView {
+    pub pinned_blobs: usize,
+    pub pinned_bytes: u64,
+    pub cached_blobs: usize,
+    pub cached_bytes: u64,
+    pub unreferenced_blobs: usize,
+    pub cache_quota_bytes: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct BlobGarbageCollectionView {
+    pub evicted_blobs: usize,
+    pub evicted_bytes: u64,
+    pub usage: StorageUsageView,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -621,6 +788,7 @@ pub struct DiscoveryStatus {
     pub configured_seed_peer_ids: Vec<String>,
     pub bootstrap_seed_peer_ids: Vec<String>,
     pub manual_ticket_peer_ids: Vec<String>,
+    pub lan_peer_ids: Vec<String>,
     pub connected_peer_ids: Vec<String>,
     pub docs_assist_peer_ids: Vec<String>,
     pub blob_assist_peer_ids: Vec<String>,
@@ -648,3 +816,52 @@ pub struct TopicSyncStatus {
     pub status_detail: String,
     pub last_error: Option<String>,
 }
+
+/// 購読経路で projection に反映された変更 1 件。
+///
+/// UI は該当 id の範囲だけを取り直す(一覧全体の polling をしない)。`channel_id` は
+/// public なら `None`。DM は `peer_pubkey` + `message_id` で会話単位に取り直す。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ContentEvent {
+    PostChanged {
+        topic_id: String,
+        channel_id: Option<String>,
+        object_id: String,
+        author_pubkey: String,
+    },
+    ThreadReply {
+        topic_id: String,
+        channel_id: Option<String>,
+        root_object_id: String,
+        object_id: String,
+        author_pubkey: String,
+    },
+    ReactionChanged {
+        topic_id: String,
+        target_object_id: String,
+    },
+    DirectMessageReceived {
+        peer_pubkey: String,
+        dm_id: String,
+        message_id: String,
+    },
+    DirectMessageAcked {
+        peer_pubkey: String,
+        dm_id: String,
+        message_id: String,
+    },
+    LiveSessionChanged {
+        topic_id: String,
+        session_id: String,
+    },
+    GameRoomChanged {
+        topic_id: String,
+        room_id: String,
+    },
+    ProfileUpdated {
+        author_pubkey: String,
+    },
+}
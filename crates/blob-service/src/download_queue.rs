@@ -0,0 +1,311 @@
+//! 未取得 blob の永続 download queue を捌く scheduler。
+//!
+//! queue 本体は kukuri-store の `download_jobs`(`DownloadJobStore`)。hydration が見つけた
+//! 未取得の本文・添付・manifest を積み、ここで priority 順・同時転送数の上限つきで
+//! `BlobService::fetch_blob` に流す。失敗は指数 backoff で再試行し、上限に達した job は
+//! `Failed` で止める(`DownloadJobStore::retry_download_job` で再開)。iroh-blobs は途中まで取れた range を store に
+//! 残すため、再試行は大きい動画でも続きから取りに行く。
+//! 起動時に前回 `Active` のまま残った job を `Queued` に戻すので、再起動をまたいで続く。
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use kukuri_store::{BlobCacheStatus, BlobCacheStore, DownloadJobRow, DownloadJobStore};
+use tokio::task::{JoinHandle, JoinSet};
+use tracing::{debug, info, warn};
+
+use crate::BlobService;
+
+/// hydration が見つけた未取得 blob の既定 priority。
+pub const DOWNLOAD_PRIORITY_BACKGROUND: i64 = 0;
+/// 画面に出ている item の blob。background より先に取る。
+pub const DOWNLOAD_PRIORITY_VISIBLE: i64 = 100;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlobDownloadSchedulerConfig {
+    /// 同時に走らせる転送数の上限。
+    pub max_concurrent: usize,
+    /// この回数失敗した job は `Failed` にして止める。
+    pub max_attempts: u32,
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+    /// 空き枠があるときに queue を見に行く間隔。
+    pub poll_interval: Duration,
+}
+
+impl Default for BlobDownloadSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 2,
+            max_attempts: 10,
+            base_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(10 * 60),
+            poll_interval: Duration::from_secs(2),
+        }
+    }
+}
+
+impl BlobDownloadSchedulerConfig {
+    /// `attempts` 回目の失敗後の待ち時間。`None` なら再試行しない。
+    pub fn retry_delay(&self, attempts: u32) -> Option<Duration> {
+        if attempts >= self.max_attempts {
+            return None;
+        }
+        let factor = 1u32
+            .checked_shl(attempts.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        Some(
+            self.base_backoff
+                .saturating_mul(factor)
+                .min(self.max_backoff),
+        )
+    }
+}
+
+pub struct BlobDownloadScheduler<S: ?Sized> {
+    store: Arc<S>,
+    blobs: Arc<dyn BlobService>,
+    config: BlobDownloadSchedulerConfig,
+}
+
+impl<S> BlobDownloadScheduler<S>
+where
+    S: DownloadJobStore + BlobCacheStore + ?Sized + 'static,
+{
+    pub fn new(
+        store: Arc<S>,
+        blobs: Arc<dyn BlobService>,
+        config: BlobDownloadSchedulerConfig,
+    ) -> Self {
+        Self {
+            store,
+            blobs,
+            config,
+        }
+    }
+
+    /// queue を捌く task を起動する。abort で止めてよい(転送途中の job は次回起動で戻る)。
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            if let Err(error) = self.run().await {
+                warn!(error = %error, "blob download scheduler stopped");
+            }
+        })
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let requeued = self
+            .store
+            .requeue_active_download_jobs(now_millis())
+            .await?;
+        if requeued > 0 {
+            info!(requeued, "resumed interrupted blob downloads");
+        }
+        let max_concurrent = self.config.max_concurrent.max(1);
+        let mut in_flight = JoinSet::new();
+        loop {
+            let free = max_concurrent.saturating_sub(in_flight.len());
+            if free > 0 {
+                for job in self.store.claim_download_jobs(now_millis(), free).await? {
+                    let store = Arc::clone(&self.store);
+                    let blobs = Arc::clone(&self.blobs);
+                    let config = self.config.clone();
+                    in_flight.spawn(async move {
+                        if let Err(error) =
+                            run_download_job(store.as_ref(), blobs.as_ref(), &config, job).await
+                        {
+                            warn!(error = %error, "failed to record blob download result");
+                        }
+                    });
+                }
+            }
+            if in_flight.is_empty() {
+                tokio::time::sleep(self.config.poll_interval).await;
+                continue;
+            }
+            tokio::select! {
+                _ = in_flight.join_next() => {}
+                _ = tokio::time::sleep(self.config.poll_interval) => {}
+            }
+        }
+    }
+
+    /// 期限の来た job を 1 巡だけ処理する(同時数の上限つき)。処理した件数を返す。
+    pub async fn run_due_jobs(&self) -> Result<usize> {
+        let jobs = self
+            .store
+            .claim_download_jobs(now_millis(), self.config.max_concurrent.max(1))
+            .await?;
+        let processed = jobs.len();
+        let mut in_flight = JoinSet::new();
+        for job in jobs {
+            let store = Arc::clone(&self.store);
+            let blobs = Arc::clone(&self.blobs);
+            let config = self.config.clone();
+            in_flight.spawn(async move {
+                run_download_job(store.as_ref(), blobs.as_ref(), &config, job).await
+            });
+        }
+        while let Some(result) = in_flight.join_next().await {
+            result??;
+        }
+        Ok(processed)
+    }
+}
+
+async fn run_download_job<S>(
+    store: &S,
+    blobs: &dyn BlobService,
+    config: &BlobDownloadSchedulerConfig,
+    job: DownloadJobRow,
+) -> Result<()>
+where
+    S: DownloadJobStore + BlobCacheStore + ?Sized,
+{
+    store
+        .mark_blob_status(&job.blob_hash, BlobCacheStatus::Downloading)
+        .await?;
+    let error = match blobs.fetch_blob(&job.blob_hash).await {
+        Ok(Some(_)) => {
+            debug!(hash = %job.blob_hash.as_str(), kind = ?job.kind, "blob download completed");
+            store.complete_download_job(&job.blob_hash).await?;
+            store
+                .mark_blob_status(&job.blob_hash, BlobCacheStatus::Available)
+                .await?;
+            return Ok(());
+        }
+        Ok(None) => "blob is not available from known peers".to_string(),
+        Err(error) => error.to_string(),
+    };
+    let attempts = job.attempts.saturating_add(1);
+    let now = now_millis();
+    // 打ち切った job も max_backoff 後までは再開させない(表示中の再要求で叩き続けないため)。
+    let (delay, exhausted) = match config.retry_delay(attempts) {
+        Some(delay) => (delay, false),
+        None => (config.max_backoff, true),
+    };
+    let next_attempt_at = now.saturating_add(i64::try_from(delay.as_millis()).unwrap_or(i64::MAX));
+    store
+        .fail_download_job(
+            &job.blob_hash,
+            error.as_str(),
+            next_attempt_at,
+            exhausted,
+            now,
+        )
+        .await?;
+    if !exhausted {
+        return store
+            .mark_blob_status(&job.blob_hash, BlobCacheStatus::Missing)
+            .await;
+    }
+    warn!(
+        hash = %job.blob_hash.as_str(),
+        kind = ?job.kind,
+        attempts,
+        error = %error,
+        "blob download gave up after max attempts"
+    );
+    store
+        .mark_blob_status(&job.blob_hash, BlobCacheStatus::Failed)
+        .await
+}
+
+pub(crate) fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| i64::try_from(duration.as_millis()).unwrap_or(i64::MAX))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kukuri_core::BlobHash;
+    use kukuri_store::{DownloadJobKind, DownloadJobStatus, MemoryStore};
+
+    use crate::MemoryBlobService;
+
+    #[test]
+    fn retry_delay_backs_off_exponentially_and_stops_at_max_attempts() {
+        let config = BlobDownloadSchedulerConfig {
+            max_attempts: 4,
+            base_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(12),
+            ..BlobDownloadSchedulerConfig::default()
+        };
+        assert_eq!(config.retry_delay(1), Some(Duration::from_secs(5)));
+        assert_eq!(config.retry_delay(2), Some(Duration::from_secs(10)));
+        assert_eq!(config.retry_delay(3), Some(Duration::from_secs(12)));
+        assert_eq!(config.retry_delay(4), None);
+    }
+
+    #[tokio::test]
+    async fn scheduler_completes_available_blobs_and_backs_off_missing_ones() {
+        let store = Arc::new(MemoryStore::default());
+        let blobs = Arc::new(MemoryBlobService::default());
+        let stored = blobs
+            .put_blob(b"attachment".to_vec(), "image/png")
+            .await
+            .expect("put blob");
+        let missing = BlobHash::new("f".repeat(64));
+        for hash in [&stored.hash, &missing] {
+            store
+                .enqueue_download_job(
+                    hash,
+                    DownloadJobKind::Attachment,
+                    DOWNLOAD_PRIORITY_BACKGROUND,
+                    0,
+                )
+                .await
+                .expect("enqueue");
+        }
+        let scheduler = BlobDownloadScheduler::new(
+            Arc::clone(&store),
+            blobs,
+            BlobDownloadSchedulerConfig {
+                max_concurrent: 1,
+                max_attempts: 2,
+                ..BlobDownloadSchedulerConfig::default()
+            },
+        );
+
+        assert_eq!(scheduler.run_due_jobs().await.expect("first pass"), 1);
+        assert_eq!(scheduler.run_due_jobs().await.expect("second pass"), 1);
+        assert!(
+            store
+                .get_download_job(&stored.hash)
+                .await
+                .expect("get job")
+                .is_none()
+        );
+        let retrying = store
+            .get_download_job(&missing)
+            .await
+            .expect("get job")
+            .expect("missing job stays queued");
+        assert_eq!(retrying.status, DownloadJobStatus::Queued);
+        assert_eq!(retrying.attempts, 1);
+        assert!(retrying.next_attempt_at > now_millis());
+        assert_eq!(
+            scheduler.run_due_jobs().await.expect("backoff pass"),
+            0,
+            "backoff 中は claim しない"
+        );
+
+        // 期限を過ぎた扱いにして 2 回目の失敗で止まることを確認する。
+        store
+            .fail_download_job(&missing, "forced", 0, false, 0)
+            .await
+            .expect("expire backoff");
+        assert_eq!(scheduler.run_due_jobs().await.expect("final pass"), 1);
+        let failed = store
+            .get_download_job(&missing)
+            .await
+            .expect("get job")
+            .expect("failed job is kept");
+        assert_eq!(failed.status, DownloadJobStatus::Failed);
+    }
+}
@@ -10,6 +10,12 @@ use kukuri_transport::{PeerAddrBook, RemoteFetchRetryState, SeedPeer, parse_endp
 use serde::{Deserialize, Serialize};
 use tokio::sync::{Mutex, RwLock};
 
+mod download_queue;
+mod retention;
+
+pub use download_queue::*;
+pub use retention::*;
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StoredBlob {
     pub hash: BlobHash,
@@ -36,6 +42,13 @@ pub trait BlobService: Send + Sync {
         self.fetch_blob(hash).await
     }
     async fn pin_blob(&self, hash: &BlobHash) -> Result<()>;
+    async fn unpin_blob(&self, hash: &BlobHash) -> Result<()>;
+    /// ローカルのコピーを手放す(retention の eviction)。pin 中の blob は手放さず `false`。
+    ///
+    /// 既定実装は何もしない(恒久保存の概念が無い実装向け)。
+    async fn evict_blob(&self, _hash: &BlobHash) -> Result<bool> {
+        Ok(false)
+    }
     async fn blob_status(&self, hash: &BlobHash) -> Result<BlobStatus>;
     async fn import_peer_ticket(&self, ticket: &str) -> Result<()>;
     async fn learn_peer(&self, _endpoint_id: &str) -> Result<()> {
@@ -113,6 +126,11 @@ impl IrohBlobService {
         Ok(())
     }
 
+    /// アドレスが既に分かっているピア(mDNS の LAN 発見など)を learned 台帳へ入れる。
+    pub async fn learn_peer_addr(&self, endpoint_addr: iroh::EndpointAddr) {
+        let _ = self.peers.insert_learned_peer_addr(endpoint_addr).await;
+    }
+
     async fn available_fetch_peer_ids(&self) -> Vec<String> {
         self.peers.available_peer_ids().await
     }
@@ -152,6 +170,18 @@ impl BlobService for MemoryBlobService {
         Ok(())
     }
 
+    async fn unpin_blob(&self, hash: &BlobHash) -> Result<()> {
+        self.pinned.write().await.remove(hash.as_str());
+        Ok(())
+    }
+
+    async fn evict_blob(&self, hash: &BlobHash) -> Result<bool> {
+        if self.pinned.read().await.contains(hash.as_str()) {
+            return Ok(false);
+        }
+        Ok(self.blobs.write().await.remove(hash.as_str()).is_some())
+    }
+
     async fn blob_status(&self, hash: &BlobHash) -> Result<BlobStatus> {
         if self.pinned.read().await.contains(hash.as_str()) {
             return Ok(BlobStatus::Pinned);
@@ -172,6 +202,7 @@ impl BlobService for IrohBlobService {
     async fn put_blob(&self, data: Vec<u8>, mime: &str) -> Result<StoredBlob> {
         let byte_len = data.len() as u64;
         let temp_tag = self.node.blobs().blobs().add_bytes(data).await?;
+        self.node.retain_blob(&temp_tag.hash);
         Ok(StoredBlob {
             hash: BlobHash::new(temp_tag.hash.to_string()),
             mime: mime.to_string(),
@@ -182,6 +213,8 @@ impl BlobService for IrohBlobService {
     async fn fetch_blob(&self, hash: &BlobHash) -> Result<Option<Vec<u8>>> {
         let hash_text = hash.as_str().to_string();
         let hash = iroh_blobs::Hash::from_str(hash.as_str())?;
+        // 使われた blob は evict 予約から外す(GC 前なら手元のコピーがそのまま残る)。
+        self.node.retain_blob(&hash);
         match self.node.blobs().blobs().get_bytes(hash).await {
             Ok(bytes) => Ok(Some(bytes.to_vec())),
             Err(error) => {
@@ -222,10 +255,30 @@ impl BlobService for IrohBlobService {
     }
 
     async fn pin_blob(&self, hash: &BlobHash) -> Result<()> {
+        self.node
+            .retain_blob(&iroh_blobs::Hash::from_str(hash.as_str())?);
         self.pinned.write().await.insert(hash.as_str().to_string());
         Ok(())
     }
 
+    async fn unpin_blob(&self, hash: &BlobHash) -> Result<()> {
+        self.pinned.write().await.remove(hash.as_str());
+        Ok(())
+    }
+
+    /// 実際の削除は iroh-blobs の GC が行う(`IrohDocsNode::evict_blob`)。
+    async fn evict_blob(&self, hash: &BlobHash) -> Result<bool> {
+        if self.pinned.read().await.contains(hash.as_str()) {
+            return Ok(false);
+        }
+        let hash = iroh_blobs::Hash::from_str(hash.as_str())?;
+        if !self.node.blobs().blobs().has(hash).await? {
+            return Ok(false);
+        }
+        self.node.evict_blob(hash);
+        Ok(true)
+    }
+
     async fn blob_status(&self, hash: &BlobHash) -> Result<BlobStatus> {
         if self.pinned.read().await.contains(hash.as_str()) {
             return Ok(BlobStatus::Pinned);
@@ -0,0 +1,431 @@
+//! ローカル blob の retention(容量の記録・pin・eviction)。
+//!
+//! 容量と最終参照時刻は kukuri-store の `blob_objects`(`BlobRetentionStore`)に持つ。
+//! `RetainedBlobService` が put / fetch / pin を記録し、`collect_blob_garbage` が
+//! projection からの参照数と LRU で手放す blob を決める:
+//! - pin 済み(自分の投稿・profile 画像・カスタムリアクション等)は unpin されるまで残す。
+//! - どこからも参照されない cache は猶予期間を過ぎたら手放す。
+//! - 参照されている cache は合計が quota を超えた分だけ、最終参照の古い順に手放す。
+//!
+//! 手放した blob は `Missing` に戻り、再び表示されれば download queue が取り直す。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use kukuri_core::BlobHash;
+use kukuri_store::{BlobRetentionRow, BlobRetentionStore};
+use kukuri_transport::SeedPeer;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::download_queue::now_millis;
+use crate::{BlobService, BlobStatus, StoredBlob};
+
+/// pin されていない blob に使ってよい容量の既定値(2 GiB)。
+pub const DEFAULT_BLOB_CACHE_QUOTA_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlobRetentionConfig {
+    /// pin されていない blob(他人の添付・preview・manifest 等)の合計容量の上限。
+    pub cache_quota_bytes: u64,
+    /// 参照の無い blob をこの期間は残す(put 直後でまだ projection が無い blob を消さないため)。
+    pub unreferenced_grace: Duration,
+    /// 定期 GC の間隔。
+    pub interval: Duration,
+}
+
+impl Default for BlobRetentionConfig {
+    fn default() -> Self {
+        Self {
+            cache_quota_bytes: DEFAULT_BLOB_CACHE_QUOTA_BYTES,
+            unreferenced_grace: Duration::from_secs(60 * 60),
+            interval: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlobRetentionReport {
+    pub evicted_blobs: usize,
+    pub evicted_bytes: u64,
+}
+
+/// ローカル blob の容量の内訳。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlobStorageUsage {
+    pub pinned_blobs: usize,
+    pub pinned_bytes: u64,
+    pub cached_blobs: usize,
+    pub cached_bytes: u64,
+    /// cache のうち、どの projection からも参照されていないもの(次の GC の候補)。
+    pub unreferenced_blobs: usize,
+}
+
+pub async fn summarize_blob_storage<S>(store: &S) -> Result<BlobStorageUsage>
+where
+    S: BlobRetentionStore + ?Sized,
+{
+    let references = store.blob_reference_counts().await?;
+    let mut usage = BlobStorageUsage::default();
+    for row in store.list_local_blobs().await? {
+        if row.pinned {
+            usage.pinned_blobs += 1;
+            usage.pinned_bytes = usage.pinned_bytes.saturating_add(row.bytes);
+            continue;
+        }
+        usage.cached_blobs += 1;
+        usage.cached_bytes = usage.cached_bytes.saturating_add(row.bytes);
+        if !is_referenced(&references, &row) {
+            usage.unreferenced_blobs += 1;
+        }
+    }
+    Ok(usage)
+}
+
+/// pin されていない blob を retention 規則に従って手放す。`now` は ms。
+pub async fn collect_blob_garbage<S>(
+    store: &S,
+    blobs: &dyn BlobService,
+    config: &BlobRetentionConfig,
+    now: i64,
+) -> Result<BlobRetentionReport>
+where
+    S: BlobRetentionStore + ?Sized,
+{
+    let references = store.blob_reference_counts().await?;
+    let grace_ms = i64::try_from(config.unreferenced_grace.as_millis()).unwrap_or(i64::MAX);
+    let mut report = BlobRetentionReport::default();
+    let mut cached = Vec::new();
+    let mut cached_bytes = 0u64;
+    // list_local_blobs は最終参照の古い順。
+    for row in store.list_local_blobs().await? {
+        if row.pinned {
+            continue;
+        }
+        if !is_referenced(&references, &row) && row.last_accessed_at.saturating_add(grace_ms) <= now
+        {
+            evict(store, blobs, &row, &mut report).await?;
+            continue;
+        }
+        cached_bytes = cached_bytes.saturating_add(row.bytes);
+        cached.push(row);
+    }
+    for row in cached {
+        if cached_bytes <= config.cache_quota_bytes {
+            break;
+        }
+        if evict(store, blobs, &row, &mut report).await? {
+            cached_bytes = cached_bytes.saturating_sub(row.bytes);
+        }
+    }
+    Ok(report)
+}
+
+fn is_referenced(references: &HashMap<String, u64>, row: &BlobRetentionRow) -> bool {
+    references
+        .get(row.blob_hash.as_str())
+        .is_some_and(|count| *count > 0)
+}
+
+async fn evict<S>(
+    store: &S,
+    blobs: &dyn BlobService,
+    row: &BlobRetentionRow,
+    report: &mut BlobRetentionReport,
+) -> Result<bool>
+where
+    S: BlobRetentionStore + ?Sized,
+{
+    // BlobService 側で pin 中 / 手元に無いものは記録を変えない。
+    if !blobs.evict_blob(&row.blob_hash).await? {
+        return Ok(false);
+    }
+    store.mark_blob_evicted(&row.blob_hash).await?;
+    report.evicted_blobs += 1;
+    report.evicted_bytes = report.evicted_bytes.saturating_add(row.bytes);
+    Ok(true)
+}
+
+/// `BlobService` の put / fetch / pin を `BlobRetentionStore` へ記録する wrapper。
+///
+/// 記録の失敗は warn に留め、blob の読み書き自体は止めない。
+pub struct RetainedBlobService<S: ?Sized> {
+    inner: Arc<dyn BlobService>,
+    store: Arc<S>,
+}
+
+impl<S> RetainedBlobService<S>
+where
+    S: BlobRetentionStore + ?Sized + 'static,
+{
+    pub fn new(inner: Arc<dyn BlobService>, store: Arc<S>) -> Self {
+        Self { inner, store }
+    }
+
+    async fn record_access(&self, hash: &BlobHash, bytes: u64) {
+        if let Err(error) = self
+            .store
+            .record_blob_access(hash, Some(bytes), now_millis())
+            .await
+        {
+            warn!(hash = %hash.as_str(), error = %error, "failed to record blob access");
+        }
+    }
+}
+
+#[async_trait]
+impl<S> BlobService for RetainedBlobService<S>
+where
+    S: BlobRetentionStore + ?Sized + 'static,
+{
+    async fn put_blob(&self, data: Vec<u8>, mime: &str) -> Result<StoredBlob> {
+        let stored = self.inner.put_blob(data, mime).await?;
+        self.record_access(&stored.hash, stored.bytes).await;
+        Ok(stored)
+    }
+
+    async fn fetch_blob(&self, hash: &BlobHash) -> Result<Option<Vec<u8>>> {
+        let bytes = self.inner.fetch_blob(hash).await?;
+        if let Some(bytes) = bytes.as_ref() {
+            self.record_access(hash, bytes.len() as u64).await;
+        }
+        Ok(bytes)
+    }
+
+    async fn fetch_blob_ephemeral(&self, hash: &BlobHash) -> Result<Option<Vec<u8>>> {
+        self.inner.fetch_blob_ephemeral(hash).await
+    }
+
+    async fn pin_blob(&self, hash: &BlobHash) -> Result<()> {
+        self.inner.pin_blob(hash).await?;
+        self.store.set_blob_pinned(hash, true).await
+    }
+
+    async fn unpin_blob(&self, hash: &BlobHash) -> Result<()> {
+        self.inner.unpin_blob(hash).await?;
+        self.store.set_blob_pinned(hash, false).await
+    }
+
+    async fn evict_blob(&self, hash: &BlobHash) -> Result<bool> {
+        self.inner.evict_blob(hash).await
+    }
+
+    async fn blob_status(&self, hash: &BlobHash) -> Result<BlobStatus> {
+        self.inner.blob_status(hash).await
+    }
+
+    async fn import_peer_ticket(&self, ticket: &str) -> Result<()> {
+        self.inner.import_peer_ticket(ticket).await
+    }
+
+    async fn learn_peer(&self, endpoint_id: &str) -> Result<()> {
+        self.inner.learn_peer(endpoint_id).await
+    }
+
+    async fn set_seed_peers(&self, peers: Vec<SeedPeer>) -> Result<()> {
+        self.inner.set_seed_peers(peers).await
+    }
+
+    async fn assist_peer_ids(&self) -> Result<Vec<String>> {
+        self.inner.assist_peer_ids().await
+    }
+}
+
+/// 定期的に `collect_blob_garbage` を回す task。
+pub struct BlobRetentionScheduler<S: ?Sized> {
+    store: Arc<S>,
+    blobs: Arc<dyn BlobService>,
+    config: BlobRetentionConfig,
+}
+
+impl<S> BlobRetentionScheduler<S>
+where
+    S: BlobRetentionStore + ?Sized + 'static,
+{
+    pub fn new(store: Arc<S>, blobs: Arc<dyn BlobService>, config: BlobRetentionConfig) -> Self {
+        Self {
+            store,
+            blobs,
+            config,
+        }
+    }
+
+    /// abort で止めてよい(eviction は 1 件ずつ記録するので途中で止めても整合する)。
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(self.config.interval).await;
+                match collect_blob_garbage(
+                    self.store.as_ref(),
+                    self.blobs.as_ref(),
+                    &self.config,
+                    now_millis(),
+                )
+                .await
+                {
+                    Ok(report) if report.evicted_blobs > 0 => {
+                        info!(
+                            evicted_blobs = report.evicted_blobs,
+                            evicted_bytes = report.evicted_bytes,
+                            "released cached blobs"
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(error) => warn!(error = %error, "blob retention pass failed"),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    use kukuri_store::MemoryStore;
+
+    use crate::MemoryBlobService;
+
+    /// 参照数だけ差し替える。projection を組み立てずに参照ありの blob を作るため。
+    struct ReferencingStore {
+        inner: Arc<MemoryStore>,
+        referenced: HashSet<String>,
+    }
+
+    #[async_trait]
+    impl BlobRetentionStore for ReferencingStore {
+        async fn record_blob_access(
+            &self,
+            hash: &BlobHash,
+            bytes: Option<u64>,
+            now: i64,
+        ) -> Result<()> {
+            self.inner.record_blob_access(hash, bytes, now).await
+        }
+
+        async fn set_blob_pinned(&self, hash: &BlobHash, pinned: bool) -> Result<()> {
+            self.inner.set_blob_pinned(hash, pinned).await
+        }
+
+        async fn list_local_blobs(&self) -> Result<Vec<BlobRetentionRow>> {
+            self.inner.list_local_blobs().await
+        }
+
+        async fn blob_reference_counts(&self) -> Result<HashMap<String, u64>> {
+            Ok(self
+                .referenced
+                .iter()
+                .map(|hash| (hash.clone(), 1))
+                .collect())
+        }
+
+        async fn mark_blob_evicted(&self, hash: &BlobHash) -> Result<()> {
+            self.inner.mark_blob_evicted(hash).await
+        }
+    }
+
+    #[tokio::test]
+    async fn collect_blob_garbage_keeps_pinned_and_evicts_unreferenced_then_lru() {
+        let memory = Arc::new(MemoryStore::default());
+        let blobs =
+            RetainedBlobService::new(Arc::new(MemoryBlobService::default()), Arc::clone(&memory));
+        let own = blobs
+            .put_blob(vec![0; 40], "image/png")
+            .await
+            .expect("put own");
+        blobs.pin_blob(&own.hash).await.expect("pin own");
+        let unreferenced = blobs
+            .put_blob(vec![1; 30], "image/png")
+            .await
+            .expect("put unreferenced");
+        let older = blobs
+            .put_blob(vec![2; 20], "image/png")
+            .await
+            .expect("put older");
+        let newer = blobs
+            .put_blob(vec![3; 21], "image/png")
+            .await
+            .expect("put newer");
+        let now = now_millis();
+        memory
+            .record_blob_access(&newer.hash, None, now + 1)
+            .await
+            .expect("touch newer");
+        let store = ReferencingStore {
+            inner: Arc::clone(&memory),
+            referenced: [older.hash.as_str(), newer.hash.as_str()]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        };
+
+        let usage = summarize_blob_storage(&store).await.expect("usage");
+        assert_eq!(usage.pinned_blobs, 1);
+        assert_eq!(usage.pinned_bytes, 40);
+        assert_eq!(usage.cached_blobs, 3);
+        assert_eq!(usage.cached_bytes, 71);
+        assert_eq!(usage.unreferenced_blobs, 1);
+
+        // 猶予期間内は参照の無い blob も残す。
+        let config = BlobRetentionConfig {
+            cache_quota_bytes: 1_000,
+            unreferenced_grace: Duration::from_secs(60),
+            ..BlobRetentionConfig::default()
+        };
+        let report = collect_blob_garbage(&store, &blobs, &config, now)
+            .await
+            .expect("gc within grace");
+        assert_eq!(report, BlobRetentionReport::default());
+
+        let report = collect_blob_garbage(&store, &blobs, &config, now + 120_000)
+            .await
+            .expect("gc after grace");
+        assert_eq!(
+            report,
+            BlobRetentionReport {
+                evicted_blobs: 1,
+                evicted_bytes: 30,
+            }
+        );
+        assert!(
+            blobs
+                .fetch_blob(&unreferenced.hash)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // quota 超過分は参照があっても最終参照の古い順に手放す。pin 済みは対象外。
+        let config = BlobRetentionConfig {
+            cache_quota_bytes: 25,
+            ..config
+        };
+        let report = collect_blob_garbage(&store, &blobs, &config, now)
+            .await
+            .expect("gc over quota");
+        assert_eq!(report.evicted_blobs, 1);
+        assert_eq!(report.evicted_bytes, 20);
+        let local = memory
+            .list_local_blobs()
+            .await
+            .expect("local blobs")
+            .into_iter()
+            .map(|row| row.blob_hash)
+            .collect::<HashSet<_>>();
+        assert_eq!(local, HashSet::from([own.hash.clone(), newer.hash.clone()]));
+
+        // unpin した blob は通常の cache として数える。
+        blobs.unpin_blob(&own.hash).await.expect("unpin");
+        let usage = summarize_blob_storage(&store)
+            .await
+            .expect("usage after unpin");
+        assert_eq!(usage.pinned_blobs, 0);
+        assert_eq!(usage.cached_blobs, 2);
+        assert_eq!(usage.unreferenced_blobs, 1);
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use sqlx::PgPool;
+
+use kukuri_cn_core::{
+    AdminOperation, apply_operator_action, initialize_database, list_admin_operators,
+    validate_admin_operation,
+};
+use kukuri_cn_protocol::build_admin_login_envelope_json;
+use kukuri_core::KukuriKeys;
+
+use crate::{AdminAction, OperatorAction};
+
+/// CLI からの運営操作を操作記録に残すときの actor。
+pub(super) const CLI_ACTOR: &str = "cn-cli";
+
+pub(crate) fn sign_login(base_url: &str, challenge: &str, secret_key_file: &Path) -> Result<()> {
+    let secret = std::fs::read_to_string(secret_key_file)
+        .with_context(|| format!("failed to read {}", secret_key_file.display()))?;
+    let keys = KukuriKeys::parse(secret.trim()).context("invalid operator secret key")?;
+    let challenge = challenge.trim();
+    if challenge.is_empty() {
+        bail!("challenge is required");
+    }
+    let envelope = build_admin_login_envelope_json(&keys, challenge, base_url)?;
+    println!("{}", serde_json::to_string(&envelope)?);
+    Ok(())
+}
+
+pub(super) async fn run(pool: &PgPool, action: AdminAction) -> Result<()> {
+    initialize_database(pool).await?;
+    match action {
+        AdminAction::Operator { action } => run_operator(pool, action).await,
+        AdminAction::SignLogin {
+            base_url,
+            challenge,
+            secret_key_file,
+        } => sign_login(&base_url, &challenge, &secret_key_file),
+    }
+}
+
+async fn run_operator(pool: &PgPool, action: OperatorAction) -> Result<()> {
+    match action {
+        OperatorAction::Add {
+            pubkey,
+            role,
+            label,
+        } => {
+            let operation = AdminOperation::SetOperatorRole {
+                pubkey,
+                role: Some(role.into()),
+                label: label.filter(|value| !value.trim().is_empty()),
+            };
+            validate_admin_operation(&operation)?;
+            let action = apply_operator_action(pool, CLI_ACTOR, &operation).await?;
+            println!(
+                "operator updated: {}  {} -> {}  audit={}",
+                action.target_id, action.before, action.after, action.id
+            );
+        }
+        OperatorAction::Remove { pubkey } => {
+            let operation = AdminOperation::SetOperatorRole {
+                pubkey,
+                role: None,
+                label: None,
+            };
+            validate_admin_operation(&operation)?;
+            let action = apply_operator_action(pool, CLI_ACTOR, &operation).await?;
+            println!(
+                "operator removed: {}  audit={}",
+                action.target_id, action.id
+            );
+        }
+        OperatorAction::List => {
+            let operators = list_admin_operators(pool).await?;
+            if operators.is_empty() {
+                println!("no operators");
+            } else {
+                println!("{} operator(s):", operators.len());
+                for operator in operators {
+                    println!(
+                        "{}  role={}  label={}  updated_at={}",
+                        operator.pubkey,
+                        operator.role.as_str(),
+                        operator.label.as_deref().unwrap_or("-"),
+                        operator.updated_at.to_rfc3339(),
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -4,12 +4,18 @@ use sqlx::PgPool;
 
 use kukuri_cn_cli::{format_timestamp, parse_enforce_at};
 use kukuri_cn_core::{
-    AdmissionMode, add_allowlist, ban_subscriber, initialize_database, issue_invite_code,
-    list_allowlist, list_banned, list_invite_codes, load_admission_config, remove_allowlist,
-    revoke_invite_code, set_admission_mode, unban_subscriber,
+    AdminOperation, AdmissionMode, VouchPolicy, add_allowlist, apply_operator_action,
+    ban_subscriber, initialize_database, issue_invite_code, list_admission_vouches, list_allowlist,
+    list_auth_sessions, list_banned, list_invite_codes, list_subscriber_sanctions,
+    load_admission_config, remove_allowlist, revoke_invite_code, revoke_subscriber_sessions,
+    set_admission_mode, set_vouch_policy, unban_subscriber, validate_admin_operation,
 };
 
-use crate::{AdmissionAction, AllowAction, BanAction, InviteAction};
+use super::admin::CLI_ACTOR;
+use crate::{
+    AdmissionAction, AllowAction, BanAction, InviteAction, SanctionAction, SessionAction,
+    VouchAction,
+};
 
 pub(super) async fn run(pool: &PgPool, action: AdmissionAction) -> Result<()> {
     initialize_database(pool).await?;
@@ -17,6 +23,10 @@ pub(super) async fn run(pool: &PgPool, action: AdmissionAction) -> Result<()> {
         AdmissionAction::Show => {
             let config = load_admission_config(pool).await?;
             println!("admission mode: {}", config.mode.as_str());
+            println!(
+                "vouch policy: required={}  proximity_weighting={}",
+                config.vouch.required_vouches, config.vouch.proximity_weighting
+            );
         }
         AdmissionAction::SetMode { mode } => {
             let mode = AdmissionMode::from(mode);
@@ -26,6 +36,9 @@ pub(super) async fn run(pool: &PgPool, action: AdmissionAction) -> Result<()> {
         AdmissionAction::Invite { action } => run_invite(pool, action).await?,
         AdmissionAction::Allow { action } => run_allow(pool, action).await?,
         AdmissionAction::Ban { action } => run_ban(pool, action).await?,
+        AdmissionAction::Sanction { action } => run_sanction(pool, action).await?,
+        AdmissionAction::Vouch { action } => run_vouch(pool, action).await?,
+        AdmissionAction::Sessions { action } => run_sessions(pool, action).await?,
     }
     Ok(())
 }
@@ -155,3 +168,155 @@ async fn run_ban(pool: &PgPool, action: BanAction) -> Result<()> {
     }
     Ok(())
 }
+
+async fn run_sanction(pool: &PgPool, action: SanctionAction) -> Result<()> {
+    match action {
+        SanctionAction::Add {
+            pubkey,
+            kind,
+            reason,
+            expires_at,
+            report_ids,
+        } => {
+            let expires_at = expires_at
+                .map(|value| parse_enforce_at(value.as_str()))
+                .transpose()?
+                .map(|timestamp| {
+                    DateTime::<Utc>::from_timestamp(timestamp, 0)
+                        .context("invalid expires_at timestamp")
+                })
+                .transpose()?;
+            let operation = AdminOperation::ImposeSanction {
+                pubkey,
+                kind: kind.into(),
+                reason,
+                expires_at,
+                report_ids,
+            };
+            validate_admin_operation(&operation)?;
+            let action = apply_operator_action(pool, CLI_ACTOR, &operation).await?;
+            println!(
+                "sanction imposed: {}  {}  audit={}",
+                action.target_id, action.after, action.id
+            );
+        }
+        SanctionAction::Lift { id } => {
+            let operation = AdminOperation::LiftSanction { sanction_id: id };
+            validate_admin_operation(&operation)?;
+            let action = apply_operator_action(pool, CLI_ACTOR, &operation).await?;
+            println!(
+                "sanction lifted: {}  {}  audit={}",
+                action.target_id, action.after, action.id
+            );
+        }
+        SanctionAction::List { pubkey, all } => {
+            let sanctions = list_subscriber_sanctions(pool, pubkey.as_deref(), all).await?;
+            if sanctions.is_empty() {
+                println!("no sanctions");
+            } else {
+                println!("{} sanction(s):", sanctions.len());
+                for sanction in sanctions {
+                    let expires_at = sanction
+                        .expires_at
+                        .map(format_timestamp)
+                        .unwrap_or_else(|| "until lifted".to_string());
+                    let lifted_at = sanction
+                        .lifted_at
+                        .map(format_timestamp)
+                        .unwrap_or_else(|| "-".to_string());
+                    let report_ids = if sanction.report_ids.is_empty() {
+                        "-".to_string()
+                    } else {
+                        sanction.report_ids.join(",")
+                    };
+                    println!(
+                        "{}  {}  kind={}  imposed={} by {}  expires={}  lifted={}  reports={}  reason={}",
+                        sanction.id,
+                        sanction.pubkey,
+                        sanction.kind.as_str(),
+                        format_timestamp(sanction.imposed_at),
+                        sanction.imposed_by,
+                        expires_at,
+                        lifted_at,
+                        report_ids,
+                        sanction.reason.replace('\n', " "),
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_vouch(pool: &PgPool, action: VouchAction) -> Result<()> {
+    match action {
+        VouchAction::Policy {
+            required,
+            proximity_weighting,
+        } => {
+            set_vouch_policy(
+                pool,
+                VouchPolicy {
+                    required_vouches: required,
+                    proximity_weighting,
+                },
+            )
+            .await?;
+            println!(
+                "vouch policy updated: required={required}  proximity_weighting={proximity_weighting}"
+            );
+        }
+        VouchAction::List { candidate } => {
+            let vouches = list_admission_vouches(pool, candidate.as_deref()).await?;
+            if vouches.is_empty() {
+                println!("no vouches");
+            } else {
+                println!("{} vouch(es):", vouches.len());
+                for vouch in vouches {
+                    println!(
+                        "{}  voucher={}  weight={:.2}  created={}",
+                        vouch.candidate_pubkey,
+                        vouch.voucher_pubkey,
+                        vouch.weight,
+                        format_timestamp(vouch.created_at),
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_sessions(pool: &PgPool, action: SessionAction) -> Result<()> {
+    match action {
+        SessionAction::List { pubkey } => {
+            let sessions = list_auth_sessions(pool, pubkey.as_str()).await?;
+            if sessions.is_empty() {
+                println!("no auth sessions");
+            } else {
+                println!("{} auth session(s):", sessions.len());
+                for session in sessions {
+                    let state = match (session.revoked_at, session.revoked_reason.as_deref()) {
+                        (Some(revoked_at), reason) => format!(
+                            "revoked={} ({})",
+                            format_timestamp(revoked_at),
+                            reason.unwrap_or("-")
+                        ),
+                        (None, _) => format!("expires={}", format_timestamp(session.expires_at)),
+                    };
+                    println!(
+                        "{}  endpoint={}  created={}  {state}",
+                        session.session_id,
+                        session.endpoint_id.as_deref().unwrap_or("-"),
+                        format_timestamp(session.created_at),
+                    );
+                }
+            }
+        }
+        SessionAction::Revoke { pubkey } => {
+            let revoked = revoke_subscriber_sessions(pool, pubkey.as_str(), "operator").await?;
+            println!("revoked {revoked} auth session(s) for {pubkey}");
+        }
+    }
+    Ok(())
+}
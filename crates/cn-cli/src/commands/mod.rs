@@ -3,6 +3,7 @@ use sqlx::PgPool;
 
 use crate::Command;
 
+pub(crate) mod admin;
 mod admission;
 mod database;
 mod indexing;
@@ -40,6 +41,7 @@ pub(crate) async fn dispatch(pool: &PgPool, command: Command) -> Result<()> {
         Command::IndexingRequest { action } => indexing::run_indexing_request(pool, action).await,
         Command::Relation { action } => relation::run(pool, action).await,
         Command::Moderation { action } => moderation::run(pool, action).await,
+        Command::Admin { action } => admin::run(pool, action).await,
         Command::Readiness {
             config,
             profile,
@@ -1,6 +1,6 @@
 //! 実行時 readiness の収集と判定（#616 T2）。
 //!
-//! cn-indexer の状態エンドポイント・Postgres の整合検査・ArcadeDB 投影・関係解析の
+//! cn-indexer の状態エンドポイント・Postgres の整合検査・index 投影（ArcadeDB / embedded）・関係解析の
 //! 実行記録を集め、`RUNTIME_CHECK_IDS` のうち走査網羅系の判定項目を合格/不合格へ
 //! 確定させる。判定は純関数（`evaluate`）に分離し、収集の失敗はすべて不合格へ倒す
 //! （安全側）。出力へ秘匿情報を含めない。
@@ -12,7 +12,7 @@ use kukuri_cn_core::{
     IndexIntegrityFindings, RelationAnalyzeRun, ensure_database_ready, inspect_index_integrity,
     latest_relation_analyze_run,
 };
-use kukuri_cn_indexer::{ArcadeDbConfig, ArcadeDbProjection, IndexerStateSnapshot};
+use kukuri_cn_indexer::{IndexBackendConfig, IndexerStateSnapshot, count_index_projection};
 use kukuri_cn_operator::{ReadinessCheck, ReadinessStatus};
 
 /// 収集した実行時情報。取得に失敗した要素は Err(要約) を保持し、判定側で不合格へ倒す。
@@ -41,9 +41,8 @@ pub(crate) async fn collect(
     let integrity = inspect_index_integrity(pool)
         .await
         .map_err(|error| format!("{error:#}"));
-    let projection_count = match ArcadeDbProjection::new(ArcadeDbConfig::from_env()) {
-        Ok(projection) => projection
-            .count_all()
+    let projection_count = match IndexBackendConfig::from_env() {
+        Ok(backend) => count_index_projection(&backend)
             .await
             .map_err(|error| format!("{error:#}")),
         Err(error) => Err(format!("{error:#}")),
@@ -1,11 +1,11 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::Utc;
 use sqlx::PgPool;
 
 use kukuri_cn_core::{
     PgCoParticipationSource, RelationAnalyzeRun, initialize_database, record_relation_analyze_run,
 };
-use kukuri_cn_indexer::{ArcadeDbConfig, ArcadeDbRelationGraph, analyze_relations};
+use kukuri_cn_indexer::{IndexBackendConfig, analyze_relations, prepare_relation_store};
 
 use crate::RelationAction;
 
@@ -52,11 +52,6 @@ pub(super) async fn run(pool: &PgPool, action: RelationAction) -> Result<()> {
 
 async fn analyze(pool: &PgPool, limit: usize) -> Result<kukuri_cn_indexer::RelationAnalysisReport> {
     let source = PgCoParticipationSource::new(pool.clone());
-    let graph = ArcadeDbRelationGraph::new(ArcadeDbConfig::from_env())
-        .context("failed to build ArcadeDB relation graph client")?;
-    graph
-        .ensure_schema()
-        .await
-        .context("failed to ensure relation graph schema")?;
-    analyze_relations(&source, &graph, limit).await
+    let graph = prepare_relation_store(&IndexBackendConfig::from_env()?, pool).await?;
+    analyze_relations(&source, graph.as_ref(), limit).await
 }
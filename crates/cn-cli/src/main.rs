@@ -4,14 +4,14 @@ use anyhow::Result;
 use clap::{Parser, Subcommand, ValueEnum};
 use kukuri_cn_core::{
     AdmissionMode, COMMUNITY_NODE_AUTH_SERVICE_NAME, IndexScopeKind, IndexingRequestStatus,
-    connect_postgres,
+    OperatorRole, SanctionKind, connect_postgres,
 };
 
 mod commands;
 
 #[derive(Debug, Parser)]
 struct Cli {
-    /// PostgreSQL 接続先。`moderation issuer-node-id` 以外の全コマンドで必須。
+    /// PostgreSQL 接続先。`moderation issuer-node-id` と `admin sign-login` 以外の全コマンドで必須。
     #[arg(long, env = "COMMUNITY_NODE_DATABASE_URL")]
     database_url: Option<String>,
     #[command(subcommand)]
@@ -65,6 +65,11 @@ enum Command {
         #[command(subcommand)]
         action: ModerationCliAction,
     },
+    /// 運営画面の運営者(role)を管理し、ログイン封筒に署名する。
+    Admin {
+        #[command(subcommand)]
+        action: AdminAction,
+    },
     /// 公開ノードの準備完了判定を実行時情報込みで検査する（#616）。
     Readiness {
         /// operator-config.yaml のパス。
@@ -184,6 +189,64 @@ enum VisibilityArg {
     Public,
 }
 
+#[derive(Debug, Subcommand)]
+enum AdminAction {
+    /// 運営者の登録・権限変更・削除。最初の admin はここで登録する。
+    Operator {
+        #[command(subcommand)]
+        action: OperatorAction,
+    },
+    /// 運営画面のログイン challenge に署名し、貼り付け用の封筒 JSON を出力する。
+    ///
+    /// DB には接続しない。秘密鍵はファイルから読み、引数や出力には含めない。
+    SignLogin {
+        /// 運営画面が表示する公開 URL(`CN_PUBLIC_BASE_URL`)。
+        #[arg(long)]
+        base_url: String,
+        #[arg(long)]
+        challenge: String,
+        /// 運営者の秘密鍵(nsec または hex)を 1 行で置いたファイル。
+        #[arg(long)]
+        secret_key_file: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum OperatorAction {
+    /// 運営者を追加する。登録済みなら role(と指定時は表示名)を更新する。
+    Add {
+        #[arg(long)]
+        pubkey: String,
+        #[arg(long)]
+        role: OperatorRoleArg,
+        #[arg(long)]
+        label: Option<String>,
+    },
+    /// 運営者を削除する。発行済みの session も失効する。
+    Remove {
+        #[arg(long)]
+        pubkey: String,
+    },
+    List,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum OperatorRoleArg {
+    Viewer,
+    Moderator,
+    Admin,
+}
+
+impl From<OperatorRoleArg> for OperatorRole {
+    fn from(value: OperatorRoleArg) -> Self {
+        match value {
+            OperatorRoleArg::Viewer => Self::Viewer,
+            OperatorRoleArg::Moderator => Self::Moderator,
+            OperatorRoleArg::Admin => Self::Admin,
+        }
+    }
+}
+
 #[derive(Debug, Subcommand)]
 enum RelationAction {
     Analyze {
@@ -280,6 +343,21 @@ enum AdmissionAction {
         #[command(subcommand)]
         action: BanAction,
     },
+    /// 期限付きの利用停止・読み取り専用(永続 ban より軽い制限)。
+    Sanction {
+        #[command(subcommand)]
+        action: SanctionAction,
+    },
+    /// vouch mode の閾値と、既存 member からの推薦。
+    Vouch {
+        #[command(subcommand)]
+        action: VouchAction,
+    },
+    /// subscriber の auth セッション(refresh token)。失効させると既存トークンも即時に 401。
+    Sessions {
+        #[command(subcommand)]
+        action: SessionAction,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -327,11 +405,86 @@ enum BanAction {
     List,
 }
 
+#[derive(Debug, Subcommand)]
+enum SanctionAction {
+    /// 制限を付与する。`--report-id` は根拠の通報で、複数指定できる。
+    Add {
+        #[arg(long)]
+        pubkey: String,
+        #[arg(long)]
+        kind: SanctionKindArg,
+        #[arg(long)]
+        reason: String,
+        /// 期限(epoch 秒または RFC3339)。省略すると解除まで続く。
+        #[arg(long)]
+        expires_at: Option<String>,
+        #[arg(long = "report-id")]
+        report_ids: Vec<String>,
+    },
+    /// 期限前に解除する。
+    Lift {
+        #[arg(long)]
+        id: String,
+    },
+    List {
+        #[arg(long)]
+        pubkey: Option<String>,
+        /// 期限切れ・解除済みも含める。
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum VouchAction {
+    /// admit に必要な推薦数を設定する。mode は変更しない。
+    Policy {
+        #[arg(long)]
+        required: u32,
+        /// 推薦者同士の relation proximity で後続の推薦を軽くする(relation graph が必要)。
+        #[arg(long)]
+        proximity_weighting: bool,
+    },
+    List {
+        #[arg(long)]
+        candidate: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum SessionAction {
+    List {
+        #[arg(long)]
+        pubkey: String,
+    },
+    /// 全セッションを失効させる(強制ログアウト)。ban と違い、再度の auth は妨げない。
+    Revoke {
+        #[arg(long)]
+        pubkey: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SanctionKindArg {
+    Suspended,
+    ReadOnly,
+}
+
+impl From<SanctionKindArg> for SanctionKind {
+    fn from(value: SanctionKindArg) -> Self {
+        match value {
+            SanctionKindArg::Suspended => Self::Suspended,
+            SanctionKindArg::ReadOnly => Self::ReadOnly,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 enum AdmissionModeArg {
     Open,
     Invite,
     Whitelist,
+    Vouch,
 }
 
 impl From<AdmissionModeArg> for AdmissionMode {
@@ -340,6 +493,7 @@ impl From<AdmissionModeArg> for AdmissionMode {
             AdmissionModeArg::Open => Self::Open,
             AdmissionModeArg::Invite => Self::Invite,
             AdmissionModeArg::Whitelist => Self::Whitelist,
+            AdmissionModeArg::Vouch => Self::Vouch,
         }
     }
 }
@@ -376,6 +530,18 @@ async fn main() -> Result<()> {
         // DB 接続を伴わない導出専用コマンド。
         return commands::moderation::print_issuer_node_id();
     }
+    if let Command::Admin {
+        action:
+            AdminAction::SignLogin {
+                base_url,
+                challenge,
+                secret_key_file,
+            },
+    } = &cli.command
+    {
+        // DB 接続を伴わない署名専用コマンド。
+        return commands::admin::sign_login(base_url, challenge, secret_key_file);
+    }
     let database_url = cli.database_url.as_deref().ok_or_else(|| {
         anyhow::anyhow!("--database-url または COMMUNITY_NODE_DATABASE_URL が必要です")
     })?;
@@ -23,6 +23,7 @@ fn root_help_keeps_public_command_surface() {
         "supported-topic",
         "indexing-request",
         "relation",
+        "admin",
     ] {
         assert!(
             output.contains(command),
@@ -42,9 +43,16 @@ fn command_and_nested_help_remain_parseable() {
         &["admission", "invite"],
         &["admission", "allow"],
         &["admission", "ban"],
+        &["admission", "sanction"],
+        &["admission", "sanction", "add"],
+        &["admission", "vouch"],
+        &["admission", "vouch", "policy"],
+        &["admission", "sessions"],
         &["supported-topic"],
         &["indexing-request"],
         &["relation"],
+        &["admin", "operator"],
+        &["admin", "sign-login"],
     ] {
         let output = help(args);
         assert!(output.contains("Usage:"), "args={args:?}: {output}");
@@ -0,0 +1,24 @@
+-- 運営者 account と admin session。
+--
+-- 運営者は自分の kukuri 鍵で `/v1/auth/challenge` の封筒に署名してログインし、
+-- operator_actions の actor にはその pubkey が残る。role は viewer < moderator < admin。
+CREATE TABLE IF NOT EXISTS cn_admin.operators (
+    pubkey TEXT PRIMARY KEY,
+    role TEXT NOT NULL CHECK (role IN ('viewer', 'moderator', 'admin')),
+    label TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- session token そのものは保存せず sha256 hex だけを持つ。role は毎 request で
+-- operators から読むため、降格・削除は既存 session にも即時に効く。
+CREATE TABLE IF NOT EXISTS cn_admin.operator_sessions (
+    token_hash TEXT PRIMARY KEY,
+    operator_pubkey TEXT NOT NULL REFERENCES cn_admin.operators (pubkey) ON DELETE CASCADE,
+    csrf_token TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_cn_admin_operator_sessions_expires_at
+    ON cn_admin.operator_sessions (expires_at);
@@ -0,0 +1,22 @@
+-- 期限付きの利用停止(suspended)と読み取り専用(read_only)の制裁。
+--
+-- 永続 ban(`subscriber_accounts.status = 'banned'`)より軽い段階的な node-local 制限。
+-- suspended は auth 発行と既存 token を止め、read_only は auth を保ったまま通報・索引要求・
+-- rendezvous heartbeat を拒否する。`expires_at` が NULL なら解除されるまで続く。
+-- 根拠の通報は `report_ids` に残し、付与・解除は cn_admin.operator_actions にも記録する。
+CREATE TABLE IF NOT EXISTS cn_user.subscriber_sanctions (
+    id TEXT PRIMARY KEY,
+    subscriber_pubkey TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('suspended', 'read_only')),
+    reason TEXT NOT NULL,
+    report_ids TEXT[] NOT NULL DEFAULT '{}',
+    imposed_by TEXT NOT NULL,
+    imposed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ,
+    lifted_by TEXT,
+    lifted_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_cn_user_subscriber_sanctions_pubkey
+    ON cn_user.subscriber_sanctions (subscriber_pubkey)
+    WHERE lifted_at IS NULL;
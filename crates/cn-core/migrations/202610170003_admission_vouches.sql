@@ -0,0 +1,19 @@
+-- vouch mode の admission で使う、既存 member の署名つき推薦。
+--
+-- 推薦者は admit 済みの active subscriber に限る。`weight` は受理時点で確定し、
+-- 近接度の重み付けが有効なら同じ候補者への既存推薦者との relation proximity で減衰させる。
+-- admission 評価では、その時点で active かつ利用停止中でない推薦者の重みだけを合計する。
+-- `envelope_json` は推薦者の署名を後から検証できるよう原文のまま残す。
+CREATE TABLE IF NOT EXISTS cn_user.admission_vouches (
+    voucher_pubkey TEXT NOT NULL,
+    candidate_pubkey TEXT NOT NULL,
+    weight DOUBLE PRECISION NOT NULL CHECK (weight >= 0 AND weight <= 1),
+    envelope_id TEXT NOT NULL,
+    envelope_json JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (voucher_pubkey, candidate_pubkey),
+    CHECK (voucher_pubkey <> candidate_pubkey)
+);
+
+CREATE INDEX IF NOT EXISTS idx_cn_user_admission_vouches_candidate
+    ON cn_user.admission_vouches (candidate_pubkey);
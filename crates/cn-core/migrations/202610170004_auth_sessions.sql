@@ -0,0 +1,36 @@
+-- auth セッションと refresh token。
+--
+-- `/v1/auth/verify` 成功ごとに 1 セッションを作り、アクセストークン(JWT)の `sid` に載せる。
+-- bearer 検証はセッションが失効していないことを確認するため、logout・ban・運営者の失効操作で
+-- 既存のアクセストークンも即時に無効になる。
+--
+-- refresh token は平文を保存せず SHA-256 のみを持つ。一度使うと `used_at` が付いて新しい
+-- token に置き換わり、使用済み token が再提出されたら盗用とみなしてセッションごと失効させる。
+CREATE TABLE IF NOT EXISTS cn_auth.auth_sessions (
+    session_id TEXT PRIMARY KEY,
+    subscriber_pubkey TEXT NOT NULL,
+    endpoint_id TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_refreshed_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    revoked_reason TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_cn_auth_auth_sessions_pubkey
+    ON cn_auth.auth_sessions (subscriber_pubkey)
+    WHERE revoked_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_cn_auth_auth_sessions_expires_at
+    ON cn_auth.auth_sessions (expires_at);
+
+CREATE TABLE IF NOT EXISTS cn_auth.refresh_tokens (
+    token_hash TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL REFERENCES cn_auth.auth_sessions (session_id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    used_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_cn_auth_refresh_tokens_session
+    ON cn_auth.refresh_tokens (session_id);
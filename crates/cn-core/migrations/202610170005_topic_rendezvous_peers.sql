@@ -0,0 +1,15 @@
+-- Redis を置かない node 向けの topic rendezvous 登録簿(`PgTopicRendezvousBackend`)。
+--
+-- topic key は client が作る不透明な hex で、生の topic id は保存しない。行は heartbeat の
+-- TTL で失効し、期限切れ行は `spawn_prune_task` が 60 秒ごとに削除する(heartbeat の経路では
+-- 表全体を掃かない)。
+CREATE TABLE IF NOT EXISTS cn_bootstrap.topic_rendezvous_peers (
+    topic_key TEXT NOT NULL,
+    endpoint_id TEXT NOT NULL,
+    addr_hint TEXT,
+    expires_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (topic_key, endpoint_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_cn_bootstrap_topic_rendezvous_peers_expires_at
+    ON cn_bootstrap.topic_rendezvous_peers (expires_at);
@@ -0,0 +1,8 @@
+-- recommendation ranking の engagement 入力。
+--
+-- cn-indexer が ingest 時に同じ共有 replica 上で観測した反応数・返信数を entry ごとに持つ。
+-- replica の再 ingest のたびに上書きされる derived な集計であり、canonical な数ではない
+-- (node が sync できた範囲の観測値)。
+ALTER TABLE cn_index.index_entries
+    ADD COLUMN reaction_count BIGINT NOT NULL DEFAULT 0 CHECK (reaction_count >= 0),
+    ADD COLUMN reply_count BIGINT NOT NULL DEFAULT 0 CHECK (reply_count >= 0);
@@ -0,0 +1,27 @@
+-- 組み込み backend（`COMMUNITY_NODE_INDEX_BACKEND=embedded`）の relation graph（ADR 0026 §6.1）。
+--
+-- ArcadeDB を置かない構成では、`RelationStore` を Postgres のテーブルで満たす。ArcadeDB 版と同じく
+-- node-local な derived overlay であり、`cn-cli relation analyze` で再構築できる（social graph
+-- canonical ではない）。proximity の合成は backend 非依存の `proximity_from_features` で行うため、
+-- ここには feature の生値だけを持つ。
+
+-- pairwise edge。正規化ペア（辞書順 (小, 大)）で 1 行だけ持ち、どちら側からも同じ feature が見える。
+CREATE TABLE cn_trust.relation_edges (
+    pubkey_low TEXT NOT NULL,
+    pubkey_high TEXT NOT NULL,
+    -- feature key → 値（`shared_topics` 等。`EdgeFeatures` の JSON 表現）。
+    features JSONB NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (pubkey_low, pubkey_high),
+    CHECK (pubkey_low <= pubkey_high)
+);
+
+-- 近傍列挙（viewer が大きい側に来る edge）の access path。
+CREATE INDEX relation_edges_pubkey_high_idx ON cn_trust.relation_edges (pubkey_high);
+
+-- cluster 帰属（解析 worker が算出する相対成分の重み付け入力）。
+CREATE TABLE cn_trust.relation_clusters (
+    pubkey TEXT PRIMARY KEY,
+    cluster_id TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,8 @@
+-- moderation event 配布の keyset pagination。
+--
+-- 配布クエリは (persisted_at, id) の降順で cursor より古い行を読む。同時刻の event が並んでも
+-- 順序が決まるよう id を tie-breaker に含めた index へ置き換える。
+DROP INDEX IF EXISTS cn_safety.idx_cn_safety_events_visibility_persisted_at;
+
+CREATE INDEX idx_cn_safety_events_visibility_persisted_at_id
+    ON cn_safety.signed_moderation_events (visibility, persisted_at DESC, id DESC);
@@ -0,0 +1,300 @@
+//! 運営者 account(role)と鍵署名ログインによる admin session。
+//!
+//! 運営者は利用者と同じ `/v1/auth/challenge` の challenge に `ADMIN_LOGIN_SCOPE` の封筒で
+//! 署名してログインする。session token は発行時に平文を返すだけで sha256 だけを保存し、
+//! role は毎 request で `cn_admin.operators` から読む(降格・削除が既存 session に即時に効く)。
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use sqlx::postgres::{PgPool, PgRow};
+use uuid::Uuid;
+
+use crate::config::AUTH_EVENT_MAX_SKEW_SECONDS;
+use kukuri_cn_protocol::{
+    ADMIN_LOGIN_SCOPE, AUTH_ENVELOPE_KIND, admin_login_capability_url, first_tag_value,
+    normalize_pubkey, parse_auth_envelope, verify_auth_envelope,
+};
+use kukuri_core::KukuriAuthEnvelopeContentV1;
+
+/// admin session の有効期間(秒)。
+pub const ADMIN_SESSION_TTL_SECONDS: i64 = 8 * 60 * 60;
+
+/// 運営者の権限。`viewer < moderator < admin` の順で上位が下位の操作を包含する。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperatorRole {
+    /// dashboard の参照だけ。
+    Viewer,
+    /// 通報の状態変更と異議申し立ての審査。
+    Moderator,
+    /// 受け入れ方式・対応トピック・運営者の管理を含む全操作。
+    Admin,
+}
+
+impl OperatorRole {
+    pub const ALL: [Self; 3] = [Self::Viewer, Self::Moderator, Self::Admin];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Moderator => "moderator",
+            Self::Admin => "admin",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim() {
+            "viewer" => Ok(Self::Viewer),
+            "moderator" => Ok(Self::Moderator),
+            "admin" => Ok(Self::Admin),
+            other => bail!("unsupported operator role `{other}`"),
+        }
+    }
+
+    /// `required` の操作を許すか。
+    pub fn allows(self, required: Self) -> bool {
+        self >= required
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminOperator {
+    pub pubkey: String,
+    pub role: OperatorRole,
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// ログイン直後に返す session。`token` はここでしか平文で得られない。
+#[derive(Clone, Debug)]
+pub struct AdminSession {
+    pub token: String,
+    pub csrf_token: String,
+    pub operator: AdminOperator,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 有効な session token から解決した運営者。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuthenticatedOperator {
+    pub operator: AdminOperator,
+    pub csrf_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+pub async fn list_admin_operators(pool: &PgPool) -> Result<Vec<AdminOperator>> {
+    let rows = sqlx::query(
+        "SELECT pubkey, role, label, created_at, updated_at
+         FROM cn_admin.operators
+         ORDER BY created_at ASC, pubkey ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+    rows.iter().map(admin_operator_from_row).collect()
+}
+
+pub async fn get_admin_operator(pool: &PgPool, pubkey: &str) -> Result<Option<AdminOperator>> {
+    let pubkey = normalize_pubkey(pubkey)?;
+    let row = sqlx::query(
+        "SELECT pubkey, role, label, created_at, updated_at
+         FROM cn_admin.operators
+         WHERE pubkey = $1",
+    )
+    .bind(&pubkey)
+    .fetch_optional(pool)
+    .await?;
+    row.as_ref().map(admin_operator_from_row).transpose()
+}
+
+/// 運営者ログイン封筒を検証し、admin session を発行する。
+///
+/// 封筒の検証は利用者向け `verify_auth_envelope_and_issue_token` と同じ順で行い、加えて
+/// scope と `capability_url` が運営者ログイン用であることを求める(利用者向け封筒の流用を拒否)。
+/// challenge は運営者として登録済みの鍵に対してだけ消費する。
+pub async fn verify_admin_login(
+    pool: &PgPool,
+    public_base_url: &str,
+    login_envelope_json: &Value,
+) -> Result<AdminSession> {
+    let expected_capability_url = admin_login_capability_url(public_base_url)?;
+    let envelope = parse_auth_envelope(login_envelope_json)?;
+    verify_auth_envelope(&envelope)?;
+    if envelope.kind != AUTH_ENVELOPE_KIND {
+        bail!("auth envelope kind mismatch");
+    }
+    let content = serde_json::from_str::<KukuriAuthEnvelopeContentV1>(&envelope.content)
+        .context("invalid admin login envelope content")?;
+    if content.scope != ADMIN_LOGIN_SCOPE {
+        bail!("admin login envelope scope mismatch");
+    }
+    let now = Utc::now();
+    if (now.timestamp() - envelope.created_at).abs() > AUTH_EVENT_MAX_SKEW_SECONDS {
+        bail!("auth envelope is stale");
+    }
+    let capability_url = first_tag_value(&envelope, "capability_url")
+        .ok_or_else(|| anyhow!("missing capability_url tag"))?;
+    if capability_url != expected_capability_url {
+        bail!("capability_url tag mismatch");
+    }
+    let challenge =
+        first_tag_value(&envelope, "challenge").ok_or_else(|| anyhow!("missing challenge tag"))?;
+    let pubkey = normalize_pubkey(envelope.pubkey.as_str())?;
+
+    let mut tx = pool.begin().await?;
+    let row = sqlx::query(
+        "SELECT pubkey, expires_at, used_at
+         FROM cn_auth.auth_challenges
+         WHERE challenge = $1
+         FOR UPDATE",
+    )
+    .bind(challenge)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(row) = row else {
+        bail!("challenge not found");
+    };
+    let stored_pubkey: String = row.try_get("pubkey")?;
+    let expires_at: DateTime<Utc> = row.try_get("expires_at")?;
+    let used_at: Option<DateTime<Utc>> = row.try_get("used_at")?;
+    if used_at.is_some() || now > expires_at {
+        bail!("challenge expired or already used");
+    }
+    if pubkey != stored_pubkey {
+        bail!("pubkey mismatch");
+    }
+    let operator = sqlx::query(
+        "SELECT pubkey, role, label, created_at, updated_at
+         FROM cn_admin.operators
+         WHERE pubkey = $1
+         FOR SHARE",
+    )
+    .bind(&pubkey)
+    .fetch_optional(&mut *tx)
+    .await?
+    .as_ref()
+    .map(admin_operator_from_row)
+    .transpose()?
+    .ok_or_else(|| anyhow!("pubkey is not a registered operator"))?;
+    sqlx::query(
+        "UPDATE cn_auth.auth_challenges
+         SET used_at = NOW()
+         WHERE challenge = $1",
+    )
+    .bind(challenge)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("DELETE FROM cn_admin.operator_sessions WHERE expires_at <= NOW()")
+        .execute(&mut *tx)
+        .await?;
+    let token = random_secret();
+    let csrf_token = random_secret();
+    let expires_at = now + Duration::seconds(ADMIN_SESSION_TTL_SECONDS);
+    sqlx::query(
+        "INSERT INTO cn_admin.operator_sessions
+            (token_hash, operator_pubkey, csrf_token, expires_at)
+         VALUES ($1, $2, $3, $4)",
+    )
+    .bind(admin_session_token_hash(&token))
+    .bind(&pubkey)
+    .bind(&csrf_token)
+    .bind(expires_at)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(AdminSession {
+        token,
+        csrf_token,
+        operator,
+        expires_at,
+    })
+}
+
+/// session token から運営者を解決する。期限切れ・失効・運営者削除済みなら `None`。
+pub async fn load_admin_session(
+    pool: &PgPool,
+    token: &str,
+) -> Result<Option<AuthenticatedOperator>> {
+    let token = token.trim();
+    if token.is_empty() {
+        return Ok(None);
+    }
+    let row = sqlx::query(
+        "SELECT o.pubkey, o.role, o.label, o.created_at, o.updated_at,
+                s.csrf_token, s.expires_at
+         FROM cn_admin.operator_sessions s
+         JOIN cn_admin.operators o ON o.pubkey = s.operator_pubkey
+         WHERE s.token_hash = $1 AND s.expires_at > NOW()",
+    )
+    .bind(admin_session_token_hash(token))
+    .fetch_optional(pool)
+    .await?;
+    row.map(|row| {
+        Ok(AuthenticatedOperator {
+            operator: admin_operator_from_row(&row)?,
+            csrf_token: row.try_get("csrf_token")?,
+            expires_at: row.try_get("expires_at")?,
+        })
+    })
+    .transpose()
+}
+
+/// session を失効させる(logout)。存在しなければ `false`。
+pub async fn revoke_admin_session(pool: &PgPool, token: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM cn_admin.operator_sessions WHERE token_hash = $1")
+        .bind(admin_session_token_hash(token.trim()))
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+fn admin_session_token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn random_secret() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+fn admin_operator_from_row(row: &PgRow) -> Result<AdminOperator> {
+    let role: String = row.try_get("role")?;
+    Ok(AdminOperator {
+        pubkey: row.try_get("pubkey")?,
+        role: OperatorRole::parse(&role)?,
+        label: row.try_get("label")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operator_roles_are_ordered_and_round_trip() {
+        assert!(OperatorRole::Admin.allows(OperatorRole::Moderator));
+        assert!(OperatorRole::Moderator.allows(OperatorRole::Viewer));
+        assert!(!OperatorRole::Viewer.allows(OperatorRole::Moderator));
+        assert!(!OperatorRole::Moderator.allows(OperatorRole::Admin));
+        for role in OperatorRole::ALL {
+            assert_eq!(OperatorRole::parse(role.as_str()).unwrap(), role);
+        }
+        assert!(OperatorRole::parse("owner").is_err());
+    }
+
+    #[test]
+    fn session_token_hash_is_stable_and_not_the_token() {
+        let token = random_secret();
+        assert_eq!(token.len(), 64);
+        assert_eq!(
+            admin_session_token_hash(&token),
+            admin_session_token_hash(&token)
+        );
+        assert_ne!(admin_session_token_hash(&token), token);
+    }
+}
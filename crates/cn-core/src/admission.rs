@@ -1,4 +1,4 @@
-//! community node の admission（招待コード / whitelist / ban）。#383
+//! community node の admission（招待コード / whitelist / 推薦 / ban）。#383
 //!
 //! public community node の利用者を限定するための server-side enforcement。
 //! これは node-local な「補助機能提供の可否」判断であり、kukuri network 全体からの
@@ -6,11 +6,14 @@
 //!
 //! enforcement の適用順序（`evaluate_admission`）:
 //! 1. `status='banned'` は mode に関わらず拒否する。
-//! 2. 既存 `status='active'` subscriber は mode 変更後も通す。
-//! 3. それ以外（未登録 pubkey）のみ mode を適用する。
+//! 2. 有効な利用停止(`sanctions`)があれば mode に関わらず拒否する。
+//! 3. 既存 `status='active'` subscriber は mode 変更後も通す。
+//! 4. それ以外（未登録 pubkey）のみ mode を適用する。
 //!    - `open`: admit
 //!    - `whitelist`: allowlist 登録のみ admit
 //!    - `invite`: allowlist 該当はコード不要 bypass、それ以外は有効コード必須
+//!    - `vouch`: allowlist 該当は bypass、それ以外は既存 member の推薦重みの合計が
+//!      `VouchPolicy::required_vouches` 以上で admit（`vouches`）
 
 use std::fmt;
 
@@ -21,7 +24,10 @@ use sha2::{Digest, Sha256};
 use sqlx::Row;
 use sqlx::postgres::PgPool;
 
+use crate::auth_sessions::revoke_subscriber_sessions;
 use crate::config::COMMUNITY_NODE_ADMISSION_SERVICE_NAME;
+use crate::sanctions::is_suspended;
+use crate::vouches::{meets_required_vouches, vouch_total_milli_weight};
 use kukuri_cn_protocol::normalize::normalize_pubkey;
 
 /// node 全体の入会モード。
@@ -35,6 +41,8 @@ pub enum AdmissionMode {
     Invite,
     /// allowlist 登録済みのみ admit。
     Whitelist,
+    /// 既存 member の署名つき推薦（vouch）が規定数集まれば admit。
+    Vouch,
 }
 
 impl AdmissionMode {
@@ -43,6 +51,7 @@ impl AdmissionMode {
             AdmissionMode::Open => "open",
             AdmissionMode::Invite => "invite",
             AdmissionMode::Whitelist => "whitelist",
+            AdmissionMode::Vouch => "vouch",
         }
     }
 }
@@ -52,18 +61,54 @@ impl AdmissionMode {
 pub struct AdmissionConfig {
     #[serde(default)]
     pub mode: AdmissionMode,
+    /// vouch mode の閾値。他 mode では参照しないが、mode を切り替えても保持する。
+    #[serde(default)]
+    pub vouch: VouchPolicy,
+}
+
+/// 推薦数の上限（運営者の設定ミスで誰も入れなくなるのを防ぐ）。
+pub const MAX_REQUIRED_VOUCHES: u32 = 20;
+
+/// vouch mode の閾値。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VouchPolicy {
+    /// admit に必要な推薦重みの合計。重み付けなしなら推薦者の人数と一致する。
+    pub required_vouches: u32,
+    /// 推薦者同士の relation proximity で後続の推薦を減衰させる（sybil cluster 対策）。
+    pub proximity_weighting: bool,
+}
+
+impl Default for VouchPolicy {
+    fn default() -> Self {
+        Self {
+            required_vouches: 2,
+            proximity_weighting: false,
+        }
+    }
+}
+
+impl VouchPolicy {
+    pub fn validate(&self) -> Result<()> {
+        if self.required_vouches == 0 || self.required_vouches > MAX_REQUIRED_VOUCHES {
+            anyhow::bail!("required vouches must be between 1 and {MAX_REQUIRED_VOUCHES}");
+        }
+        Ok(())
+    }
 }
 
 /// admission 拒否の理由。`code()` は API へ返す安定コード。
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum AdmissionRejection {
     Banned,
+    Suspended,
     InviteRequired,
     InviteInvalid,
     InviteExpired,
     InviteExhausted,
     InviteRevoked,
     NotAllowlisted,
+    VouchRequired,
 }
 
 impl AdmissionRejection {
@@ -71,12 +116,14 @@ impl AdmissionRejection {
     pub fn code(&self) -> &'static str {
         match self {
             AdmissionRejection::Banned => "BANNED",
+            AdmissionRejection::Suspended => "SUSPENDED",
             AdmissionRejection::InviteRequired => "INVITE_REQUIRED",
             AdmissionRejection::InviteInvalid => "INVITE_INVALID",
             AdmissionRejection::InviteExpired => "INVITE_EXPIRED",
             AdmissionRejection::InviteExhausted => "INVITE_EXHAUSTED",
             AdmissionRejection::InviteRevoked => "INVITE_REVOKED",
             AdmissionRejection::NotAllowlisted => "NOT_ALLOWLISTED",
+            AdmissionRejection::VouchRequired => "VOUCH_REQUIRED",
         }
     }
 
@@ -86,6 +133,9 @@ impl AdmissionRejection {
             AdmissionRejection::Banned => {
                 "this community node is not providing support services to this account"
             }
+            AdmissionRejection::Suspended => {
+                "this community node has temporarily suspended support services to this account"
+            }
             AdmissionRejection::InviteRequired => {
                 "this community node requires an invite code to join"
             }
@@ -98,6 +148,9 @@ impl AdmissionRejection {
             AdmissionRejection::NotAllowlisted => {
                 "this community node only admits allowlisted accounts"
             }
+            AdmissionRejection::VouchRequired => {
+                "this community node requires vouches from existing members to join"
+            }
         }
     }
 }
@@ -177,9 +230,23 @@ pub async fn load_admission_config(pool: &PgPool) -> Result<AdmissionConfig> {
     }
 }
 
-/// admission mode を設定する。
+/// admission mode を設定する。vouch の閾値など他の設定は保持する。
 pub async fn set_admission_mode(pool: &PgPool, mode: AdmissionMode) -> Result<()> {
-    let config_json = serde_json::to_value(AdmissionConfig { mode })?;
+    let mut config = load_admission_config(pool).await?;
+    config.mode = mode;
+    store_admission_config(pool, &config).await
+}
+
+/// vouch mode の閾値を設定する。mode は変更しない。
+pub async fn set_vouch_policy(pool: &PgPool, policy: VouchPolicy) -> Result<()> {
+    policy.validate()?;
+    let mut config = load_admission_config(pool).await?;
+    config.vouch = policy;
+    store_admission_config(pool, &config).await
+}
+
+async fn store_admission_config(pool: &PgPool, config: &AdmissionConfig) -> Result<()> {
+    let config_json = serde_json::to_value(config)?;
     sqlx::query(
         "INSERT INTO cn_admin.service_configs (service_name, version, config_json)
          VALUES ($1, 1, $2)
@@ -314,12 +381,14 @@ pub async fn list_allowlist(pool: &PgPool) -> Result<Vec<AllowlistEntry>> {
 
 /// subscriber を ban する。未登録 pubkey の事前 ban は banned 行を upsert する。
 /// 既存 active subscriber の ban は `require_bearer_identity` の status 再チェックで
-/// 既存トークンも即時失効する。
+/// 既存トークンも即時失効する。auth セッションも失効させるため、unban 後に古い
+/// refresh token で復帰することはなく、改めて auth が必要になる。
 ///
 /// `admitted` は変更しない。これにより、現メンバーを ban→unban すると member 資格が戻り、
 /// 未参加のまま事前 ban→unban した pubkey は未参加（admitted=false）のままになる。
 pub async fn ban_subscriber(pool: &PgPool, pubkey: &str) -> Result<()> {
     let pubkey = normalize_pubkey(pubkey)?;
+    let mut tx = pool.begin().await?;
     sqlx::query(
         "INSERT INTO cn_user.subscriber_accounts (subscriber_pubkey, status, admitted)
          VALUES ($1, 'banned', FALSE)
@@ -327,8 +396,10 @@ pub async fn ban_subscriber(pool: &PgPool, pubkey: &str) -> Result<()> {
          SET status = 'banned'",
     )
     .bind(&pubkey)
-    .execute(pool)
+    .execute(&mut *tx)
     .await?;
+    revoke_subscriber_sessions(&mut *tx, pubkey.as_str(), "banned").await?;
+    tx.commit().await?;
     Ok(())
 }
 
@@ -378,7 +449,7 @@ pub async fn list_banned(pool: &PgPool) -> Result<Vec<BannedEntry>> {
 pub(crate) async fn evaluate_admission(
     tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     pubkey: &str,
-    mode: AdmissionMode,
+    config: &AdmissionConfig,
     invite_code: Option<&str>,
 ) -> Result<()> {
     // 1. 既存 subscriber の状態を確認する。`admitted` で「現メンバー」と
@@ -392,21 +463,29 @@ pub(crate) async fn evaluate_admission(
     .fetch_optional(&mut **tx)
     .await?;
 
-    if let Some(row) = existing {
-        let status: String = row.try_get("status")?;
-        let admitted: bool = row.try_get("admitted")?;
-        match status.as_str() {
-            // banned は mode に関わらず拒否する。
-            "banned" => return Err(AdmissionRejection::Banned.into()),
-            // 既に admission を通過した現メンバーは mode 変更後も通す。
-            // status は active でも admitted=false（pre-ban を unban しただけ）の場合は
-            // 下の mode 適用へ進み、改めて invite/whitelist を要求する。
-            "active" if admitted => return Ok(()),
-            _ => {}
-        }
+    let (status, admitted) = match existing {
+        Some(row) => (
+            Some(row.try_get::<String, _>("status")?),
+            row.try_get::<bool, _>("admitted")?,
+        ),
+        None => (None, false),
+    };
+    // banned は mode に関わらず拒否する。
+    if status.as_deref() == Some("banned") {
+        return Err(AdmissionRejection::Banned.into());
+    }
+    // 期限付きの利用停止は現メンバーにも mode にも優先する。未登録 pubkey への事前付与も効く。
+    if is_suspended(tx, pubkey).await? {
+        return Err(AdmissionRejection::Suspended.into());
+    }
+    // 既に admission を通過した現メンバーは mode 変更後も通す。
+    // status は active でも admitted=false（pre-ban を unban しただけ）の場合は
+    // 下の mode 適用へ進み、改めて invite/whitelist を要求する。
+    if status.as_deref() == Some("active") && admitted {
+        return Ok(());
     }
 
-    match mode {
+    match config.mode {
         AdmissionMode::Open => Ok(()),
         AdmissionMode::Whitelist => {
             if is_allowlisted(tx, pubkey).await? {
@@ -425,6 +504,17 @@ pub(crate) async fn evaluate_admission(
             };
             redeem_invite_code(tx, code).await
         }
+        AdmissionMode::Vouch => {
+            if is_allowlisted(tx, pubkey).await? {
+                return Ok(());
+            }
+            let total = vouch_total_milli_weight(tx, pubkey).await?;
+            if meets_required_vouches(total, config.vouch.required_vouches) {
+                Ok(())
+            } else {
+                Err(AdmissionRejection::VouchRequired.into())
+            }
+        }
     }
 }
 
@@ -508,6 +598,7 @@ mod tests {
             (AdmissionMode::Open, "\"open\""),
             (AdmissionMode::Invite, "\"invite\""),
             (AdmissionMode::Whitelist, "\"whitelist\""),
+            (AdmissionMode::Vouch, "\"vouch\""),
         ] {
             let serialized = serde_json::to_string(&mode).unwrap();
             assert_eq!(serialized, json);
@@ -522,6 +613,35 @@ mod tests {
         // mode 省略の JSON は serde field default で open になる（既存設定の前方互換）。
         let parsed: AdmissionConfig = serde_json::from_value(serde_json::json!({})).unwrap();
         assert_eq!(parsed.mode, AdmissionMode::Open);
+        assert_eq!(parsed.vouch, VouchPolicy::default());
+    }
+
+    #[test]
+    fn vouch_policy_keeps_partial_json_and_bounds_threshold() {
+        let parsed: AdmissionConfig = serde_json::from_value(serde_json::json!({
+            "mode": "vouch",
+            "vouch": { "required_vouches": 3 }
+        }))
+        .unwrap();
+        assert_eq!(parsed.vouch.required_vouches, 3);
+        assert!(!parsed.vouch.proximity_weighting);
+
+        assert!(
+            VouchPolicy {
+                required_vouches: 0,
+                proximity_weighting: false,
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            VouchPolicy {
+                required_vouches: MAX_REQUIRED_VOUCHES + 1,
+                proximity_weighting: true,
+            }
+            .validate()
+            .is_err()
+        );
     }
 
     #[test]
@@ -536,6 +656,7 @@ mod tests {
     #[test]
     fn rejection_codes_are_stable() {
         assert_eq!(AdmissionRejection::Banned.code(), "BANNED");
+        assert_eq!(AdmissionRejection::Suspended.code(), "SUSPENDED");
         assert_eq!(AdmissionRejection::InviteRequired.code(), "INVITE_REQUIRED");
         assert_eq!(AdmissionRejection::InviteInvalid.code(), "INVITE_INVALID");
         assert_eq!(AdmissionRejection::InviteExpired.code(), "INVITE_EXPIRED");
@@ -545,6 +666,7 @@ mod tests {
         );
         assert_eq!(AdmissionRejection::InviteRevoked.code(), "INVITE_REVOKED");
         assert_eq!(AdmissionRejection::NotAllowlisted.code(), "NOT_ALLOWLISTED");
+        assert_eq!(AdmissionRejection::VouchRequired.code(), "VOUCH_REQUIRED");
     }
 
     #[test]
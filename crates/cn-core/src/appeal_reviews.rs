@@ -8,6 +8,7 @@ use sqlx::Row;
 use sqlx::postgres::{PgPool, PgRow};
 use uuid::Uuid;
 
+use crate::admin_operators::OperatorRole;
 use crate::operator_actions::OperatorAction;
 use crate::safety_appeals::{
     RiskSignalCorrection, RiskSignalMetadataEdit, validate_optional_confidence,
@@ -86,6 +87,13 @@ pub enum AppealReviewOperation {
     },
 }
 
+impl AppealReviewOperation {
+    /// 異議申し立ての審査に必要な運営者 role。
+    pub fn required_role(&self) -> OperatorRole {
+        OperatorRole::Moderator
+    }
+}
+
 pub async fn list_appeal_reviews(
     pool: &PgPool,
     limit: i64,
@@ -7,7 +7,11 @@ use serde_json::Value;
 use sqlx::Row;
 use sqlx::postgres::PgPool;
 
-use crate::admission::{AdmissionMode, evaluate_admission};
+use crate::admission::{AdmissionConfig, evaluate_admission};
+use crate::auth_sessions::{
+    IssuedRefreshToken, RefreshOutcome, create_auth_session, prune_expired_auth_sessions,
+    rotate_refresh_token,
+};
 use crate::bootstrap::{
     prune_expired_bootstrap_peer_registrations, upsert_bootstrap_peer_registration,
 };
@@ -33,6 +37,11 @@ struct AccessTokenClaims {
     exp: usize,
     #[serde(default)]
     endpoint_id: Option<String>,
+    #[serde(default)]
+    jti: Option<String>,
+    /// auth セッション。`sid` を持たないのは導入前に発行されたトークンで、期限まで有効。
+    #[serde(default)]
+    sid: Option<String>,
 }
 
 pub async fn create_auth_challenge(pool: &PgPool, pubkey: &str) -> Result<AuthChallengeResponse> {
@@ -79,7 +88,7 @@ pub async fn verify_auth_envelope_and_issue_token(
     auth_envelope_json: &Value,
     endpoint_id: Option<&str>,
     addr_hint: Option<&str>,
-    admission_mode: AdmissionMode,
+    admission: &AdmissionConfig,
     invite_code: Option<&str>,
 ) -> Result<AuthVerifyResponse> {
     let public_base_url = normalize_http_url(public_base_url)?;
@@ -126,16 +135,11 @@ pub async fn verify_auth_envelope_and_issue_token(
 
     let mut tx = pool.begin().await?;
     prune_expired_bootstrap_peer_registrations(&mut *tx).await?;
+    prune_expired_auth_sessions(&mut *tx).await?;
     // admission を challenge / invite 消費の前に評価する。拒否時は tx を rollback し、
     // challenge も invite も消費しない（無効な試行で消費させない）。invite redeem が成功した
     // 場合は subscriber 作成・challenge 消費と同一 tx でコミットされ原子性を保つ。
-    evaluate_admission(
-        &mut tx,
-        normalized_pubkey.as_str(),
-        admission_mode,
-        invite_code,
-    )
-    .await?;
+    evaluate_admission(&mut tx, normalized_pubkey.as_str(), admission, invite_code).await?;
     sqlx::query(
         "UPDATE cn_auth.auth_challenges
          SET used_at = NOW()
@@ -149,20 +153,101 @@ pub async fn verify_auth_envelope_and_issue_token(
         upsert_bootstrap_peer_registration(&mut *tx, normalized_pubkey.as_str(), seed_peer, now)
             .await?;
     }
+    let endpoint_id = registered_endpoint
+        .as_ref()
+        .map(|seed_peer| seed_peer.endpoint_id.as_str());
+    let refresh =
+        create_auth_session(&mut tx, jwt_config, normalized_pubkey.as_str(), endpoint_id).await?;
+    tx.commit().await?;
+
+    session_response(jwt_config, normalized_pubkey, endpoint_id, refresh)
+}
+
+/// refresh token でアクセストークンを再発行する。challenge と admission 評価は経ない
+/// (admit 済みのセッションの延長)が、ban・利用停止中の subscriber には発行しない。
+///
+/// refresh token は一度きりで、応答の新しい token に置き換わる。使用済み token の再提出は
+/// セッションごと失効させたうえで拒否する。`endpoint_id` があれば verify と同じく
+/// bootstrap peer として登録し直す。
+pub async fn refresh_access_token(
+    pool: &PgPool,
+    jwt_config: &JwtConfig,
+    refresh_token: &str,
+    endpoint_id: Option<&str>,
+    addr_hint: Option<&str>,
+) -> Result<AuthVerifyResponse> {
+    let now = Utc::now();
+    let registered_endpoint = endpoint_id
+        .map(|value| CommunityNodeSeedPeer::new(value, addr_hint.map(str::to_string)))
+        .transpose()?;
+    let mut tx = pool.begin().await?;
+    let session =
+        match rotate_refresh_token(&mut tx, jwt_config, refresh_token, endpoint_id).await? {
+            RefreshOutcome::Rotated(session) => session,
+            RefreshOutcome::ReuseDetected => {
+                tx.commit().await?;
+                bail!("refresh token was already used; the auth session has been revoked");
+            }
+        };
+    let active = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (
+             SELECT 1 FROM cn_user.subscriber_accounts a
+             WHERE a.subscriber_pubkey = $1
+               AND a.status = 'active'
+               AND NOT EXISTS (
+                   SELECT 1 FROM cn_user.subscriber_sanctions s
+                   WHERE s.subscriber_pubkey = a.subscriber_pubkey
+                     AND s.kind = 'suspended'
+                     AND s.lifted_at IS NULL
+                     AND (s.expires_at IS NULL OR s.expires_at > NOW())
+               )
+         )",
+    )
+    .bind(&session.subscriber_pubkey)
+    .fetch_one(&mut *tx)
+    .await?;
+    if !active {
+        bail!("subscriber is not active");
+    }
+    if let Some(seed_peer) = registered_endpoint.as_ref() {
+        prune_expired_bootstrap_peer_registrations(&mut *tx).await?;
+        upsert_bootstrap_peer_registration(
+            &mut *tx,
+            session.subscriber_pubkey.as_str(),
+            seed_peer,
+            now,
+        )
+        .await?;
+    }
     tx.commit().await?;
 
+    session_response(
+        jwt_config,
+        session.subscriber_pubkey,
+        session.endpoint_id.as_deref(),
+        session.refresh,
+    )
+}
+
+fn session_response(
+    jwt_config: &JwtConfig,
+    pubkey: String,
+    endpoint_id: Option<&str>,
+    refresh: IssuedRefreshToken,
+) -> Result<AuthVerifyResponse> {
     let (access_token, expires_at) = issue_access_token(
         jwt_config,
-        normalized_pubkey.as_str(),
-        registered_endpoint
-            .as_ref()
-            .map(|seed_peer| seed_peer.endpoint_id.as_str()),
+        pubkey.as_str(),
+        endpoint_id,
+        refresh.session_id.as_str(),
     )?;
     Ok(AuthVerifyResponse {
         access_token,
         token_type: "Bearer".to_string(),
         expires_at,
-        pubkey: normalized_pubkey,
+        pubkey,
+        refresh_token: Some(refresh.refresh_token),
+        refresh_expires_at: Some(refresh.expires_at),
     })
 }
 
@@ -189,10 +274,26 @@ pub async fn require_bearer_identity(
         .transpose()
         .map_err(|error| auth_required_error(format!("invalid bearer token endpoint: {error}")))?
         .map(|seed_peer| seed_peer.endpoint_id);
-    let active = sqlx::query_scalar::<_, String>(
-        "SELECT status FROM cn_user.subscriber_accounts WHERE subscriber_pubkey = $1",
+    // 有効な制裁(期限切れ・解除済みを除く)とセッションの失効を同じ問い合わせで読む。
+    // 期限切れで削除されたセッションも失効として扱う。
+    let row = sqlx::query(
+        "SELECT a.status,
+                COALESCE(bool_or(s.kind = 'suspended'), FALSE) AS suspended,
+                COALESCE(bool_or(s.kind = 'read_only'), FALSE) AS read_only,
+                ($2::TEXT IS NOT NULL AND NOT EXISTS (
+                    SELECT 1 FROM cn_auth.auth_sessions x
+                    WHERE x.session_id = $2 AND x.revoked_at IS NULL
+                )) AS session_revoked
+         FROM cn_user.subscriber_accounts a
+         LEFT JOIN cn_user.subscriber_sanctions s
+           ON s.subscriber_pubkey = a.subscriber_pubkey
+          AND s.lifted_at IS NULL
+          AND (s.expires_at IS NULL OR s.expires_at > NOW())
+         WHERE a.subscriber_pubkey = $1
+         GROUP BY a.status",
     )
     .bind(&pubkey)
+    .bind(claims.sid.as_deref())
     .fetch_optional(pool)
     .await
     .map_err(|error| {
@@ -202,15 +303,35 @@ pub async fn require_bearer_identity(
             error.to_string(),
         )
     })?;
-    match active.as_deref() {
-        Some("active") => Ok(BearerIdentity {
+    let Some(row) = row else {
+        return Err(auth_required_error("subscriber is not registered"));
+    };
+    let column_error = |error: sqlx::Error| {
+        ApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "DB_ERROR",
+            error.to_string(),
+        )
+    };
+    let status: String = row.try_get("status").map_err(column_error)?;
+    let suspended: bool = row.try_get("suspended").map_err(column_error)?;
+    let read_only: bool = row.try_get("read_only").map_err(column_error)?;
+    let session_revoked: bool = row.try_get("session_revoked").map_err(column_error)?;
+    if session_revoked {
+        return Err(auth_required_error("auth session has been revoked"));
+    }
+    match status.as_str() {
+        // 利用停止中の subscriber も既存トークンを即時失効させる。
+        "active" if suspended => Err(auth_required_error("subscriber is suspended")),
+        "active" => Ok(BearerIdentity {
             pubkey,
             endpoint_id,
+            read_only,
+            session_id: claims.sid,
         }),
         // banned subscriber は既存トークンでも即時失効する（#383）。
-        Some("banned") => Err(auth_required_error("subscriber is banned")),
-        Some(_) => Err(auth_required_error("subscriber is not active")),
-        None => Err(auth_required_error("subscriber is not registered")),
+        "banned" => Err(auth_required_error("subscriber is banned")),
+        _ => Err(auth_required_error("subscriber is not active")),
     }
 }
 
@@ -234,6 +355,7 @@ fn issue_access_token(
     jwt_config: &JwtConfig,
     pubkey: &str,
     endpoint_id: Option<&str>,
+    session_id: &str,
 ) -> Result<(String, i64)> {
     ensure_jwt_crypto_provider();
     let issued_at = Utc::now().timestamp();
@@ -244,6 +366,8 @@ fn issue_access_token(
         iat: issued_at as usize,
         exp: expires_at as usize,
         endpoint_id: endpoint_id.map(str::to_string),
+        jti: Some(uuid::Uuid::new_v4().to_string()),
+        sid: Some(session_id.to_string()),
     };
     let token = encode(&Header::default(), &claims, &jwt_config.encoding_key())?;
     Ok((token, expires_at))
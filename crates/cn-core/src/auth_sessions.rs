@@ -0,0 +1,301 @@
+//! auth セッションと refresh token の保存・回転・失効。
+//!
+//! `/v1/auth/verify` の成功ごとに 1 セッションを作り、アクセストークンの `sid` に載せる。
+//! bearer 検証(`require_bearer_identity`)はセッションの失効を確認するため、logout・ban・
+//! 運営者による失効で既存のアクセストークンも即時に無効になる。
+//!
+//! refresh token は一度きりで、使うたびに同じセッションの新しい token へ置き換わる。使用済みの
+//! token が再提出されたら漏洩とみなし、セッションごと失効させる(正規の端末も再認証になる)。
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use sqlx::postgres::PgPool;
+use uuid::Uuid;
+
+use crate::config::JwtConfig;
+use kukuri_cn_protocol::models::CommunityNodeSeedPeer;
+use kukuri_cn_protocol::normalize::normalize_pubkey;
+
+/// 運営者向けのセッション一覧の 1 行。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthSession {
+    pub session_id: String,
+    pub subscriber_pubkey: String,
+    pub endpoint_id: Option<String>,
+    pub created_at: i64,
+    pub last_refreshed_at: Option<i64>,
+    pub expires_at: i64,
+    pub revoked_at: Option<i64>,
+    pub revoked_reason: Option<String>,
+}
+
+/// 発行した refresh token。平文はこの応答でしか返さない。
+pub(crate) struct IssuedRefreshToken {
+    pub(crate) session_id: String,
+    pub(crate) refresh_token: String,
+    pub(crate) expires_at: i64,
+}
+
+/// 回転に成功したセッション。新しいアクセストークンはこの内容で発行する。
+pub(crate) struct RotatedSession {
+    pub(crate) subscriber_pubkey: String,
+    pub(crate) endpoint_id: Option<String>,
+    pub(crate) refresh: IssuedRefreshToken,
+}
+
+/// 回転の結果。再提出を検知した場合はセッションを失効させた状態でコミットする必要があるため、
+/// エラーではなく値で返す。
+pub(crate) enum RefreshOutcome {
+    Rotated(RotatedSession),
+    ReuseDetected,
+}
+
+pub(crate) async fn create_auth_session(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    jwt_config: &JwtConfig,
+    pubkey: &str,
+    endpoint_id: Option<&str>,
+) -> Result<IssuedRefreshToken> {
+    let session_id = Uuid::new_v4().to_string();
+    let expires_at = Utc::now() + Duration::seconds(jwt_config.refresh_ttl_seconds());
+    sqlx::query(
+        "INSERT INTO cn_auth.auth_sessions (session_id, subscriber_pubkey, endpoint_id, expires_at)
+         VALUES ($1, $2, $3, $4)",
+    )
+    .bind(&session_id)
+    .bind(pubkey)
+    .bind(endpoint_id)
+    .bind(expires_at)
+    .execute(&mut **tx)
+    .await?;
+    insert_refresh_token(tx, session_id, expires_at).await
+}
+
+/// refresh token を検証して使用済みにし、同じセッションの新しい token を発行する。
+///
+/// セッションが endpoint id に束縛されていれば、`endpoint_id` が一致しなければならない。
+pub(crate) async fn rotate_refresh_token(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    jwt_config: &JwtConfig,
+    refresh_token: &str,
+    endpoint_id: Option<&str>,
+) -> Result<RefreshOutcome> {
+    let row = sqlx::query(
+        "SELECT t.session_id, t.expires_at, t.used_at,
+                s.subscriber_pubkey, s.endpoint_id, s.revoked_at
+         FROM cn_auth.refresh_tokens t
+         JOIN cn_auth.auth_sessions s ON s.session_id = t.session_id
+         WHERE t.token_hash = $1
+         FOR UPDATE OF t, s",
+    )
+    .bind(refresh_token_hash(refresh_token.trim()))
+    .fetch_optional(&mut **tx)
+    .await?;
+    let Some(row) = row else {
+        bail!("refresh token not found");
+    };
+    let session_id: String = row.try_get("session_id")?;
+    let expires_at: DateTime<Utc> = row.try_get("expires_at")?;
+    let used_at: Option<DateTime<Utc>> = row.try_get("used_at")?;
+    let revoked_at: Option<DateTime<Utc>> = row.try_get("revoked_at")?;
+    let subscriber_pubkey: String = row.try_get("subscriber_pubkey")?;
+    let bound_endpoint: Option<String> = row.try_get("endpoint_id")?;
+    if revoked_at.is_some() {
+        bail!("auth session has been revoked");
+    }
+    if used_at.is_some() {
+        revoke_session_in(tx, session_id.as_str(), "refresh_token_reuse").await?;
+        return Ok(RefreshOutcome::ReuseDetected);
+    }
+    if Utc::now() > expires_at {
+        bail!("refresh token expired");
+    }
+    if let Some(bound_endpoint) = bound_endpoint.as_deref() {
+        let requested = endpoint_id
+            .map(|value| CommunityNodeSeedPeer::new(value, None))
+            .transpose()?
+            .map(|seed_peer| seed_peer.endpoint_id);
+        if requested.as_deref() != Some(bound_endpoint) {
+            bail!("refresh token is bound to another endpoint");
+        }
+    }
+
+    sqlx::query(
+        "UPDATE cn_auth.refresh_tokens
+         SET used_at = NOW()
+         WHERE token_hash = $1",
+    )
+    .bind(refresh_token_hash(refresh_token.trim()))
+    .execute(&mut **tx)
+    .await?;
+    let next_expires_at = Utc::now() + Duration::seconds(jwt_config.refresh_ttl_seconds());
+    sqlx::query(
+        "UPDATE cn_auth.auth_sessions
+         SET last_refreshed_at = NOW(), expires_at = $2
+         WHERE session_id = $1",
+    )
+    .bind(&session_id)
+    .bind(next_expires_at)
+    .execute(&mut **tx)
+    .await?;
+    let refresh = insert_refresh_token(tx, session_id, next_expires_at).await?;
+    Ok(RefreshOutcome::Rotated(RotatedSession {
+        subscriber_pubkey,
+        endpoint_id: bound_endpoint,
+        refresh,
+    }))
+}
+
+/// subscriber 自身のセッションを失効させる(logout)。未失効のセッションがあれば `true`。
+pub async fn revoke_auth_session(pool: &PgPool, pubkey: &str, session_id: &str) -> Result<bool> {
+    let pubkey = normalize_pubkey(pubkey)?;
+    let result = sqlx::query(
+        "UPDATE cn_auth.auth_sessions
+         SET revoked_at = NOW(), revoked_reason = 'logout'
+         WHERE session_id = $1 AND subscriber_pubkey = $2 AND revoked_at IS NULL",
+    )
+    .bind(session_id)
+    .bind(&pubkey)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// subscriber の全セッションを失効させ、失効させた件数を返す(ban・運営者の強制ログアウト)。
+pub async fn revoke_subscriber_sessions<'e, E>(
+    executor: E,
+    pubkey: &str,
+    reason: &str,
+) -> Result<u64>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+{
+    let pubkey = normalize_pubkey(pubkey)?;
+    let result = sqlx::query(
+        "UPDATE cn_auth.auth_sessions
+         SET revoked_at = NOW(), revoked_reason = $2
+         WHERE subscriber_pubkey = $1 AND revoked_at IS NULL",
+    )
+    .bind(&pubkey)
+    .bind(reason)
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// subscriber のセッションを新しい順に一覧する(期限切れで削除済みのものは含まない)。
+pub async fn list_auth_sessions(pool: &PgPool, pubkey: &str) -> Result<Vec<AuthSession>> {
+    let pubkey = normalize_pubkey(pubkey)?;
+    let rows = sqlx::query(
+        "SELECT session_id, subscriber_pubkey, endpoint_id, created_at, last_refreshed_at,
+                expires_at, revoked_at, revoked_reason
+         FROM cn_auth.auth_sessions
+         WHERE subscriber_pubkey = $1
+         ORDER BY created_at DESC",
+    )
+    .bind(&pubkey)
+    .fetch_all(pool)
+    .await?;
+    rows.iter()
+        .map(|row| {
+            Ok(AuthSession {
+                session_id: row.try_get("session_id")?,
+                subscriber_pubkey: row.try_get("subscriber_pubkey")?,
+                endpoint_id: row.try_get("endpoint_id")?,
+                created_at: row.try_get::<DateTime<Utc>, _>("created_at")?.timestamp(),
+                last_refreshed_at: row
+                    .try_get::<Option<DateTime<Utc>>, _>("last_refreshed_at")?
+                    .map(|value| value.timestamp()),
+                expires_at: row.try_get::<DateTime<Utc>, _>("expires_at")?.timestamp(),
+                revoked_at: row
+                    .try_get::<Option<DateTime<Utc>>, _>("revoked_at")?
+                    .map(|value| value.timestamp()),
+                revoked_reason: row.try_get("revoked_reason")?,
+            })
+        })
+        .collect()
+}
+
+/// 期限切れのセッション(と refresh token)を消す。`/v1/auth/verify` のたびに機会的に呼ぶ。
+///
+/// セッションの期限はアクセストークンの期限以上なので(`JwtConfig::with_refresh_ttl_seconds`)、
+/// 消したセッションに属する有効なアクセストークンは残らない。
+pub(crate) async fn prune_expired_auth_sessions<'e, E>(executor: E) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+{
+    sqlx::query(
+        "DELETE FROM cn_auth.auth_sessions
+         WHERE expires_at <= NOW()",
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+async fn insert_refresh_token(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    session_id: String,
+    expires_at: DateTime<Utc>,
+) -> Result<IssuedRefreshToken> {
+    let refresh_token = random_refresh_token();
+    sqlx::query(
+        "INSERT INTO cn_auth.refresh_tokens (token_hash, session_id, expires_at)
+         VALUES ($1, $2, $3)",
+    )
+    .bind(refresh_token_hash(refresh_token.as_str()))
+    .bind(&session_id)
+    .bind(expires_at)
+    .execute(&mut **tx)
+    .await?;
+    Ok(IssuedRefreshToken {
+        session_id,
+        refresh_token,
+        expires_at: expires_at.timestamp(),
+    })
+}
+
+async fn revoke_session_in(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    session_id: &str,
+    reason: &str,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE cn_auth.auth_sessions
+         SET revoked_at = NOW(), revoked_reason = $2
+         WHERE session_id = $1 AND revoked_at IS NULL",
+    )
+    .bind(session_id)
+    .bind(reason)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+fn refresh_token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn random_refresh_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_tokens_are_random_and_stored_only_as_hashes() {
+        let first = random_refresh_token();
+        let second = random_refresh_token();
+        assert_ne!(first, second);
+        assert_eq!(first.len(), 64);
+        let hash = refresh_token_hash(first.as_str());
+        assert_eq!(hash.len(), 64);
+        assert_ne!(hash, first);
+        assert_eq!(hash, refresh_token_hash(first.as_str()));
+    }
+}
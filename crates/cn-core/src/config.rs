@@ -7,9 +7,11 @@ use serde::{Deserialize, Serialize};
 pub const AUTH_CHALLENGE_TTL_SECONDS: i64 = 300;
 pub const AUTH_EVENT_MAX_SKEW_SECONDS: i64 = 600;
 pub const DEFAULT_TOKEN_TTL_SECONDS: i64 = 86_400;
+pub const DEFAULT_REFRESH_TOKEN_TTL_SECONDS: i64 = 30 * 86_400;
 pub const MIN_JWT_SECRET_BYTES: usize = 32;
 pub const BOOTSTRAP_PEER_REGISTRATION_TTL_SECONDS: i64 = 90;
 pub const TOPIC_RENDEZVOUS_TTL_SECONDS: u64 = 45;
+pub const COMMUNITY_NODE_RENDEZVOUS_BACKEND_ENV: &str = "COMMUNITY_NODE_RENDEZVOUS_BACKEND";
 pub const COMMUNITY_NODE_RENDEZVOUS_REDIS_URL_ENV: &str = "COMMUNITY_NODE_RENDEZVOUS_REDIS_URL";
 pub const COMMUNITY_NODE_RENDEZVOUS_KEY_PREFIX_ENV: &str = "COMMUNITY_NODE_RENDEZVOUS_KEY_PREFIX";
 pub const COMMUNITY_NODE_AUTH_SERVICE_NAME: &str = "community_node_auth";
@@ -25,17 +27,27 @@ pub struct JwtConfig {
     issuer: String,
     secret: String,
     ttl_seconds: i64,
+    refresh_ttl_seconds: i64,
 }
 
 impl JwtConfig {
     pub fn new(issuer: impl Into<String>, secret: impl Into<String>, ttl_seconds: i64) -> Self {
+        let ttl_seconds = ttl_seconds.max(60);
         Self {
             issuer: issuer.into(),
             secret: secret.into(),
-            ttl_seconds: ttl_seconds.max(60),
+            ttl_seconds,
+            refresh_ttl_seconds: DEFAULT_REFRESH_TOKEN_TTL_SECONDS.max(ttl_seconds),
         }
     }
 
+    /// refresh token(= auth セッション)の有効期間。アクセストークンより短くはしない。
+    /// セッションがアクセストークンより先に消えると、有効なトークンが失効扱いになるため。
+    pub fn with_refresh_ttl_seconds(mut self, refresh_ttl_seconds: i64) -> Self {
+        self.refresh_ttl_seconds = refresh_ttl_seconds.max(self.ttl_seconds);
+        self
+    }
+
     pub fn from_env() -> Result<Self> {
         let issuer = std::env::var("COMMUNITY_NODE_JWT_ISSUER")
             .ok()
@@ -51,7 +63,14 @@ impl JwtConfig {
             .transpose()
             .context("failed to parse COMMUNITY_NODE_JWT_TTL_SECONDS")?
             .unwrap_or(DEFAULT_TOKEN_TTL_SECONDS);
-        Ok(Self::new(issuer, secret, ttl_seconds))
+        let refresh_ttl_seconds = std::env::var("COMMUNITY_NODE_REFRESH_TOKEN_TTL_SECONDS")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| value.parse::<i64>())
+            .transpose()
+            .context("failed to parse COMMUNITY_NODE_REFRESH_TOKEN_TTL_SECONDS")?
+            .unwrap_or(DEFAULT_REFRESH_TOKEN_TTL_SECONDS);
+        Ok(Self::new(issuer, secret, ttl_seconds).with_refresh_ttl_seconds(refresh_ttl_seconds))
     }
 
     pub(crate) fn issuer(&self) -> &str {
@@ -62,6 +81,10 @@ impl JwtConfig {
         self.ttl_seconds
     }
 
+    pub(crate) fn refresh_ttl_seconds(&self) -> i64 {
+        self.refresh_ttl_seconds
+    }
+
     pub(crate) fn encoding_key(&self) -> EncodingKey {
         EncodingKey::from_secret(self.secret.as_bytes())
     }
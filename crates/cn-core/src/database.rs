@@ -43,15 +43,22 @@ pub async fn initialize_database_for_runtime(
 pub async fn ensure_database_ready(pool: &PgPool) -> Result<()> {
     for (schema, table) in [
         ("cn_auth", "auth_challenges"),
+        ("cn_auth", "auth_sessions"),
+        ("cn_auth", "refresh_tokens"),
         ("cn_user", "subscriber_accounts"),
         ("cn_user", "policy_consents"),
+        ("cn_user", "subscriber_sanctions"),
+        ("cn_user", "admission_vouches"),
         ("cn_admin", "policies"),
         ("cn_admin", "service_configs"),
         ("cn_admin", "invite_codes"),
         ("cn_admin", "admission_allowlist"),
         ("cn_admin", "operator_actions"),
+        ("cn_admin", "operators"),
+        ("cn_admin", "operator_sessions"),
         ("cn_bootstrap", "bootstrap_nodes"),
         ("cn_bootstrap", "peer_registrations"),
+        ("cn_bootstrap", "topic_rendezvous_peers"),
         ("cn_safety", "signed_moderation_events"),
         ("cn_safety", "risk_signals"),
         ("cn_safety", "scan_verdicts"),
@@ -60,6 +67,8 @@ pub async fn ensure_database_ready(pool: &PgPool) -> Result<()> {
         ("cn_index", "channel_secrets"),
         ("cn_index", "index_entries"),
         ("cn_trust", "relation_optouts"),
+        ("cn_trust", "relation_edges"),
+        ("cn_trust", "relation_clusters"),
     ] {
         let exists = sqlx::query_scalar::<_, bool>(
             "SELECT EXISTS (
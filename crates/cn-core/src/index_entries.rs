@@ -25,6 +25,23 @@ use sqlx::postgres::{PgPool, PgRow};
 use crate::index_scope::IndexScopeKind;
 use kukuri_cn_safety_runtime::MemorySafetyArtifactStore;
 
+/// ingest 時に同じ共有 replica 上で観測した engagement(recommendation ranking の入力)。
+///
+/// node が sync できた範囲の観測値で canonical な数ではない。再 ingest のたびに上書きする。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EntryEngagement {
+    /// 有効な(削除されていない)反応の数。
+    pub reactions: u64,
+    /// 有効な返信 post の数。
+    pub replies: u64,
+}
+
+impl EntryEngagement {
+    pub fn total(&self) -> u64 {
+        self.reactions.saturating_add(self.replies)
+    }
+}
+
 /// 真実源に upsert する index entry（`cn-indexer` の投影 entry と同じ内容 + verdict 参照）。
 ///
 /// 検索対象 text は持たない（text は ArcadeDB 投影のみに置き、replica の再 ingest + 再 scan で
@@ -44,6 +61,8 @@ pub struct NewIndexEntry {
     pub verdict_action: String,
     /// index 時点の critical フラグ（DB CHECK により false のみ通る）。
     pub critical: bool,
+    /// ingest 時点の engagement 観測値。
+    pub engagement: EntryEngagement,
 }
 
 /// 永続化された index entry。
@@ -58,6 +77,7 @@ pub struct StoredIndexEntry {
     pub verdict_id: String,
     pub verdict_action: String,
     pub critical: bool,
+    pub engagement: EntryEngagement,
     pub indexed_at: DateTime<Utc>,
 }
 
@@ -75,8 +95,8 @@ pub async fn upsert_index_entry(pool: &PgPool, entry: &NewIndexEntry) -> Result<
     let row = sqlx::query(
         "INSERT INTO cn_index.index_entries
             (scope_kind, scope_id, object_id, author_pubkey, created_at, source_replica_id,
-             verdict_id, verdict_action, critical)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             verdict_id, verdict_action, critical, reaction_count, reply_count)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
          ON CONFLICT (scope_kind, scope_id, object_id) DO UPDATE
          SET author_pubkey = EXCLUDED.author_pubkey,
              created_at = EXCLUDED.created_at,
@@ -84,9 +104,12 @@ pub async fn upsert_index_entry(pool: &PgPool, entry: &NewIndexEntry) -> Result<
              verdict_id = EXCLUDED.verdict_id,
              verdict_action = EXCLUDED.verdict_action,
              critical = EXCLUDED.critical,
+             reaction_count = EXCLUDED.reaction_count,
+             reply_count = EXCLUDED.reply_count,
              indexed_at = NOW()
          RETURNING scope_kind, scope_id, object_id, author_pubkey, created_at, source_replica_id,
-                   verdict_id, verdict_action, critical, indexed_at",
+                   verdict_id, verdict_action, critical, reaction_count, reply_count,
+                   indexed_at",
     )
     .bind(entry.scope_kind.as_str())
     .bind(&entry.scope_id)
@@ -97,6 +120,8 @@ pub async fn upsert_index_entry(pool: &PgPool, entry: &NewIndexEntry) -> Result<
     .bind(&entry.verdict_id)
     .bind(&entry.verdict_action)
     .bind(entry.critical)
+    .bind(count_to_db(entry.engagement.reactions))
+    .bind(count_to_db(entry.engagement.replies))
     .fetch_one(pool)
     .await?;
     index_entry_from_row(&row)
@@ -144,7 +169,7 @@ pub async fn get_index_entry(
 ) -> Result<Option<StoredIndexEntry>> {
     let row = sqlx::query(
         "SELECT scope_kind, scope_id, object_id, author_pubkey, created_at, source_replica_id,
-                verdict_id, verdict_action, critical, indexed_at
+                verdict_id, verdict_action, critical, reaction_count, reply_count, indexed_at
          FROM cn_index.index_entries
          WHERE scope_kind = $1 AND scope_id = $2 AND object_id = $3",
     )
@@ -202,6 +227,44 @@ pub async fn filter_surfaceable_objects(
         .collect()
 }
 
+/// 候補 `(scope_id, object_id)` の engagement 観測値を返す(recommendation ranking の入力)。
+///
+/// 真実源に無い候補は返さない(呼び出し側は engagement 0 として扱う)。surfacing の可否は
+/// 判定しないため、fail-closed gate を通った候補に対してだけ使うこと。
+pub async fn list_entry_engagement(
+    pool: &PgPool,
+    scope_kind: IndexScopeKind,
+    candidates: &[(String, String)],
+) -> Result<Vec<(String, String, EntryEngagement)>> {
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+    let scope_ids: Vec<&str> = candidates.iter().map(|(s, _)| s.as_str()).collect();
+    let object_ids: Vec<&str> = candidates.iter().map(|(_, o)| o.as_str()).collect();
+    let rows = sqlx::query(
+        "SELECT e.scope_id, e.object_id, e.reaction_count, e.reply_count
+         FROM UNNEST($2::text[], $3::text[]) AS candidate (scope_id, object_id)
+         JOIN cn_index.index_entries e
+           ON e.scope_kind = $1
+          AND e.scope_id = candidate.scope_id
+          AND e.object_id = candidate.object_id",
+    )
+    .bind(scope_kind.as_str())
+    .bind(&scope_ids)
+    .bind(&object_ids)
+    .fetch_all(pool)
+    .await?;
+    rows.iter()
+        .map(|row| {
+            Ok((
+                row.try_get::<String, _>("scope_id")?,
+                row.try_get::<String, _>("object_id")?,
+                engagement_from_row(row)?,
+            ))
+        })
+        .collect()
+}
+
 /// index 真実源への書き込み / 突合の抽象（#404）。
 ///
 /// 本番は Postgres（[`PgIndexEntryStore`]、`cn_index.index_entries` の DB 制約が fail-closed を
@@ -237,6 +300,13 @@ pub trait IndexEntryStore: Send + Sync {
     /// 常駐ワーカーが「サポート対象から外れたのに索引が残っている scope」を再起動をまたいで
     /// 検知し、索引解除するために使う。
     async fn list_scopes(&self) -> Result<Vec<(IndexScopeKind, String)>>;
+
+    /// 候補 `(scope_id, object_id)` の engagement 観測値(真実源に無い候補は含めない)。
+    async fn engagement(
+        &self,
+        scope_kind: IndexScopeKind,
+        candidates: &[(String, String)],
+    ) -> Result<Vec<(String, String, EntryEngagement)>>;
 }
 
 /// Postgres 実装。`cn_index.index_entries` の persist API に委譲する。
@@ -278,6 +348,14 @@ impl IndexEntryStore for PgIndexEntryStore {
         filter_surfaceable_objects(&self.pool, scope_kind, candidates).await
     }
 
+    async fn engagement(
+        &self,
+        scope_kind: IndexScopeKind,
+        candidates: &[(String, String)],
+    ) -> Result<Vec<(String, String, EntryEngagement)>> {
+        list_entry_engagement(&self.pool, scope_kind, candidates).await
+    }
+
     async fn list_scopes(&self) -> Result<Vec<(IndexScopeKind, String)>> {
         let rows = sqlx::query("SELECT DISTINCT scope_kind, scope_id FROM cn_index.index_entries")
             .fetch_all(&self.pool)
@@ -424,6 +502,22 @@ impl IndexEntryStore for MemoryIndexEntryStore {
         }
         Ok(scopes)
     }
+
+    async fn engagement(
+        &self,
+        scope_kind: IndexScopeKind,
+        candidates: &[(String, String)],
+    ) -> Result<Vec<(String, String, EntryEngagement)>> {
+        let entries = self.entries.lock().expect("entries mutex poisoned");
+        Ok(candidates
+            .iter()
+            .filter_map(|(scope_id, object_id)| {
+                entries
+                    .get(&(scope_kind, scope_id.clone(), object_id.clone()))
+                    .map(|entry| (scope_id.clone(), object_id.clone(), entry.engagement))
+            })
+            .collect())
+    }
 }
 
 fn index_entry_from_row(row: &PgRow) -> Result<StoredIndexEntry> {
@@ -437,6 +531,18 @@ fn index_entry_from_row(row: &PgRow) -> Result<StoredIndexEntry> {
         verdict_id: row.try_get("verdict_id")?,
         verdict_action: row.try_get("verdict_action")?,
         critical: row.try_get("critical")?,
+        engagement: engagement_from_row(row)?,
         indexed_at: row.try_get("indexed_at")?,
     })
 }
+
+fn engagement_from_row(row: &PgRow) -> Result<EntryEngagement> {
+    Ok(EntryEngagement {
+        reactions: row.try_get::<i64, _>("reaction_count")?.max(0) as u64,
+        replies: row.try_get::<i64, _>("reply_count")?.max(0) as u64,
+    })
+}
+
+fn count_to_db(count: u64) -> i64 {
+    i64::try_from(count).unwrap_or(i64::MAX)
+}
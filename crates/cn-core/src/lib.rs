@@ -11,9 +11,11 @@
 //! 新しいサーバ側機能は、まず上のどれに属するかを判定し、DB との合成・永続化に
 //! 該当する場合だけ本 crate へ足す(「何でも入る受け皿」に戻さない。WP-B10)。
 
+mod admin_operators;
 mod admission;
 mod appeal_reviews;
 mod auth;
+mod auth_sessions;
 mod bootstrap;
 mod co_participation;
 mod config;
@@ -28,31 +30,43 @@ mod readiness_activation;
 mod readiness_probe;
 mod readiness_runtime;
 mod relation_optouts;
+mod relation_store;
 mod rendezvous;
 mod reports;
 mod rollout;
 mod safety_appeals;
 mod safety_events;
 mod safety_runtime;
+mod sanctions;
 mod scan_verdicts;
 #[cfg(test)]
 mod tests;
 mod trust_inputs;
+mod vouches;
 
+pub use admin_operators::{
+    ADMIN_SESSION_TTL_SECONDS, AdminOperator, AdminSession, AuthenticatedOperator, OperatorRole,
+    get_admin_operator, list_admin_operators, load_admin_session, revoke_admin_session,
+    verify_admin_login,
+};
 pub use admission::{
     AdmissionConfig, AdmissionMode, AdmissionRejection, AllowlistEntry, BannedEntry,
-    InviteCodeSummary, add_allowlist, ban_subscriber, invite_code_hash, issue_invite_code,
-    list_allowlist, list_banned, list_invite_codes, load_admission_config, remove_allowlist,
-    revoke_invite_code, set_admission_mode, unban_subscriber,
+    InviteCodeSummary, MAX_REQUIRED_VOUCHES, VouchPolicy, add_allowlist, ban_subscriber,
+    invite_code_hash, issue_invite_code, list_allowlist, list_banned, list_invite_codes,
+    load_admission_config, remove_allowlist, revoke_invite_code, set_admission_mode,
+    set_vouch_policy, unban_subscriber,
 };
 pub use appeal_reviews::{
     AppealReview, AppealReviewOperation, AppealReviewReport, AppealReviewVersion,
     apply_appeal_review_action, get_appeal_review, list_appeal_reviews,
 };
 pub use auth::{
-    create_auth_challenge, require_bearer_identity, require_bearer_pubkey,
+    create_auth_challenge, refresh_access_token, require_bearer_identity, require_bearer_pubkey,
     verify_auth_envelope_and_issue_token,
 };
+pub use auth_sessions::{
+    AuthSession, list_auth_sessions, revoke_auth_session, revoke_subscriber_sessions,
+};
 pub use bootstrap::{
     load_bootstrap_nodes, load_bootstrap_seed_peers, refresh_bootstrap_peer_registration,
     upsert_bootstrap_node,
@@ -64,7 +78,8 @@ pub use config::{
     AUTH_CHALLENGE_TTL_SECONDS, AUTH_EVENT_MAX_SKEW_SECONDS, AuthMode, AuthRolloutConfig,
     BOOTSTRAP_PEER_REGISTRATION_TTL_SECONDS, COMMUNITY_NODE_ADMISSION_SERVICE_NAME,
     COMMUNITY_NODE_AUTH_SERVICE_NAME, COMMUNITY_NODE_DATABASE_INIT_MODE_ENV,
-    COMMUNITY_NODE_RENDEZVOUS_KEY_PREFIX_ENV, COMMUNITY_NODE_RENDEZVOUS_REDIS_URL_ENV,
+    COMMUNITY_NODE_RENDEZVOUS_BACKEND_ENV, COMMUNITY_NODE_RENDEZVOUS_KEY_PREFIX_ENV,
+    COMMUNITY_NODE_RENDEZVOUS_REDIS_URL_ENV, DEFAULT_REFRESH_TOKEN_TTL_SECONDS,
     DEFAULT_TOKEN_TTL_SECONDS, DatabaseInitMode, JwtConfig, TOPIC_RENDEZVOUS_TTL_SECONDS,
     USER_API_BEARER_CHALLENGE,
 };
@@ -76,9 +91,9 @@ pub use database::{
 pub use env::{parse_bool_env, parse_csv_env, parse_u32_env, parse_u64_env};
 pub use errors::{ApiError, ApiResult, auth_required_error, consent_required_error};
 pub use index_entries::{
-    IndexEntryStore, MemoryIndexEntryStore, NewIndexEntry, PgIndexEntryStore, StoredIndexEntry,
-    filter_surfaceable_objects, get_index_entry, remove_index_entry, remove_index_scope,
-    upsert_index_entry,
+    EntryEngagement, IndexEntryStore, MemoryIndexEntryStore, NewIndexEntry, PgIndexEntryStore,
+    StoredIndexEntry, filter_surfaceable_objects, get_index_entry, list_entry_engagement,
+    remove_index_entry, remove_index_scope, upsert_index_entry,
 };
 pub use index_scope::{
     ChannelSecret, ChannelSecretCipher, ChannelSecretConflict, IndexScopeKind, IndexingRequest,
@@ -101,10 +116,15 @@ pub use readiness_runtime::{
     latest_relation_analyze_run, record_relation_analyze_run,
 };
 pub use relation_optouts::{
-    clear_relation_optout, filter_relation_visible, get_relation_optout, is_relation_opted_out,
-    relation_pair_is_suppressed, set_relation_optout, should_suppress_relation_pair,
+    clear_relation_optout, filter_relation_listed, filter_relation_visible, get_relation_optout,
+    is_relation_opted_out, relation_pair_is_suppressed, set_relation_optout,
+    should_suppress_relation_pair,
+};
+pub use relation_store::PgRelationStore;
+pub use rendezvous::{
+    MemoryTopicRendezvousBackend, PgTopicRendezvousBackend, RedisTopicRendezvousBackend,
+    TopicRendezvousBackend, TopicRendezvousStore,
 };
-pub use rendezvous::TopicRendezvousStore;
 pub use reports::{
     COMMUNITY_NODE_REPORT_STATUS_RECEIVED, CommunityNodeReport, NewCommunityNodeReport,
     get_community_node_report, insert_community_node_appeal, insert_community_node_report,
@@ -117,12 +137,19 @@ pub use safety_appeals::{
     update_risk_signal_appeal_status, validate_optional_confidence, validate_optional_expires_at,
 };
 pub use safety_events::{
-    DistributionAudience, StoredModerationEvent, StoredRiskSignal, get_risk_signal,
-    get_signed_moderation_event, list_distributable_moderation_events,
+    DistributionAudience, ModerationEventCursor, StoredModerationEvent, StoredRiskSignal,
+    get_risk_signal, get_signed_moderation_event, list_distributable_moderation_events,
     list_distributable_risk_signals, list_risk_signals, list_risk_signals_for_target,
     list_risk_signals_for_user, list_signed_moderation_events, persist_risk_signal,
     persist_risk_signal_with_author, persist_signed_moderation_event,
 };
 pub use safety_runtime::{PgSafetyArtifactStore, resolve_safety_providers};
+pub use sanctions::{
+    MAX_SANCTION_REPORT_IDS, SanctionKind, SubscriberSanction, list_subscriber_sanctions,
+    require_writable,
+};
 pub use scan_verdicts::{StoredScanVerdict, get_scan_verdict, upsert_scan_verdict};
 pub use trust_inputs::{list_trust_risk_inputs, trust_risk_inputs_from};
+pub use vouches::{
+    AdmissionVouch, VouchWeightingUnavailable, list_admission_vouches, submit_vouch,
+};
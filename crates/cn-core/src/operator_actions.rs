@@ -11,8 +11,14 @@ use sqlx::Row;
 use sqlx::postgres::{PgPool, PgRow};
 use uuid::Uuid;
 
+use crate::admin_operators::OperatorRole;
 use crate::config::COMMUNITY_NODE_ADMISSION_SERVICE_NAME;
+use crate::sanctions::{
+    SanctionKind, insert_sanction, lift_sanction, validate_sanction_reason,
+    validate_sanction_report_ids,
+};
 use crate::{AdmissionConfig, AdmissionMode};
+use kukuri_cn_protocol::normalize_pubkey;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -49,6 +55,39 @@ pub enum AdminOperation {
         report_id: String,
         status: OperatorReportStatus,
     },
+    /// 運営者の追加・role 変更(`role: None` で削除)。`label` が `None` なら既存の表示名を保つ。
+    SetOperatorRole {
+        pubkey: String,
+        role: Option<OperatorRole>,
+        label: Option<String>,
+    },
+    /// 利用者への段階的な制裁。`expires_at` が `None` なら解除まで続く。
+    /// `report_ids` は根拠の通報で、操作記録にも残る。
+    ImposeSanction {
+        pubkey: String,
+        kind: SanctionKind,
+        reason: String,
+        expires_at: Option<DateTime<Utc>>,
+        report_ids: Vec<String>,
+    },
+    LiftSanction {
+        sanction_id: String,
+    },
+}
+
+impl AdminOperation {
+    /// この操作に必要な運営者 role。
+    pub fn required_role(&self) -> OperatorRole {
+        match self {
+            Self::SetReportStatus { .. }
+            | Self::ImposeSanction { .. }
+            | Self::LiftSanction { .. } => OperatorRole::Moderator,
+            Self::SetAdmissionMode { .. }
+            | Self::AddSupportedPublicTopic { .. }
+            | Self::RemoveSupportedPublicTopic { .. }
+            | Self::SetOperatorRole { .. } => OperatorRole::Admin,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -90,7 +129,10 @@ pub async fn apply_operator_action(
             .transpose()
             .context("failed to parse current admission config")?
             .unwrap_or_default();
-            let next = AdmissionConfig { mode: *mode };
+            let next = AdmissionConfig {
+                mode: *mode,
+                ..current
+            };
             sqlx::query(
                 "INSERT INTO cn_admin.service_configs (service_name, version, config_json)
                  VALUES ($1, 1, $2)
@@ -173,6 +215,112 @@ pub async fn apply_operator_action(
                 json!({ "status": status.as_str() }),
             )
         }
+        AdminOperation::SetOperatorRole {
+            pubkey,
+            role,
+            label,
+        } => {
+            let pubkey = normalize_pubkey(pubkey)?;
+            let current = sqlx::query_scalar::<_, String>(
+                "SELECT role FROM cn_admin.operators WHERE pubkey = $1 FOR UPDATE",
+            )
+            .bind(&pubkey)
+            .fetch_optional(&mut *tx)
+            .await?;
+            // 最後の admin を降格・削除すると画面からは誰も運営者を管理できなくなる。
+            if current.as_deref() == Some(OperatorRole::Admin.as_str())
+                && *role != Some(OperatorRole::Admin)
+            {
+                let admins = sqlx::query_scalar::<_, String>(
+                    "SELECT pubkey FROM cn_admin.operators WHERE role = 'admin' FOR UPDATE",
+                )
+                .fetch_all(&mut *tx)
+                .await?;
+                if admins.len() <= 1 {
+                    bail!("the last admin operator cannot be demoted or removed");
+                }
+            }
+            match role {
+                Some(role) => {
+                    sqlx::query(
+                        "INSERT INTO cn_admin.operators (pubkey, role, label)
+                         VALUES ($1, $2, $3)
+                         ON CONFLICT (pubkey) DO UPDATE
+                         SET role = EXCLUDED.role,
+                             label = COALESCE(EXCLUDED.label, cn_admin.operators.label),
+                             updated_at = NOW()",
+                    )
+                    .bind(&pubkey)
+                    .bind(role.as_str())
+                    .bind(label.as_deref().map(str::trim))
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                None => {
+                    sqlx::query("DELETE FROM cn_admin.operators WHERE pubkey = $1")
+                        .bind(&pubkey)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+            }
+            (
+                "operator.set_role",
+                "operator",
+                pubkey,
+                json!({ "role": current }),
+                json!({ "role": role.map(OperatorRole::as_str) }),
+            )
+        }
+        AdminOperation::ImposeSanction {
+            pubkey,
+            kind,
+            reason,
+            expires_at,
+            report_ids,
+        } => {
+            let pubkey = normalize_pubkey(pubkey)?;
+            let reason = validate_sanction_reason(reason)?;
+            let report_ids = validate_sanction_report_ids(report_ids)?;
+            let sanction = insert_sanction(
+                &mut tx,
+                actor,
+                pubkey.as_str(),
+                *kind,
+                reason,
+                *expires_at,
+                &report_ids,
+            )
+            .await?;
+            (
+                "sanction.impose",
+                "subscriber",
+                pubkey,
+                json!({ "sanction_id": null }),
+                json!({
+                    "sanction_id": sanction.id,
+                    "kind": sanction.kind.as_str(),
+                    "reason": sanction.reason,
+                    "expires_at": expires_at.map(|value| value.to_rfc3339()),
+                    "report_ids": sanction.report_ids,
+                }),
+            )
+        }
+        AdminOperation::LiftSanction { sanction_id } => {
+            let sanction_id = sanction_id.trim();
+            let sanction = lift_sanction(&mut tx, actor, sanction_id).await?;
+            (
+                "sanction.lift",
+                "subscriber",
+                sanction.pubkey,
+                json!({
+                    "sanction_id": sanction.id,
+                    "kind": sanction.kind.as_str(),
+                    "lifted": false,
+                    "report_ids": sanction.report_ids,
+                }),
+                json!({ "sanction_id": sanction.id, "lifted": true }),
+            )
+        }
     };
 
     let id = Uuid::new_v4().to_string();
@@ -213,6 +361,40 @@ pub fn validate_admin_operation(operation: &AdminOperation) -> Result<()> {
             }
             Ok(())
         }
+        AdminOperation::SetOperatorRole { pubkey, label, .. } => {
+            normalize_pubkey(pubkey)?;
+            if let Some(label) = label.as_deref() {
+                if label.trim().len() > 128 {
+                    bail!("operator label is too long");
+                }
+                if label.chars().any(char::is_control) {
+                    bail!("operator label must not contain control characters");
+                }
+            }
+            Ok(())
+        }
+        AdminOperation::ImposeSanction {
+            pubkey,
+            reason,
+            expires_at,
+            report_ids,
+            ..
+        } => {
+            normalize_pubkey(pubkey)?;
+            validate_sanction_reason(reason)?;
+            validate_sanction_report_ids(report_ids)?;
+            if expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+                bail!("sanction expiry must be in the future");
+            }
+            Ok(())
+        }
+        AdminOperation::LiftSanction { sanction_id } => {
+            let sanction_id = sanction_id.trim();
+            if sanction_id.is_empty() || sanction_id.len() > 128 {
+                bail!("sanction id must be 1-128 characters");
+            }
+            Ok(())
+        }
     }
 }
 
@@ -291,5 +473,34 @@ mod tests {
             })
             .is_err()
         );
+        assert!(
+            validate_admin_operation(&AdminOperation::SetOperatorRole {
+                pubkey: "not-a-pubkey".to_string(),
+                role: Some(OperatorRole::Viewer),
+                label: None,
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn operator_management_requires_admin_and_reports_require_moderator() {
+        assert_eq!(
+            AdminOperation::SetReportStatus {
+                report_id: "report-1".to_string(),
+                status: OperatorReportStatus::Reviewing,
+            }
+            .required_role(),
+            OperatorRole::Moderator
+        );
+        assert_eq!(
+            AdminOperation::SetOperatorRole {
+                pubkey: "a".repeat(64),
+                role: None,
+                label: None,
+            }
+            .required_role(),
+            OperatorRole::Admin
+        );
     }
 }
@@ -158,6 +158,41 @@ pub async fn filter_relation_visible(
     Ok(visible)
 }
 
+/// people search 用: 候補 pubkey のうち relation opt-out を選択していないものだけを入力順で返す。
+///
+/// people search は見知らぬ相手を探す surface なので、opt-out 済みの user は proximity に
+/// 関係なく一覧に載せない（viewer 本人は自分の検索結果に残す）。post surfacing の距離判定
+/// （[`filter_relation_visible`]）とは別に、呼び出し側が重ねて適用する。
+pub async fn filter_relation_listed(
+    pool: &PgPool,
+    viewer: &str,
+    pubkeys: &[String],
+) -> Result<Vec<String>> {
+    let viewer = normalize_pubkey(viewer)?;
+    let normalized: Vec<(String, String)> = pubkeys
+        .iter()
+        .map(|target| Ok((target.clone(), normalize_pubkey(target)?)))
+        .collect::<Result<_>>()?;
+    let targets: Vec<String> = normalized
+        .iter()
+        .map(|(_, target)| target.clone())
+        .collect();
+    let selected: std::collections::HashSet<String> = sqlx::query_as::<_, (String,)>(
+        "SELECT pubkey FROM cn_trust.relation_optouts WHERE pubkey = ANY($1)",
+    )
+    .bind(targets)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|(pubkey,)| pubkey)
+    .collect();
+    Ok(normalized
+        .into_iter()
+        .filter(|(_, target)| *target == viewer || !selected.contains(target))
+        .map(|(original, _)| original)
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::should_suppress_relation_pair;
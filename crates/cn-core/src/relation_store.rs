@@ -0,0 +1,140 @@
+//! relation graph の Postgres 実装（ADR 0026 §6.1。組み込み backend 用）。
+//!
+//! ArcadeDB を置かない構成（`COMMUNITY_NODE_INDEX_BACKEND=embedded`）で `RelationStore` を
+//! `cn_trust.relation_edges` / `cn_trust.relation_clusters` に置く。edge は正規化ペア（辞書順
+//! (小, 大)）で 1 行だけ持ち、どちら側から読んでも同じ feature が見える（対称性 contract）。
+//! proximity の合成は backend 非依存の [`proximity_from_features`] で行い、in-memory / ArcadeDB
+//! 実装と同一の score を返す。
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use sqlx::types::Json;
+
+use kukuri_cn_trust::{
+    ClusterRef, EdgeFeatures, Proximity, RelationStore, proximity_from_features,
+};
+
+/// 対称 edge の正規化ペア（辞書順で (小, 大)）。
+fn normalized_pair<'a>(a: &'a str, b: &'a str) -> (&'a str, &'a str) {
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+/// Postgres 上の relation graph。
+#[derive(Clone, Debug)]
+pub struct PgRelationStore {
+    pool: PgPool,
+}
+
+impl PgRelationStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RelationStore for PgRelationStore {
+    async fn upsert_edge(&self, from: &str, to: &str, features: &EdgeFeatures) -> Result<()> {
+        let (low, high) = normalized_pair(from, to);
+        sqlx::query(
+            "INSERT INTO cn_trust.relation_edges (pubkey_low, pubkey_high, features)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (pubkey_low, pubkey_high)
+             DO UPDATE SET features = EXCLUDED.features, updated_at = NOW()",
+        )
+        .bind(low)
+        .bind(high)
+        .bind(Json(features))
+        .execute(&self.pool)
+        .await
+        .context("failed to upsert relation edge")?;
+        Ok(())
+    }
+
+    async fn pairwise_proximity(&self, viewer: &str, target: &str) -> Result<Option<Proximity>> {
+        let (low, high) = normalized_pair(viewer, target);
+        let row: Option<(Json<EdgeFeatures>,)> = sqlx::query_as(
+            "SELECT features FROM cn_trust.relation_edges
+             WHERE pubkey_low = $1 AND pubkey_high = $2",
+        )
+        .bind(low)
+        .bind(high)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|(Json(features),)| proximity_from_features(&features)))
+    }
+
+    async fn pairwise_proximities(
+        &self,
+        viewer: &str,
+        targets: &[String],
+    ) -> Result<BTreeMap<String, Proximity>> {
+        if targets.is_empty() {
+            return Ok(BTreeMap::new());
+        }
+        let rows: Vec<(String, Json<EdgeFeatures>)> = sqlx::query_as(
+            "SELECT pubkey_high AS pubkey, features FROM cn_trust.relation_edges
+             WHERE pubkey_low = $1 AND pubkey_high = ANY($2)
+             UNION ALL
+             SELECT pubkey_low AS pubkey, features FROM cn_trust.relation_edges
+             WHERE pubkey_high = $1 AND pubkey_low = ANY($2) AND pubkey_low <> $1",
+        )
+        .bind(viewer)
+        .bind(targets)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(pubkey, Json(features))| (pubkey, proximity_from_features(&features)))
+            .collect())
+    }
+
+    async fn neighbors(&self, viewer: &str, k: usize) -> Result<Vec<String>> {
+        let rows: Vec<(String, Json<EdgeFeatures>)> = sqlx::query_as(
+            "SELECT pubkey_high AS pubkey, features FROM cn_trust.relation_edges
+             WHERE pubkey_low = $1
+             UNION ALL
+             SELECT pubkey_low AS pubkey, features FROM cn_trust.relation_edges
+             WHERE pubkey_high = $1 AND pubkey_low <> $1",
+        )
+        .bind(viewer)
+        .fetch_all(&self.pool)
+        .await?;
+        let mut scored: Vec<(f64, String)> = rows
+            .into_iter()
+            .map(|(pubkey, Json(features))| (proximity_from_features(&features).score, pubkey))
+            .collect();
+        // proximity 降順・同点は pubkey 辞書順（in-memory 実装と同じ決定論的な並び）。
+        scored.sort_by(|(sa, pa), (sb, pb)| {
+            sb.partial_cmp(sa)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| pa.cmp(pb))
+        });
+        Ok(scored.into_iter().take(k).map(|(_, p)| p).collect())
+    }
+
+    async fn cluster_of(&self, pubkey: &str) -> Result<Option<ClusterRef>> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT cluster_id FROM cn_trust.relation_clusters WHERE pubkey = $1")
+                .bind(pubkey)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(row.map(|(cluster,)| ClusterRef(cluster)))
+    }
+
+    async fn set_cluster(&self, pubkey: &str, cluster: &ClusterRef) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO cn_trust.relation_clusters (pubkey, cluster_id) VALUES ($1, $2)
+             ON CONFLICT (pubkey)
+             DO UPDATE SET cluster_id = EXCLUDED.cluster_id, updated_at = NOW()",
+        )
+        .bind(pubkey)
+        .bind(&cluster.0)
+        .execute(&self.pool)
+        .await
+        .context("failed to set relation cluster")?;
+        Ok(())
+    }
+}
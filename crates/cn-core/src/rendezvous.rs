@@ -1,40 +1,89 @@
-use std::collections::{BTreeMap, BTreeSet};
+//! topic rendezvous(topic ごとの生存 peer の短命な登録簿)。
+//!
+//! 保存先は [`TopicRendezvousBackend`] で差し替える。複数インスタンス構成向けの Redis
+//! ([`RedisTopicRendezvousBackend`])、user-api が既に持つ Postgres pool を使う
+//! [`PgTopicRendezvousBackend`]、単一インスタンス構成とテスト向けの in-process
+//! ([`MemoryTopicRendezvousBackend`])がある。topic key の正規化と応答の組み立ては
+//! [`TopicRendezvousStore`] が共通に行い、backend は登録・離脱・生存 peer の読み出しだけを担う。
 
-use anyhow::{Result, bail};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow, bail};
+use async_trait::async_trait;
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use sqlx::postgres::PgPool;
 
 use crate::config::TOPIC_RENDEZVOUS_TTL_SECONDS;
 use kukuri_cn_protocol::models::CommunityNodeSeedPeer;
-
-#[derive(Clone, Debug)]
-pub struct TopicRendezvousStore {
-    client: redis::Client,
-    key_prefix: String,
-    ttl_seconds: u64,
-}
-
 use kukuri_cn_protocol::{
     TopicRendezvousCandidate, TopicRendezvousHeartbeat, TopicRendezvousHeartbeatResponse,
     TopicRendezvousTopicResponse,
 };
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct StoredRendezvousPeer {
-    endpoint_id: String,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    addr_hint: Option<String>,
+/// topic rendezvous の保存先。
+///
+/// `active_topics` / `leaves` は正規化・重複除去済みの topic key。実装は `peer` を
+/// `active_topics` のそれぞれへ `ttl_seconds` 秒登録し、`leaves` から外したうえで、各 active
+/// topic の生存 peer を返す(`peer` 自身を含んでよい。除外は呼び出し側が行う)。
+#[async_trait]
+pub trait TopicRendezvousBackend: Send + Sync {
+    async fn heartbeat(
+        &self,
+        peer: &CommunityNodeSeedPeer,
+        active_topics: &[String],
+        leaves: &[String],
+        ttl_seconds: u64,
+    ) -> Result<BTreeMap<String, Vec<CommunityNodeSeedPeer>>>;
+
+    /// 期限切れの登録を消す。読み出しは常に期限で絞るので、これは保存量を抑えるための掃除で
+    /// 正しさには関わらない。key の TTL で消える backend は何もしない。
+    async fn prune_expired(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// 期限切れ登録を掃除する周期。
+const TOPIC_RENDEZVOUS_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+pub struct TopicRendezvousStore {
+    backend: Arc<dyn TopicRendezvousBackend>,
+    ttl_seconds: u64,
+}
+
+impl std::fmt::Debug for TopicRendezvousStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TopicRendezvousStore")
+            .field("ttl_seconds", &self.ttl_seconds)
+            .finish_non_exhaustive()
+    }
 }
 
 impl TopicRendezvousStore {
+    /// Redis backend で構築する。
     pub fn new(redis_url: &str, key_prefix: impl Into<String>) -> Result<Self> {
-        let client = redis::Client::open(redis_url)?;
-        let key_prefix = normalize_key_prefix(key_prefix.into().as_str())?;
-        Ok(Self {
-            client,
-            key_prefix,
+        Ok(Self::with_backend(Arc::new(
+            RedisTopicRendezvousBackend::new(redis_url, key_prefix)?,
+        )))
+    }
+
+    pub fn postgres(pool: PgPool) -> Self {
+        Self::with_backend(Arc::new(PgTopicRendezvousBackend::new(pool)))
+    }
+
+    pub fn in_memory() -> Self {
+        Self::with_backend(Arc::new(MemoryTopicRendezvousBackend::new()))
+    }
+
+    pub fn with_backend(backend: Arc<dyn TopicRendezvousBackend>) -> Self {
+        Self {
+            backend,
             ttl_seconds: TOPIC_RENDEZVOUS_TTL_SECONDS,
-        })
+        }
     }
 
     pub async fn heartbeat(
@@ -47,52 +96,135 @@ impl TopicRendezvousStore {
         let refreshes = normalize_topic_keys(heartbeat.refreshes)?;
         let leaves = normalize_topic_keys(heartbeat.leaves)?;
         let mut active_topics = BTreeSet::new();
-        active_topics.extend(joins.iter().cloned());
-        active_topics.extend(refreshes.iter().cloned());
+        active_topics.extend(joins);
+        active_topics.extend(refreshes);
+        let active_topics = active_topics.into_iter().collect::<Vec<_>>();
+
+        let mut members = self
+            .backend
+            .heartbeat(&endpoint, &active_topics, &leaves, self.ttl_seconds)
+            .await?;
+        let topics = active_topics
+            .into_iter()
+            .map(|topic_key| {
+                let mut peers = members
+                    .remove(topic_key.as_str())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|peer| peer.endpoint_id != endpoint.endpoint_id)
+                    .map(|peer| TopicRendezvousCandidate {
+                        endpoint_id: peer.endpoint_id,
+                        addr_hint: peer.addr_hint,
+                        relay_urls: relay_urls.to_vec(),
+                    })
+                    .collect::<Vec<_>>();
+                peers.sort_by(|left, right| left.endpoint_id.cmp(&right.endpoint_id));
+                peers.dedup_by(|left, right| left.endpoint_id == right.endpoint_id);
+                TopicRendezvousTopicResponse { topic_key, peers }
+            })
+            .collect();
+
+        Ok(TopicRendezvousHeartbeatResponse {
+            expires_in_seconds: self.ttl_seconds,
+            topics,
+        })
+    }
+
+    /// 期限切れ登録の定期掃除 task を起動する。heartbeat の経路では表全体を掃かない。
+    pub fn spawn_prune_task(&self) {
+        let backend = Arc::clone(&self.backend);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TOPIC_RENDEZVOUS_PRUNE_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(error) = backend.prune_expired().await {
+                    tracing::warn!(error = %error, "topic rendezvous の期限切れ登録を掃除できませんでした");
+                }
+            }
+        });
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StoredRendezvousPeer {
+    endpoint_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    addr_hint: Option<String>,
+}
+
+/// Redis(Valkey)backend。peer ごとの key と topic ごとの set を TTL つきで持つ。
+#[derive(Clone, Debug)]
+pub struct RedisTopicRendezvousBackend {
+    client: redis::Client,
+    key_prefix: String,
+}
+
+impl RedisTopicRendezvousBackend {
+    pub fn new(redis_url: &str, key_prefix: impl Into<String>) -> Result<Self> {
+        let client = redis::Client::open(redis_url)?;
+        let key_prefix = normalize_key_prefix(key_prefix.into().as_str())?;
+        Ok(Self { client, key_prefix })
+    }
+
+    fn topic_key(&self, topic_key: &str) -> String {
+        format!("{}:topic:{topic_key}", self.key_prefix)
+    }
 
+    fn peer_key(&self, endpoint_id: &str) -> String {
+        format!("{}:peer:{endpoint_id}", self.key_prefix)
+    }
+}
+
+#[async_trait]
+impl TopicRendezvousBackend for RedisTopicRendezvousBackend {
+    async fn heartbeat(
+        &self,
+        peer: &CommunityNodeSeedPeer,
+        active_topics: &[String],
+        leaves: &[String],
+        ttl_seconds: u64,
+    ) -> Result<BTreeMap<String, Vec<CommunityNodeSeedPeer>>> {
         let mut connection = self.client.get_multiplexed_async_connection().await?;
         let stored_peer = serde_json::to_string(&StoredRendezvousPeer {
-            endpoint_id: endpoint.endpoint_id.clone(),
-            addr_hint: endpoint.addr_hint.clone(),
+            endpoint_id: peer.endpoint_id.clone(),
+            addr_hint: peer.addr_hint.clone(),
         })?;
 
         if !active_topics.is_empty() {
             let _: () = connection
                 .set_ex(
-                    self.peer_key(endpoint.endpoint_id.as_str()),
+                    self.peer_key(peer.endpoint_id.as_str()),
                     stored_peer,
-                    self.ttl_seconds,
+                    ttl_seconds,
                 )
                 .await?;
         }
 
-        for topic_key in &active_topics {
+        for topic_key in active_topics {
             let key = self.topic_key(topic_key);
             let _: usize = connection
-                .sadd(key.as_str(), endpoint.endpoint_id.as_str())
-                .await?;
-            let _: bool = connection
-                .expire(key.as_str(), self.ttl_seconds as i64)
+                .sadd(key.as_str(), peer.endpoint_id.as_str())
                 .await?;
+            let _: bool = connection.expire(key.as_str(), ttl_seconds as i64).await?;
         }
 
-        for topic_key in &leaves {
+        for topic_key in leaves {
             let key = self.topic_key(topic_key);
             let _: usize = connection
-                .srem(key.as_str(), endpoint.endpoint_id.as_str())
+                .srem(key.as_str(), peer.endpoint_id.as_str())
                 .await?;
         }
 
-        let mut topics = Vec::with_capacity(active_topics.len());
+        let mut members = BTreeMap::new();
         for topic_key in active_topics {
-            let key = self.topic_key(topic_key.as_str());
+            let key = self.topic_key(topic_key);
             let mut endpoint_ids: Vec<String> = connection.smembers(key.as_str()).await?;
             endpoint_ids.sort();
             endpoint_ids.dedup();
 
             let mut peers = Vec::new();
             for endpoint_id in endpoint_ids {
-                if endpoint_id == endpoint.endpoint_id {
+                if endpoint_id == peer.endpoint_id {
                     continue;
                 }
                 let peer_json: Option<String> =
@@ -101,29 +233,173 @@ impl TopicRendezvousStore {
                     let _: usize = connection.srem(key.as_str(), endpoint_id.as_str()).await?;
                     continue;
                 };
-                let peer: StoredRendezvousPeer = serde_json::from_str(peer_json.as_str())?;
-                peers.push(TopicRendezvousCandidate {
-                    endpoint_id: peer.endpoint_id,
-                    addr_hint: peer.addr_hint,
-                    relay_urls: relay_urls.to_vec(),
+                let stored: StoredRendezvousPeer = serde_json::from_str(peer_json.as_str())?;
+                peers.push(CommunityNodeSeedPeer {
+                    endpoint_id: stored.endpoint_id,
+                    addr_hint: stored.addr_hint,
                 });
             }
-            peers.sort_by(|left, right| left.endpoint_id.cmp(&right.endpoint_id));
-            topics.push(TopicRendezvousTopicResponse { topic_key, peers });
+            members.insert(topic_key.clone(), peers);
         }
+        Ok(members)
+    }
+}
 
-        Ok(TopicRendezvousHeartbeatResponse {
-            expires_in_seconds: self.ttl_seconds,
-            topics,
-        })
+/// Postgres backend(`cn_bootstrap.topic_rendezvous_peers`)。
+///
+/// Redis を置かない小規模な node 向け。user-api の複数インスタンスでも同じ表を共有する。
+/// 期限切れ行は読み出しで除外し、実際の削除は [`TopicRendezvousStore::spawn_prune_task`] の
+/// 周期に任せる(heartbeat ごとに表全体を掃かない)。
+#[derive(Clone, Debug)]
+pub struct PgTopicRendezvousBackend {
+    pool: PgPool,
+}
+
+impl PgTopicRendezvousBackend {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
     }
+}
 
-    fn topic_key(&self, topic_key: &str) -> String {
-        format!("{}:topic:{topic_key}", self.key_prefix)
+#[async_trait]
+impl TopicRendezvousBackend for PgTopicRendezvousBackend {
+    async fn heartbeat(
+        &self,
+        peer: &CommunityNodeSeedPeer,
+        active_topics: &[String],
+        leaves: &[String],
+        ttl_seconds: u64,
+    ) -> Result<BTreeMap<String, Vec<CommunityNodeSeedPeer>>> {
+        let ttl_seconds = i64::try_from(ttl_seconds)?;
+        let mut tx = self.pool.begin().await?;
+        if !active_topics.is_empty() {
+            sqlx::query(
+                "INSERT INTO cn_bootstrap.topic_rendezvous_peers
+                    (topic_key, endpoint_id, addr_hint, expires_at)
+                 SELECT topic_key, $2, $3, NOW() + make_interval(secs => $4)
+                 FROM UNNEST($1::TEXT[]) AS topic_key
+                 ON CONFLICT (topic_key, endpoint_id) DO UPDATE
+                 SET addr_hint = EXCLUDED.addr_hint,
+                     expires_at = EXCLUDED.expires_at",
+            )
+            .bind(active_topics)
+            .bind(peer.endpoint_id.as_str())
+            .bind(peer.addr_hint.as_deref())
+            .bind(ttl_seconds as f64)
+            .execute(&mut *tx)
+            .await?;
+        }
+        if !leaves.is_empty() {
+            sqlx::query(
+                "DELETE FROM cn_bootstrap.topic_rendezvous_peers
+                 WHERE endpoint_id = $2 AND topic_key = ANY($1::TEXT[])",
+            )
+            .bind(leaves)
+            .bind(peer.endpoint_id.as_str())
+            .execute(&mut *tx)
+            .await?;
+        }
+        let rows = sqlx::query(
+            "SELECT topic_key, endpoint_id, addr_hint
+             FROM cn_bootstrap.topic_rendezvous_peers
+             WHERE topic_key = ANY($1::TEXT[]) AND expires_at > NOW()
+             ORDER BY topic_key ASC, endpoint_id ASC",
+        )
+        .bind(active_topics)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        let mut members: BTreeMap<String, Vec<CommunityNodeSeedPeer>> = BTreeMap::new();
+        for row in rows {
+            members
+                .entry(row.try_get("topic_key")?)
+                .or_default()
+                .push(CommunityNodeSeedPeer {
+                    endpoint_id: row.try_get("endpoint_id")?,
+                    addr_hint: row.try_get("addr_hint")?,
+                });
+        }
+        Ok(members)
     }
 
-    fn peer_key(&self, endpoint_id: &str) -> String {
-        format!("{}:peer:{endpoint_id}", self.key_prefix)
+    async fn prune_expired(&self) -> Result<()> {
+        sqlx::query(
+            "DELETE FROM cn_bootstrap.topic_rendezvous_peers
+             WHERE expires_at <= NOW()",
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+/// topic key → endpoint id → (addr hint, 期限)。
+type MemoryTopicMembers = HashMap<String, HashMap<String, (Option<String>, Instant)>>;
+
+/// in-process backend。
+///
+/// 登録簿はプロセス内にしか無いので、user-api を 1 インスタンスで動かす構成とテスト専用。
+/// 再起動で消えるが、client は TTL ごとに heartbeat し直すので次の周期で戻る。
+#[derive(Debug, Default)]
+pub struct MemoryTopicRendezvousBackend {
+    topics: Mutex<MemoryTopicMembers>,
+}
+
+impl MemoryTopicRendezvousBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TopicRendezvousBackend for MemoryTopicRendezvousBackend {
+    async fn heartbeat(
+        &self,
+        peer: &CommunityNodeSeedPeer,
+        active_topics: &[String],
+        leaves: &[String],
+        ttl_seconds: u64,
+    ) -> Result<BTreeMap<String, Vec<CommunityNodeSeedPeer>>> {
+        let now = Instant::now();
+        let expires_at = now + Duration::from_secs(ttl_seconds);
+        let mut topics = self
+            .topics
+            .lock()
+            .map_err(|_| anyhow!("topic rendezvous registry is poisoned"))?;
+        topics.retain(|_, peers| {
+            peers.retain(|_, (_, expires_at)| *expires_at > now);
+            !peers.is_empty()
+        });
+        for topic_key in active_topics {
+            topics.entry(topic_key.clone()).or_default().insert(
+                peer.endpoint_id.clone(),
+                (peer.addr_hint.clone(), expires_at),
+            );
+        }
+        for topic_key in leaves {
+            if let Some(peers) = topics.get_mut(topic_key) {
+                peers.remove(peer.endpoint_id.as_str());
+            }
+        }
+
+        let mut members = BTreeMap::new();
+        for topic_key in active_topics {
+            let peers = topics
+                .get(topic_key)
+                .map(|peers| {
+                    peers
+                        .iter()
+                        .map(|(endpoint_id, (addr_hint, _))| CommunityNodeSeedPeer {
+                            endpoint_id: endpoint_id.clone(),
+                            addr_hint: addr_hint.clone(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            members.insert(topic_key.clone(), peers);
+        }
+        Ok(members)
     }
 }
 
@@ -151,3 +427,80 @@ fn normalize_topic_key(value: &str) -> Result<String> {
     }
     Ok(trimmed.to_ascii_lowercase())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENDPOINT_A: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+    const ENDPOINT_B: &str = "2222222222222222222222222222222222222222222222222222222222222222";
+
+    fn heartbeat(endpoint_id: &str, joins: &[&str], leaves: &[&str]) -> TopicRendezvousHeartbeat {
+        TopicRendezvousHeartbeat {
+            endpoint_id: endpoint_id.to_string(),
+            addr_hint: None,
+            joins: joins.iter().map(|topic| topic.to_string()).collect(),
+            refreshes: Vec::new(),
+            leaves: leaves.iter().map(|topic| topic.to_string()).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn in_memory_store_returns_other_live_peers_and_honours_leaves() {
+        let store = TopicRendezvousStore::in_memory();
+        let topic = "A".repeat(64);
+
+        let first = store
+            .heartbeat(heartbeat(ENDPOINT_A, &[topic.as_str()], &[]), &[])
+            .await
+            .expect("first heartbeat");
+        assert_eq!(first.topics.len(), 1);
+        assert_eq!(first.topics[0].topic_key, "a".repeat(64));
+        assert!(first.topics[0].peers.is_empty());
+
+        let relays = vec!["https://relay.example".to_string()];
+        let second = store
+            .heartbeat(heartbeat(ENDPOINT_B, &[topic.as_str()], &[]), &relays)
+            .await
+            .expect("second heartbeat");
+        assert_eq!(second.topics[0].peers.len(), 1);
+        assert_eq!(second.topics[0].peers[0].endpoint_id, ENDPOINT_A);
+        assert_eq!(second.topics[0].peers[0].relay_urls, relays);
+
+        store
+            .heartbeat(heartbeat(ENDPOINT_A, &[], &[topic.as_str()]), &[])
+            .await
+            .expect("leave");
+        let after_leave = store
+            .heartbeat(heartbeat(ENDPOINT_B, &[topic.as_str()], &[]), &[])
+            .await
+            .expect("heartbeat after leave");
+        assert!(after_leave.topics[0].peers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn in_memory_backend_drops_expired_registrations() {
+        let backend = MemoryTopicRendezvousBackend::new();
+        let topic = vec!["b".repeat(64)];
+        let peer_a = CommunityNodeSeedPeer::new(ENDPOINT_A, None).expect("peer a");
+        let peer_b = CommunityNodeSeedPeer::new(ENDPOINT_B, None).expect("peer b");
+        backend
+            .heartbeat(&peer_a, &topic, &[], 0)
+            .await
+            .expect("expired registration");
+        let members = backend
+            .heartbeat(&peer_b, &topic, &[], 45)
+            .await
+            .expect("live registration");
+        assert_eq!(members[topic[0].as_str()], vec![peer_b]);
+    }
+
+    #[test]
+    fn topic_keys_must_be_opaque_hex() {
+        assert!(normalize_topic_key("not-a-topic").is_err());
+        assert_eq!(
+            normalize_topic_keys(vec!["C".repeat(64), "c".repeat(64)]).expect("keys"),
+            vec!["c".repeat(64)]
+        );
+    }
+}
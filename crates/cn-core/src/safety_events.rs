@@ -60,6 +60,48 @@ pub struct StoredModerationEvent {
     pub persisted_at: DateTime<Utc>,
 }
 
+/// 配布クエリの keyset 位置。`(persisted_at, id)` の降順で、この位置より古い event から返す。
+///
+/// offset と違い、先頭に新しい event が挿入されても続きのページがずれない。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModerationEventCursor {
+    pub persisted_at: DateTime<Utc>,
+    pub id: String,
+}
+
+impl ModerationEventCursor {
+    /// `event` の次(より古い側)から読む位置。
+    pub fn after(event: &StoredModerationEvent) -> Self {
+        Self {
+            persisted_at: event.persisted_at,
+            id: event.event.body.id.clone(),
+        }
+    }
+
+    /// `<persisted_at の unix マイクロ秒>:<event id>`。client は解釈せずそのまま返す。
+    pub fn encode(&self) -> String {
+        format!("{}:{}", self.persisted_at.timestamp_micros(), self.id)
+    }
+
+    pub fn decode(token: &str) -> Result<Self> {
+        let (micros, id) = token
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| anyhow!("moderation event cursor is malformed"))?;
+        let micros = micros
+            .parse::<i64>()
+            .context("moderation event cursor timestamp is malformed")?;
+        if id.is_empty() {
+            bail!("moderation event cursor id must not be empty");
+        }
+        Ok(Self {
+            persisted_at: DateTime::from_timestamp_micros(micros)
+                .ok_or_else(|| anyhow!("moderation event cursor timestamp is out of range"))?,
+            id: id.to_string(),
+        })
+    }
+}
+
 /// 永続化された risk signal。
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StoredRiskSignal {
@@ -164,27 +206,29 @@ pub async fn list_signed_moderation_events(
     rows.iter().map(moderation_event_from_row).collect()
 }
 
-/// 配布境界に従って配布可能な signed moderation event を返す。
+/// 配布境界に従って配布可能な signed moderation event を `(persisted_at, id)` の降順で返す。
 ///
 /// `local` は決して返さない。audience が `SubscribedNodes` なら `subscribed_nodes` + `public`、
-/// `Public` なら `public` のみ。
+/// `Public` なら `public` のみ。`before` があればその位置より古い event だけを返す。
 pub async fn list_distributable_moderation_events(
     pool: &PgPool,
     audience: DistributionAudience,
     limit: i64,
-    offset: i64,
+    before: Option<&ModerationEventCursor>,
 ) -> Result<Vec<StoredModerationEvent>> {
     let rows = sqlx::query(
         "SELECT id, issuer_node_id, target_type, target_id, action, reason_code, severity, basis,
                 visibility, confidence, policy_version, labels, signature, event_created_at, persisted_at
          FROM cn_safety.signed_moderation_events
          WHERE visibility = ANY($1)
-         ORDER BY persisted_at DESC
-         LIMIT $2 OFFSET $3",
+           AND ($3::timestamptz IS NULL OR (persisted_at, id) < ($3, $4))
+         ORDER BY persisted_at DESC, id DESC
+         LIMIT $2",
     )
     .bind(audience.allowed_visibilities())
     .bind(limit)
-    .bind(offset)
+    .bind(before.map(|cursor| cursor.persisted_at))
+    .bind(before.map(|cursor| cursor.id.as_str()))
     .fetch_all(pool)
     .await?;
     rows.iter().map(moderation_event_from_row).collect()
@@ -0,0 +1,332 @@
+//! 段階的な admission 制裁(期限付き利用停止 / 読み取り専用)。
+//!
+//! 永続 ban(`ban_subscriber`)は初回の spam には重すぎるため、期限と理由を持つ軽い制限を置く。
+//! どちらも node-local な補助機能の制限であり、kukuri network からの凍結ではない。
+//!
+//! - `suspended`: auth 発行(`evaluate_admission`)を拒否し、既存 token も
+//!   `require_bearer_identity` で即時失効させる。
+//! - `read_only`: auth と参照系は保ったまま、通報・索引要求・topic rendezvous heartbeat を拒否する。
+//!
+//! 付与と解除は `apply_operator_action` 経由で行い、根拠の通報 id と一緒に操作記録へ残す。
+
+use anyhow::{Result, bail};
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use sqlx::postgres::{PgPool, PgRow};
+use uuid::Uuid;
+
+use crate::errors::{ApiError, ApiResult};
+use kukuri_cn_protocol::{BearerIdentity, SUBSCRIBER_READ_ONLY_CODE, normalize_pubkey};
+
+/// 1 件の制裁に紐づけられる通報 id の上限。
+pub const MAX_SANCTION_REPORT_IDS: usize = 20;
+
+const MAX_SANCTION_REASON_CHARS: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SanctionKind {
+    /// 期限付きの利用停止。auth を含むすべての補助機能を止める。
+    Suspended,
+    /// 読み取り専用。通報・索引要求・rendezvous heartbeat だけを止める。
+    ReadOnly,
+}
+
+impl SanctionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Suspended => "suspended",
+            Self::ReadOnly => "read_only",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "suspended" => Ok(Self::Suspended),
+            "read_only" => Ok(Self::ReadOnly),
+            _ => bail!("unknown sanction kind `{value}`"),
+        }
+    }
+}
+
+/// 運営者向けの制裁記録。時刻は admission の他の一覧と同じく epoch 秒。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriberSanction {
+    pub id: String,
+    pub pubkey: String,
+    pub kind: SanctionKind,
+    pub reason: String,
+    pub report_ids: Vec<String>,
+    pub imposed_by: String,
+    pub imposed_at: i64,
+    pub expires_at: Option<i64>,
+    pub lifted_by: Option<String>,
+    pub lifted_at: Option<i64>,
+}
+
+impl SubscriberSanction {
+    /// 解除されておらず、期限前なら有効。
+    pub fn is_active_at(&self, now: i64) -> bool {
+        self.lifted_at.is_none() && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+/// 制裁を一覧する。`include_inactive = false` なら有効なものだけを返す。
+pub async fn list_subscriber_sanctions(
+    pool: &PgPool,
+    pubkey: Option<&str>,
+    include_inactive: bool,
+) -> Result<Vec<SubscriberSanction>> {
+    let pubkey = pubkey.map(normalize_pubkey).transpose()?;
+    let rows = sqlx::query(
+        "SELECT id, subscriber_pubkey, kind, reason, report_ids, imposed_by, imposed_at,
+                expires_at, lifted_by, lifted_at
+         FROM cn_user.subscriber_sanctions
+         WHERE ($1::TEXT IS NULL OR subscriber_pubkey = $1)
+           AND ($2 OR (lifted_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())))
+         ORDER BY imposed_at DESC, id ASC",
+    )
+    .bind(pubkey)
+    .bind(include_inactive)
+    .fetch_all(pool)
+    .await?;
+    rows.iter().map(sanction_from_row).collect()
+}
+
+/// 読み取り専用の制裁中なら 403 を返す。状態を変える補助機能の入口で呼ぶ。
+pub fn require_writable(identity: &BearerIdentity) -> ApiResult<()> {
+    if identity.read_only {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            SUBSCRIBER_READ_ONLY_CODE,
+            "this account is temporarily read-only on this community node",
+        ));
+    }
+    Ok(())
+}
+
+/// admission 評価用。有効な利用停止があるか。
+pub(crate) async fn is_suspended(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    pubkey: &str,
+) -> Result<bool> {
+    let suspended = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (
+             SELECT 1 FROM cn_user.subscriber_sanctions
+             WHERE subscriber_pubkey = $1
+               AND kind = 'suspended'
+               AND lifted_at IS NULL
+               AND (expires_at IS NULL OR expires_at > NOW())
+         )",
+    )
+    .bind(pubkey)
+    .fetch_one(&mut **tx)
+    .await?;
+    Ok(suspended)
+}
+
+pub(crate) fn validate_sanction_reason(reason: &str) -> Result<&str> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        bail!("sanction reason must not be empty");
+    }
+    if reason.chars().count() > MAX_SANCTION_REASON_CHARS {
+        bail!("sanction reason must be at most {MAX_SANCTION_REASON_CHARS} characters");
+    }
+    if reason
+        .chars()
+        .any(|value| value.is_control() && value != '\n')
+    {
+        bail!("sanction reason must not contain control characters");
+    }
+    Ok(reason)
+}
+
+pub(crate) fn validate_sanction_report_ids(report_ids: &[String]) -> Result<Vec<String>> {
+    if report_ids.len() > MAX_SANCTION_REPORT_IDS {
+        bail!("a sanction can reference at most {MAX_SANCTION_REPORT_IDS} reports");
+    }
+    let mut normalized = Vec::with_capacity(report_ids.len());
+    for report_id in report_ids {
+        let report_id = report_id.trim();
+        if report_id.is_empty() || report_id.len() > 128 {
+            bail!("report id must be 1-128 characters");
+        }
+        if !normalized.iter().any(|existing| existing == report_id) {
+            normalized.push(report_id.to_string());
+        }
+    }
+    Ok(normalized)
+}
+
+/// 制裁を記録する。通報 id は `cn_admin.reports` に存在するものだけを受け付ける。
+pub(crate) async fn insert_sanction(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    actor: &str,
+    pubkey: &str,
+    kind: SanctionKind,
+    reason: &str,
+    expires_at: Option<DateTime<Utc>>,
+    report_ids: &[String],
+) -> Result<SubscriberSanction> {
+    if expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+        bail!("sanction expiry must be in the future");
+    }
+    let known =
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM cn_admin.reports WHERE id = ANY($1)")
+            .bind(report_ids)
+            .fetch_one(&mut **tx)
+            .await?;
+    if known != report_ids.len() as i64 {
+        bail!("some referenced reports were not found");
+    }
+    let row = sqlx::query(
+        "INSERT INTO cn_user.subscriber_sanctions
+            (id, subscriber_pubkey, kind, reason, report_ids, imposed_by, expires_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)
+         RETURNING id, subscriber_pubkey, kind, reason, report_ids, imposed_by, imposed_at,
+                   expires_at, lifted_by, lifted_at",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(pubkey)
+    .bind(kind.as_str())
+    .bind(reason)
+    .bind(report_ids)
+    .bind(actor)
+    .bind(expires_at)
+    .fetch_one(&mut **tx)
+    .await?;
+    sanction_from_row(&row)
+}
+
+/// 制裁を解除する。解除済み・未登録は拒否し、解除前の記録を返す。
+pub(crate) async fn lift_sanction(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    actor: &str,
+    sanction_id: &str,
+) -> Result<SubscriberSanction> {
+    let row = sqlx::query(
+        "SELECT id, subscriber_pubkey, kind, reason, report_ids, imposed_by, imposed_at,
+                expires_at, lifted_by, lifted_at
+         FROM cn_user.subscriber_sanctions
+         WHERE id = $1
+         FOR UPDATE",
+    )
+    .bind(sanction_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+    let Some(row) = row else {
+        bail!("sanction `{sanction_id}` was not found");
+    };
+    let current = sanction_from_row(&row)?;
+    if current.lifted_at.is_some() {
+        bail!("sanction `{sanction_id}` has already been lifted");
+    }
+    sqlx::query(
+        "UPDATE cn_user.subscriber_sanctions
+         SET lifted_by = $2, lifted_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(sanction_id)
+    .bind(actor)
+    .execute(&mut **tx)
+    .await?;
+    Ok(current)
+}
+
+fn sanction_from_row(row: &PgRow) -> Result<SubscriberSanction> {
+    let kind: String = row.try_get("kind")?;
+    Ok(SubscriberSanction {
+        id: row.try_get("id")?,
+        pubkey: row.try_get("subscriber_pubkey")?,
+        kind: SanctionKind::parse(kind.as_str())?,
+        reason: row.try_get("reason")?,
+        report_ids: row.try_get("report_ids")?,
+        imposed_by: row.try_get("imposed_by")?,
+        imposed_at: row.try_get::<DateTime<Utc>, _>("imposed_at")?.timestamp(),
+        expires_at: row
+            .try_get::<Option<DateTime<Utc>>, _>("expires_at")?
+            .map(|value| value.timestamp()),
+        lifted_by: row.try_get("lifted_by")?,
+        lifted_at: row
+            .try_get::<Option<DateTime<Utc>>, _>("lifted_at")?
+            .map(|value| value.timestamp()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(read_only: bool) -> BearerIdentity {
+        BearerIdentity {
+            pubkey: "a".repeat(64),
+            endpoint_id: None,
+            read_only,
+            session_id: None,
+        }
+    }
+
+    #[test]
+    fn sanction_kind_wire_names_are_stable() {
+        for kind in [SanctionKind::Suspended, SanctionKind::ReadOnly] {
+            assert_eq!(
+                serde_json::to_value(kind).unwrap(),
+                serde_json::Value::String(kind.as_str().to_string())
+            );
+            assert_eq!(SanctionKind::parse(kind.as_str()).unwrap(), kind);
+        }
+        assert!(SanctionKind::parse("banned").is_err());
+    }
+
+    #[test]
+    fn sanction_activity_follows_expiry_and_lift() {
+        let mut sanction = SubscriberSanction {
+            id: "s-1".to_string(),
+            pubkey: "a".repeat(64),
+            kind: SanctionKind::Suspended,
+            reason: "spam".to_string(),
+            report_ids: Vec::new(),
+            imposed_by: "cn-cli".to_string(),
+            imposed_at: 100,
+            expires_at: Some(200),
+            lifted_by: None,
+            lifted_at: None,
+        };
+        assert!(sanction.is_active_at(199));
+        assert!(!sanction.is_active_at(200));
+        sanction.expires_at = None;
+        assert!(sanction.is_active_at(i64::MAX));
+        sanction.lifted_at = Some(150);
+        assert!(!sanction.is_active_at(160));
+    }
+
+    #[test]
+    fn sanction_inputs_are_validated() {
+        assert!(validate_sanction_reason("  ").is_err());
+        assert!(validate_sanction_reason("spam\u{7}").is_err());
+        assert_eq!(
+            validate_sanction_reason(" 初回の spam\n連投 ").unwrap(),
+            "初回の spam\n連投"
+        );
+        assert!(validate_sanction_reason(&"x".repeat(501)).is_err());
+
+        assert_eq!(
+            validate_sanction_report_ids(&[" r-1 ".to_string(), "r-1".to_string()]).unwrap(),
+            vec!["r-1".to_string()]
+        );
+        assert!(validate_sanction_report_ids(&[String::new()]).is_err());
+        assert!(validate_sanction_report_ids(&vec!["r".to_string(); 21]).is_err());
+    }
+
+    #[test]
+    fn read_only_identity_is_rejected_with_stable_code() {
+        assert!(require_writable(&identity(false)).is_ok());
+        let response = axum::response::IntoResponse::into_response(
+            require_writable(&identity(true)).unwrap_err(),
+        );
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,352 @@
+//! 既存 member の署名つき推薦(vouch)による admission。
+//!
+//! 招待コードを運営者が手で配らずに済むよう、admit 済みの member が候補者を推薦し、
+//! 推薦の重みの合計が `VouchPolicy::required_vouches` に達した候補者を auth 時に admit する
+//! (`evaluate_admission`)。node-local な補助機能提供の可否判断であり、network 上の信頼表明ではない。
+//!
+//! 近接度の重み付け(`VouchPolicy::proximity_weighting`)が有効なら、同じ候補者を既に推薦した
+//! member との relation proximity が高いほど後続の推薦を軽くする。互いに近い sybil cluster が
+//! 何人で推薦しても、ほぼ 1 人分にしか数えない。重みは受理時点で確定する。
+//!
+//! 重みは 1/1000 単位に丸めて記録し、合計と閾値の比較も整数で行う。f64 のまま足すと
+//! 0.1 を 10 回足しても 1 に届かないような誤差で、閾値ちょうどの候補者を取りこぼす。
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Row;
+use sqlx::postgres::PgPool;
+
+use crate::admission::load_admission_config;
+use crate::config::AUTH_EVENT_MAX_SKEW_SECONDS;
+use kukuri_cn_protocol::{
+    AdmissionVouchResponse, VOUCH_ENVELOPE_KIND, VOUCH_SCOPE, first_tag_value, normalize_http_url,
+    normalize_pubkey,
+};
+use kukuri_cn_trust::RelationStore;
+use kukuri_core::{KukuriAuthEnvelopeContentV1, KukuriEnvelope};
+
+/// 推薦 1 人分の重み(1/1000 単位)。
+const VOUCH_WEIGHT_SCALE: i64 = 1000;
+
+/// 推薦者の集合が近接度の計算中に変わった場合に計算し直す回数。
+const VOUCH_WEIGHT_ATTEMPTS: usize = 3;
+
+/// 運営者向けの推薦記録。
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdmissionVouch {
+    pub voucher_pubkey: String,
+    pub candidate_pubkey: String,
+    pub weight: f64,
+    pub created_at: i64,
+}
+
+/// 近接度の重み付けが有効なのに relation graph が構成されていない。
+///
+/// 重み 1 に縮退すると sybil 対策が黙って外れるため、推薦の受理自体を拒否する(fail-closed)。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VouchWeightingUnavailable;
+
+impl fmt::Display for VouchWeightingUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("vouch weighting requires a relation graph, which is not configured")
+    }
+}
+
+impl std::error::Error for VouchWeightingUnavailable {}
+
+/// 推薦を検証して記録し、候補者の現在の合計重みを返す。
+///
+/// `voucher_pubkey` は bearer 認証済みの pubkey で、封筒の署名者と一致しなければならない。
+/// 同じ推薦者からの再提出は記録済みの重みをそのまま返す(冪等)。
+pub async fn submit_vouch(
+    pool: &PgPool,
+    public_base_url: &str,
+    voucher_pubkey: &str,
+    vouch_envelope_json: &Value,
+    relation: Option<&dyn RelationStore>,
+) -> Result<AdmissionVouchResponse> {
+    let public_base_url = normalize_http_url(public_base_url)?;
+    let envelope = serde_json::from_value::<KukuriEnvelope>(vouch_envelope_json.clone())
+        .context("invalid vouch envelope json")?;
+    envelope
+        .verify()
+        .context("vouch envelope signature verification failed")?;
+    if envelope.kind != VOUCH_ENVELOPE_KIND {
+        bail!("vouch envelope kind mismatch");
+    }
+    let content = serde_json::from_str::<KukuriAuthEnvelopeContentV1>(&envelope.content)
+        .context("invalid vouch envelope content")?;
+    if content.scope != VOUCH_SCOPE {
+        bail!("vouch envelope scope mismatch");
+    }
+    if (Utc::now().timestamp() - envelope.created_at).abs() > AUTH_EVENT_MAX_SKEW_SECONDS {
+        bail!("vouch envelope is stale");
+    }
+    let capability_url = first_tag_value(&envelope, "capability_url")
+        .ok_or_else(|| anyhow!("missing capability_url tag"))?;
+    if capability_url != public_base_url {
+        bail!("capability_url tag mismatch");
+    }
+    let voucher = normalize_pubkey(envelope.pubkey.as_str())?;
+    if voucher != normalize_pubkey(voucher_pubkey)? {
+        bail!("vouch envelope must be signed by the authenticated member");
+    }
+    let candidate = normalize_pubkey(
+        first_tag_value(&envelope, "candidate").ok_or_else(|| anyhow!("missing candidate tag"))?,
+    )?;
+    if candidate == voucher {
+        bail!("a member cannot vouch for themselves");
+    }
+
+    let policy = load_admission_config(pool).await?.vouch;
+    let relation = match (policy.proximity_weighting, relation) {
+        (true, None) => return Err(VouchWeightingUnavailable.into()),
+        (true, Some(relation)) => Some(relation),
+        (false, _) => None,
+    };
+
+    // relation graph の参照は advisory lock の外で行い、lock 中は既存推薦者の集合が
+    // 計算時から変わっていないことだけを確かめる。変わっていれば計算し直す。
+    let mut attempts = 0;
+    let (mut tx, weight) = loop {
+        attempts += 1;
+        let proximities = match relation {
+            Some(relation) => {
+                let mut proximities = HashMap::new();
+                for other in list_vouchers(pool, candidate.as_str()).await? {
+                    if other == voucher {
+                        continue;
+                    }
+                    let proximity = relation
+                        .pairwise_proximity(voucher.as_str(), other.as_str())
+                        .await?;
+                    proximities.insert(other, proximity.map_or(0.0, |proximity| proximity.score));
+                }
+                Some(proximities)
+            }
+            None => None,
+        };
+
+        let mut tx = pool.begin().await?;
+        // 同じ候補者への同時推薦が互いを見ずに重みを確定しないよう、候補者単位で直列化する。
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('cn_admission_vouch:' || $1))")
+            .bind(&candidate)
+            .execute(&mut *tx)
+            .await?;
+        if !is_admitted_member(&mut tx, voucher.as_str()).await? {
+            bail!("only admitted members of this community node can vouch");
+        }
+        if is_admitted_member(&mut tx, candidate.as_str()).await? {
+            bail!("candidate is already a member of this community node");
+        }
+
+        let rows = sqlx::query(
+            "SELECT voucher_pubkey, weight
+             FROM cn_user.admission_vouches
+             WHERE candidate_pubkey = $1",
+        )
+        .bind(&candidate)
+        .fetch_all(&mut *tx)
+        .await?;
+        let mut existing_weight = None;
+        let mut other_vouchers = HashSet::with_capacity(rows.len());
+        for row in rows {
+            let pubkey: String = row.try_get("voucher_pubkey")?;
+            if pubkey == voucher {
+                existing_weight = Some(row.try_get::<f64, _>("weight")?);
+            } else {
+                other_vouchers.insert(pubkey);
+            }
+        }
+        if let Some(weight) = existing_weight {
+            break (tx, weight);
+        }
+        let weight = match proximities {
+            Some(proximities) => {
+                if other_vouchers
+                    .iter()
+                    .any(|other| !proximities.contains_key(other))
+                {
+                    if attempts < VOUCH_WEIGHT_ATTEMPTS {
+                        continue;
+                    }
+                    bail!("candidate vouches changed concurrently; retry");
+                }
+                vouch_weight(
+                    other_vouchers
+                        .iter()
+                        .filter_map(|other| proximities.get(other).copied()),
+                )
+            }
+            None => 1.0,
+        };
+        let weight = milli_weight_to_f64(vouch_milli_weight(weight));
+        sqlx::query(
+            "INSERT INTO cn_user.admission_vouches
+                (voucher_pubkey, candidate_pubkey, weight, envelope_id, envelope_json)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&voucher)
+        .bind(&candidate)
+        .bind(weight)
+        .bind(envelope.id.as_str())
+        .bind(vouch_envelope_json)
+        .execute(&mut *tx)
+        .await?;
+        break (tx, weight);
+    };
+    let total_milli_weight = vouch_total_milli_weight(&mut tx, candidate.as_str()).await?;
+    tx.commit().await?;
+
+    Ok(AdmissionVouchResponse {
+        candidate_pubkey: candidate,
+        weight,
+        total_weight: milli_weight_to_f64(total_milli_weight),
+        required_weight: milli_weight_to_f64(required_milli_weight(policy.required_vouches)),
+    })
+}
+
+/// 推薦を一覧する。`candidate` を指定するとその候補者への推薦だけを返す。
+pub async fn list_admission_vouches(
+    pool: &PgPool,
+    candidate: Option<&str>,
+) -> Result<Vec<AdmissionVouch>> {
+    let candidate = candidate.map(normalize_pubkey).transpose()?;
+    let rows = sqlx::query(
+        "SELECT voucher_pubkey, candidate_pubkey, weight, created_at
+         FROM cn_user.admission_vouches
+         WHERE ($1::TEXT IS NULL OR candidate_pubkey = $1)
+         ORDER BY candidate_pubkey ASC, created_at ASC",
+    )
+    .bind(candidate)
+    .fetch_all(pool)
+    .await?;
+    rows.iter()
+        .map(|row| {
+            Ok(AdmissionVouch {
+                voucher_pubkey: row.try_get("voucher_pubkey")?,
+                candidate_pubkey: row.try_get("candidate_pubkey")?,
+                weight: row.try_get("weight")?,
+                created_at: row
+                    .try_get::<chrono::DateTime<Utc>, _>("created_at")?
+                    .timestamp(),
+            })
+        })
+        .collect()
+}
+
+/// admission 評価用。現在も active で利用停止中でない推薦者の重みだけを 1/1000 単位で合計する。
+///
+/// 推薦後に ban・停止された member の推薦は数えない。
+pub(crate) async fn vouch_total_milli_weight(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    candidate: &str,
+) -> Result<i64> {
+    let total = sqlx::query_scalar::<_, i64>(
+        "SELECT COALESCE(SUM(ROUND(v.weight * 1000)), 0)::BIGINT
+         FROM cn_user.admission_vouches v
+         JOIN cn_user.subscriber_accounts s ON s.subscriber_pubkey = v.voucher_pubkey
+         WHERE v.candidate_pubkey = $1
+           AND s.status = 'active'
+           AND s.admitted
+           AND NOT EXISTS (
+               SELECT 1 FROM cn_user.subscriber_sanctions x
+               WHERE x.subscriber_pubkey = v.voucher_pubkey
+                 AND x.kind = 'suspended'
+                 AND x.lifted_at IS NULL
+                 AND (x.expires_at IS NULL OR x.expires_at > NOW())
+           )",
+    )
+    .bind(candidate)
+    .fetch_one(&mut **tx)
+    .await?;
+    Ok(total)
+}
+
+/// 合計重みが `required_vouches` 人分に達したか。
+pub(crate) fn meets_required_vouches(total_milli_weight: i64, required_vouches: u32) -> bool {
+    total_milli_weight >= required_milli_weight(required_vouches)
+}
+
+fn required_milli_weight(required_vouches: u32) -> i64 {
+    i64::from(required_vouches) * VOUCH_WEIGHT_SCALE
+}
+
+fn vouch_milli_weight(weight: f64) -> i64 {
+    (weight * VOUCH_WEIGHT_SCALE as f64).round() as i64
+}
+
+fn milli_weight_to_f64(milli_weight: i64) -> f64 {
+    milli_weight as f64 / VOUCH_WEIGHT_SCALE as f64
+}
+
+async fn list_vouchers(pool: &PgPool, candidate: &str) -> Result<Vec<String>> {
+    let vouchers = sqlx::query_scalar::<_, String>(
+        "SELECT voucher_pubkey
+         FROM cn_user.admission_vouches
+         WHERE candidate_pubkey = $1",
+    )
+    .bind(candidate)
+    .fetch_all(pool)
+    .await?;
+    Ok(vouchers)
+}
+
+async fn is_admitted_member(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    pubkey: &str,
+) -> Result<bool> {
+    let admitted = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (
+             SELECT 1 FROM cn_user.subscriber_accounts
+             WHERE subscriber_pubkey = $1 AND status = 'active' AND admitted
+         )",
+    )
+    .bind(pubkey)
+    .fetch_one(&mut **tx)
+    .await?;
+    Ok(admitted)
+}
+
+/// 既存推薦者との近接度から推薦の重みを決める。最も近い既存推薦者との proximity 分だけ軽くする。
+fn vouch_weight(proximities: impl IntoIterator<Item = f64>) -> f64 {
+    let closest = proximities
+        .into_iter()
+        .filter(|score| score.is_finite())
+        .fold(0.0, f64::max);
+    (1.0 - closest).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_and_unrelated_vouches_count_fully() {
+        assert_eq!(vouch_weight([]), 1.0);
+        assert_eq!(vouch_weight([0.0, 0.0]), 1.0);
+    }
+
+    #[test]
+    fn vouch_weight_is_discounted_by_closest_existing_voucher() {
+        let weight = vouch_weight([0.2, 0.75, 0.1]);
+        assert!((weight - 0.25).abs() < f64::EPSILON, "{weight}");
+        assert_eq!(vouch_weight([1.0]), 0.0);
+        assert_eq!(vouch_weight([f64::NAN, 0.5]), 0.5);
+    }
+
+    #[test]
+    fn milli_weights_reach_the_threshold_without_float_drift() {
+        let total = (0..10).map(|_| vouch_milli_weight(0.1)).sum::<i64>();
+        assert!((0..10).map(|_| 0.1).sum::<f64>() < 1.0);
+        assert!(meets_required_vouches(total, 1));
+        assert!(!meets_required_vouches(total - 1, 1));
+        assert_eq!(vouch_milli_weight(0.25), 250);
+        assert_eq!(milli_weight_to_f64(1250), 1.25);
+    }
+}
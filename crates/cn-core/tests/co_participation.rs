@@ -59,6 +59,7 @@ fn entry(
         verdict_id: verdict_id.to_string(),
         verdict_action: "allow".to_string(),
         critical: false,
+        engagement: Default::default(),
     }
 }
 
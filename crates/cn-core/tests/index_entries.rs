@@ -9,9 +9,10 @@
 
 use anyhow::Result;
 use kukuri_cn_core::{
-    IndexScopeKind, NewIndexEntry, PgSafetyArtifactStore, TestDatabase, connect_postgres,
-    filter_surfaceable_objects, get_index_entry, get_scan_verdict, initialize_database,
-    remove_index_entry, remove_index_scope, upsert_index_entry, upsert_scan_verdict,
+    EntryEngagement, IndexScopeKind, NewIndexEntry, PgSafetyArtifactStore, TestDatabase,
+    connect_postgres, filter_surfaceable_objects, get_index_entry, get_scan_verdict,
+    initialize_database, list_entry_engagement, remove_index_entry, remove_index_scope,
+    upsert_index_entry, upsert_scan_verdict,
 };
 use kukuri_cn_safety::provider::{ProviderScanRequest, SubjectKind};
 use kukuri_cn_safety::{
@@ -59,6 +60,7 @@ fn entry(scope_id: &str, object_id: &str, verdict_id: &str) -> NewIndexEntry {
         verdict_id: verdict_id.to_string(),
         verdict_action: "allow".to_string(),
         critical: false,
+        engagement: Default::default(),
     }
 }
 
@@ -225,11 +227,30 @@ async fn index_entry_upsert_is_idempotent_and_deindexable() -> Result<()> {
         // 同一 (scope, object) への再 upsert は行を増やさず更新する。
         let mut updated = entry("rust", "post-1", allow_1.id.as_str());
         updated.author_pubkey = "author-updated".to_string();
+        updated.engagement = EntryEngagement {
+            reactions: 3,
+            replies: 1,
+        };
         upsert_index_entry(&pool, &updated).await?;
         let stored = get_index_entry(&pool, IndexScopeKind::PublicTopic, "rust", "post-1")
             .await?
             .expect("entry exists");
         assert_eq!(stored.author_pubkey, "author-updated");
+        assert_eq!(stored.engagement, updated.engagement);
+        // engagement は再 ingest の観測値で上書きされ、真実源に無い候補は返らない。
+        let engagement = list_entry_engagement(
+            &pool,
+            IndexScopeKind::PublicTopic,
+            &[
+                ("rust".to_string(), "post-1".to_string()),
+                ("rust".to_string(), "post-missing".to_string()),
+            ],
+        )
+        .await?;
+        assert_eq!(
+            engagement,
+            vec![("rust".to_string(), "post-1".to_string(), updated.engagement)]
+        );
 
         upsert_index_entry(&pool, &entry("rust", "post-2", allow_2.id.as_str())).await?;
 
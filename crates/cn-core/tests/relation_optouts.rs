@@ -2,14 +2,15 @@
 //!
 //! `KUKURI_CN_RUN_INTEGRATION_TESTS=1` のときだけ実 DB に接続して実行する。
 //! - `relation_visibility_choice_is_user_controlled_and_reversible`: set → clear の往復・冪等性。
+//! - `relation_optout_keeps_user_out_of_people_listing`: people search では opt-out 済み user を距離に関係なく載せない。
 //! - opt-out は trust 入力（`list_trust_risk_inputs`）へ影響しない（troll 判定回避の手段にしない）。
 
 use anyhow::Result;
 
 use kukuri_cn_core::{
-    TestDatabase, clear_relation_optout, connect_postgres, filter_relation_visible,
-    get_relation_optout, initialize_database, is_relation_opted_out, list_trust_risk_inputs,
-    persist_risk_signal, set_relation_optout,
+    TestDatabase, clear_relation_optout, connect_postgres, filter_relation_listed,
+    filter_relation_visible, get_relation_optout, initialize_database, is_relation_opted_out,
+    list_trust_risk_inputs, persist_risk_signal, set_relation_optout,
 };
 use kukuri_cn_safety::{
     Basis, RiskSignalTarget, SafetyCategory, SafetyRiskSignal, Severity, Visibility,
@@ -96,6 +97,47 @@ async fn relation_visibility_choice_is_user_controlled_and_reversible() -> Resul
     result
 }
 
+#[tokio::test]
+async fn relation_optout_keeps_user_out_of_people_listing() -> Result<()> {
+    let Some(admin_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-core relation optout test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let database = TestDatabase::create(admin_url.as_str(), "cn_optout_listing").await?;
+    let pool = connect_postgres(database.database_url.as_str()).await?;
+    let result = async {
+        initialize_database(&pool).await?;
+
+        let candidates = vec![
+            PUBKEY_A.to_string(),
+            PUBKEY_B.to_string(),
+            PUBKEY_C.to_string(),
+        ];
+        let listed = filter_relation_listed(&pool, PUBKEY_C, &candidates).await?;
+        assert_eq!(listed, candidates);
+
+        // opt-out した本人は他人の people search に出ないが、自分の検索結果には残る。
+        set_relation_optout(&pool, PUBKEY_A).await?;
+        set_relation_optout(&pool, PUBKEY_C).await?;
+        let listed = filter_relation_listed(&pool, PUBKEY_C, &candidates).await?;
+        assert_eq!(listed, vec![PUBKEY_B.to_string(), PUBKEY_C.to_string()]);
+
+        clear_relation_optout(&pool, PUBKEY_A).await?;
+        let listed = filter_relation_listed(&pool, PUBKEY_B, &candidates).await?;
+        assert_eq!(listed, vec![PUBKEY_A.to_string(), PUBKEY_B.to_string()]);
+
+        assert!(
+            filter_relation_listed(&pool, "not-a-pubkey", &candidates)
+                .await
+                .is_err()
+        );
+        anyhow::Ok(())
+    }
+    .await;
+    database.cleanup().await?;
+    result
+}
+
 #[tokio::test]
 async fn relation_optout_does_not_affect_trust_inputs() -> Result<()> {
     let Some(admin_url) = integration_test_admin_database_url() else {
@@ -9,9 +9,9 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use kukuri_cn_core::{
-    DistributionAudience, PgSafetyArtifactStore, TestDatabase, connect_postgres,
-    dispute_risk_signal, get_risk_signal, get_signed_moderation_event, initialize_database,
-    list_distributable_moderation_events, list_distributable_risk_signals,
+    DistributionAudience, ModerationEventCursor, PgSafetyArtifactStore, TestDatabase,
+    connect_postgres, dispute_risk_signal, get_risk_signal, get_signed_moderation_event,
+    initialize_database, list_distributable_moderation_events, list_distributable_risk_signals,
     list_risk_signals_for_target, list_trust_risk_inputs, persist_risk_signal,
     persist_signed_moderation_event, update_risk_signal_appeal_status,
 };
@@ -175,7 +175,7 @@ async fn moderation_event_distribution_excludes_local() -> Result<()> {
             &pool,
             DistributionAudience::SubscribedNodes,
             50,
-            0,
+            None,
         )
         .await?;
         let ids: Vec<&str> = subscribed
@@ -188,11 +188,48 @@ async fn moderation_event_distribution_excludes_local() -> Result<()> {
 
         // public audience は public のみ。
         let public =
-            list_distributable_moderation_events(&pool, DistributionAudience::Public, 50, 0)
+            list_distributable_moderation_events(&pool, DistributionAudience::Public, 50, None)
                 .await?;
         let public_ids: Vec<&str> = public.iter().map(|e| e.event.body.id.as_str()).collect();
         assert_eq!(public_ids, vec!["evt-public"]);
 
+        // keyset cursor は先頭への挿入でずれず、続きのページは重複も欠落もしない。
+        let first_page = list_distributable_moderation_events(
+            &pool,
+            DistributionAudience::SubscribedNodes,
+            1,
+            None,
+        )
+        .await?;
+        let cursor = ModerationEventCursor::decode(
+            ModerationEventCursor::after(&first_page[0])
+                .encode()
+                .as_str(),
+        )?;
+        assert_eq!(cursor, ModerationEventCursor::after(&first_page[0]));
+        persist_signed_moderation_event(
+            &pool,
+            &issue_signed_event(
+                event_body(&issuer, "evt-newer", "bafy-newer", Visibility::Public),
+                &signer,
+            ),
+        )
+        .await?;
+        let rest = list_distributable_moderation_events(
+            &pool,
+            DistributionAudience::SubscribedNodes,
+            50,
+            Some(&cursor),
+        )
+        .await?;
+        let mut walked: Vec<&str> = first_page
+            .iter()
+            .chain(rest.iter())
+            .map(|e| e.event.body.id.as_str())
+            .collect();
+        walked.sort_unstable();
+        assert_eq!(walked, vec!["evt-public", "evt-subscribed"]);
+
         Ok::<(), anyhow::Error>(())
     }
     .await;
@@ -52,7 +52,7 @@ use kukuri_cn_safety_runtime::{
 use kukuri_cn_safety_vlm::{
     CapabilityProfile, VlmCredentials, VlmModerationProvider, VlmProviderConfig, VlmResponseFormat,
 };
-use kukuri_cn_user_api::{UserApiConfig, app_router, build_state};
+use kukuri_cn_user_api::{RendezvousBackendConfig, UserApiConfig, app_router, build_state};
 use kukuri_core::{
     AssetRef, AssetRole, BlobHash, KukuriKeys, KukuriMediaManifestV1, MediaManifestItem,
     ObjectVisibility, PayloadRef, ReplicaId, TopicId, build_media_manifest_envelope,
@@ -506,8 +506,10 @@ impl E2eStack {
         let state = build_state(&UserApiConfig {
             bind_addr: addr,
             database_url: database.database_url.clone(),
-            rendezvous_redis_url: rendezvous_redis_url(),
-            rendezvous_key_prefix: format!("cn:e2e:{prefix}"),
+            rendezvous: RendezvousBackendConfig::Redis {
+                url: rendezvous_redis_url(),
+                key_prefix: format!("cn:e2e:{prefix}"),
+            },
             base_url: api_base_url.clone(),
             public_base_url: api_base_url.clone(),
             connectivity_urls: vec![format!("http://{}", relay.http_addr())],
@@ -15,6 +15,7 @@ use anyhow::Result;
 use kukuri_cn_core::{IndexScopeKind, inspect_index_integrity};
 use kukuri_cn_e2e::{E2eStack, arachnid_scanned_media_body};
 use kukuri_cn_indexer::projection::{IndexProjection, IndexedEntry};
+use kukuri_cn_protocol::IndexEntryKind;
 use reqwest::{Client, StatusCode};
 
 const TINY_JPEG: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, 0x4A, 0x46];
@@ -61,6 +62,9 @@ async fn disallowed_content_never_surfaces_and_verdict_flip_hides_entries() -> R
             text: "tokio ghost residue".to_string(),
             created_at: 1_700_000_000,
             source_replica_id: format!("topic::{}", stack.topic_id),
+            entry_kind: IndexEntryKind::Post,
+            profile: None,
+            session: None,
         })
         .await?;
 
@@ -156,6 +156,7 @@ async fn trust_appeal_and_relation_graph_work_end_to_end() -> Result<()> {
                 verdict_id: verdict.id,
                 verdict_action: "allow".to_string(),
                 critical: false,
+                engagement: Default::default(),
             })
             .await?;
     }
@@ -11,20 +11,21 @@
 use anyhow::{Context, Result, bail};
 use async_trait::async_trait;
 use reqwest::Client;
-use serde_json::{Value, json};
+use serde_json::{Map, Value, json};
 
 use kukuri_cn_core::IndexScopeKind;
+use kukuri_cn_protocol::{IndexEntryKind, IndexSortOrder};
 
 use crate::config::ArcadeDbConfig;
 use crate::projection::{IndexProjection, IndexedEntry};
-use crate::query::IndexQuery;
+use crate::query::{IndexPage, IndexQuery, IndexQueryFilter};
 
 /// index 投影 document の ArcadeDB type 名。
 const ENTRY_TYPE: &str = "IndexedEntry";
 
 /// `SELECT` で取り出す投影 entry の列（`IndexedEntry` の serde フィールドと一致させる）。
 const ENTRY_COLUMNS: &str = "scope_kind, scope_id, object_id, author_pubkey, text, created_at, \
-     source_replica_id";
+     source_replica_id, entry_kind, profile, session";
 
 /// ArcadeDB HTTP command client（`/api/v1/command/<database>`）。
 ///
@@ -116,6 +117,9 @@ impl ArcadeDbProjection {
             ("text", "STRING"),
             ("created_at", "LONG"),
             ("source_replica_id", "STRING"),
+            ("entry_kind", "STRING"),
+            ("profile", "MAP"),
+            ("session", "MAP"),
         ] {
             self.command(
                 "sql",
@@ -181,7 +185,8 @@ impl IndexProjection for ArcadeDbProjection {
         let command = format!(
             "UPDATE {ENTRY_TYPE} SET scope_kind = :scope_kind, scope_id = :scope_id, \
              object_id = :object_id, author_pubkey = :author_pubkey, text = :text, \
-             created_at = :created_at, source_replica_id = :source_replica_id \
+             created_at = :created_at, source_replica_id = :source_replica_id, \
+             entry_kind = :entry_kind, profile = :profile, session = :session \
              UPSERT WHERE scope_kind = :scope_kind AND scope_id = :scope_id \
              AND object_id = :object_id"
         );
@@ -196,6 +201,9 @@ impl IndexProjection for ArcadeDbProjection {
                 "text": entry.text,
                 "created_at": entry.created_at,
                 "source_replica_id": entry.source_replica_id,
+                "entry_kind": entry.entry_kind.as_str(),
+                "profile": entry.profile,
+                "session": entry.session,
             }),
         )
         .await?;
@@ -284,91 +292,167 @@ impl IndexProjection for ArcadeDbProjection {
     }
 }
 
+impl ArcadeDbProjection {
+    /// 投影 entry の SELECT を組み立てて実行し、`limit + 1` 件の window から page を作る。
+    ///
+    /// `conditions` / `params` は呼び出し側の固有条件（全文検索 / scope）。絞り込み条件と keyset
+    /// 位置は [`filter_conditions`] が足す。
+    async fn select_page(
+        &self,
+        mut conditions: Vec<String>,
+        mut params: Map<String, Value>,
+        filter: &IndexQueryFilter,
+        limit: usize,
+    ) -> Result<IndexPage> {
+        conditions.extend(filter_conditions(filter, &mut params));
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {} ", conditions.join(" AND "))
+        };
+        let command = format!(
+            "SELECT {ENTRY_COLUMNS} FROM {ENTRY_TYPE} {where_clause}ORDER BY {} LIMIT {}",
+            order_clause(filter.order),
+            limit.saturating_add(1)
+        );
+        let value = self
+            .command_with_params("sql", &command, Value::Object(params))
+            .await?;
+        Ok(IndexPage::from_window(entries_from_result(&value)?, limit))
+    }
+}
+
 #[async_trait]
 impl IndexQuery for ArcadeDbProjection {
-    async fn search_scope(
+    async fn search_scope_page(
         &self,
         scope_kind: IndexScopeKind,
         scope_id: &str,
         query: &str,
+        filter: &IndexQueryFilter,
         limit: usize,
-    ) -> Result<Vec<IndexedEntry>> {
+    ) -> Result<IndexPage> {
         // Lucene 全文 index（`SEARCH_INDEX`）+ scope 絞り込み。query は Lucene クエリ構文。
-        let command = format!(
-            "SELECT {ENTRY_COLUMNS} FROM {ENTRY_TYPE} \
-             WHERE SEARCH_INDEX('{ENTRY_TYPE}[text]', :query) \
-             AND scope_kind = :scope_kind AND scope_id = :scope_id \
-             ORDER BY created_at DESC LIMIT {limit}"
+        let mut params = Map::new();
+        params.insert("query".to_string(), json!(query));
+        params.insert(
+            "scope_kind".to_string(),
+            json!(Self::scope_kind_str(scope_kind)),
         );
-        let value = self
-            .command_with_params(
-                "sql",
-                &command,
-                json!({
-                    "query": query,
-                    "scope_kind": Self::scope_kind_str(scope_kind),
-                    "scope_id": scope_id,
-                }),
-            )
-            .await?;
-        entries_from_result(&value)
+        params.insert("scope_id".to_string(), json!(scope_id));
+        self.select_page(
+            vec![
+                format!("SEARCH_INDEX('{ENTRY_TYPE}[text]', :query)"),
+                "scope_kind = :scope_kind".to_string(),
+                "scope_id = :scope_id".to_string(),
+            ],
+            params,
+            filter,
+            limit,
+        )
+        .await
     }
 
-    async fn search_all(&self, query: &str, limit: usize) -> Result<Vec<IndexedEntry>> {
-        let command = format!(
-            "SELECT {ENTRY_COLUMNS} FROM {ENTRY_TYPE} \
-             WHERE SEARCH_INDEX('{ENTRY_TYPE}[text]', :query) \
-             ORDER BY created_at DESC LIMIT {limit}"
-        );
-        let value = self
-            .command_with_params("sql", &command, json!({ "query": query }))
-            .await?;
-        entries_from_result(&value)
+    async fn search_all_page(
+        &self,
+        query: &str,
+        filter: &IndexQueryFilter,
+        limit: usize,
+    ) -> Result<IndexPage> {
+        let mut params = Map::new();
+        params.insert("query".to_string(), json!(query));
+        self.select_page(
+            vec![format!("SEARCH_INDEX('{ENTRY_TYPE}[text]', :query)")],
+            params,
+            filter,
+            limit,
+        )
+        .await
     }
 
-    async fn list_recent(
+    async fn list_recent_page(
         &self,
         scope: Option<(IndexScopeKind, &str)>,
+        filter: &IndexQueryFilter,
         limit: usize,
-    ) -> Result<Vec<IndexedEntry>> {
-        let value = match scope {
-            Some((scope_kind, scope_id)) => {
-                let command = format!(
-                    "SELECT {ENTRY_COLUMNS} FROM {ENTRY_TYPE} \
-                     WHERE scope_kind = :scope_kind AND scope_id = :scope_id \
-                     ORDER BY created_at DESC LIMIT {limit}"
-                );
-                self.command_with_params(
-                    "sql",
-                    &command,
-                    json!({
-                        "scope_kind": Self::scope_kind_str(scope_kind),
-                        "scope_id": scope_id,
-                    }),
-                )
-                .await?
-            }
-            None => {
-                let command = format!(
-                    "SELECT {ENTRY_COLUMNS} FROM {ENTRY_TYPE} \
-                     ORDER BY created_at DESC LIMIT {limit}"
-                );
-                self.command("sql", &command).await?
-            }
+    ) -> Result<IndexPage> {
+        let mut conditions = Vec::new();
+        let mut params = Map::new();
+        if let Some((scope_kind, scope_id)) = scope {
+            conditions.push("scope_kind = :scope_kind".to_string());
+            conditions.push("scope_id = :scope_id".to_string());
+            params.insert(
+                "scope_kind".to_string(),
+                json!(Self::scope_kind_str(scope_kind)),
+            );
+            params.insert("scope_id".to_string(), json!(scope_id));
+        }
+        self.select_page(conditions, params, filter, limit).await
+    }
+}
+
+/// 種別 / 著者 / 期間 / keyset 位置の条件を WHERE 句の断片にし、値を named param として積む。
+///
+/// 種別を持たない旧投影の行は post とみなす（再投影を待たずに post の surface へ出し続ける）。
+fn filter_conditions(filter: &IndexQueryFilter, params: &mut Map<String, Value>) -> Vec<String> {
+    let mut conditions = vec![match filter.entry_kind {
+        IndexEntryKind::Post => "(entry_kind IS NULL OR entry_kind = :entry_kind)".to_string(),
+        _ => "entry_kind = :entry_kind".to_string(),
+    }];
+    params.insert("entry_kind".to_string(), json!(filter.entry_kind.as_str()));
+    if let Some(author_pubkey) = filter.author_pubkey.as_deref() {
+        conditions.push("author_pubkey = :author_pubkey".to_string());
+        params.insert("author_pubkey".to_string(), json!(author_pubkey));
+    }
+    if let Some(since) = filter.since {
+        conditions.push("created_at >= :since".to_string());
+        params.insert("since".to_string(), json!(since));
+    }
+    if let Some(until) = filter.until {
+        conditions.push("created_at < :until".to_string());
+        params.insert("until".to_string(), json!(until));
+    }
+    if let Some(after) = filter.after.as_ref() {
+        // (created_at, scope_id, object_id) の辞書順で直前 page 末尾より後ろだけを取る。
+        let op = match filter.order {
+            IndexSortOrder::Newest => "<",
+            IndexSortOrder::Oldest => ">",
         };
-        entries_from_result(&value)
+        conditions.push(format!(
+            "(created_at {op} :after_created_at OR (created_at = :after_created_at AND \
+             (scope_id {op} :after_scope_id OR (scope_id = :after_scope_id AND \
+             object_id {op} :after_object_id))))"
+        ));
+        params.insert("after_created_at".to_string(), json!(after.created_at));
+        params.insert("after_scope_id".to_string(), json!(after.scope_id));
+        params.insert("after_object_id".to_string(), json!(after.object_id));
+    }
+    conditions
+}
+
+/// keyset と同じ全順序の ORDER BY 句。
+fn order_clause(order: IndexSortOrder) -> &'static str {
+    match order {
+        IndexSortOrder::Newest => "created_at DESC, scope_id DESC, object_id DESC",
+        IndexSortOrder::Oldest => "created_at ASC, scope_id ASC, object_id ASC",
     }
 }
 
 /// ArcadeDB の SELECT 応答（`{ "result": [{...}, ...] }`）から投影 entry 群を読む。
+///
+/// 値の無い property（旧投影の `entry_kind` / post の `profile` / `session`）は null で返るため、
+/// 落としてから serde の既定値に任せる。
 fn entries_from_result(value: &Value) -> Result<Vec<IndexedEntry>> {
     let Some(rows) = value.get("result").and_then(|result| result.as_array()) else {
         return Ok(Vec::new());
     };
     rows.iter()
         .map(|row| {
-            serde_json::from_value(row.clone())
-                .context("failed to decode ArcadeDB indexed entry row")
+            let mut row = row.clone();
+            if let Some(fields) = row.as_object_mut() {
+                fields.retain(|_, field| !field.is_null());
+            }
+            serde_json::from_value(row).context("failed to decode ArcadeDB indexed entry row")
         })
         .collect()
 }
@@ -399,4 +483,65 @@ mod tests {
         assert_eq!(count_from_result(&json!({ "result": [] })), 0);
         assert_eq!(count_from_result(&json!({})), 0);
     }
+
+    #[test]
+    fn filter_conditions_bind_every_value_as_a_param() {
+        let filter = IndexQueryFilter {
+            entry_kind: IndexEntryKind::Profile,
+            author_pubkey: Some("author".to_string()),
+            since: Some(10),
+            until: Some(20),
+            order: IndexSortOrder::Oldest,
+            after: Some(crate::query::IndexPosition {
+                created_at: 15,
+                scope_id: "rust".to_string(),
+                object_id: "post-1".to_string(),
+            }),
+        };
+        let mut params = Map::new();
+        let conditions = filter_conditions(&filter, &mut params);
+        assert_eq!(conditions.len(), 5);
+        assert_eq!(conditions[0], "entry_kind = :entry_kind");
+        assert!(conditions[4].starts_with("(created_at > :after_created_at"));
+        assert!(!conditions.join(" ").contains("post-1"));
+        assert_eq!(params["entry_kind"], json!("profile"));
+        assert_eq!(params["author_pubkey"], json!("author"));
+        assert_eq!(params["after_object_id"], json!("post-1"));
+        assert_eq!(
+            order_clause(filter.order),
+            "created_at ASC, scope_id ASC, object_id ASC"
+        );
+    }
+
+    #[test]
+    fn default_filter_only_selects_posts_including_legacy_rows() {
+        let mut params = Map::new();
+        assert_eq!(
+            filter_conditions(&IndexQueryFilter::default(), &mut params),
+            vec!["(entry_kind IS NULL OR entry_kind = :entry_kind)".to_string()]
+        );
+        assert_eq!(params.len(), 1);
+        assert_eq!(params["entry_kind"], json!("post"));
+    }
+
+    #[test]
+    fn legacy_rows_without_kind_decode_as_posts() {
+        let value = json!({ "result": [{
+            "scope_kind": "public_topic",
+            "scope_id": "rust",
+            "object_id": "post-1",
+            "author_pubkey": "author",
+            "text": "hello",
+            "created_at": 1,
+            "source_replica_id": "topic::rust",
+            "entry_kind": null,
+            "profile": null,
+            "session": null,
+        }] });
+        let entries = entries_from_result(&value).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].entry_kind, IndexEntryKind::Post);
+        assert_eq!(entries[0].profile, None);
+        assert_eq!(entries[0].session, None);
+    }
 }
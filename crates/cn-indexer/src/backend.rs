@@ -0,0 +1,108 @@
+//! index 投影 / relation graph の backend 組み立て（[`IndexBackendConfig`] から具象実装を選ぶ）。
+//!
+//! cn-indexer（投影の書き込み）、cn-user-api（query 境界 / relation 表示判定の読み出し）、
+//! cn-cli（relation 解析 / readiness）が同じ選択規則で backend を開くための単一の入口。
+//! 呼び出し側は trait 越しにだけ扱い、ArcadeDB / embedded の違いをここに閉じ込める。
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use sqlx::postgres::PgPool;
+
+use kukuri_cn_core::PgRelationStore;
+use kukuri_cn_trust::RelationStore;
+
+use crate::arcadedb::ArcadeDbProjection;
+use crate::config::IndexBackendConfig;
+use crate::embedded::EmbeddedIndexProjection;
+use crate::projection::IndexProjection;
+use crate::query::IndexQuery;
+use crate::relation_graph::ArcadeDbRelationGraph;
+
+/// 書き込み側（cn-indexer）の投影を開く。
+///
+/// ArcadeDB は schema 準備（`ensure_schema`）まで、embedded は index ディレクトリの作成と writer
+/// lock の取得まで行う。失敗は起動失敗にする（fail-closed。投影へ書けない構成で取り込みを
+/// 始めない）。
+pub async fn open_index_projection(
+    config: &IndexBackendConfig,
+) -> Result<Arc<dyn IndexProjection>> {
+    match config {
+        IndexBackendConfig::ArcadeDb(arcadedb) => {
+            let projection = ArcadeDbProjection::new(arcadedb.clone())
+                .context("failed to build the ArcadeDB projection client")?;
+            projection.ensure_schema().await.context(
+                "cn-indexer cannot reach ArcadeDB for the index projection \
+                 (fail-closed startup gate)",
+            )?;
+            Ok(Arc::new(projection))
+        }
+        IndexBackendConfig::Embedded { index_dir } => Ok(Arc::new(
+            EmbeddedIndexProjection::open(index_dir)
+                .context("cn-indexer cannot open the embedded index projection")?,
+        )),
+    }
+}
+
+/// 読み出し側（cn-user-api）の投影 read を開く。embedded は読み取り専用で開く。
+///
+/// これは投影の生 read であり、ユーザー向けには `FailClosedIndexQuery` で包んで使うこと。
+pub fn open_index_query(config: &IndexBackendConfig) -> Result<Arc<dyn IndexQuery>> {
+    match config {
+        IndexBackendConfig::ArcadeDb(arcadedb) => Ok(Arc::new(
+            ArcadeDbProjection::new(arcadedb.clone())
+                .context("failed to build ArcadeDB client for index query")?,
+        )),
+        IndexBackendConfig::Embedded { index_dir } => Ok(Arc::new(
+            EmbeddedIndexProjection::open_read_only(index_dir)
+                .context("failed to open the embedded index projection for index query")?,
+        )),
+    }
+}
+
+/// 投影に入っている項目の総数（#616 readiness の真実源との突合用）。
+pub async fn count_index_projection(config: &IndexBackendConfig) -> Result<u64> {
+    match config {
+        IndexBackendConfig::ArcadeDb(arcadedb) => {
+            ArcadeDbProjection::new(arcadedb.clone())?.count_all().await
+        }
+        IndexBackendConfig::Embedded { index_dir } => {
+            EmbeddedIndexProjection::open_read_only(index_dir)?
+                .count_all()
+                .await
+        }
+    }
+}
+
+/// relation graph を開く（読み出し用。schema 準備はしない）。
+///
+/// embedded 構成の relation graph は Postgres テーブルで、schema は migration が用意する。
+pub fn relation_store(
+    config: &IndexBackendConfig,
+    pool: &PgPool,
+) -> Result<Arc<dyn RelationStore>> {
+    match config {
+        IndexBackendConfig::ArcadeDb(arcadedb) => Ok(Arc::new(
+            ArcadeDbRelationGraph::new(arcadedb.clone())
+                .context("failed to build ArcadeDB relation graph client")?,
+        )),
+        IndexBackendConfig::Embedded { .. } => Ok(Arc::new(PgRelationStore::new(pool.clone()))),
+    }
+}
+
+/// relation 解析（書き込み）用に relation graph を開き、ArcadeDB なら schema を準備する。
+pub async fn prepare_relation_store(
+    config: &IndexBackendConfig,
+    pool: &PgPool,
+) -> Result<Arc<dyn RelationStore>> {
+    if let IndexBackendConfig::ArcadeDb(arcadedb) = config {
+        let graph = ArcadeDbRelationGraph::new(arcadedb.clone())
+            .context("failed to build ArcadeDB relation graph client")?;
+        graph
+            .ensure_schema()
+            .await
+            .context("failed to ensure relation graph schema")?;
+        return Ok(Arc::new(graph));
+    }
+    relation_store(config, pool)
+}
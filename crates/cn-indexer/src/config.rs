@@ -128,8 +128,8 @@ pub struct IndexerConfig {
     pub relay: RelayConfig,
     /// channel secret を復号する鍵 material（cn-user-api と同じ値）。
     pub channel_secret_key: String,
-    /// ArcadeDB index 投影の接続設定。
-    pub arcadedb: ArcadeDbConfig,
+    /// index 投影 / relation graph の backend 選択。
+    pub index_backend: IndexBackendConfig,
     /// safety scan runtime（#406）の構成。provider 未構成なら scan service は構築されない。
     pub safety: SafetyRuntimeConfig,
     /// media scan 用の一時 blob fetch（#609）の制限。
@@ -152,7 +152,7 @@ impl std::fmt::Debug for IndexerConfig {
             .field("data_dir", &self.data_dir)
             .field("relay", &self.relay)
             .field("channel_secret_key", &"<redacted>")
-            .field("arcadedb", &self.arcadedb)
+            .field("index_backend", &self.index_backend)
             // SafetyRuntimeConfig の Debug は signing_key を秘匿する。
             .field("safety", &self.safety)
             .field("media_fetch", &self.media_fetch)
@@ -189,6 +189,14 @@ pub const POLL_INTERVAL_SECS_ENV: &str = "COMMUNITY_NODE_INDEXER_POLL_INTERVAL_S
 /// 未設定なら HTTP では公開しない。例: `127.0.0.1:8630`。
 pub const STATUS_ADDR_ENV: &str = "COMMUNITY_NODE_INDEXER_STATUS_ADDR";
 
+/// index 投影と relation graph の backend（`arcadedb` / `embedded`）。未設定なら `arcadedb`。
+/// cn-indexer / cn-user-api / cn-cli が同じ値を読む。
+pub const INDEX_BACKEND_ENV: &str = "COMMUNITY_NODE_INDEX_BACKEND";
+
+/// embedded backend の投影ディレクトリ。未設定なら `./data/cn-index`。書き込む cn-indexer と
+/// 読む cn-user-api / cn-cli が同じディレクトリを指す必要がある。
+pub const INDEX_EMBEDDED_DIR_ENV: &str = "COMMUNITY_NODE_INDEX_EMBEDDED_DIR";
+
 /// 全件見直し間隔の既定値。
 pub const DEFAULT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(300);
 
@@ -276,6 +284,50 @@ impl ArcadeDbConfig {
     }
 }
 
+/// index 投影と relation graph の backend 選択（`COMMUNITY_NODE_INDEX_BACKEND`）。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IndexBackendConfig {
+    /// ArcadeDB（投影 = Lucene 全文 index、relation graph = Cypher graph）。
+    ArcadeDb(ArcadeDbConfig),
+    /// 追加のデータベースを置かない構成。投影 = ディスク上の tantivy index、relation graph =
+    /// Postgres（`cn_trust.relation_edges`）。
+    Embedded { index_dir: std::path::PathBuf },
+}
+
+impl IndexBackendConfig {
+    /// 環境変数から backend を読む。未知の backend 名は起動エラー（fail-closed）。
+    ///
+    /// cn-indexer（投影の書き込み）と cn-user-api / cn-cli（読み出し）が同じ env を共有する。
+    pub fn from_env() -> Result<Self> {
+        Self::parse(
+            non_empty_env(INDEX_BACKEND_ENV).as_deref(),
+            non_empty_env(INDEX_EMBEDDED_DIR_ENV),
+        )
+    }
+
+    fn parse(backend: Option<&str>, embedded_dir: Option<String>) -> Result<Self> {
+        match backend.map(str::to_ascii_lowercase).as_deref() {
+            None | Some("arcadedb") => Ok(Self::ArcadeDb(ArcadeDbConfig::from_env())),
+            Some("embedded") => Ok(Self::Embedded {
+                index_dir: embedded_dir
+                    .unwrap_or_else(|| "./data/cn-index".to_string())
+                    .into(),
+            }),
+            Some(other) => {
+                bail!("{INDEX_BACKEND_ENV} must be one of arcadedb or embedded (got `{other}`)")
+            }
+        }
+    }
+
+    /// ログ / 状態表示用の backend 名。
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ArcadeDb(_) => "arcadedb",
+            Self::Embedded { .. } => "embedded",
+        }
+    }
+}
+
 impl IndexerConfig {
     /// 環境変数から設定を読む。
     ///
@@ -300,7 +352,7 @@ impl IndexerConfig {
             kukuri_cn_core::parse_csv_env("COMMUNITY_NODE_INDEXER_EXTERNAL_RELAY_URLS");
         let channel_secret_key = std::env::var("COMMUNITY_NODE_CHANNEL_SECRET_KEY")
             .context("COMMUNITY_NODE_CHANNEL_SECRET_KEY is required")?;
-        let arcadedb = ArcadeDbConfig::from_env();
+        let index_backend = IndexBackendConfig::from_env()?;
         let safety = SafetyRuntimeConfig {
             providers: SafetyRuntimeProvidersConfig {
                 known_csam: safety_provider_entry(
@@ -340,7 +392,7 @@ impl IndexerConfig {
             relay: RelayConfig::new(has_own_relay, external_relay_urls)
                 .with_own_relay_urls(own_relay_urls),
             channel_secret_key,
-            arcadedb,
+            index_backend,
             safety,
             media_fetch: MediaFetchConfig::from_env()?,
             seed_peers: parse_seed_peers_env()?,
@@ -472,6 +524,8 @@ mod tests {
         "COMMUNITY_NODE_ARCADEDB_DATABASE",
         "COMMUNITY_NODE_ARCADEDB_USER",
         "COMMUNITY_NODE_ARCADEDB_PASSWORD",
+        INDEX_BACKEND_ENV,
+        INDEX_EMBEDDED_DIR_ENV,
         SAFETY_PROVIDER_KNOWN_CSAM_ENV,
         SAFETY_PROVIDER_KNOWN_CSAM_REQUIRED_ENV,
         SAFETY_PROVIDER_GENERAL_ENV,
@@ -751,6 +805,42 @@ mod tests {
         });
     }
 
+    #[test]
+    fn index_backend_env_defaults_to_arcadedb_and_selects_embedded() {
+        with_clean_indexer_env(|| {
+            set_minimal_indexer_env();
+
+            // 未設定なら従来どおり ArcadeDB。
+            let config = IndexerConfig::from_env().unwrap();
+            assert_eq!(config.index_backend.name(), "arcadedb");
+
+            unsafe {
+                std::env::set_var(INDEX_BACKEND_ENV, "embedded");
+            }
+            assert_eq!(
+                IndexerConfig::from_env().unwrap().index_backend,
+                IndexBackendConfig::Embedded {
+                    index_dir: "./data/cn-index".into()
+                }
+            );
+            unsafe {
+                std::env::set_var(INDEX_EMBEDDED_DIR_ENV, "/var/lib/kukuri/index");
+            }
+            assert_eq!(
+                IndexerConfig::from_env().unwrap().index_backend,
+                IndexBackendConfig::Embedded {
+                    index_dir: "/var/lib/kukuri/index".into()
+                }
+            );
+
+            // 未知の backend 名は起動エラー（fail-closed）。
+            unsafe {
+                std::env::set_var(INDEX_BACKEND_ENV, "neo4j");
+            }
+            assert!(IndexerConfig::from_env().is_err());
+        });
+    }
+
     #[test]
     fn status_addr_env_defaults_to_none_and_rejects_invalid() {
         with_clean_indexer_env(|| {
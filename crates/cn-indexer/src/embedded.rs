@@ -0,0 +1,918 @@
+//! 組み込み index 投影 backend（tantivy のディスク上全文 index。ArcadeDB を置かない構成用）。
+//!
+//! 小規模 operator が追加のデータベースを動かさずに indexing できるよう、`IndexProjection` /
+//! `IndexQuery` を cn-indexer プロセス内の tantivy index で満たす。投影は canonical ではない
+//! derived な写像（ADR 0025 §2.1）であり、失っても真実源（`cn_index.index_entries`）から
+//! 再投影できる点は ArcadeDB 投影と同じ。relation graph は同じ構成で Postgres
+//! （`kukuri_cn_core::PgRelationStore`）に置く。
+//!
+//! 書き込みは 1 プロセス（cn-indexer）だけが行う。tantivy の writer lock がこれを強制する。
+//! cn-user-api / cn-cli は同じディレクトリを [`EmbeddedIndexProjection::open_read_only`] で開き、
+//! commit（`meta.json` 更新）を監視して新しい segment を読む。したがってこの backend は
+//! 投影ディレクトリを共有できる同一ホスト（同一 volume）構成が前提になる。
+//!
+//! 全文検索は tantivy の query parser（Lucene 互換の構文、既定 OR 演算）で行い、並び順と keyset
+//! 位置は ArcadeDB adapter と同じ `(created_at, scope_id, object_id)` の全順序に揃える。
+
+use std::collections::BTreeSet;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use tantivy::collector::{Count, DocSetCollector, TopDocs};
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{AllQuery, BooleanQuery, Occur, Query, QueryParser, RangeQuery, TermQuery};
+use tantivy::schema::{
+    FAST, Field, INDEXED, IndexRecordOption, STORED, STRING, Schema, TEXT, Value,
+};
+use tantivy::{
+    DocAddress, Index, IndexReader, IndexWriter, Order, ReloadPolicy, Searcher, TantivyDocument,
+    Term,
+};
+
+use kukuri_cn_core::IndexScopeKind;
+use kukuri_cn_protocol::{IndexEntryKind, IndexSortOrder};
+
+use crate::projection::{IndexProjection, IndexedEntry};
+use crate::query::{IndexPage, IndexQuery, IndexQueryFilter};
+
+/// writer の memory budget（単一スレッド。tantivy の下限 15MB を満たす最小構成）。
+const WRITER_MEMORY_BUDGET_BYTES: usize = 32 * 1024 * 1024;
+
+/// tantivy の meta file / managed file 一覧（tantivy 側の定数は公開されていない）。
+const META_FILE: &str = "meta.json";
+const MANAGED_FILE: &str = ".managed.json";
+
+/// 投影 document の field 群（`IndexedEntry` のフィールドと 1:1）。
+#[derive(Clone, Copy)]
+struct EntryFields {
+    scope_kind: Field,
+    scope_id: Field,
+    object_id: Field,
+    author_pubkey: Field,
+    text: Field,
+    created_at: Field,
+    source_replica_id: Field,
+    entry_kind: Field,
+    profile: Field,
+    session: Field,
+}
+
+impl EntryFields {
+    /// 識別子系と種別は完全一致（`STRING`）、本文は全文（`TEXT`）、作成時刻は範囲 / 並び替え用に
+    /// fast field にする。profile 項目と session 状態は JSON 文字列で保存だけする。
+    fn schema() -> (Schema, Self) {
+        let mut builder = Schema::builder();
+        let fields = Self {
+            scope_kind: builder.add_text_field("scope_kind", STRING | STORED),
+            scope_id: builder.add_text_field("scope_id", STRING | STORED),
+            object_id: builder.add_text_field("object_id", STRING | STORED),
+            author_pubkey: builder.add_text_field("author_pubkey", STRING | STORED),
+            text: builder.add_text_field("text", TEXT | STORED),
+            created_at: builder.add_i64_field("created_at", INDEXED | FAST | STORED),
+            source_replica_id: builder.add_text_field("source_replica_id", STORED),
+            entry_kind: builder.add_text_field("entry_kind", STRING | STORED),
+            profile: builder.add_text_field("profile", STORED),
+            session: builder.add_text_field("session", STORED),
+        };
+        (builder.build(), fields)
+    }
+
+    fn document(&self, entry: &IndexedEntry) -> Result<TantivyDocument> {
+        let mut document = TantivyDocument::default();
+        document.add_text(self.scope_kind, entry.scope_kind.as_str());
+        document.add_text(self.scope_id, &entry.scope_id);
+        document.add_text(self.object_id, &entry.object_id);
+        document.add_text(self.author_pubkey, &entry.author_pubkey);
+        document.add_text(self.text, &entry.text);
+        document.add_i64(self.created_at, entry.created_at);
+        document.add_text(self.source_replica_id, &entry.source_replica_id);
+        document.add_text(self.entry_kind, entry.entry_kind.as_str());
+        if let Some(profile) = entry.profile.as_ref() {
+            document.add_text(
+                self.profile,
+                serde_json::to_string(profile).context("failed to encode index profile")?,
+            );
+        }
+        if let Some(session) = entry.session.as_ref() {
+            document.add_text(
+                self.session,
+                serde_json::to_string(session).context("failed to encode index session")?,
+            );
+        }
+        Ok(document)
+    }
+
+    fn entry(&self, document: &TantivyDocument) -> Result<IndexedEntry> {
+        let text = |field: Field, name: &str| -> Result<String> {
+            document
+                .get_first(field)
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+                .with_context(|| format!("embedded index document is missing `{name}`"))
+        };
+        Ok(IndexedEntry {
+            scope_kind: IndexScopeKind::parse(&text(self.scope_kind, "scope_kind")?)?,
+            scope_id: text(self.scope_id, "scope_id")?,
+            object_id: text(self.object_id, "object_id")?,
+            author_pubkey: text(self.author_pubkey, "author_pubkey")?,
+            text: text(self.text, "text")?,
+            created_at: document
+                .get_first(self.created_at)
+                .and_then(|value| value.as_i64())
+                .context("embedded index document is missing `created_at`")?,
+            source_replica_id: text(self.source_replica_id, "source_replica_id")?,
+            entry_kind: IndexEntryKind::parse(&text(self.entry_kind, "entry_kind")?)?,
+            profile: document
+                .get_first(self.profile)
+                .and_then(|value| value.as_str())
+                .map(serde_json::from_str)
+                .transpose()
+                .context("embedded index document has a malformed `profile`")?,
+            session: document
+                .get_first(self.session)
+                .and_then(|value| value.as_str())
+                .map(serde_json::from_str)
+                .transpose()
+                .context("embedded index document has a malformed `session`")?,
+        })
+    }
+
+    fn term_query(field: Field, value: &str) -> Box<dyn Query> {
+        Box::new(TermQuery::new(
+            Term::from_field_text(field, value),
+            IndexRecordOption::Basic,
+        ))
+    }
+
+    fn scope_query(&self, scope_kind: IndexScopeKind, scope_id: &str) -> Vec<Box<dyn Query>> {
+        vec![
+            Self::term_query(self.scope_kind, scope_kind.as_str()),
+            Self::term_query(self.scope_id, scope_id),
+        ]
+    }
+
+    fn object_query(
+        &self,
+        scope_kind: IndexScopeKind,
+        scope_id: &str,
+        object_id: &str,
+    ) -> Box<dyn Query> {
+        let mut clauses = self.scope_query(scope_kind, scope_id);
+        clauses.push(Self::term_query(self.object_id, object_id));
+        must_all(clauses)
+    }
+
+    /// 種別 / 著者 / 期間 / keyset 位置の条件を query 句にする（ArcadeDB の `filter_conditions` と
+    /// 同じ意味）。
+    fn filter_clauses(&self, filter: &IndexQueryFilter) -> Vec<Box<dyn Query>> {
+        let mut clauses = vec![Self::term_query(
+            self.entry_kind,
+            filter.entry_kind.as_str(),
+        )];
+        if let Some(author_pubkey) = filter.author_pubkey.as_deref() {
+            clauses.push(Self::term_query(self.author_pubkey, author_pubkey));
+        }
+        if filter.since.is_some() || filter.until.is_some() {
+            clauses.push(Box::new(RangeQuery::new(
+                filter.since.map_or(Bound::Unbounded, |since| {
+                    Bound::Included(Term::from_field_i64(self.created_at, since))
+                }),
+                filter.until.map_or(Bound::Unbounded, |until| {
+                    Bound::Excluded(Term::from_field_i64(self.created_at, until))
+                }),
+            )) as Box<dyn Query>);
+        }
+        if let Some(after) = filter.after.as_ref() {
+            // (created_at, scope_id, object_id) の辞書順で直前 page 末尾より後ろだけを取る。
+            let beyond_i64 = |field: Field, value: i64| {
+                strictly_beyond(filter.order, Term::from_field_i64(field, value))
+            };
+            let beyond_text = |field: Field, value: &str| {
+                strictly_beyond(filter.order, Term::from_field_text(field, value))
+            };
+            let same_created_at = Box::new(RangeQuery::new(
+                Bound::Included(Term::from_field_i64(self.created_at, after.created_at)),
+                Bound::Included(Term::from_field_i64(self.created_at, after.created_at)),
+            )) as Box<dyn Query>;
+            let same_scope = must_all(vec![
+                same_created_at.box_clone(),
+                Self::term_query(self.scope_id, &after.scope_id),
+                beyond_text(self.object_id, &after.object_id),
+            ]);
+            let later_scope = must_all(vec![
+                same_created_at,
+                beyond_text(self.scope_id, &after.scope_id),
+            ]);
+            clauses.push(Box::new(BooleanQuery::new(vec![
+                (Occur::Should, beyond_i64(self.created_at, after.created_at)),
+                (Occur::Should, later_scope),
+                (Occur::Should, same_scope),
+            ])));
+        }
+        clauses
+    }
+}
+
+/// 並び順の向きで `term` より厳密に後ろの値だけを取る範囲 query。
+fn strictly_beyond(order: IndexSortOrder, term: Term) -> Box<dyn Query> {
+    let query = match order {
+        IndexSortOrder::Newest => RangeQuery::new(Bound::Unbounded, Bound::Excluded(term)),
+        IndexSortOrder::Oldest => RangeQuery::new(Bound::Excluded(term), Bound::Unbounded),
+    };
+    Box::new(query)
+}
+
+/// 全句の AND。句が無ければ全件。
+fn must_all(clauses: Vec<Box<dyn Query>>) -> Box<dyn Query> {
+    if clauses.is_empty() {
+        return Box::new(AllQuery);
+    }
+    Box::new(BooleanQuery::new(
+        clauses
+            .into_iter()
+            .map(|clause| (Occur::Must, clause))
+            .collect(),
+    ))
+}
+
+fn tantivy_order(order: IndexSortOrder) -> Order {
+    match order {
+        IndexSortOrder::Newest => Order::Desc,
+        IndexSortOrder::Oldest => Order::Asc,
+    }
+}
+
+struct EmbeddedIndex {
+    index: Index,
+    reader: IndexReader,
+    /// 書き込み側（cn-indexer）だけが持つ。読み取り専用で開いた場合は None。
+    writer: Option<Mutex<IndexWriter>>,
+    fields: EntryFields,
+}
+
+impl EmbeddedIndex {
+    fn writer(&self) -> Result<std::sync::MutexGuard<'_, IndexWriter>> {
+        let Some(writer) = self.writer.as_ref() else {
+            bail!("embedded index projection is opened read-only");
+        };
+        Ok(writer.lock().expect("embedded index writer lock poisoned"))
+    }
+
+    /// 削除と追加を 1 commit にまとめ、同一プロセスの読み口へ即時に反映する。
+    fn apply(
+        &self,
+        delete: Option<Box<dyn Query>>,
+        document: Option<TantivyDocument>,
+    ) -> Result<()> {
+        let mut writer = self.writer()?;
+        if let Some(delete) = delete {
+            writer
+                .delete_query(delete)
+                .context("failed to delete from the embedded index")?;
+        }
+        if let Some(document) = document {
+            writer
+                .add_document(document)
+                .context("failed to add to the embedded index")?;
+        }
+        writer
+            .commit()
+            .context("failed to commit the embedded index")?;
+        self.reader
+            .reload()
+            .context("failed to reload the embedded index reader")?;
+        Ok(())
+    }
+
+    fn count(&self, query: &dyn Query) -> Result<usize> {
+        self.reader
+            .searcher()
+            .search(query, &Count)
+            .context("failed to count embedded index entries")
+    }
+
+    fn text_query(&self, query: &str) -> Box<dyn Query> {
+        // 利用者入力の構文誤りで失敗させず、解釈できた部分で検索する（ArcadeDB の Lucene と
+        // 同じく既定は OR）。
+        QueryParser::for_index(&self.index, vec![self.fields.text])
+            .parse_query_lenient(query)
+            .0
+    }
+
+    /// 条件に合う entry を並び順で `limit + 1` 件まで取り、page を作る。
+    ///
+    /// tantivy の並び替えは `created_at` 単独なので、window 末尾と同時刻の entry は別途すべて
+    /// 取り、`(created_at, scope_id, object_id)` の全順序で並べ直してから切り詰める（同時刻の
+    /// entry が page 境界をまたいでも重複・欠落しない）。
+    fn page(
+        &self,
+        mut clauses: Vec<Box<dyn Query>>,
+        filter: &IndexQueryFilter,
+        limit: usize,
+    ) -> Result<IndexPage> {
+        clauses.extend(self.fields.filter_clauses(filter));
+        let query = must_all(clauses);
+        let searcher = self.reader.searcher();
+        let window = limit.saturating_add(1);
+        let top = searcher
+            .search(
+                &query,
+                &TopDocs::with_limit(window)
+                    .order_by_fast_field::<i64>("created_at", tantivy_order(filter.order)),
+            )
+            .context("failed to search the embedded index")?;
+        let mut addresses: BTreeSet<DocAddress> = top.iter().map(|(_, address)| *address).collect();
+        if top.len() == window
+            && let Some((boundary, _)) = top.last()
+        {
+            let boundary = Term::from_field_i64(self.fields.created_at, *boundary);
+            let ties = must_all(vec![
+                query,
+                Box::new(RangeQuery::new(
+                    Bound::Included(boundary.clone()),
+                    Bound::Included(boundary),
+                )),
+            ]);
+            addresses.extend(
+                searcher
+                    .search(&ties, &DocSetCollector)
+                    .context("failed to search the embedded index")?,
+            );
+        }
+        let mut entries = self.load(&searcher, addresses)?;
+        entries.sort_by(|left, right| filter.compare_entries(left, right));
+        entries.truncate(window);
+        Ok(IndexPage::from_window(entries, limit))
+    }
+
+    fn load(
+        &self,
+        searcher: &Searcher,
+        addresses: impl IntoIterator<Item = DocAddress>,
+    ) -> Result<Vec<IndexedEntry>> {
+        addresses
+            .into_iter()
+            .map(|address| {
+                let document: TantivyDocument = searcher
+                    .doc(address)
+                    .context("failed to load an embedded index document")?;
+                self.fields.entry(&document)
+            })
+            .collect()
+    }
+}
+
+/// tantivy のディスク上 index に置く組み込み投影。
+#[derive(Clone)]
+pub struct EmbeddedIndexProjection {
+    inner: Arc<EmbeddedIndex>,
+}
+
+impl EmbeddedIndexProjection {
+    /// 書き込み側（cn-indexer）として開く。ディレクトリと index が無ければ作る。
+    ///
+    /// writer lock を取るため、同じディレクトリを書き込み側で開けるのは 1 プロセスだけ。
+    pub fn open(index_dir: &Path) -> Result<Self> {
+        Self::open_with(index_dir, true)
+    }
+
+    /// 読み取り専用で開く（cn-user-api の query 境界 / cn-cli readiness）。書き込み側の commit を
+    /// 監視して自動で読み直す。書き込み系メソッドはエラーになる。
+    pub fn open_read_only(index_dir: &Path) -> Result<Self> {
+        Self::open_with(index_dir, false)
+    }
+
+    fn open_with(index_dir: &Path, writable: bool) -> Result<Self> {
+        std::fs::create_dir_all(index_dir).with_context(|| {
+            format!(
+                "failed to create the embedded index directory {}",
+                index_dir.display()
+            )
+        })?;
+        let directory = MmapDirectory::open(index_dir).with_context(|| {
+            format!(
+                "failed to open the embedded index directory {}",
+                index_dir.display()
+            )
+        })?;
+        let (schema, fields) = EntryFields::schema();
+        if Index::exists(&directory)? && Index::open(directory.clone())?.schema() != schema {
+            if !writable {
+                bail!(
+                    "the embedded index at {} has an outdated schema; start cn-indexer to rebuild it",
+                    index_dir.display()
+                );
+            }
+            discard_outdated_index(index_dir, directory.clone())?;
+        }
+        let index = Index::open_or_create(directory, schema).with_context(|| {
+            format!(
+                "failed to open the embedded index at {}",
+                index_dir.display()
+            )
+        })?;
+        let writer = if writable {
+            Some(Mutex::new(
+                index
+                    .writer_with_num_threads(1, WRITER_MEMORY_BUDGET_BYTES)
+                    .context("embedded index is locked by another writer")?,
+            ))
+        } else {
+            None
+        };
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::OnCommitWithDelay)
+            .try_into()
+            .context("failed to open the embedded index reader")?;
+        Ok(Self {
+            inner: Arc::new(EmbeddedIndex {
+                index,
+                reader,
+                writer,
+                fields,
+            }),
+        })
+    }
+
+    /// 投影に入っている項目の総数を返す（#616 readiness の真実源との突合用）。
+    pub async fn count_all(&self) -> Result<u64> {
+        let count = self.blocking(|index| index.count(&AllQuery)).await?;
+        Ok(u64::try_from(count).unwrap_or(u64::MAX))
+    }
+
+    /// tantivy の同期 API（fsync を伴う commit を含む）を blocking pool で実行する。
+    async fn blocking<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&EmbeddedIndex) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let inner = Arc::clone(&self.inner);
+        tokio::task::spawn_blocking(move || f(&inner))
+            .await
+            .context("embedded index task panicked")?
+    }
+}
+
+impl std::fmt::Debug for EmbeddedIndexProjection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmbeddedIndexProjection")
+            .field("writable", &self.inner.writer.is_some())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl IndexProjection for EmbeddedIndexProjection {
+    async fn upsert_entry(&self, entry: &IndexedEntry) -> Result<()> {
+        // (scope_kind, scope_id, object_id) 一意: 既存を消してから同じ commit で入れ直す。
+        let entry = entry.clone();
+        self.blocking(move |index| {
+            let fields = index.fields;
+            index.apply(
+                Some(fields.object_query(entry.scope_kind, &entry.scope_id, &entry.object_id)),
+                Some(fields.document(&entry)?),
+            )
+        })
+        .await
+    }
+
+    async fn contains_object(
+        &self,
+        scope_kind: IndexScopeKind,
+        scope_id: &str,
+        object_id: &str,
+    ) -> Result<bool> {
+        let (scope_id, object_id) = (scope_id.to_string(), object_id.to_string());
+        self.blocking(move |index| {
+            let query = index.fields.object_query(scope_kind, &scope_id, &object_id);
+            Ok(index.count(&*query)? > 0)
+        })
+        .await
+    }
+
+    async fn count_scope(&self, scope_kind: IndexScopeKind, scope_id: &str) -> Result<usize> {
+        let scope_id = scope_id.to_string();
+        self.blocking(move |index| {
+            let query = must_all(index.fields.scope_query(scope_kind, &scope_id));
+            index.count(&*query)
+        })
+        .await
+    }
+
+    async fn remove_scope(&self, scope_kind: IndexScopeKind, scope_id: &str) -> Result<()> {
+        let scope_id = scope_id.to_string();
+        self.blocking(move |index| {
+            let query = must_all(index.fields.scope_query(scope_kind, &scope_id));
+            index.apply(Some(query), None)
+        })
+        .await
+    }
+
+    async fn remove_object(
+        &self,
+        scope_kind: IndexScopeKind,
+        scope_id: &str,
+        object_id: &str,
+    ) -> Result<()> {
+        let (scope_id, object_id) = (scope_id.to_string(), object_id.to_string());
+        self.blocking(move |index| {
+            let query = index.fields.object_query(scope_kind, &scope_id, &object_id);
+            index.apply(Some(query), None)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl IndexQuery for EmbeddedIndexProjection {
+    async fn search_scope_page(
+        &self,
+        scope_kind: IndexScopeKind,
+        scope_id: &str,
+        query: &str,
+        filter: &IndexQueryFilter,
+        limit: usize,
+    ) -> Result<IndexPage> {
+        let (scope_id, query, filter) = (scope_id.to_string(), query.to_string(), filter.clone());
+        self.blocking(move |index| {
+            let mut clauses = vec![index.text_query(&query)];
+            clauses.extend(index.fields.scope_query(scope_kind, &scope_id));
+            index.page(clauses, &filter, limit)
+        })
+        .await
+    }
+
+    async fn search_all_page(
+        &self,
+        query: &str,
+        filter: &IndexQueryFilter,
+        limit: usize,
+    ) -> Result<IndexPage> {
+        let (query, filter) = (query.to_string(), filter.clone());
+        self.blocking(move |index| index.page(vec![index.text_query(&query)], &filter, limit))
+            .await
+    }
+
+    async fn list_recent_page(
+        &self,
+        scope: Option<(IndexScopeKind, &str)>,
+        filter: &IndexQueryFilter,
+        limit: usize,
+    ) -> Result<IndexPage> {
+        let scope = scope.map(|(scope_kind, scope_id)| (scope_kind, scope_id.to_string()));
+        let filter = filter.clone();
+        self.blocking(move |index| {
+            let clauses = match scope {
+                Some((scope_kind, scope_id)) => index.fields.scope_query(scope_kind, &scope_id),
+                None => Vec::new(),
+            };
+            index.page(clauses, &filter, limit)
+        })
+        .await
+    }
+}
+
+/// schema の変わった（例: entry 種別 / profile / session の field を持たない）index を空にする。
+///
+/// tantivy は既存 index に field を足せないので作り直すしかない。投影は derived なので、空にした
+/// index は worker の次の全件見直しで replica から再投影される。別 writer が掴んでいる index は
+/// 消さず、writer lock は削除が終わるまで持ち続ける。消すのは tantivy が管理する file（meta.json /
+/// .managed.json / segment file）だけで、lock file と dir 内の他の file には触れない。読み取り側は
+/// 作り直し後に開き直すこと。
+fn discard_outdated_index(index_dir: &Path, directory: MmapDirectory) -> Result<()> {
+    let outdated = Index::open(directory)?;
+    let writer: IndexWriter = outdated
+        .writer_with_num_threads(1, WRITER_MEMORY_BUDGET_BYTES)
+        .context("embedded index is locked by another writer")?;
+    tracing::warn!(
+        index_dir = %index_dir.display(),
+        "embedded index の schema が古いため作り直します(次の全件見直しで再投影されます)"
+    );
+    let managed_files = outdated.directory().list_managed_files();
+    // meta.json を最初に消し、途中で失敗しても古い schema の index としては開かれないようにする。
+    let tantivy_files = [Path::new(META_FILE), Path::new(MANAGED_FILE)]
+        .into_iter()
+        .chain(managed_files.iter().map(PathBuf::as_path));
+    for relative in tantivy_files {
+        match std::fs::remove_file(index_dir.join(relative)) {
+            Ok(()) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => {
+                return Err(error)
+                    .with_context(|| format!("failed to remove {}", relative.display()));
+            }
+        }
+    }
+    drop(writer);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use kukuri_cn_protocol::{
+        IndexProfileView, IndexSessionKind, IndexSessionStatus, IndexSessionView,
+    };
+
+    use super::*;
+    use crate::query::IndexPosition;
+
+    fn entry(scope_id: &str, object_id: &str, text: &str, created_at: i64) -> IndexedEntry {
+        IndexedEntry {
+            scope_kind: IndexScopeKind::PublicTopic,
+            scope_id: scope_id.to_string(),
+            object_id: object_id.to_string(),
+            author_pubkey: "author".to_string(),
+            text: text.to_string(),
+            created_at,
+            source_replica_id: format!("topic::{scope_id}"),
+            entry_kind: IndexEntryKind::Post,
+            profile: None,
+            session: None,
+        }
+    }
+
+    fn ids(page: &IndexPage) -> Vec<&str> {
+        page.entries
+            .iter()
+            .map(|entry| entry.object_id.as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn upsert_replaces_by_object_and_removals_deindex() {
+        let dir = tempfile::tempdir().unwrap();
+        let projection = EmbeddedIndexProjection::open(dir.path()).unwrap();
+        projection
+            .upsert_entry(&entry("t1", "o1", "first", 1))
+            .await
+            .unwrap();
+        projection
+            .upsert_entry(&entry("t1", "o1", "second", 1))
+            .await
+            .unwrap();
+        projection
+            .upsert_entry(&entry("t1", "o2", "other", 2))
+            .await
+            .unwrap();
+        projection
+            .upsert_entry(&entry("t2", "o3", "elsewhere", 3))
+            .await
+            .unwrap();
+        assert_eq!(
+            projection
+                .count_scope(IndexScopeKind::PublicTopic, "t1")
+                .await
+                .unwrap(),
+            2
+        );
+        let page = projection
+            .search_scope_page(
+                IndexScopeKind::PublicTopic,
+                "t1",
+                "second",
+                &IndexQueryFilter::default(),
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.entries, vec![entry("t1", "o1", "second", 1)]);
+
+        projection
+            .remove_object(IndexScopeKind::PublicTopic, "t1", "o1")
+            .await
+            .unwrap();
+        assert!(
+            !projection
+                .contains_object(IndexScopeKind::PublicTopic, "t1", "o1")
+                .await
+                .unwrap()
+        );
+        projection
+            .remove_scope(IndexScopeKind::PublicTopic, "t1")
+            .await
+            .unwrap();
+        assert_eq!(projection.count_all().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn pages_walk_every_entry_once_across_equal_timestamps() {
+        let dir = tempfile::tempdir().unwrap();
+        let projection = EmbeddedIndexProjection::open(dir.path()).unwrap();
+        for (scope_id, object_id, created_at) in [
+            ("t1", "o1", 10),
+            ("t2", "o2", 20),
+            ("t1", "o3", 20),
+            ("t1", "o4", 20),
+            ("t1", "o5", 30),
+        ] {
+            projection
+                .upsert_entry(&entry(scope_id, object_id, "post", created_at))
+                .await
+                .unwrap();
+        }
+        for (order, expected) in [
+            (IndexSortOrder::Newest, ["o5", "o2", "o4", "o3", "o1"]),
+            (IndexSortOrder::Oldest, ["o1", "o3", "o4", "o2", "o5"]),
+        ] {
+            let mut filter = IndexQueryFilter {
+                order,
+                ..IndexQueryFilter::default()
+            };
+            let mut seen = Vec::new();
+            loop {
+                let page = projection
+                    .search_all_page("post", &filter, 2)
+                    .await
+                    .unwrap();
+                seen.extend(page.entries.iter().map(|entry| entry.object_id.clone()));
+                match page.next {
+                    Some(next) => filter.after = Some(next),
+                    None => break,
+                }
+            }
+            assert_eq!(seen, expected, "{order:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn filter_narrows_by_author_time_window_and_keyset() {
+        let dir = tempfile::tempdir().unwrap();
+        let projection = EmbeddedIndexProjection::open(dir.path()).unwrap();
+        for (object_id, author, created_at) in [
+            ("o1", "alice", 10),
+            ("o2", "bob", 20),
+            ("o3", "alice", 30),
+            ("o4", "alice", 40),
+        ] {
+            projection
+                .upsert_entry(&IndexedEntry {
+                    author_pubkey: author.to_string(),
+                    ..entry("t1", object_id, "post", created_at)
+                })
+                .await
+                .unwrap();
+        }
+        let filter = IndexQueryFilter {
+            author_pubkey: Some("alice".to_string()),
+            since: Some(10),
+            until: Some(40),
+            ..IndexQueryFilter::default()
+        };
+        let page = projection
+            .list_recent_page(Some((IndexScopeKind::PublicTopic, "t1")), &filter, 10)
+            .await
+            .unwrap();
+        assert_eq!(ids(&page), vec!["o3", "o1"]);
+        assert_eq!(page.next, None);
+
+        let after = IndexQueryFilter {
+            after: Some(IndexPosition {
+                created_at: 30,
+                scope_id: "t1".to_string(),
+                object_id: "o3".to_string(),
+            }),
+            ..filter
+        };
+        let page = projection.list_recent_page(None, &after, 10).await.unwrap();
+        assert_eq!(ids(&page), vec!["o1"]);
+    }
+
+    #[tokio::test]
+    async fn entry_kind_separates_profiles_and_sessions_from_posts() {
+        let dir = tempfile::tempdir().unwrap();
+        let projection = EmbeddedIndexProjection::open(dir.path()).unwrap();
+        let profile = IndexedEntry {
+            entry_kind: IndexEntryKind::Profile,
+            profile: Some(IndexProfileView {
+                name: Some("alice".to_string()),
+                display_name: None,
+                about: Some("rust notes".to_string()),
+            }),
+            ..entry("t1", "profile:author", "alice\nrust notes", 2)
+        };
+        let session = IndexedEntry {
+            entry_kind: IndexEntryKind::Session,
+            session: Some(IndexSessionView {
+                session_kind: IndexSessionKind::LiveSession,
+                session_id: "live-1".to_string(),
+                topic_id: "t1".to_string(),
+                status: IndexSessionStatus::Live,
+                title: "rust live".to_string(),
+                description: None,
+                participant_count: None,
+            }),
+            ..entry("t1", "live:live-1", "rust live", 3)
+        };
+        projection.upsert_entry(&profile).await.unwrap();
+        projection.upsert_entry(&session).await.unwrap();
+        projection
+            .upsert_entry(&entry("t1", "o1", "rust post", 1))
+            .await
+            .unwrap();
+
+        let posts = projection
+            .search_all_page("rust", &IndexQueryFilter::default(), 10)
+            .await
+            .unwrap();
+        assert_eq!(ids(&posts), vec!["o1"]);
+        let people = projection
+            .search_all_page(
+                "rust",
+                &IndexQueryFilter {
+                    entry_kind: IndexEntryKind::Profile,
+                    ..IndexQueryFilter::default()
+                },
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(people.entries, vec![profile]);
+        let live = projection
+            .list_recent_page(
+                None,
+                &IndexQueryFilter {
+                    entry_kind: IndexEntryKind::Session,
+                    ..IndexQueryFilter::default()
+                },
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(live.entries, vec![session]);
+    }
+
+    #[tokio::test]
+    async fn read_only_handle_sees_writer_commits_and_rejects_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = EmbeddedIndexProjection::open(dir.path()).unwrap();
+        let reader = EmbeddedIndexProjection::open_read_only(dir.path()).unwrap();
+        writer
+            .upsert_entry(&entry("t1", "o1", "hello world", 1))
+            .await
+            .unwrap();
+
+        let mut found = false;
+        for _ in 0..50 {
+            if reader
+                .contains_object(IndexScopeKind::PublicTopic, "t1", "o1")
+                .await
+                .unwrap()
+            {
+                found = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert!(found, "read-only handle must pick up the writer's commit");
+        let error = reader
+            .upsert_entry(&entry("t1", "o2", "nope", 2))
+            .await
+            .expect_err("read-only handle must not write");
+        assert!(format!("{error:#}").contains("read-only"), "{error:#}");
+        assert!(
+            EmbeddedIndexProjection::open(dir.path()).is_err(),
+            "a second writer must be refused by the writer lock"
+        );
+    }
+
+    #[tokio::test]
+    async fn outdated_schema_is_rebuilt_by_the_writer_and_refused_by_readers() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            // entry 種別 / profile / session の field を足す前の schema で書かれた index。
+            let mut builder = Schema::builder();
+            let object_id = builder.add_text_field("object_id", STRING | STORED);
+            let index = Index::create_in_dir(dir.path(), builder.build()).unwrap();
+            let mut writer: IndexWriter = index
+                .writer_with_num_threads(1, WRITER_MEMORY_BUDGET_BYTES)
+                .unwrap();
+            let mut document = TantivyDocument::default();
+            document.add_text(object_id, "legacy");
+            writer.add_document(document).unwrap();
+            writer.commit().unwrap();
+        }
+        // 運用者が index dir に置いた tantivy 以外の file は作り直しで消さない。
+        std::fs::write(dir.path().join("operator-note.txt"), "keep me").unwrap();
+
+        assert!(
+            EmbeddedIndexProjection::open_read_only(dir.path()).is_err(),
+            "a reader must not open an index with an outdated schema"
+        );
+        let projection = EmbeddedIndexProjection::open(dir.path()).unwrap();
+        assert_eq!(projection.count_all().await.unwrap(), 0);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("operator-note.txt")).unwrap(),
+            "keep me"
+        );
+        projection
+            .upsert_entry(&entry("t1", "o1", "reprojected", 1))
+            .await
+            .unwrap();
+        drop(projection);
+        let reader = EmbeddedIndexProjection::open_read_only(dir.path()).unwrap();
+        assert_eq!(reader.count_all().await.unwrap(), 1);
+    }
+}
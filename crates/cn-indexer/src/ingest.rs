@@ -24,28 +24,47 @@
 //! （`IndexProjection`。ArcadeDB）の順で書く。① が失敗したら ② は書かない（真実源に無い
 //! entry は query 境界の突合で surfacing されないため、投影残留も安全側に倒れる）。
 //! de-index は真実源 → 投影の順で両方から消す。
+//!
+//! public topic では、index 済み post を持つ著者の署名済み profile（name / display_name /
+//! about）も同じ gate を通して `profile` entry として投影する（people search。private channel
+//! の参加者は対象外）。
+//!
+//! live session / game room（`sessions/{live,game}/<id>/state`）は、現在の manifest blob の
+//! title / description を同じ gate に通して `session` entry として投影する（live now）。
+//! 終了した session は de-index する。
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 
 use anyhow::{Context, Result, bail};
 use tracing::{debug, warn};
 
 use kukuri_blob_service::BlobService;
-use kukuri_cn_core::{IndexEntryStore, IndexScopeKind, NewIndexEntry};
+use kukuri_cn_core::{EntryEngagement, IndexEntryStore, IndexScopeKind, NewIndexEntry};
+use kukuri_cn_protocol::{
+    IndexEntryKind, IndexProfileView, IndexSessionKind, IndexSessionStatus, IndexSessionView,
+};
 use kukuri_cn_safety::ReasonCode;
 use kukuri_cn_safety::provider::{ProviderScanRequest, SubjectKind};
 use kukuri_cn_safety_runtime::{SafetyScanOutcome, SafetyScanService};
 use kukuri_core::{
-    AssetRef, KukuriEnvelope, KukuriMediaManifestV1, ObjectStatus, PayloadRef, ReplicaId, blob_hash,
+    AssetRef, AuthorProfileDocV1, ChannelId, GameRoomKind, GameRoomManifestBlobV1,
+    GameRoomStateDocV1, GameRoomStatus, KukuriEnvelope, KukuriMediaManifestV1,
+    LiveSessionManifestBlobV1, LiveSessionStateDocV1, LiveSessionStatus, ManifestBlobRef,
+    ObjectStatus, PayloadRef, Profile, Pubkey, ReplicaId, TopicId, blob_hash, parse_profile,
+};
+use kukuri_docs_sync::{
+    DocFetchPolicy, DocQuery, DocRecord, DocsSync, author_replica_id, stable_key,
 };
-use kukuri_docs_sync::{DocFetchPolicy, DocQuery, DocRecord, DocsSync, stable_key};
+use serde::de::DeserializeOwned;
 
 use crate::projection::{IndexProjection, IndexedEntry};
 
 /// app-api の投稿上限（10,000 Unicode scalar values）を UTF-8 bytes でも有界にする。
 const MAX_INDEXABLE_POST_BODY_CHARS: usize = 10_000;
 const MAX_INDEXABLE_POST_BODY_BYTES: u64 = (MAX_INDEXABLE_POST_BODY_CHARS as u64) * 4;
+/// session manifest blob の一時取得上限（metaverse room は scene / chat 履歴を含むため post 本文より大きい）。
+const MAX_SESSION_MANIFEST_BYTES: u64 = 1024 * 1024;
 
 /// 単一 scope（topic / channel）を ingest した結果のサマリ（監査 / テスト用）。
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -58,6 +77,10 @@ pub struct IngestSummary {
     pub skipped_non_allow: usize,
     /// tombstone / deleted で de-index した entry 数。
     pub deindexed: usize,
+    /// people search 用に投影した著者 profile 数（public topic のみ）。
+    pub profiles_indexed: usize,
+    /// live now 用に投影した進行中の live session / game room 数。
+    pub sessions_indexed: usize,
 }
 
 /// ingest pipeline。docs replica + safety scan service + index 投影を束ねる。
@@ -172,11 +195,17 @@ impl IngestPipeline {
             .with_context(|| format!("failed to query replica {}", replica_id.as_str()))?;
 
         // 同一 prefix scan の envelope entry を object_id -> envelope record で index 化し、
-        // blob text の本文取得で追加クエリ（N+1）を発生させないようにする。
+        // blob text の本文取得で追加クエリ（N+1）を発生させないようにする。編集 revision
+        // （`objects/<id>/revisions/<sort_key>`）は revision envelope id -> record で持つ。
         let mut envelopes: HashMap<String, DocRecord> = HashMap::new();
+        let mut revisions: HashMap<String, DocRecord> = HashMap::new();
         let mut state_records: Vec<DocRecord> = Vec::new();
         for record in records {
-            if let Some(object_id) = record.key.strip_suffix("/envelope") {
+            if record.key.contains("/revisions/") {
+                if let Ok(envelope) = serde_json::from_slice::<KukuriEnvelope>(&record.value) {
+                    revisions.insert(envelope.id.as_str().to_string(), record);
+                }
+            } else if let Some(object_id) = record.key.strip_suffix("/envelope") {
                 if let Some(object_id) = object_id.strip_prefix("objects/") {
                     envelopes.insert(object_id.to_string(), record);
                 }
@@ -185,14 +214,25 @@ impl IngestPipeline {
             }
         }
 
+        let engagement = self.observe_engagement(replica_id, &state_records).await;
+        let scope_records = ScopeRecords {
+            envelopes,
+            revisions,
+            engagement,
+        };
+
         let mut summary = IngestSummary::default();
+        let mut indexed_authors: BTreeSet<String> = BTreeSet::new();
         for record in &state_records {
             summary.scanned += 1;
             match self
-                .ingest_object_record(scope_kind, scope_id, replica_id, record, &envelopes)
+                .ingest_object_record(scope_kind, scope_id, replica_id, record, &scope_records)
                 .await
             {
-                Ok(IngestOutcome::Indexed) => summary.indexed += 1,
+                Ok(IngestOutcome::Indexed { author }) => {
+                    summary.indexed += 1;
+                    indexed_authors.insert(author);
+                }
                 Ok(IngestOutcome::SkippedNonAllow) => summary.skipped_non_allow += 1,
                 Ok(IngestOutcome::Deindexed) => summary.deindexed += 1,
                 Ok(IngestOutcome::Ignored) => {}
@@ -208,16 +248,469 @@ impl IngestPipeline {
                 }
             }
         }
+
+        // people search: index 済み post を持つ著者の profile を同じ scope へ投影する。private
+        // channel の参加者は横断の people search へ出さない。post が 1 件も index されていない
+        // 著者の profile は de-index する。
+        if scope_kind == IndexScopeKind::PublicTopic {
+            let seen_authors: BTreeSet<String> = state_records
+                .iter()
+                .filter_map(|record| serde_json::from_slice::<PostObjectView>(&record.value).ok())
+                .map(|object| object.author)
+                .collect();
+            for author in seen_authors {
+                let result = if indexed_authors.contains(&author) {
+                    self.ingest_author_profile(scope_kind, scope_id, &author)
+                        .await
+                } else {
+                    self.deindex_object(scope_kind, scope_id, &profile_object_id(&author))
+                        .await
+                        .map(|()| false)
+                };
+                match result {
+                    Ok(true) => summary.profiles_indexed += 1,
+                    Ok(false) => {}
+                    Err(error) => warn!(
+                        scope_id = %scope_id,
+                        author = %author,
+                        error = %format!("{error:#}"),
+                        "failed to ingest author profile; not indexing it (fail-closed)"
+                    ),
+                }
+            }
+        }
+
+        // live now: scope の replica 上の live session / game room を投影し、終了したものは
+        // de-index する。
+        let session_records = self
+            .docs_sync
+            .query_replica_with_policy(
+                replica_id,
+                DocQuery::Prefix(stable_key("sessions", "")),
+                DocFetchPolicy::LocalThenRemote,
+            )
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to query sessions of replica {}",
+                    replica_id.as_str()
+                )
+            })?;
+        for record in session_records
+            .iter()
+            .filter(|record| record.key.ends_with("/state"))
+        {
+            match self
+                .ingest_session_record(scope_kind, scope_id, replica_id, record)
+                .await
+            {
+                Ok(IngestOutcome::Indexed { .. }) => summary.sessions_indexed += 1,
+                Ok(IngestOutcome::SkippedNonAllow) => summary.skipped_non_allow += 1,
+                Ok(IngestOutcome::Deindexed) => summary.deindexed += 1,
+                Ok(IngestOutcome::Ignored) => {}
+                Err(error) => {
+                    warn!(
+                        replica_id = %replica_id.as_str(),
+                        key = %record.key,
+                        error = %format!("{error:#}"),
+                        "failed to ingest session record; skipping (fail-closed)"
+                    );
+                    summary.skipped_non_allow += 1;
+                }
+            }
+        }
         Ok(summary)
     }
 
+    /// live session / game room の state 1 件を scan→allow 判定して live now 投影へ反映する。
+    ///
+    /// title / description は state が指す manifest blob から一時取得し、宣言サイズと BLAKE3 hash、
+    /// state との session id / owner / topic / channel の一致を検証する。scope 外（public topic に
+    /// channel の session、別 channel の session）の state は扱わない。終了済みは de-index し、
+    /// manifest の解決失敗・非 allow も de-index する（fail-closed）。
+    async fn ingest_session_record(
+        &self,
+        scope_kind: IndexScopeKind,
+        scope_id: &str,
+        replica_id: &ReplicaId,
+        record: &DocRecord,
+    ) -> Result<IngestOutcome> {
+        let state = match SessionState::decode(record) {
+            Ok(Some(state)) => state,
+            Ok(None) => return Ok(IngestOutcome::Ignored),
+            Err(error) => {
+                debug!(key = %record.key, error = %error, "record is not a session state; ignoring");
+                return Ok(IngestOutcome::Ignored);
+            }
+        };
+        let in_scope = match scope_kind {
+            IndexScopeKind::PublicTopic => state.channel_id.is_none(),
+            IndexScopeKind::PrivateChannel => state
+                .channel_id
+                .as_ref()
+                .is_some_and(|channel_id| channel_id.as_str() == scope_id),
+        };
+        if !in_scope {
+            return Ok(IngestOutcome::Ignored);
+        }
+        let object_id = state.object_id();
+        if state.ended {
+            self.deindex_object(scope_kind, scope_id, &object_id)
+                .await?;
+            return Ok(IngestOutcome::Deindexed);
+        }
+
+        let session = match self.resolve_session_manifest(&state).await {
+            Ok(Some(session)) => session,
+            Ok(None) => {
+                self.deindex_object(scope_kind, scope_id, &object_id)
+                    .await?;
+                return Ok(IngestOutcome::Deindexed);
+            }
+            Err(error) => {
+                self.deindex_object(scope_kind, scope_id, &object_id)
+                    .await?;
+                warn!(
+                    object_id = %object_id,
+                    error = %format!("{error:#}"),
+                    "failed to resolve session manifest; not indexing the session (fail-closed)"
+                );
+                return Ok(IngestOutcome::SkippedNonAllow);
+            }
+        };
+        let text = [Some(session.title.as_str()), session.description.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // session の告知文は post と同じく作成者の content として scan する。
+        let request = ProviderScanRequest::for_subject(SubjectKind::Post, object_id.clone())
+            .with_text(text.clone());
+        let outcome = self
+            .scan_and_record_with_metrics(&request, state.owner.as_str())
+            .await?;
+        let report = &outcome.report;
+        let Some(verdict_id) = outcome
+            .verdict_id
+            .as_deref()
+            .filter(|_| report.verdict.is_indexable())
+        else {
+            self.deindex_object(scope_kind, scope_id, &object_id)
+                .await?;
+            debug!(
+                object_id = %object_id,
+                reason = ?report.verdict.reason_code,
+                "session verdict is not allow; not indexing (fail-closed)"
+            );
+            return Ok(IngestOutcome::SkippedNonAllow);
+        };
+
+        // session state の作成時刻は unix ミリ秒なので、post と同じ unix 秒へ揃える。
+        let created_at = state.created_at.div_euclid(1000);
+        let author = state.owner.as_str().to_string();
+        self.entries
+            .upsert_entry(&NewIndexEntry {
+                scope_kind,
+                scope_id: scope_id.to_string(),
+                object_id: object_id.clone(),
+                author_pubkey: author.clone(),
+                created_at,
+                source_replica_id: replica_id.as_str().to_string(),
+                verdict_id: verdict_id.to_string(),
+                verdict_action: report.verdict.action.as_str().to_string(),
+                critical: report.verdict.critical,
+                engagement: EntryEngagement::default(),
+            })
+            .await
+            .context("failed to record session entry in the authoritative store")?;
+        self.projection
+            .upsert_entry(&IndexedEntry {
+                scope_kind,
+                scope_id: scope_id.to_string(),
+                object_id,
+                author_pubkey: author.clone(),
+                text,
+                created_at,
+                source_replica_id: replica_id.as_str().to_string(),
+                entry_kind: IndexEntryKind::Session,
+                profile: None,
+                session: Some(session),
+            })
+            .await?;
+        Ok(IngestOutcome::Indexed { author })
+    }
+
+    /// session state が指す manifest blob を一時取得し、live now の表示項目へ変換する。
+    ///
+    /// manifest が終了済みなら `None`。blob service 未構成・取得不可・検証失敗は Err。
+    async fn resolve_session_manifest(
+        &self,
+        state: &SessionState,
+    ) -> Result<Option<IndexSessionView>> {
+        let session = match state.kind {
+            SessionStateKind::Live => {
+                let manifest: LiveSessionManifestBlobV1 =
+                    self.fetch_session_manifest(&state.manifest).await?;
+                state.ensure_manifest_matches(
+                    &manifest.session_id,
+                    &manifest.owner_pubkey,
+                    &manifest.topic_id,
+                    manifest.channel_id.as_ref(),
+                )?;
+                let status = match manifest.status {
+                    LiveSessionStatus::Scheduled => IndexSessionStatus::Scheduled,
+                    LiveSessionStatus::Live => IndexSessionStatus::Live,
+                    LiveSessionStatus::Paused => IndexSessionStatus::Paused,
+                    LiveSessionStatus::Ended => return Ok(None),
+                };
+                IndexSessionView {
+                    session_kind: IndexSessionKind::LiveSession,
+                    session_id: manifest.session_id,
+                    topic_id: manifest.topic_id.as_str().to_string(),
+                    status,
+                    title: manifest.title,
+                    description: Some(manifest.description),
+                    participant_count: None,
+                }
+            }
+            SessionStateKind::Game => {
+                let manifest: GameRoomManifestBlobV1 =
+                    self.fetch_session_manifest(&state.manifest).await?;
+                state.ensure_manifest_matches(
+                    &manifest.room_id,
+                    &manifest.owner_pubkey,
+                    &manifest.topic_id,
+                    manifest.channel_id.as_ref(),
+                )?;
+                let status = match manifest.status {
+                    GameRoomStatus::Waiting => IndexSessionStatus::Waiting,
+                    GameRoomStatus::Running => IndexSessionStatus::Running,
+                    GameRoomStatus::Paused => IndexSessionStatus::Paused,
+                    GameRoomStatus::Ended => return Ok(None),
+                };
+                IndexSessionView {
+                    session_kind: match manifest.room_kind {
+                        GameRoomKind::ScoreGame => IndexSessionKind::GameRoom,
+                        GameRoomKind::MetaverseRoom => IndexSessionKind::MetaverseRoom,
+                    },
+                    session_id: manifest.room_id,
+                    topic_id: manifest.topic_id.as_str().to_string(),
+                    status,
+                    title: manifest.title,
+                    description: Some(manifest.description),
+                    participant_count: Some(
+                        u32::try_from(manifest.participants.len()).unwrap_or(u32::MAX),
+                    ),
+                }
+            }
+        };
+        let title = session.title.trim().to_string();
+        if title.is_empty() {
+            bail!("session manifest has an empty title");
+        }
+        let description = session
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|description| !description.is_empty())
+            .map(str::to_string);
+        Ok(Some(IndexSessionView {
+            title,
+            description,
+            ..session
+        }))
+    }
+
+    /// manifest blob を一時取得し、宣言サイズ・上限・BLAKE3 hash を検証して decode する。
+    async fn fetch_session_manifest<T: DeserializeOwned>(
+        &self,
+        manifest: &ManifestBlobRef,
+    ) -> Result<T> {
+        if manifest.bytes > MAX_SESSION_MANIFEST_BYTES {
+            bail!(
+                "session manifest declared size exceeds the limit ({} > {} bytes)",
+                manifest.bytes,
+                MAX_SESSION_MANIFEST_BYTES
+            );
+        }
+        let blob_service = self
+            .blob_service
+            .as_ref()
+            .context("blob service is not configured for session manifests")?;
+        let fetched = blob_service
+            .fetch_blob_ephemeral(&manifest.hash)
+            .await
+            .context("failed to fetch session manifest")?
+            .context("session manifest is not retrievable")?;
+        let actual_bytes =
+            u64::try_from(fetched.len()).context("session manifest size does not fit in u64")?;
+        if actual_bytes != manifest.bytes {
+            bail!(
+                "session manifest size does not match the state ({} != {} bytes)",
+                actual_bytes,
+                manifest.bytes
+            );
+        }
+        if blob_hash(&fetched) != manifest.hash {
+            bail!("session manifest hash does not match the state");
+        }
+        serde_json::from_slice(&fetched).context("failed to decode session manifest")
+    }
+
+    /// 著者の最新 profile を scan→allow 判定して scope の people search 投影へ反映する。
+    ///
+    /// 対象は著者 replica の `profile/latest` が指す署名済み profile envelope のみで、署名検証と
+    /// 署名者 = 著者の一致を要求する。profile が無い / 検索語が空 / 非 allow / 解決失敗は
+    /// de-index する（fail-closed）。投影したら true を返す。
+    async fn ingest_author_profile(
+        &self,
+        scope_kind: IndexScopeKind,
+        scope_id: &str,
+        author: &str,
+    ) -> Result<bool> {
+        let object_id = profile_object_id(author);
+        let profile = match self.resolve_author_profile(author).await {
+            Ok(Some(profile)) => profile,
+            Ok(None) => {
+                self.deindex_object(scope_kind, scope_id, &object_id)
+                    .await?;
+                return Ok(false);
+            }
+            Err(error) => {
+                self.deindex_object(scope_kind, scope_id, &object_id)
+                    .await?;
+                return Err(error);
+            }
+        };
+        let text = [&profile.name, &profile.display_name, &profile.about]
+            .into_iter()
+            .filter_map(|field| field.as_deref().map(str::trim))
+            .filter(|field| !field.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if text.is_empty() {
+            self.deindex_object(scope_kind, scope_id, &object_id)
+                .await?;
+            return Ok(false);
+        }
+
+        let request = ProviderScanRequest::for_subject(SubjectKind::User, author.to_string())
+            .with_text(text.clone());
+        let outcome = self.scan_and_record_with_metrics(&request, author).await?;
+        let report = &outcome.report;
+        let Some(verdict_id) = outcome
+            .verdict_id
+            .as_deref()
+            .filter(|_| report.verdict.is_indexable())
+        else {
+            self.deindex_object(scope_kind, scope_id, &object_id)
+                .await?;
+            debug!(
+                author = %author,
+                reason = ?report.verdict.reason_code,
+                "profile verdict is not allow; not indexing (fail-closed)"
+            );
+            return Ok(false);
+        };
+
+        // profile envelope の作成時刻は unix ミリ秒なので、post と同じ unix 秒へ揃える。
+        let created_at = profile.updated_at.div_euclid(1000);
+        let source_replica_id = author_replica_id(author).as_str().to_string();
+        self.entries
+            .upsert_entry(&NewIndexEntry {
+                scope_kind,
+                scope_id: scope_id.to_string(),
+                object_id: object_id.clone(),
+                author_pubkey: author.to_string(),
+                created_at,
+                source_replica_id: source_replica_id.clone(),
+                verdict_id: verdict_id.to_string(),
+                verdict_action: report.verdict.action.as_str().to_string(),
+                critical: report.verdict.critical,
+                engagement: EntryEngagement::default(),
+            })
+            .await
+            .context("failed to record profile entry in the authoritative store")?;
+        self.projection
+            .upsert_entry(&IndexedEntry {
+                scope_kind,
+                scope_id: scope_id.to_string(),
+                object_id,
+                author_pubkey: author.to_string(),
+                text,
+                created_at,
+                source_replica_id,
+                entry_kind: IndexEntryKind::Profile,
+                profile: Some(IndexProfileView {
+                    name: profile.name,
+                    display_name: profile.display_name,
+                    about: profile.about,
+                }),
+                session: None,
+            })
+            .await?;
+        Ok(true)
+    }
+
+    /// 著者 replica から署名済みの最新 profile を解決する（未公開なら `None`）。
+    async fn resolve_author_profile(&self, author: &str) -> Result<Option<Profile>> {
+        let replica_id = author_replica_id(author);
+        self.docs_sync.open_replica(&replica_id).await?;
+        let Some(record) = self
+            .docs_sync
+            .query_replica_with_policy(
+                &replica_id,
+                DocQuery::Exact(stable_key("profile", "latest")),
+                DocFetchPolicy::LocalThenRemote,
+            )
+            .await
+            .context("failed to query author profile")?
+            .into_iter()
+            .next()
+        else {
+            return Ok(None);
+        };
+        let doc: AuthorProfileDocV1 =
+            serde_json::from_slice(&record.value).context("failed to decode author profile doc")?;
+        if doc.author_pubkey.as_str() != author {
+            bail!("author profile doc belongs to a different author");
+        }
+        let Some(record) = self
+            .docs_sync
+            .query_replica_with_policy(
+                &replica_id,
+                DocQuery::Exact(stable_key("envelopes", doc.envelope_id.as_str())),
+                DocFetchPolicy::LocalThenRemote,
+            )
+            .await
+            .context("failed to query author profile envelope")?
+            .into_iter()
+            .next()
+        else {
+            bail!("author profile envelope is not present in the replica");
+        };
+        let envelope: KukuriEnvelope = serde_json::from_slice(&record.value)
+            .context("failed to decode author profile envelope")?;
+        envelope
+            .verify()
+            .context("author profile envelope failed verification")?;
+        if envelope.pubkey.as_str() != author {
+            bail!("author profile envelope is not signed by the author (fail-closed)");
+        }
+        match parse_profile(&envelope)? {
+            Some(profile) => Ok(Some(profile)),
+            None => bail!("author profile envelope is not a profile"),
+        }
+    }
+
     async fn ingest_object_record(
         &self,
         scope_kind: IndexScopeKind,
         scope_id: &str,
         replica_id: &ReplicaId,
         record: &DocRecord,
-        envelopes: &HashMap<String, DocRecord>,
+        scope_records: &ScopeRecords,
     ) -> Result<IngestOutcome> {
         let object: PostObjectView = match serde_json::from_slice(&record.value) {
             Ok(object) => object,
@@ -239,7 +732,15 @@ impl IngestPipeline {
         }
 
         // 本文 text を取り出す。blob 参照は scan 用の一時 fetch のみ（恒久保存しない）。
-        let text = match self.resolve_body_text(replica_id, &object, envelopes).await {
+        let text = match self
+            .resolve_body_text(
+                replica_id,
+                &object,
+                &scope_records.envelopes,
+                &scope_records.revisions,
+            )
+            .await
+        {
             Ok(text) => text,
             Err(error) => {
                 self.deindex_object(scope_kind, scope_id, object.object_id.as_str())
@@ -349,6 +850,11 @@ impl IngestPipeline {
                 verdict_id: verdict_id.to_string(),
                 verdict_action: report.verdict.action.as_str().to_string(),
                 critical: report.verdict.critical,
+                engagement: scope_records
+                    .engagement
+                    .get(object.object_id.as_str())
+                    .copied()
+                    .unwrap_or_default(),
             })
             .await
             .context("failed to record index entry in the authoritative store")?;
@@ -365,9 +871,71 @@ impl IngestPipeline {
             text: text_with_tags(&text, &derived_tags),
             created_at: object.created_at,
             source_replica_id: replica_id.as_str().to_string(),
+            entry_kind: IndexEntryKind::Post,
+            profile: None,
+            session: None,
         };
         self.projection.upsert_entry(&entry).await?;
-        Ok(IngestOutcome::Indexed)
+        Ok(IngestOutcome::Indexed {
+            author: entry.author_pubkey,
+        })
+    }
+
+    /// recommendation ranking の engagement 入力を、同じ replica 上の返信と反応から数える。
+    ///
+    /// 返信は scope 内の有効な post state の `reply_to`、反応は `reactions/<target>/<id>/state`
+    /// の有効な state。ranking の advisory 入力なので、反応の取得に失敗しても ingest は止めず
+    /// 返信数だけで続ける。
+    async fn observe_engagement(
+        &self,
+        replica_id: &ReplicaId,
+        state_records: &[DocRecord],
+    ) -> HashMap<String, EntryEngagement> {
+        let mut engagement: HashMap<String, EntryEngagement> = HashMap::new();
+        for record in state_records {
+            let Ok(object) = serde_json::from_slice::<PostObjectView>(&record.value) else {
+                continue;
+            };
+            if let Some(parent) = object.reply_to.as_deref()
+                && is_live(&object.status)
+            {
+                engagement.entry(parent.to_string()).or_default().replies += 1;
+            }
+        }
+        let reactions = match self
+            .docs_sync
+            .query_replica_with_policy(
+                replica_id,
+                DocQuery::Prefix(stable_key("reactions", "")),
+                DocFetchPolicy::LocalThenRemote,
+            )
+            .await
+        {
+            Ok(records) => records,
+            Err(error) => {
+                warn!(
+                    replica_id = %replica_id.as_str(),
+                    error = %error,
+                    "failed to query reactions; ranking engagement counts replies only"
+                );
+                Vec::new()
+            }
+        };
+        for record in reactions {
+            if !record.key.ends_with("/state") {
+                continue;
+            }
+            let Ok(reaction) = serde_json::from_slice::<ReactionStateView>(&record.value) else {
+                continue;
+            };
+            if is_live(&reaction.status) {
+                engagement
+                    .entry(reaction.target_object_id)
+                    .or_default()
+                    .reactions += 1;
+            }
+        }
+        engagement
     }
 
     /// object を index 真実源 → 投影の順で両方から消す。
@@ -475,11 +1043,13 @@ impl IngestPipeline {
     /// inline text はそのまま返す。blob text は同一 scope scan で取得済みの署名済み envelope と object
     /// state の参照を突合し、`BlobService::fetch_blob_ephemeral` で本文 bytes を一時取得する。取得した
     /// bytes は宣言サイズ・上限・BLAKE3 hash・UTF-8 を検証し、raw blob は恒久保存しない。
+    /// 編集済み object は state の `envelope_id` が指す署名済み edit revision と突合する。
     async fn resolve_body_text(
         &self,
         _replica_id: &ReplicaId,
         object: &PostObjectView,
         envelopes: &HashMap<String, DocRecord>,
+        revisions: &HashMap<String, DocRecord>,
     ) -> Result<String> {
         match &object.payload_ref {
             PayloadRef::InlineText { text } => Ok(text.clone()),
@@ -491,25 +1061,57 @@ impl IngestPipeline {
                         MAX_INDEXABLE_POST_BODY_BYTES
                     );
                 }
-                let record = envelopes
-                    .get(object.object_id.as_str())
-                    .context("blob text envelope is missing")?;
-                let envelope: KukuriEnvelope = serde_json::from_slice(&record.value)
-                    .context("failed to decode post envelope for blob text")?;
-                envelope
-                    .verify()
-                    .context("post envelope failed verification")?;
-                if envelope.id.as_str() != object.object_id {
-                    bail!("blob text envelope id does not match the object state");
-                }
-                if envelope.pubkey.as_str() != object.author {
-                    bail!("blob text envelope author does not match the object state");
-                }
-                let content = envelope
-                    .post_content()
-                    .context("failed to parse blob text post content")?
-                    .context("blob text envelope is not a post")?;
-                if content.payload_ref != object.payload_ref {
+                let signed_payload_ref = match object
+                    .envelope_id
+                    .as_deref()
+                    .filter(|envelope_id| *envelope_id != object.object_id)
+                {
+                    Some(revision_id) => {
+                        let record = revisions
+                            .get(revision_id)
+                            .context("blob text revision envelope is missing")?;
+                        let envelope: KukuriEnvelope = serde_json::from_slice(&record.value)
+                            .context("failed to decode post revision envelope for blob text")?;
+                        envelope
+                            .verify()
+                            .context("post revision envelope failed verification")?;
+                        if envelope.pubkey.as_str() != object.author {
+                            bail!("blob text revision author does not match the object state");
+                        }
+                        let revision = envelope
+                            .post_revision_content()
+                            .context("failed to parse blob text post revision content")?
+                            .context("blob text revision envelope is not a post revision")?;
+                        if revision.object_id.as_str() != object.object_id {
+                            bail!("blob text revision targets a different object");
+                        }
+                        revision
+                            .payload_ref
+                            .context("blob text revision does not carry a payload")?
+                    }
+                    None => {
+                        let record = envelopes
+                            .get(object.object_id.as_str())
+                            .context("blob text envelope is missing")?;
+                        let envelope: KukuriEnvelope = serde_json::from_slice(&record.value)
+                            .context("failed to decode post envelope for blob text")?;
+                        envelope
+                            .verify()
+                            .context("post envelope failed verification")?;
+                        if envelope.id.as_str() != object.object_id {
+                            bail!("blob text envelope id does not match the object state");
+                        }
+                        if envelope.pubkey.as_str() != object.author {
+                            bail!("blob text envelope author does not match the object state");
+                        }
+                        envelope
+                            .post_content()
+                            .context("failed to parse blob text post content")?
+                            .context("blob text envelope is not a post")?
+                            .payload_ref
+                    }
+                };
+                if signed_payload_ref != object.payload_ref {
                     bail!("blob text payload metadata does not match the signed envelope");
                 }
 
@@ -556,8 +1158,18 @@ impl IngestPipeline {
     }
 }
 
+/// 1 scope の prefix 走査から組み立てた、entry ごとの ingest が参照する索引。
+struct ScopeRecords {
+    /// object id → envelope record。
+    envelopes: HashMap<String, DocRecord>,
+    /// revision envelope id → record。
+    revisions: HashMap<String, DocRecord>,
+    /// object id → 観測した engagement。
+    engagement: HashMap<String, EntryEngagement>,
+}
+
 enum IngestOutcome {
-    Indexed,
+    Indexed { author: String },
     SkippedNonAllow,
     Deindexed,
     Ignored,
@@ -570,6 +1182,8 @@ enum IngestOutcome {
 #[derive(Debug, serde::Deserialize)]
 struct PostObjectView {
     object_id: String,
+    #[serde(default)]
+    envelope_id: Option<String>,
     author: String,
     created_at: i64,
     payload_ref: PayloadRef,
@@ -579,6 +1193,112 @@ struct PostObjectView {
     media_manifest_refs: Vec<String>,
     #[serde(default)]
     status: ObjectStatus,
+    #[serde(default)]
+    reply_to: Option<String>,
+}
+
+/// docs replica に保存された reaction state(`ReactionDocV1`)の最小 view。
+#[derive(Debug, serde::Deserialize)]
+struct ReactionStateView {
+    target_object_id: String,
+    #[serde(default)]
+    status: ObjectStatus,
+}
+
+fn is_live(status: &ObjectStatus) -> bool {
+    !matches!(status, ObjectStatus::Deleted | ObjectStatus::Tombstoned)
+}
+
+/// 著者 profile の投影 object id（scope 内で post id と衝突しない）。
+pub fn profile_object_id(author_pubkey: &str) -> String {
+    format!("profile:{author_pubkey}")
+}
+
+/// live session の投影 object id。
+pub fn live_session_object_id(session_id: &str) -> String {
+    format!("live:{session_id}")
+}
+
+/// game / metaverse room の投影 object id。
+pub fn game_room_object_id(room_id: &str) -> String {
+    format!("room:{room_id}")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SessionStateKind {
+    Live,
+    Game,
+}
+
+/// docs replica の `LiveSessionStateDocV1` / `GameRoomStateDocV1` を live now 用に揃えた view。
+#[derive(Debug)]
+struct SessionState {
+    kind: SessionStateKind,
+    session_id: String,
+    topic_id: TopicId,
+    channel_id: Option<ChannelId>,
+    owner: Pubkey,
+    created_at: i64,
+    ended: bool,
+    manifest: ManifestBlobRef,
+}
+
+impl SessionState {
+    /// `sessions/live/<id>/state` / `sessions/game/<id>/state` を読む（それ以外の key は `None`）。
+    fn decode(record: &DocRecord) -> Result<Option<Self>> {
+        if record.key.starts_with("sessions/live/") {
+            let state: LiveSessionStateDocV1 = serde_json::from_slice(&record.value)?;
+            Ok(Some(Self {
+                kind: SessionStateKind::Live,
+                session_id: state.session_id,
+                topic_id: state.topic_id,
+                channel_id: state.channel_id,
+                owner: state.owner_pubkey,
+                created_at: state.created_at,
+                ended: state.status == LiveSessionStatus::Ended,
+                manifest: state.current_manifest,
+            }))
+        } else if record.key.starts_with("sessions/game/") {
+            let state: GameRoomStateDocV1 = serde_json::from_slice(&record.value)?;
+            Ok(Some(Self {
+                kind: SessionStateKind::Game,
+                session_id: state.room_id,
+                topic_id: state.topic_id,
+                channel_id: state.channel_id,
+                owner: state.owner_pubkey,
+                created_at: state.created_at,
+                ended: state.status == GameRoomStatus::Ended,
+                manifest: state.current_manifest,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn object_id(&self) -> String {
+        match self.kind {
+            SessionStateKind::Live => live_session_object_id(&self.session_id),
+            SessionStateKind::Game => game_room_object_id(&self.session_id),
+        }
+    }
+
+    /// manifest が同じ session（id / owner / topic / channel）のものであることを確かめる。
+    fn ensure_manifest_matches(
+        &self,
+        session_id: &str,
+        owner: &Pubkey,
+        topic_id: &TopicId,
+        channel_id: Option<&ChannelId>,
+    ) -> Result<()> {
+        if session_id != self.session_id
+            || owner != &self.owner
+            || topic_id != &self.topic_id
+            || channel_id != self.channel_id.as_ref()
+        {
+            bail!("session manifest does not belong to the session state");
+        }
+        Ok(())
+    }
 }
 
 /// scan 対象の media 参照 1 件（blob hash + 参照元 metadata 由来の mime）。
@@ -5,25 +5,30 @@
 //!
 //! 1. supported topic / 許可 channel の共有 replica を sync する participant（`participant`）。
 //! 2. 共有 replica に実在する post entry のみを scan→`allow` 判定して index 投影に書く（`ingest`）。
-//! 3. index 投影 store の境界と ArcadeDB adapter（`projection` / `arcadedb`）。全文のみ、canonical
-//!    ではない写像。
+//! 3. index 投影 store の境界と ArcadeDB adapter / 組み込み（tantivy）adapter（`projection` /
+//!    `arcadedb` / `embedded`）。全文のみ、canonical ではない写像。どちらを使うかは `config` の
+//!    backend 選択で決まり、`backend` が具象実装を組み立てる。
 //! 4. relay validation 起動 gate（`config`）: 自前 relay も外部 relay も無ければ indexing を起動しない。
 //!
 //! scope 管理 state（supported set / user request / channel capability）は cn-core（Postgres）が所有し、
 //! ユーザー向け indexing request 受付 API は cn-user-api が持つ。ユーザー向け search / discovery /
 //! recommendation の query 境界と fail-closed query gate は `query`（#404）が持ち、cn-user-api が
-//! それを read エンドポイントとして公開する。
+//! それを read エンドポイントとして公開する。recommendation は gate 通過済みの候補を `ranking` が
+//! recency / engagement / 著者 trust / viewer proximity で並べ替える。
 //!
 //! 設計の真実源:
 //! - `docs/adr/0025-community-node-indexing-foundation.md`（§2.2 scope / §2.5 fail-closed / §2.7 検索 UX / §6 Model C）
 
 pub mod arcadedb;
+pub mod backend;
 pub mod config;
+pub mod embedded;
 pub mod ingest;
 pub mod media_fetcher;
 pub mod participant;
 pub mod projection;
 pub mod query;
+pub mod ranking;
 pub mod relation_graph;
 pub mod relation_worker;
 pub mod runtime;
@@ -32,12 +37,27 @@ pub mod status;
 pub mod worker;
 
 pub use arcadedb::ArcadeDbProjection;
-pub use config::{ArcadeDbConfig, IndexerConfig, MediaFetchConfig, RelayConfig, RelayValidation};
+pub use backend::{
+    count_index_projection, open_index_projection, open_index_query, prepare_relation_store,
+    relation_store,
+};
+pub use config::{
+    ArcadeDbConfig, IndexBackendConfig, IndexerConfig, MediaFetchConfig, RelayConfig,
+    RelayValidation,
+};
+pub use embedded::EmbeddedIndexProjection;
 pub use ingest::{IngestPipeline, IngestSummary};
 pub use media_fetcher::BlobMediaFetcher;
 pub use participant::{IndexerParticipant, ScopeReplica};
 pub use projection::{IndexProjection, IndexedEntry, MemoryIndexProjection};
-pub use query::{FailClosedIndexQuery, IndexQuery, MAX_QUERY_LIMIT, clamp_query_limit};
+pub use query::{
+    FailClosedIndexQuery, IndexCursor, IndexPage, IndexPosition, IndexQuery, IndexQueryFilter,
+    MAX_QUERY_LIMIT, clamp_query_limit,
+};
+pub use ranking::{
+    AuthorTrustSource, RankedEntry, RankedPosition, RankingParams, RankingSignals,
+    RecommendationRanker, StaticAuthorTrust, score_entry,
+};
 pub use relation_graph::ArcadeDbRelationGraph;
 pub use relation_worker::{
     DEFAULT_ANALYSIS_LIMIT, RelationAnalysisReport, analyze_relations, topic_cluster,
@@ -309,6 +309,10 @@ mod tests {
                 Ok(())
             }
 
+            async fn unpin_blob(&self, _hash: &BlobHash) -> anyhow::Result<()> {
+                Ok(())
+            }
+
             async fn blob_status(
                 &self,
                 _hash: &BlobHash,
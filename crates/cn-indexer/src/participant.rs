@@ -237,6 +237,8 @@ impl IndexerParticipant {
                     total.indexed += summary.indexed;
                     total.skipped_non_allow += summary.skipped_non_allow;
                     total.deindexed += summary.deindexed;
+                    total.profiles_indexed += summary.profiles_indexed;
+                    total.sessions_indexed += summary.sessions_indexed;
                 }
                 Err(error) => warn!(
                     kind = scope.kind.as_str(),
@@ -322,6 +324,10 @@ mod tests {
             unreachable!("not used by this contract test")
         }
 
+        async fn unpin_blob(&self, _hash: &BlobHash) -> Result<()> {
+            unreachable!("not used by this contract test")
+        }
+
         async fn blob_status(&self, _hash: &BlobHash) -> Result<BlobStatus> {
             unreachable!("not used by this contract test")
         }
@@ -12,13 +12,15 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 use kukuri_cn_core::IndexScopeKind;
+use kukuri_cn_protocol::{IndexEntryKind, IndexProfileView, IndexSessionView};
 
-use crate::query::IndexQuery;
+use crate::query::{IndexPage, IndexQuery, IndexQueryFilter};
 
 /// 投影 1 件（検索対象エントリ）。
 ///
-/// canonical ではない derived な写像。text は post 本文（media は将来 VLM 派生タグ）で、raw blob は
-/// 含めない（no permanent blob storage / ADR 0025 §2.3）。
+/// canonical ではない derived な写像。text は post 本文（media は将来 VLM 派生タグ）、profile entry
+/// では署名済み profile の name / display_name / about、session entry では title / description で、
+/// raw blob は含めない（no permanent blob storage / ADR 0025 §2.3）。
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IndexedEntry {
     /// scope 種別（public_topic / private_channel）。
@@ -35,6 +37,15 @@ pub struct IndexedEntry {
     pub created_at: i64,
     /// 由来の共有 replica id（監査用。ghost 注入でないことの追跡）。
     pub source_replica_id: String,
+    /// entry 種別（post / profile / session）。旧投影の行は種別を持たないため post とみなす。
+    #[serde(default)]
+    pub entry_kind: IndexEntryKind,
+    /// profile entry の署名済み profile 項目（post では `None`）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<IndexProfileView>,
+    /// session entry の状態（status / title / 参加者数。post / profile では `None`）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<IndexSessionView>,
 }
 
 /// index 投影 store の境界。ArcadeDB / in-memory が同じ API を満たす。
@@ -180,70 +191,79 @@ fn memory_text_matches(text: &str, query: &str) -> bool {
         .any(|term| !term.is_empty() && text.contains(&term.to_lowercase()))
 }
 
-#[async_trait]
-impl IndexQuery for MemoryIndexProjection {
-    async fn search_scope(
+impl MemoryIndexProjection {
+    /// 条件に合う entry を並び順で整列し、`limit + 1` 件の window から page を作る。
+    async fn page_where(
         &self,
-        scope_kind: IndexScopeKind,
-        scope_id: &str,
-        query: &str,
+        filter: &IndexQueryFilter,
         limit: usize,
-    ) -> Result<Vec<IndexedEntry>> {
+        predicate: impl Fn(&IndexedEntry) -> bool + Send,
+    ) -> IndexPage {
         let mut hits: Vec<IndexedEntry> = self
             .entries
             .lock()
             .await
             .iter()
-            .filter(|entry| {
+            .filter(|entry| predicate(entry) && filter.admits(entry))
+            .cloned()
+            .collect();
+        hits.sort_by(|left, right| filter.compare_entries(left, right));
+        hits.truncate(limit.saturating_add(1));
+        IndexPage::from_window(hits, limit)
+    }
+}
+
+#[async_trait]
+impl IndexQuery for MemoryIndexProjection {
+    async fn search_scope_page(
+        &self,
+        scope_kind: IndexScopeKind,
+        scope_id: &str,
+        query: &str,
+        filter: &IndexQueryFilter,
+        limit: usize,
+    ) -> Result<IndexPage> {
+        Ok(self
+            .page_where(filter, limit, |entry| {
                 entry.scope_kind == scope_kind
                     && entry.scope_id == scope_id
                     && memory_text_matches(&entry.text, query)
             })
-            .cloned()
-            .collect();
-        hits.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-        hits.truncate(limit);
-        Ok(hits)
+            .await)
     }
 
-    async fn search_all(&self, query: &str, limit: usize) -> Result<Vec<IndexedEntry>> {
-        let mut hits: Vec<IndexedEntry> = self
-            .entries
-            .lock()
-            .await
-            .iter()
-            .filter(|entry| memory_text_matches(&entry.text, query))
-            .cloned()
-            .collect();
-        hits.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-        hits.truncate(limit);
-        Ok(hits)
+    async fn search_all_page(
+        &self,
+        query: &str,
+        filter: &IndexQueryFilter,
+        limit: usize,
+    ) -> Result<IndexPage> {
+        Ok(self
+            .page_where(filter, limit, |entry| {
+                memory_text_matches(&entry.text, query)
+            })
+            .await)
     }
 
-    async fn list_recent(
+    async fn list_recent_page(
         &self,
         scope: Option<(IndexScopeKind, &str)>,
+        filter: &IndexQueryFilter,
         limit: usize,
-    ) -> Result<Vec<IndexedEntry>> {
-        let mut hits: Vec<IndexedEntry> = self
-            .entries
-            .lock()
-            .await
-            .iter()
-            .filter(|entry| match scope {
+    ) -> Result<IndexPage> {
+        Ok(self
+            .page_where(filter, limit, |entry| match scope {
                 Some((kind, id)) => entry.scope_kind == kind && entry.scope_id == id,
                 None => true,
             })
-            .cloned()
-            .collect();
-        hits.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-        hits.truncate(limit);
-        Ok(hits)
+            .await)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use kukuri_cn_protocol::IndexSortOrder;
+
     use super::*;
 
     fn entry(scope_id: &str, object_id: &str, text: &str) -> IndexedEntry {
@@ -255,6 +275,9 @@ mod tests {
             text: text.to_string(),
             created_at: 1,
             source_replica_id: format!("topic::{scope_id}"),
+            entry_kind: IndexEntryKind::Post,
+            profile: None,
+            session: None,
         }
     }
 
@@ -341,4 +364,74 @@ mod tests {
             1
         );
     }
+
+    #[tokio::test]
+    async fn pages_walk_every_entry_once_across_equal_timestamps() {
+        let projection = MemoryIndexProjection::new();
+        for (object_id, created_at) in [("o1", 10), ("o2", 20), ("o3", 20), ("o4", 30)] {
+            projection
+                .upsert_entry(&IndexedEntry {
+                    created_at,
+                    ..entry("t1", object_id, "post")
+                })
+                .await
+                .unwrap();
+        }
+        for (order, expected) in [
+            (IndexSortOrder::Newest, ["o4", "o3", "o2", "o1"]),
+            (IndexSortOrder::Oldest, ["o1", "o2", "o3", "o4"]),
+        ] {
+            let mut filter = IndexQueryFilter {
+                order,
+                ..IndexQueryFilter::default()
+            };
+            let mut seen = Vec::new();
+            loop {
+                let page = projection.list_recent_page(None, &filter, 2).await.unwrap();
+                seen.extend(page.entries.into_iter().map(|entry| entry.object_id));
+                match page.next {
+                    Some(next) => filter.after = Some(next),
+                    None => break,
+                }
+            }
+            assert_eq!(seen, expected, "{order:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn filter_narrows_by_author_and_time_window() {
+        let projection = MemoryIndexProjection::new();
+        for (object_id, author, created_at) in [
+            ("o1", "alice", 10),
+            ("o2", "bob", 20),
+            ("o3", "alice", 30),
+            ("o4", "alice", 40),
+        ] {
+            projection
+                .upsert_entry(&IndexedEntry {
+                    author_pubkey: author.to_string(),
+                    created_at,
+                    ..entry("t1", object_id, "post")
+                })
+                .await
+                .unwrap();
+        }
+        let filter = IndexQueryFilter {
+            author_pubkey: Some("alice".to_string()),
+            since: Some(10),
+            until: Some(40),
+            ..IndexQueryFilter::default()
+        };
+        let page = projection
+            .search_scope_page(IndexScopeKind::PublicTopic, "t1", "post", &filter, 10)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = page
+            .entries
+            .iter()
+            .map(|entry| entry.object_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["o3", "o1"]);
+        assert_eq!(page.next, None);
+    }
 }
@@ -4,8 +4,12 @@
 //! - topic 内検索（基本 UX。`search_scope`）
 //! - supported set 横断検索（別画面。`search_all`。投影には supported scope の entry しか
 //!   入らないため、投影全体 = supported set 全体）
-//! - 新着列挙（`list_recent`。discovery / recommendation の最小 surface。ranking / 関連度
-//!   スコアリングの具体は ADR 0025 §4 でスコープ外）
+//! - 新着列挙（`list_recent`。discovery の surface。recommendation はこれを候補生成に使い、
+//!   `crate::ranking` が gate 通過後に並べ替える）
+//!
+//! いずれも [`IndexQueryFilter`]（種別 / 著者 / 期間 / 並び順 / keyset 位置）つきの `*_page` が本体で、
+//! 続きの有無は [`IndexPage::next`] で返す。続き位置は gate で落とす前の投影 window から取るため、
+//! gate や opt-out で結果が limit 未満に減っても、続きがあるのに打ち切ることはない。
 //!
 //! fail-closed query gate（[`FailClosedIndexQuery`]）が唯一のユーザー向け入口である。投影
 //! （ArcadeDB）の hit を index 真実源（`cn_index.index_entries` + 最新 verdict join）と突合し、
@@ -14,14 +18,20 @@
 //! （`search_discovery_recommendation_excludes_non_allow`）が、投影の残留や de-index の遅延に
 //! 依存せず成立する。
 
+use std::cmp::Ordering;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
 
 use kukuri_cn_core::{IndexEntryStore, IndexScopeKind};
+use kukuri_cn_protocol::{IndexEntryKind, IndexSortOrder};
 
 use crate::projection::IndexedEntry;
+use crate::ranking::RankedPosition;
 
 /// query 1 回で返す hit 数の上限（投影への問い合わせと真実源突合の両方を有界にする）。
 pub const MAX_QUERY_LIMIT: usize = 100;
@@ -31,30 +41,232 @@ pub fn clamp_query_limit(limit: usize) -> usize {
     limit.clamp(1, MAX_QUERY_LIMIT)
 }
 
+/// keyset pagination の位置（直前 page の末尾 entry）。
+///
+/// 並びは `(created_at, scope_id, object_id)` の辞書順（`IndexSortOrder` の向き）で全順序にする。
+/// 同時刻の entry が page 境界をまたいでも、重複・欠落しない。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexPosition {
+    pub created_at: i64,
+    pub scope_id: String,
+    pub object_id: String,
+}
+
+impl IndexPosition {
+    pub fn of(entry: &IndexedEntry) -> Self {
+        Self {
+            created_at: entry.created_at,
+            scope_id: entry.scope_id.clone(),
+            object_id: entry.object_id.clone(),
+        }
+    }
+
+    /// entry が並びの上でこの位置以前（`(created_at, scope_id, object_id)` の辞書順で以下）か。
+    pub fn covers(&self, entry: &IndexedEntry) -> bool {
+        entry_key(entry) <= self.key()
+    }
+
+    fn key(&self) -> (i64, &str, &str) {
+        (
+            self.created_at,
+            self.scope_id.as_str(),
+            self.object_id.as_str(),
+        )
+    }
+}
+
+fn entry_key(entry: &IndexedEntry) -> (i64, &str, &str) {
+    (
+        entry.created_at,
+        entry.scope_id.as_str(),
+        entry.object_id.as_str(),
+    )
+}
+
+/// search / discovery / recommendation 候補生成に共通の絞り込み条件。
+///
+/// `since` は含み、`until` は含まない（unix 秒）。`after` は続き読みの keyset 位置。`entry_kind` は
+/// 既定が post で、post 向けの surface に profile / session entry が混ざらない。people search は
+/// profile、live now は session を指定する。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexQueryFilter {
+    pub entry_kind: IndexEntryKind,
+    pub author_pubkey: Option<String>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub order: IndexSortOrder,
+    pub after: Option<IndexPosition>,
+}
+
+impl IndexQueryFilter {
+    /// entry が種別 / 著者 / 期間 / keyset 位置の条件を満たすか（in-memory 実装用）。
+    pub fn admits(&self, entry: &IndexedEntry) -> bool {
+        if entry.entry_kind != self.entry_kind {
+            return false;
+        }
+        if self
+            .author_pubkey
+            .as_deref()
+            .is_some_and(|author| author != entry.author_pubkey)
+        {
+            return false;
+        }
+        if self.since.is_some_and(|since| entry.created_at < since) {
+            return false;
+        }
+        if self.until.is_some_and(|until| entry.created_at >= until) {
+            return false;
+        }
+        match self.after.as_ref() {
+            Some(after) => self.compare(entry_key(entry), after.key()) == Ordering::Greater,
+            None => true,
+        }
+    }
+
+    /// 要求された並び順での entry 同士の比較（in-memory 実装用）。
+    pub fn compare_entries(&self, left: &IndexedEntry, right: &IndexedEntry) -> Ordering {
+        self.compare(entry_key(left), entry_key(right))
+    }
+
+    fn compare(&self, left: (i64, &str, &str), right: (i64, &str, &str)) -> Ordering {
+        match self.order {
+            IndexSortOrder::Newest => right.cmp(&left),
+            IndexSortOrder::Oldest => left.cmp(&right),
+        }
+    }
+}
+
+/// 1 page 分の hit と、続きがある場合の次の keyset 位置。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexPage {
+    pub entries: Vec<IndexedEntry>,
+    pub next: Option<IndexPosition>,
+}
+
+impl IndexPage {
+    /// `limit + 1` 件まで取った window から page を作る（超過分があれば続きあり）。
+    pub fn from_window(mut window: Vec<IndexedEntry>, limit: usize) -> Self {
+        let next = if window.len() > limit {
+            window.truncate(limit);
+            window.last().map(IndexPosition::of)
+        } else {
+            None
+        };
+        Self {
+            entries: window,
+            next,
+        }
+    }
+}
+
+/// client へ渡す不透明な continuation cursor。
+///
+/// 中身は JSON の base64url で、client は解釈せず次の要求へそのまま返す。search / discovery は
+/// keyset 位置（並び順つき。順序を変えた再利用は拒否する）、recommendation は ranking の基準時刻・
+/// 候補 window の最新端・順位内の keyset 位置を運ぶ。
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IndexCursor {
+    After {
+        order: IndexSortOrder,
+        position: IndexPosition,
+    },
+    Ranked {
+        /// ranking の基準時刻（unix 秒）。続きの page も同じ時刻で recency を測る。
+        snapshot_at: i64,
+        /// 先頭 page の候補 window の最新端。続きの page はこれより新しい entry を候補に入れない。
+        watermark: Option<IndexPosition>,
+        /// 直前 page の末尾。
+        after: RankedPosition,
+    },
+}
+
+impl IndexCursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).expect("index cursor serializes"))
+    }
+
+    pub fn decode(token: &str) -> Result<Self> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(token.trim())
+            .context("index cursor is not valid base64url")?;
+        serde_json::from_slice(&bytes).context("index cursor is malformed")
+    }
+}
+
 /// index 投影への読み口（search / 新着列挙）。ArcadeDB / in-memory が同じ API を満たす。
 ///
 /// この trait は投影の生 read であり、fail-closed gate を通っていない。ユーザー向けには必ず
-/// [`FailClosedIndexQuery`] 越しに使うこと。
+/// [`FailClosedIndexQuery`] 越しに使うこと。実装は `*_page`（絞り込み + keyset pagination）を
+/// 持ち、絞り込み無しの簡易形はそれに委譲する。
 #[async_trait]
 pub trait IndexQuery: Send + Sync {
     /// topic 内検索（scope 指定つき全文検索）。
-    async fn search_scope(
+    async fn search_scope_page(
         &self,
         scope_kind: IndexScopeKind,
         scope_id: &str,
         query: &str,
+        filter: &IndexQueryFilter,
         limit: usize,
-    ) -> Result<Vec<IndexedEntry>>;
+    ) -> Result<IndexPage>;
 
     /// supported set 横断検索（投影全体への全文検索）。
-    async fn search_all(&self, query: &str, limit: usize) -> Result<Vec<IndexedEntry>>;
+    async fn search_all_page(
+        &self,
+        query: &str,
+        filter: &IndexQueryFilter,
+        limit: usize,
+    ) -> Result<IndexPage>;
+
+    /// 新着列挙（既定は created_at 降順。`filter.order` で昇順も可）。scope 指定ありは scope 内、
+    /// なしは横断。
+    async fn list_recent_page(
+        &self,
+        scope: Option<(IndexScopeKind, &str)>,
+        filter: &IndexQueryFilter,
+        limit: usize,
+    ) -> Result<IndexPage>;
+
+    /// topic 内検索の先頭 page（絞り込み無し）。
+    async fn search_scope(
+        &self,
+        scope_kind: IndexScopeKind,
+        scope_id: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<IndexedEntry>> {
+        Ok(self
+            .search_scope_page(
+                scope_kind,
+                scope_id,
+                query,
+                &IndexQueryFilter::default(),
+                limit,
+            )
+            .await?
+            .entries)
+    }
+
+    /// 横断検索の先頭 page（絞り込み無し）。
+    async fn search_all(&self, query: &str, limit: usize) -> Result<Vec<IndexedEntry>> {
+        Ok(self
+            .search_all_page(query, &IndexQueryFilter::default(), limit)
+            .await?
+            .entries)
+    }
 
-    /// 新着列挙（created_at 降順）。scope 指定ありは scope 内、なしは横断。
+    /// 新着列挙の先頭 page（絞り込み無し・created_at 降順）。
     async fn list_recent(
         &self,
         scope: Option<(IndexScopeKind, &str)>,
         limit: usize,
-    ) -> Result<Vec<IndexedEntry>>;
+    ) -> Result<Vec<IndexedEntry>> {
+        Ok(self
+            .list_recent_page(scope, &IndexQueryFilter::default(), limit)
+            .await?
+            .entries)
+    }
 }
 
 /// fail-closed query gate（#404 の単一判定点への突合）。
@@ -107,44 +319,58 @@ impl FailClosedIndexQuery {
             })
             .collect())
     }
+
+    /// page の hit を gate に通す。続き位置は gate 前の window のまま残す。
+    async fn gate_page(&self, page: IndexPage) -> Result<IndexPage> {
+        Ok(IndexPage {
+            entries: self.gate(page.entries).await?,
+            next: page.next,
+        })
+    }
 }
 
 #[async_trait]
 impl IndexQuery for FailClosedIndexQuery {
-    async fn search_scope(
+    async fn search_scope_page(
         &self,
         scope_kind: IndexScopeKind,
         scope_id: &str,
         query: &str,
+        filter: &IndexQueryFilter,
         limit: usize,
-    ) -> Result<Vec<IndexedEntry>> {
+    ) -> Result<IndexPage> {
         let limit = clamp_query_limit(limit);
-        let hits = self
+        let page = self
             .inner
-            .search_scope(scope_kind, scope_id, query, limit)
+            .search_scope_page(scope_kind, scope_id, query, filter, limit)
             .await?;
-        self.gate(hits).await
+        self.gate_page(page).await
     }
 
-    async fn search_all(&self, query: &str, limit: usize) -> Result<Vec<IndexedEntry>> {
+    async fn search_all_page(
+        &self,
+        query: &str,
+        filter: &IndexQueryFilter,
+        limit: usize,
+    ) -> Result<IndexPage> {
         let limit = clamp_query_limit(limit);
-        let hits = exclude_private_channel(self.inner.search_all(query, limit).await?);
-        self.gate(hits).await
+        let mut page = self.inner.search_all_page(query, filter, limit).await?;
+        page.entries = exclude_private_channel(page.entries);
+        self.gate_page(page).await
     }
 
-    async fn list_recent(
+    async fn list_recent_page(
         &self,
         scope: Option<(IndexScopeKind, &str)>,
+        filter: &IndexQueryFilter,
         limit: usize,
-    ) -> Result<Vec<IndexedEntry>> {
+    ) -> Result<IndexPage> {
         let limit = clamp_query_limit(limit);
-        let hits = self.inner.list_recent(scope, limit).await?;
-        let hits = if scope.is_none() {
-            exclude_private_channel(hits)
-        } else {
-            hits
-        };
-        self.gate(hits).await
+        let mut page = self.inner.list_recent_page(scope, filter, limit).await?;
+        if scope.is_none() {
+            page.entries = exclude_private_channel(page.entries);
+        }
+        self.gate_page(page).await
     }
 }
 
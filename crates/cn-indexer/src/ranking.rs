@@ -0,0 +1,427 @@
+//! recommendation の ranking stage（新着列挙の置き換え）。
+//!
+//! fail-closed query gate（[`crate::FailClosedIndexQuery`]）を通った候補だけを受け取り、並べ替えて
+//! 上位を返す。ranking は gate の出力を並べ替え・切り詰めるだけで候補を足さないため、
+//! 「`allow` 以外の verdict が recommendation に入らない」は gate の保証のまま成立する。
+//!
+//! score は 3 成分の重み付き和に、著者の trust を係数として掛けたもの:
+//! - recency: `0.5^(age_hours / half_life_hours)`（新しいほど 1 に近い）
+//! - engagement: ingest 時に同じ replica 上で観測した反応 + 返信数の飽和変換 `n / (n + k)`
+//! - viewer proximity: viewer から見た著者の relation proximity（edge が無ければ 0）
+//! - author trust: `cn-trust` の合成 trust（`[-1, 1]`）を `1 + trust_weight * trust` の係数にする
+//!   （負の trust は沈め、正の trust は持ち上げる。trust 0 は中立）
+//!
+//! 各 entry には、寄与した成分を寄与の大きい順に [`RecommendationReason`] として同伴させ、
+//! client が「なぜ推薦されたか」を説明できるようにする。score 自体は wire に出さない
+//! （重みのチューニングで値の意味が変わるため、安定した契約は reason code のみ）。
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use kukuri_cn_core::{EntryEngagement, IndexEntryStore, IndexScopeKind};
+use kukuri_cn_protocol::RecommendationReason;
+use kukuri_cn_trust::RelationStore;
+
+use crate::projection::IndexedEntry;
+
+/// 著者の合成 trust の読み口。本番は risk signal 入力（Postgres）から `cn-trust` で合成し、
+/// テストは固定値を返す。
+#[async_trait]
+pub trait AuthorTrustSource: Send + Sync {
+    /// 著者 pubkey → 合成 trust（`[-1, 1]`）。risk signal の無い著者は含めなくてよい（0 扱い）。
+    async fn composed_trust(
+        &self,
+        authors: &[String],
+        now: DateTime<Utc>,
+    ) -> Result<HashMap<String, f64>>;
+}
+
+/// 固定値の trust 読み口（contract / unit テスト用、trust を使わない構成用）。
+#[derive(Clone, Debug, Default)]
+pub struct StaticAuthorTrust(pub HashMap<String, f64>);
+
+#[async_trait]
+impl AuthorTrustSource for StaticAuthorTrust {
+    async fn composed_trust(
+        &self,
+        authors: &[String],
+        _now: DateTime<Utc>,
+    ) -> Result<HashMap<String, f64>> {
+        Ok(authors
+            .iter()
+            .filter_map(|author| self.0.get(author).map(|trust| (author.clone(), *trust)))
+            .collect())
+    }
+}
+
+/// ranking の重み（初期決め打ち。チューニングは運用の観測を見て後続で行う）。
+#[derive(Clone, Debug, PartialEq)]
+pub struct RankingParams {
+    /// recency 成分が半分になるまでの時間。
+    pub recency_half_life_hours: f64,
+    pub recency_weight: f64,
+    pub engagement_weight: f64,
+    /// engagement の飽和定数 `k`（`n / (n + k)`。k 件で 0.5）。
+    pub engagement_saturation: f64,
+    pub proximity_weight: f64,
+    /// trust 係数の振れ幅（`1 + trust_weight * trust`）。
+    pub trust_weight: f64,
+}
+
+impl Default for RankingParams {
+    fn default() -> Self {
+        Self {
+            recency_half_life_hours: 24.0,
+            recency_weight: 1.0,
+            engagement_weight: 1.0,
+            engagement_saturation: 5.0,
+            proximity_weight: 1.0,
+            trust_weight: 0.5,
+        }
+    }
+}
+
+/// reason code を付ける成分の下限（成分値。これ未満の成分は説明に出さない）。
+const RECENT_REASON_MIN: f64 = 0.5;
+const ENGAGED_REASON_MIN: f64 = 0.0;
+const CLOSE_REASON_MIN: f64 = 0.0;
+const TRUSTED_REASON_MIN: f64 = 0.0;
+
+/// 1 候補の ranking 入力（gate 通過済み entry + 外部から集めた signal）。
+#[derive(Clone, Debug, PartialEq)]
+pub struct RankingSignals {
+    pub engagement: EntryEngagement,
+    /// 著者の合成 trust。risk signal が無ければ 0。
+    pub author_trust: f64,
+    /// viewer から見た著者の proximity。edge が無ければ 0。
+    pub viewer_proximity: f64,
+}
+
+/// 並べ替え済みの recommendation 1 件。
+#[derive(Clone, Debug, PartialEq)]
+pub struct RankedEntry {
+    pub entry: IndexedEntry,
+    pub score: f64,
+    /// 寄与の大きい順。
+    pub reasons: Vec<RecommendationReason>,
+}
+
+/// recommendation の順位内の位置（直前 page の末尾）。続き読みの keyset cursor に載せる。
+///
+/// 並びは score 降順・新しい順・`(scope_id, object_id)` 順の全順序で、[`RecommendationRanker::rank`]
+/// の並びと一致する。順位内の offset と違い、page 間で候補が増減しても重複・欠落しない。
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RankedPosition {
+    pub score: f64,
+    pub created_at: i64,
+    pub scope_id: String,
+    pub object_id: String,
+}
+
+impl RankedPosition {
+    pub fn of(ranked: &RankedEntry) -> Self {
+        Self {
+            score: ranked.score,
+            created_at: ranked.entry.created_at,
+            scope_id: ranked.entry.scope_id.clone(),
+            object_id: ranked.entry.object_id.clone(),
+        }
+    }
+
+    /// `ranked` が順位でこの位置より後ろにあるか。
+    pub fn precedes(&self, ranked: &RankedEntry) -> bool {
+        rank_order(
+            (
+                self.score,
+                self.created_at,
+                self.scope_id.as_str(),
+                self.object_id.as_str(),
+            ),
+            rank_key(ranked),
+        ) == Ordering::Less
+    }
+}
+
+fn rank_key(ranked: &RankedEntry) -> (f64, i64, &str, &str) {
+    (
+        ranked.score,
+        ranked.entry.created_at,
+        ranked.entry.scope_id.as_str(),
+        ranked.entry.object_id.as_str(),
+    )
+}
+
+fn rank_order(left: (f64, i64, &str, &str), right: (f64, i64, &str, &str)) -> Ordering {
+    right
+        .0
+        .total_cmp(&left.0)
+        .then_with(|| right.1.cmp(&left.1))
+        .then_with(|| left.2.cmp(right.2))
+        .then_with(|| left.3.cmp(right.3))
+}
+
+/// 1 候補の score と reason code を求める（純関数）。
+pub fn score_entry(
+    entry: &IndexedEntry,
+    signals: &RankingSignals,
+    now: DateTime<Utc>,
+    params: &RankingParams,
+) -> (f64, Vec<RecommendationReason>) {
+    let age_hours = (now.timestamp() - entry.created_at).max(0) as f64 / 3_600.0;
+    let recency = 0.5_f64.powf(age_hours / params.recency_half_life_hours);
+    let engagement_count = signals.engagement.total() as f64;
+    let engagement = engagement_count / (engagement_count + params.engagement_saturation);
+    let proximity = signals.viewer_proximity.clamp(0.0, 1.0);
+    let trust = signals.author_trust.clamp(-1.0, 1.0);
+
+    let recency_part = params.recency_weight * recency;
+    let engagement_part = params.engagement_weight * engagement;
+    let proximity_part = params.proximity_weight * proximity;
+    let trust_factor = (1.0 + params.trust_weight * trust).max(0.0);
+    let score = (recency_part + engagement_part + proximity_part) * trust_factor;
+
+    let mut contributions: Vec<(RecommendationReason, f64)> = Vec::new();
+    if recency >= RECENT_REASON_MIN {
+        contributions.push((RecommendationReason::Recent, recency_part));
+    }
+    if engagement > ENGAGED_REASON_MIN {
+        contributions.push((RecommendationReason::Engaged, engagement_part));
+    }
+    if proximity > CLOSE_REASON_MIN {
+        contributions.push((RecommendationReason::CloseToViewer, proximity_part));
+    }
+    if trust > TRUSTED_REASON_MIN {
+        // trust は係数なので、持ち上げた分（score の増分）を寄与とみなす。
+        let lift = (recency_part + engagement_part + proximity_part) * params.trust_weight * trust;
+        contributions.push((RecommendationReason::TrustedAuthor, lift));
+    }
+    contributions.sort_by(|left, right| right.1.total_cmp(&left.1));
+    (
+        score,
+        contributions
+            .into_iter()
+            .map(|(reason, _)| reason)
+            .collect(),
+    )
+}
+
+/// recommendation の ranking stage。signal の読み口を束ね、gate 通過済みの候補を並べ替える。
+pub struct RecommendationRanker {
+    engagement: Arc<dyn IndexEntryStore>,
+    relation: Arc<dyn RelationStore>,
+    trust: Arc<dyn AuthorTrustSource>,
+    params: RankingParams,
+}
+
+impl RecommendationRanker {
+    pub fn new(
+        engagement: Arc<dyn IndexEntryStore>,
+        relation: Arc<dyn RelationStore>,
+        trust: Arc<dyn AuthorTrustSource>,
+    ) -> Self {
+        Self {
+            engagement,
+            relation,
+            trust,
+            params: RankingParams::default(),
+        }
+    }
+
+    pub fn with_params(mut self, params: RankingParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// 候補を score 降順に並べ、上位 `limit` 件を返す（同点は新しい順、さらに scope / object id 順）。
+    ///
+    /// `candidates` は必ず fail-closed gate を通したものにすること（ranking は候補を足さない）。
+    pub async fn rank(
+        &self,
+        viewer_pubkey: &str,
+        candidates: Vec<IndexedEntry>,
+        limit: usize,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<RankedEntry>> {
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+        let engagement = self.load_engagement(&candidates).await?;
+        let mut authors: Vec<String> = candidates
+            .iter()
+            .map(|entry| entry.author_pubkey.clone())
+            .collect();
+        authors.sort();
+        authors.dedup();
+        let trust = self.trust.composed_trust(&authors, now).await?;
+        let targets: Vec<String> = authors
+            .iter()
+            .filter(|author| author.as_str() != viewer_pubkey)
+            .cloned()
+            .collect();
+        let proximity = self
+            .relation
+            .pairwise_proximities(viewer_pubkey, &targets)
+            .await?;
+
+        let mut ranked: Vec<RankedEntry> = candidates
+            .into_iter()
+            .map(|entry| {
+                let signals = RankingSignals {
+                    engagement: engagement
+                        .get(&(
+                            entry.scope_kind,
+                            entry.scope_id.clone(),
+                            entry.object_id.clone(),
+                        ))
+                        .copied()
+                        .unwrap_or_default(),
+                    author_trust: trust.get(&entry.author_pubkey).copied().unwrap_or(0.0),
+                    viewer_proximity: proximity
+                        .get(&entry.author_pubkey)
+                        .map_or(0.0, |found| found.score),
+                };
+                let (score, reasons) = score_entry(&entry, &signals, now, &self.params);
+                RankedEntry {
+                    entry,
+                    score,
+                    reasons,
+                }
+            })
+            .collect();
+        ranked.sort_by(|left, right| rank_order(rank_key(left), rank_key(right)));
+        ranked.truncate(limit);
+        Ok(ranked)
+    }
+
+    async fn load_engagement(
+        &self,
+        candidates: &[IndexedEntry],
+    ) -> Result<HashMap<(IndexScopeKind, String, String), EntryEngagement>> {
+        let mut engagement = HashMap::new();
+        for scope_kind in [IndexScopeKind::PublicTopic, IndexScopeKind::PrivateChannel] {
+            let keys: Vec<(String, String)> = candidates
+                .iter()
+                .filter(|entry| entry.scope_kind == scope_kind)
+                .map(|entry| (entry.scope_id.clone(), entry.object_id.clone()))
+                .collect();
+            if keys.is_empty() {
+                continue;
+            }
+            for (scope_id, object_id, observed) in
+                self.engagement.engagement(scope_kind, &keys).await?
+            {
+                engagement.insert((scope_kind, scope_id, object_id), observed);
+            }
+        }
+        Ok(engagement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kukuri_cn_protocol::IndexEntryKind;
+
+    use super::*;
+
+    fn entry(object_id: &str, author: &str, created_at: i64) -> IndexedEntry {
+        IndexedEntry {
+            scope_kind: IndexScopeKind::PublicTopic,
+            scope_id: "kukuri:topic:ranking".to_string(),
+            object_id: object_id.to_string(),
+            author_pubkey: author.to_string(),
+            text: "hello".to_string(),
+            created_at,
+            source_replica_id: "topic::kukuri:topic:ranking".to_string(),
+            entry_kind: IndexEntryKind::Post,
+            profile: None,
+            session: None,
+        }
+    }
+
+    fn neutral() -> RankingSignals {
+        RankingSignals {
+            engagement: EntryEngagement::default(),
+            author_trust: 0.0,
+            viewer_proximity: 0.0,
+        }
+    }
+
+    #[test]
+    fn recency_decays_and_only_fresh_entries_are_explained_as_recent() {
+        let now = Utc::now();
+        let params = RankingParams::default();
+        let (fresh, fresh_reasons) =
+            score_entry(&entry("a", "x", now.timestamp()), &neutral(), now, &params);
+        let (day_old, day_old_reasons) = score_entry(
+            &entry("b", "x", now.timestamp() - 24 * 3_600),
+            &neutral(),
+            now,
+            &params,
+        );
+        let (old, old_reasons) = score_entry(
+            &entry("c", "x", now.timestamp() - 72 * 3_600),
+            &neutral(),
+            now,
+            &params,
+        );
+        assert!(fresh > day_old && day_old > old);
+        assert!((day_old - 0.5).abs() < 1e-9);
+        assert_eq!(fresh_reasons, vec![RecommendationReason::Recent]);
+        assert_eq!(day_old_reasons, vec![RecommendationReason::Recent]);
+        assert!(old_reasons.is_empty());
+    }
+
+    #[test]
+    fn reasons_are_ordered_by_contribution_and_negative_trust_sinks() {
+        let now = Utc::now();
+        let params = RankingParams::default();
+        let old = entry("a", "x", now.timestamp() - 48 * 3_600);
+        let signals = RankingSignals {
+            engagement: EntryEngagement {
+                reactions: 12,
+                replies: 3,
+            },
+            author_trust: 0.4,
+            viewer_proximity: 0.3,
+        };
+        let (score, reasons) = score_entry(&old, &signals, now, &params);
+        assert_eq!(
+            reasons,
+            vec![
+                RecommendationReason::Engaged,
+                RecommendationReason::CloseToViewer,
+                RecommendationReason::TrustedAuthor,
+            ]
+        );
+
+        let distrusted = RankingSignals {
+            author_trust: -1.0,
+            ..signals
+        };
+        let (sunk, sunk_reasons) = score_entry(&old, &distrusted, now, &params);
+        assert!(sunk < score);
+        assert!(!sunk_reasons.contains(&RecommendationReason::TrustedAuthor));
+    }
+
+    #[test]
+    fn ranked_position_orders_by_score_then_recency_then_ids() {
+        let ranked = |object_id: &str, score: f64, created_at: i64| RankedEntry {
+            entry: entry(object_id, "x", created_at),
+            score,
+            reasons: Vec::new(),
+        };
+        let position = RankedPosition::of(&ranked("b", 1.0, 100));
+        assert!(position.precedes(&ranked("a", 0.5, 200)));
+        assert!(position.precedes(&ranked("a", 1.0, 99)));
+        assert!(position.precedes(&ranked("c", 1.0, 100)));
+        assert!(!position.precedes(&ranked("b", 1.0, 100)));
+        assert!(!position.precedes(&ranked("a", 1.0, 100)));
+        assert!(!position.precedes(&ranked("z", 1.5, 0)));
+    }
+}
@@ -15,6 +15,8 @@
 //! - edge は正規化ペア（辞書順 (小, 大)）で 1 本だけ張り、読みは無向 match（`-[r]-`）で
 //!   双方向から同じ feature が見える（対称性 contract）。
 
+use std::collections::BTreeMap;
+
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde_json::{Value, json};
@@ -153,6 +155,39 @@ impl RelationStore for ArcadeDbRelationGraph {
         Ok(Some(proximity_from_features(&features_from_json(&raw)?)))
     }
 
+    async fn pairwise_proximities(
+        &self,
+        viewer: &str,
+        targets: &[String],
+    ) -> Result<BTreeMap<String, Proximity>> {
+        if targets.is_empty() {
+            return Ok(BTreeMap::new());
+        }
+        let value = self
+            .client
+            .command_with_params(
+                "cypher",
+                &format!(
+                    "MATCH (a:{USER_TYPE} {{pubkey: $viewer}})-[r:{EDGE_TYPE}]-(b:{USER_TYPE}) \
+                     WHERE b.pubkey IN $targets \
+                     RETURN b.pubkey AS pubkey, r.features_json AS features_json"
+                ),
+                json!({ "viewer": viewer, "targets": targets }),
+            )
+            .await?;
+        let mut proximities = BTreeMap::new();
+        for row in result_rows(&value) {
+            let (Some(pubkey), Some(raw)) = (
+                string_column(&row, "pubkey"),
+                string_column(&row, "features_json"),
+            ) else {
+                continue;
+            };
+            proximities.insert(pubkey, proximity_from_features(&features_from_json(&raw)?));
+        }
+        Ok(proximities)
+    }
+
     async fn neighbors(&self, viewer: &str, k: usize) -> Result<Vec<String>> {
         let value = self
             .client
@@ -30,7 +30,7 @@ use kukuri_docs_sync::{DocsSync, IrohDocsSync};
 use kukuri_iroh_node::IrohDocsNode;
 use kukuri_transport::{DhtDiscoveryOptions, TransportNetworkConfig, TransportRelayConfig};
 
-use crate::arcadedb::ArcadeDbProjection;
+use crate::backend::open_index_projection;
 use crate::config::IndexerConfig;
 use crate::ingest::IngestPipeline;
 use crate::media_fetcher::BlobMediaFetcher;
@@ -42,7 +42,7 @@ use crate::worker::{IndexerWorker, WorkerConfig};
 /// 環境変数から設定を読み、relay validation 起動 gate を適用して cn-indexer を起動する。
 ///
 /// 現段階では relay gate の適用、scope state の準備確認、ingest 経路の本番依存の組み立て
-/// （共有 iroh node / docs 同期 / ArcadeDB 投影 / 真実源 / 取り込みパイプライン）までを行う。
+/// （共有 iroh node / docs 同期 / index 投影 / 真実源 / 取り込みパイプライン）までを行う。
 /// 常駐 ingest loop の起動は #613 T2 で行う。
 pub async fn run_from_env() -> Result<()> {
     init_tracing();
@@ -54,7 +54,7 @@ pub async fn run_from_env() -> Result<()> {
 ///
 /// image smoke / operator の構成確認用の軽量モード。`run()` が DB 接続より前後で行う
 /// fail-closed 検証（relay gate / channel secret 鍵 / provider 解決 / scan service 構成）を
-/// 同じ判定ロジックで実行するが、Postgres / ArcadeDB / iroh へは接続しない。
+/// 同じ判定ロジックで実行するが、Postgres / ArcadeDB / embedded index / iroh へは接続しない。
 pub fn validate_config_from_env() -> Result<()> {
     init_tracing();
     let config = IndexerConfig::from_env()?;
@@ -112,8 +112,7 @@ async fn run(config: IndexerConfig) -> Result<()> {
 
     info!(
         data_dir = %config.data_dir.display(),
-        arcadedb_url = %config.arcadedb.base_url,
-        arcadedb_database = %config.arcadedb.database,
+        index_backend = config.index_backend.name(),
         "cn-indexer configuration validated"
     );
 
@@ -265,7 +264,8 @@ async fn wait_for_shutdown_signal() {
 ///
 /// - 共有 iroh node から `IrohDocsSync` を作り、シードピアを docs sync と blob fetch の双方へ
 ///   適用できるよう participant へ渡す。
-/// - ArcadeDB 投影は起動時に schema 準備（`ensure_schema`）を行い、接続できなければ起動失敗に
+/// - index 投影は backend 選択（ArcadeDB / embedded）に従って開く。ArcadeDB は schema 準備、
+///   embedded は index ディレクトリの writer lock 取得までを起動時に行い、失敗なら起動失敗に
 ///   する（fail-closed。投影へ書けない構成で取り込みを始めない）。
 /// - 索引の真実源は Postgres（`PgIndexEntryStore`）。
 async fn compose_ingest_stack(
@@ -290,13 +290,7 @@ async fn compose_ingest_stack(
     }
 
     let entries = Arc::new(PgIndexEntryStore::new(pool.clone()));
-    let projection = ArcadeDbProjection::new(config.arcadedb.clone())
-        .context("failed to build the ArcadeDB projection client")?;
-    projection.ensure_schema().await.context(
-        "cn-indexer cannot reach ArcadeDB for the index projection \
-         (fail-closed startup gate)",
-    )?;
-    let projection = Arc::new(projection);
+    let projection = open_index_projection(&config.index_backend).await?;
 
     let pipeline = IngestPipeline::new(
         docs_sync.clone(),
@@ -325,7 +319,7 @@ fn init_tracing() {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{ArcadeDbConfig, MediaFetchConfig, RelayConfig};
+    use crate::config::{ArcadeDbConfig, IndexBackendConfig, MediaFetchConfig, RelayConfig};
     use kukuri_cn_safety_runtime::{
         SafetyRuntimeConfig, SafetyRuntimeProviderEntry, SafetyRuntimeProvidersConfig,
     };
@@ -340,12 +334,12 @@ mod tests {
             data_dir: "./data/validate-config-test".into(),
             relay: RelayConfig::new(false, vec!["https://relay.example.net".to_string()]),
             channel_secret_key: "validate-config-test-channel-secret-key-0123456789".to_string(),
-            arcadedb: ArcadeDbConfig {
+            index_backend: IndexBackendConfig::ArcadeDb(ArcadeDbConfig {
                 base_url: "http://127.0.0.1:2480".to_string(),
                 database: "kukuri_index".to_string(),
                 username: "root".to_string(),
                 password: String::new(),
-            },
+            }),
             safety,
             media_fetch: MediaFetchConfig::default(),
             seed_peers: Vec::new(),
@@ -198,6 +198,8 @@ mod tests {
                 indexed: 2,
                 skipped_non_allow: 1,
                 deindexed: 0,
+                profiles_indexed: 0,
+                sessions_indexed: 0,
             },
         );
         state.record_scan_error();
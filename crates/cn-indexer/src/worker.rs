@@ -317,6 +317,8 @@ impl IndexerWorker {
                     replica_id = %key,
                     scanned = summary.scanned,
                     indexed = summary.indexed,
+                    profiles_indexed = summary.profiles_indexed,
+                    sessions_indexed = summary.sessions_indexed,
                     "scope ingested"
                 );
                 true
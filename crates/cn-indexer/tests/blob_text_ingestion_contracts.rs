@@ -16,8 +16,9 @@ use kukuri_cn_safety_runtime::{
     Secp256k1ModerationEventSigner,
 };
 use kukuri_core::{
-    KukuriKeys, ObjectVisibility, PayloadRef, ReplicaId, TopicId, blob_hash,
-    build_post_envelope_with_payload,
+    KukuriKeys, ObjectVisibility, PayloadRef, ReplicaId, TopicId, apply_post_revision, blob_hash,
+    build_post_edit_envelope, build_post_envelope_with_payload, build_post_tombstone_envelope,
+    timeline_sort_key,
 };
 use kukuri_docs_sync::{DocOp, DocsSync, MemoryDocsSync, stable_key, topic_replica_id};
 
@@ -169,6 +170,10 @@ impl BlobService for SwitchableBlobService {
         Ok(())
     }
 
+    async fn unpin_blob(&self, _hash: &kukuri_core::BlobHash) -> Result<()> {
+        Ok(())
+    }
+
     async fn blob_status(&self, _hash: &kukuri_core::BlobHash) -> Result<BlobStatus> {
         Ok(if self.body.lock().expect("blob body mutex").is_some() {
             BlobStatus::Available
@@ -381,3 +386,103 @@ async fn blob_text_fetch_failure_deindexes_an_existing_entry() -> Result<()> {
     );
     Ok(())
 }
+
+#[tokio::test]
+async fn edited_blob_text_post_is_reindexed_and_tombstone_deindexes() -> Result<()> {
+    let docs = Arc::new(MemoryDocsSync::default());
+    let blobs = Arc::new(MemoryBlobService::default());
+    let projection = Arc::new(MemoryIndexProjection::new());
+    let topic = TopicId::new("rust");
+    let replica = topic_replica_id("rust");
+    let keys = KukuriKeys::generate();
+    let original_body = blobs.put_blob(b"typo body".to_vec(), "text/plain").await?;
+    let envelope = build_post_envelope_with_payload(
+        &keys,
+        &topic,
+        PayloadRef::BlobText {
+            hash: original_body.hash,
+            mime: original_body.mime,
+            bytes: original_body.bytes,
+        },
+        Vec::new(),
+        Vec::new(),
+        None,
+        ObjectVisibility::Public,
+    )?;
+    let object = envelope.to_post_object()?.expect("post object present");
+    let object_id = object.object_id.as_str().to_string();
+    docs.open_replica(&replica).await?;
+    docs.apply_doc_op(
+        &replica,
+        DocOp::SetJson {
+            key: stable_key("objects", &format!("{object_id}/envelope")),
+            value: serde_json::to_value(&envelope)?,
+        },
+    )
+    .await?;
+
+    let edited_body = blobs.put_blob(b"fixed body".to_vec(), "text/plain").await?;
+    let edit = build_post_edit_envelope(
+        &keys,
+        &object,
+        PayloadRef::BlobText {
+            hash: edited_body.hash,
+            mime: edited_body.mime,
+            bytes: edited_body.bytes,
+        },
+        Vec::new(),
+        Vec::new(),
+    )?;
+    let edited = apply_post_revision(&object, &edit)?;
+    docs.apply_doc_op(
+        &replica,
+        DocOp::SetJson {
+            key: stable_key(
+                "objects",
+                &format!(
+                    "{object_id}/revisions/{}",
+                    timeline_sort_key(edit.created_at, &edit.id)
+                ),
+            ),
+            value: serde_json::to_value(&edit)?,
+        },
+    )
+    .await?;
+    docs.apply_doc_op(
+        &replica,
+        DocOp::SetJson {
+            key: stable_key("objects", &format!("{object_id}/state")),
+            value: serde_json::to_value(&edited)?,
+        },
+    )
+    .await?;
+
+    let (pipeline, entries) = pipeline_with(&docs, &projection);
+    let pipeline = pipeline.with_blob_service(blobs);
+    let summary = pipeline
+        .ingest_scope(IndexScopeKind::PublicTopic, "rust", &replica)
+        .await?;
+    assert_eq!(summary.indexed, 1);
+    let stored = projection
+        .entries_in_scope(IndexScopeKind::PublicTopic, "rust")
+        .await;
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].text, "fixed body");
+
+    let tombstone = build_post_tombstone_envelope(&keys, &edited)?;
+    let deleted = apply_post_revision(&edited, &tombstone)?;
+    docs.apply_doc_op(
+        &replica,
+        DocOp::SetJson {
+            key: stable_key("objects", &format!("{object_id}/state")),
+            value: serde_json::to_value(&deleted)?,
+        },
+    )
+    .await?;
+    let summary = pipeline
+        .ingest_scope(IndexScopeKind::PublicTopic, "rust", &replica)
+        .await?;
+    assert_eq!(summary.deindexed, 1);
+    assert!(!entries.contains(IndexScopeKind::PublicTopic, "rust", &object_id));
+    Ok(())
+}
@@ -12,11 +12,12 @@ use async_trait::async_trait;
 use kukuri_blob_service::MemoryBlobService;
 use kukuri_cn_core::{IndexScopeKind, MemoryIndexEntryStore};
 use kukuri_cn_indexer::config::{MediaFetchConfig, RelayConfig};
-use kukuri_cn_indexer::ingest::IngestPipeline;
+use kukuri_cn_indexer::ingest::{IngestPipeline, profile_object_id};
 use kukuri_cn_indexer::media_fetcher::BlobMediaFetcher;
 use kukuri_cn_indexer::participant::ScopeReplica;
 use kukuri_cn_indexer::projection::{IndexProjection, MemoryIndexProjection};
 use kukuri_cn_indexer::state::IndexerRuntimeState;
+use kukuri_cn_protocol::IndexEntryKind;
 use kukuri_cn_safety::provider::{
     MediaFetcher, ProviderScanRequest, ProviderScanResult, ScanError, ScanOutcome, SubjectKind,
 };
@@ -31,11 +32,14 @@ use kukuri_cn_safety_runtime::{
     SafetyOrchestrator, Secp256k1ModerationEventSigner, verify_signed_event,
 };
 use kukuri_core::{
-    KukuriKeys, KukuriMediaManifestV1, MediaManifestItem, ObjectVisibility, PayloadRef, ReplicaId,
-    TopicId, blob_hash, build_media_manifest_envelope, build_post_envelope,
-    build_post_envelope_with_payload,
+    AuthorProfileDocV1, KukuriKeys, KukuriMediaManifestV1, KukuriProfileEnvelopeContentV1,
+    MediaManifestItem, ObjectVisibility, PayloadRef, ReplicaId, TopicId, blob_hash,
+    build_media_manifest_envelope, build_post_envelope, build_post_envelope_with_payload,
+    build_profile_envelope, parse_profile,
+};
+use kukuri_docs_sync::{
+    DocOp, DocQuery, DocsSync, MemoryDocsSync, author_replica_id, stable_key, topic_replica_id,
 };
-use kukuri_docs_sync::{DocOp, DocQuery, DocsSync, MemoryDocsSync, stable_key, topic_replica_id};
 
 const TEST_SECRET: &str = "0000000000000000000000000000000000000000000000000000000000000001";
 
@@ -117,8 +121,18 @@ async fn persist_post(
     topic: &TopicId,
     body: &str,
 ) -> String {
-    let keys = KukuriKeys::generate();
-    let envelope = build_post_envelope(&keys, topic, body, None).expect("envelope");
+    persist_post_by(docs, replica, topic, &KukuriKeys::generate(), body).await
+}
+
+/// 指定した著者鍵で post を共有 replica に実在させる（profile と著者を揃えるテスト用）。
+async fn persist_post_by(
+    docs: &MemoryDocsSync,
+    replica: &ReplicaId,
+    topic: &TopicId,
+    keys: &KukuriKeys,
+    body: &str,
+) -> String {
+    let envelope = build_post_envelope(keys, topic, body, None).expect("envelope");
     let object = envelope
         .to_post_object()
         .expect("post object")
@@ -146,6 +160,53 @@ async fn persist_post(
     object_id
 }
 
+/// 著者 replica に署名済み profile を公開する（app-api の `persist_profile_doc` と同じ key 形状）。
+async fn persist_profile(docs: &MemoryDocsSync, keys: &KukuriKeys, name: &str, about: &str) {
+    let envelope = build_profile_envelope(
+        keys,
+        &KukuriProfileEnvelopeContentV1 {
+            author_pubkey: keys.public_key(),
+            name: Some(name.to_string()),
+            about: Some(about.to_string()),
+            ..KukuriProfileEnvelopeContentV1::default()
+        },
+    )
+    .expect("profile envelope");
+    let profile = parse_profile(&envelope)
+        .expect("parse profile")
+        .expect("profile present");
+    let replica = author_replica_id(keys.public_key().as_str());
+    docs.open_replica(&replica).await.expect("open");
+    docs.apply_doc_op(
+        &replica,
+        DocOp::SetJson {
+            key: stable_key("profile", "latest"),
+            value: serde_json::to_value(AuthorProfileDocV1 {
+                author_pubkey: profile.pubkey.clone(),
+                name: profile.name.clone(),
+                display_name: profile.display_name.clone(),
+                about: profile.about.clone(),
+                picture: None,
+                picture_asset: None,
+                updated_at: profile.updated_at,
+                envelope_id: envelope.id.clone(),
+            })
+            .expect("profile doc json"),
+        },
+    )
+    .await
+    .expect("profile doc op");
+    docs.apply_doc_op(
+        &replica,
+        DocOp::SetJson {
+            key: stable_key("envelopes", envelope.id.as_str()),
+            value: serde_json::to_value(&envelope).expect("envelope json"),
+        },
+    )
+    .await
+    .expect("profile envelope op");
+}
+
 const MEDIA_MANIFEST_ID: &str = "media-manifest-test";
 /// manifest item の blob 本体（scan 対象 hash はここから導出する）。
 const MEDIA_BLOB_BYTES: &[u8] = b"tiny-png-bytes";
@@ -978,3 +1039,84 @@ async fn ingest_allow_records_no_artifacts() -> Result<()> {
     assert!(store.signals().is_empty());
     Ok(())
 }
+
+#[tokio::test]
+async fn author_profiles_of_indexed_posts_are_indexed_for_people_search() -> Result<()> {
+    // index 済み post を持つ著者の署名済み profile は profile entry として同じ scope に入る。
+    let docs = Arc::new(MemoryDocsSync::default());
+    let projection = Arc::new(MemoryIndexProjection::new());
+    let topic = TopicId::new("rust");
+    let replica = topic_replica_id("rust");
+    let keys = KukuriKeys::generate();
+    let author = keys.public_key().as_str().to_string();
+    persist_profile(&docs, &keys, "alice", "writes about tokio").await;
+    persist_post_by(&docs, &replica, &topic, &keys, "hello rust").await;
+    // profile 未公開の著者は post だけが入る。
+    persist_post(&docs, &replica, &topic, "no profile here").await;
+
+    let (pipeline, entries, _) = pipeline_with(&docs, &projection, allow_service());
+    let summary = pipeline
+        .ingest_scope(IndexScopeKind::PublicTopic, "rust", &replica)
+        .await?;
+
+    assert_eq!(summary.indexed, 2);
+    assert_eq!(summary.profiles_indexed, 1);
+    let object_id = profile_object_id(&author);
+    let stored = projection
+        .entries_in_scope(IndexScopeKind::PublicTopic, "rust")
+        .await;
+    let profile = stored
+        .iter()
+        .find(|entry| entry.object_id == object_id)
+        .expect("profile entry is projected");
+    assert_eq!(profile.entry_kind, IndexEntryKind::Profile);
+    assert_eq!(profile.author_pubkey, author);
+    assert_eq!(profile.text, "alice\nwrites about tokio");
+    assert_eq!(
+        profile
+            .profile
+            .as_ref()
+            .and_then(|view| view.name.as_deref()),
+        Some("alice")
+    );
+    assert!(entries.contains(IndexScopeKind::PublicTopic, "rust", &object_id));
+    Ok(())
+}
+
+#[tokio::test]
+async fn author_profiles_are_not_indexed_from_private_channels_or_when_not_allowed() -> Result<()> {
+    let docs = Arc::new(MemoryDocsSync::default());
+    let projection = Arc::new(MemoryIndexProjection::new());
+    let topic = TopicId::new("rust");
+    let keys = KukuriKeys::generate();
+    let author = keys.public_key().as_str().to_string();
+    persist_profile(&docs, &keys, "alice", "writes about tokio").await;
+
+    // private channel の参加者は people search に出さない。
+    let channel = ScopeReplica::from_scope(IndexScopeKind::PrivateChannel, "secret");
+    persist_post_by(&docs, &channel.replica_id, &topic, &keys, "channel post").await;
+    let (pipeline, _, _) = pipeline_with(&docs, &projection, allow_service());
+    let summary = pipeline
+        .ingest_scope(channel.kind, &channel.id, &channel.replica_id)
+        .await?;
+    assert_eq!(summary.indexed, 1);
+    assert_eq!(summary.profiles_indexed, 0);
+
+    // profile の verdict が非 allow なら post が index されても profile は入らない。
+    let replica = topic_replica_id("rust");
+    persist_post_by(&docs, &replica, &topic, &keys, "hello rust").await;
+    let (pipeline, entries, _) = pipeline_with(&docs, &projection, known_csam_service(&author));
+    let summary = pipeline
+        .ingest_scope(IndexScopeKind::PublicTopic, "rust", &replica)
+        .await?;
+    assert_eq!(summary.indexed, 1);
+    assert_eq!(summary.profiles_indexed, 0);
+    let object_id = profile_object_id(&author);
+    assert!(
+        !projection
+            .contains_object(IndexScopeKind::PublicTopic, "rust", &object_id)
+            .await?
+    );
+    assert!(!entries.contains(IndexScopeKind::PublicTopic, "rust", &object_id));
+    Ok(())
+}
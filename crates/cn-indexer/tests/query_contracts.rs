@@ -15,6 +15,7 @@ use kukuri_cn_core::{IndexScopeKind, MemoryIndexEntryStore};
 use kukuri_cn_indexer::ingest::IngestPipeline;
 use kukuri_cn_indexer::projection::{IndexProjection, IndexedEntry, MemoryIndexProjection};
 use kukuri_cn_indexer::query::{FailClosedIndexQuery, IndexQuery, MAX_QUERY_LIMIT};
+use kukuri_cn_protocol::IndexEntryKind;
 use kukuri_cn_safety::provider::SubjectKind;
 use kukuri_cn_safety::{
     MockSafetyProvider, ModerationEventSigner, ReasonCode, SafetyAction, SafetyVerdict,
@@ -219,6 +220,9 @@ async fn projection_residue_without_authoritative_entry_is_not_surfaced() -> Res
             text: "ghost searchable text".to_string(),
             created_at: 1,
             source_replica_id: "topic::rust".to_string(),
+            entry_kind: IndexEntryKind::Post,
+            profile: None,
+            session: None,
         })
         .await?;
 
@@ -73,6 +73,7 @@ async fn seeded_entries() -> Result<MemoryIndexEntryStore> {
                 verdict_id,
                 verdict_action: "allow".to_string(),
                 critical: false,
+                engagement: Default::default(),
             })
             .await?;
     }
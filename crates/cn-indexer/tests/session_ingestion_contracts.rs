@@ -0,0 +1,361 @@
+//! live now: live session / game room の state doc を manifest 経由で `session` entry として投影する contract。
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use kukuri_blob_service::{BlobService, MemoryBlobService};
+use kukuri_cn_core::{IndexScopeKind, MemoryIndexEntryStore};
+use kukuri_cn_indexer::ingest::{IngestPipeline, game_room_object_id, live_session_object_id};
+use kukuri_cn_indexer::projection::{IndexProjection, MemoryIndexProjection};
+use kukuri_cn_protocol::{IndexEntryKind, IndexSessionKind, IndexSessionStatus};
+use kukuri_cn_safety::{MockSafetyProvider, ModerationEventSigner};
+use kukuri_cn_safety_runtime::clock::SystemScanClock;
+use kukuri_cn_safety_runtime::id::UuidEventIdGenerator;
+use kukuri_cn_safety_runtime::{
+    MemorySafetyArtifactStore, SafetyOrchestrator, SafetyScanService,
+    Secp256k1ModerationEventSigner,
+};
+use kukuri_core::{
+    ChannelId, EnvelopeId, GAME_MANIFEST_MIME, GameParticipant, GameRoomKind,
+    GameRoomManifestBlobV1, GameRoomStateDocV1, GameRoomStatus, KukuriKeys, LIVE_MANIFEST_MIME,
+    LiveSessionManifestBlobV1, LiveSessionStateDocV1, LiveSessionStatus, ManifestBlobRef,
+    ReplicaId, TopicId, blob_hash,
+};
+use kukuri_docs_sync::{DocOp, DocsSync, MemoryDocsSync, stable_key, topic_replica_id};
+
+const TEST_SECRET: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+
+fn allow_service() -> (Arc<SafetyScanService>, Arc<MemorySafetyArtifactStore>) {
+    let signer = Secp256k1ModerationEventSigner::from_secret(TEST_SECRET).expect("signer");
+    let issuer = signer.issuer_node_id().to_string();
+    let store = Arc::new(MemorySafetyArtifactStore::new());
+    let orchestrator = SafetyOrchestrator::builder(
+        &issuer,
+        Arc::new(SystemScanClock),
+        Arc::new(UuidEventIdGenerator),
+    )
+    .provider(Arc::new(MockSafetyProvider::known_csam("mock-known-csam")))
+    .build()
+    .expect("orchestrator");
+    let service = SafetyScanService::builder(Arc::new(orchestrator), store.clone())
+        .signer(Arc::new(signer))
+        .build()
+        .expect("service");
+    (Arc::new(service), store)
+}
+
+fn pipeline_with(
+    docs: &Arc<MemoryDocsSync>,
+    projection: &Arc<MemoryIndexProjection>,
+) -> (IngestPipeline, Arc<MemoryIndexEntryStore>) {
+    let (service, store) = allow_service();
+    let entries = Arc::new(MemoryIndexEntryStore::new(store));
+    let pipeline = IngestPipeline::new(docs.clone(), service, entries.clone(), projection.clone());
+    (pipeline, entries)
+}
+
+async fn put_manifest<T: serde::Serialize>(
+    blobs: &MemoryBlobService,
+    manifest: &T,
+    mime: &str,
+) -> ManifestBlobRef {
+    let stored = blobs
+        .put_blob(serde_json::to_vec(manifest).expect("manifest json"), mime)
+        .await
+        .expect("store manifest blob");
+    ManifestBlobRef {
+        hash: stored.hash,
+        mime: stored.mime,
+        bytes: stored.bytes,
+    }
+}
+
+async fn set_state<T: serde::Serialize>(
+    docs: &MemoryDocsSync,
+    replica: &ReplicaId,
+    prefix: &str,
+    id: &str,
+    state: &T,
+) {
+    docs.open_replica(replica).await.expect("open");
+    docs.apply_doc_op(
+        replica,
+        DocOp::SetJson {
+            key: stable_key(prefix, &format!("{id}/state")),
+            value: serde_json::to_value(state).expect("state json"),
+        },
+    )
+    .await
+    .expect("state op");
+}
+
+async fn persist_live_session(
+    docs: &MemoryDocsSync,
+    blobs: &MemoryBlobService,
+    replica: &ReplicaId,
+    keys: &KukuriKeys,
+    session_id: &str,
+    channel_id: Option<ChannelId>,
+    status: LiveSessionStatus,
+) {
+    let manifest = LiveSessionManifestBlobV1 {
+        session_id: session_id.to_string(),
+        topic_id: TopicId::new("rust"),
+        channel_id: channel_id.clone(),
+        owner_pubkey: keys.public_key(),
+        title: "Rust 配信".to_string(),
+        description: "async の話".to_string(),
+        status: status.clone(),
+        started_at: 1_700_000_000_000,
+        ended_at: (status == LiveSessionStatus::Ended).then_some(1_700_000_600_000),
+    };
+    let current_manifest = put_manifest(blobs, &manifest, LIVE_MANIFEST_MIME).await;
+    let state = LiveSessionStateDocV1 {
+        session_id: session_id.to_string(),
+        topic_id: TopicId::new("rust"),
+        channel_id,
+        owner_pubkey: keys.public_key(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        status,
+        current_manifest,
+        last_envelope_id: EnvelopeId::from("live-envelope"),
+    };
+    set_state(docs, replica, "sessions/live", session_id, &state).await;
+}
+
+async fn persist_game_room(
+    docs: &MemoryDocsSync,
+    blobs: &MemoryBlobService,
+    replica: &ReplicaId,
+    keys: &KukuriKeys,
+    room_id: &str,
+    status: GameRoomStatus,
+) {
+    let manifest = GameRoomManifestBlobV1 {
+        room_id: room_id.to_string(),
+        topic_id: TopicId::new("rust"),
+        channel_id: None,
+        owner_pubkey: keys.public_key(),
+        title: "Rust ロビー".to_string(),
+        description: String::new(),
+        status: status.clone(),
+        phase_label: None,
+        participants: ["alice", "bob"]
+            .into_iter()
+            .map(|label| GameParticipant {
+                participant_id: label.to_string(),
+                label: label.to_string(),
+            })
+            .collect(),
+        scores: Vec::new(),
+        room_kind: GameRoomKind::MetaverseRoom,
+        metaverse: None,
+        updated_at: 1_700_000_000_000,
+    };
+    let current_manifest = put_manifest(blobs, &manifest, GAME_MANIFEST_MIME).await;
+    let state = GameRoomStateDocV1 {
+        room_id: room_id.to_string(),
+        topic_id: TopicId::new("rust"),
+        channel_id: None,
+        owner_pubkey: keys.public_key(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        status,
+        current_manifest,
+        last_envelope_id: EnvelopeId::from("game-envelope"),
+    };
+    set_state(docs, replica, "sessions/game", room_id, &state).await;
+}
+
+#[tokio::test]
+async fn active_sessions_are_indexed_and_ended_sessions_are_removed() -> Result<()> {
+    let docs = Arc::new(MemoryDocsSync::default());
+    let blobs = Arc::new(MemoryBlobService::default());
+    let projection = Arc::new(MemoryIndexProjection::new());
+    let replica = topic_replica_id("rust");
+    let keys = KukuriKeys::generate();
+    persist_live_session(
+        &docs,
+        &blobs,
+        &replica,
+        &keys,
+        "live-1",
+        None,
+        LiveSessionStatus::Live,
+    )
+    .await;
+    persist_game_room(
+        &docs,
+        &blobs,
+        &replica,
+        &keys,
+        "room-1",
+        GameRoomStatus::Running,
+    )
+    .await;
+
+    let (pipeline, entries) = pipeline_with(&docs, &projection);
+    let pipeline = pipeline.with_blob_service(blobs.clone());
+    let summary = pipeline
+        .ingest_scope(IndexScopeKind::PublicTopic, "rust", &replica)
+        .await?;
+    assert_eq!(summary.sessions_indexed, 2);
+
+    let live_id = live_session_object_id("live-1");
+    let room_id = game_room_object_id("room-1");
+    assert!(entries.contains(IndexScopeKind::PublicTopic, "rust", &live_id));
+    assert!(entries.contains(IndexScopeKind::PublicTopic, "rust", &room_id));
+    let stored = projection
+        .entries_in_scope(IndexScopeKind::PublicTopic, "rust")
+        .await;
+    assert_eq!(stored.len(), 2);
+    let live = stored
+        .iter()
+        .find(|entry| entry.object_id == live_id)
+        .expect("live entry");
+    assert_eq!(live.entry_kind, IndexEntryKind::Session);
+    assert_eq!(live.author_pubkey, keys.public_key_hex());
+    assert_eq!(live.created_at, 1_700_000_000);
+    assert_eq!(live.text, "Rust 配信\nasync の話");
+    let live_view = live.session.as_ref().expect("live session view");
+    assert_eq!(live_view.session_kind, IndexSessionKind::LiveSession);
+    assert_eq!(live_view.status, IndexSessionStatus::Live);
+    assert_eq!(live_view.participant_count, None);
+    let room = stored
+        .iter()
+        .find(|entry| entry.object_id == room_id)
+        .expect("room entry");
+    let room_view = room.session.as_ref().expect("room session view");
+    assert_eq!(room_view.session_kind, IndexSessionKind::MetaverseRoom);
+    assert_eq!(room_view.status, IndexSessionStatus::Running);
+    assert_eq!(room_view.description, None);
+    assert_eq!(room_view.participant_count, Some(2));
+
+    persist_live_session(
+        &docs,
+        &blobs,
+        &replica,
+        &keys,
+        "live-1",
+        None,
+        LiveSessionStatus::Ended,
+    )
+    .await;
+    let summary = pipeline
+        .ingest_scope(IndexScopeKind::PublicTopic, "rust", &replica)
+        .await?;
+    assert_eq!(summary.sessions_indexed, 1);
+    assert_eq!(summary.deindexed, 1);
+    assert!(!entries.contains(IndexScopeKind::PublicTopic, "rust", &live_id));
+    assert!(
+        !projection
+            .contains_object(IndexScopeKind::PublicTopic, "rust", &live_id)
+            .await?
+    );
+    assert!(
+        projection
+            .contains_object(IndexScopeKind::PublicTopic, "rust", &room_id)
+            .await?
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn channel_sessions_stay_in_their_channel_scope() -> Result<()> {
+    let docs = Arc::new(MemoryDocsSync::default());
+    let blobs = Arc::new(MemoryBlobService::default());
+    let projection = Arc::new(MemoryIndexProjection::new());
+    let replica = topic_replica_id("rust");
+    let keys = KukuriKeys::generate();
+    persist_live_session(
+        &docs,
+        &blobs,
+        &replica,
+        &keys,
+        "live-private",
+        Some(ChannelId::new("channel-1")),
+        LiveSessionStatus::Live,
+    )
+    .await;
+
+    let (pipeline, entries) = pipeline_with(&docs, &projection);
+    let pipeline = pipeline.with_blob_service(blobs);
+    let summary = pipeline
+        .ingest_scope(IndexScopeKind::PublicTopic, "rust", &replica)
+        .await?;
+    assert_eq!(summary.sessions_indexed, 0);
+    let object_id = live_session_object_id("live-private");
+    assert!(!entries.contains(IndexScopeKind::PublicTopic, "rust", &object_id));
+
+    let summary = pipeline
+        .ingest_scope(IndexScopeKind::PrivateChannel, "channel-1", &replica)
+        .await?;
+    assert_eq!(summary.sessions_indexed, 1);
+    assert!(entries.contains(IndexScopeKind::PrivateChannel, "channel-1", &object_id));
+    Ok(())
+}
+
+#[tokio::test]
+async fn unverifiable_session_manifests_fail_closed() -> Result<()> {
+    let docs = Arc::new(MemoryDocsSync::default());
+    let blobs = Arc::new(MemoryBlobService::default());
+    let projection = Arc::new(MemoryIndexProjection::new());
+    let replica = topic_replica_id("rust");
+    let keys = KukuriKeys::generate();
+    persist_live_session(
+        &docs,
+        &blobs,
+        &replica,
+        &keys,
+        "live-1",
+        None,
+        LiveSessionStatus::Live,
+    )
+    .await;
+    let object_id = live_session_object_id("live-1");
+
+    // blob service が無ければ manifest を検証できないため index しない。
+    let (pipeline, entries) = pipeline_with(&docs, &projection);
+    let summary = pipeline
+        .ingest_scope(IndexScopeKind::PublicTopic, "rust", &replica)
+        .await?;
+    assert_eq!(summary.sessions_indexed, 0);
+    assert_eq!(summary.skipped_non_allow, 1);
+    assert!(!entries.contains(IndexScopeKind::PublicTopic, "rust", &object_id));
+
+    // state doc の参照と一致しない manifest は、既存 entry ごと落とす。
+    let pipeline = pipeline.with_blob_service(blobs.clone());
+    let summary = pipeline
+        .ingest_scope(IndexScopeKind::PublicTopic, "rust", &replica)
+        .await?;
+    assert_eq!(summary.sessions_indexed, 1);
+    let tampered = LiveSessionStateDocV1 {
+        session_id: "live-1".to_string(),
+        topic_id: TopicId::new("rust"),
+        channel_id: None,
+        owner_pubkey: keys.public_key(),
+        created_at: 1_700_000_000_000,
+        updated_at: 1_700_000_000_000,
+        status: LiveSessionStatus::Live,
+        current_manifest: ManifestBlobRef {
+            hash: blob_hash(b"missing manifest"),
+            mime: LIVE_MANIFEST_MIME.to_string(),
+            bytes: 16,
+        },
+        last_envelope_id: EnvelopeId::from("live-envelope"),
+    };
+    set_state(&docs, &replica, "sessions/live", "live-1", &tampered).await;
+    let summary = pipeline
+        .ingest_scope(IndexScopeKind::PublicTopic, "rust", &replica)
+        .await?;
+    assert_eq!(summary.sessions_indexed, 0);
+    assert_eq!(summary.skipped_non_allow, 1);
+    assert!(!entries.contains(IndexScopeKind::PublicTopic, "rust", &object_id));
+    assert!(
+        !projection
+            .contains_object(IndexScopeKind::PublicTopic, "rust", &object_id)
+            .await?
+    );
+    Ok(())
+}
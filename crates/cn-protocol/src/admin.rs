@@ -0,0 +1,217 @@
+//! 運営者向け JSON admin API の共有通信契約。
+//!
+//! admin listener(IAP 内部)の `/api/v1` 配下で、HTML 運営画面と同じ preview -> apply の
+//! 意味論を JSON で提供する。認証は運営者ログインで得た admin session token を
+//! `Authorization: Bearer` で送る(cookie は使わないため CSRF token は不要)。
+//! `action` の値は HTML form と操作記録(`operator_actions.action`)と同じ識別子を使う。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const ADMIN_API_LOGIN_CHALLENGE_PATH: &str = "/api/v1/login/challenge";
+pub const ADMIN_API_LOGIN_VERIFY_PATH: &str = "/api/v1/login/verify";
+pub const ADMIN_API_LOGOUT_PATH: &str = "/api/v1/logout";
+pub const ADMIN_API_SESSION_PATH: &str = "/api/v1/session";
+pub const ADMIN_API_OPERATORS_PATH: &str = "/api/v1/operators";
+pub const ADMIN_API_REPORTS_PATH: &str = "/api/v1/reports";
+pub const ADMIN_API_APPEALS_PATH: &str = "/api/v1/appeals";
+pub const ADMIN_API_OPERATOR_ACTIONS_PATH: &str = "/api/v1/operator-actions";
+pub const ADMIN_API_PREVIEW_PATH: &str = "/api/v1/actions/preview";
+pub const ADMIN_API_APPLY_PATH: &str = "/api/v1/actions/apply";
+
+/// `POST /api/v1/login/verify` の要求本文。封筒は `build_admin_login_envelope_json` で作る。
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdminLoginRequest {
+    pub login_envelope_json: Value,
+}
+
+/// 運営者の登録情報。`role` は `viewer` / `moderator` / `admin`。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminOperatorInfo {
+    pub pubkey: String,
+    pub role: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// RFC 3339。
+    pub updated_at: String,
+}
+
+/// `POST /api/v1/login/verify` の成功応答。`token` はここでしか得られない。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminLoginResponse {
+    pub token: String,
+    /// Unix 秒。
+    pub expires_at: i64,
+    pub operator: AdminOperatorInfo,
+}
+
+/// `GET /api/v1/session` の応答。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminSessionResponse {
+    /// Unix 秒。
+    pub expires_at: i64,
+    pub operator: AdminOperatorInfo,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminOperatorsResponse {
+    pub items: Vec<AdminOperatorInfo>,
+}
+
+/// 一覧 endpoint の query。未指定は 50 件・先頭から。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminListQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+}
+
+/// 通報の一覧項目。補足説明と連絡先は HTML 画面と同じく含めない。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminReportSummary {
+    pub id: String,
+    /// RFC 3339。
+    pub created_at: String,
+    pub subject_kind: String,
+    pub subject_id: String,
+    pub capability: String,
+    pub reason: String,
+    pub status: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminReportsResponse {
+    pub items: Vec<AdminReportSummary>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminAppealReport {
+    pub id: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// RFC 3339。
+    pub created_at: String,
+}
+
+/// 審査対象のリスク判定と、それに紐づく異議申し立て。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminAppealReview {
+    pub risk_signal_id: String,
+    pub issuer_node_id: String,
+    pub target: String,
+    pub target_id: String,
+    pub category: String,
+    pub severity: String,
+    pub basis: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<u8>,
+    pub visibility: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    pub appeal_status: String,
+    pub reports: Vec<AdminAppealReport>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminAppealReviewsResponse {
+    pub items: Vec<AdminAppealReview>,
+}
+
+/// 操作記録(append-only audit)の 1 行。apply の成功応答も同じ形。
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdminOperatorActionRecord {
+    pub id: String,
+    /// RFC 3339。
+    pub occurred_at: String,
+    pub actor: String,
+    pub action: String,
+    pub target_kind: String,
+    pub target_id: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdminOperatorActionsResponse {
+    pub items: Vec<AdminOperatorActionRecord>,
+}
+
+/// 運営操作。`action` で判別し、HTML form と同じ検証を通る。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action")]
+pub enum AdminActionRequest {
+    #[serde(rename = "admission.set_mode")]
+    AdmissionSetMode { mode: String },
+    #[serde(rename = "supported_topic.add")]
+    SupportedTopicAdd { topic_id: String },
+    #[serde(rename = "supported_topic.remove")]
+    SupportedTopicRemove { topic_id: String },
+    #[serde(rename = "report.set_status")]
+    ReportSetStatus { report_id: String, status: String },
+    /// `role` が `None` なら運営者から削除する。
+    #[serde(rename = "operator.set_role")]
+    OperatorSetRole {
+        pubkey: String,
+        #[serde(default)]
+        role: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    },
+    #[serde(rename = "appeal.accept")]
+    AppealAccept { risk_signal_id: String },
+    #[serde(rename = "appeal.reject")]
+    AppealReject { risk_signal_id: String },
+    /// 未指定の項目は現在値を維持する。
+    #[serde(rename = "appeal.edit")]
+    AppealEdit {
+        risk_signal_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        category: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        severity: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        confidence: Option<u8>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expires_at: Option<String>,
+    },
+    #[serde(rename = "appeal.reissue")]
+    AppealReissue {
+        risk_signal_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        category: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        severity: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        confidence: Option<u8>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        visibility: Option<String>,
+    },
+}
+
+/// `POST /api/v1/actions/apply` の要求本文。
+///
+/// `expected_state` は異議申し立て審査で必須で、preview 応答の値をそのまま送り返す。
+/// 確認後に判定や関連通報が変わっていれば apply は拒否される。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminActionApplyRequest {
+    #[serde(flatten)]
+    pub action: AdminActionRequest,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_state: Option<String>,
+}
+
+/// `POST /api/v1/actions/preview` の応答。状態は変更しない。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminActionPreviewResponse {
+    pub action: String,
+    /// 操作に必要な運営者 role。
+    pub required_role: String,
+    pub actor: String,
+    pub target: String,
+    pub after: String,
+    pub impact: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_state: Option<String>,
+}
@@ -5,7 +5,7 @@ use serde_json::Value;
 
 use kukuri_core::{KukuriAuthEnvelopeContentV1, KukuriKeys, sign_envelope_json};
 
-use crate::normalize::normalize_http_url;
+use crate::normalize::{normalize_http_url, normalize_pubkey};
 
 /// 認証封筒の kind(cn endpoint contract の一部)。
 pub const AUTH_ENVELOPE_KIND: &str = "auth";
@@ -14,19 +14,89 @@ pub fn build_auth_envelope_json(
     keys: &KukuriKeys,
     challenge: &str,
     public_base_url: &str,
+) -> Result<Value> {
+    build_scoped_auth_envelope_json(
+        keys,
+        challenge,
+        normalize_http_url(public_base_url)?,
+        AUTH_SCOPE,
+    )
+}
+
+/// 利用者向け認証封筒の scope。
+pub const AUTH_SCOPE: &str = "community-node-auth";
+
+/// 運営者ログイン封筒の scope。利用者向けの認証封筒と取り違えないよう scope と
+/// `capability_url`(`admin_login_capability_url`)の両方を分ける。
+pub const ADMIN_LOGIN_SCOPE: &str = "community-node-admin";
+
+/// 運営者ログイン封筒の `capability_url`。node の公開 URL に `/admin` を足したもので、
+/// 利用者向け `/v1/auth/verify` はこの値を capability mismatch として拒否する。
+pub fn admin_login_capability_url(public_base_url: &str) -> Result<String> {
+    Ok(format!("{}/admin", normalize_http_url(public_base_url)?))
+}
+
+/// 運営者ログイン封筒を構築する(`cn-cli admin sign-login` が使う)。challenge は利用者向けと
+/// 同じ `/v1/auth/challenge` の発行物を再利用する。
+pub fn build_admin_login_envelope_json(
+    keys: &KukuriKeys,
+    challenge: &str,
+    public_base_url: &str,
+) -> Result<Value> {
+    build_scoped_auth_envelope_json(
+        keys,
+        challenge,
+        admin_login_capability_url(public_base_url)?,
+        ADMIN_LOGIN_SCOPE,
+    )
+}
+
+/// 推薦(vouch)封筒の kind。auth 封筒として再利用されないよう kind から分ける。
+pub const VOUCH_ENVELOPE_KIND: &str = "vouch";
+
+/// 推薦封筒の scope。
+pub const VOUCH_SCOPE: &str = "community-node-vouch";
+
+/// 既存 member が `candidate_pubkey` の参加を推薦する封筒を構築する。
+///
+/// `capability_url` で推薦先の node を固定し、別 node への流用を防ぐ。
+pub fn build_vouch_envelope_json(
+    keys: &KukuriKeys,
+    candidate_pubkey: &str,
+    public_base_url: &str,
 ) -> Result<Value> {
     let signed = sign_envelope_json(
         keys,
-        AUTH_ENVELOPE_KIND,
+        VOUCH_ENVELOPE_KIND,
         vec![
-            vec!["challenge".into(), challenge.to_string()],
+            vec!["candidate".into(), normalize_pubkey(candidate_pubkey)?],
             vec![
                 "capability_url".into(),
                 normalize_http_url(public_base_url)?,
             ],
         ],
         &KukuriAuthEnvelopeContentV1 {
-            scope: "community-node-auth".into(),
+            scope: VOUCH_SCOPE.into(),
+        },
+    )?;
+    serde_json::to_value(signed).context("failed to encode vouch envelope json")
+}
+
+fn build_scoped_auth_envelope_json(
+    keys: &KukuriKeys,
+    challenge: &str,
+    capability_url: String,
+    scope: &str,
+) -> Result<Value> {
+    let signed = sign_envelope_json(
+        keys,
+        AUTH_ENVELOPE_KIND,
+        vec![
+            vec!["challenge".into(), challenge.to_string()],
+            vec!["capability_url".into(), capability_url],
+        ],
+        &KukuriAuthEnvelopeContentV1 {
+            scope: scope.into(),
         },
     )?;
     serde_json::to_value(signed).context("failed to encode auth envelope json")
@@ -40,3 +40,33 @@ pub const RELATION_VISIBILITY_NOT_CONFIGURED_CODE: &str = "RELATION_VISIBILITY_N
 
 /// 距離利用停止の有効化が失効している(404)。
 pub const RELATION_VISIBILITY_NOT_ACTIVATED_CODE: &str = "RELATION_VISIBILITY_NOT_ACTIVATED";
+
+/// 認証済み鍵(pubkey)ごとの route 予算を超えた(429。`Retry-After` 付き)。
+/// IP 単位の段とは独立に数え、同じ鍵が IP を変えても予算は共有される。
+pub const PUBKEY_RATE_LIMITED_CODE: &str = "PUBKEY_RATE_LIMITED";
+
+/// 読み取り専用の制裁中のため、通報・索引要求・rendezvous heartbeat を受け付けない(403)。
+/// auth と参照系は引き続き使える。
+pub const SUBSCRIBER_READ_ONLY_CODE: &str = "SUBSCRIBER_READ_ONLY";
+
+/// 推薦(vouch)封筒が不正、または推薦者・候補者の条件を満たさない(400)。
+pub const ADMISSION_VOUCH_INVALID_CODE: &str = "ADMISSION_VOUCH_INVALID";
+
+/// 近接度による推薦の重み付けが設定されているが、relation graph が構成されていない(503)。
+pub const ADMISSION_VOUCH_WEIGHTING_UNAVAILABLE_CODE: &str =
+    "ADMISSION_VOUCH_WEIGHTING_UNAVAILABLE";
+
+/// admin API で運営者の role が操作に足りない(403)。
+pub const ADMIN_ROLE_REQUIRED_CODE: &str = "ADMIN_ROLE_REQUIRED";
+
+/// admin API の操作内容が不正(未対応の action・値・確認情報の欠落。400)。
+pub const ADMIN_ACTION_INVALID_CODE: &str = "ADMIN_ACTION_INVALID";
+
+/// admin API の操作対象(審査対象のリスク判定など)が見つからない(404)。
+pub const ADMIN_TARGET_NOT_FOUND_CODE: &str = "ADMIN_TARGET_NOT_FOUND";
+
+/// admin API の操作を現在の状態で拒否した(確認後の変更・審査済み・最後の admin など。409)。
+pub const ADMIN_ACTION_REJECTED_CODE: &str = "ADMIN_ACTION_REJECTED";
+
+/// このノードでは異議申し立ての運営者確認が無効(503)。
+pub const ADMIN_OPERATOR_REVIEW_DISABLED_CODE: &str = "ADMIN_OPERATOR_REVIEW_DISABLED";
@@ -15,6 +15,43 @@ pub enum IndexScopeKind {
     PrivateChannel,
 }
 
+/// Kinds of entries projected into the Community Node index.
+///
+/// Search, discovery, and recommendations read `post` entries only; people search
+/// reads `profile` entries and live-now discovery reads `session` entries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "snake_case")]
+pub enum IndexEntryKind {
+    #[default]
+    Post,
+    Profile,
+    Session,
+}
+
+impl IndexEntryKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Post => "post",
+            Self::Profile => "profile",
+            Self::Session => "session",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "post" => Ok(Self::Post),
+            "profile" => Ok(Self::Profile),
+            "session" => Ok(Self::Session),
+            other => bail!("unknown index entry kind `{other}`"),
+        }
+    }
+
+    pub fn is_post(&self) -> bool {
+        *self == Self::Post
+    }
+}
+
 /// indexing request の処理状態。
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(ts_rs::TS))]
@@ -109,10 +146,47 @@ pub const CHANNEL_MEMBERSHIP_SECRET_HEADER: &str = "x-kukuri-channel-secret";
 /// 非所属者に索引の存在有無を漏らさない。
 pub const CHANNEL_MEMBERSHIP_REQUIRED_CODE: &str = "CHANNEL_MEMBERSHIP_REQUIRED";
 
-/// Query parameters shared by search, discovery, and recommendations.
+/// Result ordering for search and discovery.
+///
+/// Recommendations are ranked and do not accept an explicit order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "snake_case")]
+pub enum IndexSortOrder {
+    /// `created_at` descending (the default).
+    #[default]
+    Newest,
+    /// `created_at` ascending.
+    Oldest,
+}
+
+impl IndexSortOrder {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Newest => "newest",
+            Self::Oldest => "oldest",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "newest" => Ok(Self::Newest),
+            "oldest" => Ok(Self::Oldest),
+            other => bail!("unknown index sort order `{other}`"),
+        }
+    }
+}
+
+/// Query parameters shared by search, discovery, recommendations, and people search.
 ///
 /// `scope_kind` and `scope_id` must either both be present or both be absent.
 /// The HTTP handler validates that cross-field rule.
+///
+/// `since` / `until` are unix seconds bounding `created_at` (`since` inclusive,
+/// `until` exclusive). `cursor` is the opaque `next_cursor` from a previous
+/// response and must be sent together with the same filters and `order`.
+/// `order` is kept as a string so the server can answer an unknown value with
+/// the stable `INVALID_INDEX_QUERY` code.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
@@ -125,11 +199,24 @@ pub struct IndexQueryParams {
     pub scope_id: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub limit: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_pubkey: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
 }
 
 /// One projected index result.
 ///
 /// `text` may contain derived tags and is not canonical post content.
+///
+/// `reasons` is only populated by recommendations and is ordered by how much each
+/// signal contributed to the entry's rank (strongest first).
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub struct IndexEntryView {
@@ -139,12 +226,108 @@ pub struct IndexEntryView {
     pub author_pubkey: String,
     pub text: String,
     pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "ts", ts(as = "Option<Vec<RecommendationReason>>"))]
+    pub reasons: Vec<RecommendationReason>,
+    /// Omitted for posts, so the post wire shape is unchanged.
+    #[serde(default, skip_serializing_if = "IndexEntryKind::is_post")]
+    #[cfg_attr(feature = "ts", ts(as = "Option<IndexEntryKind>"))]
+    pub entry_kind: IndexEntryKind,
+    /// Signed profile fields, present only on `profile` entries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<IndexProfileView>,
+    /// Session state, present only on `session` entries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<IndexSessionView>,
+}
+
+/// Profile fields of a `profile` index entry, taken from the author's signed
+/// profile envelope.
+///
+/// `created_at` of the entry is the profile's `updated_at`, in unix seconds.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
+pub struct IndexProfileView {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub about: Option<String>,
+}
+
+/// Kinds of sessions listed by live-now discovery.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "snake_case")]
+pub enum IndexSessionKind {
+    LiveSession,
+    GameRoom,
+    MetaverseRoom,
+}
+
+/// Status of an indexed session.
+///
+/// Ended sessions are removed from the index, so there is no `ended` status.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "snake_case")]
+pub enum IndexSessionStatus {
+    Scheduled,
+    Live,
+    Waiting,
+    Running,
+    Paused,
+}
+
+/// State of a `session` index entry, taken from the session's current manifest.
+///
+/// `created_at` of the entry is when the session was created, in unix seconds.
+/// `participant_count` is only known for game and metaverse rooms; live session
+/// viewers are tracked through ephemeral presence that the index does not see.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
+pub struct IndexSessionView {
+    pub session_kind: IndexSessionKind,
+    pub session_id: String,
+    pub topic_id: String,
+    pub status: IndexSessionStatus,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub participant_count: Option<u32>,
+}
+
+/// Why a recommendation was ranked where it was.
+///
+/// Clients map these stable codes to their own explanation text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationReason {
+    /// Posted recently.
+    Recent,
+    /// Drew reactions or replies on the shared replica.
+    Engaged,
+    /// The author has a positive node-local trust score.
+    TrustedAuthor,
+    /// The author is relationally close to the viewer.
+    CloseToViewer,
 }
 
+/// One page of index results.
+///
+/// `next_cursor` is absent on the last page.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
 pub struct IndexQueryResponse {
     pub entries: Vec<IndexEntryView>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 /// Stable non-2xx JSON body returned by `cn-user-api`.
@@ -8,11 +8,13 @@
 //! **ここにある型・関数の serde 表現と挙動は cn endpoint contract の一部**
 //! (REFACTORING.md の凍結境界)。変更は互換性の検討とセットで行うこと。
 
+pub mod admin;
 pub mod auth;
 pub mod error_codes;
 pub mod index;
 pub mod manifest;
 pub mod models;
+pub mod moderation;
 pub mod normalize;
 pub mod paths;
 pub mod rendezvous;
@@ -20,11 +22,13 @@ pub mod report;
 pub mod requests;
 pub mod trust_relation;
 
+pub use admin::*;
 pub use auth::*;
 pub use error_codes::*;
 pub use index::*;
 pub use manifest::*;
 pub use models::*;
+pub use moderation::*;
 pub use normalize::*;
 pub use paths::*;
 pub use rendezvous::*;
@@ -95,6 +95,22 @@ pub struct AuthVerifyResponse {
     pub token_type: String,
     pub expires_at: i64,
     pub pubkey: String,
+    /// アクセストークン失効後に `AUTH_REFRESH_PATH` で再発行するための一度きりのトークン。
+    /// 旧 node は返さない。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_expires_at: Option<i64>,
+}
+
+/// 推薦の受理結果。`total_weight` が `required_weight` 以上になると候補者は auth で admit される。
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdmissionVouchResponse {
+    pub candidate_pubkey: String,
+    /// この推薦の重み(近接度による減衰後。`[0, 1]`)。
+    pub weight: f64,
+    pub total_weight: f64,
+    pub required_weight: f64,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -138,4 +154,8 @@ pub struct CommunityNodeConsentStatus {
 pub struct BearerIdentity {
     pub pubkey: String,
     pub endpoint_id: Option<String>,
+    /// 読み取り専用の制裁中。状態を変える補助機能(通報・索引要求・rendezvous)を拒否する。
+    pub read_only: bool,
+    /// アクセストークンが属する auth セッション。logout はこのセッションを失効させる。
+    pub session_id: Option<String>,
 }
@@ -0,0 +1,94 @@
+//! signed moderation event の配布 wire 契約(`GET /v1/moderation/events`)。
+//!
+//! community node は自身が署名した moderation event を advisory として配布し、
+//! client は node manifest の `node_id`(issuer 鍵)で検証してから端末内 policy で扱いを決める
+//! (ADR 0027 §2.1 advisory ≠ command)。`local` visibility の event は配布対象にならない。
+
+use std::str::FromStr;
+
+use anyhow::{Context, Result, bail};
+use kukuri_cn_safety::Visibility;
+pub use kukuri_cn_safety::{
+    ModerationAction, ModerationEventBody, SafetyLabel, SignedModerationEvent, SubjectKind,
+};
+use secp256k1::schnorr::Signature;
+use secp256k1::{SECP256K1, XOnlyPublicKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 1 回の pull で返す件数の既定値と上限。
+pub const MODERATION_EVENTS_DEFAULT_LIMIT: usize = 50;
+pub const MODERATION_EVENTS_MAX_LIMIT: usize = 100;
+
+/// pull 要求者に適用された配布範囲。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationEventAudience {
+    /// 匿名の要求者。`public` visibility の event のみ。
+    Public,
+    /// bearer で認証できた subscriber。`subscribed_nodes` + `public`。
+    SubscribedNodes,
+}
+
+impl ModerationEventAudience {
+    /// この audience に配布してよい visibility か。
+    pub fn allows(self, visibility: Visibility) -> bool {
+        match visibility {
+            Visibility::Local => false,
+            Visibility::SubscribedNodes => matches!(self, Self::SubscribedNodes),
+            Visibility::Public => true,
+        }
+    }
+}
+
+/// `GET /v1/moderation/events` の query。新しい順(永続化時刻と event id の降順)の keyset paging。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModerationEventsQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// 前のページの `next_cursor`。client は解釈せずそのまま返す。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// `GET /v1/moderation/events` の応答。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModerationEventsResponse {
+    pub audience: ModerationEventAudience,
+    pub items: Vec<SignedModerationEvent>,
+    /// 続き(より古い event)がある場合の cursor。最後のページでは None。
+    /// 先頭に新しい event が増えても続きの位置はずれない。
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+/// 配布された moderation event を issuer 鍵で検証する。
+///
+/// `expected_issuer_node_id` は node manifest の `node_id`(署名鍵の x-only 公開鍵 hex)。
+/// issuer の不一致・`local` visibility・改竄 / 別鍵の署名はいずれも `Err` を返す。
+/// 署名方式は cn-safety-runtime の signer と同じ `sha256(canonical_bytes)` への schnorr 署名。
+pub fn verify_moderation_event(
+    event: &SignedModerationEvent,
+    expected_issuer_node_id: &str,
+) -> Result<()> {
+    let body = &event.body;
+    if body.issuer_node_id != expected_issuer_node_id.trim() {
+        bail!("moderation event issuer does not match the node manifest");
+    }
+    if body.visibility == Visibility::Local {
+        bail!("local moderation events must not be distributed");
+    }
+    if body.id.trim().is_empty() || body.target_id.trim().is_empty() {
+        bail!("moderation event id and target_id must not be empty");
+    }
+    let public_key = XOnlyPublicKey::from_str(body.issuer_node_id.as_str())
+        .context("invalid moderation event issuer")?;
+    let signature = Signature::from_str(event.signature.as_str())
+        .context("invalid moderation event signature")?;
+    let digest: [u8; 32] = Sha256::digest(body.canonical_bytes()).into();
+    SECP256K1
+        .verify_schnorr(&signature, &digest, &public_key)
+        .context("moderation event signature verification failed")?;
+    Ok(())
+}
@@ -5,6 +5,12 @@
 
 pub const AUTH_CHALLENGE_PATH: &str = "/v1/auth/challenge";
 pub const AUTH_VERIFY_PATH: &str = "/v1/auth/verify";
+/// refresh token によるアクセストークンの再発行(challenge を経ない)。
+pub const AUTH_REFRESH_PATH: &str = "/v1/auth/refresh";
+/// 現在のセッション(アクセストークンと refresh token)の失効。
+pub const AUTH_LOGOUT_PATH: &str = "/v1/auth/logout";
+/// 既存 member による参加推薦(vouch)の提出。
+pub const ADMISSION_VOUCHES_PATH: &str = "/v1/admission/vouches";
 pub const CONSENTS_PATH: &str = "/v1/consents";
 pub const CONSENTS_STATUS_PATH: &str = "/v1/consents/status";
 pub const BOOTSTRAP_NODES_PATH: &str = "/v1/bootstrap/nodes";
@@ -15,12 +21,17 @@ pub const INDEXING_REQUESTS_PATH: &str = "/v1/indexing/requests";
 pub const INDEX_SEARCH_PATH: &str = "/v1/index/search";
 pub const INDEX_DISCOVERY_PATH: &str = "/v1/index/discovery";
 pub const INDEX_RECOMMENDATIONS_PATH: &str = "/v1/index/recommendations";
+/// profile entry の全文検索(people search)。relation opt-out 済みの user は載らない。
+pub const INDEX_PEOPLE_PATH: &str = "/v1/index/people";
+/// 進行中の live session / game room の列挙(live now)。終了した session は載らない。
+pub const INDEX_LIVE_PATH: &str = "/v1/index/live";
 pub const TRUST_USERS_PATH_PREFIX: &str = "/v1/trust/users/";
 pub const TRUST_USERS_ROUTE: &str = "/v1/trust/users/{pubkey}";
 pub const RELATION_USERS_PATH_PREFIX: &str = "/v1/relation/users/";
 pub const RELATION_USERS_ROUTE: &str = "/v1/relation/users/{target}";
 pub const RELATION_NEIGHBORS_PATH: &str = "/v1/relation/neighbors";
 pub const RELATION_OPTOUT_PATH: &str = "/v1/relation/optout";
+pub const MODERATION_EVENTS_PATH: &str = "/v1/moderation/events";
 /// 通報受付。client は manifest の `report_endpoint` から動的に解決するため
 /// 直接この定数で URL を組み立てるのはサーバ側(route 定義)のみ。
 pub const REPORT_PATH: &str = "/v1/report";
@@ -28,6 +28,25 @@ pub struct AuthVerifyRequest {
     pub invite_code: Option<String>,
 }
 
+/// `AuthVerifyResponse::refresh_token` でアクセストークンを再発行する。refresh token は
+/// 一度きりで、応答の新しい refresh token に置き換わる。
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthRefreshRequest {
+    pub refresh_token: String,
+    /// セッションが endpoint id に束縛されている場合は同じ値を送る。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint_id: Option<String>,
+    /// verify と同じく `endpoint_id` と合わせて bootstrap peer として登録し直す。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub addr_hint: Option<String>,
+}
+
+/// vouch mode の node へ既存 member が提出する推薦(`build_vouch_envelope_json` の署名封筒)。
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdmissionVouchRequest {
+    pub vouch_envelope_json: Value,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AcceptConsentsRequest {
     #[serde(default)]
@@ -0,0 +1,98 @@
+use kukuri_cn_protocol::{
+    ADMIN_API_APPLY_PATH, ADMIN_API_LOGIN_CHALLENGE_PATH, ADMIN_API_LOGIN_VERIFY_PATH,
+    ADMIN_API_OPERATOR_ACTIONS_PATH, ADMIN_API_PREVIEW_PATH, AdminActionApplyRequest,
+    AdminActionPreviewResponse, AdminActionRequest,
+};
+
+#[test]
+fn admin_api_paths_are_versioned() {
+    assert_eq!(ADMIN_API_LOGIN_CHALLENGE_PATH, "/api/v1/login/challenge");
+    assert_eq!(ADMIN_API_LOGIN_VERIFY_PATH, "/api/v1/login/verify");
+    assert_eq!(ADMIN_API_OPERATOR_ACTIONS_PATH, "/api/v1/operator-actions");
+    assert_eq!(ADMIN_API_PREVIEW_PATH, "/api/v1/actions/preview");
+    assert_eq!(ADMIN_API_APPLY_PATH, "/api/v1/actions/apply");
+}
+
+#[test]
+fn admin_action_tags_match_operator_action_names() {
+    let request: AdminActionRequest = serde_json::from_value(serde_json::json!({
+        "action": "report.set_status",
+        "report_id": "report-1",
+        "status": "resolved"
+    }))
+    .unwrap();
+    assert_eq!(
+        request,
+        AdminActionRequest::ReportSetStatus {
+            report_id: "report-1".to_string(),
+            status: "resolved".to_string(),
+        }
+    );
+
+    // role を省略すると運営者から削除する。
+    let request: AdminActionRequest = serde_json::from_value(serde_json::json!({
+        "action": "operator.set_role",
+        "pubkey": "pk"
+    }))
+    .unwrap();
+    assert_eq!(
+        request,
+        AdminActionRequest::OperatorSetRole {
+            pubkey: "pk".to_string(),
+            role: None,
+            label: None,
+        }
+    );
+
+    assert!(
+        serde_json::from_value::<AdminActionRequest>(serde_json::json!({
+            "action": "admission.drop_all"
+        }))
+        .is_err()
+    );
+}
+
+#[test]
+fn apply_request_flattens_action_and_carries_expected_state() {
+    let request = AdminActionApplyRequest {
+        action: AdminActionRequest::AppealEdit {
+            risk_signal_id: "signal-1".to_string(),
+            category: None,
+            severity: Some("low".to_string()),
+            confidence: Some(40),
+            expires_at: None,
+        },
+        expected_state: Some("{\"appeal_status\":\"disputed\"}".to_string()),
+    };
+    let value = serde_json::to_value(&request).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "action": "appeal.edit",
+            "risk_signal_id": "signal-1",
+            "severity": "low",
+            "confidence": 40,
+            "expected_state": "{\"appeal_status\":\"disputed\"}"
+        })
+    );
+    assert_eq!(
+        serde_json::from_value::<AdminActionApplyRequest>(value).unwrap(),
+        request
+    );
+
+    let preview = AdminActionPreviewResponse {
+        action: "supported_topic.add".to_string(),
+        required_role: "admin".to_string(),
+        actor: "pk".to_string(),
+        target: "kukuri:topic:demo".to_string(),
+        after: "対応トピックに追加".to_string(),
+        impact: "impact".to_string(),
+        expected_state: None,
+    };
+    assert!(
+        serde_json::to_value(&preview)
+            .unwrap()
+            .get("expected_state")
+            .is_none()
+    );
+}
@@ -1,7 +1,9 @@
 use kukuri_cn_protocol::{
-    ApiErrorBody, INDEX_DISCOVERY_PATH, INDEX_RECOMMENDATIONS_PATH, INDEX_SEARCH_PATH,
-    INDEXING_REQUESTS_PATH, IndexEntryView, IndexQueryParams, IndexQueryResponse, IndexScopeKind,
-    IndexingRequestStatus, SubmitIndexingRequestRequest, SubmitIndexingRequestResponse,
+    ApiErrorBody, INDEX_DISCOVERY_PATH, INDEX_LIVE_PATH, INDEX_PEOPLE_PATH,
+    INDEX_RECOMMENDATIONS_PATH, INDEX_SEARCH_PATH, INDEXING_REQUESTS_PATH, IndexEntryKind,
+    IndexEntryView, IndexProfileView, IndexQueryParams, IndexQueryResponse, IndexScopeKind,
+    IndexSessionKind, IndexSessionStatus, IndexSessionView, IndexSortOrder, IndexingRequestStatus,
+    RecommendationReason, SubmitIndexingRequestRequest, SubmitIndexingRequestResponse,
 };
 
 #[test]
@@ -9,6 +11,8 @@ fn index_paths_are_stable() {
     assert_eq!(INDEX_SEARCH_PATH, "/v1/index/search");
     assert_eq!(INDEX_DISCOVERY_PATH, "/v1/index/discovery");
     assert_eq!(INDEX_RECOMMENDATIONS_PATH, "/v1/index/recommendations");
+    assert_eq!(INDEX_PEOPLE_PATH, "/v1/index/people");
+    assert_eq!(INDEX_LIVE_PATH, "/v1/index/live");
     assert_eq!(INDEXING_REQUESTS_PATH, "/v1/indexing/requests");
 }
 
@@ -48,6 +52,7 @@ fn index_query_params_preserve_scope_pair_and_optional_fields() {
         scope_kind: Some(IndexScopeKind::PublicTopic.as_str().to_string()),
         scope_id: Some("rust".to_string()),
         limit: Some(20),
+        ..IndexQueryParams::default()
     };
 
     assert_eq!(
@@ -70,6 +75,52 @@ fn index_query_params_preserve_scope_pair_and_optional_fields() {
     assert_eq!(decoded.limit, None);
 }
 
+#[test]
+fn index_query_pagination_and_filters_round_trip() {
+    let params = IndexQueryParams {
+        q: Some("tokio".to_string()),
+        cursor: Some("opaque".to_string()),
+        author_pubkey: Some("author".to_string()),
+        since: Some(100),
+        until: Some(200),
+        order: Some(IndexSortOrder::Oldest.as_str().to_string()),
+        ..IndexQueryParams::default()
+    };
+    assert_eq!(
+        serde_json::to_value(&params).unwrap(),
+        serde_json::json!({
+            "q": "tokio",
+            "cursor": "opaque",
+            "author_pubkey": "author",
+            "since": 100,
+            "until": 200,
+            "order": "oldest"
+        })
+    );
+
+    for order in [IndexSortOrder::Newest, IndexSortOrder::Oldest] {
+        assert_eq!(IndexSortOrder::parse(order.as_str()).unwrap(), order);
+        assert_eq!(
+            serde_json::to_value(order).unwrap(),
+            serde_json::json!(order.as_str())
+        );
+    }
+    assert_eq!(IndexSortOrder::default(), IndexSortOrder::Newest);
+    assert!(IndexSortOrder::parse("relevance").is_err());
+
+    let response = IndexQueryResponse {
+        entries: Vec::new(),
+        next_cursor: Some("next".to_string()),
+    };
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        serde_json::json!({ "entries": [], "next_cursor": "next" })
+    );
+    let last_page: IndexQueryResponse =
+        serde_json::from_value(serde_json::json!({ "entries": [] })).unwrap();
+    assert_eq!(last_page.next_cursor, None);
+}
+
 #[test]
 fn index_query_response_wire_shape_is_stable() {
     let response = IndexQueryResponse {
@@ -80,7 +131,12 @@ fn index_query_response_wire_shape_is_stable() {
             author_pubkey: "author".to_string(),
             text: "body\nderived-tag".to_string(),
             created_at: 42,
+            reasons: Vec::new(),
+            entry_kind: IndexEntryKind::Post,
+            profile: None,
+            session: None,
         }],
+        next_cursor: None,
     };
 
     assert_eq!(
@@ -98,6 +154,125 @@ fn index_query_response_wire_shape_is_stable() {
     );
 }
 
+#[test]
+fn recommendation_reasons_are_stable_codes_and_omitted_when_empty() {
+    let mut entry = IndexEntryView {
+        scope_kind: IndexScopeKind::PublicTopic,
+        scope_id: "rust".to_string(),
+        object_id: "post-1".to_string(),
+        author_pubkey: "author".to_string(),
+        text: "body".to_string(),
+        created_at: 42,
+        reasons: vec![
+            RecommendationReason::Engaged,
+            RecommendationReason::Recent,
+            RecommendationReason::TrustedAuthor,
+            RecommendationReason::CloseToViewer,
+        ],
+        entry_kind: IndexEntryKind::Post,
+        profile: None,
+        session: None,
+    };
+    assert_eq!(
+        serde_json::to_value(&entry).unwrap()["reasons"],
+        serde_json::json!(["engaged", "recent", "trusted_author", "close_to_viewer"])
+    );
+
+    entry.reasons.clear();
+    let encoded = serde_json::to_value(&entry).unwrap();
+    assert!(encoded.get("reasons").is_none());
+    let decoded: IndexEntryView = serde_json::from_value(encoded).unwrap();
+    assert!(decoded.reasons.is_empty());
+}
+
+#[test]
+fn profile_entries_carry_kind_and_profile_fields() {
+    let entry = IndexEntryView {
+        scope_kind: IndexScopeKind::PublicTopic,
+        scope_id: "rust".to_string(),
+        object_id: "profile:author".to_string(),
+        author_pubkey: "author".to_string(),
+        text: "alice\nAlice\nrustacean".to_string(),
+        created_at: 42,
+        reasons: Vec::new(),
+        entry_kind: IndexEntryKind::Profile,
+        profile: Some(IndexProfileView {
+            name: Some("alice".to_string()),
+            display_name: Some("Alice".to_string()),
+            about: None,
+        }),
+        session: None,
+    };
+    let encoded = serde_json::to_value(&entry).unwrap();
+    assert_eq!(encoded["entry_kind"], serde_json::json!("profile"));
+    assert_eq!(
+        encoded["profile"],
+        serde_json::json!({ "name": "alice", "display_name": "Alice" })
+    );
+    let decoded: IndexEntryView = serde_json::from_value(encoded).unwrap();
+    assert_eq!(decoded, entry);
+
+    // post entry は従来の wire shape のまま(entry_kind は省略され、既定で post に戻る)。
+    let post: IndexEntryView = serde_json::from_value(serde_json::json!({
+        "scope_kind": "public_topic",
+        "scope_id": "rust",
+        "object_id": "post-1",
+        "author_pubkey": "author",
+        "text": "body",
+        "created_at": 42
+    }))
+    .unwrap();
+    assert_eq!(post.entry_kind, IndexEntryKind::Post);
+    assert_eq!(post.profile, None);
+    for kind in [
+        IndexEntryKind::Post,
+        IndexEntryKind::Profile,
+        IndexEntryKind::Session,
+    ] {
+        assert_eq!(IndexEntryKind::parse(kind.as_str()).unwrap(), kind);
+    }
+    assert!(IndexEntryKind::parse("room").is_err());
+}
+
+#[test]
+fn session_entries_carry_kind_status_and_participants() {
+    let entry = IndexEntryView {
+        scope_kind: IndexScopeKind::PublicTopic,
+        scope_id: "kukuri:topic:games".to_string(),
+        object_id: "room:room-1".to_string(),
+        author_pubkey: "host".to_string(),
+        text: "Friday league".to_string(),
+        created_at: 42,
+        reasons: Vec::new(),
+        entry_kind: IndexEntryKind::Session,
+        profile: None,
+        session: Some(IndexSessionView {
+            session_kind: IndexSessionKind::GameRoom,
+            session_id: "room-1".to_string(),
+            topic_id: "kukuri:topic:games".to_string(),
+            status: IndexSessionStatus::Running,
+            title: "Friday league".to_string(),
+            description: None,
+            participant_count: Some(3),
+        }),
+    };
+    let encoded = serde_json::to_value(&entry).unwrap();
+    assert_eq!(encoded["entry_kind"], serde_json::json!("session"));
+    assert_eq!(
+        encoded["session"],
+        serde_json::json!({
+            "session_kind": "game_room",
+            "session_id": "room-1",
+            "topic_id": "kukuri:topic:games",
+            "status": "running",
+            "title": "Friday league",
+            "participant_count": 3
+        })
+    );
+    let decoded: IndexEntryView = serde_json::from_value(encoded).unwrap();
+    assert_eq!(decoded, entry);
+}
+
 #[test]
 fn api_error_body_wire_shape_is_stable() {
     let body = ApiErrorBody {
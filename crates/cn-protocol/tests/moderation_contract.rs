@@ -0,0 +1,72 @@
+use kukuri_cn_protocol::{
+    MODERATION_EVENTS_PATH, ModerationEventAudience, ModerationEventsResponse,
+    verify_moderation_event,
+};
+use kukuri_cn_safety::{
+    Basis, ModerationAction, ModerationEventBody, ReasonCode, SafetyCategory, SafetyLabel,
+    Severity, SignedModerationEvent, SubjectKind, Visibility,
+};
+use kukuri_core::{KukuriKeys, generate_keys};
+use sha2::{Digest, Sha256};
+
+fn signed_event(keys: &KukuriKeys, visibility: Visibility) -> SignedModerationEvent {
+    let body = ModerationEventBody {
+        id: "event-1".to_string(),
+        issuer_node_id: keys.public_key_hex(),
+        target_type: SubjectKind::Post,
+        target_id: "post-1".to_string(),
+        action: ModerationAction::RiskLabel,
+        labels: vec![SafetyLabel::new(SafetyCategory::Spam)],
+        reason_code: ReasonCode::GeneralModeration,
+        severity: Severity::Medium,
+        confidence: Some(80),
+        basis: Basis::ClassifierScore,
+        visibility,
+        policy_version: "v1".to_string(),
+        created_at: "2026-10-01T00:00:00Z".to_string(),
+    };
+    let digest: [u8; 32] = Sha256::digest(body.canonical_bytes()).into();
+    let signature = keys.sign_schnorr(&digest).to_string();
+    SignedModerationEvent { body, signature }
+}
+
+#[test]
+fn moderation_events_wire_contract_keeps_audience_and_paging() {
+    let keys = generate_keys();
+    let response = ModerationEventsResponse {
+        audience: ModerationEventAudience::SubscribedNodes,
+        items: vec![signed_event(&keys, Visibility::SubscribedNodes)],
+        next_cursor: Some("1790000000000000:event-1".to_string()),
+    };
+
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(MODERATION_EVENTS_PATH, "/v1/moderation/events");
+    assert_eq!(json["audience"], "subscribed_nodes");
+    assert_eq!(json["items"][0]["body"]["action"], "risk_label");
+    assert_eq!(json["next_cursor"], "1790000000000000:event-1");
+    assert_eq!(
+        serde_json::from_value::<ModerationEventsResponse>(json).unwrap(),
+        response
+    );
+    assert!(ModerationEventAudience::SubscribedNodes.allows(Visibility::SubscribedNodes));
+    assert!(!ModerationEventAudience::Public.allows(Visibility::SubscribedNodes));
+    assert!(!ModerationEventAudience::SubscribedNodes.allows(Visibility::Local));
+}
+
+#[test]
+fn moderation_event_verification_requires_manifest_issuer_and_valid_signature() {
+    let keys = generate_keys();
+    let issuer = keys.public_key_hex();
+    let event = signed_event(&keys, Visibility::Public);
+    verify_moderation_event(&event, issuer.as_str()).expect("verify event");
+
+    let other = generate_keys().public_key_hex();
+    assert!(verify_moderation_event(&event, other.as_str()).is_err());
+
+    let mut tampered = event.clone();
+    tampered.body.target_id = "post-2".to_string();
+    assert!(verify_moderation_event(&tampered, issuer.as_str()).is_err());
+
+    let local = signed_event(&keys, Visibility::Local);
+    assert!(verify_moderation_event(&local, issuer.as_str()).is_err());
+}
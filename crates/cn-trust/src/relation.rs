@@ -113,6 +113,23 @@ pub trait RelationStore: Send + Sync {
     /// viewer 視点の target への近接度（根拠つき）。edge が無ければ None。
     async fn pairwise_proximity(&self, viewer: &str, target: &str) -> Result<Option<Proximity>>;
 
+    /// viewer 視点の複数 target への近接度。edge の無い target は含めない。
+    ///
+    /// 既定は `pairwise_proximity` の繰り返しで、backend は 1 回の問い合わせにまとめて上書きする。
+    async fn pairwise_proximities(
+        &self,
+        viewer: &str,
+        targets: &[String],
+    ) -> Result<BTreeMap<String, Proximity>> {
+        let mut proximities = BTreeMap::new();
+        for target in targets {
+            if let Some(proximity) = self.pairwise_proximity(viewer, target).await? {
+                proximities.insert(target.clone(), proximity);
+            }
+        }
+        Ok(proximities)
+    }
+
     /// discovery / surfacing 用の近接近傍（proximity 降順で最大 k 件）。
     async fn neighbors(&self, viewer: &str, k: usize) -> Result<Vec<String>>;
 
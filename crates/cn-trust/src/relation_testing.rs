@@ -108,6 +108,46 @@ pub async fn assert_symmetric_lookup(store: &dyn RelationStore, prefix: &str) ->
     Ok(())
 }
 
+/// `pairwise_proximities`: 1 件ずつ引いた `pairwise_proximity` と同じ値を返し、edge の無い
+/// target は含めない。
+pub async fn assert_batched_proximity(store: &dyn RelationStore, prefix: &str) -> Result<()> {
+    let v = pk(prefix, "bviewer");
+    let (near, far, stranger) = (
+        pk(prefix, "bnear"),
+        pk(prefix, "bfar"),
+        pk(prefix, "bstranger"),
+    );
+    store
+        .upsert_edge(
+            &near,
+            &v,
+            &EdgeFeatures::new().with(FEATURE_SHARED_TOPICS, 6.0),
+        )
+        .await?;
+    store
+        .upsert_edge(
+            &v,
+            &far,
+            &EdgeFeatures::new().with(FEATURE_SHARED_TOPICS, 1.0),
+        )
+        .await?;
+
+    let batched = store
+        .pairwise_proximities(&v, &[near.clone(), far.clone(), stranger.clone()])
+        .await?;
+    ensure!(
+        !batched.contains_key(&stranger) && batched.len() == 2,
+        "batched proximity must omit targets without an edge: got {batched:?}"
+    );
+    for target in [&near, &far] {
+        ensure!(
+            batched.get(target) == store.pairwise_proximity(&v, target).await?.as_ref(),
+            "batched proximity must match pairwise proximity for {target}"
+        );
+    }
+    Ok(())
+}
+
 /// `neighbors`: proximity 降順で最大 k 件。
 pub async fn assert_neighbors_ranked(store: &dyn RelationStore, prefix: &str) -> Result<()> {
     let v = pk(prefix, "viewer");
@@ -167,6 +207,7 @@ pub async fn assert_relation_store_contracts(
     assert_pairwise_cluster_proximity(store, prefix).await?;
     assert_proximity_is_explainable(store, prefix).await?;
     assert_symmetric_lookup(store, prefix).await?;
+    assert_batched_proximity(store, prefix).await?;
     assert_neighbors_ranked(store, prefix).await?;
     assert_cluster_roundtrip(store, prefix).await?;
     Ok(())
@@ -4,8 +4,8 @@
 //! ArcadeDB 実装（`cn-indexer`）も同じスイートを実行する（backend 間の drift 防止）。
 
 use kukuri_cn_trust::relation_testing::{
-    assert_cluster_roundtrip, assert_neighbors_ranked, assert_pairwise_cluster_proximity,
-    assert_proximity_is_explainable, assert_symmetric_lookup,
+    assert_batched_proximity, assert_cluster_roundtrip, assert_neighbors_ranked,
+    assert_pairwise_cluster_proximity, assert_proximity_is_explainable, assert_symmetric_lookup,
 };
 use kukuri_cn_trust::{
     EdgeFeatures, FEATURE_FOLLOW_PROJECTION, FEATURE_SHARED_TOPICS, MemoryRelationStore,
@@ -34,6 +34,12 @@ async fn relation_lookup_is_symmetric() {
     assert_symmetric_lookup(&store, "sym").await.unwrap();
 }
 
+#[tokio::test]
+async fn relation_batched_lookup_matches_pairwise() {
+    let store = MemoryRelationStore::new();
+    assert_batched_proximity(&store, "batch").await.unwrap();
+}
+
 #[tokio::test]
 async fn relation_neighbors_are_ranked_and_limited() {
     let store = MemoryRelationStore::new();
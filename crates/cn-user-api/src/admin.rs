@@ -1,37 +1,39 @@
 //! IAP TCP forwarding 経由でのみ公開する operator dashboard。
 //!
 //! public user API と route / listener を分離する。browser write は ADR 0029 に従い、
-//! runtime DB 操作だけを preview / CSRF / deployment actor / append-only audit 付きで扱う。
+//! runtime DB 操作だけを preview / CSRF / 運営者ログインと role / append-only audit 付きで扱う。
+//! 運営者ログインと session は `admin_session` が担う。
 
 use anyhow::{Context, bail};
 use axum::extract::State;
-use axum::http::StatusCode;
-use axum::response::{Html, IntoResponse, Response};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Redirect, Response};
 use axum::routing::{get, post};
 use axum::{Form, Router};
 use kukuri_cn_core::{
-    AdminOperation, AdmissionMode, AppealReview, AppealReviewOperation, AppealReviewVersion,
-    OperatorReportStatus, RiskSignalCorrection, RiskSignalMetadataEdit, apply_appeal_review_action,
-    apply_operator_action, get_appeal_review, latest_readiness_activation, list_appeal_reviews,
-    list_community_node_reports, list_operator_actions, list_supported_topics,
+    AdminOperation, AdminOperator, AdmissionMode, AppealReview, AppealReviewOperation,
+    AppealReviewVersion, AuthenticatedOperator, OperatorReportStatus, OperatorRole,
+    RiskSignalCorrection, RiskSignalMetadataEdit, apply_appeal_review_action,
+    apply_operator_action, get_appeal_review, latest_readiness_activation, list_admin_operators,
+    list_appeal_reviews, list_community_node_reports, list_operator_actions, list_supported_topics,
     load_admission_config, parse_bool_env, validate_admin_operation, validate_optional_confidence,
     validate_optional_expires_at,
 };
 use serde::Deserialize;
 use tower_http::trace::TraceLayer;
-use uuid::Uuid;
 
 use crate::admin_action_render::{render_action_error_page, render_action_success, render_preview};
+use crate::admin_api::admin_api_routes;
 use crate::admin_appeal_render::{render_appeal_preview, render_appeal_reviews};
+use crate::admin_session::{
+    current_operator, login_challenge, login_page, login_verify, logout, require_operator,
+    role_denied,
+};
 use crate::admin_shell::render_admin_page;
+use crate::rate_limit::PubkeyRateLimitCounter;
 use crate::state::UserApiState;
 
 pub(crate) fn admin_router(state: UserApiState) -> Router {
-    let actor = std::env::var("COMMUNITY_NODE_ADMIN_ACTOR")
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .map(|value| value.trim().to_string());
-    let csrf_token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
     let operator_review_enabled =
         parse_bool_env("COMMUNITY_NODE_SAFETY_OPERATOR_REVIEW", false).unwrap_or_else(|error| {
             tracing::warn!(error = %format!("{error:#}"), "運営者確認の有効化設定を解釈できないため無効化します");
@@ -39,25 +41,26 @@ pub(crate) fn admin_router(state: UserApiState) -> Router {
         });
     let state = AdminState {
         runtime: state,
-        actor,
-        csrf_token,
         operator_review_enabled,
     };
     Router::new()
         .route("/", get(dashboard))
         .route("/healthz", get(|| async { "ok" }))
+        .route("/login", get(login_page))
+        .route("/login/challenge", post(login_challenge))
+        .route("/login/verify", post(login_verify))
+        .route("/logout", post(logout))
         .route("/actions/preview", post(preview_action))
         .route("/actions/apply", post(apply_action))
+        .merge(admin_api_routes())
         .with_state(state)
         .layer(TraceLayer::new_for_http())
 }
 
 #[derive(Clone)]
-struct AdminState {
-    runtime: UserApiState,
-    actor: Option<String>,
-    csrf_token: String,
-    operator_review_enabled: bool,
+pub(crate) struct AdminState {
+    pub(crate) runtime: UserApiState,
+    pub(crate) operator_review_enabled: bool,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -80,67 +83,68 @@ pub(crate) struct AdminActionForm {
     pub(crate) expires_at: String,
     #[serde(default)]
     pub(crate) visibility: String,
+    #[serde(default)]
+    pub(crate) label: String,
 }
 
-async fn dashboard(State(state): State<AdminState>) -> Html<String> {
-    match load_dashboard(&state).await {
-        Ok(view) => Html(render_dashboard(&view)),
+async fn dashboard(State(state): State<AdminState>, headers: HeaderMap) -> Response {
+    let operator = match current_operator(&state, &headers).await {
+        Ok(Some(operator)) => operator,
+        Ok(None) => return Redirect::to("/login").into_response(),
+        Err(error) => {
+            tracing::error!(error = %format!("{error:#}"), "admin session の確認に失敗しました");
+            return Html(render_error()).into_response();
+        }
+    };
+    match load_dashboard(&state, operator).await {
+        Ok(view) => Html(render_dashboard(&view)).into_response(),
         Err(error) => {
             tracing::error!(error = %format!("{error:#}"), "admin dashboard data load failed");
-            Html(render_error())
+            Html(render_error()).into_response()
         }
     }
 }
 
 async fn preview_action(
     State(state): State<AdminState>,
+    headers: HeaderMap,
     Form(form): Form<AdminActionForm>,
 ) -> Response {
-    let Some(actor) = state.actor.as_deref() else {
-        return render_action_error(
-            StatusCode::SERVICE_UNAVAILABLE,
-            "COMMUNITY_NODE_ADMIN_ACTOR が未設定のため、変更操作は無効です。",
-        );
+    let operator = match require_operator(&state, &headers, form.csrf_token.as_str()).await {
+        Ok(operator) => operator,
+        Err(response) => return response,
     };
-    if !csrf_matches(state.csrf_token.as_str(), form.csrf_token.as_str()) {
-        return render_action_error(
-            StatusCode::FORBIDDEN,
-            "画面の確認情報が無効または期限切れです。画面を読み直してください。",
-        );
-    }
     if form.action.starts_with("appeal.") {
-        return preview_appeal_action(&state, actor, &form).await;
+        return preview_appeal_action(&state, &operator, &form).await;
     }
     match operation_from_form(&form) {
-        Ok(operation) => Html(render_preview(
-            state.csrf_token.as_str(),
-            actor,
-            &form,
-            &operation,
-        ))
-        .into_response(),
+        Ok(operation) => {
+            if let Some(response) = role_denied(&operator, operation.required_role()) {
+                return response;
+            }
+            Html(render_preview(
+                operator.csrf_token.as_str(),
+                operator.operator.pubkey.as_str(),
+                &form,
+                &operation,
+            ))
+            .into_response()
+        }
         Err(error) => render_action_error(StatusCode::BAD_REQUEST, format!("{error:#}").as_str()),
     }
 }
 
 async fn apply_action(
     State(state): State<AdminState>,
+    headers: HeaderMap,
     Form(form): Form<AdminActionForm>,
 ) -> Response {
-    let Some(actor) = state.actor.as_deref() else {
-        return render_action_error(
-            StatusCode::SERVICE_UNAVAILABLE,
-            "COMMUNITY_NODE_ADMIN_ACTOR が未設定のため、変更操作は無効です。",
-        );
+    let operator = match require_operator(&state, &headers, form.csrf_token.as_str()).await {
+        Ok(operator) => operator,
+        Err(response) => return response,
     };
-    if !csrf_matches(state.csrf_token.as_str(), form.csrf_token.as_str()) {
-        return render_action_error(
-            StatusCode::FORBIDDEN,
-            "画面の確認情報が無効または期限切れです。画面を読み直してください。",
-        );
-    }
     if form.action.starts_with("appeal.") {
-        return apply_appeal_action(&state, actor, &form).await;
+        return apply_appeal_action(&state, &operator, &form).await;
     }
     let operation = match operation_from_form(&form) {
         Ok(operation) => operation,
@@ -148,6 +152,11 @@ async fn apply_action(
             return render_action_error(StatusCode::BAD_REQUEST, format!("{error:#}").as_str());
         }
     };
+    // role は確認時だけでなく適用時にも現在値で検査する(確認後の降格を通さない)。
+    if let Some(response) = role_denied(&operator, operation.required_role()) {
+        return response;
+    }
+    let actor = operator.operator.pubkey.as_str();
     match apply_operator_action(&state.runtime.pool, actor, &operation).await {
         Ok(action) => {
             tracing::info!(
@@ -173,32 +182,21 @@ async fn apply_action(
 
 async fn preview_appeal_action(
     state: &AdminState,
-    actor: &str,
+    operator: &AuthenticatedOperator,
     form: &AdminActionForm,
 ) -> Response {
+    if let Some(response) = role_denied(operator, OperatorRole::Moderator) {
+        return response;
+    }
     if !state.operator_review_enabled {
         return render_action_error(
             StatusCode::SERVICE_UNAVAILABLE,
             "このノードでは異議申し立ての運営者確認が無効です。",
         );
     }
-    let review = match get_appeal_review(&state.runtime.pool, form.target_id.trim()).await {
-        Ok(Some(review)) if review.appeal_status == "disputed" => review,
-        Ok(Some(_)) => {
-            return render_action_error(
-                StatusCode::BAD_REQUEST,
-                "この異議申し立てはすでに審査対象ではありません。",
-            );
-        }
-        Ok(None) => {
-            return render_action_error(
-                StatusCode::NOT_FOUND,
-                "審査対象のリスク判定が見つかりません。",
-            );
-        }
-        Err(error) => {
-            return render_action_error(StatusCode::BAD_REQUEST, format!("{error:#}").as_str());
-        }
+    let review = match load_disputed_review(state, form.target_id.trim()).await {
+        Ok(review) => review,
+        Err((status, message)) => return render_action_error(status, message.as_str()),
     };
     // 表示値と適用値の出所を一致させるため、解析済み操作をそのまま描画へ渡す(#701)。
     let operation = match appeal_operation_from_form(form, review.version()) {
@@ -208,15 +206,19 @@ async fn preview_appeal_action(
         }
     };
     Html(render_appeal_preview(
-        state.csrf_token.as_str(),
-        actor,
+        operator.csrf_token.as_str(),
+        operator.operator.pubkey.as_str(),
         &operation,
         &review,
     ))
     .into_response()
 }
 
-async fn apply_appeal_action(state: &AdminState, actor: &str, form: &AdminActionForm) -> Response {
+async fn apply_appeal_action(
+    state: &AdminState,
+    operator: &AuthenticatedOperator,
+    form: &AdminActionForm,
+) -> Response {
     let expected = match serde_json::from_str::<AppealReviewVersion>(&form.expected_state) {
         Ok(expected) => expected,
         Err(_) => {
@@ -232,6 +234,10 @@ async fn apply_appeal_action(state: &AdminState, actor: &str, form: &AdminAction
             return render_action_error(StatusCode::BAD_REQUEST, format!("{error:#}").as_str());
         }
     };
+    if let Some(response) = role_denied(operator, operation.required_role()) {
+        return response;
+    }
+    let actor = operator.operator.pubkey.as_str();
     match apply_appeal_review_action(
         &state.runtime.pool,
         actor,
@@ -249,7 +255,26 @@ async fn apply_appeal_action(state: &AdminState, actor: &str, form: &AdminAction
     }
 }
 
-fn appeal_operation_from_form(
+/// 審査中(disputed)のリスク判定を読む。拒否時は状態コードと表示文言を返す。
+pub(crate) async fn load_disputed_review(
+    state: &AdminState,
+    risk_signal_id: &str,
+) -> Result<AppealReview, (StatusCode, String)> {
+    match get_appeal_review(&state.runtime.pool, risk_signal_id).await {
+        Ok(Some(review)) if review.appeal_status == "disputed" => Ok(review),
+        Ok(Some(_)) => Err((
+            StatusCode::BAD_REQUEST,
+            "この異議申し立てはすでに審査対象ではありません。".to_string(),
+        )),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            "審査対象のリスク判定が見つかりません。".to_string(),
+        )),
+        Err(error) => Err((StatusCode::BAD_REQUEST, format!("{error:#}"))),
+    }
+}
+
+pub(crate) fn appeal_operation_from_form(
     form: &AdminActionForm,
     expected: AppealReviewVersion,
 ) -> anyhow::Result<AppealReviewOperation> {
@@ -330,8 +355,11 @@ struct DashboardView {
     reports: Vec<ReportView>,
     appeals: Vec<AppealReview>,
     audit: Vec<AuditView>,
+    pubkey_rate_limit_enabled: bool,
+    pubkey_rate_limit: Vec<PubkeyRateLimitCounter>,
+    operators: Vec<AdminOperator>,
     gcp_project: Option<String>,
-    actor: Option<String>,
+    operator: AdminOperator,
     csrf_token: String,
     operator_review_enabled: bool,
 }
@@ -356,7 +384,10 @@ struct AuditView {
     change: String,
 }
 
-async fn load_dashboard(state: &AdminState) -> anyhow::Result<DashboardView> {
+async fn load_dashboard(
+    state: &AdminState,
+    operator: AuthenticatedOperator,
+) -> anyhow::Result<DashboardView> {
     let admission = load_admission_config(&state.runtime.pool).await?;
     let readiness = latest_readiness_activation(&state.runtime.pool)
         .await?
@@ -417,31 +448,35 @@ async fn load_dashboard(state: &AdminState) -> anyhow::Result<DashboardView> {
         reports,
         appeals,
         audit,
+        pubkey_rate_limit_enabled: state.runtime.pubkey_rate_limit.is_enabled(),
+        pubkey_rate_limit: state.runtime.pubkey_rate_limit.counters(),
+        operators: list_admin_operators(&state.runtime.pool).await?,
         gcp_project: std::env::var("COMMUNITY_NODE_GCP_PROJECT")
             .ok()
             .filter(|value| !value.trim().is_empty()),
-        actor: state.actor.clone(),
-        csrf_token: state.csrf_token.clone(),
+        operator: operator.operator,
+        csrf_token: operator.csrf_token,
         operator_review_enabled: state.operator_review_enabled,
     })
 }
 
 fn render_dashboard(view: &DashboardView) -> String {
-    let write_enabled = view.actor.is_some();
-    let admission_control = view.actor.as_ref().map_or_else(
-        || "<p class=\"boundary\">現在は参照専用です。変更するには、確認済みの配備手順で <code>COMMUNITY_NODE_ADMIN_ACTOR</code> を設定してください。</p>".to_string(),
-        |actor| {
-            format!(
-                r#"<form method="post" action="/actions/preview" class="inline-form">
+    let role = view.operator.role;
+    let write_enabled = role.allows(OperatorRole::Admin);
+    let report_write_enabled = role.allows(OperatorRole::Moderator);
+    let admission_control = if write_enabled {
+        format!(
+            r#"<form method="post" action="/actions/preview" class="inline-form">
 <input type="hidden" name="csrf_token" value="{}"><input type="hidden" name="action" value="admission.set_mode">
 <label>変更後の受け入れ方式 <select name="value">{}</select></label><button type="submit">変更内容を確認</button>
 </form><p class="meta">操作記録に残る運営者: <code>{}</code></p>"#,
-                escape_html(&view.csrf_token),
-                admission_options(&view.admission_mode),
-                escape_html(actor),
-            )
-        },
-    );
+            escape_html(&view.csrf_token),
+            admission_options(&view.admission_mode),
+            escape_html(&view.operator.pubkey),
+        )
+    } else {
+        "<p class=\"boundary\">受け入れ方式の変更には admin 権限が必要です。</p>".to_string()
+    };
     let topics = if view.topics.is_empty() {
         table_empty("対応トピックは設定されていません。", 4)
     } else {
@@ -481,7 +516,7 @@ fn render_dashboard(view: &DashboardView) -> String {
         view.reports
             .iter()
             .map(|report| {
-                let action = if write_enabled {
+                let action = if report_write_enabled {
                     format!(
                         r#"<form method="post" action="/actions/preview" class="compact-form"><input type="hidden" name="csrf_token" value="{}"><input type="hidden" name="action" value="report.set_status"><input type="hidden" name="target_id" value="{}"><select aria-label="変更後の状態" name="value">{}</select><button class="secondary" type="submit">変更内容を確認</button></form>"#,
                         escape_html(&view.csrf_token),
@@ -506,7 +541,7 @@ fn render_dashboard(view: &DashboardView) -> String {
     };
     let appeals = render_appeal_reviews(
         &view.appeals,
-        view.actor.is_some() && view.operator_review_enabled,
+        report_write_enabled && view.operator_review_enabled,
         &view.csrf_token,
     );
     let audit = if view.audit.is_empty() {
@@ -527,6 +562,28 @@ fn render_dashboard(view: &DashboardView) -> String {
             })
             .collect()
     };
+    let pubkey_rate_limit = if view.pubkey_rate_limit_enabled {
+        let rows: String = view
+            .pubkey_rate_limit
+            .iter()
+            .map(|counter| {
+                format!(
+                    "<tr><td>{}</td><td><code>{}</code></td><td>{} 件 / 分(最大 {} 件)</td><td>{}</td></tr>",
+                    escape_html(counter.route.label()),
+                    escape_html(counter.route.as_str()),
+                    counter.budget.per_minute,
+                    counter.budget.burst,
+                    counter.rejected,
+                )
+            })
+            .collect();
+        format!(
+            "<p>認証済みの鍵ごとに数えた上限超過の件数です(起動時からの累計)。</p><table><thead><tr><th>対象</th><th>区分</th><th>上限</th><th>拒否数</th></tr></thead><tbody>{rows}</tbody></table>"
+        )
+    } else {
+        "<p>鍵ごとの利用制限は無効です。有効にするには配備設定で <code>COMMUNITY_NODE_PUBKEY_RATE_LIMIT_ENABLED</code> を設定してください。</p>".to_string()
+    };
+    let operators = render_operators(view, write_enabled);
     let logs_link = view.gcp_project.as_ref().map_or_else(
         || "<p><code>COMMUNITY_NODE_GCP_PROJECT</code> を設定するとログへのリンクを表示します。</p>".to_string(),
         |project| format!(
@@ -537,26 +594,72 @@ fn render_dashboard(view: &DashboardView) -> String {
 
     let main = format!(
         r#"<section><h2>稼働状態と受け入れ方式</h2><div class="metrics"><div class="metric"><strong>利用者向け接続先</strong><br>稼働中</div><div class="metric"><strong>受け入れ方式</strong><br>{}</div><div class="metric"><strong>直近の準備確認</strong><br>{}</div></div>{}</section>
+<section><h2>鍵ごとの利用制限</h2>{}</section>
 <section><h2>対応トピック</h2><p>この画面で変更できるのは公開トピックだけです。非公開チャンネルの権限は変更できません。</p>{}<table><thead><tr><th>種類</th><th>識別子</th><th>作成時刻</th><th>操作</th></tr></thead><tbody>{}</tbody></table></section>
 <section><h2>最近の通報</h2><p>新しい順に 50 件を表示します。補足説明と連絡先は、この一覧と操作記録には表示しません。</p><table><thead><tr><th>受信時刻</th><th>識別子</th><th>対象</th><th>機能</th><th>理由</th><th>状態</th><th>操作</th></tr></thead><tbody>{}</tbody></table></section>
 <section><h2>異議申し立ての審査</h2>{}</section>
+<section><h2>運営者</h2>{}</section>
 <section><h2>運営操作の記録</h2><p>画面から行った直近 50 件の操作です。データベースで追記専用に保護されています。</p><table><thead><tr><th>実行時刻</th><th>識別子</th><th>運営者</th><th>操作</th><th>対象</th><th>変更</th></tr></thead><tbody>{}</tbody></table></section>
 <section><h2>運用ログ</h2><p>実行時ログは既存のログ基盤に残します。この画面が秘密情報や生ログを複製することはありません。</p>{}<p><code>sudo journalctl -u kukuri-readiness.service -n 100 --no-pager</code></p></section>
 <section><h2>配備との責任境界</h2><p class="boundary">プロバイダーや言語モデルの認証情報、機能の有効化、配備版、非公開チャンネルの秘密、招待符号、許可一覧、禁止一覧はこの画面から変更できません。確認済みの <code>operator-config.yaml</code>、<code>Terraform</code>、秘密管理、<code>cn-cli readiness</code> を使ってください。画面からの変更は、内容確認と明示的な確定を必須にします。</p></section>"#,
         escape_html(&view.admission_mode),
         escape_html(&view.readiness),
         admission_control,
+        pubkey_rate_limit,
         add_topic,
         topics,
         reports,
         appeals,
+        operators,
         audit,
         logs_link,
     );
-    render_admin_page(
-        "kukuri コミュニティノード運営",
-        "<h1>コミュニティノード運営</h1><p>運営者専用の画面です。接続は <code>GCP IAP</code> の転送と権限管理で制限されます。</p>",
-        &main,
+    let header = format!(
+        r#"<h1>コミュニティノード運営</h1><p>運営者専用の画面です。接続は <code>GCP IAP</code> の転送と権限管理で制限されます。</p><form method="post" action="/logout" class="inline-form"><span class="meta">ログイン中の運営者: <code>{}</code>({})</span><input type="hidden" name="csrf_token" value="{}"><button class="secondary" type="submit">ログアウト</button></form>"#,
+        escape_html(&view.operator.pubkey),
+        view.operator.role.as_str(),
+        escape_html(&view.csrf_token),
+    );
+    render_admin_page("kukuri コミュニティノード運営", &header, &main)
+}
+
+fn render_operators(view: &DashboardView, write_enabled: bool) -> String {
+    let rows: String = view
+        .operators
+        .iter()
+        .map(|operator| {
+            let action = if write_enabled {
+                format!(
+                    r#"<form method="post" action="/actions/preview" class="compact-form"><input type="hidden" name="csrf_token" value="{}"><input type="hidden" name="action" value="operator.set_role"><input type="hidden" name="target_id" value="{}"><select aria-label="変更後の権限" name="value">{}{}</select><button class="secondary" type="submit">変更内容を確認</button></form>"#,
+                    escape_html(&view.csrf_token),
+                    escape_html(&operator.pubkey),
+                    operator_role_options(operator.role.as_str()),
+                    option("remove", "削除", false),
+                )
+            } else {
+                "<span class=\"meta\">参照専用</span>".to_string()
+            };
+            format!(
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(&operator.pubkey),
+                escape_html(operator.label.as_deref().unwrap_or("-")),
+                operator.role.as_str(),
+                escape_html(&operator.updated_at.to_rfc3339()),
+                action,
+            )
+        })
+        .collect();
+    let add_operator = if write_enabled {
+        format!(
+            r#"<form method="post" action="/actions/preview" class="inline-form"><input type="hidden" name="csrf_token" value="{}"><input type="hidden" name="action" value="operator.set_role"><label>公開鍵 <input name="target_id" required placeholder="npub1… または hex"></label><label>権限 <select name="value">{}</select></label><label>表示名 <input name="label" maxlength="128"></label><button type="submit">追加内容を確認</button></form>"#,
+            escape_html(&view.csrf_token),
+            operator_role_options("viewer"),
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        "<p>viewer は参照だけ、moderator は通報と異議申し立ての審査、admin は全操作と運営者の管理ができます。</p>{add_operator}<table><thead><tr><th>公開鍵</th><th>表示名</th><th>権限</th><th>更新時刻</th><th>操作</th></tr></thead><tbody>{rows}</tbody></table>"
     )
 }
 
@@ -568,12 +671,19 @@ fn table_empty(message: &str, colspan: usize) -> String {
 }
 
 fn admission_options(current: &str) -> String {
-    ["open", "invite", "whitelist"]
+    ["open", "invite", "whitelist", "vouch"]
         .into_iter()
         .map(|value| option(value, value, value == current))
         .collect()
 }
 
+fn operator_role_options(current: &str) -> String {
+    OperatorRole::ALL
+        .into_iter()
+        .map(|role| option(role.as_str(), role.as_str(), role.as_str() == current))
+        .collect()
+}
+
 fn report_status_options(current: &str) -> String {
     ["received", "reviewing", "actioned", "dismissed"]
         .into_iter()
@@ -590,13 +700,14 @@ pub(crate) fn option(value: &str, label: &str, selected: bool) -> String {
     )
 }
 
-fn operation_from_form(form: &AdminActionForm) -> anyhow::Result<AdminOperation> {
+pub(crate) fn operation_from_form(form: &AdminActionForm) -> anyhow::Result<AdminOperation> {
     let operation = match form.action.as_str() {
         "admission.set_mode" => {
             let mode = match form.value.trim() {
                 "open" => AdmissionMode::Open,
                 "invite" => AdmissionMode::Invite,
                 "whitelist" => AdmissionMode::Whitelist,
+                "vouch" => AdmissionMode::Vouch,
                 _ => bail!("unsupported admission mode"),
             };
             AdminOperation::SetAdmissionMode { mode }
@@ -620,13 +731,21 @@ fn operation_from_form(form: &AdminActionForm) -> anyhow::Result<AdminOperation>
                 status,
             }
         }
+        "operator.set_role" => AdminOperation::SetOperatorRole {
+            pubkey: form.target_id.trim().to_string(),
+            role: match form.value.trim() {
+                "remove" => None,
+                value => Some(OperatorRole::parse(value)?),
+            },
+            label: optional_text(&form.label),
+        },
         _ => bail!("unsupported admin operation"),
     };
     validate_admin_operation(&operation)?;
     Ok(operation)
 }
 
-fn csrf_matches(expected: &str, supplied: &str) -> bool {
+pub(crate) fn csrf_matches(expected: &str, supplied: &str) -> bool {
     if expected.len() != supplied.len() || expected.is_empty() {
         return false;
     }
@@ -639,7 +758,7 @@ fn csrf_matches(expected: &str, supplied: &str) -> bool {
         == 0
 }
 
-fn render_action_error(status: StatusCode, message: &str) -> Response {
+pub(crate) fn render_action_error(status: StatusCode, message: &str) -> Response {
     (status, Html(render_action_error_page(message))).into_response()
 }
 
@@ -659,6 +778,7 @@ pub(crate) fn escape_html(value: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rate_limit::PubkeyRateLimiter;
 
     #[test]
     fn dashboard_escapes_database_and_environment_values() {
@@ -705,8 +825,11 @@ mod tests {
                 target: "report/report-1".to_string(),
                 change: "received -> reviewing".to_string(),
             }],
+            pubkey_rate_limit_enabled: true,
+            pubkey_rate_limit: PubkeyRateLimiter::disabled().counters(),
+            operators: vec![sample_operator("<b>ops</b>", OperatorRole::Admin)],
             gcp_project: Some("project-id".to_string()),
-            actor: Some("ops@example.com".to_string()),
+            operator: sample_operator("ops", OperatorRole::Admin),
             csrf_token: "csrf-token".to_string(),
             operator_review_enabled: true,
         });
@@ -717,6 +840,98 @@ mod tests {
         assert!(html.contains("&quot;quoted&quot;"));
         assert!(!html.contains("<img src=x onerror=alert(1)>"));
         assert!(html.contains("&lt;img src=x onerror=alert(1)&gt;"));
+        assert!(html.contains("<code>rendezvous_heartbeat</code>"));
+        assert!(!html.contains("<b>ops</b>"));
+        assert!(html.contains("&lt;b&gt;ops&lt;/b&gt;"));
+    }
+
+    fn sample_operator(label: &str, role: OperatorRole) -> AdminOperator {
+        AdminOperator {
+            pubkey: "a".repeat(64),
+            role,
+            label: Some(label.to_string()),
+            created_at: "2026-10-17T00:00:00Z".parse().expect("timestamp"),
+            updated_at: "2026-10-17T00:00:00Z".parse().expect("timestamp"),
+        }
+    }
+
+    fn dashboard_for(role: OperatorRole) -> String {
+        render_dashboard(&DashboardView {
+            admission_mode: "open".to_string(),
+            readiness: "active".to_string(),
+            topics: vec![(
+                "public_topic".to_string(),
+                "demo".to_string(),
+                "now".to_string(),
+            )],
+            reports: vec![ReportView {
+                created_at: "now".to_string(),
+                id: "report-1".to_string(),
+                subject: "event/1".to_string(),
+                capability: "report_endpoint".to_string(),
+                reason: "spam".to_string(),
+                status: "received".to_string(),
+            }],
+            appeals: Vec::new(),
+            audit: Vec::new(),
+            pubkey_rate_limit_enabled: false,
+            pubkey_rate_limit: Vec::new(),
+            operators: vec![sample_operator("ops", role)],
+            gcp_project: None,
+            operator: sample_operator("ops", role),
+            csrf_token: "csrf-token".to_string(),
+            operator_review_enabled: true,
+        })
+    }
+
+    #[test]
+    fn dashboard_controls_follow_operator_role() {
+        let viewer = dashboard_for(OperatorRole::Viewer);
+        assert!(!viewer.contains("value=\"admission.set_mode\""));
+        assert!(!viewer.contains("value=\"report.set_status\""));
+        assert!(!viewer.contains("value=\"operator.set_role\""));
+        assert!(viewer.contains("action=\"/logout\""));
+
+        let moderator = dashboard_for(OperatorRole::Moderator);
+        assert!(!moderator.contains("value=\"admission.set_mode\""));
+        assert!(moderator.contains("value=\"report.set_status\""));
+        assert!(!moderator.contains("value=\"operator.set_role\""));
+
+        let admin = dashboard_for(OperatorRole::Admin);
+        assert!(admin.contains("value=\"admission.set_mode\""));
+        assert!(admin.contains("value=\"supported_topic.remove\""));
+        assert!(admin.contains("value=\"operator.set_role\""));
+    }
+
+    #[test]
+    fn operator_form_maps_roles_and_removal() {
+        let mut form = AdminActionForm {
+            action: "operator.set_role".to_string(),
+            target_id: "b".repeat(64),
+            value: "moderator".to_string(),
+            label: " 夜間当番 ".to_string(),
+            ..AdminActionForm::default()
+        };
+        assert_eq!(
+            operation_from_form(&form).unwrap(),
+            AdminOperation::SetOperatorRole {
+                pubkey: "b".repeat(64),
+                role: Some(OperatorRole::Moderator),
+                label: Some("夜間当番".to_string()),
+            }
+        );
+        form.value = "remove".to_string();
+        form.label = String::new();
+        assert!(matches!(
+            operation_from_form(&form).unwrap(),
+            AdminOperation::SetOperatorRole {
+                role: None,
+                label: None,
+                ..
+            }
+        ));
+        form.value = "owner".to_string();
+        assert!(operation_from_form(&form).is_err());
     }
 
     #[test]
@@ -4,7 +4,7 @@
 //! 隠し入力と `preview -> confirm -> apply` の契約(ADR 0029)は `admin.rs` のハンドラが担い、
 //! ここでは表示だけを扱う。preview の「変更前」は DB から読まず、apply 時の再取得に委ねる。
 
-use kukuri_cn_core::{AdminOperation, OperatorAction};
+use kukuri_cn_core::{AdminOperation, OperatorAction, SanctionKind};
 
 use crate::admin::{AdminActionForm, escape_html};
 use crate::admin_shell::{
@@ -22,7 +22,7 @@ pub(crate) fn render_preview(
     let (title, target, after, impact) = operation_summary(operation);
     let header = action_header("適用前の確認", title);
     let main = format!(
-        r#"<section class="dialog">{}<p class="boundary">適用時に現在値と入力をもう一度検証します。</p><form method="post" action="/actions/apply"><input type="hidden" name="csrf_token" value="{}"><input type="hidden" name="action" value="{}"><input type="hidden" name="target_id" value="{}"><input type="hidden" name="value" value="{}"><div class="actions"><button class="primary" type="submit">確認して適用</button>{}</div></form></section>"#,
+        r#"<section class="dialog">{}<p class="boundary">適用時に現在値と入力をもう一度検証します。</p><form method="post" action="/actions/apply"><input type="hidden" name="csrf_token" value="{}"><input type="hidden" name="action" value="{}"><input type="hidden" name="target_id" value="{}"><input type="hidden" name="value" value="{}"><input type="hidden" name="label" value="{}"><div class="actions"><button class="primary" type="submit">確認して適用</button>{}</div></form></section>"#,
         facts(&[
             code_fact("運営者", actor),
             code_fact("対象", &target),
@@ -35,13 +35,16 @@ pub(crate) fn render_preview(
         escape_html(&form.action),
         escape_html(&form.target_id),
         escape_html(&form.value),
+        escape_html(&form.label),
         dashboard_link("取り消す", false),
     );
     render_admin_page("運営操作の確認", &header, &main)
 }
 
-/// 見出し・対象・変更後の値・影響。
-fn operation_summary(operation: &AdminOperation) -> (&'static str, String, String, String) {
+/// 見出し・対象・変更後の値・影響。HTML の確認画面と JSON admin API の preview が共有する。
+pub(crate) fn operation_summary(
+    operation: &AdminOperation,
+) -> (&'static str, String, String, String) {
     match operation {
         AdminOperation::SetAdmissionMode { mode } => (
             "受け入れ方式を変更しますか",
@@ -75,6 +78,55 @@ fn operation_summary(operation: &AdminOperation) -> (&'static str, String, Strin
                 status.as_str()
             ),
         ),
+        AdminOperation::SetOperatorRole { pubkey, role, .. } => match role {
+            Some(role) => (
+                "運営者の権限を設定しますか",
+                pubkey.clone(),
+                role.as_str().to_string(),
+                format!(
+                    "この鍵を {} 権限の運営者にします。既存の session にも次の操作から反映されます。",
+                    role.as_str()
+                ),
+            ),
+            None => (
+                "運営者を削除しますか",
+                pubkey.clone(),
+                "運営者から削除".to_string(),
+                "この鍵の運営者登録と発行済みの session を削除します。最後の admin は削除できません。"
+                    .to_string(),
+            ),
+        },
+        AdminOperation::ImposeSanction {
+            pubkey,
+            kind,
+            expires_at,
+            ..
+        } => (
+            "利用者を制限しますか",
+            pubkey.clone(),
+            format!(
+                "{} · {}",
+                kind.as_str(),
+                expires_at
+                    .map(|value| format!("{} まで", value.to_rfc3339()))
+                    .unwrap_or_else(|| "解除まで".to_string())
+            ),
+            match kind {
+                SanctionKind::Suspended => {
+                    "この鍵の認証と発行済み token を止めます。期限が来るか解除すると元に戻ります。"
+                }
+                SanctionKind::ReadOnly => {
+                    "認証と参照は維持し、通報・索引要求・rendezvous heartbeat を止めます。"
+                }
+            }
+            .to_string(),
+        ),
+        AdminOperation::LiftSanction { sanction_id } => (
+            "制限を解除しますか",
+            sanction_id.clone(),
+            "解除".to_string(),
+            "この制限を期限前に解除します。記録と根拠の通報は操作記録に残ります。".to_string(),
+        ),
     }
 }
 
@@ -0,0 +1,651 @@
+//! 運営者向け JSON admin API(`/api/v1`)。
+//!
+//! HTML 運営画面と同じ検証・role・preview -> apply の意味論を、運営ツールから扱える JSON で
+//! 提供する。認証は admin session token の `Authorization: Bearer` で、cookie を使わないため
+//! CSRF token は要求しない。通信契約は `kukuri_cn_protocol::admin` が正本。
+
+use axum::extract::{Query, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use kukuri_cn_core::{
+    AdminOperation, AdminOperator, ApiError, ApiResult, AppealReview, AppealReviewOperation,
+    AppealReviewVersion, AuthenticatedOperator, OperatorAction, OperatorRole,
+    apply_appeal_review_action, apply_operator_action, auth_required_error, create_auth_challenge,
+    list_admin_operators, list_appeal_reviews, list_community_node_reports, list_operator_actions,
+    load_admin_session, revoke_admin_session, verify_admin_login,
+};
+use kukuri_cn_protocol::{
+    ADMIN_ACTION_INVALID_CODE, ADMIN_ACTION_REJECTED_CODE, ADMIN_API_APPEALS_PATH,
+    ADMIN_API_APPLY_PATH, ADMIN_API_LOGIN_CHALLENGE_PATH, ADMIN_API_LOGIN_VERIFY_PATH,
+    ADMIN_API_LOGOUT_PATH, ADMIN_API_OPERATOR_ACTIONS_PATH, ADMIN_API_OPERATORS_PATH,
+    ADMIN_API_PREVIEW_PATH, ADMIN_API_REPORTS_PATH, ADMIN_API_SESSION_PATH,
+    ADMIN_OPERATOR_REVIEW_DISABLED_CODE, ADMIN_ROLE_REQUIRED_CODE, ADMIN_TARGET_NOT_FOUND_CODE,
+    AdminActionApplyRequest, AdminActionPreviewResponse, AdminActionRequest, AdminAppealReport,
+    AdminAppealReview, AdminAppealReviewsResponse, AdminListQuery, AdminLoginRequest,
+    AdminLoginResponse, AdminOperatorActionRecord, AdminOperatorActionsResponse, AdminOperatorInfo,
+    AdminOperatorsResponse, AdminReportSummary, AdminReportsResponse, AdminSessionResponse,
+    AuthChallengeRequest, AuthChallengeResponse,
+};
+
+use crate::admin::{
+    AdminActionForm, AdminState, appeal_operation_from_form, load_disputed_review,
+    operation_from_form,
+};
+use crate::admin_action_render::operation_summary;
+use crate::admin_appeal_render::{appeal_operation_summary, enum_text};
+use crate::errors::internal_error;
+
+const DEFAULT_LIST_LIMIT: i64 = 50;
+const MAX_LIST_LIMIT: i64 = 200;
+
+pub(crate) fn admin_api_routes() -> Router<AdminState> {
+    Router::new()
+        .route(ADMIN_API_LOGIN_CHALLENGE_PATH, post(login_challenge))
+        .route(ADMIN_API_LOGIN_VERIFY_PATH, post(login_verify))
+        .route(ADMIN_API_LOGOUT_PATH, post(logout))
+        .route(ADMIN_API_SESSION_PATH, get(session))
+        .route(ADMIN_API_OPERATORS_PATH, get(operators))
+        .route(ADMIN_API_REPORTS_PATH, get(reports))
+        .route(ADMIN_API_APPEALS_PATH, get(appeals))
+        .route(ADMIN_API_OPERATOR_ACTIONS_PATH, get(operator_actions))
+        .route(ADMIN_API_PREVIEW_PATH, post(preview))
+        .route(ADMIN_API_APPLY_PATH, post(apply))
+}
+
+async fn login_challenge(
+    State(state): State<AdminState>,
+    Json(request): Json<AuthChallengeRequest>,
+) -> ApiResult<Json<AuthChallengeResponse>> {
+    create_auth_challenge(&state.runtime.pool, request.pubkey.as_str())
+        .await
+        .map(Json)
+        .map_err(|error| {
+            ApiError::new(
+                StatusCode::BAD_REQUEST,
+                ADMIN_ACTION_INVALID_CODE,
+                format!("{error:#}"),
+            )
+        })
+}
+
+async fn login_verify(
+    State(state): State<AdminState>,
+    Json(request): Json<AdminLoginRequest>,
+) -> ApiResult<Json<AdminLoginResponse>> {
+    let session = verify_admin_login(
+        &state.runtime.pool,
+        state
+            .runtime
+            .self_node
+            .resolved_urls
+            .public_base_url
+            .as_str(),
+        &request.login_envelope_json,
+    )
+    .await
+    .map_err(|error| {
+        tracing::warn!(error = %format!("{error:#}"), "運営者ログインを拒否しました");
+        ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "AUTH_FAILED",
+            format!("{error:#}"),
+        )
+    })?;
+    tracing::info!(
+        operator = %session.operator.pubkey,
+        role = session.operator.role.as_str(),
+        "運営者が admin API にログインしました"
+    );
+    Ok(Json(AdminLoginResponse {
+        token: session.token,
+        expires_at: session.expires_at.timestamp(),
+        operator: operator_info(&session.operator),
+    }))
+}
+
+async fn logout(State(state): State<AdminState>, headers: HeaderMap) -> ApiResult<StatusCode> {
+    let token = bearer_token(&headers)?;
+    require_api_operator(&state, &headers).await?;
+    revoke_admin_session(&state.runtime.pool, token)
+        .await
+        .map_err(internal_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn session(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> ApiResult<Json<AdminSessionResponse>> {
+    let operator = require_api_operator(&state, &headers).await?;
+    Ok(Json(AdminSessionResponse {
+        expires_at: operator.expires_at.timestamp(),
+        operator: operator_info(&operator.operator),
+    }))
+}
+
+async fn operators(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> ApiResult<Json<AdminOperatorsResponse>> {
+    require_api_operator(&state, &headers).await?;
+    let items = list_admin_operators(&state.runtime.pool)
+        .await
+        .map_err(internal_error)?
+        .iter()
+        .map(operator_info)
+        .collect();
+    Ok(Json(AdminOperatorsResponse { items }))
+}
+
+async fn reports(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Query(query): Query<AdminListQuery>,
+) -> ApiResult<Json<AdminReportsResponse>> {
+    require_api_operator(&state, &headers).await?;
+    let (limit, offset) = list_window(&query);
+    let items = list_community_node_reports(&state.runtime.pool, limit, offset)
+        .await
+        .map_err(internal_error)?
+        .into_iter()
+        .map(|report| AdminReportSummary {
+            id: report.id,
+            created_at: report.created_at.to_rfc3339(),
+            subject_kind: report.subject_kind,
+            subject_id: report.subject_id,
+            capability: report.capability,
+            reason: report.reason,
+            status: report.status,
+        })
+        .collect();
+    Ok(Json(AdminReportsResponse { items }))
+}
+
+async fn appeals(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Query(query): Query<AdminListQuery>,
+) -> ApiResult<Json<AdminAppealReviewsResponse>> {
+    require_api_operator(&state, &headers).await?;
+    let (limit, offset) = list_window(&query);
+    let items = list_appeal_reviews(&state.runtime.pool, limit, offset)
+        .await
+        .map_err(internal_error)?
+        .into_iter()
+        .map(appeal_review_info)
+        .collect();
+    Ok(Json(AdminAppealReviewsResponse { items }))
+}
+
+async fn operator_actions(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Query(query): Query<AdminListQuery>,
+) -> ApiResult<Json<AdminOperatorActionsResponse>> {
+    require_api_operator(&state, &headers).await?;
+    let (limit, offset) = list_window(&query);
+    let items = list_operator_actions(&state.runtime.pool, limit, offset)
+        .await
+        .map_err(internal_error)?
+        .into_iter()
+        .map(operator_action_record)
+        .collect();
+    Ok(Json(AdminOperatorActionsResponse { items }))
+}
+
+async fn preview(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Json(request): Json<AdminActionRequest>,
+) -> ApiResult<Json<AdminActionPreviewResponse>> {
+    let operator = require_api_operator(&state, &headers).await?;
+    let form = form_from_request(&request, None);
+    let actor = operator.operator.pubkey.clone();
+    if is_appeal_action(&request) {
+        let review = load_api_review(&state, &operator, form.target_id.as_str()).await?;
+        let expected = review.version();
+        let expected_state = serde_json::to_string(&expected).map_err(internal_error)?;
+        let operation = appeal_operation_from_form(&form, expected).map_err(invalid_action)?;
+        let (action, _, impact) = appeal_operation_summary(&operation);
+        return Ok(Json(AdminActionPreviewResponse {
+            action: action.to_string(),
+            required_role: operation.required_role().as_str().to_string(),
+            actor,
+            target: review.risk_signal_id,
+            after: appeal_after(&operation),
+            impact: impact.to_string(),
+            expected_state: Some(expected_state),
+        }));
+    }
+    let operation = operation_from_form(&form).map_err(invalid_action)?;
+    require_role(&operator, operation.required_role())?;
+    let (_, target, after, impact) = operation_summary(&operation);
+    Ok(Json(AdminActionPreviewResponse {
+        action: form.action,
+        required_role: operation.required_role().as_str().to_string(),
+        actor,
+        target,
+        after,
+        impact,
+        expected_state: None,
+    }))
+}
+
+async fn apply(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Json(request): Json<AdminActionApplyRequest>,
+) -> ApiResult<Json<AdminOperatorActionRecord>> {
+    let operator = require_api_operator(&state, &headers).await?;
+    let form = form_from_request(&request.action, request.expected_state.as_deref());
+    let actor = operator.operator.pubkey.as_str();
+    let result = if is_appeal_action(&request.action) {
+        let operation = appeal_operation_for_apply(&form)?;
+        // role と有効化は確認時だけでなく適用時にも現在値で検査する。
+        require_role(&operator, operation.required_role())?;
+        require_operator_review(&state)?;
+        apply_appeal_review_action(
+            &state.runtime.pool,
+            actor,
+            form.target_id.trim(),
+            &operation,
+            state.operator_review_enabled,
+        )
+        .await
+    } else {
+        let operation: AdminOperation = operation_from_form(&form).map_err(invalid_action)?;
+        require_role(&operator, operation.required_role())?;
+        apply_operator_action(&state.runtime.pool, actor, &operation).await
+    };
+    match result {
+        Ok(action) => {
+            tracing::info!(
+                action_id = %action.id,
+                actor = %action.actor,
+                action = %action.action,
+                target_kind = %action.target_kind,
+                target_id = %action.target_id,
+                "admin API operation applied"
+            );
+            Ok(Json(operator_action_record(action)))
+        }
+        Err(error) => Err(apply_error(actor, error)),
+    }
+}
+
+/// admin session token を `Authorization: Bearer` から取り出す。
+fn bearer_token(headers: &HeaderMap) -> ApiResult<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| auth_required_error("missing admin session token"))?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| auth_required_error("invalid admin session token"))
+}
+
+async fn require_api_operator(
+    state: &AdminState,
+    headers: &HeaderMap,
+) -> ApiResult<AuthenticatedOperator> {
+    let token = bearer_token(headers)?;
+    load_admin_session(&state.runtime.pool, token)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| auth_required_error("admin session is invalid or expired"))
+}
+
+fn require_role(operator: &AuthenticatedOperator, required: OperatorRole) -> ApiResult<()> {
+    if operator.operator.role.allows(required) {
+        return Ok(());
+    }
+    Err(ApiError::new(
+        StatusCode::FORBIDDEN,
+        ADMIN_ROLE_REQUIRED_CODE,
+        format!(
+            "this operation requires the {} role (current: {})",
+            required.as_str(),
+            operator.operator.role.as_str()
+        ),
+    ))
+}
+
+fn require_operator_review(state: &AdminState) -> ApiResult<()> {
+    if state.operator_review_enabled {
+        return Ok(());
+    }
+    Err(ApiError::new(
+        StatusCode::SERVICE_UNAVAILABLE,
+        ADMIN_OPERATOR_REVIEW_DISABLED_CODE,
+        "operator review of appeals is disabled on this node",
+    ))
+}
+
+/// 審査対象のリスク判定を読む。role と有効化を先に検査し、対象の存否を漏らさない。
+async fn load_api_review(
+    state: &AdminState,
+    operator: &AuthenticatedOperator,
+    risk_signal_id: &str,
+) -> ApiResult<AppealReview> {
+    require_role(operator, OperatorRole::Moderator)?;
+    require_operator_review(state)?;
+    load_disputed_review(state, risk_signal_id.trim())
+        .await
+        .map_err(|(status, message)| match status {
+            StatusCode::NOT_FOUND => {
+                ApiError::new(StatusCode::NOT_FOUND, ADMIN_TARGET_NOT_FOUND_CODE, message)
+            }
+            _ => ApiError::new(StatusCode::CONFLICT, ADMIN_ACTION_REJECTED_CODE, message),
+        })
+}
+
+fn appeal_operation_for_apply(form: &AdminActionForm) -> ApiResult<AppealReviewOperation> {
+    let expected =
+        serde_json::from_str::<AppealReviewVersion>(&form.expected_state).map_err(|_| {
+            ApiError::new(
+                StatusCode::BAD_REQUEST,
+                ADMIN_ACTION_INVALID_CODE,
+                "expected_state from preview is required for appeal review operations",
+            )
+        })?;
+    appeal_operation_from_form(form, expected).map_err(invalid_action)
+}
+
+fn invalid_action(error: anyhow::Error) -> ApiError {
+    ApiError::new(
+        StatusCode::BAD_REQUEST,
+        ADMIN_ACTION_INVALID_CODE,
+        format!("{error:#}"),
+    )
+}
+
+/// apply 失敗の写像。DB 障害だけを 500 にし、それ以外は現在の状態による拒否(409)とする。
+fn apply_error(actor: &str, error: anyhow::Error) -> ApiError {
+    if error.downcast_ref::<sqlx::Error>().is_some() {
+        tracing::error!(actor, error = %format!("{error:#}"), "admin API operation failed");
+        return internal_error(format!("{error:#}"));
+    }
+    tracing::warn!(actor, error = %format!("{error:#}"), "admin API operation rejected");
+    ApiError::new(
+        StatusCode::CONFLICT,
+        ADMIN_ACTION_REJECTED_CODE,
+        format!("{error:#}"),
+    )
+}
+
+fn is_appeal_action(request: &AdminActionRequest) -> bool {
+    matches!(
+        request,
+        AdminActionRequest::AppealAccept { .. }
+            | AdminActionRequest::AppealReject { .. }
+            | AdminActionRequest::AppealEdit { .. }
+            | AdminActionRequest::AppealReissue { .. }
+    )
+}
+
+/// JSON 要求を HTML form と同じ形へ写し、検証(`operation_from_form` など)を共有する。
+fn form_from_request(
+    request: &AdminActionRequest,
+    expected_state: Option<&str>,
+) -> AdminActionForm {
+    let mut form = AdminActionForm {
+        expected_state: expected_state.unwrap_or_default().to_string(),
+        ..AdminActionForm::default()
+    };
+    match request {
+        AdminActionRequest::AdmissionSetMode { mode } => {
+            form.action = "admission.set_mode".to_string();
+            form.value = mode.clone();
+        }
+        AdminActionRequest::SupportedTopicAdd { topic_id } => {
+            form.action = "supported_topic.add".to_string();
+            form.target_id = topic_id.clone();
+        }
+        AdminActionRequest::SupportedTopicRemove { topic_id } => {
+            form.action = "supported_topic.remove".to_string();
+            form.target_id = topic_id.clone();
+        }
+        AdminActionRequest::ReportSetStatus { report_id, status } => {
+            form.action = "report.set_status".to_string();
+            form.target_id = report_id.clone();
+            form.value = status.clone();
+        }
+        AdminActionRequest::OperatorSetRole {
+            pubkey,
+            role,
+            label,
+        } => {
+            form.action = "operator.set_role".to_string();
+            form.target_id = pubkey.clone();
+            form.value = role.clone().unwrap_or_else(|| "remove".to_string());
+            form.label = label.clone().unwrap_or_default();
+        }
+        AdminActionRequest::AppealAccept { risk_signal_id } => {
+            form.action = "appeal.accept".to_string();
+            form.target_id = risk_signal_id.clone();
+        }
+        AdminActionRequest::AppealReject { risk_signal_id } => {
+            form.action = "appeal.reject".to_string();
+            form.target_id = risk_signal_id.clone();
+        }
+        AdminActionRequest::AppealEdit {
+            risk_signal_id,
+            category,
+            severity,
+            confidence,
+            expires_at,
+        } => {
+            form.action = "appeal.edit".to_string();
+            form.target_id = risk_signal_id.clone();
+            form.category = category.clone().unwrap_or_default();
+            form.severity = severity.clone().unwrap_or_default();
+            form.confidence = confidence
+                .map(|value| value.to_string())
+                .unwrap_or_default();
+            form.expires_at = expires_at.clone().unwrap_or_default();
+        }
+        AdminActionRequest::AppealReissue {
+            risk_signal_id,
+            category,
+            severity,
+            confidence,
+            visibility,
+        } => {
+            form.action = "appeal.reissue".to_string();
+            form.target_id = risk_signal_id.clone();
+            form.category = category.clone().unwrap_or_default();
+            form.severity = severity.clone().unwrap_or_default();
+            form.confidence = confidence
+                .map(|value| value.to_string())
+                .unwrap_or_default();
+            form.visibility = visibility.clone().unwrap_or_default();
+        }
+    }
+    form
+}
+
+/// 異議申し立て審査の変更後状態(`appeal_status` と指定した項目)。未指定の項目は現在値を維持する。
+fn appeal_after(operation: &AppealReviewOperation) -> String {
+    let (status, fields) = match operation {
+        AppealReviewOperation::Accept { .. } => ("cleared", Vec::new()),
+        AppealReviewOperation::Reject { .. } => ("none", Vec::new()),
+        AppealReviewOperation::Edit { edit, .. } => (
+            "disputed",
+            vec![
+                ("category", edit.category.as_ref().map(enum_text)),
+                ("severity", edit.severity.as_ref().map(enum_text)),
+                ("confidence", edit.confidence.map(|value| value.to_string())),
+                ("expires_at", edit.expires_at.clone()),
+            ],
+        ),
+        AppealReviewOperation::Reissue { correction, .. } => (
+            "cleared",
+            vec![
+                ("category", correction.category.as_ref().map(enum_text)),
+                ("severity", correction.severity.as_ref().map(enum_text)),
+                (
+                    "confidence",
+                    correction.confidence.map(|value| value.to_string()),
+                ),
+                ("visibility", correction.visibility.as_ref().map(enum_text)),
+            ],
+        ),
+    };
+    std::iter::once(format!("appeal_status={status}"))
+        .chain(
+            fields
+                .into_iter()
+                .filter_map(|(field, value)| value.map(|value| format!("{field}={value}"))),
+        )
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn list_window(query: &AdminListQuery) -> (i64, i64) {
+    (
+        query
+            .limit
+            .unwrap_or(DEFAULT_LIST_LIMIT)
+            .clamp(1, MAX_LIST_LIMIT),
+        query.offset.unwrap_or(0).max(0),
+    )
+}
+
+fn operator_info(operator: &AdminOperator) -> AdminOperatorInfo {
+    AdminOperatorInfo {
+        pubkey: operator.pubkey.clone(),
+        role: operator.role.as_str().to_string(),
+        label: operator.label.clone(),
+        updated_at: operator.updated_at.to_rfc3339(),
+    }
+}
+
+fn appeal_review_info(review: AppealReview) -> AdminAppealReview {
+    AdminAppealReview {
+        risk_signal_id: review.risk_signal_id,
+        issuer_node_id: review.issuer_node_id,
+        target: review.target,
+        target_id: review.target_id,
+        category: review.category,
+        severity: review.severity,
+        basis: review.basis,
+        confidence: review.confidence,
+        visibility: review.visibility,
+        expires_at: review.expires_at,
+        appeal_status: review.appeal_status,
+        reports: review
+            .reports
+            .into_iter()
+            .map(|report| AdminAppealReport {
+                id: report.id,
+                status: report.status,
+                details: report.details,
+                created_at: report.created_at.to_rfc3339(),
+            })
+            .collect(),
+    }
+}
+
+fn operator_action_record(action: OperatorAction) -> AdminOperatorActionRecord {
+    AdminOperatorActionRecord {
+        id: action.id,
+        occurred_at: action.occurred_at.to_rfc3339(),
+        actor: action.actor,
+        action: action.action,
+        target_kind: action.target_kind,
+        target_id: action.target_id,
+        before: action.before,
+        after: action.after,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use kukuri_cn_core::AdmissionMode;
+
+    #[test]
+    fn json_requests_share_form_validation() {
+        let form = form_from_request(
+            &AdminActionRequest::AdmissionSetMode {
+                mode: "invite".to_string(),
+            },
+            None,
+        );
+        assert_eq!(
+            operation_from_form(&form).expect("admission"),
+            AdminOperation::SetAdmissionMode {
+                mode: AdmissionMode::Invite
+            }
+        );
+
+        let form = form_from_request(
+            &AdminActionRequest::OperatorSetRole {
+                pubkey: "a".repeat(64),
+                role: None,
+                label: None,
+            },
+            None,
+        );
+        assert_eq!(form.value, "remove");
+        assert!(matches!(
+            operation_from_form(&form).expect("operator"),
+            AdminOperation::SetOperatorRole { role: None, .. }
+        ));
+
+        let form = form_from_request(
+            &AdminActionRequest::ReportSetStatus {
+                report_id: "report-1".to_string(),
+                status: "unknown".to_string(),
+            },
+            None,
+        );
+        let error = invalid_action(operation_from_form(&form).unwrap_err());
+        let response = axum::response::IntoResponse::into_response(error);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let form = form_from_request(
+            &AdminActionRequest::AppealEdit {
+                risk_signal_id: "signal-1".to_string(),
+                category: None,
+                severity: Some("low".to_string()),
+                confidence: Some(101),
+                expires_at: None,
+            },
+            Some("{}"),
+        );
+        assert_eq!(form.action, "appeal.edit");
+        assert_eq!(form.confidence, "101");
+        assert!(appeal_operation_for_apply(&form).is_err());
+    }
+
+    #[test]
+    fn bearer_token_is_required_and_cookie_is_ignored() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::header::COOKIE,
+            HeaderValue::from_static("kukuri_admin_session=cookie-token"),
+        );
+        assert!(bearer_token(&headers).is_err());
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer  "));
+        assert!(bearer_token(&headers).is_err());
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer session-1"));
+        assert_eq!(bearer_token(&headers).expect("token"), "session-1");
+    }
+
+    #[test]
+    fn list_window_defaults_and_clamps() {
+        assert_eq!(list_window(&AdminListQuery::default()), (50, 0));
+        assert_eq!(
+            list_window(&AdminListQuery {
+                limit: Some(10_000),
+                offset: Some(-3),
+            }),
+            (MAX_LIST_LIMIT, 0)
+        );
+    }
+}
@@ -20,7 +20,7 @@ pub(crate) fn render_appeal_reviews(
     let boundary = if write_enabled {
         String::new()
     } else {
-        "<p class=\"boundary\">現在は参照専用です。変更には <code>COMMUNITY_NODE_ADMIN_ACTOR</code> と <code>COMMUNITY_NODE_SAFETY_OPERATOR_REVIEW</code> の両方が必要です。</p>".to_string()
+        "<p class=\"boundary\">現在は参照専用です。変更には moderator 以上の運営者権限と <code>COMMUNITY_NODE_SAFETY_OPERATOR_REVIEW</code> の両方が必要です。</p>".to_string()
     };
     let items = reviews
         .iter()
@@ -129,31 +129,15 @@ pub(crate) fn render_appeal_preview(
     operation: &AppealReviewOperation,
     review: &AppealReview,
 ) -> String {
-    let (action, title, impact, changes) = match operation {
-        AppealReviewOperation::Accept { .. } => (
-            "appeal.accept",
-            "異議申し立てを認容しますか",
-            "このリスク判定を Cleared にし、関連通報を処理済みにします。次回の信頼評価から、この判定の寄与は除外されます。",
-            None,
-        ),
-        AppealReviewOperation::Reject { .. } => (
-            "appeal.reject",
-            "異議申し立てを棄却しますか",
-            "このリスク判定を None に戻し、関連通報を棄却済みにします。信頼評価への寄与は残ります。",
-            None,
-        ),
-        AppealReviewOperation::Edit { edit, .. } => (
-            "appeal.edit",
-            "検知情報を調整しますか",
-            "署名済みモデレーション事象と利用者の正本状態は変更せず、このノードのリスク判定だけを調整します。異議申し立ては審査中のままです。",
-            Some(render_change_list(&edit_changes(edit, review))),
-        ),
-        AppealReviewOperation::Reissue { correction, .. } => (
-            "appeal.reissue",
-            "訂正版を再発行しますか",
-            "現在のリスク判定を認容(信頼評価への寄与なし)として終結させたまま根拠一覧に残し、指定した検知情報と公開範囲で新しいリスク判定を発行します。新しいリスク判定の失効時刻は未設定になります。関連通報は処理済みになります。",
-            Some(render_change_list(&reissue_changes(correction, review))),
-        ),
+    let (action, title, impact) = appeal_operation_summary(operation);
+    let changes = match operation {
+        AppealReviewOperation::Accept { .. } | AppealReviewOperation::Reject { .. } => None,
+        AppealReviewOperation::Edit { edit, .. } => {
+            Some(render_change_list(&edit_changes(edit, review)))
+        }
+        AppealReviewOperation::Reissue { correction, .. } => {
+            Some(render_change_list(&reissue_changes(correction, review)))
+        }
     };
     let expected = match operation {
         AppealReviewOperation::Accept { expected }
@@ -190,6 +174,34 @@ pub(crate) fn render_appeal_preview(
     render_admin_page("異議申し立て審査の確認", &header, &main)
 }
 
+/// 操作識別子・見出し・影響。HTML の確認画面と JSON admin API の preview が共有する。
+pub(crate) fn appeal_operation_summary(
+    operation: &AppealReviewOperation,
+) -> (&'static str, &'static str, &'static str) {
+    match operation {
+        AppealReviewOperation::Accept { .. } => (
+            "appeal.accept",
+            "異議申し立てを認容しますか",
+            "このリスク判定を Cleared にし、関連通報を処理済みにします。次回の信頼評価から、この判定の寄与は除外されます。",
+        ),
+        AppealReviewOperation::Reject { .. } => (
+            "appeal.reject",
+            "異議申し立てを棄却しますか",
+            "このリスク判定を None に戻し、関連通報を棄却済みにします。信頼評価への寄与は残ります。",
+        ),
+        AppealReviewOperation::Edit { .. } => (
+            "appeal.edit",
+            "検知情報を調整しますか",
+            "署名済みモデレーション事象と利用者の正本状態は変更せず、このノードのリスク判定だけを調整します。異議申し立ては審査中のままです。",
+        ),
+        AppealReviewOperation::Reissue { .. } => (
+            "appeal.reissue",
+            "訂正版を再発行しますか",
+            "現在のリスク判定を認容(信頼評価への寄与なし)として終結させたまま根拠一覧に残し、指定した検知情報と公開範囲で新しいリスク判定を発行します。新しいリスク判定の失効時刻は未設定になります。関連通報は処理済みになります。",
+        ),
+    }
+}
+
 /// 変更前後表示の 1 項目。`after` が `None`(未入力)の項目は現在値を維持する。
 struct FieldChange {
     label: &'static str,
@@ -316,7 +328,7 @@ fn hidden_metadata_fields(operation: &AppealReviewOperation) -> String {
 }
 
 /// snake_case enum の wire 表現(解析側 `parse_optional_enum` と対称の serde 経由)。
-fn enum_text<T: serde::Serialize>(value: &T) -> String {
+pub(crate) fn enum_text<T: serde::Serialize>(value: &T) -> String {
     match serde_json::to_value(value) {
         Ok(serde_json::Value::String(text)) => text,
         _ => String::new(),
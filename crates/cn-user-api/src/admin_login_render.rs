@@ -0,0 +1,57 @@
+//! 運営者ログイン画面(pubkey 入力と challenge 署名)の表示。
+
+use crate::admin::escape_html;
+use crate::admin_shell::{code_fact, facts, render_admin_page, text_fact};
+
+const LOGIN_HEADER: &str = "<h1>コミュニティノード運営</h1><p>運営者として登録された鍵でログインします。秘密鍵はこの画面に入力しません。</p>";
+
+pub(crate) fn render_login_page(error: Option<&str>) -> String {
+    let error = error
+        .map(|message| format!("<p class=\"danger\">{}</p>", escape_html(message)))
+        .unwrap_or_default();
+    let main = format!(
+        r#"<section class="dialog"><h2>ログイン</h2>{error}<form method="post" action="/login/challenge" class="inline-form"><label>運営者の公開鍵 <input name="pubkey" required autocomplete="off" placeholder="npub1… または hex"></label><button type="submit">署名用の文字列を発行</button></form><p class="boundary">運営者の追加と権限変更は <code>cn-cli admin operator</code> か、admin 権限の運営者がこの画面から行います。</p></section>"#
+    );
+    render_admin_page("kukuri 運営者ログイン", LOGIN_HEADER, &main)
+}
+
+pub(crate) fn render_login_challenge(
+    pubkey: &str,
+    challenge: &str,
+    public_base_url: &str,
+) -> String {
+    let command = format!(
+        "cn-cli admin sign-login --base-url {public_base_url} --challenge {challenge} --secret-key-file <秘密鍵ファイル>"
+    );
+    let main = format!(
+        r#"<section class="dialog"><h2>署名してログイン</h2>{}<p>手元の端末で次のコマンドを実行し、出力された JSON を貼り付けてください。署名用の文字列は一度だけ有効です。</p><p><code>{}</code></p><form method="post" action="/login/verify"><label>署名済みの封筒 <textarea name="login_envelope_json" required rows="8" cols="80" spellcheck="false"></textarea></label><div class="actions"><button class="primary" type="submit">ログイン</button><a class="button secondary" href="/login">やり直す</a></div></form></section>"#,
+        facts(&[
+            code_fact("公開鍵", pubkey),
+            code_fact("署名用の文字列", challenge),
+            text_fact("有効範囲", "この運営画面へのログインだけに使えます"),
+        ]),
+        escape_html(&command),
+    );
+    render_admin_page("kukuri 運営者ログイン", LOGIN_HEADER, &main)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn login_pages_escape_inputs_and_never_ask_for_secret_key() {
+        let html = render_login_page(Some("<b>拒否</b>"));
+        assert!(html.contains("&lt;b&gt;拒否&lt;/b&gt;"));
+        assert!(html.contains("action=\"/login/challenge\""));
+
+        let html = render_login_challenge("pub<script>", "challenge-1", "https://node.example");
+        assert!(!html.contains("pub<script>"));
+        assert!(html.contains("pub&lt;script&gt;"));
+        assert!(html.contains(
+            "cn-cli admin sign-login --base-url https://node.example --challenge challenge-1"
+        ));
+        assert!(html.contains("name=\"login_envelope_json\""));
+        assert!(!html.contains("name=\"secret_key\""));
+    }
+}
@@ -0,0 +1,246 @@
+//! 運営者の鍵署名ログインと admin session(cookie)。
+//!
+//! 運営者は `/login` で pubkey を入力して challenge を受け取り、`cn-cli admin sign-login` で
+//! 作った封筒を貼り付けてログインする。session token は HttpOnly / SameSite=Strict の cookie
+//! に載せ、状態変更 form は session ごとの CSRF token を要求する。
+
+use axum::Form;
+use axum::extract::State;
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use kukuri_cn_core::{
+    ADMIN_SESSION_TTL_SECONDS, AuthenticatedOperator, OperatorRole, create_auth_challenge,
+    load_admin_session, revoke_admin_session, verify_admin_login,
+};
+use serde::Deserialize;
+
+use crate::admin::{AdminState, csrf_matches, render_action_error};
+use crate::admin_login_render::{render_login_challenge, render_login_page};
+
+/// admin session token を載せる cookie 名。
+pub(crate) const ADMIN_SESSION_COOKIE: &str = "kukuri_admin_session";
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct LoginChallengeForm {
+    pubkey: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct LoginVerifyForm {
+    login_envelope_json: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct LogoutForm {
+    csrf_token: String,
+}
+
+pub(crate) async fn login_page() -> Html<String> {
+    Html(render_login_page(None))
+}
+
+pub(crate) async fn login_challenge(
+    State(state): State<AdminState>,
+    Form(form): Form<LoginChallengeForm>,
+) -> Response {
+    match create_auth_challenge(&state.runtime.pool, form.pubkey.as_str()).await {
+        Ok(challenge) => Html(render_login_challenge(
+            form.pubkey.trim(),
+            challenge.challenge.as_str(),
+            state
+                .runtime
+                .self_node
+                .resolved_urls
+                .public_base_url
+                .as_str(),
+        ))
+        .into_response(),
+        Err(error) => (
+            StatusCode::BAD_REQUEST,
+            Html(render_login_page(Some(
+                format!("ログインを開始できません: {error:#}").as_str(),
+            ))),
+        )
+            .into_response(),
+    }
+}
+
+pub(crate) async fn login_verify(
+    State(state): State<AdminState>,
+    Form(form): Form<LoginVerifyForm>,
+) -> Response {
+    let envelope = match serde_json::from_str(form.login_envelope_json.trim()) {
+        Ok(envelope) => envelope,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Html(render_login_page(Some(
+                    "署名済みの封筒を JSON のまま貼り付けてください。",
+                ))),
+            )
+                .into_response();
+        }
+    };
+    let session = match verify_admin_login(
+        &state.runtime.pool,
+        state
+            .runtime
+            .self_node
+            .resolved_urls
+            .public_base_url
+            .as_str(),
+        &envelope,
+    )
+    .await
+    {
+        Ok(session) => session,
+        Err(error) => {
+            tracing::warn!(error = %format!("{error:#}"), "運営者ログインを拒否しました");
+            return (
+                StatusCode::UNAUTHORIZED,
+                Html(render_login_page(Some(
+                    format!("ログインできません: {error:#}").as_str(),
+                ))),
+            )
+                .into_response();
+        }
+    };
+    tracing::info!(
+        operator = %session.operator.pubkey,
+        role = session.operator.role.as_str(),
+        "運営者がログインしました"
+    );
+    let mut response = Redirect::to("/").into_response();
+    if let Ok(cookie) = HeaderValue::from_str(&format!(
+        "{ADMIN_SESSION_COOKIE}={}; Path=/; HttpOnly; SameSite=Strict; Max-Age={ADMIN_SESSION_TTL_SECONDS}",
+        session.token
+    )) {
+        response.headers_mut().insert(header::SET_COOKIE, cookie);
+    }
+    response
+}
+
+pub(crate) async fn logout(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Form(form): Form<LogoutForm>,
+) -> Response {
+    let operator = match require_operator(&state, &headers, form.csrf_token.as_str()).await {
+        Ok(operator) => operator,
+        Err(response) => return response,
+    };
+    if let Some(token) = session_token(&headers)
+        && let Err(error) = revoke_admin_session(&state.runtime.pool, token).await
+    {
+        tracing::error!(error = %format!("{error:#}"), "admin session の失効に失敗しました");
+        return render_action_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "ログアウトできませんでした。",
+        );
+    }
+    tracing::info!(operator = %operator.operator.pubkey, "運営者がログアウトしました");
+    let mut response = Redirect::to("/login").into_response();
+    if let Ok(cookie) = HeaderValue::from_str(&format!(
+        "{ADMIN_SESSION_COOKIE}=; Path=/; HttpOnly; SameSite=Strict; Max-Age=0"
+    )) {
+        response.headers_mut().insert(header::SET_COOKIE, cookie);
+    }
+    response
+}
+
+/// cookie の session から運営者を解決する。未ログイン・期限切れは `None`。
+pub(crate) async fn current_operator(
+    state: &AdminState,
+    headers: &HeaderMap,
+) -> anyhow::Result<Option<AuthenticatedOperator>> {
+    let Some(token) = session_token(headers) else {
+        return Ok(None);
+    };
+    load_admin_session(&state.runtime.pool, token).await
+}
+
+/// 状態変更 form 用。session と CSRF token の両方を検査し、拒否時は応答を返す。
+pub(crate) async fn require_operator(
+    state: &AdminState,
+    headers: &HeaderMap,
+    csrf_token: &str,
+) -> Result<AuthenticatedOperator, Response> {
+    let operator = match current_operator(state, headers).await {
+        Ok(Some(operator)) => operator,
+        Ok(None) => {
+            return Err(render_action_error(
+                StatusCode::UNAUTHORIZED,
+                "ログインが必要です。運営者の鍵でログインし直してください。",
+            ));
+        }
+        Err(error) => {
+            tracing::error!(error = %format!("{error:#}"), "admin session の確認に失敗しました");
+            return Err(render_action_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "ログイン状態を確認できませんでした。",
+            ));
+        }
+    };
+    if !csrf_matches(operator.csrf_token.as_str(), csrf_token) {
+        return Err(render_action_error(
+            StatusCode::FORBIDDEN,
+            "画面の確認情報が無効または期限切れです。画面を読み直してください。",
+        ));
+    }
+    Ok(operator)
+}
+
+/// 運営者の role が操作に足りなければ 403 の応答を返す。
+pub(crate) fn role_denied(
+    operator: &AuthenticatedOperator,
+    required: OperatorRole,
+) -> Option<Response> {
+    if operator.operator.role.allows(required) {
+        return None;
+    }
+    Some(render_action_error(
+        StatusCode::FORBIDDEN,
+        format!(
+            "この操作には {} 以上の運営者権限が必要です(現在: {})。",
+            required.as_str(),
+            operator.operator.role.as_str()
+        )
+        .as_str(),
+    ))
+}
+
+fn session_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| {
+            let (name, value) = pair.trim().split_once('=')?;
+            (name == ADMIN_SESSION_COOKIE && !value.is_empty()).then_some(value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_token_is_read_from_any_cookie_header() {
+        let mut headers = HeaderMap::new();
+        headers.append(header::COOKIE, HeaderValue::from_static("theme=dark"));
+        headers.append(
+            header::COOKIE,
+            HeaderValue::from_static("a=1; kukuri_admin_session=token-1; b=2"),
+        );
+        assert_eq!(session_token(&headers), Some("token-1"));
+
+        let mut empty = HeaderMap::new();
+        empty.insert(
+            header::COOKIE,
+            HeaderValue::from_static("kukuri_admin_session="),
+        );
+        assert_eq!(session_token(&empty), None);
+        assert_eq!(session_token(&HeaderMap::new()), None);
+    }
+}
@@ -8,7 +8,7 @@ use crate::admin::escape_html;
 
 /// 管理画面全体で共有する style。色値は `DESIGN.md` §9 のクイックリファレンスを正とする
 /// (server-rendered HTML は `tokens.css` を参照できないため、ここが唯一の置き場)。
-pub(crate) const ADMIN_STYLE: &str = r#":root{color-scheme:dark light;font-family:system-ui,sans-serif;--surface:#101923;--panel:#162231;--raised:#233241;--text:#f6f1e8;--muted:#cbbdae;--border:#39495a;--primary:#f59d62;--primary-text:#0e1b26;--focus:#00b3a4;--warning:#e6b066}@media(prefers-color-scheme:light){:root{--surface:#f4efe6;--panel:#fff;--raised:#dfe6ec;--text:#21303b;--muted:#5f6c76;--border:#b7c2cb;--primary:#d77d45;--primary-text:#fff7ef;--focus:#0f8c82;--warning:#9a6e2a}}*{box-sizing:border-box}body{max-width:1240px;margin:0 auto;padding:24px;line-height:1.5;background:var(--surface);color:var(--text)}header,section,.dialog{border:1px solid var(--border);border-radius:18px;padding:18px;margin-bottom:18px;background:var(--panel)}.dialog{max-width:840px}.metrics{display:grid;grid-template-columns:repeat(auto-fit,minmax(240px,1fr));gap:12px}.metric{background:var(--raised);border-radius:12px;padding:12px}table{border-collapse:collapse;width:100%;display:block;overflow:auto}th,td{border-bottom:1px solid var(--border);padding:9px;text-align:left;vertical-align:top}code{overflow-wrap:anywhere}.boundary{border-left:4px solid var(--warning);padding-left:12px}a{color:var(--focus)}:focus-visible{outline:3px solid var(--focus);outline-offset:2px}button,a.button{display:inline-flex;align-items:center;justify-content:center;min-height:44px;border:0;border-radius:999px;padding:8px 14px;background:var(--primary);color:var(--primary-text);font-weight:700;cursor:pointer;text-decoration:none;font-size:1rem}button.secondary,a.button.secondary{background:var(--raised);color:var(--text)}input,select{min-height:44px;border:1px solid var(--border);border-radius:10px;padding:7px 10px;background:var(--surface);color:var(--text)}.inline-form,.compact-form,.review-actions{display:flex;flex-wrap:wrap;align-items:end;gap:10px;margin:12px 0}.compact-form{min-width:270px}label{display:grid;gap:4px}.meta{color:var(--muted);font-size:.875rem}.danger{color:var(--warning)}.crumbs{display:flex;flex-wrap:wrap;gap:8px;margin:0 0 6px;color:var(--muted);font-size:.875rem}.crumbs [aria-current]{color:var(--text);font-weight:700}.facts{display:grid;grid-template-columns:minmax(120px,auto) minmax(0,1fr);gap:8px 14px;margin:14px 0}.facts dt{font-weight:700}.facts dd{margin:0}.facts ul{margin:0;padding-left:18px}.facts .meta{margin:0 0 4px}.actions{display:flex;flex-wrap:wrap;align-items:center;gap:10px;margin-top:16px}.appeal-card{border:1px solid var(--border);border-radius:14px;padding:14px;margin:14px 0;background:var(--surface)}.appeal-grid{display:grid;grid-template-columns:minmax(100px,auto) minmax(0,1fr);gap:6px 12px}.appeal-grid dt{font-weight:700}.appeal-grid dd{margin:0}.edit-form{display:grid;grid-template-columns:repeat(auto-fit,minmax(140px,1fr));gap:10px;width:100%;padding:12px;border:1px solid var(--border);border-radius:12px}@media(max-width:720px){body{padding:12px}.appeal-grid,.facts{grid-template-columns:1fr}}"#;
+pub(crate) const ADMIN_STYLE: &str = r#":root{color-scheme:dark light;font-family:system-ui,sans-serif;--surface:#101923;--panel:#162231;--raised:#233241;--text:#f6f1e8;--muted:#cbbdae;--border:#39495a;--primary:#f59d62;--primary-text:#0e1b26;--focus:#00b3a4;--warning:#e6b066}@media(prefers-color-scheme:light){:root{--surface:#f4efe6;--panel:#fff;--raised:#dfe6ec;--text:#21303b;--muted:#5f6c76;--border:#b7c2cb;--primary:#d77d45;--primary-text:#fff7ef;--focus:#0f8c82;--warning:#9a6e2a}}*{box-sizing:border-box}body{max-width:1240px;margin:0 auto;padding:24px;line-height:1.5;background:var(--surface);color:var(--text)}header,section,.dialog{border:1px solid var(--border);border-radius:18px;padding:18px;margin-bottom:18px;background:var(--panel)}.dialog{max-width:840px}.metrics{display:grid;grid-template-columns:repeat(auto-fit,minmax(240px,1fr));gap:12px}.metric{background:var(--raised);border-radius:12px;padding:12px}table{border-collapse:collapse;width:100%;display:block;overflow:auto}th,td{border-bottom:1px solid var(--border);padding:9px;text-align:left;vertical-align:top}code{overflow-wrap:anywhere}.boundary{border-left:4px solid var(--warning);padding-left:12px}a{color:var(--focus)}:focus-visible{outline:3px solid var(--focus);outline-offset:2px}button,a.button{display:inline-flex;align-items:center;justify-content:center;min-height:44px;border:0;border-radius:999px;padding:8px 14px;background:var(--primary);color:var(--primary-text);font-weight:700;cursor:pointer;text-decoration:none;font-size:1rem}button.secondary,a.button.secondary{background:var(--raised);color:var(--text)}input,select,textarea{min-height:44px;border:1px solid var(--border);border-radius:10px;padding:7px 10px;background:var(--surface);color:var(--text)}.inline-form,.compact-form,.review-actions{display:flex;flex-wrap:wrap;align-items:end;gap:10px;margin:12px 0}.compact-form{min-width:270px}label{display:grid;gap:4px}.meta{color:var(--muted);font-size:.875rem}.danger{color:var(--warning)}.crumbs{display:flex;flex-wrap:wrap;gap:8px;margin:0 0 6px;color:var(--muted);font-size:.875rem}.crumbs [aria-current]{color:var(--text);font-weight:700}.facts{display:grid;grid-template-columns:minmax(120px,auto) minmax(0,1fr);gap:8px 14px;margin:14px 0}.facts dt{font-weight:700}.facts dd{margin:0}.facts ul{margin:0;padding-left:18px}.facts .meta{margin:0 0 4px}.actions{display:flex;flex-wrap:wrap;align-items:center;gap:10px;margin-top:16px}.appeal-card{border:1px solid var(--border);border-radius:14px;padding:14px;margin:14px 0;background:var(--surface)}.appeal-grid{display:grid;grid-template-columns:minmax(100px,auto) minmax(0,1fr);gap:6px 12px}.appeal-grid dt{font-weight:700}.appeal-grid dd{margin:0}.edit-form{display:grid;grid-template-columns:repeat(auto-fit,minmax(140px,1fr));gap:10px;width:100%;padding:12px;border:1px solid var(--border);border-radius:12px}@media(max-width:720px){body{padding:12px}.appeal-grid,.facts{grid-template-columns:1fr}}"#;
 
 /// 管理画面の document 枠。`header` と `main` は生成済み HTML を受け取る。
 pub(crate) fn render_admin_page(title: &str, header: &str, main: &str) -> String {
@@ -5,20 +5,31 @@ use std::path::PathBuf;
 
 use anyhow::{Context, Result};
 use kukuri_cn_core::{
-    COMMUNITY_NODE_RENDEZVOUS_KEY_PREFIX_ENV, COMMUNITY_NODE_RENDEZVOUS_REDIS_URL_ENV, JwtConfig,
-    parse_bool_env, parse_csv_env, parse_u32_env, parse_u64_env,
+    COMMUNITY_NODE_RENDEZVOUS_BACKEND_ENV, COMMUNITY_NODE_RENDEZVOUS_KEY_PREFIX_ENV,
+    COMMUNITY_NODE_RENDEZVOUS_REDIS_URL_ENV, JwtConfig, parse_bool_env, parse_csv_env,
+    parse_u32_env, parse_u64_env,
 };
 use kukuri_cn_protocol::{normalize_http_url, normalize_http_url_list};
 
 pub const RELATION_DISTANCE_OPTOUT_MIN_PROXIMITY_ENV: &str =
     "COMMUNITY_NODE_RELATION_DISTANCE_OPTOUT_MIN_PROXIMITY";
 
+/// topic rendezvous の保存先(`COMMUNITY_NODE_RENDEZVOUS_BACKEND`)。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RendezvousBackendConfig {
+    /// Redis(Valkey)。user-api を複数インスタンスで動かす構成向け。
+    Redis { url: String, key_prefix: String },
+    /// user-api が使う Postgres に相乗りする。Redis を置かない小規模な node 向け。
+    Postgres,
+    /// プロセス内。user-api が 1 インスタンスの構成とテスト専用。
+    Memory,
+}
+
 #[derive(Clone)]
 pub struct UserApiConfig {
     pub bind_addr: SocketAddr,
     pub database_url: String,
-    pub rendezvous_redis_url: String,
-    pub rendezvous_key_prefix: String,
+    pub rendezvous: RendezvousBackendConfig,
     pub base_url: String,
     pub public_base_url: String,
     pub connectivity_urls: Vec<String>,
@@ -32,12 +43,13 @@ pub struct UserApiConfig {
     /// ユーザー向け index query(search / discovery / recommendation)を公開するか(#404)。
     /// 既定 false。capability は Available だが、readiness activation 完了までは
     /// `/v1/index/*` を公開しない。
-    /// 有効化すると ArcadeDB(`COMMUNITY_NODE_ARCADEDB_*`)へ接続する。
+    /// 有効化すると `COMMUNITY_NODE_INDEX_BACKEND` の投影(ArcadeDB = `COMMUNITY_NODE_ARCADEDB_*`、
+    /// embedded = `COMMUNITY_NODE_INDEX_EMBEDDED_DIR`)を読む。
     pub index_query_enabled: bool,
     /// trust / relation read surface を公開するか(#415)。
     /// 既定 false。capability は Available だが、readiness activation 完了までは
-    /// `/v1/trust/*` / `/v1/relation/*` は 404。有効化すると relation graph の
-    /// ArcadeDB(`COMMUNITY_NODE_ARCADEDB_*`)へ接続し、trust パラメータを
+    /// `/v1/trust/*` / `/v1/relation/*` は 404。有効化すると relation graph
+    /// (`COMMUNITY_NODE_INDEX_BACKEND` に従い ArcadeDB または Postgres)へ接続し、trust パラメータを
     /// `COMMUNITY_NODE_TRUST_*` env から読む。
     pub trust_read_enabled: bool,
     /// distance opt-out が「遠い」と判定する node-local proximity 境界。
@@ -59,8 +71,7 @@ impl std::fmt::Debug for UserApiConfig {
         f.debug_struct("UserApiConfig")
             .field("bind_addr", &self.bind_addr)
             .field("database_url", &self.database_url)
-            .field("rendezvous_redis_url", &self.rendezvous_redis_url)
-            .field("rendezvous_key_prefix", &self.rendezvous_key_prefix)
+            .field("rendezvous", &self.rendezvous)
             .field("base_url", &self.base_url)
             .field("public_base_url", &self.public_base_url)
             .field("connectivity_urls", &self.connectivity_urls)
@@ -94,12 +105,11 @@ impl UserApiConfig {
             .context("failed to parse COMMUNITY_NODE_BIND_ADDR")?;
         let database_url = std::env::var("COMMUNITY_NODE_DATABASE_URL")
             .context("COMMUNITY_NODE_DATABASE_URL is required")?;
-        let rendezvous_redis_url = std::env::var(COMMUNITY_NODE_RENDEZVOUS_REDIS_URL_ENV)
-            .with_context(|| format!("{COMMUNITY_NODE_RENDEZVOUS_REDIS_URL_ENV} is required"))?;
-        let rendezvous_key_prefix = std::env::var(COMMUNITY_NODE_RENDEZVOUS_KEY_PREFIX_ENV)
-            .ok()
-            .filter(|value| !value.trim().is_empty())
-            .unwrap_or_else(|| "cn:rendezvous:v1".to_string());
+        let rendezvous = parse_rendezvous_backend(
+            non_empty_env(COMMUNITY_NODE_RENDEZVOUS_BACKEND_ENV).as_deref(),
+            non_empty_env(COMMUNITY_NODE_RENDEZVOUS_REDIS_URL_ENV),
+            non_empty_env(COMMUNITY_NODE_RENDEZVOUS_KEY_PREFIX_ENV),
+        )?;
         let base_url = normalize_http_url(
             std::env::var("COMMUNITY_NODE_BASE_URL")
                 .context("COMMUNITY_NODE_BASE_URL is required")?
@@ -146,8 +156,7 @@ impl UserApiConfig {
         Ok(Self {
             bind_addr,
             database_url,
-            rendezvous_redis_url,
-            rendezvous_key_prefix,
+            rendezvous,
             base_url,
             public_base_url,
             connectivity_urls,
@@ -164,6 +173,41 @@ impl UserApiConfig {
     }
 }
 
+fn non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// backend 未指定なら、Redis URL があれば Redis、無ければ Postgres を使う
+/// (どちらも複数インスタンスで共有できる。in-process は明示指定したときだけ)。
+fn parse_rendezvous_backend(
+    backend: Option<&str>,
+    redis_url: Option<String>,
+    key_prefix: Option<String>,
+) -> Result<RendezvousBackendConfig> {
+    let backend = match backend.map(|value| value.trim().to_ascii_lowercase()) {
+        Some(backend) => backend,
+        None if redis_url.is_some() => "redis".to_string(),
+        None => "postgres".to_string(),
+    };
+    match backend.as_str() {
+        "redis" => Ok(RendezvousBackendConfig::Redis {
+            url: redis_url.with_context(|| {
+                format!(
+                    "{COMMUNITY_NODE_RENDEZVOUS_REDIS_URL_ENV} is required for the redis rendezvous backend"
+                )
+            })?,
+            key_prefix: key_prefix.unwrap_or_else(|| "cn:rendezvous:v1".to_string()),
+        }),
+        "postgres" => Ok(RendezvousBackendConfig::Postgres),
+        "memory" => Ok(RendezvousBackendConfig::Memory),
+        other => anyhow::bail!(
+            "{COMMUNITY_NODE_RENDEZVOUS_BACKEND_ENV} must be one of redis, postgres or memory (got `{other}`)"
+        ),
+    }
+}
+
 fn parse_relation_distance_optout_min_proximity(
     raw: Option<&str>,
     required: bool,
@@ -234,9 +278,117 @@ impl RateLimitConfig {
     }
 }
 
+/// 1 route 分の per-pubkey 予算(1 分あたりの補充数と burst)。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PubkeyRouteBudget {
+    pub per_minute: u32,
+    pub burst: u32,
+}
+
+/// bearer identity(pubkey)単位の 2 段目の rate limit。
+///
+/// IP 単位の `RateLimitConfig` は CGNAT 配下の利用者で bucket を共有し、IP を変える鍵は
+/// 素通りする。認証済み route はこちらで鍵ごとに予算を課す。既定は IP 段と同じく無効。
+#[derive(Clone, Copy, Debug)]
+pub struct PubkeyRateLimitConfig {
+    pub enabled: bool,
+    /// `POST /v1/report`(bearer 付きの通報のみ。匿名通報は IP 段だけで制限する)。
+    pub report: PubkeyRouteBudget,
+    /// `POST /v1/indexing/requests`。
+    pub indexing_request: PubkeyRouteBudget,
+    /// bootstrap / topic rendezvous heartbeat。
+    pub rendezvous_heartbeat: PubkeyRouteBudget,
+    /// `/v1/index/*`(search / discovery / recommendations)。
+    pub search: PubkeyRouteBudget,
+}
+
+impl Default for PubkeyRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            report: PubkeyRouteBudget {
+                per_minute: 5,
+                burst: 10,
+            },
+            indexing_request: PubkeyRouteBudget {
+                per_minute: 2,
+                burst: 5,
+            },
+            rendezvous_heartbeat: PubkeyRouteBudget {
+                per_minute: 30,
+                burst: 60,
+            },
+            search: PubkeyRouteBudget {
+                per_minute: 60,
+                burst: 30,
+            },
+        }
+    }
+}
+
+impl PubkeyRateLimitConfig {
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+        Ok(Self {
+            enabled: parse_bool_env("COMMUNITY_NODE_PUBKEY_RATE_LIMIT_ENABLED", defaults.enabled)?,
+            report: parse_route_budget_env("REPORT", defaults.report)?,
+            indexing_request: parse_route_budget_env(
+                "INDEXING_REQUEST",
+                defaults.indexing_request,
+            )?,
+            rendezvous_heartbeat: parse_route_budget_env(
+                "RENDEZVOUS_HEARTBEAT",
+                defaults.rendezvous_heartbeat,
+            )?,
+            search: parse_route_budget_env("SEARCH", defaults.search)?,
+        })
+    }
+}
+
+/// `COMMUNITY_NODE_PUBKEY_RATE_LIMIT_<ROUTE>_PER_MINUTE` / `_BURST` を読む。
+fn parse_route_budget_env(route: &str, defaults: PubkeyRouteBudget) -> Result<PubkeyRouteBudget> {
+    Ok(PubkeyRouteBudget {
+        per_minute: parse_u32_env(
+            &format!("COMMUNITY_NODE_PUBKEY_RATE_LIMIT_{route}_PER_MINUTE"),
+            defaults.per_minute,
+        )?
+        .max(1),
+        burst: parse_u32_env(
+            &format!("COMMUNITY_NODE_PUBKEY_RATE_LIMIT_{route}_BURST"),
+            defaults.burst,
+        )?
+        .max(1),
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_relation_distance_optout_min_proximity;
+    use super::{
+        RendezvousBackendConfig, parse_relation_distance_optout_min_proximity,
+        parse_rendezvous_backend,
+    };
+
+    #[test]
+    fn rendezvous_backend_defaults_to_redis_only_when_url_is_set() {
+        assert_eq!(
+            parse_rendezvous_backend(None, Some("redis://valkey:6379/".into()), None).unwrap(),
+            RendezvousBackendConfig::Redis {
+                url: "redis://valkey:6379/".into(),
+                key_prefix: "cn:rendezvous:v1".into(),
+            }
+        );
+        assert_eq!(
+            parse_rendezvous_backend(None, None, None).unwrap(),
+            RendezvousBackendConfig::Postgres
+        );
+        assert_eq!(
+            parse_rendezvous_backend(Some("Memory"), Some("redis://valkey:6379/".into()), None)
+                .unwrap(),
+            RendezvousBackendConfig::Memory
+        );
+        assert!(parse_rendezvous_backend(Some("redis"), None, None).is_err());
+        assert!(parse_rendezvous_backend(Some("etcd"), None, None).is_err());
+    }
 
     #[test]
     fn distance_optout_policy_is_required_for_read_surfaces() {
@@ -21,6 +21,8 @@ pub(crate) enum AccountLifecycleOperation {
     CreateAuthChallenge,
     LoadAdmissionConfig,
     VerifyAuth,
+    RefreshAuth,
+    Logout,
     GetConsentStatus,
     AcceptConsents,
 }
@@ -58,6 +60,16 @@ impl AccountLifecycleError {
             Self::AuthFailed(source)
         }
     }
+
+    /// refresh token の不備(未知・使用済み・失効・期限切れ・endpoint 不一致)は 401 にし、
+    /// 端末が challenge からの再認証へ戻れるようにする。
+    pub(crate) fn auth_refresh(source: anyhow::Error) -> Self {
+        if source.downcast_ref::<sqlx::Error>().is_some() {
+            Self::infrastructure(AccountLifecycleOperation::RefreshAuth, source)
+        } else {
+            Self::AuthFailed(source)
+        }
+    }
 }
 
 pub(crate) fn account_lifecycle_error(error: AccountLifecycleError) -> ApiError {
@@ -112,7 +124,11 @@ pub(crate) enum IndexingOperation {
     SearchAll,
     Discovery,
     Recommendations,
+    RankRecommendations,
+    People,
+    Live,
     FilterRelationVisibility,
+    FilterRelationListed,
     VerifyChannelMembership,
 }
 
@@ -1,15 +1,22 @@
-//! 認証(challenge / verify)。
+//! 認証(challenge / verify / refresh / logout)と、vouch mode の参加推薦。
 
 use axum::Json;
 use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
 use kukuri_cn_core::{
-    ApiResult, create_auth_challenge, load_admission_config, verify_auth_envelope_and_issue_token,
+    ApiError, ApiResult, VouchWeightingUnavailable, create_auth_challenge, load_admission_config,
+    refresh_access_token, require_bearer_identity, require_consents, require_writable,
+    revoke_auth_session, submit_vouch, verify_auth_envelope_and_issue_token,
 };
 use kukuri_cn_protocol::{
-    AuthChallengeRequest, AuthChallengeResponse, AuthVerifyRequest, AuthVerifyResponse,
+    ADMISSION_VOUCH_INVALID_CODE, ADMISSION_VOUCH_WEIGHTING_UNAVAILABLE_CODE,
+    AdmissionVouchRequest, AdmissionVouchResponse, AuthChallengeRequest, AuthChallengeResponse,
+    AuthRefreshRequest, AuthVerifyRequest, AuthVerifyResponse,
 };
 
-use crate::errors::{AccountLifecycleError, AccountLifecycleOperation, account_lifecycle_error};
+use crate::errors::{
+    AccountLifecycleError, AccountLifecycleOperation, account_lifecycle_error, internal_error,
+};
 use crate::state::UserApiState;
 
 pub(crate) async fn auth_challenge(
@@ -48,7 +55,7 @@ pub(crate) async fn auth_verify(
         &request.auth_envelope_json,
         request.endpoint_id.as_deref(),
         request.addr_hint.as_deref(),
-        admission_config.mode,
+        &admission_config,
         request.invite_code.as_deref(),
     )
     .await
@@ -57,6 +64,97 @@ pub(crate) async fn auth_verify(
     Ok(Json(response))
 }
 
+/// refresh token でアクセストークンを再発行する。bearer は要らない(失効したアクセストークンの
+/// 代わりに refresh token 自体が資格になる)。無効・使用済み・失効済みの token は 401。
+pub(crate) async fn auth_refresh(
+    State(state): State<UserApiState>,
+    Json(request): Json<AuthRefreshRequest>,
+) -> ApiResult<Json<AuthVerifyResponse>> {
+    let response = refresh_access_token(
+        &state.pool,
+        &state.jwt_config,
+        request.refresh_token.as_str(),
+        request.endpoint_id.as_deref(),
+        request.addr_hint.as_deref(),
+    )
+    .await
+    .map_err(AccountLifecycleError::auth_refresh)
+    .map_err(account_lifecycle_error)?;
+    Ok(Json(response))
+}
+
+/// 現在のセッションを失効させる。同じセッションのアクセストークンと refresh token は以後 401。
+/// セッション導入前に発行されたトークンは失効させる対象がなく、期限まで有効なまま 204 を返す。
+pub(crate) async fn auth_logout(
+    State(state): State<UserApiState>,
+    headers: HeaderMap,
+) -> ApiResult<StatusCode> {
+    let identity = require_bearer_identity(&state.pool, &state.jwt_config, &headers).await?;
+    if let Some(session_id) = identity.session_id.as_deref() {
+        revoke_auth_session(&state.pool, identity.pubkey.as_str(), session_id)
+            .await
+            .map_err(|source| {
+                AccountLifecycleError::infrastructure(AccountLifecycleOperation::Logout, source)
+            })
+            .map_err(account_lifecycle_error)?;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 既存 member が候補者の参加を推薦する。推薦者は bearer 認証済み・必須ポリシー同意済みで、
+/// 封筒の署名者と一致すること。読み取り専用の制裁中は推薦できない。
+///
+/// relation graph は trust read と distance opt-out のどちらかが構成していれば、近接度の
+/// 重み付けに使う(どちらも同じ graph backend を指す)。
+pub(crate) async fn submit_admission_vouch(
+    State(state): State<UserApiState>,
+    headers: HeaderMap,
+    Json(request): Json<AdmissionVouchRequest>,
+) -> ApiResult<Json<AdmissionVouchResponse>> {
+    let identity = require_bearer_identity(&state.pool, &state.jwt_config, &headers).await?;
+    require_writable(&identity)?;
+    let _ = require_consents(&state.pool, identity.pubkey.as_str()).await?;
+    let relation = state
+        .trust_read
+        .as_ref()
+        .map(|trust_read| trust_read.relation.as_ref())
+        .or_else(|| {
+            state
+                .relation_visibility
+                .as_ref()
+                .map(|visibility| visibility.relation.as_ref())
+        });
+    let response = submit_vouch(
+        &state.pool,
+        state.self_node.resolved_urls.public_base_url.as_str(),
+        identity.pubkey.as_str(),
+        &request.vouch_envelope_json,
+        relation,
+    )
+    .await
+    .map_err(vouch_error)?;
+    Ok(Json(response))
+}
+
+fn vouch_error(error: anyhow::Error) -> ApiError {
+    if error.downcast_ref::<VouchWeightingUnavailable>().is_some() {
+        return ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            ADMISSION_VOUCH_WEIGHTING_UNAVAILABLE_CODE,
+            error.to_string(),
+        );
+    }
+    if error.downcast_ref::<sqlx::Error>().is_some() {
+        tracing::error!(error = %error, "admission vouch infrastructure failure");
+        return internal_error(error);
+    }
+    ApiError::new(
+        StatusCode::BAD_REQUEST,
+        ADMISSION_VOUCH_INVALID_CODE,
+        error.to_string(),
+    )
+}
+
 /// auth/verify の失敗を HTTP 応答へマップする。admission 拒否(#383)は 403 + 専用コードで
 /// 区別し、それ以外の署名・challenge 検証失敗は従来通り 401 AUTH_FAILED にする。
 #[cfg(test)]
@@ -66,6 +164,8 @@ mod tests {
 
     use crate::errors::{AccountLifecycleError, account_lifecycle_error, assert_error_contract};
 
+    use super::vouch_error;
+
     #[tokio::test]
     async fn auth_verify_error_contracts_are_stable() {
         assert_error_contract(
@@ -96,4 +196,22 @@ mod tests {
         )
         .await;
     }
+
+    #[tokio::test]
+    async fn vouch_error_contracts_are_stable() {
+        assert_error_contract(
+            vouch_error(kukuri_cn_core::VouchWeightingUnavailable.into()),
+            StatusCode::SERVICE_UNAVAILABLE,
+            "ADMISSION_VOUCH_WEIGHTING_UNAVAILABLE",
+            "vouch weighting requires a relation graph, which is not configured",
+        )
+        .await;
+        assert_error_contract(
+            vouch_error(anyhow::anyhow!("candidate is already a member")),
+            StatusCode::BAD_REQUEST,
+            "ADMISSION_VOUCH_INVALID",
+            "candidate is already a member",
+        )
+        .await;
+    }
 }
@@ -6,6 +6,7 @@ use axum::http::HeaderMap;
 use kukuri_cn_core::{
     ApiResult, auth_required_error, load_bootstrap_nodes, load_bootstrap_seed_peers,
     refresh_bootstrap_peer_registration, require_bearer_identity, require_consents,
+    require_writable,
 };
 use kukuri_cn_protocol::{
     BootstrapHeartbeatRequest, BootstrapHeartbeatResponse, BootstrapNodesResponse,
@@ -13,6 +14,7 @@ use kukuri_cn_protocol::{
 };
 
 use crate::errors::{SupportEndpointError, SupportEndpointOperation, support_endpoint_error};
+use crate::rate_limit::PubkeyRateLimitRoute;
 use crate::state::UserApiState;
 
 pub(crate) async fn bootstrap_nodes(
@@ -51,6 +53,9 @@ pub(crate) async fn bootstrap_heartbeat(
     Json(request): Json<BootstrapHeartbeatRequest>,
 ) -> ApiResult<Json<BootstrapHeartbeatResponse>> {
     let identity = require_bearer_identity(&state.pool, &state.jwt_config, &headers).await?;
+    state
+        .pubkey_rate_limit
+        .check(PubkeyRateLimitRoute::RendezvousHeartbeat, &identity.pubkey)?;
     if let Some(bound_endpoint_id) = identity.endpoint_id.as_deref()
         && bound_endpoint_id != request.endpoint_id
     {
@@ -76,6 +81,10 @@ pub(crate) async fn topic_rendezvous_heartbeat(
     Json(request): Json<TopicRendezvousHeartbeat>,
 ) -> ApiResult<Json<TopicRendezvousHeartbeatResponse>> {
     let identity = require_bearer_identity(&state.pool, &state.jwt_config, &headers).await?;
+    require_writable(&identity)?;
+    state
+        .pubkey_rate_limit
+        .check(PubkeyRateLimitRoute::RendezvousHeartbeat, &identity.pubkey)?;
     let _ = require_consents(&state.pool, identity.pubkey.as_str()).await?;
     if let Some(bound_endpoint_id) = identity.endpoint_id.as_deref()
         && bound_endpoint_id != request.endpoint_id
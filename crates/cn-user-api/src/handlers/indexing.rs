@@ -1,25 +1,37 @@
 //! indexing request の受付(#413)と、ユーザー向け index query(#404)。
 //! route 上も /v1/indexing(登録)と /v1/index(検索)で対になっているため同居させる。
 
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use async_trait::async_trait;
 use axum::Json;
 use axum::extract::{Query, State};
 use axum::http::{HeaderMap, StatusCode};
+use chrono::{DateTime, Utc};
 use kukuri_cn_core::{
-    ApiError, ApiResult, IndexScopeKind, filter_relation_visible, get_channel_secret,
-    insert_indexing_request, register_channel_secret, require_bearer_identity, require_consents,
+    ApiError, ApiResult, IndexScopeKind, filter_relation_listed, filter_relation_visible,
+    get_channel_secret, insert_indexing_request, list_trust_risk_inputs, register_channel_secret,
+    require_bearer_identity, require_consents, require_writable,
+};
+use kukuri_cn_indexer::{
+    AuthorTrustSource, IndexCursor, IndexPage, IndexPosition, IndexQuery, IndexQueryFilter,
+    MAX_QUERY_LIMIT, RankedPosition, RecommendationRanker, clamp_query_limit,
 };
-use kukuri_cn_indexer::IndexQuery;
 use kukuri_cn_protocol::{
     CHANNEL_MEMBERSHIP_REQUIRED_CODE, CHANNEL_MEMBERSHIP_SECRET_HEADER,
     INDEX_QUERY_NOT_ACTIVATED_CODE, INDEX_QUERY_NOT_CONFIGURED_CODE,
-    INDEXING_REQUEST_NOT_ACTIVATED_CODE, INDEXING_REQUEST_NOT_CONFIGURED_CODE, IndexEntryView,
-    IndexQueryParams, IndexQueryResponse, RELATION_VISIBILITY_NOT_CONFIGURED_CODE,
-    SubmitIndexingRequestRequest, SubmitIndexingRequestResponse,
+    INDEXING_REQUEST_NOT_ACTIVATED_CODE, INDEXING_REQUEST_NOT_CONFIGURED_CODE, IndexEntryKind,
+    IndexEntryView, IndexQueryParams, IndexQueryResponse, IndexSortOrder,
+    RELATION_VISIBILITY_NOT_CONFIGURED_CODE, RecommendationReason, SubmitIndexingRequestRequest,
+    SubmitIndexingRequestResponse,
 };
+use kukuri_cn_safety::RiskSignalTarget;
+use kukuri_cn_trust::{TrustParams, UniformRelationWeight, build_trust_read};
+use sqlx::postgres::PgPool;
 
 use crate::errors::{IndexingError, IndexingOperation, indexing_error};
+use crate::rate_limit::PubkeyRateLimitRoute;
 use crate::state::{RelationVisibilityState, UserApiState};
 
 /// user からの indexing request を受け付けて保存する(#413 / ADR 0025 §2.2 / §6.3)。
@@ -55,6 +67,10 @@ pub(crate) async fn submit_indexing_request(
         ));
     }
     let identity = require_bearer_identity(&state.pool, &state.jwt_config, &headers).await?;
+    require_writable(&identity)?;
+    state
+        .pubkey_rate_limit
+        .check(PubkeyRateLimitRoute::IndexingRequest, &identity.pubkey)?;
     let _ = require_consents(&state.pool, identity.pubkey.as_str()).await?;
 
     let kind = match request.kind.trim() {
@@ -117,19 +133,75 @@ pub(crate) async fn submit_indexing_request(
     }))
 }
 
-fn index_query_response(entries: Vec<kukuri_cn_indexer::IndexedEntry>) -> IndexQueryResponse {
+fn index_query_response(
+    entries: Vec<kukuri_cn_indexer::IndexedEntry>,
+    next: Option<IndexPosition>,
+    order: IndexSortOrder,
+) -> IndexQueryResponse {
     IndexQueryResponse {
         entries: entries
             .into_iter()
-            .map(|entry| IndexEntryView {
-                scope_kind: entry.scope_kind,
-                scope_id: entry.scope_id,
-                object_id: entry.object_id,
-                author_pubkey: entry.author_pubkey,
-                text: entry.text,
-                created_at: entry.created_at,
-            })
+            .map(|entry| index_entry_view(entry, Vec::new()))
             .collect(),
+        next_cursor: next.map(|position| IndexCursor::After { order, position }.encode()),
+    }
+}
+
+fn index_entry_view(
+    entry: kukuri_cn_indexer::IndexedEntry,
+    reasons: Vec<RecommendationReason>,
+) -> IndexEntryView {
+    IndexEntryView {
+        scope_kind: entry.scope_kind,
+        scope_id: entry.scope_id,
+        object_id: entry.object_id,
+        author_pubkey: entry.author_pubkey,
+        text: entry.text,
+        created_at: entry.created_at,
+        reasons,
+        entry_kind: entry.entry_kind,
+        profile: entry.profile,
+        session: entry.session,
+    }
+}
+
+/// recommendation ranking の著者 trust 読み口。risk signal 入力(Postgres)を trust read と
+/// 同じ合成式(`build_trust_read`)で畳む。risk signal の無い著者は中立(0)のまま返さない。
+struct PgAuthorTrust {
+    pool: PgPool,
+    params: TrustParams,
+}
+
+#[async_trait]
+impl AuthorTrustSource for PgAuthorTrust {
+    async fn composed_trust(
+        &self,
+        authors: &[String],
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<HashMap<String, f64>> {
+        let now_rfc3339 = now.to_rfc3339();
+        let mut trust = HashMap::new();
+        for author in authors {
+            let inputs = list_trust_risk_inputs(
+                &self.pool,
+                RiskSignalTarget::UserPubkey,
+                author.as_str(),
+                now_rfc3339.as_str(),
+            )
+            .await?;
+            if inputs.absolute.is_empty() && inputs.relative.is_empty() {
+                continue;
+            }
+            let view = build_trust_read(
+                author.as_str(),
+                &inputs,
+                now,
+                &self.params,
+                &UniformRelationWeight::default(),
+            );
+            trust.insert(author.clone(), view.trust);
+        }
+        Ok(trust)
     }
 }
 
@@ -164,6 +236,9 @@ async fn require_index_query(
         ));
     }
     let identity = require_bearer_identity(&state.pool, &state.jwt_config, headers).await?;
+    state
+        .pubkey_rate_limit
+        .check(PubkeyRateLimitRoute::Search, &identity.pubkey)?;
     let _ = require_consents(&state.pool, identity.pubkey.as_str()).await?;
     Ok((index_query, relation_visibility, identity.pubkey))
 }
@@ -295,10 +370,83 @@ fn index_query_limit(params: &IndexQueryParams) -> usize {
     params.limit.unwrap_or(20)
 }
 
+fn invalid_index_query(message: impl Into<String>) -> ApiError {
+    ApiError::new(StatusCode::BAD_REQUEST, "INVALID_INDEX_QUERY", message)
+}
+
+/// 著者 / 期間 / 並び順パラメータを解釈する(cursor は読み口ごとに別途解釈する)。
+fn parse_index_filter_params(params: &IndexQueryParams) -> Result<IndexQueryFilter, ApiError> {
+    if let (Some(since), Some(until)) = (params.since, params.until)
+        && since >= until
+    {
+        return Err(invalid_index_query("since must be earlier than until"));
+    }
+    let order = match params
+        .order
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    {
+        Some(order) => {
+            IndexSortOrder::parse(order).map_err(|error| invalid_index_query(error.to_string()))?
+        }
+        None => IndexSortOrder::default(),
+    };
+    Ok(IndexQueryFilter {
+        entry_kind: IndexEntryKind::Post,
+        author_pubkey: params
+            .author_pubkey
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string),
+        since: params.since,
+        until: params.until,
+        order,
+        after: None,
+    })
+}
+
+/// cursor パラメータを復号する。改ざん・破損した cursor は 400。
+fn parse_index_cursor(params: &IndexQueryParams) -> Result<Option<IndexCursor>, ApiError> {
+    params
+        .cursor
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|token| {
+            IndexCursor::decode(token).map_err(|_| invalid_index_query("cursor is invalid"))
+        })
+        .transpose()
+}
+
+/// search / discovery 用: 絞り込み条件に keyset cursor の続き位置を載せる。
+///
+/// 別の読み口(recommendation)の cursor や、並び順を変えての再利用は 400。
+fn parse_keyset_filter_params(params: &IndexQueryParams) -> Result<IndexQueryFilter, ApiError> {
+    let mut filter = parse_index_filter_params(params)?;
+    match parse_index_cursor(params)? {
+        None => {}
+        Some(IndexCursor::After { order, position }) if order == filter.order => {
+            filter.after = Some(position);
+        }
+        Some(_) => {
+            return Err(invalid_index_query(
+                "cursor does not belong to this query order",
+            ));
+        }
+    }
+    Ok(filter)
+}
+
 /// ユーザー向け検索(#404 / ADR 0025 §2.7)。
 ///
 /// `scope_kind` + `scope_id` 指定で topic 内検索(基本 UX)、無指定で supported set 横断検索
 /// (別画面)。結果は fail-closed query gate を通った `allow` verdict の entry のみ。
+///
+/// search / discovery は `author_pubkey` / `since` / `until` で絞れ、`next_cursor` を返して
+/// keyset で続きを辿れる。gate や distance opt-out で page が `limit` 未満に減っても、続きが
+/// あれば cursor は返る(空 page の後にも続きがありうる)。
 pub(crate) async fn index_search(
     State(state): State<UserApiState>,
     headers: HeaderMap,
@@ -319,13 +467,14 @@ pub(crate) async fn index_search(
             )
         })?;
     let limit = index_query_limit(&params);
-    let entries = match parse_index_scope_params(&params)? {
+    let filter = parse_keyset_filter_params(&params)?;
+    let page = match parse_index_scope_params(&params)? {
         Some((scope_kind, scope_id)) => {
             if scope_kind == IndexScopeKind::PrivateChannel {
                 require_channel_membership(&state, &headers, scope_id.as_str()).await?;
             }
             index_query
-                .search_scope(scope_kind, scope_id.as_str(), query, limit)
+                .search_scope_page(scope_kind, scope_id.as_str(), query, &filter, limit)
                 .await
                 .map_err(|source| {
                     IndexingError::infrastructure(IndexingOperation::SearchScope, source)
@@ -333,7 +482,7 @@ pub(crate) async fn index_search(
                 .map_err(indexing_error)?
         }
         None => index_query
-            .search_all(query, limit)
+            .search_all_page(query, &filter, limit)
             .await
             .map_err(|source| IndexingError::infrastructure(IndexingOperation::SearchAll, source))
             .map_err(indexing_error)?,
@@ -342,16 +491,17 @@ pub(crate) async fn index_search(
         &state,
         relation_visibility.as_ref(),
         viewer_pubkey.as_str(),
-        entries,
+        page.entries,
     )
     .await?;
-    Ok(Json(index_query_response(entries)))
+    Ok(Json(index_query_response(entries, page.next, filter.order)))
 }
 
 /// discovery(新着列挙。#404)。scope 指定で topic 内、無指定で supported set 横断。
 ///
 /// ranking / 関連度スコアリングの具体は ADR 0025 §4 でスコープ外のため、最小 surface として
-/// created_at 降順の新着を返す。critical / 非 allow verdict は fail-closed gate で入らない。
+/// created_at 降順(`order=oldest` で昇順)の新着を返す。critical / 非 allow verdict は
+/// fail-closed gate で入らない。
 pub(crate) async fn index_discovery(
     State(state): State<UserApiState>,
     headers: HeaderMap,
@@ -360,12 +510,17 @@ pub(crate) async fn index_discovery(
     let (index_query, relation_visibility, viewer_pubkey) =
         require_index_query(&state, &headers).await?;
     let limit = index_query_limit(&params);
+    let filter = parse_keyset_filter_params(&params)?;
     let scope = parse_index_scope_params(&params)?;
     if let Some((IndexScopeKind::PrivateChannel, scope_id)) = scope.as_ref() {
         require_channel_membership(&state, &headers, scope_id.as_str()).await?;
     }
-    let entries = index_query
-        .list_recent(scope.as_ref().map(|(kind, id)| (*kind, id.as_str())), limit)
+    let page = index_query
+        .list_recent_page(
+            scope.as_ref().map(|(kind, id)| (*kind, id.as_str())),
+            &filter,
+            limit,
+        )
         .await
         .map_err(|source| IndexingError::infrastructure(IndexingOperation::Discovery, source))
         .map_err(indexing_error)?;
@@ -373,16 +528,24 @@ pub(crate) async fn index_discovery(
         &state,
         relation_visibility.as_ref(),
         viewer_pubkey.as_str(),
-        entries,
+        page.entries,
     )
     .await?;
-    Ok(Json(index_query_response(entries)))
+    Ok(Json(index_query_response(entries, page.next, filter.order)))
 }
 
-/// recommendation(#404)。supported set 横断の新着列挙を最小 surface として返す。
+/// recommendation(#404)。supported set 横断の新着を候補にし、ranking stage で並べ替えて返す。
 ///
-/// ranking アルゴリズムの具体は ADR 0025 §4 でスコープ外。critical verdict が recommendation に
-/// 入らないことは fail-closed gate(真実源 + 最新 verdict 突合)が保証する。
+/// 候補は著者 / 期間で絞り、fail-closed gate と distance opt-out を通した後に
+/// `MAX_QUERY_LIMIT` 件まで集め、recency / engagement / 著者 trust / viewer proximity で
+/// score 付けした順位の `limit` 件ずつを、寄与した signal の reason code つきで返す。
+///
+/// recommendation が並べ替えるのは gate を通った新着 `MAX_QUERY_LIMIT` 件だけで、それより古い
+/// entry は score に関わらず候補に入らない。続き読みの cursor は先頭 page の基準時刻と候補 window の
+/// 最新端を持ち回り、続きの page も同じ時刻で recency を測り、後から入った新着を候補に混ぜない。
+/// 位置は順位内の offset ではなく `(score, created_at, scope_id, object_id)` の keyset なので、
+/// 候補が増減しても重複・欠落しない。ranking は候補を並べ替えるだけなので、critical verdict が
+/// recommendation に入らないことは引き続き gate が保証する。
 pub(crate) async fn index_recommendations(
     State(state): State<UserApiState>,
     headers: HeaderMap,
@@ -390,20 +553,310 @@ pub(crate) async fn index_recommendations(
 ) -> ApiResult<Json<IndexQueryResponse>> {
     let (index_query, relation_visibility, viewer_pubkey) =
         require_index_query(&state, &headers).await?;
-    let limit = index_query_limit(&params);
-    let entries = index_query
-        .list_recent(None, limit)
+    let limit = clamp_query_limit(index_query_limit(&params));
+    if params.order.is_some() {
+        return Err(invalid_index_query(
+            "recommendations are ranked and do not accept order",
+        ));
+    }
+    let mut filter = parse_index_filter_params(&params)?;
+    let (now, watermark, after) = match parse_index_cursor(&params)? {
+        None => (Utc::now(), None, None),
+        Some(IndexCursor::Ranked {
+            snapshot_at,
+            watermark,
+            after,
+        }) => (
+            DateTime::from_timestamp(snapshot_at, 0)
+                .ok_or_else(|| invalid_index_query("cursor is invalid"))?,
+            watermark,
+            Some(after),
+        ),
+        Some(_) => {
+            return Err(invalid_index_query(
+                "cursor does not belong to recommendations",
+            ));
+        }
+    };
+    if let Some(watermark) = watermark.as_ref() {
+        let bound = watermark.created_at.saturating_add(1);
+        filter.until = Some(filter.until.map_or(bound, |until| until.min(bound)));
+    }
+    let mut candidates = index_query
+        .list_recent_page(None, &filter, MAX_QUERY_LIMIT)
         .await
         .map_err(|source| IndexingError::infrastructure(IndexingOperation::Recommendations, source))
+        .map_err(indexing_error)?
+        .entries;
+    let watermark = match watermark {
+        Some(watermark) => {
+            // 同じ秒に後から入った entry も先頭 page の window には無かったので除く。
+            candidates.retain(|entry| watermark.covers(entry));
+            Some(watermark)
+        }
+        None => candidates.first().map(IndexPosition::of),
+    };
+    let candidates = filter_index_entries(
+        &state,
+        relation_visibility.as_ref(),
+        viewer_pubkey.as_str(),
+        candidates,
+    )
+    .await?;
+    let trust_params = state
+        .trust_read
+        .as_ref()
+        .map(|trust_read| trust_read.params.clone())
+        .unwrap_or_default();
+    let ranker = RecommendationRanker::new(
+        state.index_entries.clone(),
+        relation_visibility.relation.clone(),
+        Arc::new(PgAuthorTrust {
+            pool: state.pool.clone(),
+            params: trust_params,
+        }),
+    );
+    let ranked = ranker
+        .rank(viewer_pubkey.as_str(), candidates, MAX_QUERY_LIMIT, now)
+        .await
+        .map_err(|source| {
+            IndexingError::infrastructure(IndexingOperation::RankRecommendations, source)
+        })
         .map_err(indexing_error)?;
+    let mut page = ranked
+        .into_iter()
+        .filter(|ranked| after.as_ref().is_none_or(|after| after.precedes(ranked)))
+        .take(limit + 1)
+        .collect::<Vec<_>>();
+    let next_cursor = if page.len() > limit {
+        page.truncate(limit);
+        page.last().map(|last| {
+            IndexCursor::Ranked {
+                snapshot_at: now.timestamp(),
+                watermark: watermark.clone(),
+                after: RankedPosition::of(last),
+            }
+            .encode()
+        })
+    } else {
+        None
+    };
+    Ok(Json(IndexQueryResponse {
+        entries: page
+            .into_iter()
+            .map(|ranked| index_entry_view(ranked.entry, ranked.reasons))
+            .collect(),
+        next_cursor,
+    }))
+}
+
+/// people search が 1 要求で投影を読み進める page 数の上限。
+///
+/// 同じ著者の 2 件目以降や opt-out 済みの著者を読み飛ばして page を埋めるが、読み進める量は
+/// 有界にする。上限に達したら読めた分だけ返し、続きは cursor で辿る。
+const PEOPLE_SCAN_PAGES: usize = 5;
+
+/// people search(profile entry の全文検索)。
+///
+/// index 済み post を持つ著者の署名済み profile(name / display_name / about)を `q` で検索する。
+/// `scope_kind=public_topic` + `scope_id` 指定でその topic の著者に絞れる。profile は private
+/// channel からは投影しないため、private channel scope は 400。
+///
+/// 結果は post と同じ fail-closed gate と distance opt-out を通したうえで、relation opt-out を
+/// 選択した user を proximity に関係なく除く(見知らぬ相手を探す surface なので一覧に載せない)。
+/// 同じ著者の profile は topic ごとに投影され、topic ごとに本文が異なりうるため、`q` に一致して
+/// gate を通った hit の並びで著者ごとに最初の 1 件だけを返す。続きの page では cursor より前に
+/// 同じ著者の hit があるかを確かめるので、cursor で続きを辿っても著者は 1 回しか出ない。読み
+/// 飛ばした分は続きの hit で page を埋める。
+pub(crate) async fn index_people(
+    State(state): State<UserApiState>,
+    headers: HeaderMap,
+    Query(params): Query<IndexQueryParams>,
+) -> ApiResult<Json<IndexQueryResponse>> {
+    let (index_query, relation_visibility, viewer_pubkey) =
+        require_index_query(&state, &headers).await?;
+    let query = params
+        .q
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| invalid_index_query("q is required"))?;
+    let limit = clamp_query_limit(index_query_limit(&params));
+    let filter = IndexQueryFilter {
+        entry_kind: IndexEntryKind::Profile,
+        ..parse_keyset_filter_params(&params)?
+    };
+    let scope = parse_index_scope_params(&params)?;
+    if let Some((IndexScopeKind::PrivateChannel, _)) = scope.as_ref() {
+        return Err(invalid_index_query(
+            "people search does not cover private channels",
+        ));
+    }
+    let scope = scope
+        .as_ref()
+        .map(|(kind, scope_id)| (*kind, scope_id.as_str()));
+
+    let mut window = filter.clone();
+    let mut seen_authors = HashSet::new();
+    let mut people = Vec::new();
+    let mut next = None;
+    for _ in 0..PEOPLE_SCAN_PAGES {
+        let page = search_people_page(index_query.as_ref(), scope, query, &window, limit).await?;
+        let entries = filter_index_entries(
+            &state,
+            relation_visibility.as_ref(),
+            viewer_pubkey.as_str(),
+            page.entries,
+        )
+        .await?;
+        let authors: Vec<String> = entries
+            .iter()
+            .map(|entry| entry.author_pubkey.clone())
+            .collect();
+        let listed: HashSet<String> =
+            filter_relation_listed(&state.pool, viewer_pubkey.as_str(), authors.as_slice())
+                .await
+                .map_err(|source| {
+                    IndexingError::infrastructure(IndexingOperation::FilterRelationListed, source)
+                })
+                .map_err(indexing_error)?
+                .into_iter()
+                .collect();
+        for entry in entries {
+            if !listed.contains(&entry.author_pubkey)
+                || !seen_authors.insert(entry.author_pubkey.clone())
+            {
+                continue;
+            }
+            // cursor より前の page で同じ著者の hit を返していれば、これは 2 件目。
+            if filter.after.is_some() {
+                let first = first_people_hit(
+                    index_query.as_ref(),
+                    scope,
+                    query,
+                    &filter,
+                    entry.author_pubkey.as_str(),
+                )
+                .await?;
+                if first.as_ref() != Some(&IndexPosition::of(&entry)) {
+                    continue;
+                }
+            }
+            people.push(entry);
+            if people.len() == limit {
+                break;
+            }
+        }
+        if people.len() == limit {
+            next = people.last().map(IndexPosition::of);
+            break;
+        }
+        next = page.next;
+        match next.as_ref() {
+            Some(position) => window.after = Some(position.clone()),
+            None => break,
+        }
+    }
+    Ok(Json(index_query_response(people, next, filter.order)))
+}
+
+/// people search の読み口(scope 指定で topic 内、無指定で supported set 横断)から 1 page 読む。
+async fn search_people_page(
+    index_query: &dyn IndexQuery,
+    scope: Option<(IndexScopeKind, &str)>,
+    query: &str,
+    filter: &IndexQueryFilter,
+    limit: usize,
+) -> ApiResult<IndexPage> {
+    match scope {
+        Some((scope_kind, scope_id)) => {
+            index_query
+                .search_scope_page(scope_kind, scope_id, query, filter, limit)
+                .await
+        }
+        None => index_query.search_all_page(query, filter, limit).await,
+    }
+    .map_err(|source| IndexingError::infrastructure(IndexingOperation::People, source))
+    .map_err(indexing_error)
+}
+
+/// 著者の profile hit のうち、`filter` の並びで gate を通った最初のものの位置(cursor は無視する)。
+///
+/// relation の絞り込みは著者単位なので、同じ著者の hit はどれも同じ判定になり、ここでは見ない。
+async fn first_people_hit(
+    index_query: &dyn IndexQuery,
+    scope: Option<(IndexScopeKind, &str)>,
+    query: &str,
+    filter: &IndexQueryFilter,
+    author_pubkey: &str,
+) -> ApiResult<Option<IndexPosition>> {
+    let mut window = IndexQueryFilter {
+        author_pubkey: Some(author_pubkey.to_string()),
+        after: None,
+        ..filter.clone()
+    };
+    loop {
+        let page = search_people_page(index_query, scope, query, &window, MAX_QUERY_LIMIT).await?;
+        if let Some(first) = page.entries.first() {
+            return Ok(Some(IndexPosition::of(first)));
+        }
+        match page.next {
+            Some(position) => window.after = Some(position),
+            None => return Ok(None),
+        }
+    }
+}
+
+/// live now(進行中の live session / game room の列挙)。
+///
+/// `session` entry だけを対象に、`q` 指定で告知文(title / description)の全文検索、無指定で
+/// 開始の新しい順の列挙を返す。scope 指定で topic / private channel 内に絞れ、private channel は
+/// discovery と同じく membership の提示が必須。終了した session は ingest 時に index から
+/// 外れるため載らない。gate と distance opt-out は post と同じものを通す。
+pub(crate) async fn index_live(
+    State(state): State<UserApiState>,
+    headers: HeaderMap,
+    Query(params): Query<IndexQueryParams>,
+) -> ApiResult<Json<IndexQueryResponse>> {
+    let (index_query, relation_visibility, viewer_pubkey) =
+        require_index_query(&state, &headers).await?;
+    let query = params
+        .q
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let limit = index_query_limit(&params);
+    let filter = IndexQueryFilter {
+        entry_kind: IndexEntryKind::Session,
+        ..parse_keyset_filter_params(&params)?
+    };
+    let scope = parse_index_scope_params(&params)?;
+    if let Some((IndexScopeKind::PrivateChannel, scope_id)) = scope.as_ref() {
+        require_channel_membership(&state, &headers, scope_id.as_str()).await?;
+    }
+    let page = match (query, scope.as_ref()) {
+        (Some(query), Some((scope_kind, scope_id))) => {
+            index_query
+                .search_scope_page(*scope_kind, scope_id.as_str(), query, &filter, limit)
+                .await
+        }
+        (Some(query), None) => index_query.search_all_page(query, &filter, limit).await,
+        (None, scope) => {
+            index_query
+                .list_recent_page(scope.map(|(kind, id)| (*kind, id.as_str())), &filter, limit)
+                .await
+        }
+    }
+    .map_err(|source| IndexingError::infrastructure(IndexingOperation::Live, source))
+    .map_err(indexing_error)?;
     let entries = filter_index_entries(
         &state,
         relation_visibility.as_ref(),
         viewer_pubkey.as_str(),
-        entries,
+        page.entries,
     )
     .await?;
-    Ok(Json(index_query_response(entries)))
+    Ok(Json(index_query_response(entries, page.next, filter.order)))
 }
 
 /// channel secret 登録失敗を HTTP 応答へマップする。
@@ -443,7 +896,10 @@ mod error_contract_tests {
             IndexingOperation::SearchAll,
             IndexingOperation::Discovery,
             IndexingOperation::Recommendations,
+            IndexingOperation::People,
+            IndexingOperation::Live,
             IndexingOperation::FilterRelationVisibility,
+            IndexingOperation::FilterRelationListed,
         ] {
             assert_error_contract(
                 indexing_error(IndexingError::infrastructure(
@@ -4,5 +4,6 @@ pub(crate) mod auth;
 pub(crate) mod bootstrap;
 pub(crate) mod consents;
 pub(crate) mod indexing;
+pub(crate) mod moderation;
 pub(crate) mod reports;
 pub(crate) mod trust_relation;
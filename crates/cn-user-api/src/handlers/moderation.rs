@@ -0,0 +1,71 @@
+//! signed moderation event の pull 配布(ADR 0027 §2.1 / §2.6)。
+
+use axum::Json;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use kukuri_cn_core::{
+    ApiError, ApiResult, DistributionAudience, ModerationEventCursor,
+    list_distributable_moderation_events, require_bearer_identity,
+};
+use kukuri_cn_protocol::{
+    MODERATION_EVENTS_DEFAULT_LIMIT, MODERATION_EVENTS_MAX_LIMIT, ModerationEventAudience,
+    ModerationEventsQuery, ModerationEventsResponse,
+};
+
+use crate::errors::internal_error;
+use crate::state::UserApiState;
+
+/// 署名済み moderation event を新しい順に keyset cursor で返す。
+///
+/// `local` は返さない。bearer で認証できた subscriber には `subscribed_nodes` + `public`、
+/// 匿名には `public` のみを返す(`trust_pull` と同じ audience 判定)。event は advisory であり、
+/// 扱いは client が issuer 鍵で検証したうえで端末内 policy で決める。
+pub(crate) async fn moderation_events(
+    State(state): State<UserApiState>,
+    headers: HeaderMap,
+    Query(query): Query<ModerationEventsQuery>,
+) -> ApiResult<Json<ModerationEventsResponse>> {
+    let audience = match require_bearer_identity(&state.pool, &state.jwt_config, &headers).await {
+        Ok(_) => ModerationEventAudience::SubscribedNodes,
+        Err(_) => ModerationEventAudience::Public,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(MODERATION_EVENTS_DEFAULT_LIMIT)
+        .clamp(1, MODERATION_EVENTS_MAX_LIMIT);
+    let cursor = query
+        .cursor
+        .as_deref()
+        .map(ModerationEventCursor::decode)
+        .transpose()
+        .map_err(|_| {
+            ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "INVALID_MODERATION_EVENTS_QUERY",
+                "cursor is invalid",
+            )
+        })?;
+    // 1 件多く読んで次ページの有無を判定する。
+    let mut events = list_distributable_moderation_events(
+        &state.pool,
+        match audience {
+            ModerationEventAudience::SubscribedNodes => DistributionAudience::SubscribedNodes,
+            ModerationEventAudience::Public => DistributionAudience::Public,
+        },
+        (limit + 1) as i64,
+        cursor.as_ref(),
+    )
+    .await
+    .map_err(internal_error)?;
+    let has_more = events.len() > limit;
+    events.truncate(limit);
+    let next_cursor = events
+        .last()
+        .filter(|_| has_more)
+        .map(|last| ModerationEventCursor::after(last).encode());
+    Ok(Json(ModerationEventsResponse {
+        audience,
+        items: events.into_iter().map(|stored| stored.event).collect(),
+        next_cursor,
+    }))
+}
@@ -2,14 +2,16 @@
 
 use axum::Json;
 use axum::extract::State;
-use axum::http::StatusCode;
+use axum::http::header::AUTHORIZATION;
+use axum::http::{HeaderMap, StatusCode};
 use kukuri_cn_core::{
     ApiError, ApiResult, NewCommunityNodeReport, insert_community_node_appeal,
-    insert_community_node_report,
+    insert_community_node_report, require_bearer_identity, require_writable,
 };
 use kukuri_cn_protocol::{CommunityNodeReportRequest, CommunityNodeReportResponse};
 
 use crate::errors::{SupportEndpointError, SupportEndpointOperation, support_endpoint_error};
+use crate::rate_limit::PubkeyRateLimitRoute;
 use crate::state::UserApiState;
 
 /// 通報受信リクエスト(#370)。client(#310)が provenance + manifest authority scope で
@@ -24,8 +26,14 @@ use crate::state::UserApiState;
 /// node の authority scope への opt-in であり、中央通報窓口を作らない。通報先の解決自体は client
 /// (#310)が provenance + manifest authority scope で行っているため、ここへ届く時点で対象は
 /// この node が関与した範囲に絞られている。reporter の identity / social graph は保持しない。
+///
+/// bearer 付きで届いた通報は鍵単位の予算(`PubkeyRateLimitRoute::Report`)と
+/// 読み取り専用の制裁を適用し、bearer が不正・失効なら 401 / 403 で拒否する。identity は
+/// 予算と制裁の判定にだけ使い、通報の記録には残さない。Authorization header の無い匿名通報は
+/// 制裁の対象外とし、IP 段だけで制限する。
 pub(crate) async fn submit_report(
     State(state): State<UserApiState>,
+    headers: HeaderMap,
     Json(request): Json<CommunityNodeReportRequest>,
 ) -> ApiResult<Json<CommunityNodeReportResponse>> {
     // report endpoint capability が無効な node は通報を受け付けない。
@@ -41,6 +49,15 @@ pub(crate) async fn submit_report(
             "this community node does not accept reports",
         ));
     }
+    // Authorization header が付いていれば検証の失敗をそのまま返す。不正・失効した bearer を
+    // 匿名扱いへ落とすと、制裁中の鍵が header を壊すだけで制裁と鍵単位の予算を迂回できる。
+    if headers.contains_key(AUTHORIZATION) {
+        let identity = require_bearer_identity(&state.pool, &state.jwt_config, &headers).await?;
+        require_writable(&identity)?;
+        state
+            .pubkey_rate_limit
+            .check(PubkeyRateLimitRoute::Report, &identity.pubkey)?;
+    }
 
     let subject_kind = request.subject_kind.trim();
     let subject_id = request.subject_id.trim();
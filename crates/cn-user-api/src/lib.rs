@@ -5,7 +5,7 @@
 //!
 //! - `config` … 起動設定(env 読込)と rate limit 設定
 //! - `state` … 実行時 state(DI)と構築
-//! - `rate_limit` … per-client rate limit layer
+//! - `rate_limit` … per-client rate limit layer と per-pubkey の route 予算
 //! - `routes` … route 定義(パスは kukuri-cn-protocol の共有定数)と起動
 //! - `errors` … 共通のエラー写像
 //! - `handlers/` … ドメイン別ハンドラ(auth / consents / reports / indexing /
@@ -13,7 +13,10 @@
 
 mod admin;
 mod admin_action_render;
+mod admin_api;
 mod admin_appeal_render;
+mod admin_login_render;
+mod admin_session;
 mod admin_shell;
 mod config;
 mod errors;
@@ -22,7 +25,10 @@ mod rate_limit;
 mod routes;
 mod state;
 
-pub use config::{RateLimitConfig, UserApiConfig};
+pub use config::{
+    PubkeyRateLimitConfig, PubkeyRouteBudget, RateLimitConfig, RendezvousBackendConfig,
+    UserApiConfig,
+};
 pub use rate_limit::apply_rate_limit;
 pub use routes::{app_router, manifest_routes, run_from_env};
 pub use state::{RelationVisibilityState, TrustReadState, UserApiState, build_state};
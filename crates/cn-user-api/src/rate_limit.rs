@@ -1,12 +1,26 @@
-//! 公開 HTTP surface への per-client rate limit(設定は `RateLimitConfig`)。
+//! 公開 HTTP surface への rate limit。
+//!
+//! - IP 段: router 全体に掛ける per-client layer(設定は `RateLimitConfig`)。
+//! - pubkey 段: 認証済み route の handler が bearer identity ごとに route 予算を検査する
+//!   (設定は `PubkeyRateLimitConfig`)。拒否数は admin dashboard に出す。
+
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use axum::Router;
+use axum::http::{StatusCode, header};
+use governor::clock::Clock;
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
+use kukuri_cn_core::ApiError;
+use kukuri_cn_protocol::PUBKEY_RATE_LIMITED_CODE;
 use tower_governor::GovernorLayer;
 use tower_governor::governor::GovernorConfigBuilder;
 use tower_governor::key_extractor::{KeyExtractor, PeerIpKeyExtractor, SmartIpKeyExtractor};
 
-use crate::config::RateLimitConfig;
+use crate::config::{PubkeyRateLimitConfig, PubkeyRouteBudget, RateLimitConfig};
 
 /// Apply the rate limit layer to `router` when enabled. Layering returns a plain
 /// `Router` regardless of the key-extractor type, so both branches unify cleanly.
@@ -51,3 +65,244 @@ where
     });
     Ok(router.layer(GovernorLayer::new(governor)))
 }
+
+/// pubkey 段の予算を分ける route 区分。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PubkeyRateLimitRoute {
+    Report,
+    IndexingRequest,
+    RendezvousHeartbeat,
+    Search,
+}
+
+impl PubkeyRateLimitRoute {
+    pub(crate) const ALL: [Self; 4] = [
+        Self::Report,
+        Self::IndexingRequest,
+        Self::RendezvousHeartbeat,
+        Self::Search,
+    ];
+
+    fn index(self) -> usize {
+        match self {
+            Self::Report => 0,
+            Self::IndexingRequest => 1,
+            Self::RendezvousHeartbeat => 2,
+            Self::Search => 3,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Report => "report",
+            Self::IndexingRequest => "indexing_request",
+            Self::RendezvousHeartbeat => "rendezvous_heartbeat",
+            Self::Search => "search",
+        }
+    }
+
+    /// admin dashboard の表示名。
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Report => "通報",
+            Self::IndexingRequest => "索引申請",
+            Self::RendezvousHeartbeat => "接続情報の更新",
+            Self::Search => "検索・発見・おすすめ",
+        }
+    }
+
+    fn budget(self, config: &PubkeyRateLimitConfig) -> PubkeyRouteBudget {
+        match self {
+            Self::Report => config.report,
+            Self::IndexingRequest => config.indexing_request,
+            Self::RendezvousHeartbeat => config.rendezvous_heartbeat,
+            Self::Search => config.search,
+        }
+    }
+}
+
+struct PubkeyRouteLimiter {
+    limiter: Option<DefaultKeyedRateLimiter<String>>,
+    budget: PubkeyRouteBudget,
+    rejected: AtomicU64,
+}
+
+/// admin dashboard に出す route ごとの拒否数(プロセス起動からの累計)。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PubkeyRateLimitCounter {
+    pub(crate) route: PubkeyRateLimitRoute,
+    pub(crate) budget: PubkeyRouteBudget,
+    pub(crate) rejected: u64,
+}
+
+/// bearer identity(pubkey)単位の route 予算。user API と admin dashboard が同じ
+/// instance を共有し、拒否数をそのまま表示する。
+pub(crate) struct PubkeyRateLimiter {
+    enabled: bool,
+    routes: [PubkeyRouteLimiter; 4],
+}
+
+impl PubkeyRateLimiter {
+    pub(crate) fn new(config: &PubkeyRateLimitConfig) -> Self {
+        let routes = PubkeyRateLimitRoute::ALL.map(|route| {
+            let budget = route.budget(config);
+            PubkeyRouteLimiter {
+                limiter: config
+                    .enabled
+                    .then(|| RateLimiter::keyed(route_quota(budget))),
+                budget,
+                rejected: AtomicU64::new(0),
+            }
+        });
+        Self {
+            enabled: config.enabled,
+            routes,
+        }
+    }
+
+    pub(crate) fn disabled() -> Self {
+        Self::new(&PubkeyRateLimitConfig::default())
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// `pubkey` の `route` 予算を 1 消費する。超過時は 429(`PUBKEY_RATE_LIMITED`)を返し、
+    /// 拒否数を数える。
+    pub(crate) fn check(&self, route: PubkeyRateLimitRoute, pubkey: &str) -> Result<(), ApiError> {
+        let entry = &self.routes[route.index()];
+        let Some(limiter) = entry.limiter.as_ref() else {
+            return Ok(());
+        };
+        let Err(not_until) = limiter.check_key(&pubkey.to_string()) else {
+            return Ok(());
+        };
+        entry.rejected.fetch_add(1, Ordering::Relaxed);
+        let wait = not_until.wait_time_from(limiter.clock().now());
+        let retry_after_secs = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+        Err(ApiError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            PUBKEY_RATE_LIMITED_CODE,
+            format!(
+                "too many {} requests for this key; retry after {retry_after_secs}s",
+                route.as_str()
+            ),
+        )
+        .with_header(header::RETRY_AFTER, retry_after_secs.max(1).to_string()))
+    }
+
+    pub(crate) fn counters(&self) -> Vec<PubkeyRateLimitCounter> {
+        PubkeyRateLimitRoute::ALL
+            .into_iter()
+            .map(|route| {
+                let entry = &self.routes[route.index()];
+                PubkeyRateLimitCounter {
+                    route,
+                    budget: entry.budget,
+                    rejected: entry.rejected.load(Ordering::Relaxed),
+                }
+            })
+            .collect()
+    }
+
+    /// 補充し切った鍵の bucket を捨てる(鍵の数だけ state が増え続けないように)。
+    fn retain_recent(&self) {
+        for entry in &self.routes {
+            if let Some(limiter) = entry.limiter.as_ref() {
+                limiter.retain_recent();
+                limiter.shrink_to_fit();
+            }
+        }
+    }
+}
+
+fn route_quota(budget: PubkeyRouteBudget) -> Quota {
+    let per_minute = NonZeroU32::new(budget.per_minute).unwrap_or(NonZeroU32::MIN);
+    let burst = NonZeroU32::new(budget.burst).unwrap_or(NonZeroU32::MIN);
+    Quota::per_minute(per_minute).allow_burst(burst)
+}
+
+/// pubkey 段の idle bucket を定期回収する task を起動する(IP 段の `apply_governor` と同じ周期)。
+pub(crate) fn spawn_pubkey_rate_limit_gc(limiter: Arc<PubkeyRateLimiter>) {
+    if !limiter.is_enabled() {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            limiter.retain_recent();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::response::IntoResponse;
+
+    use super::*;
+
+    fn config_with_search(burst: u32) -> PubkeyRateLimitConfig {
+        PubkeyRateLimitConfig {
+            enabled: true,
+            search: PubkeyRouteBudget {
+                per_minute: 1,
+                burst,
+            },
+            ..PubkeyRateLimitConfig::default()
+        }
+    }
+
+    #[test]
+    fn pubkey_budget_is_per_key_and_per_route() {
+        let limiter = PubkeyRateLimiter::new(&config_with_search(2));
+
+        assert!(limiter.check(PubkeyRateLimitRoute::Search, "alice").is_ok());
+        assert!(limiter.check(PubkeyRateLimitRoute::Search, "alice").is_ok());
+        let rejected = limiter
+            .check(PubkeyRateLimitRoute::Search, "alice")
+            .expect_err("burst を超えた 3 件目は拒否する");
+        let response = rejected.into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+
+        assert!(
+            limiter.check(PubkeyRateLimitRoute::Search, "bob").is_ok(),
+            "別の鍵は別の bucket"
+        );
+        assert!(
+            limiter
+                .check(PubkeyRateLimitRoute::RendezvousHeartbeat, "alice")
+                .is_ok(),
+            "別 route の予算は消費しない"
+        );
+
+        let counters = limiter.counters();
+        let search = counters
+            .iter()
+            .find(|counter| counter.route == PubkeyRateLimitRoute::Search)
+            .expect("search counter");
+        assert_eq!(search.rejected, 1);
+        assert!(
+            counters
+                .iter()
+                .filter(|counter| counter.route != PubkeyRateLimitRoute::Search)
+                .all(|counter| counter.rejected == 0)
+        );
+    }
+
+    #[test]
+    fn disabled_pubkey_limiter_never_rejects() {
+        let limiter = PubkeyRateLimiter::disabled();
+        for _ in 0..100 {
+            assert!(limiter.check(PubkeyRateLimitRoute::Report, "alice").is_ok());
+        }
+        assert!(
+            limiter
+                .counters()
+                .iter()
+                .all(|counter| counter.rejected == 0)
+        );
+    }
+}
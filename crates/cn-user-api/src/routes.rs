@@ -13,31 +13,36 @@ use axum::routing::{get, post};
 use axum::{Json, Router};
 use kukuri_cn_operator::CommunityNodeManifest;
 use kukuri_cn_protocol::{
-    AUTH_CHALLENGE_PATH, AUTH_VERIFY_PATH, BOOTSTRAP_HEARTBEAT_PATH, BOOTSTRAP_NODES_PATH,
-    CONSENTS_PATH, CONSENTS_STATUS_PATH, INDEX_DISCOVERY_PATH, INDEX_RECOMMENDATIONS_PATH,
-    INDEX_SEARCH_PATH, INDEXING_REQUESTS_PATH, NODE_MANIFEST_PATH, RELATION_NEIGHBORS_PATH,
-    RELATION_OPTOUT_PATH, RELATION_USERS_ROUTE, REPORT_PATH, TOPIC_RENDEZVOUS_HEARTBEAT_PATH,
-    TRUST_USERS_ROUTE,
+    ADMISSION_VOUCHES_PATH, AUTH_CHALLENGE_PATH, AUTH_LOGOUT_PATH, AUTH_REFRESH_PATH,
+    AUTH_VERIFY_PATH, BOOTSTRAP_HEARTBEAT_PATH, BOOTSTRAP_NODES_PATH, CONSENTS_PATH,
+    CONSENTS_STATUS_PATH, INDEX_DISCOVERY_PATH, INDEX_LIVE_PATH, INDEX_PEOPLE_PATH,
+    INDEX_RECOMMENDATIONS_PATH, INDEX_SEARCH_PATH, INDEXING_REQUESTS_PATH, MODERATION_EVENTS_PATH,
+    NODE_MANIFEST_PATH, RELATION_NEIGHBORS_PATH, RELATION_OPTOUT_PATH, RELATION_USERS_ROUTE,
+    REPORT_PATH, TOPIC_RENDEZVOUS_HEARTBEAT_PATH, TRUST_USERS_ROUTE,
 };
 use serde_json::{Value, json};
 use tower_http::trace::TraceLayer;
 
 use crate::admin::admin_router;
-use crate::config::{RateLimitConfig, UserApiConfig};
-use crate::handlers::auth::{auth_challenge, auth_verify};
+use crate::config::{PubkeyRateLimitConfig, RateLimitConfig, UserApiConfig};
+use crate::handlers::auth::{
+    auth_challenge, auth_logout, auth_refresh, auth_verify, submit_admission_vouch,
+};
 use crate::handlers::bootstrap::{
     bootstrap_heartbeat, bootstrap_nodes, topic_rendezvous_heartbeat,
 };
 use crate::handlers::consents::{accept_consents_handler, consent_status};
 use crate::handlers::indexing::{
-    index_discovery, index_recommendations, index_search, submit_indexing_request,
+    index_discovery, index_live, index_people, index_recommendations, index_search,
+    submit_indexing_request,
 };
+use crate::handlers::moderation::moderation_events;
 use crate::handlers::reports::submit_report;
 use crate::handlers::trust_relation::{
     relation_neighbors, relation_optout_clear, relation_optout_get, relation_optout_set,
     relation_user_read, trust_pull, trust_user_read,
 };
-use crate::rate_limit::apply_rate_limit;
+use crate::rate_limit::{apply_rate_limit, spawn_pubkey_rate_limit_gc};
 use crate::state::{ManifestState, UserApiState, build_runtime_state};
 
 pub fn app_router(state: UserApiState) -> Router {
@@ -49,6 +54,9 @@ pub fn app_router(state: UserApiState) -> Router {
         .route("/healthz", get(healthz))
         .route(AUTH_CHALLENGE_PATH, post(auth_challenge))
         .route(AUTH_VERIFY_PATH, post(auth_verify))
+        .route(AUTH_REFRESH_PATH, post(auth_refresh))
+        .route(AUTH_LOGOUT_PATH, post(auth_logout))
+        .route(ADMISSION_VOUCHES_PATH, post(submit_admission_vouch))
         .route(CONSENTS_STATUS_PATH, get(consent_status))
         .route(CONSENTS_PATH, post(accept_consents_handler))
         .route(BOOTSTRAP_NODES_PATH, get(bootstrap_nodes))
@@ -62,8 +70,11 @@ pub fn app_router(state: UserApiState) -> Router {
         .route(INDEX_SEARCH_PATH, get(index_search))
         .route(INDEX_DISCOVERY_PATH, get(index_discovery))
         .route(INDEX_RECOMMENDATIONS_PATH, get(index_recommendations))
+        .route(INDEX_PEOPLE_PATH, get(index_people))
+        .route(INDEX_LIVE_PATH, get(index_live))
         .route(TRUST_USERS_ROUTE, get(trust_user_read))
         .route("/v1/trust/pull/{pubkey}", get(trust_pull))
+        .route(MODERATION_EVENTS_PATH, get(moderation_events))
         .route(RELATION_USERS_ROUTE, get(relation_user_read))
         .route(RELATION_NEIGHBORS_PATH, get(relation_neighbors))
         .route(
@@ -179,7 +190,12 @@ pub async fn run_from_env() -> Result<()> {
     let config = UserApiConfig::from_env()?;
     let bind_addr = config.bind_addr;
     let rate_limit = RateLimitConfig::from_env()?;
-    let state = build_runtime_state(&config).await?;
+    let pubkey_rate_limit = PubkeyRateLimitConfig::from_env()?;
+    let state = build_runtime_state(&config)
+        .await?
+        .with_pubkey_rate_limit(&pubkey_rate_limit);
+    spawn_pubkey_rate_limit_gc(state.pubkey_rate_limit.clone());
+    state.rendezvous_store.spawn_prune_task();
     let admin_bind_addr = std::env::var("COMMUNITY_NODE_ADMIN_BIND_ADDR")
         .ok()
         .filter(|value| !value.trim().is_empty())
@@ -196,6 +212,15 @@ pub async fn run_from_env() -> Result<()> {
             "community-node user-api rate limit enabled"
         );
     }
+    if pubkey_rate_limit.enabled {
+        tracing::info!(
+            report = ?pubkey_rate_limit.report,
+            indexing_request = ?pubkey_rate_limit.indexing_request,
+            rendezvous_heartbeat = ?pubkey_rate_limit.rendezvous_heartbeat,
+            search = ?pubkey_rate_limit.search,
+            "community-node user-api per-pubkey rate limit enabled"
+        );
+    }
     let listener = tokio::net::TcpListener::bind(bind_addr)
         .await
         .with_context(|| format!("failed to bind user api at {bind_addr}"))?;
@@ -5,12 +5,12 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use kukuri_cn_core::{
-    ChannelSecretCipher, DatabaseInitMode, JwtConfig, PgIndexEntryStore, TopicRendezvousStore,
-    connect_postgres, initialize_database, initialize_database_for_runtime,
+    ChannelSecretCipher, DatabaseInitMode, IndexEntryStore, JwtConfig, PgIndexEntryStore,
+    TopicRendezvousStore, connect_postgres, initialize_database, initialize_database_for_runtime,
     latest_readiness_activation, readiness_context_fingerprint,
 };
 use kukuri_cn_indexer::{
-    ArcadeDbConfig, ArcadeDbProjection, ArcadeDbRelationGraph, FailClosedIndexQuery, IndexQuery,
+    FailClosedIndexQuery, IndexBackendConfig, IndexQuery, open_index_query, relation_store,
 };
 use kukuri_cn_operator::{
     CommunityNodeManifest, READINESS_CHECK_IDS, build_manifest, generate_all, load_and_validate,
@@ -19,7 +19,8 @@ use kukuri_cn_protocol::{CommunityNodeBootstrapNode, CommunityNodeResolvedUrls};
 use kukuri_cn_trust::{RelationStore, TrustParams};
 use sqlx::postgres::PgPool;
 
-use crate::config::UserApiConfig;
+use crate::config::{PubkeyRateLimitConfig, RendezvousBackendConfig, UserApiConfig};
+use crate::rate_limit::PubkeyRateLimiter;
 
 #[derive(Clone)]
 pub struct UserApiState {
@@ -39,11 +40,15 @@ pub struct UserApiState {
     /// fail-closed query gate(`FailClosedIndexQuery`)を通した読み口のみを持つ。
     /// None = 設定無効。readiness activation は起動後も変化するため、各requestで検査する。
     pub(crate) index_query: Option<Arc<dyn IndexQuery>>,
+    /// recommendation ranking が engagement を読む index 真実源(既定は Postgres)。
+    pub(crate) index_entries: Arc<dyn IndexEntryStore>,
     /// trust / relation read surface(#415 / ADR 0026)。
     /// None = 設定無効。readiness activation は起動後も変化するため、各requestで検査する。
     pub(crate) trust_read: Option<Arc<TrustReadState>>,
     /// user / post surfacing に適用する node-local distance opt-out 判定依存。
     pub(crate) relation_visibility: Option<Arc<RelationVisibilityState>>,
+    /// bearer identity 単位の route 予算。admin dashboard と共有して拒否数を表示する。
+    pub(crate) pubkey_rate_limit: Arc<PubkeyRateLimiter>,
     readiness_activation_requirement: Option<ReadinessActivationRequirement>,
 }
 
@@ -57,7 +62,7 @@ struct ReadinessActivationRequirement {
 /// trust / relation read surface の依存一式(#415)。
 ///
 /// trust の入力(risk signal)は Postgres(`UserApiState::pool`)から読み、relation は
-/// graph backend(本番 = ArcadeDB、テスト = in-memory)から読む。
+/// graph backend(本番 = ArcadeDB または Postgres、テスト = in-memory)から読む。
 pub struct TrustReadState {
     /// trust 合成のパラメータ(operator 可変, ADR 0026 §6.2)。
     pub params: TrustParams,
@@ -92,6 +97,12 @@ impl UserApiState {
         self
     }
 
+    /// recommendation ranking の engagement 読み口を差し替える(テスト用の in-memory 真実源注入)。
+    pub fn with_index_entries(mut self, index_entries: Arc<dyn IndexEntryStore>) -> Self {
+        self.index_entries = index_entries;
+        self
+    }
+
     /// trust / relation read surface を差し替える(テスト用の in-memory relation 注入、
     /// または明示的な有効化)。
     pub fn with_trust_read(mut self, trust_read: Arc<TrustReadState>) -> Self {
@@ -108,6 +119,12 @@ impl UserApiState {
         self
     }
 
+    /// pubkey 段の rate limit を設定する(既定は無効)。
+    pub fn with_pubkey_rate_limit(mut self, config: &PubkeyRateLimitConfig) -> Self {
+        self.pubkey_rate_limit = Arc::new(PubkeyRateLimiter::new(config));
+        self
+    }
+
     /// 起動後もactivationの期限・失効を各read requestで再確認する。
     pub(crate) async fn readiness_activation_is_valid(&self) -> bool {
         let Some(requirement) = self.readiness_activation_requirement.as_ref() else {
@@ -150,10 +167,13 @@ pub(crate) async fn build_runtime_state(config: &UserApiConfig) -> Result<UserAp
 }
 
 async fn build_state_from_pool(config: &UserApiConfig, pool: PgPool) -> Result<UserApiState> {
-    let rendezvous_store = TopicRendezvousStore::new(
-        config.rendezvous_redis_url.as_str(),
-        config.rendezvous_key_prefix.as_str(),
-    )?;
+    let rendezvous_store = match &config.rendezvous {
+        RendezvousBackendConfig::Redis { url, key_prefix } => {
+            TopicRendezvousStore::new(url.as_str(), key_prefix.as_str())?
+        }
+        RendezvousBackendConfig::Postgres => TopicRendezvousStore::postgres(pool.clone()),
+        RendezvousBackendConfig::Memory => TopicRendezvousStore::in_memory(),
+    };
     let LoadedManifest {
         manifest,
         public_disclosures,
@@ -221,14 +241,21 @@ async fn build_state_from_pool(config: &UserApiConfig, pool: PgPool) -> Result<U
         }
     }
 
-    // ユーザー向け index query(#404)。有効時のみ ArcadeDB(投影)+ Postgres(真実源)を
+    // index 投影 / relation graph の backend(ArcadeDB / embedded)。cn-indexer と同じ env を読む。
+    let index_backend = if config.index_query_enabled || config.trust_read_enabled {
+        Some(IndexBackendConfig::from_env()?)
+    } else {
+        None
+    };
+    // ユーザー向け index query(#404)。有効時のみ投影 + Postgres(真実源)を
     // fail-closed gate(`FailClosedIndexQuery`)で束ねる。読み口はこの gate 以外に作らない。
     let index_query: Option<Arc<dyn IndexQuery>> = if config.index_query_enabled {
-        let projection = ArcadeDbProjection::new(ArcadeDbConfig::from_env())
-            .context("failed to build ArcadeDB client for index query")?;
+        let backend = index_backend
+            .as_ref()
+            .context("index backend must be configured for index queries")?;
         let entries = PgIndexEntryStore::new(pool.clone());
         Some(Arc::new(FailClosedIndexQuery::new(
-            Arc::new(projection),
+            open_index_query(backend)?,
             Arc::new(entries),
         )))
     } else {
@@ -241,17 +268,19 @@ async fn build_state_from_pool(config: &UserApiConfig, pool: PgPool) -> Result<U
             let min_proximity = config
                 .relation_distance_optout_min_proximity
                 .context("relation distance opt-out policy is required for index/trust surfaces")?;
-            let relation = ArcadeDbRelationGraph::new(ArcadeDbConfig::from_env())
-                .context("failed to build ArcadeDB client for relation visibility")?;
+            let backend = index_backend
+                .as_ref()
+                .context("index backend must be configured for relation visibility")?;
             Some(Arc::new(RelationVisibilityState::new(
-                Arc::new(relation),
+                relation_store(backend, &pool)?,
                 min_proximity,
             )?))
         } else {
             None
         };
     // trust / relation read surface(#415)。有効時のみ trust パラメータ(operator 可変)を
-    // 検証つきで読み、relation graph(ArcadeDB。`cn-cli relation analyze` が構築する)へ接続する。
+    // 検証つきで読み、relation graph(ArcadeDB または Postgres。`cn-cli relation analyze` が
+    // 構築する)へ接続する。
     let trust_read: Option<Arc<TrustReadState>> = if config.trust_read_enabled {
         let params = TrustParams::from_env().context("invalid COMMUNITY_NODE_TRUST_* params")?;
         let relation = relation_visibility
@@ -263,6 +292,7 @@ async fn build_state_from_pool(config: &UserApiConfig, pool: PgPool) -> Result<U
     } else {
         None
     };
+    let index_entries: Arc<dyn IndexEntryStore> = Arc::new(PgIndexEntryStore::new(pool.clone()));
     Ok(UserApiState {
         pool,
         rendezvous_store,
@@ -279,8 +309,10 @@ async fn build_state_from_pool(config: &UserApiConfig, pool: PgPool) -> Result<U
         public_disclosures,
         channel_secret_cipher,
         index_query,
+        index_entries,
         trust_read,
         relation_visibility,
+        pubkey_rate_limit: Arc::new(PubkeyRateLimiter::disabled()),
         readiness_activation_requirement,
     })
 }
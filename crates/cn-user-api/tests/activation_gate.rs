@@ -18,7 +18,7 @@ use kukuri_cn_core::{
     record_readiness_activation, record_readiness_revocation,
 };
 use kukuri_cn_operator::READINESS_CHECK_IDS;
-use kukuri_cn_user_api::{UserApiConfig, app_router, build_state};
+use kukuri_cn_user_api::{RendezvousBackendConfig, UserApiConfig, app_router, build_state};
 use reqwest::{Client, StatusCode};
 
 mod support;
@@ -65,8 +65,10 @@ async fn spawn_api(
     let state = build_state(&UserApiConfig {
         bind_addr: addr,
         database_url: database_url.to_string(),
-        rendezvous_redis_url: integration_test_rendezvous_redis_url(),
-        rendezvous_key_prefix: format!("cn:test:{prefix}"),
+        rendezvous: RendezvousBackendConfig::Redis {
+            url: integration_test_rendezvous_redis_url(),
+            key_prefix: format!("cn:test:{prefix}"),
+        },
         base_url: base_url.clone(),
         public_base_url: base_url.clone(),
         connectivity_urls: vec!["http://127.0.0.1:13340".to_string()],
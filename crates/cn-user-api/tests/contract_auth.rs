@@ -4,10 +4,10 @@ mod support;
 
 use anyhow::Result;
 use kukuri_cn_core::{
-    AdmissionMode, add_allowlist, ban_subscriber, issue_invite_code, set_admission_mode,
-    unban_subscriber,
+    AdminOperation, AdmissionMode, SanctionKind, VouchPolicy, add_allowlist, apply_operator_action,
+    ban_subscriber, issue_invite_code, set_admission_mode, set_vouch_policy, unban_subscriber,
 };
-use kukuri_cn_protocol::build_auth_envelope_json;
+use kukuri_cn_protocol::{AuthVerifyResponse, build_auth_envelope_json, build_vouch_envelope_json};
 use kukuri_core::generate_keys;
 use reqwest::{Client, StatusCode};
 use sqlx::postgres::PgPool;
@@ -107,6 +107,167 @@ async fn auth_verify_rejects_capability_url_mismatch() -> Result<()> {
     server.shutdown().await
 }
 
+async fn refresh(
+    client: &Client,
+    base_url: &str,
+    refresh_token: &str,
+    endpoint_id: &str,
+) -> Result<(StatusCode, Option<AuthVerifyResponse>)> {
+    let response = client
+        .post(format!("{base_url}/v1/auth/refresh"))
+        .json(&serde_json::json!({
+            "refresh_token": refresh_token,
+            "endpoint_id": endpoint_id,
+            "addr_hint": "127.0.0.1:4100",
+        }))
+        .send()
+        .await?;
+    let status = response.status();
+    if status != StatusCode::OK {
+        return Ok((status, None));
+    }
+    Ok((status, Some(response.json().await?)))
+}
+
+async fn bearer_status(client: &Client, base_url: &str, access_token: &str) -> Result<StatusCode> {
+    Ok(client
+        .get(format!("{base_url}/v1/consents/status"))
+        .bearer_auth(access_token)
+        .send()
+        .await?
+        .status())
+}
+
+#[tokio::test]
+async fn auth_refresh_rotates_tokens_and_revokes_session_on_reuse() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api integration test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let server = TestServer::spawn(admin_database_url.as_str(), "cn_auth_refresh").await?;
+    let client = Client::new();
+    let pool = PgPool::connect(server.database.database_url.as_str()).await?;
+
+    let keys = generate_keys();
+    let session = authenticate_session(&client, &server.base_url, &keys, "peer-a").await?;
+    let first_refresh = session.refresh_token.clone().expect("refresh token");
+    assert!(session.refresh_expires_at.expect("refresh expiry") > session.expires_at);
+
+    // セッションは verify 時の endpoint に束縛される。別 endpoint からの refresh は消費せずに拒否する。
+    let (status, _) = refresh(&client, &server.base_url, first_refresh.as_str(), "peer-b").await?;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // refresh でも verify と同じく endpoint / addr_hint を bootstrap peer として登録し直す。
+    sqlx::query("DELETE FROM cn_bootstrap.peer_registrations")
+        .execute(&pool)
+        .await?;
+    let (status, rotated) =
+        refresh(&client, &server.base_url, first_refresh.as_str(), "peer-a").await?;
+    assert_eq!(status, StatusCode::OK);
+    let rotated = rotated.expect("refresh response");
+    assert_eq!(rotated.pubkey, keys.public_key_hex());
+    assert_ne!(rotated.access_token, session.access_token);
+    let registered = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT addr_hint FROM cn_bootstrap.peer_registrations
+         WHERE subscriber_pubkey = $1 AND endpoint_id = 'peer-a'",
+    )
+    .bind(keys.public_key_hex())
+    .fetch_optional(&pool)
+    .await?;
+    assert_eq!(registered, Some(Some("127.0.0.1:4100".to_string())));
+    let second_refresh = rotated
+        .refresh_token
+        .clone()
+        .expect("rotated refresh token");
+    assert_ne!(second_refresh, first_refresh);
+    assert_eq!(
+        bearer_status(&client, &server.base_url, rotated.access_token.as_str()).await?,
+        StatusCode::OK
+    );
+
+    // 使用済み refresh token の再提出は漏洩とみなし、セッションごと失効させる。
+    let (status, _) = refresh(&client, &server.base_url, first_refresh.as_str(), "peer-a").await?;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        bearer_status(&client, &server.base_url, rotated.access_token.as_str()).await?,
+        StatusCode::UNAUTHORIZED
+    );
+    let (status, _) = refresh(&client, &server.base_url, second_refresh.as_str(), "peer-a").await?;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    server.shutdown().await
+}
+
+#[tokio::test]
+async fn auth_logout_revokes_only_the_current_session() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api integration test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let server = TestServer::spawn(admin_database_url.as_str(), "cn_auth_logout").await?;
+    let client = Client::new();
+
+    let keys = generate_keys();
+    let session = authenticate_session(&client, &server.base_url, &keys, "peer-a").await?;
+    let other = authenticate_session(&client, &server.base_url, &keys, "peer-b").await?;
+
+    let logout = client
+        .post(format!("{}/v1/auth/logout", server.base_url))
+        .bearer_auth(session.access_token.as_str())
+        .send()
+        .await?;
+    assert_eq!(logout.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        bearer_status(&client, &server.base_url, session.access_token.as_str()).await?,
+        StatusCode::UNAUTHORIZED
+    );
+    let (status, _) = refresh(
+        &client,
+        &server.base_url,
+        session.refresh_token.as_deref().expect("refresh token"),
+        "peer-a",
+    )
+    .await?;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // 同じ subscriber の別端末のセッションは残る。
+    assert_eq!(
+        bearer_status(&client, &server.base_url, other.access_token.as_str()).await?,
+        StatusCode::OK
+    );
+    server.shutdown().await
+}
+
+#[tokio::test]
+async fn auth_refresh_is_rejected_after_ban_even_once_unbanned() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api integration test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let server = TestServer::spawn(admin_database_url.as_str(), "cn_auth_refresh_ban").await?;
+    let client = Client::new();
+    let pool = PgPool::connect(server.database.database_url.as_str()).await?;
+
+    let keys = generate_keys();
+    let session = authenticate_session(&client, &server.base_url, &keys, "peer-a").await?;
+    ban_subscriber(&pool, keys.public_key_hex().as_str()).await?;
+    assert!(unban_subscriber(&pool, keys.public_key_hex().as_str()).await?);
+
+    // ban はセッションを失効させるので、unban 後も古い refresh token では戻れない。
+    let (status, _) = refresh(
+        &client,
+        &server.base_url,
+        session.refresh_token.as_deref().expect("refresh token"),
+        "peer-a",
+    )
+    .await?;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        bearer_status(&client, &server.base_url, session.access_token.as_str()).await?,
+        StatusCode::UNAUTHORIZED
+    );
+    server.shutdown().await
+}
+
 #[tokio::test]
 async fn admission_open_mode_admits_new_subscriber() -> Result<()> {
     let Some(admin_database_url) = integration_test_admin_database_url() else {
@@ -443,3 +604,246 @@ async fn admission_unban_of_existing_member_restores_access() -> Result<()> {
     assert!(!token_after.is_empty());
     server.shutdown().await
 }
+
+fn impose(
+    pubkey: String,
+    kind: SanctionKind,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> AdminOperation {
+    AdminOperation::ImposeSanction {
+        pubkey,
+        kind,
+        reason: "初回の spam".to_string(),
+        expires_at,
+        report_ids: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn admission_suspension_rejects_verify_and_revokes_token_until_expiry() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api integration test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let server = TestServer::spawn(admin_database_url.as_str(), "cn_admission_suspend").await?;
+    let client = Client::new();
+    let pool = PgPool::connect(server.database.database_url.as_str()).await?;
+
+    let keys = generate_keys();
+    let (access_token, _) = authenticate(&client, &server.base_url, &keys, "peer-a", None).await?;
+    accept_required_consents(&client, &server.base_url, access_token.as_str()).await?;
+
+    // 現メンバーでも利用停止中は既存トークンが 401、再認証は SUSPENDED になる。
+    let suspension = apply_operator_action(
+        &pool,
+        "cn-cli",
+        &impose(
+            keys.public_key_hex(),
+            SanctionKind::Suspended,
+            Some(chrono::Utc::now() + chrono::Duration::hours(1)),
+        ),
+    )
+    .await?;
+    let revoked = client
+        .get(format!("{}/v1/bootstrap/nodes", server.base_url))
+        .bearer_auth(access_token.as_str())
+        .send()
+        .await?;
+    assert_eq!(revoked.status(), StatusCode::UNAUTHORIZED);
+    let (status, body) = raw_auth_verify(&client, &server.base_url, &keys, None).await?;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "SUSPENDED");
+
+    // 期限が過ぎれば、解除操作なしで元に戻る。
+    sqlx::query(
+        "UPDATE cn_user.subscriber_sanctions
+         SET expires_at = NOW() - INTERVAL '1 second'
+         WHERE id = $1",
+    )
+    .bind(suspension.after["sanction_id"].as_str())
+    .execute(&pool)
+    .await?;
+    let (token_after, _) = authenticate(&client, &server.base_url, &keys, "peer-a", None).await?;
+    assert!(!token_after.is_empty());
+    server.shutdown().await
+}
+
+#[tokio::test]
+async fn admission_read_only_keeps_auth_but_blocks_rendezvous_heartbeat() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api integration test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let server = TestServer::spawn(admin_database_url.as_str(), "cn_admission_read_only").await?;
+    let client = Client::new();
+    let pool = PgPool::connect(server.database.database_url.as_str()).await?;
+
+    let keys = generate_keys();
+    apply_operator_action(
+        &pool,
+        "cn-cli",
+        &impose(keys.public_key_hex(), SanctionKind::ReadOnly, None),
+    )
+    .await?;
+
+    // 読み取り専用は auth と参照系を保つ。
+    let (access_token, _) = authenticate(&client, &server.base_url, &keys, "peer-a", None).await?;
+    accept_required_consents(&client, &server.base_url, access_token.as_str()).await?;
+    let nodes = client
+        .get(format!("{}/v1/bootstrap/nodes", server.base_url))
+        .bearer_auth(access_token.as_str())
+        .send()
+        .await?;
+    assert_eq!(nodes.status(), StatusCode::OK);
+
+    let heartbeat = client
+        .post(format!(
+            "{}/v1/rendezvous/topics/heartbeat",
+            server.base_url
+        ))
+        .bearer_auth(access_token.as_str())
+        .json(&serde_json::json!({
+            "endpoint_id": "peer-a",
+            "addr_hint": null,
+            "joins": [],
+            "refreshes": [],
+            "leaves": []
+        }))
+        .send()
+        .await?;
+    assert_eq!(heartbeat.status(), StatusCode::FORBIDDEN);
+    let body = heartbeat.json::<serde_json::Value>().await?;
+    assert_eq!(body["code"], "SUBSCRIBER_READ_ONLY");
+    server.shutdown().await
+}
+
+async fn vouch(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    envelope: serde_json::Value,
+) -> Result<(StatusCode, serde_json::Value)> {
+    let response = client
+        .post(format!("{base_url}/v1/admission/vouches"))
+        .bearer_auth(access_token)
+        .json(&serde_json::json!({ "vouch_envelope_json": envelope }))
+        .send()
+        .await?;
+    Ok((response.status(), response.json().await?))
+}
+
+#[tokio::test]
+async fn admission_vouch_mode_admits_after_required_member_vouches() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api integration test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let server = TestServer::spawn(admin_database_url.as_str(), "cn_admission_vouch").await?;
+    let client = Client::new();
+    let pool = PgPool::connect(server.database.database_url.as_str()).await?;
+
+    // open mode の間に参加した member が、vouch mode 切り替え後の推薦者になる。
+    let member_a = generate_keys();
+    let member_b = generate_keys();
+    let (token_a, _) = authenticate(&client, &server.base_url, &member_a, "peer-a", None).await?;
+    let (token_b, _) = authenticate(&client, &server.base_url, &member_b, "peer-b", None).await?;
+    accept_required_consents(&client, &server.base_url, token_a.as_str()).await?;
+    accept_required_consents(&client, &server.base_url, token_b.as_str()).await?;
+    set_vouch_policy(
+        &pool,
+        VouchPolicy {
+            required_vouches: 2,
+            proximity_weighting: false,
+        },
+    )
+    .await?;
+    set_admission_mode(&pool, AdmissionMode::Vouch).await?;
+
+    let candidate = generate_keys();
+    let candidate_pubkey = candidate.public_key_hex();
+    let (status, body) = raw_auth_verify(&client, &server.base_url, &candidate, None).await?;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "VOUCH_REQUIRED");
+
+    // 他人の署名封筒は bearer の member として提出できない。
+    let (status, body) = vouch(
+        &client,
+        &server.base_url,
+        token_a.as_str(),
+        build_vouch_envelope_json(&member_b, candidate_pubkey.as_str(), &server.base_url)?,
+    )
+    .await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "ADMISSION_VOUCH_INVALID");
+
+    let envelope_a =
+        build_vouch_envelope_json(&member_a, candidate_pubkey.as_str(), &server.base_url)?;
+    let (status, body) = vouch(
+        &client,
+        &server.base_url,
+        token_a.as_str(),
+        envelope_a.clone(),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total_weight"], 1.0);
+    assert_eq!(body["required_weight"], 2.0);
+    // 同じ推薦者の再提出は重複して数えない。
+    let (status, body) = vouch(&client, &server.base_url, token_a.as_str(), envelope_a).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total_weight"], 1.0);
+    let (status, body) = raw_auth_verify(&client, &server.base_url, &candidate, None).await?;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "VOUCH_REQUIRED");
+
+    let (status, body) = vouch(
+        &client,
+        &server.base_url,
+        token_b.as_str(),
+        build_vouch_envelope_json(&member_b, candidate_pubkey.as_str(), &server.base_url)?,
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total_weight"], 2.0);
+    let (access_token, _) =
+        authenticate(&client, &server.base_url, &candidate, "peer-c", None).await?;
+    assert!(!access_token.is_empty());
+    server.shutdown().await
+}
+
+#[tokio::test]
+async fn admission_vouch_weighting_fails_closed_without_relation_graph() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api integration test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let server =
+        TestServer::spawn(admin_database_url.as_str(), "cn_admission_vouch_weighting").await?;
+    let client = Client::new();
+    let pool = PgPool::connect(server.database.database_url.as_str()).await?;
+
+    let member = generate_keys();
+    let (access_token, _) =
+        authenticate(&client, &server.base_url, &member, "peer-a", None).await?;
+    accept_required_consents(&client, &server.base_url, access_token.as_str()).await?;
+    set_vouch_policy(
+        &pool,
+        VouchPolicy {
+            required_vouches: 1,
+            proximity_weighting: true,
+        },
+    )
+    .await?;
+
+    let candidate = generate_keys();
+    let (status, body) = vouch(
+        &client,
+        &server.base_url,
+        access_token.as_str(),
+        build_vouch_envelope_json(&member, &candidate.public_key_hex(), &server.base_url)?,
+    )
+    .await?;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["code"], "ADMISSION_VOUCH_WEIGHTING_UNAVAILABLE");
+    server.shutdown().await
+}
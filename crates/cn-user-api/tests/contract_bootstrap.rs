@@ -4,6 +4,7 @@ mod support;
 
 use anyhow::{Context, Result};
 use kukuri_cn_core::USER_API_BEARER_CHALLENGE;
+use kukuri_cn_user_api::RendezvousBackendConfig;
 use kukuri_core::{
     TopicId, generate_keys, private_topic_rendezvous_key_hex_secret, public_topic_rendezvous_key,
 };
@@ -390,6 +391,38 @@ async fn topic_rendezvous_batch_heartbeat_returns_fresh_peer_candidates() -> Res
         return Ok(());
     };
     let server = TestServer::spawn(admin_database_url.as_str(), "cn_user_api_rendezvous").await?;
+    assert_topic_rendezvous_round_trip(&server).await?;
+    server.shutdown().await
+}
+
+#[tokio::test]
+async fn topic_rendezvous_postgres_backend_works_without_redis() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api integration test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let server = TestServer::spawn_with_rendezvous(
+        admin_database_url.as_str(),
+        "cn_user_api_rendezvous_pg",
+        RendezvousBackendConfig::Postgres,
+    )
+    .await?;
+    assert_topic_rendezvous_round_trip(&server).await?;
+
+    let pool = PgPool::connect(server.database.database_url.as_str()).await?;
+    let remaining: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM cn_bootstrap.topic_rendezvous_peers")
+            .fetch_one(&pool)
+            .await?;
+    // peer-b は leave 済み、peer-a の refresh だけが残る。
+    assert_eq!(remaining, 1);
+    pool.close().await;
+
+    server.shutdown().await
+}
+
+/// 2 endpoint で join / refresh / leave を往復し、候補の出入りを確かめる(backend 共通)。
+async fn assert_topic_rendezvous_round_trip(server: &TestServer) -> Result<()> {
     let client = Client::new();
 
     let keys_a = generate_keys();
@@ -525,8 +558,7 @@ async fn topic_rendezvous_batch_heartbeat_returns_fresh_peer_candidates() -> Res
         after_leave["topics"][0]["peers"].as_array().map(Vec::len),
         Some(0)
     );
-
-    server.shutdown().await
+    Ok(())
 }
 
 #[tokio::test]
@@ -575,11 +607,10 @@ async fn topic_rendezvous_keys_do_not_expose_raw_topic_ids() -> Result<()> {
         .await?
         .error_for_status()?;
 
-    let keys = redis_keys(
-        server.rendezvous_redis_url.as_str(),
-        format!("{}*", server.rendezvous_key_prefix).as_str(),
-    )
-    .await?;
+    let RendezvousBackendConfig::Redis { url, key_prefix } = &server.rendezvous else {
+        panic!("contract server should use the redis rendezvous backend");
+    };
+    let keys = redis_keys(url.as_str(), format!("{key_prefix}*").as_str()).await?;
     assert!(!keys.is_empty());
     let serialized_keys = keys.join("\n");
     assert!(!serialized_keys.contains(raw_public_topic.as_str()));
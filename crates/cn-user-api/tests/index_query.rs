@@ -14,17 +14,22 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use kukuri_cn_core::{
-    ChannelSecretCipher, IndexEntryStore, IndexScopeKind, JwtConfig, MemoryIndexEntryStore,
-    NewIndexEntry, TestDatabase, connect_postgres, register_channel_secret,
+    ChannelSecretCipher, EntryEngagement, IndexEntryStore, IndexScopeKind, JwtConfig,
+    MemoryIndexEntryStore, NewIndexEntry, TestDatabase, connect_postgres, register_channel_secret,
 };
 use kukuri_cn_indexer::projection::{IndexProjection, IndexedEntry, MemoryIndexProjection};
 use kukuri_cn_indexer::query::FailClosedIndexQuery;
-use kukuri_cn_protocol::{CHANNEL_MEMBERSHIP_SECRET_HEADER, build_auth_envelope_json};
+use kukuri_cn_protocol::{
+    CHANNEL_MEMBERSHIP_SECRET_HEADER, IndexEntryKind, IndexProfileView, IndexSessionKind,
+    IndexSessionStatus, IndexSessionView, build_auth_envelope_json,
+};
 use kukuri_cn_safety::provider::SubjectKind;
 use kukuri_cn_safety::{ReasonCode, SafetyAction, SafetyVerdict};
 use kukuri_cn_safety_runtime::{MemorySafetyArtifactStore, SafetyArtifactStore};
 use kukuri_cn_trust::{EdgeFeatures, FEATURE_SHARED_TOPICS, MemoryRelationStore, RelationStore};
-use kukuri_cn_user_api::{RelationVisibilityState, UserApiConfig, app_router, build_state};
+use kukuri_cn_user_api::{
+    RelationVisibilityState, RendezvousBackendConfig, UserApiConfig, app_router, build_state,
+};
 use kukuri_core::{KukuriKeys, generate_keys};
 use reqwest::{Client, StatusCode};
 
@@ -99,6 +104,27 @@ impl MemoryIndex {
         object_id: &str,
         author_pubkey: &str,
         text: &str,
+    ) -> Result<()> {
+        self.seed_allow_engaged(
+            scope_kind,
+            scope_id,
+            object_id,
+            author_pubkey,
+            text,
+            EntryEngagement::default(),
+        )
+        .await
+    }
+
+    /// 反応・返信数つきで seed する（recommendation の engagement signal 用）。
+    async fn seed_allow_engaged(
+        &self,
+        scope_kind: IndexScopeKind,
+        scope_id: &str,
+        object_id: &str,
+        author_pubkey: &str,
+        text: &str,
+        engagement: EntryEngagement,
     ) -> Result<()> {
         let verdict_id = self
             .store
@@ -119,6 +145,7 @@ impl MemoryIndex {
                 verdict_id,
                 verdict_action: "allow".to_string(),
                 critical: false,
+                engagement,
             })
             .await?;
         self.projection
@@ -130,6 +157,123 @@ impl MemoryIndex {
                 text: text.to_string(),
                 created_at: 1_700_000_000,
                 source_replica_id: format!("topic::{scope_id}"),
+                entry_kind: IndexEntryKind::Post,
+                profile: None,
+                session: None,
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// allow verdict つきの profile entry を seed する（ingest の people search 経路と同型）。
+    async fn seed_profile(&self, scope_id: &str, author_pubkey: &str, name: &str) -> Result<()> {
+        self.seed_profile_about(scope_id, author_pubkey, name, "tokio enthusiast")
+            .await
+    }
+
+    /// topic ごとに本文の違う profile を投影する（著者が topic ごとに別版の profile を署名した状態）。
+    async fn seed_profile_about(
+        &self,
+        scope_id: &str,
+        author_pubkey: &str,
+        name: &str,
+        about: &str,
+    ) -> Result<()> {
+        let object_id = format!("profile:{author_pubkey}");
+        let verdict_id = self
+            .store
+            .persist_verdict(
+                SubjectKind::User,
+                author_pubkey,
+                &verdict(SafetyAction::Allow, false),
+            )
+            .await?;
+        self.entries
+            .upsert_entry(&NewIndexEntry {
+                scope_kind: IndexScopeKind::PublicTopic,
+                scope_id: scope_id.to_string(),
+                object_id: object_id.clone(),
+                author_pubkey: author_pubkey.to_string(),
+                created_at: 1_700_000_000,
+                source_replica_id: format!("author::{author_pubkey}"),
+                verdict_id,
+                verdict_action: "allow".to_string(),
+                critical: false,
+                engagement: EntryEngagement::default(),
+            })
+            .await?;
+        self.projection
+            .upsert_entry(&IndexedEntry {
+                scope_kind: IndexScopeKind::PublicTopic,
+                scope_id: scope_id.to_string(),
+                object_id,
+                author_pubkey: author_pubkey.to_string(),
+                text: format!("{name}\n{about}"),
+                created_at: 1_700_000_000,
+                source_replica_id: format!("author::{author_pubkey}"),
+                entry_kind: IndexEntryKind::Profile,
+                profile: Some(IndexProfileView {
+                    name: Some(name.to_string()),
+                    display_name: None,
+                    about: Some(about.to_string()),
+                }),
+                session: None,
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// allow verdict つきの live session entry を seed する（ingest の live now 経路と同型）。
+    async fn seed_live_session(
+        &self,
+        scope_id: &str,
+        session_id: &str,
+        author_pubkey: &str,
+        title: &str,
+    ) -> Result<()> {
+        let object_id = format!("live:{session_id}");
+        let verdict_id = self
+            .store
+            .persist_verdict(
+                SubjectKind::Post,
+                object_id.as_str(),
+                &verdict(SafetyAction::Allow, false),
+            )
+            .await?;
+        self.entries
+            .upsert_entry(&NewIndexEntry {
+                scope_kind: IndexScopeKind::PublicTopic,
+                scope_id: scope_id.to_string(),
+                object_id: object_id.clone(),
+                author_pubkey: author_pubkey.to_string(),
+                created_at: 1_700_000_000,
+                source_replica_id: format!("topic::{scope_id}"),
+                verdict_id,
+                verdict_action: "allow".to_string(),
+                critical: false,
+                engagement: EntryEngagement::default(),
+            })
+            .await?;
+        self.projection
+            .upsert_entry(&IndexedEntry {
+                scope_kind: IndexScopeKind::PublicTopic,
+                scope_id: scope_id.to_string(),
+                object_id,
+                author_pubkey: author_pubkey.to_string(),
+                text: title.to_string(),
+                created_at: 1_700_000_000,
+                source_replica_id: format!("topic::{scope_id}"),
+                entry_kind: IndexEntryKind::Session,
+                profile: None,
+                session: Some(IndexSessionView {
+                    session_kind: IndexSessionKind::LiveSession,
+                    session_id: session_id.to_string(),
+                    topic_id: scope_id.to_string(),
+                    status: IndexSessionStatus::Live,
+                    title: title.to_string(),
+                    description: None,
+                    participant_count: None,
+                }),
             })
             .await?;
         Ok(())
@@ -161,7 +305,7 @@ impl TestServer {
     async fn spawn(
         admin_database_url: &str,
         prefix: &str,
-        index: Option<Arc<FailClosedIndexQuery>>,
+        index: Option<&MemoryIndex>,
     ) -> Result<Self> {
         Self::spawn_with_channel_secret_key(admin_database_url, prefix, index, None).await
     }
@@ -170,7 +314,7 @@ impl TestServer {
     async fn spawn_with_channel_secret_key(
         admin_database_url: &str,
         prefix: &str,
-        index: Option<Arc<FailClosedIndexQuery>>,
+        index: Option<&MemoryIndex>,
         channel_secret_key: Option<&str>,
     ) -> Result<Self> {
         let database = TestDatabase::create(admin_database_url, prefix).await?;
@@ -183,8 +327,10 @@ impl TestServer {
         let mut state = build_state(&UserApiConfig {
             bind_addr: addr,
             database_url: database.database_url.clone(),
-            rendezvous_redis_url: integration_test_rendezvous_redis_url(),
-            rendezvous_key_prefix: format!("cn:test:{prefix}"),
+            rendezvous: RendezvousBackendConfig::Redis {
+                url: integration_test_rendezvous_redis_url(),
+                key_prefix: format!("cn:test:{prefix}"),
+            },
             base_url: base_url.clone(),
             public_base_url: base_url.clone(),
             connectivity_urls: vec!["http://127.0.0.1:13340".to_string()],
@@ -201,7 +347,8 @@ impl TestServer {
         .await?;
         if let Some(index) = index {
             state = state
-                .with_index_query(index)
+                .with_index_query(index.query.clone())
+                .with_index_entries(index.entries.clone())
                 .with_relation_visibility(Arc::new(RelationVisibilityState::new(
                     relation.clone(),
                     0.5,
@@ -316,7 +463,7 @@ async fn index_query_requires_auth_and_consent() -> Result<()> {
     let server = TestServer::spawn(
         admin_database_url.as_str(),
         "cn_index_query_auth",
-        Some(index.query.clone()),
+        Some(&index),
     )
     .await?;
     let client = Client::new();
@@ -356,13 +503,16 @@ async fn search_discovery_recommendation_return_gated_allow_entries_only() -> Re
             text: "tokio ghost residue".to_string(),
             created_at: 1_700_000_001,
             source_replica_id: "topic::rust".to_string(),
+            entry_kind: IndexEntryKind::Post,
+            profile: None,
+            session: None,
         })
         .await?;
 
     let server = TestServer::spawn(
         admin_database_url.as_str(),
         "cn_index_query_gate",
-        Some(index.query.clone()),
+        Some(&index),
     )
     .await?;
     let client = Client::new();
@@ -410,6 +560,112 @@ async fn search_discovery_recommendation_return_gated_allow_entries_only() -> Re
     server.shutdown().await
 }
 
+#[tokio::test]
+async fn recommendations_rank_by_engagement_and_proximity_with_reasons() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api index query test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let index = memory_index();
+    let viewer_keys = generate_keys();
+    let viewer = viewer_keys.public_key_hex();
+    let plain_author = generate_keys().public_key_hex();
+    let engaged_author = generate_keys().public_key_hex();
+    let close_author = generate_keys().public_key_hex();
+    // 3 件とも同時刻（新しさは同点）。engagement / viewer 近接度だけが差になる。
+    index
+        .seed_allow("rust", "post-plain", plain_author.as_str(), "plain post")
+        .await?;
+    index
+        .seed_allow_engaged(
+            IndexScopeKind::PublicTopic,
+            "rust",
+            "post-engaged",
+            engaged_author.as_str(),
+            "engaged post",
+            EntryEngagement {
+                reactions: 20,
+                replies: 5,
+            },
+        )
+        .await?;
+    index
+        .seed_allow("rust", "post-close", close_author.as_str(), "close post")
+        .await?;
+
+    let server = TestServer::spawn(
+        admin_database_url.as_str(),
+        "cn_index_query_ranking",
+        Some(&index),
+    )
+    .await?;
+    server
+        .relation
+        .upsert_edge(
+            viewer.as_str(),
+            close_author.as_str(),
+            &EdgeFeatures::new().with(FEATURE_SHARED_TOPICS, 3.0),
+        )
+        .await?;
+    let client = Client::new();
+    let token = authenticate_and_consent(&client, server.base_url.as_str(), &viewer_keys).await?;
+
+    let response = client
+        .get(format!("{}/v1/index/recommendations", server.base_url))
+        .bearer_auth(token.as_str())
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.json::<serde_json::Value>().await?;
+    assert_eq!(
+        entry_ids(&body),
+        vec![
+            "post-engaged".to_string(),
+            "post-close".to_string(),
+            "post-plain".to_string(),
+        ]
+    );
+    let entries = body["entries"].as_array().expect("entries");
+    assert_eq!(entries[0]["reasons"], serde_json::json!(["engaged"]));
+    assert_eq!(
+        entries[1]["reasons"],
+        serde_json::json!(["close_to_viewer"])
+    );
+    // 根拠の無い項目は reasons を持たない（既存 client の wire 互換）。
+    assert!(entries[2].get("reasons").is_none());
+
+    // limit は ranking 後に適用される。
+    let limited = client
+        .get(format!(
+            "{}/v1/index/recommendations?limit=1",
+            server.base_url
+        ))
+        .bearer_auth(token.as_str())
+        .send()
+        .await?
+        .json::<serde_json::Value>()
+        .await?;
+    assert_eq!(entry_ids(&limited), vec!["post-engaged".to_string()]);
+
+    // discovery は新着順のままで reasons を付けない。
+    let discovery = client
+        .get(format!("{}/v1/index/discovery", server.base_url))
+        .bearer_auth(token.as_str())
+        .send()
+        .await?
+        .json::<serde_json::Value>()
+        .await?;
+    assert!(
+        discovery["entries"]
+            .as_array()
+            .expect("entries")
+            .iter()
+            .all(|entry| entry.get("reasons").is_none())
+    );
+
+    server.shutdown().await
+}
+
 #[tokio::test]
 async fn distance_optout_filters_posts_on_every_index_surface() -> Result<()> {
     let Some(admin_database_url) = integration_test_admin_database_url() else {
@@ -443,7 +699,7 @@ async fn distance_optout_filters_posts_on_every_index_surface() -> Result<()> {
     let server = TestServer::spawn(
         admin_database_url.as_str(),
         "cn_index_query_distance_optout",
-        Some(index.query.clone()),
+        Some(&index),
     )
     .await?;
     server
@@ -533,6 +789,448 @@ async fn distance_optout_filters_posts_on_every_index_surface() -> Result<()> {
     server.shutdown().await
 }
 
+#[tokio::test]
+async fn people_search_lists_profiles_once_and_keeps_opted_out_users_unlisted() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api index query test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let index = memory_index();
+    let viewer_keys = generate_keys();
+    let close_keys = generate_keys();
+    let other_keys = generate_keys();
+    let viewer = viewer_keys.public_key_hex();
+    let close = close_keys.public_key_hex();
+    let other = other_keys.public_key_hex();
+    index
+        .seed_allow("rust", "post-close", close.as_str(), "tokio post")
+        .await?;
+    // 同じ著者の profile は topic ごとに投影されるが、結果には 1 件だけ出る。
+    index.seed_profile("rust", close.as_str(), "close").await?;
+    index.seed_profile("async", close.as_str(), "close").await?;
+    index.seed_profile("rust", other.as_str(), "other").await?;
+
+    let server = TestServer::spawn(
+        admin_database_url.as_str(),
+        "cn_index_query_people",
+        Some(&index),
+    )
+    .await?;
+    server
+        .relation
+        .upsert_edge(
+            viewer.as_str(),
+            close.as_str(),
+            &EdgeFeatures::new().with(FEATURE_SHARED_TOPICS, 3.0),
+        )
+        .await?;
+    let client = Client::new();
+    let viewer_token =
+        authenticate_and_consent(&client, server.base_url.as_str(), &viewer_keys).await?;
+    let close_token =
+        authenticate_and_consent(&client, server.base_url.as_str(), &close_keys).await?;
+    let people = |token: String| {
+        let client = client.clone();
+        let url = format!("{}/v1/index/people?q=tokio", server.base_url);
+        async move {
+            client
+                .get(url)
+                .bearer_auth(token)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<serde_json::Value>()
+                .await
+        }
+    };
+
+    let body = people(viewer_token.clone()).await?;
+    let mut ids = entry_ids(&body);
+    ids.sort();
+    let mut expected = vec![format!("profile:{close}"), format!("profile:{other}")];
+    expected.sort();
+    assert_eq!(ids, expected);
+    let first = &body["entries"][0];
+    assert_eq!(first["entry_kind"], "profile");
+    assert_eq!(first["profile"]["about"], "tokio enthusiast");
+
+    // post 向けの surface に profile entry は混ざらない。
+    let body: serde_json::Value = client
+        .get(format!("{}/v1/index/search?q=tokio", server.base_url))
+        .bearer_auth(viewer_token.as_str())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    assert_eq!(entry_ids(&body), vec!["post-close".to_string()]);
+
+    // relation opt-out した user は近い viewer からも people search に出ない（本人には出る）。
+    client
+        .put(format!("{}/v1/relation/optout", server.base_url))
+        .bearer_auth(close_token.as_str())
+        .send()
+        .await?
+        .error_for_status()?;
+    let body = people(viewer_token.clone()).await?;
+    assert_eq!(entry_ids(&body), vec![format!("profile:{other}")]);
+    let body = people(close_token.clone()).await?;
+    assert!(entry_ids(&body).contains(&format!("profile:{close}")));
+
+    // profile は private channel から投影しないため、channel scope は受け付けない。
+    let response = client
+        .get(format!(
+            "{}/v1/index/people?q=tokio&scope_kind=private_channel&scope_id=secret",
+            server.base_url
+        ))
+        .bearer_auth(viewer_token.as_str())
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    server.shutdown().await
+}
+
+#[tokio::test]
+async fn people_search_lists_each_author_once_across_pages() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api index query test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let index = memory_index();
+    let author = generate_keys().public_key_hex();
+    let other = generate_keys().public_key_hex();
+    // 同じ著者の profile が 2 topic に投影されていても、1 件ずつの page を辿って 1 回だけ出る。
+    index
+        .seed_profile("rust", author.as_str(), "author")
+        .await?;
+    index
+        .seed_profile("async", author.as_str(), "author")
+        .await?;
+    index.seed_profile("rust", other.as_str(), "other").await?;
+
+    let server = TestServer::spawn(
+        admin_database_url.as_str(),
+        "cn_index_query_people_pages",
+        Some(&index),
+    )
+    .await?;
+    let client = Client::new();
+    let keys = generate_keys();
+    let token = authenticate_and_consent(&client, &server.base_url, &keys).await?;
+    let mut ids = walk_pages(
+        &client,
+        server.base_url.as_str(),
+        &token,
+        "/v1/index/people?q=tokio&limit=1",
+    )
+    .await?;
+    ids.sort();
+    let mut expected = vec![format!("profile:{author}"), format!("profile:{other}")];
+    expected.sort();
+    assert_eq!(ids, expected);
+
+    server.shutdown().await
+}
+
+#[tokio::test]
+async fn people_search_lists_authors_by_the_profile_that_matched() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api index query test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let index = memory_index();
+    let author = generate_keys().public_key_hex();
+    let others: Vec<String> = (0..3).map(|_| generate_keys().public_key_hex()).collect();
+    // "async" の profile は q に一致しない。一致した "rust" の profile で著者が出る。
+    index
+        .seed_profile_about("async", author.as_str(), "author", "embedded rust")
+        .await?;
+    index
+        .seed_profile_about("rust", author.as_str(), "author", "tokio enthusiast")
+        .await?;
+    for other in &others {
+        index.seed_profile("rust", other.as_str(), "other").await?;
+        index.seed_profile("tokio", other.as_str(), "other").await?;
+    }
+
+    let server = TestServer::spawn(
+        admin_database_url.as_str(),
+        "cn_index_query_people_matched",
+        Some(&index),
+    )
+    .await?;
+    let client = Client::new();
+    let keys = generate_keys();
+    let token = authenticate_and_consent(&client, &server.base_url, &keys).await?;
+
+    let body: serde_json::Value = client
+        .get(format!("{}/v1/index/people?q=tokio", server.base_url))
+        .bearer_auth(token.as_str())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let entries = body["entries"].as_array().cloned().unwrap_or_default();
+    assert_eq!(entries.len(), 4);
+    let matched = entries
+        .iter()
+        .find(|entry| entry["object_id"] == format!("profile:{author}"))
+        .expect("the author whose rust profile matched is listed");
+    assert_eq!(matched["scope_id"], "rust");
+    assert_eq!(matched["profile"]["about"], "tokio enthusiast");
+
+    // 同じ著者の 2 件目を読み飛ばしても page は limit まで埋まり、続きでも著者は 1 回だけ出る。
+    let body: serde_json::Value = client
+        .get(format!(
+            "{}/v1/index/people?q=tokio&limit=2",
+            server.base_url
+        ))
+        .bearer_auth(token.as_str())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    assert_eq!(entry_ids(&body).len(), 2);
+    let mut ids = walk_pages(
+        &client,
+        server.base_url.as_str(),
+        &token,
+        "/v1/index/people?q=tokio&limit=2",
+    )
+    .await?;
+    ids.sort();
+    let mut expected: Vec<String> = std::iter::once(&author)
+        .chain(others.iter())
+        .map(|pubkey| format!("profile:{pubkey}"))
+        .collect();
+    expected.sort();
+    assert_eq!(ids, expected);
+
+    server.shutdown().await
+}
+
+#[tokio::test]
+async fn live_now_lists_only_session_entries() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api index query test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let index = memory_index();
+    let author = generate_keys().public_key_hex();
+    index
+        .seed_allow("rust", "post-1", author.as_str(), "tokio post")
+        .await?;
+    index
+        .seed_live_session("rust", "live-1", author.as_str(), "tokio live")
+        .await?;
+    index
+        .seed_live_session("rust", "live-2", author.as_str(), "async live")
+        .await?;
+    index.flip_to_excluded("live:live-2").await?;
+
+    let server = TestServer::spawn(
+        admin_database_url.as_str(),
+        "cn_index_query_live",
+        Some(&index),
+    )
+    .await?;
+    let client = Client::new();
+    let keys = generate_keys();
+    let token = authenticate_and_consent(&client, &server.base_url, &keys).await?;
+    let get = |path: &str| {
+        let request = client
+            .get(format!("{}{path}", server.base_url))
+            .bearer_auth(token.as_str());
+        async move {
+            request
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<serde_json::Value>()
+                .await
+        }
+    };
+
+    // q 無しは進行中 session の列挙。gate に落ちた session と post は載らない。
+    let body = get("/v1/index/live").await?;
+    assert_eq!(entry_ids(&body), vec!["live:live-1".to_string()]);
+    let entry = &body["entries"][0];
+    assert_eq!(entry["entry_kind"], "session");
+    assert_eq!(entry["session"]["session_kind"], "live_session");
+    assert_eq!(entry["session"]["status"], "live");
+    assert!(entry["session"].get("participant_count").is_none());
+
+    let body = get("/v1/index/live?q=tokio&scope_kind=public_topic&scope_id=rust").await?;
+    assert_eq!(entry_ids(&body), vec!["live:live-1".to_string()]);
+
+    // post 向けの surface に session entry は混ざらない。
+    let body = get("/v1/index/discovery").await?;
+    assert_eq!(entry_ids(&body), vec!["post-1".to_string()]);
+
+    server.shutdown().await
+}
+
+/// `next_cursor` を辿って全 page の object_id を集める（続きが無くなるまで）。
+async fn walk_pages(
+    client: &Client,
+    base_url: &str,
+    token: &str,
+    path: &str,
+) -> Result<Vec<String>> {
+    let mut ids = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..20 {
+        let mut request = client.get(format!("{base_url}{path}")).bearer_auth(token);
+        if let Some(cursor) = cursor.as_deref() {
+            request = request.query(&[("cursor", cursor)]);
+        }
+        let response = request.send().await?;
+        assert_eq!(response.status(), StatusCode::OK, "{path}");
+        let body = response.json::<serde_json::Value>().await?;
+        ids.extend(entry_ids(&body));
+        match body["next_cursor"].as_str() {
+            Some(next) => cursor = Some(next.to_string()),
+            None => return Ok(ids),
+        }
+    }
+    anyhow::bail!("{path}: pagination did not terminate")
+}
+
+#[tokio::test]
+async fn index_queries_page_with_cursor_and_filter_by_author_and_time() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!("skipping cn-user-api index query test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1");
+        return Ok(());
+    };
+    let index = memory_index();
+    let author = generate_keys().public_key_hex();
+    let other_author = generate_keys().public_key_hex();
+    // 同時刻の entry を複数置き、page 境界が同時刻をまたいでも重複・欠落しないことを見る。
+    for object_id in ["post-1", "post-2", "post-3", "post-4"] {
+        index
+            .seed_allow("rust", object_id, author.as_str(), "tokio post")
+            .await?;
+    }
+    index
+        .seed_allow("rust", "post-other", other_author.as_str(), "tokio other")
+        .await?;
+    // page の途中で gate に落とされる entry があっても続きは打ち切られない。
+    index.flip_to_excluded("post-2").await?;
+
+    let server = TestServer::spawn(
+        admin_database_url.as_str(),
+        "cn_index_query_paging",
+        Some(&index),
+    )
+    .await?;
+    let client = Client::new();
+    let keys = generate_keys();
+    let token = authenticate_and_consent(&client, &server.base_url, &keys).await?;
+    let base_url = server.base_url.as_str();
+
+    let newest = walk_pages(&client, base_url, &token, "/v1/index/discovery?limit=2").await?;
+    assert_eq!(
+        newest,
+        vec!["post-other", "post-4", "post-3", "post-1"],
+        "newest first, ties broken by object_id"
+    );
+    let oldest = walk_pages(
+        &client,
+        base_url,
+        &token,
+        "/v1/index/search?q=tokio&scope_kind=public_topic&scope_id=rust&order=oldest&limit=2",
+    )
+    .await?;
+    assert_eq!(oldest, vec!["post-1", "post-3", "post-4", "post-other"]);
+    let mut ranked = walk_pages(
+        &client,
+        base_url,
+        &token,
+        "/v1/index/recommendations?limit=2",
+    )
+    .await?;
+    ranked.sort();
+    assert_eq!(ranked, vec!["post-1", "post-3", "post-4", "post-other"]);
+
+    let by_author = walk_pages(
+        &client,
+        base_url,
+        &token,
+        format!("/v1/index/search?q=tokio&author_pubkey={other_author}").as_str(),
+    )
+    .await?;
+    assert_eq!(by_author, vec!["post-other"]);
+
+    // since は含み、until は含まない（seed は全件 created_at = 1_700_000_000）。
+    for (path, expected) in [
+        ("/v1/index/discovery?since=1700000000", 4),
+        ("/v1/index/discovery?since=1700000001", 0),
+        ("/v1/index/discovery?until=1700000000", 0),
+        ("/v1/index/recommendations?until=1700000001", 4),
+    ] {
+        let ids = walk_pages(&client, base_url, &token, path).await?;
+        assert_eq!(ids.len(), expected, "{path}");
+    }
+
+    // 別の読み口・別の並び順の cursor は使い回せない。
+    let first = client
+        .get(format!("{base_url}/v1/index/discovery?limit=1"))
+        .bearer_auth(token.as_str())
+        .send()
+        .await?
+        .json::<serde_json::Value>()
+        .await?;
+    let cursor = first["next_cursor"].as_str().expect("next cursor");
+    for path in [
+        format!("/v1/index/discovery?order=oldest&cursor={cursor}"),
+        format!("/v1/index/recommendations?cursor={cursor}"),
+    ] {
+        let response = client
+            .get(format!("{base_url}{path}"))
+            .bearer_auth(token.as_str())
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{path}");
+    }
+
+    // recommendation の続き読みは先頭 page の候補 window に固定され、page の間に入った新着で
+    // 順位がずれて重複・欠落しない。
+    let first = client
+        .get(format!("{base_url}/v1/index/recommendations?limit=2"))
+        .bearer_auth(token.as_str())
+        .send()
+        .await?
+        .json::<serde_json::Value>()
+        .await?;
+    let mut across_insert = entry_ids(&first);
+    index
+        .seed_allow("rust", "post-zz-late", author.as_str(), "tokio late")
+        .await?;
+    let rest = client
+        .get(format!("{base_url}/v1/index/recommendations?limit=10"))
+        .query(&[(
+            "cursor",
+            first["next_cursor"].as_str().expect("next cursor"),
+        )])
+        .bearer_auth(token.as_str())
+        .send()
+        .await?
+        .json::<serde_json::Value>()
+        .await?;
+    across_insert.extend(entry_ids(&rest));
+    assert!(rest["next_cursor"].is_null());
+    across_insert.sort();
+    assert_eq!(
+        across_insert,
+        vec!["post-1", "post-3", "post-4", "post-other"]
+    );
+
+    server.shutdown().await
+}
+
 #[tokio::test]
 async fn index_query_validates_parameters() -> Result<()> {
     let Some(admin_database_url) = integration_test_admin_database_url() else {
@@ -543,7 +1241,7 @@ async fn index_query_validates_parameters() -> Result<()> {
     let server = TestServer::spawn(
         admin_database_url.as_str(),
         "cn_index_query_params",
-        Some(index.query.clone()),
+        Some(&index),
     )
     .await?;
     let client = Client::new();
@@ -580,6 +1278,23 @@ async fn index_query_validates_parameters() -> Result<()> {
         .await?;
     assert_eq!(bad_kind.status(), StatusCode::BAD_REQUEST);
 
+    // 壊れた cursor / 未知の並び順 / 逆転した期間 / recommendation への並び順指定は 400。
+    for path in [
+        "/v1/index/discovery?cursor=not-a-cursor",
+        "/v1/index/search?q=hello&order=relevance",
+        "/v1/index/discovery?since=200&until=100",
+        "/v1/index/recommendations?order=newest",
+    ] {
+        let response = client
+            .get(format!("{}{path}", server.base_url))
+            .bearer_auth(token.as_str())
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{path}");
+        let body = response.json::<serde_json::Value>().await?;
+        assert_eq!(body["code"], "INVALID_INDEX_QUERY", "{path}");
+    }
+
     server.shutdown().await
 }
 
@@ -612,7 +1327,7 @@ async fn private_channel_reads_are_limited_to_members_with_secret_proof() -> Res
     let server = TestServer::spawn_with_channel_secret_key(
         admin_database_url.as_str(),
         "cn_index_query_membership",
-        Some(index.query.clone()),
+        Some(&index),
         Some(key_material),
     )
     .await?;
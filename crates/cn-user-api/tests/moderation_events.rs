@@ -0,0 +1,149 @@
+//! `GET /v1/moderation/events` の contract test。
+//!
+//! 配布境界(ADR 0027 §2.6): `local` は返さない、匿名は `public` のみ、bearer で認証できた
+//! subscriber は `subscribed_nodes` + `public`。応答の event は issuer 鍵で検証できる。
+//!
+//! Postgres + Redis を要するため `KUKURI_CN_RUN_INTEGRATION_TESTS=1` で gate する。
+
+use anyhow::Result;
+use kukuri_cn_core::{connect_postgres, persist_signed_moderation_event};
+use kukuri_cn_protocol::{
+    ModerationEventAudience, ModerationEventsResponse, verify_moderation_event,
+};
+use kukuri_cn_safety::{
+    Basis, ModerationAction, ModerationEventBody, ReasonCode, SafetyCategory, SafetyLabel,
+    Severity, SubjectKind, Visibility, issue_signed_event,
+};
+use kukuri_cn_safety_runtime::Secp256k1ModerationEventSigner;
+use kukuri_core::generate_keys;
+use reqwest::{Client, StatusCode};
+
+mod support;
+use support::{TestServer, authenticate, integration_test_admin_database_url};
+
+fn event_body(issuer: &str, id: &str, visibility: Visibility) -> ModerationEventBody {
+    ModerationEventBody {
+        id: id.to_string(),
+        issuer_node_id: issuer.to_string(),
+        target_type: SubjectKind::Post,
+        target_id: format!("post-{id}"),
+        action: ModerationAction::RiskLabel,
+        labels: vec![SafetyLabel::new(SafetyCategory::Spam)],
+        reason_code: ReasonCode::GeneralModeration,
+        severity: Severity::Medium,
+        confidence: Some(80),
+        basis: Basis::ClassifierScore,
+        visibility,
+        policy_version: "v1".to_string(),
+        created_at: "2026-10-01T00:00:00Z".to_string(),
+    }
+}
+
+fn event_ids(response: &ModerationEventsResponse) -> Vec<String> {
+    let mut ids = response
+        .items
+        .iter()
+        .map(|event| event.body.id.clone())
+        .collect::<Vec<_>>();
+    ids.sort();
+    ids
+}
+
+#[tokio::test]
+async fn moderation_events_pull_respects_visibility_and_pages() -> Result<()> {
+    let Some(admin_database_url) = integration_test_admin_database_url() else {
+        eprintln!(
+            "skipping cn-user-api moderation events test; set KUKURI_CN_RUN_INTEGRATION_TESTS=1"
+        );
+        return Ok(());
+    };
+    let server = TestServer::spawn(admin_database_url.as_str(), "cn_moderation_events").await?;
+    let pool = connect_postgres(server.database.database_url.as_str()).await?;
+    let client = Client::new();
+    let issuer_keys = generate_keys();
+    let issuer = issuer_keys.public_key_hex();
+    let signer = Secp256k1ModerationEventSigner::new(issuer_keys);
+    for (id, visibility) in [
+        ("public-1", Visibility::Public),
+        ("public-2", Visibility::Public),
+        ("subscribed", Visibility::SubscribedNodes),
+        ("local", Visibility::Local),
+    ] {
+        persist_signed_moderation_event(
+            &pool,
+            &issue_signed_event(event_body(issuer.as_str(), id, visibility), &signer),
+        )
+        .await?;
+    }
+    let url = format!("{}/v1/moderation/events", server.base_url);
+
+    let anonymous: ModerationEventsResponse = client
+        .get(url.as_str())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    assert_eq!(anonymous.audience, ModerationEventAudience::Public);
+    assert_eq!(event_ids(&anonymous), vec!["public-1", "public-2"]);
+    for event in &anonymous.items {
+        verify_moderation_event(event, issuer.as_str())?;
+    }
+
+    let (token, _) = authenticate(
+        &client,
+        server.base_url.as_str(),
+        &generate_keys(),
+        "peer-a",
+        None,
+    )
+    .await?;
+    let first_page: ModerationEventsResponse = client
+        .get(url.as_str())
+        .bearer_auth(token.as_str())
+        .query(&[("limit", "2")])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    assert_eq!(
+        first_page.audience,
+        ModerationEventAudience::SubscribedNodes
+    );
+    assert_eq!(first_page.items.len(), 2);
+    let cursor = first_page.next_cursor.clone().expect("next cursor");
+    // 先頭に event が増えても cursor の続きはずれない。
+    persist_signed_moderation_event(
+        &pool,
+        &issue_signed_event(
+            event_body(issuer.as_str(), "public-newer", Visibility::Public),
+            &signer,
+        ),
+    )
+    .await?;
+    let last_page: ModerationEventsResponse = client
+        .get(url.as_str())
+        .bearer_auth(token.as_str())
+        .query(&[("limit", "2"), ("cursor", cursor.as_str())])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    assert_eq!(last_page.items.len(), 1);
+    assert_eq!(last_page.next_cursor, None);
+    let mut ids = event_ids(&first_page);
+    ids.extend(event_ids(&last_page));
+    ids.sort();
+    assert_eq!(ids, vec!["public-1", "public-2", "subscribed"]);
+
+    let invalid_cursor = client
+        .get(url.as_str())
+        .query(&[("cursor", "not-a-cursor")])
+        .send()
+        .await?;
+    assert_eq!(invalid_cursor.status(), StatusCode::BAD_REQUEST);
+
+    server.shutdown().await
+}
@@ -14,7 +14,9 @@ use kukuri_cn_core::{
 };
 use kukuri_cn_protocol::{CommunityNodeReportAppeal, CommunityNodeReportRequest};
 use kukuri_cn_trust::{MemoryRelationStore, TrustParams};
-use kukuri_cn_user_api::{TrustReadState, UserApiConfig, app_router, build_state};
+use kukuri_cn_user_api::{
+    RendezvousBackendConfig, TrustReadState, UserApiConfig, app_router, build_state,
+};
 use kukuri_core::{KukuriKeys, generate_keys};
 use reqwest::{Client, StatusCode};
 
@@ -108,8 +110,10 @@ impl TestServer {
         let state = build_state(&UserApiConfig {
             bind_addr: addr,
             database_url: database.database_url.clone(),
-            rendezvous_redis_url: integration_test_rendezvous_redis_url(),
-            rendezvous_key_prefix: format!("cn:test:{prefix}"),
+            rendezvous: RendezvousBackendConfig::Redis {
+                url: integration_test_rendezvous_redis_url(),
+                key_prefix: format!("cn:test:{prefix}"),
+            },
             base_url: base_url.clone(),
             public_base_url: base_url.clone(),
             connectivity_urls: vec!["http://127.0.0.1:13340".to_string()],
@@ -240,6 +244,20 @@ async fn report_endpoint_accepts_stores_and_validates() -> Result<()> {
         .await?;
     assert_eq!(anonymous.status(), StatusCode::OK);
 
+    // 不正な bearer は匿名扱いに落とさず 401 で拒否する。
+    let invalid_bearer = client
+        .post(format!("{}/v1/report", server.base_url))
+        .bearer_auth("not-a-valid-token")
+        .json(&serde_json::json!({
+            "subject_kind": "post",
+            "subject_id": "object-123",
+            "capability": "community_index",
+            "reason": "spam",
+        }))
+        .send()
+        .await?;
+    assert_eq!(invalid_bearer.status(), StatusCode::UNAUTHORIZED);
+
     // 必須欠落は 400。
     let invalid = client
         .post(format!("{}/v1/report", server.base_url))
@@ -7,7 +7,7 @@ use std::net::SocketAddr;
 use anyhow::{Context, Result};
 use kukuri_cn_core::{JwtConfig, TestDatabase};
 use kukuri_cn_protocol::build_auth_envelope_json;
-use kukuri_cn_user_api::{UserApiConfig, app_router, build_state};
+use kukuri_cn_user_api::{RendezvousBackendConfig, UserApiConfig, app_router, build_state};
 use kukuri_core::KukuriKeys;
 use redis::AsyncCommands;
 use reqwest::{Client, StatusCode};
@@ -19,14 +19,24 @@ pub struct TestServer {
     pub task: tokio::task::JoinHandle<()>,
     pub database: TestDatabase,
     pub base_url: String,
-    pub rendezvous_redis_url: String,
-    pub rendezvous_key_prefix: String,
+    pub rendezvous: RendezvousBackendConfig,
 }
 
 impl TestServer {
     pub async fn spawn(admin_database_url: &str, prefix: &str) -> Result<Self> {
-        let rendezvous_redis_url = integration_test_rendezvous_redis_url();
-        let rendezvous_key_prefix = format!("cn:test:{prefix}");
+        let rendezvous = RendezvousBackendConfig::Redis {
+            url: integration_test_rendezvous_redis_url(),
+            key_prefix: format!("cn:test:{prefix}"),
+        };
+        Self::spawn_with_rendezvous(admin_database_url, prefix, rendezvous).await
+    }
+
+    /// rendezvous の backend を指定して起動する(Redis 以外の backend の contract 用)。
+    pub async fn spawn_with_rendezvous(
+        admin_database_url: &str,
+        prefix: &str,
+        rendezvous: RendezvousBackendConfig,
+    ) -> Result<Self> {
         let database = TestDatabase::create(admin_database_url, prefix).await?;
         let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
             .await
@@ -36,8 +46,7 @@ impl TestServer {
         let state = build_state(&UserApiConfig {
             bind_addr: addr,
             database_url: database.database_url.clone(),
-            rendezvous_redis_url: rendezvous_redis_url.clone(),
-            rendezvous_key_prefix: rendezvous_key_prefix.clone(),
+            rendezvous: rendezvous.clone(),
             base_url: base_url.clone(),
             public_base_url: base_url.clone(),
             connectivity_urls: vec!["http://127.0.0.1:13340".to_string()],
@@ -65,8 +74,7 @@ impl TestServer {
             task,
             database,
             base_url,
-            rendezvous_redis_url,
-            rendezvous_key_prefix,
+            rendezvous,
         })
     }
 
@@ -135,6 +143,33 @@ pub async fn authenticate_with_invite(
     addr_hint: Option<&str>,
     invite_code: Option<&str>,
 ) -> Result<(String, serde_json::Value)> {
+    let (verify, auth_envelope_json) =
+        verify_session(client, base_url, keys, endpoint_id, addr_hint, invite_code).await?;
+    Ok((verify.access_token, auth_envelope_json))
+}
+
+/// auth/verify の応答全体(refresh token を含む)を返す。
+pub async fn authenticate_session(
+    client: &Client,
+    base_url: &str,
+    keys: &KukuriKeys,
+    endpoint_id: &str,
+) -> Result<kukuri_cn_protocol::AuthVerifyResponse> {
+    Ok(
+        verify_session(client, base_url, keys, endpoint_id, None, None)
+            .await?
+            .0,
+    )
+}
+
+async fn verify_session(
+    client: &Client,
+    base_url: &str,
+    keys: &KukuriKeys,
+    endpoint_id: &str,
+    addr_hint: Option<&str>,
+    invite_code: Option<&str>,
+) -> Result<(kukuri_cn_protocol::AuthVerifyResponse, serde_json::Value)> {
     let pubkey = keys.public_key_hex();
     let challenge = client
         .post(format!("{base_url}/v1/auth/challenge"))
@@ -159,7 +194,7 @@ pub async fn authenticate_with_invite(
         .error_for_status()?
         .json::<kukuri_cn_protocol::AuthVerifyResponse>()
         .await?;
-    Ok((verify.access_token, auth_envelope_json))
+    Ok((verify, auth_envelope_json))
 }
 
 /// auth/verify を生で叩き、HTTP status とボディ JSON を返す（拒否ケースの検証用）。
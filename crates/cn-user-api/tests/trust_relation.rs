@@ -25,7 +25,8 @@ use kukuri_cn_trust::{
     EdgeFeatures, FEATURE_SHARED_TOPICS, MemoryRelationStore, RelationStore, TrustParams,
 };
 use kukuri_cn_user_api::{
-    RelationVisibilityState, TrustReadState, UserApiConfig, app_router, build_state,
+    RelationVisibilityState, RendezvousBackendConfig, TrustReadState, UserApiConfig, app_router,
+    build_state,
 };
 use kukuri_core::{KukuriKeys, generate_keys};
 use reqwest::{Client, StatusCode};
@@ -56,8 +57,10 @@ impl TestServer {
         let mut state = build_state(&UserApiConfig {
             bind_addr: addr,
             database_url: database.database_url.clone(),
-            rendezvous_redis_url: integration_test_rendezvous_redis_url(),
-            rendezvous_key_prefix: format!("cn:test:{prefix}"),
+            rendezvous: RendezvousBackendConfig::Redis {
+                url: integration_test_rendezvous_redis_url(),
+                key_prefix: format!("cn:test:{prefix}"),
+            },
             base_url: base_url.clone(),
             public_base_url: base_url.clone(),
             connectivity_urls: vec!["http://127.0.0.1:13340".to_string()],
@@ -51,6 +51,10 @@ impl KukuriKeys {
         hex::encode(self.secret_key.secret_bytes())
     }
 
+    pub(crate) fn secret_bytes(&self) -> [u8; 32] {
+        self.secret_key.secret_bytes()
+    }
+
     pub fn sign_schnorr(&self, message: &[u8]) -> Signature {
         let keypair = Keypair::from_secret_key(SECP256K1, &self.secret_key);
         SECP256K1.sign_schnorr(message, &keypair)
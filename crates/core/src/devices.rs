@@ -0,0 +1,322 @@
+use anyhow::{Context, Result, anyhow, bail};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use secp256k1::rand::{RngCore, rng};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::derive_hkdf_key;
+use crate::{EnvelopeId, KukuriEnvelope, KukuriKeys, Pubkey};
+
+pub const DEVICE_AUTHORIZATION_KIND: &str = "device-authorization";
+const DEVICE_ID_MAX_LEN: usize = 64;
+const DEVICE_LABEL_MAX_CHARS: usize = 64;
+const DEVICE_PAIRING_KEY_INFO: &[u8] = b"kukuri-device-pairing-v1";
+const DEVICE_STATE_KEY_SALT: &[u8] = b"kukuri-device-state-v1";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KukuriDeviceAuthorizationEnvelopeContentV1 {
+    pub device_id: String,
+    pub device_label: String,
+    #[serde(default)]
+    pub authorized_by_device_id: Option<String>,
+}
+
+/// author 鍵で署名された device 登録。author replica の `devices/{device_id}/authorization` に置く。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
+pub struct DeviceAuthorization {
+    pub author_pubkey: Pubkey,
+    pub device_id: String,
+    pub device_label: String,
+    pub authorized_by_device_id: Option<String>,
+    pub authorized_at: i64,
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub envelope_id: EnvelopeId,
+}
+
+/// 新 device へ渡す pairing ticket。author secret は pairing code から導出した鍵で暗号化し、
+/// ticket 単体では復号できない(code は別経路で入力してもらう)。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DevicePairingTicketV1 {
+    pub authorization: KukuriEnvelope,
+    pub expires_at: i64,
+    pub salt_hex: String,
+    pub nonce_hex: String,
+    pub ciphertext_hex: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct DevicePairing {
+    pub keys: KukuriKeys,
+    pub authorization: DeviceAuthorization,
+}
+
+/// device replica に置く暗号化済み state doc。鍵は author secret から導出するため、
+/// 同じ author の device 同士だけが読める(device replica 自体は公開 namespace)。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceStateDocV1 {
+    pub nonce_hex: String,
+    pub ciphertext_hex: String,
+}
+
+pub fn validate_device_id(device_id: &str) -> Result<()> {
+    if device_id.is_empty() || device_id.len() > DEVICE_ID_MAX_LEN {
+        bail!("device id must be 1..={DEVICE_ID_MAX_LEN} characters");
+    }
+    if !device_id
+        .chars()
+        .all(|value| value.is_ascii_alphanumeric() || matches!(value, '-' | '_'))
+    {
+        bail!("device id must contain only ascii alphanumerics, '-' or '_'");
+    }
+    Ok(())
+}
+
+pub fn generate_device_id() -> String {
+    let mut bytes = [0u8; 16];
+    rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// 人が書き写せるよう 4 文字ごとに `-` で区切った 128 bit の pairing code を返す。
+pub fn generate_device_pairing_code() -> String {
+    let mut bytes = [0u8; 16];
+    rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+pub fn build_device_authorization_envelope(
+    keys: &KukuriKeys,
+    device_id: &str,
+    device_label: &str,
+    authorized_by_device_id: Option<&str>,
+) -> Result<KukuriEnvelope> {
+    let device_id = device_id.trim();
+    validate_device_id(device_id)?;
+    let device_label = normalize_device_label(device_label)?;
+    if let Some(authorized_by_device_id) = authorized_by_device_id {
+        validate_device_id(authorized_by_device_id).context("invalid authorizing device id")?;
+    }
+    crate::sign_envelope_json(
+        keys,
+        DEVICE_AUTHORIZATION_KIND,
+        vec![
+            vec!["object".into(), DEVICE_AUTHORIZATION_KIND.into()],
+            vec!["device".into(), device_id.to_string()],
+        ],
+        &KukuriDeviceAuthorizationEnvelopeContentV1 {
+            device_id: device_id.to_string(),
+            device_label,
+            authorized_by_device_id: authorized_by_device_id.map(str::to_string),
+        },
+    )
+}
+
+pub fn parse_device_authorization(envelope: &KukuriEnvelope) -> Result<DeviceAuthorization> {
+    envelope.verify()?;
+    if envelope.kind != DEVICE_AUTHORIZATION_KIND {
+        bail!("device authorization envelope kind must be {DEVICE_AUTHORIZATION_KIND}");
+    }
+    let content: KukuriDeviceAuthorizationEnvelopeContentV1 =
+        serde_json::from_str(envelope.content.as_str())
+            .context("failed to decode device authorization content")?;
+    validate_device_id(content.device_id.as_str())?;
+    let tagged_device = envelope
+        .tags
+        .iter()
+        .find(|tag| tag.first().map(String::as_str) == Some("device"))
+        .and_then(|tag| tag.get(1));
+    if tagged_device != Some(&content.device_id) {
+        bail!("device authorization tag does not match content");
+    }
+    Ok(DeviceAuthorization {
+        author_pubkey: envelope.pubkey.clone(),
+        device_id: content.device_id,
+        device_label: content.device_label,
+        authorized_by_device_id: content.authorized_by_device_id,
+        authorized_at: envelope.created_at,
+        envelope_id: envelope.id.clone(),
+    })
+}
+
+pub fn build_device_pairing_ticket(
+    keys: &KukuriKeys,
+    authorization: &KukuriEnvelope,
+    pairing_code: &str,
+    expires_at: i64,
+) -> Result<String> {
+    let parsed = parse_device_authorization(authorization)?;
+    if parsed.author_pubkey != keys.public_key() {
+        bail!("device pairing authorization must be signed by the pairing author");
+    }
+    let mut salt = [0u8; 16];
+    rng().fill_bytes(&mut salt);
+    let mut nonce = [0u8; 24];
+    rng().fill_bytes(&mut nonce);
+    let cipher = device_pairing_cipher(&salt, pairing_code)?;
+    let ciphertext = cipher
+        .encrypt(
+            &XNonce::from(nonce),
+            Payload {
+                msg: keys.export_secret_hex().as_bytes(),
+                aad: device_pairing_aad(authorization.id.as_str(), expires_at).as_slice(),
+            },
+        )
+        .map_err(|_| anyhow!("failed to encrypt device pairing secret"))?;
+    let ticket = DevicePairingTicketV1 {
+        authorization: authorization.clone(),
+        expires_at,
+        salt_hex: hex::encode(salt),
+        nonce_hex: hex::encode(nonce),
+        ciphertext_hex: hex::encode(ciphertext),
+    };
+    serde_json::to_string(&ticket).context("failed to encode device pairing ticket")
+}
+
+pub fn open_device_pairing_ticket(
+    ticket: &str,
+    pairing_code: &str,
+    now_millis: i64,
+) -> Result<DevicePairing> {
+    let ticket: DevicePairingTicketV1 =
+        serde_json::from_str(ticket.trim()).context("failed to parse device pairing ticket")?;
+    if ticket.expires_at <= now_millis {
+        bail!("device pairing ticket has expired");
+    }
+    let authorization = parse_device_authorization(&ticket.authorization)?;
+    let salt = hex::decode(ticket.salt_hex.trim()).context("invalid device pairing salt")?;
+    let nonce = hex::decode(ticket.nonce_hex.trim()).context("invalid device pairing nonce")?;
+    if nonce.len() != 24 {
+        bail!("device pairing nonce must be 24 bytes");
+    }
+    let ciphertext =
+        hex::decode(ticket.ciphertext_hex.trim()).context("invalid device pairing ciphertext")?;
+    let secret = device_pairing_cipher(salt.as_slice(), pairing_code)?
+        .decrypt(
+            <&XNonce>::try_from(nonce.as_slice()).expect("nonce length checked"),
+            Payload {
+                msg: ciphertext.as_slice(),
+                aad: device_pairing_aad(ticket.authorization.id.as_str(), ticket.expires_at)
+                    .as_slice(),
+            },
+        )
+        .map_err(|_| anyhow!("device pairing code is incorrect or the ticket was modified"))?;
+    let secret = String::from_utf8(secret).context("device pairing secret is not utf-8")?;
+    let keys = KukuriKeys::parse(secret.as_str())?;
+    if keys.public_key() != authorization.author_pubkey {
+        bail!("device pairing secret does not match the authorizing author");
+    }
+    Ok(DevicePairing {
+        keys,
+        authorization,
+    })
+}
+
+pub fn encrypt_device_state_doc<T: Serialize>(
+    keys: &KukuriKeys,
+    doc_key: &str,
+    value: &T,
+) -> Result<DeviceStateDocV1> {
+    let plaintext = serde_json::to_vec(value).context("failed to encode device state doc")?;
+    let mut nonce = [0u8; 24];
+    rng().fill_bytes(&mut nonce);
+    let ciphertext = device_state_cipher(keys)?
+        .encrypt(
+            &XNonce::from(nonce),
+            Payload {
+                msg: plaintext.as_slice(),
+                aad: doc_key.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("failed to encrypt device state doc"))?;
+    Ok(DeviceStateDocV1 {
+        nonce_hex: hex::encode(nonce),
+        ciphertext_hex: hex::encode(ciphertext),
+    })
+}
+
+pub fn decrypt_device_state_doc<T: DeserializeOwned>(
+    keys: &KukuriKeys,
+    doc_key: &str,
+    doc: &DeviceStateDocV1,
+) -> Result<T> {
+    let nonce = hex::decode(doc.nonce_hex.trim()).context("invalid device state nonce")?;
+    if nonce.len() != 24 {
+        bail!("device state nonce must be 24 bytes");
+    }
+    let ciphertext =
+        hex::decode(doc.ciphertext_hex.trim()).context("invalid device state ciphertext")?;
+    let plaintext = device_state_cipher(keys)?
+        .decrypt(
+            <&XNonce>::try_from(nonce.as_slice()).expect("nonce length checked"),
+            Payload {
+                msg: ciphertext.as_slice(),
+                aad: doc_key.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("failed to decrypt device state doc"))?;
+    serde_json::from_slice(&plaintext).context("failed to decode device state doc")
+}
+
+fn normalize_device_label(device_label: &str) -> Result<String> {
+    let device_label = device_label.trim();
+    if device_label.is_empty() {
+        bail!("device label is required");
+    }
+    if device_label.chars().count() > DEVICE_LABEL_MAX_CHARS {
+        bail!("device label must be at most {DEVICE_LABEL_MAX_CHARS} characters");
+    }
+    Ok(device_label.to_string())
+}
+
+/// 区切り `-` / 空白 / 大文字小文字の揺れは同じ code として扱う。
+fn normalize_device_pairing_code(pairing_code: &str) -> Result<String> {
+    let normalized = pairing_code
+        .chars()
+        .filter(|value| !value.is_whitespace() && *value != '-')
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if normalized.len() < 16 {
+        bail!("device pairing code is too short");
+    }
+    Ok(normalized)
+}
+
+fn device_pairing_cipher(salt: &[u8], pairing_code: &str) -> Result<XChaCha20Poly1305> {
+    let code = normalize_device_pairing_code(pairing_code)?;
+    let key = derive_hkdf_key(
+        salt,
+        code.as_bytes(),
+        DEVICE_PAIRING_KEY_INFO,
+        "device pairing key",
+    )?;
+    XChaCha20Poly1305::new_from_slice(&key).context("failed to initialize device pairing cipher")
+}
+
+/// ticket の平文 field のうち改竄されると困るもの(version / authorization / 有効期限)を AEAD の
+/// aad に束ねる。salt は鍵導出に入るので、書き換えれば復号に失敗する。
+fn device_pairing_aad(authorization_id: &str, expires_at: i64) -> Vec<u8> {
+    let mut aad = DEVICE_PAIRING_KEY_INFO.to_vec();
+    aad.push(0);
+    aad.extend_from_slice(authorization_id.as_bytes());
+    aad.push(0);
+    aad.extend_from_slice(&expires_at.to_be_bytes());
+    aad
+}
+
+fn device_state_cipher(keys: &KukuriKeys) -> Result<XChaCha20Poly1305> {
+    let key = derive_hkdf_key(
+        DEVICE_STATE_KEY_SALT,
+        &keys.secret_bytes(),
+        keys.public_key_hex().as_bytes(),
+        "device state key",
+    )?;
+    XChaCha20Poly1305::new_from_slice(&key).context("failed to initialize device state cipher")
+}
@@ -0,0 +1,783 @@
+use std::str::FromStr;
+
+use anyhow::{Context, Result, anyhow, bail};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hkdf::Hkdf;
+use secp256k1::rand::{RngCore, rng};
+use secp256k1::schnorr::Signature;
+use secp256k1::{SECP256K1, XOnlyPublicKey};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::crypto::{derive_hkdf_key, pairwise_shared_secret, sha256_digest, validate_pubkey};
+use crate::direct_messages::derive_direct_message_secret;
+use crate::{DirectMessageFrameV1, DirectMessagePayloadV1, KukuriKeys, Pubkey};
+
+pub const DIRECT_MESSAGE_FRAME_VERSION_V1: u8 = 1;
+pub const DIRECT_MESSAGE_FRAME_VERSION_V2: u8 = 2;
+/// 1 frame の受信で導出してよい skipped message key の上限。欠番を装った DoS を防ぐ。
+pub const DIRECT_MESSAGE_MAX_SKIPPED_KEYS: u32 = 1000;
+const SEEN_SESSION_INIT_LIMIT: usize = 16;
+
+/// このクライアントが復号できる frame version。v1 payload に載せて相手へ広告する。
+pub fn supported_direct_message_frame_versions() -> Vec<u8> {
+    vec![
+        DIRECT_MESSAGE_FRAME_VERSION_V1,
+        DIRECT_MESSAGE_FRAME_VERSION_V2,
+    ]
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectMessageRatchetHeaderV2 {
+    pub ratchet_pubkey: Pubkey,
+    pub previous_chain_length: u32,
+    pub message_number: u32,
+    /// 送信側がまだ相手から session 上の frame を受け取っていない(相手の session prekey か
+    /// identity 鍵に対して session を開始した)ことを示す。受信側はこの frame から session を採用できる。
+    #[serde(default)]
+    pub session_init: bool,
+    /// session_init が DH した受信側の session prekey。無ければ受信側の identity 鍵に対する
+    /// 旧形式の init(prekey を広告しない相手向け)。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipient_prekey: Option<Pubkey>,
+}
+
+/// double ratchet で鍵を導出する DM frame。blob の JSON に `version` と `header` が
+/// あれば v2、無ければ v1 として扱う(`DirectMessageFrame`)。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectMessageFrameV2 {
+    pub version: u8,
+    pub dm_id: String,
+    pub message_id: String,
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub created_at: i64,
+    pub header: DirectMessageRatchetHeaderV2,
+    pub nonce_hex: String,
+    pub ciphertext_hex: String,
+    pub signature: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DirectMessageFrame {
+    V2(DirectMessageFrameV2),
+    V1(DirectMessageFrameV1),
+}
+
+/// 1 peer との ratchet session。秘密鍵と chain key を含むため、ローカル DB の外へ出さない。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectMessageRatchetStateV2 {
+    pub peer_pubkey: Pubkey,
+    pub root_key_hex: String,
+    pub local_ratchet_secret_hex: String,
+    pub remote_ratchet_pubkey: Pubkey,
+    pub send_chain_key_hex: String,
+    #[serde(default)]
+    pub recv_chain_key_hex: Option<String>,
+    pub send_message_number: u32,
+    pub recv_message_number: u32,
+    pub previous_send_chain_length: u32,
+    /// 自分が開始した session に相手の返信がまだ無い間は true。
+    pub awaiting_first_reply: bool,
+    /// 現 session で受け取った相手 frame の最大 created_at。遅延到着した古い init frame で
+    /// session を巻き戻さないための基準。
+    #[serde(default)]
+    pub last_remote_created_at: i64,
+    /// 処理済みの相手側 session 開始鍵(新しい順、上限付き)。
+    #[serde(default)]
+    pub seen_session_init_pubkeys: Vec<Pubkey>,
+}
+
+/// 受信順が前後した frame 用に保存しておく message key。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectMessageSkippedKeyV2 {
+    pub ratchet_pubkey: Pubkey,
+    pub message_number: u32,
+    pub message_key_hex: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct DirectMessageRatchetEncryption {
+    pub frame: DirectMessageFrameV2,
+    pub state: DirectMessageRatchetStateV2,
+}
+
+#[derive(Clone, Debug)]
+pub struct DirectMessageRatchetDecryption {
+    pub payload: DirectMessagePayloadV1,
+    /// 更新後の session。skipped key での復号や、採用しなかった init frame では None。
+    pub state: Option<DirectMessageRatchetStateV2>,
+    /// 今回の受信で飛ばした message key。呼び出し側で保存する。
+    pub skipped_keys: Vec<DirectMessageSkippedKeyV2>,
+    /// 自分の session prekey に対する init を採用した。呼び出し側はその prekey の秘密鍵を
+    /// 破棄して新しい prekey に差し替える。
+    pub session_prekey_consumed: bool,
+}
+
+impl DirectMessageFrameV2 {
+    pub fn verify(&self) -> Result<()> {
+        if self.version != DIRECT_MESSAGE_FRAME_VERSION_V2 {
+            bail!("direct message frame version must be {DIRECT_MESSAGE_FRAME_VERSION_V2}");
+        }
+        validate_pubkey(self.sender.as_str()).context("invalid direct message sender pubkey")?;
+        validate_pubkey(self.recipient.as_str())
+            .context("invalid direct message recipient pubkey")?;
+        validate_pubkey(self.header.ratchet_pubkey.as_str())
+            .context("invalid direct message ratchet pubkey")?;
+        if let Some(prekey) = self.header.recipient_prekey.as_ref() {
+            validate_pubkey(prekey.as_str()).context("invalid direct message session prekey")?;
+        }
+        if self.dm_id.trim().is_empty() {
+            bail!("direct message frame dm_id is required");
+        }
+        if self.message_id.trim().is_empty() {
+            bail!("direct message frame message_id is required");
+        }
+        let nonce =
+            hex::decode(self.nonce_hex.trim()).context("invalid direct message frame nonce")?;
+        if nonce.len() != 24 {
+            bail!("direct message frame nonce must be 24 bytes");
+        }
+        let _ = hex::decode(self.ciphertext_hex.trim())
+            .context("invalid direct message frame ciphertext")?;
+        let signature = Signature::from_str(self.signature.as_str())
+            .context("invalid direct message frame signature")?;
+        let sender =
+            XOnlyPublicKey::from_str(self.sender.as_str()).context("invalid frame sender")?;
+        let digest = sha256_digest(canonical_direct_message_frame_v2_payload(self)?.as_bytes());
+        SECP256K1
+            .verify_schnorr(&signature, &digest, &sender)
+            .context("direct message frame signature verification failed")?;
+        Ok(())
+    }
+}
+
+impl DirectMessageFrame {
+    pub fn version(&self) -> u8 {
+        match self {
+            Self::V1(_) => DIRECT_MESSAGE_FRAME_VERSION_V1,
+            Self::V2(_) => DIRECT_MESSAGE_FRAME_VERSION_V2,
+        }
+    }
+
+    pub fn dm_id(&self) -> &str {
+        match self {
+            Self::V1(frame) => frame.dm_id.as_str(),
+            Self::V2(frame) => frame.dm_id.as_str(),
+        }
+    }
+
+    pub fn message_id(&self) -> &str {
+        match self {
+            Self::V1(frame) => frame.message_id.as_str(),
+            Self::V2(frame) => frame.message_id.as_str(),
+        }
+    }
+
+    pub fn sender(&self) -> &Pubkey {
+        match self {
+            Self::V1(frame) => &frame.sender,
+            Self::V2(frame) => &frame.sender,
+        }
+    }
+
+    pub fn recipient(&self) -> &Pubkey {
+        match self {
+            Self::V1(frame) => &frame.recipient,
+            Self::V2(frame) => &frame.recipient,
+        }
+    }
+
+    pub fn created_at(&self) -> i64 {
+        match self {
+            Self::V1(frame) => frame.created_at,
+            Self::V2(frame) => frame.created_at,
+        }
+    }
+
+    pub fn verify(&self) -> Result<()> {
+        match self {
+            Self::V1(frame) => frame.verify(),
+            Self::V2(frame) => frame.verify(),
+        }
+    }
+}
+
+/// 次の v2 frame を暗号化して session を進める。`state` が無ければ相手が広告した
+/// `peer_prekey`(無ければ identity 鍵)に対して session を開始する(相手の返信で DH ratchet が
+/// 回るまで header に session_init を立てる)。prekey の無い init は相手の identity 鍵の漏洩に
+/// 対して forward secrecy を持たない(`decrypt_session_init_frame` を参照)。
+#[allow(clippy::too_many_arguments)]
+pub fn encrypt_direct_message_frame_v2(
+    local_keys: &KukuriKeys,
+    state: Option<&DirectMessageRatchetStateV2>,
+    recipient_pubkey: &Pubkey,
+    peer_prekey: Option<&Pubkey>,
+    dm_id: &str,
+    message_id: &str,
+    created_at: i64,
+    payload: &DirectMessagePayloadV1,
+) -> Result<DirectMessageRatchetEncryption> {
+    if dm_id.trim().is_empty() {
+        bail!("direct message dm_id is required");
+    }
+    if message_id.trim().is_empty() {
+        bail!("direct message message_id is required");
+    }
+    validate_pubkey(recipient_pubkey.as_str())
+        .context("invalid direct message recipient pubkey")?;
+    let mut state = match state {
+        Some(state) => {
+            if &state.peer_pubkey != recipient_pubkey {
+                bail!("direct message ratchet session belongs to a different peer");
+            }
+            state.clone()
+        }
+        None => initiate_ratchet_session(local_keys, recipient_pubkey, peer_prekey)?,
+    };
+    let local_ratchet = KukuriKeys::parse(state.local_ratchet_secret_hex.as_str())
+        .context("invalid direct message ratchet secret")?;
+    let (message_key, next_chain_key) = derive_chain_step(&decode_key(
+        state.send_chain_key_hex.as_str(),
+        "send chain key",
+    )?)?;
+    let header = DirectMessageRatchetHeaderV2 {
+        ratchet_pubkey: local_ratchet.public_key(),
+        previous_chain_length: state.previous_send_chain_length,
+        message_number: state.send_message_number,
+        session_init: state.awaiting_first_reply,
+        recipient_prekey: (state.awaiting_first_reply
+            && state.remote_ratchet_pubkey != state.peer_pubkey)
+            .then(|| state.remote_ratchet_pubkey.clone()),
+    };
+    state.send_chain_key_hex = hex::encode(next_chain_key);
+    state.send_message_number = state
+        .send_message_number
+        .checked_add(1)
+        .ok_or_else(|| anyhow!("direct message send chain is exhausted"))?;
+
+    let sender = local_keys.public_key();
+    let plaintext =
+        serde_json::to_vec(payload).context("failed to encode direct message payload")?;
+    let mut nonce = [0u8; 24];
+    rng().fill_bytes(&mut nonce);
+    let aad = direct_message_frame_v2_aad(
+        dm_id.trim(),
+        message_id.trim(),
+        sender.as_str(),
+        recipient_pubkey.as_str(),
+        created_at,
+        &header,
+    );
+    let ciphertext = XChaCha20Poly1305::new_from_slice(message_key.as_slice())
+        .context("failed to initialize direct message frame cipher")?
+        .encrypt(
+            &XNonce::from(nonce),
+            Payload {
+                msg: plaintext.as_slice(),
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("failed to encrypt direct message frame"))?;
+    let mut frame = DirectMessageFrameV2 {
+        version: DIRECT_MESSAGE_FRAME_VERSION_V2,
+        dm_id: dm_id.trim().to_string(),
+        message_id: message_id.trim().to_string(),
+        sender,
+        recipient: recipient_pubkey.clone(),
+        created_at,
+        header,
+        nonce_hex: hex::encode(nonce),
+        ciphertext_hex: hex::encode(ciphertext),
+        signature: String::new(),
+    };
+    let digest = sha256_digest(canonical_direct_message_frame_v2_payload(&frame)?.as_bytes());
+    frame.signature = local_keys.sign_schnorr(&digest).to_string();
+    Ok(DirectMessageRatchetEncryption { frame, state })
+}
+
+/// v2 frame を復号する。`skipped_message_key_hex` は呼び出し側が
+/// (`header.ratchet_pubkey`, `header.message_number`) で引いた保存済み key。
+/// `session_prekey` は自分が広告中の session prekey で、prekey 宛ての init frame の復号に使う。
+pub fn decrypt_direct_message_frame_v2(
+    local_keys: &KukuriKeys,
+    session_prekey: Option<&KukuriKeys>,
+    state: Option<&DirectMessageRatchetStateV2>,
+    frame: &DirectMessageFrameV2,
+    skipped_message_key_hex: Option<&str>,
+) -> Result<DirectMessageRatchetDecryption> {
+    frame.verify()?;
+    if local_keys.public_key() != frame.recipient {
+        bail!("direct message frame recipient pubkey must match decrypting author");
+    }
+    if let Some(state) = state
+        && state.peer_pubkey != frame.sender
+    {
+        bail!("direct message ratchet session belongs to a different peer");
+    }
+    let header = &frame.header;
+
+    if let Some(message_key_hex) = skipped_message_key_hex {
+        let payload = open_frame_v2(frame, &decode_key(message_key_hex, "skipped message key")?)?;
+        return Ok(DirectMessageRatchetDecryption {
+            payload,
+            state: None,
+            skipped_keys: Vec::new(),
+            session_prekey_consumed: false,
+        });
+    }
+
+    if let Some(state) = state
+        && state.remote_ratchet_pubkey == header.ratchet_pubkey
+        && let Some(recv_chain_key_hex) = state.recv_chain_key_hex.as_deref()
+    {
+        if header.message_number < state.recv_message_number {
+            bail!("direct message frame key was already used");
+        }
+        let mut state = state.clone();
+        let mut skipped_keys = Vec::new();
+        let (message_key, next_chain_key) = advance_receive_chain(
+            &header.ratchet_pubkey,
+            decode_key(recv_chain_key_hex, "receive chain key")?,
+            state.recv_message_number,
+            header.message_number,
+            &mut skipped_keys,
+        )?;
+        let payload = open_frame_v2(frame, &message_key)?;
+        state.recv_chain_key_hex = Some(hex::encode(next_chain_key));
+        state.recv_message_number = header.message_number + 1;
+        state.last_remote_created_at = state.last_remote_created_at.max(frame.created_at);
+        return Ok(DirectMessageRatchetDecryption {
+            payload,
+            state: Some(state),
+            skipped_keys,
+            session_prekey_consumed: false,
+        });
+    }
+
+    if header.session_init {
+        return decrypt_session_init_frame(local_keys, session_prekey, state, frame);
+    }
+
+    let Some(state) = state else {
+        bail!("direct message ratchet session is unknown");
+    };
+    // 相手が新しい ratchet 鍵へ進んだ: 旧 receive chain の残りを保存してから DH ratchet を回す。
+    let mut state = state.clone();
+    let mut skipped_keys = Vec::new();
+    if let Some(recv_chain_key_hex) = state.recv_chain_key_hex.as_deref()
+        && header.previous_chain_length > state.recv_message_number
+    {
+        advance_receive_chain(
+            &state.remote_ratchet_pubkey,
+            decode_key(recv_chain_key_hex, "receive chain key")?,
+            state.recv_message_number,
+            header.previous_chain_length,
+            &mut skipped_keys,
+        )?;
+    }
+    let local_ratchet = KukuriKeys::parse(state.local_ratchet_secret_hex.as_str())
+        .context("invalid direct message ratchet secret")?;
+    let (root_key, recv_chain_key) = derive_root_step(
+        &decode_key(state.root_key_hex.as_str(), "root key")?,
+        &local_ratchet,
+        &header.ratchet_pubkey,
+    )?;
+    let (message_key, next_chain_key) = advance_receive_chain(
+        &header.ratchet_pubkey,
+        recv_chain_key,
+        0,
+        header.message_number,
+        &mut skipped_keys,
+    )?;
+    let payload = open_frame_v2(frame, &message_key)?;
+    state.previous_send_chain_length = state.send_message_number;
+    rotate_send_chain(&mut state, root_key, &header.ratchet_pubkey)?;
+    state.remote_ratchet_pubkey = header.ratchet_pubkey.clone();
+    state.recv_chain_key_hex = Some(hex::encode(next_chain_key));
+    state.recv_message_number = header.message_number + 1;
+    state.awaiting_first_reply = false;
+    state.last_remote_created_at = state.last_remote_created_at.max(frame.created_at);
+    Ok(DirectMessageRatchetDecryption {
+        payload,
+        state: Some(state),
+        skipped_keys,
+        session_prekey_consumed: false,
+    })
+}
+
+pub fn encrypt_direct_message_attachment_with_key(
+    attachment_key_hex: &str,
+    message_id: &str,
+    blob_id: &str,
+    plaintext: &[u8],
+) -> Result<crate::DirectMessageEncryptedAttachmentV1> {
+    if message_id.trim().is_empty() {
+        bail!("direct message attachment message_id is required");
+    }
+    if blob_id.trim().is_empty() {
+        bail!("direct message attachment blob_id is required");
+    }
+    let mut nonce = [0u8; 24];
+    rng().fill_bytes(&mut nonce);
+    let ciphertext = direct_message_attachment_cipher(attachment_key_hex, message_id, blob_id)?
+        .encrypt(
+            &XNonce::from(nonce),
+            Payload {
+                msg: plaintext,
+                aad: direct_message_attachment_v2_aad(message_id, blob_id).as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("failed to encrypt direct message attachment"))?;
+    Ok(crate::DirectMessageEncryptedAttachmentV1 {
+        blob_id: blob_id.trim().to_string(),
+        nonce_hex: hex::encode(nonce),
+        ciphertext_hex: hex::encode(ciphertext),
+    })
+}
+
+pub fn decrypt_direct_message_attachment_with_key(
+    attachment_key_hex: &str,
+    message_id: &str,
+    attachment: &crate::DirectMessageEncryptedAttachmentV1,
+) -> Result<Vec<u8>> {
+    let nonce = hex::decode(attachment.nonce_hex.trim())
+        .context("invalid direct message attachment nonce")?;
+    if nonce.len() != 24 {
+        bail!("direct message attachment nonce must be 24 bytes");
+    }
+    let ciphertext = hex::decode(attachment.ciphertext_hex.trim())
+        .context("invalid direct message attachment ciphertext")?;
+    direct_message_attachment_cipher(attachment_key_hex, message_id, attachment.blob_id.as_str())?
+        .decrypt(
+            <&XNonce>::try_from(nonce.as_slice()).expect("nonce length checked"),
+            Payload {
+                msg: ciphertext.as_slice(),
+                aad: direct_message_attachment_v2_aad(message_id, attachment.blob_id.as_str())
+                    .as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("failed to decrypt direct message attachment"))
+}
+
+/// v2 frame の添付用の使い捨て鍵。payload に入れて ratchet 鍵で運ぶ。
+pub fn generate_direct_message_attachment_key() -> String {
+    let mut key = [0u8; 32];
+    rng().fill_bytes(&mut key);
+    hex::encode(key)
+}
+
+/// identity 同士の DH を root にし、相手の session prekey(無ければ identity 鍵)へ
+/// 使い捨て ratchet 鍵で DH して最初の send chain を作る。
+fn initiate_ratchet_session(
+    local_keys: &KukuriKeys,
+    peer_pubkey: &Pubkey,
+    peer_prekey: Option<&Pubkey>,
+) -> Result<DirectMessageRatchetStateV2> {
+    let remote_ratchet_pubkey = match peer_prekey {
+        Some(prekey) => {
+            validate_pubkey(prekey.as_str()).context("invalid direct message session prekey")?;
+            prekey.clone()
+        }
+        None => peer_pubkey.clone(),
+    };
+    let root_key = derive_direct_message_secret(local_keys, peer_pubkey)?;
+    let mut state = DirectMessageRatchetStateV2 {
+        peer_pubkey: peer_pubkey.clone(),
+        root_key_hex: String::new(),
+        local_ratchet_secret_hex: String::new(),
+        remote_ratchet_pubkey: remote_ratchet_pubkey.clone(),
+        send_chain_key_hex: String::new(),
+        recv_chain_key_hex: None,
+        send_message_number: 0,
+        recv_message_number: 0,
+        previous_send_chain_length: 0,
+        awaiting_first_reply: true,
+        last_remote_created_at: 0,
+        seen_session_init_pubkeys: Vec::new(),
+    };
+    rotate_send_chain(&mut state, root_key, &remote_ratchet_pubkey)?;
+    Ok(state)
+}
+
+/// 相手が session_init で送ってきた frame。相手の ratchet 鍵と自分の session prekey で
+/// receive chain を導出できるため、session を採用しない場合でも復号できる。
+///
+/// init 段階の鍵は identity 同士の DH と「送信側の使い捨て ratchet 鍵 × 受信側の session
+/// prekey」の DH から導出する。prekey の秘密鍵は init を採用した時点で呼び出し側が破棄する
+/// ため、後から identity 秘密鍵が漏れても保存済みの init frame は復号できない。
+/// `recipient_prekey` の無い旧形式の init は受信側の identity 鍵に DH しており、identity
+/// 秘密鍵の漏洩に対して forward secrecy を持たない(prekey を広告しない相手との互換用)。
+fn decrypt_session_init_frame(
+    local_keys: &KukuriKeys,
+    session_prekey: Option<&KukuriKeys>,
+    state: Option<&DirectMessageRatchetStateV2>,
+    frame: &DirectMessageFrameV2,
+) -> Result<DirectMessageRatchetDecryption> {
+    let header = &frame.header;
+    let init_keys = match header.recipient_prekey.as_ref() {
+        Some(prekey) => session_prekey
+            .filter(|session_prekey| &session_prekey.public_key() == prekey)
+            .ok_or_else(|| anyhow!("direct message session prekey is unknown or retired"))?,
+        None => local_keys,
+    };
+    let initial_root = derive_direct_message_secret(local_keys, &frame.sender)?;
+    let (root_key, recv_chain_key) =
+        derive_root_step(&initial_root, init_keys, &header.ratchet_pubkey)?;
+    let mut skipped_keys = Vec::new();
+    let (message_key, next_chain_key) = advance_receive_chain(
+        &header.ratchet_pubkey,
+        recv_chain_key,
+        0,
+        header.message_number,
+        &mut skipped_keys,
+    )?;
+    let payload = open_frame_v2(frame, &message_key)?;
+
+    let adopt = match state {
+        None => true,
+        Some(state)
+            if state
+                .seen_session_init_pubkeys
+                .contains(&header.ratchet_pubkey) =>
+        {
+            false
+        }
+        // 双方が同時に session を開始した場合は pubkey の小さい側の session に揃える。
+        Some(state) if state.awaiting_first_reply => frame.sender < frame.recipient,
+        // 確立済み session より新しい init は相手側の state 喪失とみなして張り直す。
+        Some(state) => frame.created_at > state.last_remote_created_at,
+    };
+    if !adopt {
+        let state = state.map(|state| {
+            let mut state = state.clone();
+            remember_session_init(&mut state, &header.ratchet_pubkey);
+            state
+        });
+        return Ok(DirectMessageRatchetDecryption {
+            payload,
+            state,
+            skipped_keys: Vec::new(),
+            session_prekey_consumed: false,
+        });
+    }
+
+    let mut adopted = DirectMessageRatchetStateV2 {
+        peer_pubkey: frame.sender.clone(),
+        root_key_hex: String::new(),
+        local_ratchet_secret_hex: String::new(),
+        remote_ratchet_pubkey: header.ratchet_pubkey.clone(),
+        send_chain_key_hex: String::new(),
+        recv_chain_key_hex: Some(hex::encode(next_chain_key)),
+        send_message_number: 0,
+        recv_message_number: header.message_number + 1,
+        previous_send_chain_length: 0,
+        awaiting_first_reply: false,
+        last_remote_created_at: frame.created_at,
+        seen_session_init_pubkeys: state
+            .map(|state| state.seen_session_init_pubkeys.clone())
+            .unwrap_or_default(),
+    };
+    remember_session_init(&mut adopted, &header.ratchet_pubkey);
+    rotate_send_chain(&mut adopted, root_key, &header.ratchet_pubkey)?;
+    Ok(DirectMessageRatchetDecryption {
+        payload,
+        state: Some(adopted),
+        skipped_keys,
+        session_prekey_consumed: header.recipient_prekey.is_some(),
+    })
+}
+
+fn remember_session_init(state: &mut DirectMessageRatchetStateV2, ratchet_pubkey: &Pubkey) {
+    if state.seen_session_init_pubkeys.contains(ratchet_pubkey) {
+        return;
+    }
+    state
+        .seen_session_init_pubkeys
+        .insert(0, ratchet_pubkey.clone());
+    state
+        .seen_session_init_pubkeys
+        .truncate(SEEN_SESSION_INIT_LIMIT);
+}
+
+/// 新しい ratchet 鍵を生成し、相手の ratchet 鍵との DH で root key と send chain を進める。
+fn rotate_send_chain(
+    state: &mut DirectMessageRatchetStateV2,
+    root_key: [u8; 32],
+    remote_ratchet_pubkey: &Pubkey,
+) -> Result<()> {
+    let local_ratchet = KukuriKeys::generate();
+    let (root_key, send_chain_key) =
+        derive_root_step(&root_key, &local_ratchet, remote_ratchet_pubkey)?;
+    state.root_key_hex = hex::encode(root_key);
+    state.local_ratchet_secret_hex = local_ratchet.export_secret_hex();
+    state.send_chain_key_hex = hex::encode(send_chain_key);
+    state.send_message_number = 0;
+    Ok(())
+}
+
+fn derive_root_step(
+    root_key: &[u8; 32],
+    local_ratchet: &KukuriKeys,
+    remote_ratchet_pubkey: &Pubkey,
+) -> Result<([u8; 32], [u8; 32])> {
+    let shared = pairwise_shared_secret(local_ratchet, remote_ratchet_pubkey)?;
+    let hkdf = Hkdf::<Sha256>::new(Some(root_key.as_slice()), shared.secret_bytes().as_slice());
+    let mut output = [0u8; 64];
+    hkdf.expand(b"kukuri:direct-message:v2:root", &mut output)
+        .map_err(|_| anyhow!("failed to derive direct message ratchet root key"))?;
+    let mut next_root = [0u8; 32];
+    let mut chain_key = [0u8; 32];
+    next_root.copy_from_slice(&output[..32]);
+    chain_key.copy_from_slice(&output[32..]);
+    Ok((next_root, chain_key))
+}
+
+fn derive_chain_step(chain_key: &[u8; 32]) -> Result<([u8; 32], [u8; 32])> {
+    let message_key = derive_hkdf_key(
+        b"kukuri/direct-message/v2/chain",
+        chain_key.as_slice(),
+        b"message-key",
+        "direct message ratchet message key",
+    )?;
+    let next_chain_key = derive_hkdf_key(
+        b"kukuri/direct-message/v2/chain",
+        chain_key.as_slice(),
+        b"chain-key",
+        "direct message ratchet chain key",
+    )?;
+    Ok((message_key, next_chain_key))
+}
+
+/// `from` から `to` まで receive chain を進め、`to` の message key と次の chain key を返す。
+/// 途中の key は `skipped_keys` に積む。
+fn advance_receive_chain(
+    ratchet_pubkey: &Pubkey,
+    mut chain_key: [u8; 32],
+    from: u32,
+    to: u32,
+    skipped_keys: &mut Vec<DirectMessageSkippedKeyV2>,
+) -> Result<([u8; 32], [u8; 32])> {
+    if to.saturating_sub(from) > DIRECT_MESSAGE_MAX_SKIPPED_KEYS {
+        bail!("direct message frame skips too many messages");
+    }
+    for message_number in from..to {
+        let (message_key, next_chain_key) = derive_chain_step(&chain_key)?;
+        skipped_keys.push(DirectMessageSkippedKeyV2 {
+            ratchet_pubkey: ratchet_pubkey.clone(),
+            message_number,
+            message_key_hex: hex::encode(message_key),
+        });
+        chain_key = next_chain_key;
+    }
+    derive_chain_step(&chain_key)
+}
+
+fn open_frame_v2(
+    frame: &DirectMessageFrameV2,
+    message_key: &[u8; 32],
+) -> Result<DirectMessagePayloadV1> {
+    let nonce =
+        hex::decode(frame.nonce_hex.trim()).context("invalid direct message frame nonce")?;
+    let ciphertext = hex::decode(frame.ciphertext_hex.trim())
+        .context("invalid direct message frame ciphertext")?;
+    let aad = direct_message_frame_v2_aad(
+        frame.dm_id.as_str(),
+        frame.message_id.as_str(),
+        frame.sender.as_str(),
+        frame.recipient.as_str(),
+        frame.created_at,
+        &frame.header,
+    );
+    let plaintext = XChaCha20Poly1305::new_from_slice(message_key.as_slice())
+        .context("failed to initialize direct message frame cipher")?
+        .decrypt(
+            <&XNonce>::try_from(nonce.as_slice()).expect("nonce length checked"),
+            Payload {
+                msg: ciphertext.as_slice(),
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("failed to decrypt direct message frame"))?;
+    serde_json::from_slice(&plaintext).context("failed to decode direct message payload")
+}
+
+fn decode_key(value: &str, label: &str) -> Result<[u8; 32]> {
+    let bytes = hex::decode(value.trim())
+        .with_context(|| format!("invalid direct message ratchet {label}"))?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow!("direct message ratchet {label} must be 32 bytes"))
+}
+
+fn direct_message_attachment_cipher(
+    attachment_key_hex: &str,
+    message_id: &str,
+    blob_id: &str,
+) -> Result<XChaCha20Poly1305> {
+    let key = derive_hkdf_key(
+        b"kukuri/direct-message/v2/attachment",
+        &decode_key(attachment_key_hex, "attachment key")?,
+        direct_message_attachment_v2_aad(message_id, blob_id).as_bytes(),
+        "direct message attachment key",
+    )?;
+    XChaCha20Poly1305::new_from_slice(key.as_slice())
+        .context("failed to initialize direct message attachment cipher")
+}
+
+pub(crate) fn canonical_direct_message_frame_v2_payload(
+    frame: &DirectMessageFrameV2,
+) -> Result<String> {
+    let mut fields = serde_json::json!([
+        0,
+        frame.version,
+        frame.dm_id,
+        frame.message_id,
+        frame.sender,
+        frame.recipient,
+        frame.created_at,
+        frame.header.ratchet_pubkey,
+        frame.header.previous_chain_length,
+        frame.header.message_number,
+        frame.header.session_init,
+        frame.nonce_hex,
+        frame.ciphertext_hex
+    ]);
+    // prekey の無い frame は従来どおりの署名対象になるよう、ある時だけ末尾に足す。
+    if let (Some(prekey), Some(fields)) = (
+        frame.header.recipient_prekey.as_ref(),
+        fields.as_array_mut(),
+    ) {
+        fields.push(serde_json::json!(prekey));
+    }
+    serde_json::to_string(&fields)
+        .context("failed to encode canonical direct message frame payload")
+}
+
+fn direct_message_frame_v2_aad(
+    dm_id: &str,
+    message_id: &str,
+    sender: &str,
+    recipient: &str,
+    created_at: i64,
+    header: &DirectMessageRatchetHeaderV2,
+) -> String {
+    let mut aad = format!(
+        "kukuri:direct-message:frame:v2:{dm_id}:{message_id}:{sender}:{recipient}:{created_at}:{}:{}:{}:{}",
+        header.ratchet_pubkey.as_str(),
+        header.previous_chain_length,
+        header.message_number,
+        header.session_init
+    );
+    if let Some(prekey) = header.recipient_prekey.as_ref() {
+        aad.push(':');
+        aad.push_str(prekey.as_str());
+    }
+    aad
+}
+
+fn direct_message_attachment_v2_aad(message_id: &str, blob_id: &str) -> String {
+    format!("kukuri:direct-message:attachment:v2:{message_id}:{blob_id}")
+}
@@ -46,6 +46,19 @@ pub struct DirectMessagePayloadV1 {
     pub reply_to: Option<String>,
     #[serde(default)]
     pub attachment_manifest: Option<DirectMessageAttachmentManifestV1>,
+    /// 送信側が復号できる frame version。相手が v2 を含めていれば以後 ratchet frame で送る。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub supported_frame_versions: Vec<u8>,
+    /// v2 frame の添付を暗号化した使い捨て鍵。frame と同じ ratchet message key で守られる。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment_key_hex: Option<String>,
+    /// グループ DM の鍵更新。これを載せた pairwise DM は会話に表示しない。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_key_update: Option<crate::GroupDirectMessageKeyUpdateV1>,
+    /// 送信側がこの会話で受け付ける session prekey(使い捨ての公開鍵)。相手は次に v2
+    /// session を開始するとき identity 鍵の代わりにこれへ DH する。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_prekey: Option<Pubkey>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
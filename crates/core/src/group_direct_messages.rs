@@ -0,0 +1,288 @@
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use anyhow::{Context, Result, anyhow, bail};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use secp256k1::rand::{RngCore, rng};
+use secp256k1::schnorr::Signature;
+use secp256k1::{SECP256K1, XOnlyPublicKey};
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::{derive_hkdf_key, sha256_digest, validate_pubkey};
+use crate::{DirectMessagePayloadV1, KukuriKeys, Pubkey, TopicId};
+
+/// owner を含むグループ DM の人数の下限 / 上限。
+pub const GROUP_DIRECT_MESSAGE_MIN_MEMBERS: usize = 3;
+pub const GROUP_DIRECT_MESSAGE_MAX_MEMBERS: usize = 32;
+const GROUP_DIRECT_MESSAGE_ID_PREFIX: &str = "group-dm-";
+
+/// owner が各メンバーへ pairwise DM payload で配る鍵更新。メンバー変更のたびに
+/// epoch を進めて新しい secret を配り、外されたメンバーには secret 無しで届ける。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupDirectMessageKeyUpdateV1 {
+    pub group_id: String,
+    pub owner: Pubkey,
+    #[serde(default)]
+    pub title: Option<String>,
+    pub epoch: u64,
+    pub members: Vec<Pubkey>,
+    #[serde(default)]
+    pub epoch_secret_hex: Option<String>,
+    pub created_at: i64,
+}
+
+/// グループ topic に流す frame。鍵は epoch secret から message ごとに導出する。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupDirectMessageFrameV1 {
+    pub group_id: String,
+    pub epoch: u64,
+    pub message_id: String,
+    pub sender: Pubkey,
+    pub created_at: i64,
+    pub nonce_hex: String,
+    pub ciphertext_hex: String,
+    pub signature: String,
+}
+
+impl GroupDirectMessageKeyUpdateV1 {
+    pub fn validate(&self) -> Result<()> {
+        validate_group_direct_message_id(self.group_id.as_str())?;
+        validate_pubkey(self.owner.as_str()).context("invalid group direct message owner")?;
+        let members = normalize_group_direct_message_members(&self.owner, &self.members)?;
+        if members != self.members {
+            bail!("group direct message members must be sorted, unique and include the owner");
+        }
+        if let Some(secret) = self.epoch_secret_hex.as_deref() {
+            decode_epoch_secret(secret)?;
+        }
+        Ok(())
+    }
+
+    pub fn is_member(&self, pubkey: &Pubkey) -> bool {
+        self.members.contains(pubkey)
+    }
+}
+
+impl GroupDirectMessageFrameV1 {
+    pub fn verify(&self) -> Result<()> {
+        validate_group_direct_message_id(self.group_id.as_str())?;
+        validate_pubkey(self.sender.as_str())
+            .context("invalid group direct message sender pubkey")?;
+        if self.message_id.trim().is_empty() {
+            bail!("group direct message frame message_id is required");
+        }
+        let nonce = hex::decode(self.nonce_hex.trim())
+            .context("invalid group direct message frame nonce")?;
+        if nonce.len() != 24 {
+            bail!("group direct message frame nonce must be 24 bytes");
+        }
+        let _ = hex::decode(self.ciphertext_hex.trim())
+            .context("invalid group direct message frame ciphertext")?;
+        let signature = Signature::from_str(self.signature.as_str())
+            .context("invalid group direct message frame signature")?;
+        let sender =
+            XOnlyPublicKey::from_str(self.sender.as_str()).context("invalid frame sender")?;
+        let digest = sha256_digest(canonical_group_direct_message_frame_payload(self)?.as_bytes());
+        SECP256K1
+            .verify_schnorr(&signature, &digest, &sender)
+            .context("group direct message frame signature verification failed")?;
+        Ok(())
+    }
+}
+
+pub fn generate_group_direct_message_id() -> String {
+    let mut bytes = [0u8; 16];
+    rng().fill_bytes(&mut bytes);
+    format!("{GROUP_DIRECT_MESSAGE_ID_PREFIX}{}", hex::encode(bytes))
+}
+
+pub fn is_group_direct_message_id(value: &str) -> bool {
+    validate_group_direct_message_id(value).is_ok()
+}
+
+pub fn generate_group_direct_message_epoch_secret() -> String {
+    let mut secret = [0u8; 32];
+    rng().fill_bytes(&mut secret);
+    hex::encode(secret)
+}
+
+/// owner を加えて重複を除き、pubkey 順に並べたメンバー一覧を返す。人数が範囲外ならエラー。
+pub fn normalize_group_direct_message_members(
+    owner: &Pubkey,
+    members: &[Pubkey],
+) -> Result<Vec<Pubkey>> {
+    let mut normalized = BTreeSet::new();
+    for member in members.iter().chain(std::iter::once(owner)) {
+        validate_pubkey(member.as_str()).context("invalid group direct message member")?;
+        normalized.insert(member.as_str().to_string());
+    }
+    if normalized.len() < GROUP_DIRECT_MESSAGE_MIN_MEMBERS {
+        bail!(
+            "group direct message requires at least {GROUP_DIRECT_MESSAGE_MIN_MEMBERS} members including the owner"
+        );
+    }
+    if normalized.len() > GROUP_DIRECT_MESSAGE_MAX_MEMBERS {
+        bail!(
+            "group direct message allows at most {GROUP_DIRECT_MESSAGE_MAX_MEMBERS} members including the owner"
+        );
+    }
+    Ok(normalized.into_iter().map(Pubkey::from).collect())
+}
+
+/// epoch ごとの hint topic。外されたメンバーは新しい epoch の topic を導出できない。
+pub fn derive_group_direct_message_topic(
+    group_id: &str,
+    epoch: u64,
+    epoch_secret_hex: &str,
+) -> Result<TopicId> {
+    let topic_key = derive_hkdf_key(
+        b"kukuri/group-direct-message/topic",
+        decode_epoch_secret(epoch_secret_hex)?.as_slice(),
+        format!("kukuri:group-direct-message:topic:{group_id}:{epoch}").as_bytes(),
+        "group direct message topic",
+    )?;
+    Ok(TopicId::new(format!(
+        "{}{}",
+        crate::wire::DM_TOPIC_PREFIX,
+        hex::encode(topic_key)
+    )))
+}
+
+pub fn encrypt_group_direct_message_frame(
+    local_keys: &KukuriKeys,
+    group_id: &str,
+    epoch: u64,
+    epoch_secret_hex: &str,
+    message_id: &str,
+    created_at: i64,
+    payload: &DirectMessagePayloadV1,
+) -> Result<GroupDirectMessageFrameV1> {
+    validate_group_direct_message_id(group_id)?;
+    if message_id.trim().is_empty() {
+        bail!("group direct message message_id is required");
+    }
+    let sender = local_keys.public_key();
+    let plaintext =
+        serde_json::to_vec(payload).context("failed to encode group direct message payload")?;
+    let mut nonce = [0u8; 24];
+    rng().fill_bytes(&mut nonce);
+    let aad = group_direct_message_frame_aad(
+        group_id,
+        epoch,
+        message_id.trim(),
+        sender.as_str(),
+        created_at,
+    );
+    let ciphertext = group_direct_message_frame_cipher(epoch_secret_hex, aad.as_str())?
+        .encrypt(
+            &XNonce::from(nonce),
+            Payload {
+                msg: plaintext.as_slice(),
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("failed to encrypt group direct message frame"))?;
+    let mut frame = GroupDirectMessageFrameV1 {
+        group_id: group_id.to_string(),
+        epoch,
+        message_id: message_id.trim().to_string(),
+        sender,
+        created_at,
+        nonce_hex: hex::encode(nonce),
+        ciphertext_hex: hex::encode(ciphertext),
+        signature: String::new(),
+    };
+    let digest = sha256_digest(canonical_group_direct_message_frame_payload(&frame)?.as_bytes());
+    frame.signature = local_keys.sign_schnorr(&digest).to_string();
+    Ok(frame)
+}
+
+/// frame の epoch に対応する secret で復号する。送信者がその epoch のメンバーかどうかは
+/// 呼び出し側が確認する。
+pub fn decrypt_group_direct_message_frame(
+    epoch_secret_hex: &str,
+    frame: &GroupDirectMessageFrameV1,
+) -> Result<DirectMessagePayloadV1> {
+    frame.verify()?;
+    let nonce =
+        hex::decode(frame.nonce_hex.trim()).context("invalid group direct message frame nonce")?;
+    let ciphertext = hex::decode(frame.ciphertext_hex.trim())
+        .context("invalid group direct message frame ciphertext")?;
+    let aad = group_direct_message_frame_aad(
+        frame.group_id.as_str(),
+        frame.epoch,
+        frame.message_id.as_str(),
+        frame.sender.as_str(),
+        frame.created_at,
+    );
+    let plaintext = group_direct_message_frame_cipher(epoch_secret_hex, aad.as_str())?
+        .decrypt(
+            <&XNonce>::try_from(nonce.as_slice()).expect("nonce length checked"),
+            Payload {
+                msg: ciphertext.as_slice(),
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("failed to decrypt group direct message frame"))?;
+    serde_json::from_slice(&plaintext).context("failed to decode group direct message payload")
+}
+
+fn validate_group_direct_message_id(group_id: &str) -> Result<()> {
+    let Some(suffix) = group_id.strip_prefix(GROUP_DIRECT_MESSAGE_ID_PREFIX) else {
+        bail!("group direct message id must start with {GROUP_DIRECT_MESSAGE_ID_PREFIX}");
+    };
+    if suffix.len() != 32 || !suffix.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        bail!("group direct message id must end with 32 hex characters");
+    }
+    Ok(())
+}
+
+fn decode_epoch_secret(epoch_secret_hex: &str) -> Result<[u8; 32]> {
+    hex::decode(epoch_secret_hex.trim())
+        .context("invalid group direct message epoch secret")?
+        .try_into()
+        .map_err(|_| anyhow!("group direct message epoch secret must be 32 bytes"))
+}
+
+fn group_direct_message_frame_cipher(
+    epoch_secret_hex: &str,
+    aad: &str,
+) -> Result<XChaCha20Poly1305> {
+    let key = derive_hkdf_key(
+        b"kukuri/group-direct-message/frame",
+        decode_epoch_secret(epoch_secret_hex)?.as_slice(),
+        aad.as_bytes(),
+        "group direct message frame key",
+    )?;
+    XChaCha20Poly1305::new_from_slice(key.as_slice())
+        .context("failed to initialize group direct message frame cipher")
+}
+
+pub(crate) fn canonical_group_direct_message_frame_payload(
+    frame: &GroupDirectMessageFrameV1,
+) -> Result<String> {
+    serde_json::to_string(&serde_json::json!([
+        0,
+        frame.group_id,
+        frame.epoch,
+        frame.message_id,
+        frame.sender,
+        frame.created_at,
+        frame.nonce_hex,
+        frame.ciphertext_hex
+    ]))
+    .context("failed to encode canonical group direct message frame payload")
+}
+
+fn group_direct_message_frame_aad(
+    group_id: &str,
+    epoch: u64,
+    message_id: &str,
+    sender: &str,
+    created_at: i64,
+) -> String {
+    format!(
+        "kukuri:group-direct-message:frame:{group_id}:{epoch}:{message_id}:{sender}:{created_at}"
+    )
+}
@@ -0,0 +1,234 @@
+use anyhow::{Context, Result, anyhow, bail};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use secp256k1::rand::{RngCore, rng};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::{KukuriKeys, Pubkey};
+
+pub const IDENTITY_BACKUP_VERSION_V1: u32 = 1;
+pub const IDENTITY_BACKUP_KDF_ARGON2ID: &str = "argon2id";
+pub const IDENTITY_BACKUP_PASSPHRASE_MIN_CHARS: usize = 12;
+const IDENTITY_BACKUP_AAD_PREFIX: &str = "kukuri-identity-backup-v1";
+const IDENTITY_BACKUP_SALT_LEN: usize = 16;
+const IDENTITY_BACKUP_NONCE_LEN: usize = 24;
+// import 時に巨大な KDF 引数で端末を固めさせないための上限。
+const IDENTITY_BACKUP_MAX_MEMORY_KIB: u32 = 1024 * 1024;
+const IDENTITY_BACKUP_MAX_ITERATIONS: u32 = 16;
+const IDENTITY_BACKUP_MAX_PARALLELISM: u32 = 8;
+
+/// passphrase から鍵を導く argon2id の引数。bundle に平文で残し、復元時に同じ引数で導出する。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityBackupKdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for IdentityBackupKdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityBackupKdfV1 {
+    pub algorithm: String,
+    pub salt_hex: String,
+    #[serde(flatten)]
+    pub params: IdentityBackupKdfParams,
+}
+
+/// passphrase で暗号化した identity backup。author 鍵と端末内 state をまとめて 1 ファイルにする。
+///
+/// `author_pubkey` / `created_at` は復元前の確認表示用に平文で持ち、AAD で暗号文に束縛する。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityBackupBundleV1 {
+    pub version: u32,
+    pub author_pubkey: Pubkey,
+    pub created_at: i64,
+    pub kdf: IdentityBackupKdfV1,
+    pub nonce_hex: String,
+    pub ciphertext_hex: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IdentityBackupPlaintextV1<T> {
+    secret_key_hex: String,
+    state: T,
+}
+
+#[derive(Clone, Debug)]
+pub struct IdentityBackup<T> {
+    pub keys: KukuriKeys,
+    pub created_at: i64,
+    pub state: T,
+}
+
+pub fn seal_identity_backup<T: Serialize>(
+    keys: &KukuriKeys,
+    state: &T,
+    passphrase: &str,
+    created_at: i64,
+) -> Result<String> {
+    seal_identity_backup_with_params(
+        keys,
+        state,
+        passphrase,
+        created_at,
+        IdentityBackupKdfParams::default(),
+    )
+}
+
+pub fn seal_identity_backup_with_params<T: Serialize>(
+    keys: &KukuriKeys,
+    state: &T,
+    passphrase: &str,
+    created_at: i64,
+    params: IdentityBackupKdfParams,
+) -> Result<String> {
+    validate_identity_backup_passphrase(passphrase)?;
+    validate_identity_backup_kdf_params(&params)?;
+    let mut salt = [0u8; IDENTITY_BACKUP_SALT_LEN];
+    rng().fill_bytes(&mut salt);
+    let mut nonce = [0u8; IDENTITY_BACKUP_NONCE_LEN];
+    rng().fill_bytes(&mut nonce);
+    let kdf = IdentityBackupKdfV1 {
+        algorithm: IDENTITY_BACKUP_KDF_ARGON2ID.to_string(),
+        salt_hex: hex::encode(salt),
+        params,
+    };
+    let author_pubkey = keys.public_key();
+    let plaintext = serde_json::to_vec(&IdentityBackupPlaintextV1 {
+        secret_key_hex: keys.export_secret_hex(),
+        state,
+    })
+    .context("failed to encode identity backup")?;
+    let aad = identity_backup_aad(&author_pubkey, created_at);
+    let ciphertext = identity_backup_cipher(passphrase, &salt, &params)?
+        .encrypt(
+            &XNonce::from(nonce),
+            Payload {
+                msg: plaintext.as_slice(),
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("failed to encrypt identity backup"))?;
+    let bundle = IdentityBackupBundleV1 {
+        version: IDENTITY_BACKUP_VERSION_V1,
+        author_pubkey,
+        created_at,
+        kdf,
+        nonce_hex: hex::encode(nonce),
+        ciphertext_hex: hex::encode(ciphertext),
+    };
+    serde_json::to_string(&bundle).context("failed to encode identity backup bundle")
+}
+
+/// bundle の形式だけを検証して返す(passphrase 不要)。復元前の確認表示に使う。
+pub fn parse_identity_backup_bundle(bundle: &str) -> Result<IdentityBackupBundleV1> {
+    let bundle: IdentityBackupBundleV1 =
+        serde_json::from_str(bundle.trim()).context("failed to parse identity backup bundle")?;
+    if bundle.version != IDENTITY_BACKUP_VERSION_V1 {
+        bail!("unsupported identity backup version {}", bundle.version);
+    }
+    if bundle.kdf.algorithm != IDENTITY_BACKUP_KDF_ARGON2ID {
+        bail!("unsupported identity backup kdf `{}`", bundle.kdf.algorithm);
+    }
+    validate_identity_backup_kdf_params(&bundle.kdf.params)?;
+    crate::crypto::validate_pubkey(bundle.author_pubkey.as_str())
+        .context("invalid identity backup author pubkey")?;
+    Ok(bundle)
+}
+
+pub fn open_identity_backup<T: DeserializeOwned>(
+    bundle: &str,
+    passphrase: &str,
+) -> Result<IdentityBackup<T>> {
+    let bundle = parse_identity_backup_bundle(bundle)?;
+    let salt = hex::decode(bundle.kdf.salt_hex.trim()).context("invalid identity backup salt")?;
+    if salt.len() != IDENTITY_BACKUP_SALT_LEN {
+        bail!("identity backup salt must be {IDENTITY_BACKUP_SALT_LEN} bytes");
+    }
+    let nonce = hex::decode(bundle.nonce_hex.trim()).context("invalid identity backup nonce")?;
+    if nonce.len() != IDENTITY_BACKUP_NONCE_LEN {
+        bail!("identity backup nonce must be {IDENTITY_BACKUP_NONCE_LEN} bytes");
+    }
+    let ciphertext =
+        hex::decode(bundle.ciphertext_hex.trim()).context("invalid identity backup ciphertext")?;
+    let aad = identity_backup_aad(&bundle.author_pubkey, bundle.created_at);
+    let plaintext = identity_backup_cipher(passphrase, salt.as_slice(), &bundle.kdf.params)?
+        .decrypt(
+            <&XNonce>::try_from(nonce.as_slice()).expect("nonce length checked"),
+            Payload {
+                msg: ciphertext.as_slice(),
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("identity backup passphrase is incorrect"))?;
+    let plaintext: IdentityBackupPlaintextV1<T> =
+        serde_json::from_slice(&plaintext).context("failed to decode identity backup")?;
+    let keys = KukuriKeys::parse(plaintext.secret_key_hex.as_str())?;
+    if keys.public_key() != bundle.author_pubkey {
+        bail!("identity backup secret does not match its author pubkey");
+    }
+    Ok(IdentityBackup {
+        keys,
+        created_at: bundle.created_at,
+        state: plaintext.state,
+    })
+}
+
+fn validate_identity_backup_passphrase(passphrase: &str) -> Result<()> {
+    if passphrase.chars().count() < IDENTITY_BACKUP_PASSPHRASE_MIN_CHARS {
+        bail!(
+            "identity backup passphrase must be at least {IDENTITY_BACKUP_PASSPHRASE_MIN_CHARS} characters"
+        );
+    }
+    Ok(())
+}
+
+fn validate_identity_backup_kdf_params(params: &IdentityBackupKdfParams) -> Result<()> {
+    if params.memory_kib > IDENTITY_BACKUP_MAX_MEMORY_KIB {
+        bail!("identity backup kdf memory exceeds {IDENTITY_BACKUP_MAX_MEMORY_KIB} KiB");
+    }
+    if params.iterations == 0 || params.iterations > IDENTITY_BACKUP_MAX_ITERATIONS {
+        bail!("identity backup kdf iterations must be 1..={IDENTITY_BACKUP_MAX_ITERATIONS}");
+    }
+    if params.parallelism == 0 || params.parallelism > IDENTITY_BACKUP_MAX_PARALLELISM {
+        bail!("identity backup kdf parallelism must be 1..={IDENTITY_BACKUP_MAX_PARALLELISM}");
+    }
+    Ok(())
+}
+
+fn identity_backup_aad(author_pubkey: &Pubkey, created_at: i64) -> String {
+    format!(
+        "{IDENTITY_BACKUP_AAD_PREFIX}:{}:{created_at}",
+        author_pubkey.as_str()
+    )
+}
+
+fn identity_backup_cipher(
+    passphrase: &str,
+    salt: &[u8],
+    params: &IdentityBackupKdfParams,
+) -> Result<XChaCha20Poly1305> {
+    let params = Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(32),
+    )
+    .map_err(|error| anyhow!("invalid identity backup kdf params: {error}"))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|error| anyhow!("failed to derive identity backup key: {error}"))?;
+    XChaCha20Poly1305::new_from_slice(&key).context("failed to initialize identity backup cipher")
+}
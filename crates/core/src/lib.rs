@@ -1,7 +1,11 @@
 mod crypto;
+mod devices;
+mod direct_message_ratchet;
 mod direct_messages;
 mod envelope;
 mod game;
+mod group_direct_messages;
+mod identity_backup;
 mod ids;
 mod live;
 mod media;
@@ -18,6 +22,22 @@ mod tests;
 pub use crypto::{
     KukuriKeys, LEGACY_SECRET_HRP, encode_secret_key_bech32, generate_keys, is_placeholder_secret,
 };
+pub use devices::{
+    DEVICE_AUTHORIZATION_KIND, DeviceAuthorization, DevicePairing, DevicePairingTicketV1,
+    DeviceStateDocV1, KukuriDeviceAuthorizationEnvelopeContentV1,
+    build_device_authorization_envelope, build_device_pairing_ticket, decrypt_device_state_doc,
+    encrypt_device_state_doc, generate_device_id, generate_device_pairing_code,
+    open_device_pairing_ticket, parse_device_authorization, validate_device_id,
+};
+pub use direct_message_ratchet::{
+    DIRECT_MESSAGE_FRAME_VERSION_V1, DIRECT_MESSAGE_FRAME_VERSION_V2,
+    DIRECT_MESSAGE_MAX_SKIPPED_KEYS, DirectMessageFrame, DirectMessageFrameV2,
+    DirectMessageRatchetDecryption, DirectMessageRatchetEncryption, DirectMessageRatchetHeaderV2,
+    DirectMessageRatchetStateV2, DirectMessageSkippedKeyV2,
+    decrypt_direct_message_attachment_with_key, decrypt_direct_message_frame_v2,
+    encrypt_direct_message_attachment_with_key, encrypt_direct_message_frame_v2,
+    generate_direct_message_attachment_key, supported_direct_message_frame_versions,
+};
 pub use direct_messages::{
     DirectMessageAckV1, DirectMessageAttachmentKind, DirectMessageAttachmentManifestV1,
     DirectMessageEncryptedAttachmentV1, DirectMessageEncryptedBlobRefV1, DirectMessageFrameV1,
@@ -37,6 +57,19 @@ pub use game::{
     MetaverseRoomStateV1, SharedRoomObjectV1, build_game_session_envelope,
     build_metaverse_room_event_envelope,
 };
+pub use group_direct_messages::{
+    GROUP_DIRECT_MESSAGE_MAX_MEMBERS, GROUP_DIRECT_MESSAGE_MIN_MEMBERS, GroupDirectMessageFrameV1,
+    GroupDirectMessageKeyUpdateV1, decrypt_group_direct_message_frame,
+    derive_group_direct_message_topic, encrypt_group_direct_message_frame,
+    generate_group_direct_message_epoch_secret, generate_group_direct_message_id,
+    is_group_direct_message_id, normalize_group_direct_message_members,
+};
+pub use identity_backup::{
+    IDENTITY_BACKUP_KDF_ARGON2ID, IDENTITY_BACKUP_PASSPHRASE_MIN_CHARS, IDENTITY_BACKUP_VERSION_V1,
+    IdentityBackup, IdentityBackupBundleV1, IdentityBackupKdfParams, IdentityBackupKdfV1,
+    open_identity_backup, parse_identity_backup_bundle, seal_identity_backup,
+    seal_identity_backup_with_params,
+};
 pub use ids::{
     BlobHash, ChannelId, EnvelopeId, Pubkey, ReplicaId, TopicId, author_profile_topic_id,
 };
@@ -49,10 +82,12 @@ pub use media::{
     ManifestBlobRef, MediaManifestItem, blob_hash, build_media_manifest_envelope,
 };
 pub use posts::{
-    CanonicalPostHeader, ChannelRef, KukuriPostEnvelopeContentV1, KukuriPostObjectV1, ObjectStatus,
-    ObjectVisibility, PayloadRef, RepostSourceSnapshotV1, ThreadRef, TimelineScope,
-    build_post_envelope, build_post_envelope_with_payload,
-    build_post_envelope_with_payload_in_channel, build_repost_envelope, timeline_sort_key,
+    CanonicalPostHeader, ChannelRef, KukuriPostEnvelopeContentV1, KukuriPostObjectV1,
+    KukuriPostRevisionEnvelopeContentV1, ObjectStatus, ObjectVisibility, PayloadRef,
+    RepostSourceSnapshotV1, ThreadRef, TimelineScope, apply_post_revision,
+    build_post_edit_envelope, build_post_envelope, build_post_envelope_with_payload,
+    build_post_envelope_with_payload_in_channel, build_post_tombstone_envelope,
+    build_repost_envelope, timeline_sort_key,
 };
 pub use private_channels::{
     ChannelAudienceKind, ChannelSharingState, CreatePrivateChannelInput, FriendOnlyGrantPreview,
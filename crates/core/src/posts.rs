@@ -377,3 +377,162 @@ pub fn build_repost_envelope(
         &content,
     )
 }
+
+/// 既存 post / comment の編集・取り消しを表す revision envelope の content。
+///
+/// 元 object の `object_id` を参照し、object state を上書きする。元 envelope は
+/// `objects/{id}/envelope` に残り、revision envelope は object ごとの履歴として別 key に積む。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KukuriPostRevisionEnvelopeContentV1 {
+    pub object_id: EnvelopeId,
+    pub topic_id: TopicId,
+    #[serde(default)]
+    pub channel_id: Option<ChannelId>,
+    pub previous_envelope_id: EnvelopeId,
+    pub status: ObjectStatus,
+    #[serde(default)]
+    pub payload_ref: Option<PayloadRef>,
+    #[serde(default)]
+    pub attachments: Vec<AssetRef>,
+    #[serde(default)]
+    pub media_manifest_refs: Vec<String>,
+}
+
+impl KukuriEnvelope {
+    pub fn post_revision_content(&self) -> Result<Option<KukuriPostRevisionEnvelopeContentV1>> {
+        if !matches!(self.kind.as_str(), "post-edit" | "post-tombstone") {
+            return Ok(None);
+        }
+        serde_json::from_str(self.content.as_str())
+            .map(Some)
+            .context("failed to parse post revision envelope content")
+    }
+}
+
+pub fn build_post_edit_envelope(
+    keys: &KukuriKeys,
+    current: &KukuriPostObjectV1,
+    payload_ref: PayloadRef,
+    attachments: Vec<AssetRef>,
+    media_manifest_refs: Vec<String>,
+) -> Result<KukuriEnvelope> {
+    build_post_revision_envelope(
+        keys,
+        "post-edit",
+        current,
+        KukuriPostRevisionEnvelopeContentV1 {
+            object_id: current.object_id.clone(),
+            topic_id: current.topic_id.clone(),
+            channel_id: current.channel_id.clone(),
+            previous_envelope_id: current.envelope_id.clone(),
+            status: ObjectStatus::Edited,
+            payload_ref: Some(payload_ref),
+            attachments,
+            media_manifest_refs,
+        },
+    )
+}
+
+pub fn build_post_tombstone_envelope(
+    keys: &KukuriKeys,
+    current: &KukuriPostObjectV1,
+) -> Result<KukuriEnvelope> {
+    build_post_revision_envelope(
+        keys,
+        "post-tombstone",
+        current,
+        KukuriPostRevisionEnvelopeContentV1 {
+            object_id: current.object_id.clone(),
+            topic_id: current.topic_id.clone(),
+            channel_id: current.channel_id.clone(),
+            previous_envelope_id: current.envelope_id.clone(),
+            status: ObjectStatus::Deleted,
+            payload_ref: None,
+            attachments: Vec::new(),
+            media_manifest_refs: Vec::new(),
+        },
+    )
+}
+
+fn build_post_revision_envelope(
+    keys: &KukuriKeys,
+    kind: &str,
+    current: &KukuriPostObjectV1,
+    content: KukuriPostRevisionEnvelopeContentV1,
+) -> Result<KukuriEnvelope> {
+    if !matches!(current.object_kind.as_str(), "post" | "comment") {
+        bail!("only posts and comments can be revised");
+    }
+    if keys.public_key() != current.author {
+        bail!("only the author can revise a post");
+    }
+    if matches!(
+        current.status,
+        ObjectStatus::Deleted | ObjectStatus::Tombstoned
+    ) {
+        bail!("deleted posts cannot be revised");
+    }
+    let mut tags = vec![
+        vec!["topic".into(), current.topic_id.as_str().into()],
+        vec!["object".into(), kind.into()],
+        vec![
+            "target_object".into(),
+            current.object_id.as_str().to_string(),
+        ],
+    ];
+    if let Some(channel_id) = current.channel_id.as_ref() {
+        tags.push(vec!["channel".into(), channel_id.as_str().to_string()]);
+    }
+    crate::sign_envelope_json(keys, kind, tags, &content)
+}
+
+/// revision envelope を現在の object state に適用した次の state を返す。
+///
+/// 署名検証は呼び出し側の責務。ここでは作者・対象 object・適用順序の整合のみを検査する。
+pub fn apply_post_revision(
+    current: &KukuriPostObjectV1,
+    revision: &KukuriEnvelope,
+) -> Result<KukuriPostObjectV1> {
+    let content = revision
+        .post_revision_content()?
+        .context("envelope is not a post revision")?;
+    if content.object_id != current.object_id {
+        bail!("post revision targets a different object");
+    }
+    if revision.pubkey != current.author {
+        bail!("post revision author does not match the object author");
+    }
+    if matches!(
+        current.status,
+        ObjectStatus::Deleted | ObjectStatus::Tombstoned
+    ) {
+        bail!("deleted posts cannot be revised");
+    }
+    if revision.created_at < current.updated_at {
+        bail!("post revision is older than the current object state");
+    }
+    let mut next = current.clone();
+    next.envelope_id = revision.id.clone();
+    next.updated_at = revision.created_at;
+    next.signature = revision.sig.clone();
+    match (revision.kind.as_str(), content.status) {
+        ("post-edit", ObjectStatus::Edited) => {
+            next.payload_ref = content
+                .payload_ref
+                .context("post edit revision is missing payload")?;
+            next.attachments = content.attachments;
+            next.media_manifest_refs = content.media_manifest_refs;
+            next.status = ObjectStatus::Edited;
+        }
+        ("post-tombstone", status @ (ObjectStatus::Deleted | ObjectStatus::Tombstoned)) => {
+            next.payload_ref = PayloadRef::InlineText {
+                text: String::new(),
+            };
+            next.attachments = Vec::new();
+            next.media_manifest_refs = Vec::new();
+            next.status = status;
+        }
+        _ => bail!("post revision status does not match its kind"),
+    }
+    Ok(next)
+}
@@ -0,0 +1,75 @@
+use crate::*;
+
+#[test]
+fn device_pairing_ticket_roundtrip_requires_matching_code() {
+    let keys = generate_keys();
+    let device_id = generate_device_id();
+    let authorization =
+        build_device_authorization_envelope(&keys, device_id.as_str(), "laptop", Some("desktop"))
+            .expect("device authorization");
+    let parsed = parse_device_authorization(&authorization).expect("parse authorization");
+    assert_eq!(parsed.author_pubkey, keys.public_key());
+    assert_eq!(parsed.device_id, device_id);
+    assert_eq!(parsed.authorized_by_device_id.as_deref(), Some("desktop"));
+
+    let code = generate_device_pairing_code();
+    let ticket = build_device_pairing_ticket(&keys, &authorization, code.as_str(), i64::MAX)
+        .expect("pairing ticket");
+    assert!(!ticket.contains(keys.export_secret_hex().as_str()));
+
+    let typed_code = format!(" {} ", code.replace('-', "").to_ascii_uppercase());
+    let pairing =
+        open_device_pairing_ticket(ticket.as_str(), typed_code.as_str(), 0).expect("open ticket");
+    assert_eq!(pairing.keys.public_key(), keys.public_key());
+    assert_eq!(pairing.authorization, parsed);
+
+    let error = open_device_pairing_ticket(ticket.as_str(), "0000-0000-0000-0000", 0)
+        .expect_err("wrong code must fail");
+    assert!(error.to_string().contains("incorrect"));
+    let error = open_device_pairing_ticket(ticket.as_str(), code.as_str(), i64::MAX)
+        .expect_err("expired ticket must fail");
+    assert!(error.to_string().contains("expired"));
+}
+
+#[test]
+fn device_pairing_ticket_rejects_tampered_expiry() {
+    let keys = generate_keys();
+    let authorization = build_device_authorization_envelope(&keys, "device-1", "laptop", None)
+        .expect("device authorization");
+    let code = generate_device_pairing_code();
+    let ticket = build_device_pairing_ticket(&keys, &authorization, code.as_str(), 1_000)
+        .expect("pairing ticket");
+    let mut tampered: DevicePairingTicketV1 =
+        serde_json::from_str(ticket.as_str()).expect("decode ticket");
+    tampered.expires_at = i64::MAX;
+    let tampered = serde_json::to_string(&tampered).expect("encode ticket");
+
+    let error = open_device_pairing_ticket(tampered.as_str(), code.as_str(), 2_000)
+        .expect_err("extended expiry must not decrypt");
+    assert!(error.to_string().contains("incorrect"));
+}
+
+#[test]
+fn device_authorization_rejects_invalid_ids_and_tampering() {
+    let keys = generate_keys();
+    assert!(build_device_authorization_envelope(&keys, "device::1", "laptop", None).is_err());
+    assert!(build_device_authorization_envelope(&keys, "device-1", "  ", None).is_err());
+
+    let mut authorization = build_device_authorization_envelope(&keys, "device-1", "laptop", None)
+        .expect("device authorization");
+    authorization.tags = vec![vec!["device".into(), "device-2".into()]];
+    assert!(parse_device_authorization(&authorization).is_err());
+}
+
+#[test]
+fn device_state_doc_is_bound_to_author_and_doc_key() {
+    let keys = generate_keys();
+    let doc = encrypt_device_state_doc(&keys, "mutes/abc", &vec!["abc".to_string()])
+        .expect("encrypt device state");
+    let decoded: Vec<String> =
+        decrypt_device_state_doc(&keys, "mutes/abc", &doc).expect("decrypt device state");
+    assert_eq!(decoded, vec!["abc".to_string()]);
+
+    assert!(decrypt_device_state_doc::<Vec<String>>(&keys, "mutes/other", &doc).is_err());
+    assert!(decrypt_device_state_doc::<Vec<String>>(&generate_keys(), "mutes/abc", &doc).is_err());
+}
@@ -27,6 +27,10 @@ fn dm_frame_encrypt_decrypt_roundtrip_and_tamper_reject() {
         text: Some("hello bob".into()),
         reply_to: Some("message-0".into()),
         attachment_manifest: None,
+        supported_frame_versions: Vec::new(),
+        attachment_key_hex: None,
+        group_key_update: None,
+        session_prekey: None,
     };
 
     let frame = encrypt_direct_message_frame(
@@ -90,3 +94,230 @@ fn dm_ack_signature_verification_rejects_wrong_signer() {
     let error = tampered.verify().expect_err("tampered ack must fail");
     assert!(error.to_string().contains("signature"));
 }
+
+/// テスト用の ratchet 端点。store の代わりに state と skipped key、session prekey を保持する。
+struct RatchetPeer {
+    keys: KukuriKeys,
+    state: Option<DirectMessageRatchetStateV2>,
+    skipped: std::collections::HashMap<(Pubkey, u32), String>,
+    session_prekey: Option<KukuriKeys>,
+    peer_prekey: Option<Pubkey>,
+}
+
+impl RatchetPeer {
+    fn new(keys: KukuriKeys) -> Self {
+        Self {
+            keys,
+            state: None,
+            skipped: std::collections::HashMap::new(),
+            session_prekey: None,
+            peer_prekey: None,
+        }
+    }
+
+    fn send(&mut self, peer: &Pubkey, message_id: &str, text: &str) -> DirectMessageFrameV2 {
+        let dm_id = direct_message_id_for_participants(&self.keys.public_key(), peer);
+        let encrypted = encrypt_direct_message_frame_v2(
+            &self.keys,
+            self.state.as_ref(),
+            peer,
+            self.peer_prekey.as_ref(),
+            dm_id.as_str(),
+            message_id,
+            1_000,
+            &DirectMessagePayloadV1 {
+                text: Some(text.into()),
+                ..Default::default()
+            },
+        )
+        .expect("encrypt v2 frame");
+        self.state = Some(encrypted.state);
+        encrypted.frame
+    }
+
+    fn receive(&mut self, frame: &DirectMessageFrameV2) -> anyhow::Result<String> {
+        let skipped_key = self.skipped.remove(&(
+            frame.header.ratchet_pubkey.clone(),
+            frame.header.message_number,
+        ));
+        let decrypted = decrypt_direct_message_frame_v2(
+            &self.keys,
+            self.session_prekey.as_ref(),
+            self.state.as_ref(),
+            frame,
+            skipped_key.as_deref(),
+        )?;
+        if let Some(state) = decrypted.state {
+            self.state = Some(state);
+        }
+        if decrypted.session_prekey_consumed {
+            self.session_prekey = Some(KukuriKeys::generate());
+        }
+        for key in decrypted.skipped_keys {
+            self.skipped.insert(
+                (key.ratchet_pubkey, key.message_number),
+                key.message_key_hex,
+            );
+        }
+        Ok(decrypted.payload.text.unwrap_or_default())
+    }
+}
+
+#[test]
+fn dm_v2_ratchet_decrypts_out_of_order_and_rotates_keys() {
+    let mut alice = RatchetPeer::new(generate_keys());
+    let mut bob = RatchetPeer::new(generate_keys());
+    let alice_pubkey = alice.keys.public_key();
+    let bob_pubkey = bob.keys.public_key();
+
+    let first = alice.send(&bob_pubkey, "m-1", "one");
+    let second = alice.send(&bob_pubkey, "m-2", "two");
+    let third = alice.send(&bob_pubkey, "m-3", "three");
+    assert!(first.header.session_init);
+    assert_eq!(third.header.message_number, 2);
+
+    assert_eq!(bob.receive(&third).expect("third"), "three");
+    assert_eq!(bob.skipped.len(), 2);
+    assert_eq!(bob.receive(&first).expect("first"), "one");
+    assert_eq!(bob.receive(&second).expect("second"), "two");
+    assert!(bob.skipped.is_empty());
+    let replay = bob.receive(&second).expect_err("replayed frame must fail");
+    assert!(replay.to_string().contains("already used"));
+
+    let reply = bob.send(&alice_pubkey, "m-4", "reply");
+    assert!(!reply.header.session_init);
+    assert_ne!(reply.header.ratchet_pubkey, first.header.ratchet_pubkey);
+    assert_eq!(alice.receive(&reply).expect("reply"), "reply");
+
+    // 返信を受けた側は DH ratchet を回し、前の chain 長を header に載せる。
+    let next = alice.send(&bob_pubkey, "m-5", "next");
+    assert!(!next.header.session_init);
+    assert_ne!(next.header.ratchet_pubkey, first.header.ratchet_pubkey);
+    assert_eq!(next.header.previous_chain_length, 3);
+    let late = alice.send(&bob_pubkey, "m-6", "late");
+    assert_eq!(bob.receive(&late).expect("late"), "late");
+    assert_eq!(bob.receive(&next).expect("next"), "next");
+
+    // 旧 chain の frame は ratchet 後の state では導出できない。
+    let mut fresh_bob = RatchetPeer::new(bob.keys.clone());
+    fresh_bob.state = bob.state.clone();
+    let error = fresh_bob
+        .receive(&reply)
+        .expect_err("own frame is not decryptable");
+    assert!(error.to_string().contains("recipient"));
+}
+
+#[test]
+fn dm_v2_concurrent_session_init_converges_on_one_session() {
+    let mut alice = RatchetPeer::new(generate_keys());
+    let mut bob = RatchetPeer::new(generate_keys());
+    let alice_pubkey = alice.keys.public_key();
+    let bob_pubkey = bob.keys.public_key();
+
+    let from_alice = alice.send(&bob_pubkey, "a-1", "hi bob");
+    let from_bob = bob.send(&alice_pubkey, "b-1", "hi alice");
+    assert!(from_alice.header.session_init && from_bob.header.session_init);
+
+    assert_eq!(
+        alice.receive(&from_bob).expect("alice reads bob"),
+        "hi alice"
+    );
+    assert_eq!(bob.receive(&from_alice).expect("bob reads alice"), "hi bob");
+
+    // 衝突後は双方の送信がもう一方の session で復号できる。
+    for round in 0..3 {
+        let ping = alice.send(&bob_pubkey, format!("a-ping-{round}").as_str(), "ping");
+        assert_eq!(bob.receive(&ping).expect("ping"), "ping");
+        let pong = bob.send(&alice_pubkey, format!("b-pong-{round}").as_str(), "pong");
+        assert_eq!(alice.receive(&pong).expect("pong"), "pong");
+    }
+    let alice_state = alice.state.as_ref().expect("alice state");
+    let bob_state = bob.state.as_ref().expect("bob state");
+    assert!(!alice_state.awaiting_first_reply && !bob_state.awaiting_first_reply);
+}
+
+#[test]
+fn dm_v2_session_init_to_prekey_needs_the_retired_prekey_not_identity() {
+    let mut alice = RatchetPeer::new(generate_keys());
+    let mut bob = RatchetPeer::new(generate_keys());
+    let bob_pubkey = bob.keys.public_key();
+    let bob_prekey = KukuriKeys::generate();
+    bob.session_prekey = Some(bob_prekey.clone());
+    alice.peer_prekey = Some(bob_prekey.public_key());
+
+    let first = alice.send(&bob_pubkey, "m-1", "one");
+    let second = alice.send(&bob_pubkey, "m-2", "two");
+    assert!(first.header.session_init);
+    assert_eq!(first.header.recipient_prekey, Some(bob_prekey.public_key()));
+
+    // identity 秘密鍵だけを持つ第三者(漏洩後の攻撃者)は init frame を開けない。
+    let mut identity_only = RatchetPeer::new(bob.keys.clone());
+    let error = identity_only
+        .receive(&first)
+        .expect_err("identity key alone must not open a prekey init");
+    assert!(error.to_string().contains("prekey"));
+
+    // prekey を外すと署名と AAD が合わなくなる。
+    let mut stripped = first.clone();
+    stripped.header.recipient_prekey = None;
+    assert!(bob.receive(&stripped).is_err());
+
+    assert_eq!(bob.receive(&second).expect("second"), "two");
+    assert_eq!(bob.receive(&first).expect("first"), "one");
+    let rotated = bob.session_prekey.as_ref().expect("rotated prekey");
+    assert_ne!(rotated.public_key(), bob_prekey.public_key());
+
+    // 採用後は破棄済みの prekey 宛ての init を受け付けない。
+    let mut late_peer = RatchetPeer::new(alice.keys.clone());
+    late_peer.peer_prekey = Some(bob_prekey.public_key());
+    let replayed_init = late_peer.send(&bob_pubkey, "m-3", "late");
+    let mut restored_bob = RatchetPeer::new(bob.keys.clone());
+    restored_bob.session_prekey = bob.session_prekey.clone();
+    let error = restored_bob
+        .receive(&replayed_init)
+        .expect_err("retired prekey must not decrypt");
+    assert!(error.to_string().contains("retired"));
+}
+
+#[test]
+fn dm_frame_decodes_v1_and_v2_blobs() {
+    let alice = generate_keys();
+    let bob = generate_keys();
+    let dm_id = direct_message_id_for_participants(&alice.public_key(), &bob.public_key());
+    let v1 = encrypt_direct_message_frame(
+        &alice,
+        &bob.public_key(),
+        dm_id.as_str(),
+        "message-1",
+        42,
+        &DirectMessagePayloadV1::default(),
+    )
+    .expect("v1 frame");
+    let v2 = encrypt_direct_message_frame_v2(
+        &alice,
+        None,
+        &bob.public_key(),
+        None,
+        dm_id.as_str(),
+        "message-2",
+        43,
+        &DirectMessagePayloadV1::default(),
+    )
+    .expect("v2 frame")
+    .frame;
+
+    let decoded_v1: DirectMessageFrame =
+        serde_json::from_slice(&serde_json::to_vec(&v1).expect("encode v1")).expect("decode v1");
+    let decoded_v2: DirectMessageFrame =
+        serde_json::from_slice(&serde_json::to_vec(&v2).expect("encode v2")).expect("decode v2");
+    assert_eq!(decoded_v1, DirectMessageFrame::V1(v1));
+    assert_eq!(decoded_v2.version(), DIRECT_MESSAGE_FRAME_VERSION_V2);
+    decoded_v2.verify().expect("verify v2");
+
+    let DirectMessageFrame::V2(mut tampered) = decoded_v2 else {
+        panic!("expected v2 frame");
+    };
+    tampered.header.message_number += 1;
+    let error = tampered.verify().expect_err("header is signed");
+    assert!(error.to_string().contains("signature"));
+}
@@ -0,0 +1,87 @@
+use crate::*;
+
+#[test]
+fn group_dm_members_are_normalized_and_bounded() {
+    let owner = generate_keys().public_key();
+    let alice = generate_keys().public_key();
+    let bob = generate_keys().public_key();
+
+    let members =
+        normalize_group_direct_message_members(&owner, &[bob.clone(), alice.clone(), bob.clone()])
+            .expect("normalize members");
+    let mut expected = vec![owner.clone(), alice.clone(), bob];
+    expected.sort_by(|left, right| left.as_str().cmp(right.as_str()));
+    assert_eq!(members, expected);
+
+    assert!(normalize_group_direct_message_members(&owner, &[alice]).is_err());
+    let crowd = (0..GROUP_DIRECT_MESSAGE_MAX_MEMBERS)
+        .map(|_| generate_keys().public_key())
+        .collect::<Vec<_>>();
+    assert!(normalize_group_direct_message_members(&owner, &crowd).is_err());
+}
+
+#[test]
+fn group_dm_frame_roundtrip_is_bound_to_epoch_secret() {
+    let alice = generate_keys();
+    let group_id = generate_group_direct_message_id();
+    assert!(is_group_direct_message_id(group_id.as_str()));
+    let epoch_secret = generate_group_direct_message_epoch_secret();
+    let next_epoch_secret = generate_group_direct_message_epoch_secret();
+
+    let frame = encrypt_group_direct_message_frame(
+        &alice,
+        group_id.as_str(),
+        1,
+        epoch_secret.as_str(),
+        "group-message-1",
+        1_000,
+        &DirectMessagePayloadV1 {
+            text: Some("hello group".into()),
+            ..Default::default()
+        },
+    )
+    .expect("encrypt group frame");
+    let payload =
+        decrypt_group_direct_message_frame(epoch_secret.as_str(), &frame).expect("decrypt");
+    assert_eq!(payload.text.as_deref(), Some("hello group"));
+    assert!(decrypt_group_direct_message_frame(next_epoch_secret.as_str(), &frame).is_err());
+
+    let mut tampered = frame.clone();
+    tampered.epoch = 2;
+    assert!(tampered.verify().is_err());
+
+    assert_ne!(
+        derive_group_direct_message_topic(group_id.as_str(), 1, epoch_secret.as_str())
+            .expect("topic epoch 1"),
+        derive_group_direct_message_topic(group_id.as_str(), 2, next_epoch_secret.as_str())
+            .expect("topic epoch 2"),
+    );
+}
+
+#[test]
+fn group_dm_key_update_requires_canonical_member_list() {
+    let owner = generate_keys().public_key();
+    let members = normalize_group_direct_message_members(
+        &owner,
+        &[generate_keys().public_key(), generate_keys().public_key()],
+    )
+    .expect("normalize members");
+    let update = GroupDirectMessageKeyUpdateV1 {
+        group_id: generate_group_direct_message_id(),
+        owner: owner.clone(),
+        title: Some("team".into()),
+        epoch: 1,
+        members: members.clone(),
+        epoch_secret_hex: Some(generate_group_direct_message_epoch_secret()),
+        created_at: 1_000,
+    };
+    update.validate().expect("valid key update");
+    assert!(update.is_member(&owner));
+
+    let mut reversed = update.clone();
+    reversed.members.reverse();
+    assert!(reversed.validate().is_err());
+    let mut without_owner = update;
+    without_owner.members.retain(|member| member != &owner);
+    assert!(without_owner.validate().is_err());
+}
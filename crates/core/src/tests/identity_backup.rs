@@ -0,0 +1,74 @@
+use crate::*;
+
+const PASSPHRASE: &str = "correct horse battery staple";
+
+// テストでは KDF を軽くする(形式と検証の確認が目的)。
+fn test_params() -> IdentityBackupKdfParams {
+    IdentityBackupKdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    }
+}
+
+#[test]
+fn identity_backup_roundtrip_restores_keys_and_state() {
+    let keys = generate_keys();
+    let state = vec!["channel-a".to_string(), "channel-b".to_string()];
+    let bundle = seal_identity_backup_with_params(&keys, &state, PASSPHRASE, 42, test_params())
+        .expect("seal backup");
+    assert!(!bundle.contains(keys.export_secret_hex().as_str()));
+    assert!(!bundle.contains("channel-a"));
+
+    let parsed = parse_identity_backup_bundle(bundle.as_str()).expect("parse bundle");
+    assert_eq!(parsed.version, IDENTITY_BACKUP_VERSION_V1);
+    assert_eq!(parsed.author_pubkey, keys.public_key());
+    assert_eq!(parsed.kdf.algorithm, IDENTITY_BACKUP_KDF_ARGON2ID);
+    assert_eq!(parsed.kdf.params, test_params());
+
+    let restored: IdentityBackup<Vec<String>> =
+        open_identity_backup(bundle.as_str(), PASSPHRASE).expect("open backup");
+    assert_eq!(restored.keys.public_key(), keys.public_key());
+    assert_eq!(restored.created_at, 42);
+    assert_eq!(restored.state, state);
+}
+
+#[test]
+fn identity_backup_rejects_wrong_passphrase_and_tampered_header() {
+    let keys = generate_keys();
+    let bundle = seal_identity_backup_with_params(&keys, &(), PASSPHRASE, 42, test_params())
+        .expect("seal backup");
+
+    let error = open_identity_backup::<()>(bundle.as_str(), "incorrect passphrase")
+        .expect_err("wrong passphrase must fail");
+    assert!(error.to_string().contains("incorrect"));
+
+    let mut tampered = parse_identity_backup_bundle(bundle.as_str()).expect("parse bundle");
+    tampered.created_at += 1;
+    let tampered = serde_json::to_string(&tampered).expect("encode tampered bundle");
+    assert!(open_identity_backup::<()>(tampered.as_str(), PASSPHRASE).is_err());
+}
+
+#[test]
+fn identity_backup_validates_version_passphrase_and_kdf_bounds() {
+    let keys = generate_keys();
+    assert!(seal_identity_backup_with_params(&keys, &(), "short", 0, test_params()).is_err());
+
+    let bundle = seal_identity_backup_with_params(&keys, &(), PASSPHRASE, 0, test_params())
+        .expect("seal backup");
+    let mut future = parse_identity_backup_bundle(bundle.as_str()).expect("parse bundle");
+    future.version = IDENTITY_BACKUP_VERSION_V1 + 1;
+    let error = parse_identity_backup_bundle(&serde_json::to_string(&future).expect("encode"))
+        .expect_err("unknown version must fail");
+    assert!(
+        error
+            .to_string()
+            .contains("unsupported identity backup version")
+    );
+
+    let mut expensive = parse_identity_backup_bundle(bundle.as_str()).expect("parse bundle");
+    expensive.kdf.params.memory_kib = u32::MAX;
+    assert!(
+        parse_identity_backup_bundle(&serde_json::to_string(&expensive).expect("encode")).is_err()
+    );
+}
@@ -1,6 +1,9 @@
 mod derivation_golden;
+mod devices;
 mod direct_messages;
 mod envelope;
+mod group_direct_messages;
+mod identity_backup;
 mod media_live_game;
 mod posts;
 mod private_channels;
@@ -47,3 +47,86 @@ fn repost_envelope_roundtrip() {
         "quote commentary"
     );
 }
+
+#[test]
+fn post_edit_revision_updates_payload_and_keeps_identity() {
+    let keys = generate_keys();
+    let original = build_post_envelope(&keys, &TopicId::new("kukuri:topic:edit"), "typo", None)
+        .expect("post envelope");
+    let object = original
+        .to_post_object()
+        .expect("parse post")
+        .expect("post object");
+    let edit = build_post_edit_envelope(
+        &keys,
+        &object,
+        PayloadRef::InlineText {
+            text: "fixed".into(),
+        },
+        Vec::new(),
+        Vec::new(),
+    )
+    .expect("edit envelope");
+
+    edit.verify().expect("signature verification");
+    let revision = edit
+        .post_revision_content()
+        .expect("parse revision")
+        .expect("revision content");
+    assert_eq!(revision.object_id, original.id);
+    assert_eq!(revision.previous_envelope_id, original.id);
+
+    let edited = apply_post_revision(&object, &edit).expect("apply edit");
+    assert_eq!(edited.object_id, original.id);
+    assert_eq!(edited.envelope_id, edit.id);
+    assert_eq!(edited.status, ObjectStatus::Edited);
+    assert_eq!(
+        edited.payload_ref,
+        PayloadRef::InlineText {
+            text: "fixed".into()
+        }
+    );
+}
+
+#[test]
+fn post_tombstone_revision_clears_payload_and_blocks_further_edits() {
+    let keys = generate_keys();
+    let original = build_post_envelope(&keys, &TopicId::new("kukuri:topic:delete"), "bye", None)
+        .expect("post envelope");
+    let object = original
+        .to_post_object()
+        .expect("parse post")
+        .expect("post object");
+    let tombstone = build_post_tombstone_envelope(&keys, &object).expect("tombstone envelope");
+
+    let deleted = apply_post_revision(&object, &tombstone).expect("apply tombstone");
+    assert_eq!(deleted.status, ObjectStatus::Deleted);
+    assert_eq!(
+        deleted.payload_ref,
+        PayloadRef::InlineText {
+            text: String::new()
+        }
+    );
+    assert!(deleted.attachments.is_empty());
+    assert!(build_post_tombstone_envelope(&keys, &deleted).is_err());
+    assert!(apply_post_revision(&deleted, &tombstone).is_err());
+}
+
+#[test]
+fn post_revision_rejects_foreign_author() {
+    let author = generate_keys();
+    let original = build_post_envelope(&author, &TopicId::new("kukuri:topic:edit"), "mine", None)
+        .expect("post envelope");
+    let object = original
+        .to_post_object()
+        .expect("parse post")
+        .expect("post object");
+
+    let intruder = generate_keys();
+    assert!(build_post_tombstone_envelope(&intruder, &object).is_err());
+
+    let mut forged_target = object.clone();
+    forged_target.author = intruder.public_key();
+    let forged = build_post_tombstone_envelope(&intruder, &forged_target).expect("forged");
+    assert!(apply_post_revision(&object, &forged).is_err());
+}
@@ -194,6 +194,10 @@ fn dm_frame_fixture_decrypts_for_recipient_and_reserializes_identically() {
             text: Some("golden frame".to_string()),
             reply_to: None,
             attachment_manifest: None,
+            supported_frame_versions: Vec::new(),
+            attachment_key_hex: None,
+            group_key_update: None,
+            session_prekey: None,
         }
     );
     assert_eq!(
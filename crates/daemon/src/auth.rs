@@ -0,0 +1,189 @@
+//! local socket の bearer token。
+//!
+//! token は起動時に確定し、同一ユーザーの client だけが読めるファイルへ書き出す。
+//! loopback に bind していても同一ホストの他プロセスからは到達できるため、
+//! 全 endpoint(websocket upgrade を含む)で `Authorization: Bearer` を要求する。
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use axum::http::{HeaderMap, header};
+use secp256k1::rand::{RngCore, rng};
+
+const TOKEN_BYTES: usize = 32;
+
+#[derive(Clone)]
+pub struct AuthToken(String);
+
+impl AuthToken {
+    pub fn new(token: impl Into<String>) -> Result<Self> {
+        let token = token.into().trim().to_string();
+        anyhow::ensure!(!token.is_empty(), "daemon auth token must not be empty");
+        Ok(Self(token))
+    }
+
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; TOKEN_BYTES];
+        rng().fill_bytes(&mut bytes);
+        Self(hex::encode(bytes))
+    }
+
+    /// 既存の token ファイルがあれば再利用し、無ければ生成して書き出す。
+    /// 再起動のたびに client 側の設定を更新しなくて済むようにする。
+    ///
+    /// 他ユーザーが読める権限になっていた既存ファイルは、権限を 0600 に締めたうえで
+    /// token を作り直す(既に読まれている可能性があるため再利用しない)。
+    pub fn load_or_create(path: &Path) -> Result<Self> {
+        if restrict_token_permissions(path)?
+            && let Ok(existing) = fs::read_to_string(path)
+            && let Ok(token) = Self::new(existing)
+        {
+            return Ok(token);
+        }
+        let token = Self::generate();
+        token.write_to(path)?;
+        Ok(token)
+    }
+
+    pub fn write_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("failed to create token directory `{}`", parent.display())
+            })?;
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        // 作成時点から owner のみ読める権限にし、書き込み前に他ユーザーへ見える窓を作らない。
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(path)
+            .with_context(|| format!("failed to open daemon token `{}`", path.display()))?;
+        file.write_all(self.0.as_bytes())
+            .with_context(|| format!("failed to write daemon token `{}`", path.display()))?;
+        Ok(())
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// `Authorization: Bearer <token>` が一致するか(比較は長さ以外で早期終了しない)。
+    pub fn authorizes(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|presented| {
+                constant_time_eq(presented.trim().as_bytes(), self.0.as_bytes())
+            })
+    }
+}
+
+/// 既存の token ファイルが owner 以外に読めないかを確かめ、読める場合は 0600 に締める。
+/// ファイルが無い、または権限を締めた場合は false(既存の token を使わない)。
+fn restrict_token_permissions(path: &Path) -> Result<bool> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(error) => {
+            return Err(error)
+                .with_context(|| format!("failed to inspect daemon token `{}`", path.display()));
+        }
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o077 != 0 {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+                .with_context(|| format!("failed to restrict daemon token `{}`", path.display()))?;
+            return Ok(false);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = metadata;
+    Ok(true)
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+    left.iter()
+        .zip(right)
+        .fold(0u8, |diff, (left, right)| diff | (left ^ right))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use tempfile::tempdir;
+
+    fn bearer(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(value).expect("header value"),
+        );
+        headers
+    }
+
+    #[test]
+    fn bearer_header_must_match_token() {
+        let token = AuthToken::new("secret").unwrap();
+
+        assert!(token.authorizes(&bearer("Bearer secret")));
+        assert!(!token.authorizes(&bearer("Bearer secreT")));
+        assert!(!token.authorizes(&bearer("Bearer secret2")));
+        assert!(!token.authorizes(&bearer("Basic secret")));
+        assert!(!token.authorizes(&HeaderMap::new()));
+    }
+
+    #[test]
+    fn token_file_is_reused_across_restarts() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("daemon.token");
+
+        let first = AuthToken::load_or_create(&path).unwrap();
+        let second = AuthToken::load_or_create(&path).unwrap();
+
+        assert_eq!(first.as_str().len(), TOKEN_BYTES * 2);
+        assert_eq!(first.as_str(), second.as_str());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn readable_token_file_is_restricted_and_rotated() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("daemon.token");
+        fs::write(&path, "leaked").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let token = AuthToken::load_or_create(&path).unwrap();
+
+        assert_ne!(token.as_str(), "leaked");
+        assert_eq!(fs::read_to_string(&path).unwrap(), token.as_str());
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn empty_token_is_rejected() {
+        assert!(AuthToken::new("  ").is_err());
+    }
+}
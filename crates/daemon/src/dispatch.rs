@@ -0,0 +1,232 @@
+//! JSON-RPC method 名から `DesktopRuntime` の操作への振り分け。
+//!
+//! method 名は desktop の Tauri command 名と同じにし、`params` には command の
+//! `request` 引数と同じ JSON object をそのまま渡す。Tauri 側にしかない OS 連携
+//! (通知許可・起動状態など)と、runtime 起動前に行う `accept_device_pairing` /
+//! `import_identity_backup` は扱わない。
+
+use kukuri_desktop_runtime::{
+    AcceptCommunityNodeConsentsRequest, AuthorRequest, BookmarkCustomReactionRequest,
+    BookmarkPostRequest, CommunityNodeIndexQueryRequest, CommunityNodeIndexingRequest,
+    CommunityNodeRelationNeighborsRequest, CommunityNodeTargetRequest,
+    CommunityNodeUserAdvisoryRequest, CreateCustomReactionAssetRequest, CreateDevicePairingRequest,
+    CreateGameRoomRequest, CreateLiveSessionRequest, CreateMetaverseRoomRequest, CreatePostRequest,
+    CreatePrivateChannelRequest, CreateRepostRequest, DeleteDirectMessageMessageRequest,
+    DesktopRuntime, DirectMessageRequest, ExportChannelAccessTokenRequest,
+    ExportFriendOnlyGrantRequest, ExportFriendPlusShareRequest, ExportIdentityBackupRequest,
+    ExportPrivateChannelInviteRequest, FreezePrivateChannelRequest, GetBlobMediaRequest,
+    GetBlobPreviewRequest, ImportChannelAccessTokenRequest, ImportFriendOnlyGrantRequest,
+    ImportFriendPlusShareRequest, ImportMetaverseRoomAssetRequest, ImportPeerTicketRequest,
+    ImportPrivateChannelInviteRequest, LeavePrivateChannelRequest,
+    ListDirectMessageMessagesRequest, ListGameRoomsRequest, ListJoinedPrivateChannelsRequest,
+    ListLiveSessionsRequest, ListMetaverseRoomEventsRequest, ListProfileTimelineRequest,
+    ListRecentReactionsRequest, ListSocialConnectionsRequest, ListThreadRequest,
+    ListTimelineRequest, LiveSessionCommandRequest, NotificationIdRequest,
+    PreviewChannelAccessTokenRequest, PublishMetaverseRoomEventRequest,
+    RebuildTopicProjectionsRequest, RemoveBookmarkedCustomReactionRequest,
+    RemoveBookmarkedPostRequest, RotatePrivateChannelRequest, SendDirectMessageRequest,
+    SetChannelGossipEnabledRequest, SetCommunityNodeConfigRequest,
+    SetCommunityNodeInviteCodeRequest, SetCommunityNodeModerationPolicyRequest,
+    SetDiscoverySeedsRequest, SetMyProfileRequest, SetTopicGossipEnabledRequest,
+    SubmitCommunityNodeReportRequest, ToggleReactionRequest, UnsubscribeTopicRequest,
+    UpdateGameRoomRequest, UpdateMetaverseRoomRequest,
+};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::rpc::RpcError;
+
+/// websocket 接続でのみ受け付ける、runtime event 購読開始の method 名。
+pub const SUBSCRIBE_EVENTS_METHOD: &str = "subscribe_events";
+
+/// `params` を request 型へ復元する。省略(`null`)は空 object として扱い、
+/// 全フィールドが省略可能な request はそのまま呼べるようにする。
+pub(crate) fn parse_params<T: DeserializeOwned>(params: Option<Value>) -> Result<T, RpcError> {
+    let params = match params {
+        None | Some(Value::Null) => Value::Object(Default::default()),
+        Some(params) => params,
+    };
+    serde_json::from_value(params).map_err(RpcError::invalid_params)
+}
+
+fn respond<T, E>(result: Result<T, E>) -> Result<Value, RpcError>
+where
+    T: Serialize,
+    RpcError: From<E>,
+{
+    let value = result.map_err(RpcError::from)?;
+    serde_json::to_value(value).map_err(|error| {
+        <RpcError as From<anyhow::Error>>::from(
+            anyhow::Error::new(error).context("failed to encode result"),
+        )
+    })
+}
+
+macro_rules! rpc_methods {
+    (
+        request { $($request_name:literal => $request_fn:ident($request_ty:ty)),* $(,)? }
+        plain { $($plain_name:literal => $plain_fn:ident),* $(,)? }
+    ) => {
+        /// daemon が受け付ける method 名の一覧(`subscribe_events` を除く)。
+        pub const METHODS: &[&str] = &[$($request_name,)* $($plain_name,)*];
+
+        /// 1 request を runtime へ振り分け、戻り値を JSON にして返す。
+        pub(crate) async fn dispatch(
+            runtime: &DesktopRuntime,
+            method: &str,
+            params: Option<Value>,
+        ) -> Result<Value, RpcError> {
+            match method {
+                $($request_name => {
+                    let request: $request_ty = parse_params(params)?;
+                    respond(runtime.$request_fn(request).await)
+                })*
+                $($plain_name => respond(runtime.$plain_fn().await),)*
+                _ => Err(RpcError::method_not_found(method)),
+            }
+        }
+    };
+}
+
+rpc_methods! {
+    request {
+        "create_post" => create_post(CreatePostRequest),
+        "create_repost" => create_repost(CreateRepostRequest),
+        "toggle_reaction" => toggle_reaction(ToggleReactionRequest),
+        "list_recent_reactions" => list_recent_reactions(ListRecentReactionsRequest),
+        "create_custom_reaction_asset" => create_custom_reaction_asset(CreateCustomReactionAssetRequest),
+        "bookmark_custom_reaction" => bookmark_custom_reaction(BookmarkCustomReactionRequest),
+        "remove_bookmarked_custom_reaction" => remove_bookmarked_custom_reaction(RemoveBookmarkedCustomReactionRequest),
+        "bookmark_post" => bookmark_post(BookmarkPostRequest),
+        "remove_bookmarked_post" => remove_bookmarked_post(RemoveBookmarkedPostRequest),
+        "list_timeline" => list_timeline(ListTimelineRequest),
+        "list_thread" => list_thread(ListThreadRequest),
+        "list_profile_timeline" => list_profile_timeline(ListProfileTimelineRequest),
+        "set_my_profile" => set_my_profile(SetMyProfileRequest),
+        "follow_author" => follow_author(AuthorRequest),
+        "unfollow_author" => unfollow_author(AuthorRequest),
+        "get_author_social_view" => get_author_social_view(AuthorRequest),
+        "mute_author" => mute_author(AuthorRequest),
+        "unmute_author" => unmute_author(AuthorRequest),
+        "list_social_connections" => list_social_connections(ListSocialConnectionsRequest),
+        "create_device_pairing" => create_device_pairing(CreateDevicePairingRequest),
+        "export_identity_backup" => export_identity_backup(ExportIdentityBackupRequest),
+        "mark_notification_read" => mark_notification_read(NotificationIdRequest),
+        "open_direct_message" => open_direct_message(DirectMessageRequest),
+        "list_direct_message_messages" => list_direct_message_messages(ListDirectMessageMessagesRequest),
+        "send_direct_message" => send_direct_message(SendDirectMessageRequest),
+        "delete_direct_message_message" => delete_direct_message_message(DeleteDirectMessageMessageRequest),
+        "clear_direct_message" => clear_direct_message(DirectMessageRequest),
+        "get_direct_message_status" => get_direct_message_status(DirectMessageRequest),
+        "get_direct_message_topic_status" => get_direct_message_topic_status(DirectMessageRequest),
+        "create_private_channel" => create_private_channel(CreatePrivateChannelRequest),
+        "export_private_channel_invite" => export_private_channel_invite(ExportPrivateChannelInviteRequest),
+        "import_private_channel_invite" => import_private_channel_invite(ImportPrivateChannelInviteRequest),
+        "export_channel_access_token" => export_channel_access_token(ExportChannelAccessTokenRequest),
+        "import_channel_access_token" => import_channel_access_token(ImportChannelAccessTokenRequest),
+        "preview_channel_access_token" => preview_channel_access_token(PreviewChannelAccessTokenRequest),
+        "export_friend_only_grant" => export_friend_only_grant(ExportFriendOnlyGrantRequest),
+        "import_friend_only_grant" => import_friend_only_grant(ImportFriendOnlyGrantRequest),
+        "export_friend_plus_share" => export_friend_plus_share(ExportFriendPlusShareRequest),
+        "import_friend_plus_share" => import_friend_plus_share(ImportFriendPlusShareRequest),
+        "freeze_private_channel" => freeze_private_channel(FreezePrivateChannelRequest),
+        "rotate_private_channel" => rotate_private_channel(RotatePrivateChannelRequest),
+        "leave_private_channel" => leave_private_channel(LeavePrivateChannelRequest),
+        "list_joined_private_channels" => list_joined_private_channels(ListJoinedPrivateChannelsRequest),
+        "list_live_sessions" => list_live_sessions(ListLiveSessionsRequest),
+        "create_live_session" => create_live_session(CreateLiveSessionRequest),
+        "end_live_session" => end_live_session(LiveSessionCommandRequest),
+        "join_live_session" => join_live_session(LiveSessionCommandRequest),
+        "leave_live_session" => leave_live_session(LiveSessionCommandRequest),
+        "list_game_rooms" => list_game_rooms(ListGameRoomsRequest),
+        "create_game_room" => create_game_room(CreateGameRoomRequest),
+        "update_game_room" => update_game_room(UpdateGameRoomRequest),
+        "create_metaverse_room" => create_metaverse_room(CreateMetaverseRoomRequest),
+        "update_metaverse_room" => update_metaverse_room(UpdateMetaverseRoomRequest),
+        "publish_metaverse_room_event" => publish_metaverse_room_event(PublishMetaverseRoomEventRequest),
+        "list_metaverse_room_events" => list_metaverse_room_events(ListMetaverseRoomEventsRequest),
+        "import_metaverse_room_asset" => import_metaverse_room_asset(ImportMetaverseRoomAssetRequest),
+        "import_peer_ticket" => import_peer_ticket(ImportPeerTicketRequest),
+        "set_discovery_seeds" => set_discovery_seeds(SetDiscoverySeedsRequest),
+        "unsubscribe_topic" => unsubscribe_topic(UnsubscribeTopicRequest),
+        "rebuild_topic_projections" => rebuild_topic_projections(RebuildTopicProjectionsRequest),
+        "set_topic_gossip_enabled" => set_topic_gossip_enabled(SetTopicGossipEnabledRequest),
+        "set_channel_gossip_enabled" => set_channel_gossip_enabled(SetChannelGossipEnabledRequest),
+        "get_blob_media_payload" => get_blob_media_payload(GetBlobMediaRequest),
+        "get_blob_preview_url" => get_blob_preview_url(GetBlobPreviewRequest),
+        "set_community_node_config" => set_community_node_config(SetCommunityNodeConfigRequest),
+        "authenticate_community_node" => authenticate_community_node(CommunityNodeTargetRequest),
+        "set_community_node_invite_code" => set_community_node_invite_code(SetCommunityNodeInviteCodeRequest),
+        "clear_community_node_token" => clear_community_node_token(CommunityNodeTargetRequest),
+        "get_community_node_consent_status" => get_community_node_consent_status(CommunityNodeTargetRequest),
+        "accept_community_node_consents" => accept_community_node_consents(AcceptCommunityNodeConsentsRequest),
+        "refresh_community_node_metadata" => refresh_community_node_metadata(CommunityNodeTargetRequest),
+        "fetch_community_node_manifest" => fetch_community_node_manifest(CommunityNodeTargetRequest),
+        "set_community_node_moderation_policy" => set_community_node_moderation_policy(SetCommunityNodeModerationPolicyRequest),
+        "submit_community_node_report" => submit_community_node_report(SubmitCommunityNodeReportRequest),
+        "submit_community_node_indexing_request" => submit_community_node_indexing_request(CommunityNodeIndexingRequest),
+        "search_community_node_index" => search_community_node_index(CommunityNodeIndexQueryRequest),
+        "discover_community_node_index" => discover_community_node_index(CommunityNodeIndexQueryRequest),
+        "recommend_community_node_index" => recommend_community_node_index(CommunityNodeIndexQueryRequest),
+        "search_community_node_people" => search_community_node_people(CommunityNodeIndexQueryRequest),
+        "list_community_node_live_now" => list_community_node_live_now(CommunityNodeIndexQueryRequest),
+        "read_community_node_trust_user" => read_community_node_trust_user(CommunityNodeUserAdvisoryRequest),
+        "read_community_node_relation_user" => read_community_node_relation_user(CommunityNodeUserAdvisoryRequest),
+        "list_community_node_relation_neighbors" => list_community_node_relation_neighbors(CommunityNodeRelationNeighborsRequest),
+        "get_community_node_relation_optout" => get_community_node_relation_optout(CommunityNodeTargetRequest),
+        "set_community_node_relation_optout" => set_community_node_relation_optout(CommunityNodeTargetRequest),
+        "clear_community_node_relation_optout" => clear_community_node_relation_optout(CommunityNodeTargetRequest),
+    }
+    plain {
+        "list_my_custom_reaction_assets" => list_my_custom_reaction_assets,
+        "list_bookmarked_custom_reactions" => list_bookmarked_custom_reactions,
+        "list_bookmarked_posts" => list_bookmarked_posts,
+        "get_my_profile" => get_my_profile,
+        "list_linked_devices" => list_linked_devices,
+        "sync_linked_devices" => sync_linked_devices,
+        "list_notifications" => list_notifications,
+        "mark_all_notifications_read" => mark_all_notifications_read,
+        "get_notification_status" => get_notification_status,
+        "list_direct_messages" => list_direct_messages,
+        "get_sync_status" => get_sync_status,
+        "get_storage_usage" => get_storage_usage,
+        "collect_blob_garbage" => collect_blob_garbage,
+        "get_discovery_config" => get_discovery_config,
+        "get_local_peer_ticket" => local_peer_ticket,
+        "get_community_node_config" => get_community_node_config,
+        "get_community_node_statuses" => get_community_node_statuses,
+        "clear_community_node_config" => clear_community_node_config,
+        "list_community_node_moderation_policies" => list_community_node_moderation_policies,
+        "reapply_community_node_connectivity" => reapply_community_node_connectivity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::INVALID_PARAMS;
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    #[test]
+    fn method_names_are_unique_and_exclude_subscription() {
+        let unique = METHODS.iter().collect::<HashSet<_>>();
+        assert_eq!(unique.len(), METHODS.len());
+        assert!(!METHODS.contains(&SUBSCRIBE_EVENTS_METHOD));
+        assert!(METHODS.contains(&"get_local_peer_ticket"));
+    }
+
+    #[test]
+    fn params_must_match_request_shape() {
+        let request: CommunityNodeTargetRequest =
+            parse_params(Some(json!({ "base_url": "https://node.example" }))).unwrap();
+        assert_eq!(request.base_url, "https://node.example");
+
+        let error =
+            parse_params::<CommunityNodeTargetRequest>(Some(json!({ "base_url": 1 }))).unwrap_err();
+        assert_eq!(error.code, INVALID_PARAMS);
+        let error = parse_params::<CommunityNodeTargetRequest>(None).unwrap_err();
+        assert_eq!(error.code, INVALID_PARAMS);
+    }
+}
@@ -0,0 +1,117 @@
+//! desktop-runtime を GUI なしで常駐させる headless daemon(`kukurid`)。
+//!
+//! identity・iroh stack・community-node session を desktop と同じく起動し、
+//! Tauri command と同名の操作を local の認証付き JSON-RPC(HTTP / websocket)で公開する。
+//! bot・アーカイブ用 mirror・常時接続の server peer から使う想定。
+
+mod auth;
+mod dispatch;
+mod rpc;
+mod server;
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use kukuri_desktop_runtime::DesktopRuntime;
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+pub use auth::AuthToken;
+pub use dispatch::{METHODS, SUBSCRIBE_EVENTS_METHOD};
+pub use rpc::{
+    COMMAND_FAILED, COMMAND_FAILED_CODE, INVALID_PARAMS, INVALID_REQUEST, JSONRPC_VERSION,
+    METHOD_NOT_FOUND, PARSE_ERROR, RUNTIME_EVENT_METHOD, RpcError, RpcErrorData, RpcNotification,
+    RpcRequest, RpcResponse, parse_request,
+};
+pub use server::{DaemonState, RUNTIME_EVENTS_LAGGED_METHOD, router, serve};
+
+pub const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:7677";
+pub(crate) const TOKEN_FILE_EXTENSION: &str = "daemon-token";
+
+#[derive(Clone, Debug)]
+pub struct DaemonConfig {
+    pub db_path: PathBuf,
+    pub listen: SocketAddr,
+    /// 明示指定の token。未指定なら `token_file` を再利用または生成する。
+    pub token: Option<String>,
+    /// 未指定なら DB と同じ場所の `<db>.daemon-token`。
+    pub token_file: Option<PathBuf>,
+    /// loopback 以外への bind を許可する(既定は拒否)。
+    pub allow_non_loopback: bool,
+}
+
+pub fn token_file_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension(TOKEN_FILE_EXTENSION)
+}
+
+/// runtime を起動して RPC を待ち受け、SIGINT / SIGTERM で runtime を止めて戻る。
+pub async fn run(config: DaemonConfig) -> Result<()> {
+    anyhow::ensure!(
+        config.listen.ip().is_loopback() || config.allow_non_loopback,
+        "refusing to listen on non-loopback address {} without --allow-non-loopback",
+        config.listen
+    );
+    let token = match config.token.as_deref() {
+        Some(token) => AuthToken::new(token)?,
+        None => {
+            let token_path = config
+                .token_file
+                .clone()
+                .unwrap_or_else(|| token_file_path(&config.db_path));
+            let token = AuthToken::load_or_create(&token_path)?;
+            info!(token_file = %token_path.display(), "daemon auth token ready");
+            token
+        }
+    };
+    let listener = TcpListener::bind(config.listen)
+        .await
+        .with_context(|| format!("failed to bind daemon rpc at {}", config.listen))?;
+
+    let runtime = Arc::new(DesktopRuntime::from_env(&config.db_path).await?);
+    runtime.start_community_node_session_scheduler().await;
+    runtime.start_sync_status_observer().await;
+    runtime.start_linked_device_sync().await;
+    info!(
+        listen = %config.listen,
+        db_path = %config.db_path.display(),
+        "kukurid listening"
+    );
+
+    let state = DaemonState {
+        runtime: Arc::clone(&runtime),
+        token,
+    };
+    let result = serve(listener, state, shutdown_signal()).await;
+    runtime.shutdown().await;
+    result
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(error) = tokio::signal::ctrl_c().await {
+            warn!(error = %error, "failed to listen for ctrl-c");
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(error) => {
+                warn!(error = %error, "failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    info!("kukurid shutting down");
+}
@@ -0,0 +1,53 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use kukuri_daemon::{DEFAULT_LISTEN_ADDR, DaemonConfig};
+use kukuri_desktop_runtime::resolve_db_path_from_env;
+use tracing_subscriber::EnvFilter;
+
+const DEFAULT_TRACING_DIRECTIVES: &str = "info,kukuri_daemon=debug";
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "kukurid",
+    about = "headless kukuri runtime with a local JSON-RPC API"
+)]
+struct Cli {
+    /// app data dir。`KUKURI_APP_DATA_DIR` / `KUKURI_INSTANCE` は desktop と同じ規則で優先する。
+    #[arg(long, env = "KUKURI_DAEMON_DATA_DIR", default_value = "kukuri-data")]
+    data_dir: PathBuf,
+    /// RPC の待ち受けアドレス。
+    #[arg(long, env = "KUKURI_DAEMON_LISTEN", default_value = DEFAULT_LISTEN_ADDR)]
+    listen: SocketAddr,
+    /// bearer token の保存先。既定は `<data dir>/kukuri.daemon-token`。
+    #[arg(long, env = "KUKURI_DAEMON_TOKEN_FILE")]
+    token_file: Option<PathBuf>,
+    /// bearer token を直接指定する(ファイルには書き出さない)。
+    #[arg(long, env = "KUKURI_DAEMON_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+    /// loopback 以外への bind を許可する。
+    #[arg(long)]
+    allow_non_loopback: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let env_filter = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|value| EnvFilter::try_new(value).ok())
+        .unwrap_or_else(|| EnvFilter::new(DEFAULT_TRACING_DIRECTIVES));
+    tracing_subscriber::fmt().with_env_filter(env_filter).init();
+
+    let cli = Cli::parse();
+    let db_path = resolve_db_path_from_env(&cli.data_dir)?;
+    kukuri_daemon::run(DaemonConfig {
+        db_path,
+        listen: cli.listen,
+        token: cli.token,
+        token_file: cli.token_file,
+        allow_non_loopback: cli.allow_non_loopback,
+    })
+    .await
+}
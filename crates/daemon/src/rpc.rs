@@ -0,0 +1,269 @@
+//! JSON-RPC 2.0 の wire 型と、runtime エラーから RPC エラーへの写像。
+//!
+//! `error.data` は src-tauri の `CommandError` 封筒(`code` / `status` /
+//! `retry_after_seconds`)と同じ形にし、desktop と daemon で機械判定を揃える。
+
+use kukuri_desktop_runtime::{
+    CommunityNodeIndexQueryError, CommunityNodeIndexingRequestError, CommunityNodeReportError,
+    CommunityNodeTrustRelationError,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const JSONRPC_VERSION: &str = "2.0";
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// runtime 側の操作が失敗した(JSON-RPC の server error 帯)。
+pub const COMMAND_FAILED: i64 = -32000;
+
+/// `error.data.code` の既定値。src-tauri の `COMMAND_FAILED_CODE` と同じ。
+pub const COMMAND_FAILED_CODE: &str = "command_failed";
+
+/// runtime event を push する notification の method 名。
+pub const RUNTIME_EVENT_METHOD: &str = "runtime_event";
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct RpcRequest {
+    pub jsonrpc: String,
+    /// 省略時は notification として扱い、応答を返さない。
+    #[serde(default)]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Option<Value>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    pub fn success(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn failure(id: Value, error: RpcError) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RpcNotification {
+    pub jsonrpc: String,
+    pub method: String,
+    pub params: Value,
+}
+
+impl RpcNotification {
+    pub fn new(method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<RpcErrorData>,
+}
+
+/// desktop の `CommandError` と同じ機械判定用フィールド。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcErrorData {
+    pub code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_seconds: Option<u64>,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn parse_error(error: impl std::fmt::Display) -> Self {
+        Self::new(PARSE_ERROR, format!("parse error: {error}"))
+    }
+
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self::new(INVALID_REQUEST, message)
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(METHOD_NOT_FOUND, format!("method not found: {method}"))
+    }
+
+    pub fn invalid_params(error: impl std::fmt::Display) -> Self {
+        Self::new(INVALID_PARAMS, format!("invalid params: {error}"))
+    }
+
+    fn command_failed(
+        code: String,
+        message: String,
+        status: Option<u16>,
+        retry_after_seconds: Option<u64>,
+    ) -> Self {
+        Self {
+            code: COMMAND_FAILED,
+            message,
+            data: Some(RpcErrorData {
+                code,
+                status,
+                retry_after_seconds,
+            }),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::command_failed(
+            COMMAND_FAILED_CODE.to_string(),
+            format!("{error:#}"),
+            None,
+            None,
+        )
+    }
+}
+
+impl From<CommunityNodeIndexQueryError> for RpcError {
+    fn from(error: CommunityNodeIndexQueryError) -> Self {
+        Self::command_failed(
+            error.code,
+            error.message,
+            error.status,
+            error.retry_after_seconds,
+        )
+    }
+}
+
+impl From<CommunityNodeIndexingRequestError> for RpcError {
+    fn from(error: CommunityNodeIndexingRequestError) -> Self {
+        Self::command_failed(
+            error.code,
+            error.message,
+            error.status,
+            error.retry_after_seconds,
+        )
+    }
+}
+
+impl From<CommunityNodeTrustRelationError> for RpcError {
+    fn from(error: CommunityNodeTrustRelationError) -> Self {
+        Self::command_failed(error.code, error.message, error.status, None)
+    }
+}
+
+impl From<CommunityNodeReportError> for RpcError {
+    fn from(error: CommunityNodeReportError) -> Self {
+        Self::command_failed(error.code, error.message, error.status, None)
+    }
+}
+
+/// 受信した 1 メッセージを request として解釈する。
+///
+/// batch(配列)は扱わない。`jsonrpc` が `"2.0"` 以外なら invalid request。
+pub fn parse_request(text: &str) -> Result<RpcRequest, RpcError> {
+    let value: Value = serde_json::from_str(text).map_err(RpcError::parse_error)?;
+    if value.is_array() {
+        return Err(RpcError::invalid_request(
+            "batch requests are not supported",
+        ));
+    }
+    let request: RpcRequest = serde_json::from_value(value)
+        .map_err(|error| RpcError::invalid_request(error.to_string()))?;
+    if request.jsonrpc != JSONRPC_VERSION {
+        return Err(RpcError::invalid_request("jsonrpc must be \"2.0\""));
+    }
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use serde_json::json;
+
+    #[test]
+    fn anyhow_error_maps_to_command_failed_envelope() {
+        let error = RpcError::from(anyhow!("inner").context("outer"));
+        let json = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(
+            json,
+            json!({
+                "code": COMMAND_FAILED,
+                "message": "outer: inner",
+                "data": { "code": "command_failed" }
+            })
+        );
+    }
+
+    #[test]
+    fn community_node_error_keeps_code_status_and_retry_after() {
+        let error = RpcError::from(CommunityNodeIndexQueryError {
+            code: "rate_limited".to_string(),
+            message: "slow down".to_string(),
+            status: Some(429),
+            retry_after_seconds: Some(30),
+        });
+
+        assert_eq!(error.code, COMMAND_FAILED);
+        assert_eq!(
+            error.data,
+            Some(RpcErrorData {
+                code: "rate_limited".to_string(),
+                status: Some(429),
+                retry_after_seconds: Some(30),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_request_rejects_malformed_batch_and_wrong_version() {
+        assert_eq!(parse_request("{").unwrap_err().code, PARSE_ERROR);
+        assert_eq!(parse_request("[]").unwrap_err().code, INVALID_REQUEST);
+        assert_eq!(
+            parse_request(r#"{"jsonrpc":"1.0","id":1,"method":"get_my_profile"}"#)
+                .unwrap_err()
+                .code,
+            INVALID_REQUEST
+        );
+
+        let request =
+            parse_request(r#"{"jsonrpc":"2.0","id":"a","method":"list_timeline","params":{}}"#)
+                .unwrap();
+        assert_eq!(request.id, Some(json!("a")));
+        assert_eq!(request.method, "list_timeline");
+        assert_eq!(request.params, Some(json!({})));
+    }
+}
@@ -0,0 +1,196 @@
+//! local JSON-RPC の HTTP / websocket endpoint。
+//!
+//! - `POST /rpc`: 1 request を受けて 1 response を返す(notification なら 204)。
+//! - `GET /rpc/ws`: text frame ごとに 1 request。`subscribe_events` 以降は runtime event を
+//!   `runtime_event` notification として push する。
+
+use std::future::Future;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::Json;
+use axum::Router;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use futures_util::{SinkExt, StreamExt};
+use kukuri_desktop_runtime::DesktopRuntime;
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::auth::AuthToken;
+use crate::dispatch::{SUBSCRIBE_EVENTS_METHOD, dispatch};
+use crate::rpc::{
+    RUNTIME_EVENT_METHOD, RpcError, RpcNotification, RpcRequest, RpcResponse, parse_request,
+};
+
+/// 購読中に broadcast から取りこぼしが出たことを client に伝える notification。
+/// 受け取った client は必要な状態を RPC で取り直す。
+pub const RUNTIME_EVENTS_LAGGED_METHOD: &str = "runtime_events_lagged";
+
+#[derive(Clone)]
+pub struct DaemonState {
+    pub runtime: Arc<DesktopRuntime>,
+    pub token: AuthToken,
+}
+
+pub fn router(state: DaemonState) -> Router {
+    Router::new()
+        .route("/rpc", post(rpc_http))
+        .route("/rpc/ws", get(rpc_ws))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+/// `shutdown` が完了するまで待ち受ける。runtime の停止は呼び出し側が行う。
+pub async fn serve(
+    listener: TcpListener,
+    state: DaemonState,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<()> {
+    axum::serve(listener, router(state))
+        .with_graceful_shutdown(shutdown)
+        .await
+        .context("daemon rpc server failed")
+}
+
+async fn require_token(State(state): State<DaemonState>, request: Request, next: Next) -> Response {
+    if !state.token.authorizes(request.headers()) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+/// request を実行し、id 付きなら response を返す(notification は実行のみ)。
+pub(crate) async fn execute(runtime: &DesktopRuntime, request: RpcRequest) -> Option<RpcResponse> {
+    let result = dispatch(runtime, request.method.as_str(), request.params).await;
+    let id = request.id?;
+    Some(match result {
+        Ok(result) => RpcResponse::success(id, result),
+        Err(error) => RpcResponse::failure(id, error),
+    })
+}
+
+async fn rpc_http(State(state): State<DaemonState>, body: String) -> Response {
+    let request = match parse_request(body.as_str()) {
+        Ok(request) => request,
+        Err(error) => return Json(RpcResponse::failure(Value::Null, error)).into_response(),
+    };
+    if request.method == SUBSCRIBE_EVENTS_METHOD {
+        let error = RpcError::invalid_request("subscribe_events is only available over /rpc/ws");
+        return Json(RpcResponse::failure(
+            request.id.unwrap_or(Value::Null),
+            error,
+        ))
+        .into_response();
+    }
+    match execute(&state.runtime, request).await {
+        Some(response) => Json(response).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    }
+}
+
+async fn rpc_ws(State(state): State<DaemonState>, upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(move |socket| serve_socket(state.runtime, socket))
+}
+
+fn encode<T: serde::Serialize>(message: &T) -> Option<String> {
+    serde_json::to_string(message)
+        .inspect_err(|error| warn!(error = %error, "failed to encode daemon rpc message"))
+        .ok()
+}
+
+async fn serve_socket(runtime: Arc<DesktopRuntime>, socket: WebSocket) {
+    let (mut sink, mut stream) = socket.split();
+    // 応答と event push を 1 本の writer に集め、並行実行中の request から順不同に書き込む。
+    let (outbound, mut outbound_rx) = mpsc::unbounded_channel::<String>();
+    let writer = tokio::spawn(async move {
+        while let Some(text) = outbound_rx.recv().await {
+            if sink.send(Message::Text(text.into())).await.is_err() {
+                break;
+            }
+        }
+    });
+    let mut event_forwarder: Option<JoinHandle<()>> = None;
+
+    while let Some(Ok(message)) = stream.next().await {
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let request = match parse_request(text.as_str()) {
+            Ok(request) => request,
+            Err(error) => {
+                if let Some(text) = encode(&RpcResponse::failure(Value::Null, error)) {
+                    let _ = outbound.send(text);
+                }
+                continue;
+            }
+        };
+        if request.method == SUBSCRIBE_EVENTS_METHOD {
+            if event_forwarder.is_none() {
+                event_forwarder = Some(spawn_event_forwarder(&runtime, outbound.clone()));
+            }
+            if let Some(id) = request.id
+                && let Some(text) = encode(&RpcResponse::success(id, json!({ "subscribed": true })))
+            {
+                let _ = outbound.send(text);
+            }
+            continue;
+        }
+        let runtime = Arc::clone(&runtime);
+        let outbound = outbound.clone();
+        tokio::spawn(async move {
+            if let Some(response) = execute(&runtime, request).await
+                && let Some(text) = encode(&response)
+            {
+                let _ = outbound.send(text);
+            }
+        });
+    }
+
+    debug!("daemon websocket client disconnected");
+    if let Some(event_forwarder) = event_forwarder {
+        event_forwarder.abort();
+    }
+    writer.abort();
+}
+
+fn spawn_event_forwarder(
+    runtime: &DesktopRuntime,
+    outbound: mpsc::UnboundedSender<String>,
+) -> JoinHandle<()> {
+    let mut events = runtime.subscribe_events();
+    tokio::spawn(async move {
+        loop {
+            let notification = match events.recv().await {
+                Ok(event) => match serde_json::to_value(&event) {
+                    Ok(params) => RpcNotification::new(RUNTIME_EVENT_METHOD, params),
+                    Err(error) => {
+                        warn!(error = %error, "failed to encode runtime event");
+                        continue;
+                    }
+                },
+                Err(RecvError::Lagged(skipped)) => RpcNotification::new(
+                    RUNTIME_EVENTS_LAGGED_METHOD,
+                    json!({ "skipped": skipped }),
+                ),
+                Err(RecvError::Closed) => break,
+            };
+            let Some(text) = encode(&notification) else {
+                continue;
+            };
+            if outbound.send(text).is_err() {
+                break;
+            }
+        }
+    })
+}
@@ -3,8 +3,9 @@ use std::fmt;
 use chrono::Utc;
 use kukuri_cn_protocol::{
     AUTH_REQUIRED_CODE, ApiErrorBody, CHANNEL_MEMBERSHIP_SECRET_HEADER, CONSENT_REQUIRED_CODE,
-    INDEX_DISCOVERY_PATH, INDEX_RECOMMENDATIONS_PATH, INDEX_SEARCH_PATH, IndexQueryParams,
-    IndexQueryResponse, IndexScopeKind, normalize_http_url,
+    INDEX_DISCOVERY_PATH, INDEX_LIVE_PATH, INDEX_PEOPLE_PATH, INDEX_RECOMMENDATIONS_PATH,
+    INDEX_SEARCH_PATH, IndexEntryKind, IndexQueryParams, IndexQueryResponse, IndexScopeKind,
+    IndexSortOrder, normalize_http_url,
 };
 use kukuri_store::{ContentObservationRow, ContentObservationStore};
 use reqwest::{StatusCode, header::RETRY_AFTER};
@@ -25,6 +26,15 @@ pub struct CommunityNodeIndexQueryRequest {
     /// チャンネルの capability から引くために使う(#711)。
     pub topic_id: Option<String>,
     pub limit: Option<usize>,
+    /// 直前 page の `next_cursor`。同じ条件のまま渡すと続きの page を返す(無限スクロール)。
+    pub cursor: Option<String>,
+    pub author_pubkey: Option<String>,
+    /// `created_at` の下限(unix 秒、含む)。
+    pub since: Option<i64>,
+    /// `created_at` の上限(unix 秒、含まない)。
+    pub until: Option<i64>,
+    /// search / discovery の並び順。recommendation は順位付けのため指定できない。
+    pub order: Option<IndexSortOrder>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -83,6 +93,8 @@ pub(crate) enum IndexOperation {
     Search,
     Discovery,
     Recommendations,
+    People,
+    Live,
 }
 
 impl IndexOperation {
@@ -91,12 +103,14 @@ impl IndexOperation {
             Self::Search => INDEX_SEARCH_PATH,
             Self::Discovery => INDEX_DISCOVERY_PATH,
             Self::Recommendations => INDEX_RECOMMENDATIONS_PATH,
+            Self::People => INDEX_PEOPLE_PATH,
+            Self::Live => INDEX_LIVE_PATH,
         }
     }
 
     fn observation_capability(self) -> &'static str {
         match self {
-            Self::Search | Self::Discovery => "community_index",
+            Self::Search | Self::Discovery | Self::People | Self::Live => "community_index",
             Self::Recommendations => "recommendation",
         }
     }
@@ -131,7 +145,31 @@ impl DesktopRuntime {
                 "scope_kind and scope_id must be specified together",
             ));
         }
-        if matches!(operation, IndexOperation::Search)
+        if matches!(operation, IndexOperation::Recommendations) && request.order.is_some() {
+            return Err(CommunityNodeIndexQueryError::new(
+                "INVALID_INDEX_QUERY",
+                "recommendations are ranked and do not accept order",
+            ));
+        }
+        if let (Some(since), Some(until)) = (request.since, request.until)
+            && since >= until
+        {
+            return Err(CommunityNodeIndexQueryError::new(
+                "INVALID_INDEX_QUERY",
+                "since must be earlier than until",
+            ));
+        }
+        // profile は非公開チャンネルから索引されないため、people search は channel を指定できない
+        // (所属証明を無駄に送らない)。
+        if matches!(operation, IndexOperation::People)
+            && request.scope_kind == Some(IndexScopeKind::PrivateChannel)
+        {
+            return Err(CommunityNodeIndexQueryError::new(
+                "INVALID_INDEX_QUERY",
+                "people search does not cover private channels",
+            ));
+        }
+        if matches!(operation, IndexOperation::Search | IndexOperation::People)
             && request
                 .query
                 .as_deref()
@@ -212,6 +250,11 @@ impl DesktopRuntime {
                 .map(|scope_kind| scope_kind.as_str().to_string()),
             scope_id: request.scope_id,
             limit: request.limit,
+            cursor: request.cursor,
+            author_pubkey: request.author_pubkey,
+            since: request.since,
+            until: request.until,
+            order: request.order.map(|order| order.as_str().to_string()),
         };
 
         let response = match self
@@ -258,11 +301,17 @@ impl DesktopRuntime {
     ) -> Result<(), CommunityNodeIndexQueryError> {
         let observed_at = Utc::now().timestamp_millis();
         for entry in &response.entries {
-            let observations = [
-                ("post", entry.object_id.as_str()),
-                ("profile", entry.author_pubkey.as_str()),
-            ];
-            for (subject_kind, subject_id) in observations {
+            let observations: &[(&str, &str)] = match entry.entry_kind {
+                IndexEntryKind::Post => &[
+                    ("post", entry.object_id.as_str()),
+                    ("profile", entry.author_pubkey.as_str()),
+                ],
+                // session 自体は通報対象の subject ではないため、開催者の profile だけを記録する。
+                IndexEntryKind::Profile | IndexEntryKind::Session => {
+                    &[("profile", entry.author_pubkey.as_str())]
+                }
+            };
+            for &(subject_kind, subject_id) in observations {
                 self.store
                     .put_content_observation(ContentObservationRow {
                         subject_kind: subject_kind.to_string(),
@@ -4,18 +4,20 @@ use std::path::Path;
 use anyhow::{Context, Result, anyhow};
 use chrono::Utc;
 use kukuri_cn_protocol::{
-    AUTH_CHALLENGE_PATH, AUTH_VERIFY_PATH, AcceptConsentsRequest, ApiErrorBody,
-    AuthChallengeRequest, AuthChallengeResponse, AuthVerifyRequest, AuthVerifyResponse,
-    BOOTSTRAP_HEARTBEAT_PATH, BOOTSTRAP_NODES_PATH, BootstrapHeartbeatRequest,
-    BootstrapHeartbeatResponse, CONSENTS_PATH, CONSENTS_STATUS_PATH, CommunityNodeConsentStatus,
-    CommunityNodeReportRequest, CommunityNodeReportResponse, CommunityNodeResolvedUrls,
-    CommunityNodeSeedPeer, NODE_MANIFEST_PATH, TOPIC_RENDEZVOUS_HEARTBEAT_PATH,
-    TopicRendezvousHeartbeat, build_auth_envelope_json, normalize_http_url,
+    AUTH_CHALLENGE_PATH, AUTH_LOGOUT_PATH, AUTH_REFRESH_PATH, AUTH_VERIFY_PATH,
+    AcceptConsentsRequest, ApiErrorBody, AuthChallengeRequest, AuthChallengeResponse,
+    AuthRefreshRequest, AuthVerifyRequest, AuthVerifyResponse, BOOTSTRAP_HEARTBEAT_PATH,
+    BOOTSTRAP_NODES_PATH, BootstrapHeartbeatRequest, BootstrapHeartbeatResponse, CONSENTS_PATH,
+    CONSENTS_STATUS_PATH, CommunityNodeConsentStatus, CommunityNodeReportRequest,
+    CommunityNodeReportResponse, CommunityNodeResolvedUrls, CommunityNodeSeedPeer,
+    NODE_MANIFEST_PATH, TOPIC_RENDEZVOUS_HEARTBEAT_PATH, TopicRendezvousHeartbeat,
+    build_auth_envelope_json, normalize_http_url,
 };
 use kukuri_core::{
     TopicId, public_topic_rendezvous_key,
     wire::{HINT_TOPIC_PREFIX, PRIVATE_CHANNEL_TOPIC_PREFIX},
 };
+use kukuri_store::CommunityModerationPolicy;
 use kukuri_transport::{SeedPeer, Transport, TransportRelayConfig, parse_seed_peer};
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
@@ -32,6 +34,7 @@ mod index_query_support;
 mod indexing_request_support;
 mod invite_storage_support;
 mod manifest_support;
+mod moderation_events_support;
 mod reconnect_support;
 mod report_routing_support;
 mod requests_support;
@@ -50,9 +53,10 @@ pub use indexing_request_support::{
 };
 pub(crate) use invite_storage_support::*;
 pub use kukuri_cn_protocol::{
-    CommunityNodeReportAppeal, IndexEntryView, IndexQueryResponse, IndexScopeKind,
-    RelationNeighborsResponse, RelationOptoutResponse, RelationReadResponse,
-    SubmitIndexingRequestResponse, TrustUserReadResponse,
+    CommunityNodeReportAppeal, IndexEntryKind, IndexEntryView, IndexProfileView,
+    IndexQueryResponse, IndexScopeKind, IndexSessionKind, IndexSessionStatus, IndexSessionView,
+    IndexSortOrder, RecommendationReason, RelationNeighborsResponse, RelationOptoutResponse,
+    RelationReadResponse, SubmitIndexingRequestResponse, TrustUserReadResponse,
 };
 pub use manifest_support::{
     CommunityNodeAuthorityScope, CommunityNodeCapabilityScope, CommunityNodeManifest,
@@ -100,6 +104,9 @@ pub(crate) const COMMUNITY_NODE_SESSION_SCHEDULER_TICK_SECONDS: u64 = 15;
 // 実際の POST が deadline から最大 tick 分遅れても TTL に達しないため(deadline +25 秒、
 // 最悪 POST +40 秒 < TTL 45 秒)。
 pub(crate) const COMMUNITY_NODE_TOPIC_RENDEZVOUS_REFRESH_MARGIN_SECONDS: i64 = 20;
+// moderation event は advisory で即時性を要しないため、session tick(15 秒)ごとではなく
+// この間隔で pull する。
+pub(crate) const COMMUNITY_NODE_MODERATION_PULL_INTERVAL_SECONDS: i64 = 300;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(ts_rs::TS))]
@@ -157,6 +164,15 @@ pub struct SetCommunityNodeInviteCodeRequest {
     pub invite_code: Option<String>,
 }
 
+/// node ごとの moderation event の扱いを設定する要求。未設定の node は `label` 扱い。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts", ts(optional_fields = nullable))]
+pub struct SetCommunityNodeModerationPolicyRequest {
+    pub base_url: String,
+    pub policy: CommunityModerationPolicy,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -168,6 +184,8 @@ pub enum CommunityNodeAdmissionRejectionCode {
     InviteRevoked,
     NotAllowlisted,
     Banned,
+    Suspended,
+    VouchRequired,
 }
 
 impl CommunityNodeAdmissionRejectionCode {
@@ -180,6 +198,8 @@ impl CommunityNodeAdmissionRejectionCode {
             "INVITE_REVOKED" => Some(Self::InviteRevoked),
             "NOT_ALLOWLISTED" => Some(Self::NotAllowlisted),
             "BANNED" => Some(Self::Banned),
+            "SUSPENDED" => Some(Self::Suspended),
+            "VOUCH_REQUIRED" => Some(Self::VouchRequired),
             _ => None,
         }
     }
@@ -263,6 +283,7 @@ pub(crate) struct CommunityNodeSessionState {
     pub(crate) rendezvous_refresh_deadline: i64,
     pub(crate) metadata_refresh_deadline: i64,
     pub(crate) session_retry_deadline: i64,
+    pub(crate) moderation_pull_deadline: i64,
     pub(crate) session_phase: CommunityNodeSessionPhase,
     pub(crate) ready_refresh_pending: bool,
     pub(crate) last_error: Option<String>,
@@ -297,6 +318,23 @@ pub struct CommunityNodeNodeStatus {
 pub(crate) struct StoredCommunityNodeToken {
     pub(crate) access_token: String,
     pub(crate) expires_at: i64,
+    /// アクセストークンの失効時に challenge を経ず再発行するための一度きりのトークン。
+    /// 旧 node や導入前に保存したトークンには無い。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) refresh_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) refresh_expires_at: Option<i64>,
+}
+
+impl From<AuthVerifyResponse> for StoredCommunityNodeToken {
+    fn from(response: AuthVerifyResponse) -> Self {
+        Self {
+            access_token: response.access_token,
+            expires_at: response.expires_at,
+            refresh_token: response.refresh_token,
+            refresh_expires_at: response.refresh_expires_at,
+        }
+    }
 }
 
 #[cfg(test)]
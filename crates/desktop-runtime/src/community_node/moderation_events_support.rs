@@ -0,0 +1,256 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::Utc;
+use kukuri_cn_protocol::{
+    MODERATION_EVENTS_DEFAULT_LIMIT, MODERATION_EVENTS_PATH, ModerationEventsQuery,
+    ModerationEventsResponse, SignedModerationEvent, normalize_http_url, verify_moderation_event,
+};
+use kukuri_store::{
+    CommunityModerationEventRow, CommunityModerationStore, CommunityModerationSyncRow,
+};
+use serde::Serialize;
+use tracing::warn;
+
+use super::{
+    COMMUNITY_NODE_MODERATION_PULL_INTERVAL_SECONDS, CommunityNodeSessionState,
+    community_node_http_client, load_community_node_token,
+};
+use crate::runtime::DesktopRuntime;
+
+/// serde の snake_case 表現をそのまま保存用の文字列にする(`post` / `risk_label` など)。
+fn moderation_wire_name<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_value(value)
+        .context("failed to encode moderation event field")?
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("moderation event field is not a string"))
+}
+
+/// 検証済みの moderation event を端末内保存用の行へ変換する。
+pub(crate) fn community_moderation_event_row(
+    base_url: &str,
+    event: &SignedModerationEvent,
+    received_at: i64,
+) -> Result<CommunityModerationEventRow> {
+    let body = &event.body;
+    Ok(CommunityModerationEventRow {
+        node_base_url: base_url.to_string(),
+        event_id: body.id.clone(),
+        issuer_node_id: body.issuer_node_id.clone(),
+        target_kind: moderation_wire_name(&body.target_type)?,
+        target_id: body.target_id.clone(),
+        action: moderation_wire_name(&body.action)?,
+        labels: body
+            .labels
+            .iter()
+            .map(|label| moderation_wire_name(&label.category))
+            .collect::<Result<Vec<_>>>()?,
+        reason_code: moderation_wire_name(&body.reason_code)?,
+        signed_event_json: serde_json::to_string(event)
+            .context("failed to encode signed moderation event")?,
+        received_at,
+    })
+}
+
+impl DesktopRuntime {
+    /// 期限到来した node からのみ moderation event を pull する(session scheduler の tick 用)。
+    /// 成否に関わらず次回期限を進め、失敗時に tick ごとの再試行で node を叩き続けない。
+    pub(crate) async fn pull_community_node_moderation_events_if_due(
+        &self,
+        base_url: &str,
+    ) -> Result<usize> {
+        let now = Utc::now().timestamp();
+        let due = self
+            .community_node_sessions
+            .lock()
+            .await
+            .get(base_url)
+            .is_none_or(|session| session.moderation_pull_deadline <= now);
+        if !due {
+            return Ok(0);
+        }
+        let result = self.pull_community_node_moderation_events(base_url).await;
+        self.community_node_sessions
+            .lock()
+            .await
+            .entry(base_url.to_string())
+            .or_insert_with(CommunityNodeSessionState::default)
+            .moderation_pull_deadline =
+            now.saturating_add(COMMUNITY_NODE_MODERATION_PULL_INTERVAL_SECONDS);
+        result
+    }
+
+    /// node が配布する signed moderation event を取得し、manifest の `node_id` で検証して保存する。
+    ///
+    /// 認証済みなら bearer を付けて `subscribed_nodes` まで、未認証なら `public` のみを受け取る。
+    /// manifest 未公開・`node_id` 空の node は issuer 鍵が分からないため何も保存しない。
+    /// 検証に失敗した event は捨てる(advisory を無検証で timeline に反映しない)。
+    ///
+    /// 新しい順に keyset cursor で辿り、既知の event に達したら打ち切る。初回 pull と、前回の
+    /// pull が途中で止まって取りこぼしがある場合(`backfill_cursor` が残っている)は、既知の
+    /// event を越えて最後のページまで辿る。進捗はページごとに保存するため、途中で失敗しても
+    /// 次回はその位置から続ける。戻り値は新規に保存した件数。
+    pub(crate) async fn pull_community_node_moderation_events(
+        &self,
+        base_url: &str,
+    ) -> Result<usize> {
+        let base_url = normalize_http_url(base_url)?;
+        let Some(manifest) = self
+            .request_community_node_manifest(base_url.as_str())
+            .await?
+            .manifest
+        else {
+            return Ok(0);
+        };
+        let issuer_node_id = manifest.node_id.trim().to_string();
+        if issuer_node_id.is_empty() {
+            return Ok(0);
+        }
+        let token =
+            load_community_node_token(&self.db_path, self.identity_mode, base_url.as_str())?;
+        let client = community_node_http_client()?;
+        let url = format!("{base_url}{MODERATION_EVENTS_PATH}");
+        let sync = self
+            .store
+            .get_community_moderation_sync(base_url.as_str())
+            .await?;
+        // 初回は既知の event で止まらず最後まで辿る。
+        let mut backfilling = sync.is_none();
+        let mut pending_backfill = sync.and_then(|sync| sync.backfill_cursor);
+        let mut progress_saved = false;
+        let mut cursor: Option<String> = None;
+        let mut stored = 0;
+        loop {
+            let mut request = client.get(url.as_str()).query(&ModerationEventsQuery {
+                limit: Some(MODERATION_EVENTS_DEFAULT_LIMIT),
+                cursor: cursor.clone(),
+            });
+            if let Some(token) = token.as_ref() {
+                request = request.bearer_auth(token.access_token.as_str());
+            }
+            let response = request
+                .send()
+                .await
+                .context("failed to fetch community node moderation events")?
+                .error_for_status()
+                .context("community node moderation events request failed")?
+                .json::<ModerationEventsResponse>()
+                .await
+                .context("failed to decode community node moderation events")?;
+            let received_at = Utc::now().timestamp_millis();
+            let mut reached_known = false;
+            for event in &response.items {
+                if let Err(error) = verify_moderation_event(event, issuer_node_id.as_str()) {
+                    warn!(
+                        base_url = %base_url,
+                        event_id = %event.body.id,
+                        error = %error,
+                        "discarding unverifiable community-node moderation event"
+                    );
+                    continue;
+                }
+                let row = community_moderation_event_row(base_url.as_str(), event, received_at)?;
+                if self.store.put_community_moderation_event(row).await? {
+                    stored += 1;
+                } else {
+                    reached_known = true;
+                }
+            }
+            let Some(next_cursor) = response.next_cursor else {
+                // 最後のページまで辿ったので取りこぼしは無い。
+                self.save_community_moderation_backfill(base_url.as_str(), None)
+                    .await?;
+                break;
+            };
+            if backfilling || !reached_known {
+                // ここで止まっても次回この位置から古い側を辿り直せるようにしておく。
+                self.save_community_moderation_backfill(
+                    base_url.as_str(),
+                    Some(next_cursor.clone()),
+                )
+                .await?;
+                progress_saved = true;
+                cursor = Some(next_cursor);
+                continue;
+            }
+            if let Some(resume) = pending_backfill.take() {
+                backfilling = true;
+                cursor = Some(resume);
+                continue;
+            }
+            if progress_saved {
+                self.save_community_moderation_backfill(base_url.as_str(), None)
+                    .await?;
+            }
+            break;
+        }
+        Ok(stored)
+    }
+
+    async fn save_community_moderation_backfill(
+        &self,
+        base_url: &str,
+        backfill_cursor: Option<String>,
+    ) -> Result<()> {
+        self.store
+            .put_community_moderation_sync(CommunityModerationSyncRow {
+                node_base_url: base_url.to_string(),
+                backfill_cursor,
+                updated_at: Utc::now().timestamp_millis(),
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kukuri_core::generate_keys;
+
+    fn unsigned_event(issuer_node_id: &str) -> SignedModerationEvent {
+        serde_json::from_value(serde_json::json!({
+            "body": {
+                "id": "event-1",
+                "issuer_node_id": issuer_node_id,
+                "target_type": "post",
+                "target_id": "post-1",
+                "action": "risk_label",
+                "labels": [{ "category": "spam" }],
+                "reason_code": "general_moderation",
+                "severity": "medium",
+                "confidence": 80,
+                "basis": "classifier_score",
+                "visibility": "public",
+                "policy_version": "v1",
+                "created_at": "2026-10-01T00:00:00Z"
+            },
+            "signature": "00"
+        }))
+        .expect("decode moderation event")
+    }
+
+    #[test]
+    fn moderation_event_row_keeps_wire_names_and_signed_payload() {
+        let issuer = generate_keys().public_key_hex();
+        let event = unsigned_event(issuer.as_str());
+        let row = community_moderation_event_row("https://node.example", &event, 7).unwrap();
+
+        assert_eq!(row.node_base_url, "https://node.example");
+        assert_eq!(row.event_id, "event-1");
+        assert_eq!(row.target_kind, "post");
+        assert_eq!(row.action, "risk_label");
+        assert_eq!(row.labels, vec!["spam".to_string()]);
+        assert_eq!(row.reason_code, "general_moderation");
+        assert_eq!(row.received_at, 7);
+        let decoded: SignedModerationEvent =
+            serde_json::from_str(row.signed_event_json.as_str()).unwrap();
+        assert_eq!(decoded, event);
+    }
+
+    #[test]
+    fn unsigned_moderation_event_is_rejected_before_storage() {
+        let issuer = generate_keys().public_key_hex();
+        let event = unsigned_event(issuer.as_str());
+
+        assert!(verify_moderation_event(&event, issuer.as_str()).is_err());
+    }
+}
@@ -209,6 +209,7 @@ mod tests {
                 configured_seed_peer_ids: Vec::new(),
                 bootstrap_seed_peer_ids: Vec::new(),
                 manual_ticket_peer_ids: Vec::new(),
+                lan_peer_ids: Vec::new(),
                 connected_peer_ids: Vec::new(),
                 docs_assist_peer_ids: Vec::new(),
                 blob_assist_peer_ids: Vec::new(),
@@ -217,6 +218,7 @@ mod tests {
             },
             gossip_disabled_topics: Vec::new(),
             gossip_disabled_channels: Vec::new(),
+            blob_downloads: Default::default(),
         }
     }
 
@@ -6,6 +6,11 @@ impl DesktopRuntime {
         base_url: &str,
     ) -> Result<StoredCommunityNodeToken> {
         let base_url = normalize_http_url(base_url)?;
+        // refresh token が残っていれば challenge を経ずに再発行する。拒否されたら(失効・
+        // ban・使用済みなど)通常の challenge / verify に戻り、admission の判定もそちらで受ける。
+        if let Some(token) = self.refresh_community_node_token(base_url.as_str()).await? {
+            return Ok(token);
+        }
         let client = community_node_http_client()?;
         let challenge_url = format!("{base_url}{AUTH_CHALLENGE_PATH}");
         let pubkey = self.author_keys.public_key_hex();
@@ -83,10 +88,7 @@ impl DesktopRuntime {
             .json::<AuthVerifyResponse>()
             .await
             .context("failed to decode auth verify response")?;
-        let token = StoredCommunityNodeToken {
-            access_token: verify.access_token,
-            expires_at: verify.expires_at,
-        };
+        let token = StoredCommunityNodeToken::from(verify);
         persist_community_node_token(&self.db_path, self.identity_mode, base_url.as_str(), &token)?;
         Ok(token)
     }
@@ -4,9 +4,10 @@ use std::time::Duration;
 use super::*;
 
 impl DesktopRuntime {
-    /// CN セッション維持の 1 tick。設定済みの全 node へ registration refresh(if_due)を回し、
-    /// 続けて connectivity self-heal 判定を実行する。従来 `get_sync_status` の副作用として
-    /// UI ポーリング経由でのみ駆動されていた内容を、ポーリング非依存で駆動する(WP-C1)。
+    /// CN セッション維持の 1 tick。設定済みの全 node へ registration refresh(if_due)と
+    /// moderation event の pull(if_due)を回し、続けて connectivity self-heal 判定を実行する。
+    /// 従来 `get_sync_status` の副作用として UI ポーリング経由でのみ駆動されていた内容を、
+    /// ポーリング非依存で駆動する(WP-C1)。
     /// refresh は deadline ゲート済みの冪等設計のため、短い間隔で繰り返し呼んでも安全。
     pub(crate) async fn run_community_node_session_maintenance_once(&self) {
         let config = self.community_node_config.lock().await.clone();
@@ -24,6 +25,16 @@ impl DesktopRuntime {
                     "failed to refresh community-node registration from session scheduler"
                 );
             }
+            if let Err(error) = self
+                .pull_community_node_moderation_events_if_due(node.base_url.as_str())
+                .await
+            {
+                warn!(
+                    base_url = %node.base_url,
+                    error = %error,
+                    "failed to pull community-node moderation events from session scheduler"
+                );
+            }
         }
         match self.app_service.get_sync_status().await {
             Ok(status) => {
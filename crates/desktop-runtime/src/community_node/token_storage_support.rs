@@ -29,3 +29,116 @@ pub(crate) fn persist_community_node_token(
         encoded.as_str(),
     )
 }
+
+impl DesktopRuntime {
+    /// 保存済みの refresh token でアクセストークンを再発行し、保存し直して返す。
+    ///
+    /// refresh token が無い・期限切れ・node に拒否された(401/403)場合は `None` を返し、
+    /// 呼び出し側は challenge / verify による再認証に戻る。拒否された token は使えないので捨てる。
+    /// refresh 未対応の node(404/405)・5xx・通信失敗でも `None` を返して challenge に戻るが、
+    /// token はまだ有効な可能性があるので残す。
+    pub(crate) async fn refresh_community_node_token(
+        &self,
+        base_url: &str,
+    ) -> Result<Option<StoredCommunityNodeToken>> {
+        let Some(stored) = load_community_node_token(&self.db_path, self.identity_mode, base_url)?
+        else {
+            return Ok(None);
+        };
+        let Some(refresh_token) = stored.refresh_token else {
+            return Ok(None);
+        };
+        if stored
+            .refresh_expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now().timestamp())
+        {
+            return Ok(None);
+        }
+        let seed_peer = self.local_community_node_seed_peer("auth refresh").await?;
+        let response = match community_node_http_client()?
+            .post(format!("{base_url}{AUTH_REFRESH_PATH}"))
+            .json(&AuthRefreshRequest {
+                refresh_token,
+                // verify と同じく endpoint を bootstrap peer として登録し直させる。
+                endpoint_id: Some(seed_peer.endpoint_id),
+                addr_hint: seed_peer.addr_hint,
+            })
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(error) => {
+                debug!(
+                    base_url,
+                    error = %error,
+                    "community-node token refresh failed to send; falling back to challenge"
+                );
+                return Ok(None);
+            }
+        };
+        let status = response.status();
+        if matches!(status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) {
+            debug!(
+                base_url,
+                status = %status,
+                "community-node refresh token was rejected; falling back to challenge"
+            );
+            crate::identity::delete_optional_secret(
+                &self.db_path,
+                self.identity_mode,
+                COMMUNITY_NODE_TOKEN_PURPOSE,
+                base_url,
+            )?;
+            return Ok(None);
+        }
+        if matches!(
+            status,
+            StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+        ) || status.is_server_error()
+        {
+            debug!(
+                base_url,
+                status = %status,
+                "community-node token refresh is unavailable; falling back to challenge"
+            );
+            return Ok(None);
+        }
+        let token = StoredCommunityNodeToken::from(
+            response
+                .error_for_status()
+                .context("community-node token refresh failed")?
+                .json::<AuthVerifyResponse>()
+                .await
+                .context("failed to decode community-node token refresh response")?,
+        );
+        persist_community_node_token(&self.db_path, self.identity_mode, base_url, &token)?;
+        Ok(Some(token))
+    }
+
+    /// node 側のセッションを失効させる(logout)。端末側のトークン破棄の前に呼ぶ best-effort で、
+    /// node に届かなくても破棄は続ける(その場合 node 側のセッションは期限切れで消える)。
+    pub(crate) async fn logout_community_node_token(&self, base_url: &str) {
+        let token = match load_community_node_token(&self.db_path, self.identity_mode, base_url) {
+            Ok(Some(token)) => token,
+            Ok(None) => return,
+            Err(error) => {
+                warn!(error = %error, base_url, "failed to load community-node token for logout");
+                return;
+            }
+        };
+        let result = match community_node_http_client() {
+            Ok(client) => client
+                .post(format!("{base_url}{AUTH_LOGOUT_PATH}"))
+                .bearer_auth(token.access_token.as_str())
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .map(|_| ())
+                .map_err(anyhow::Error::from),
+            Err(error) => Err(error),
+        };
+        if let Err(error) = result {
+            warn!(error = %error, base_url, "failed to log out of community node");
+        }
+    }
+}
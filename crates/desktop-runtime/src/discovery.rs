@@ -123,8 +123,9 @@ pub(crate) fn parse_discovery_mode(value: &str) -> Result<DiscoveryMode> {
     match value.trim() {
         "static_peer" => Ok(DiscoveryMode::StaticPeer),
         "seeded_dht" => Ok(DiscoveryMode::SeededDht),
+        "lan" => Ok(DiscoveryMode::Lan),
         other => Err(anyhow!(
-            "invalid {} value `{}` (expected static_peer or seeded_dht)",
+            "invalid {} value `{}` (expected static_peer, seeded_dht or lan)",
             DISCOVERY_MODE_ENV,
             other
         )),
@@ -35,6 +35,15 @@ pub(crate) fn load_or_create_keys(db_path: &Path, mode: IdentityStorageMode) ->
     load_or_create_keys_with_keyring(db_path, mode, &SystemKeyringStore)
 }
 
+/// pairing ticket や identity backup で受け取った author 鍵を、まだ identity を持たない db_path に保存する。
+pub(crate) fn install_paired_keys(
+    db_path: &Path,
+    mode: IdentityStorageMode,
+    keys: &KukuriKeys,
+) -> Result<()> {
+    install_paired_keys_with_keyring(db_path, mode, keys, &SystemKeyringStore)
+}
+
 pub(crate) fn load_optional_secret(
     db_path: &Path,
     mode: IdentityStorageMode,
@@ -89,8 +98,41 @@ fn load_or_create_keys_with_keyring(
     }
 
     let keys = KukuriKeys::generate();
-    let encoded = keys.export_secret_hex();
+    persist_new_keys(db_path, mode, &keys, keyring)?;
+    Ok(keys)
+}
 
+fn install_paired_keys_with_keyring(
+    db_path: &Path,
+    mode: IdentityStorageMode,
+    keys: &KukuriKeys,
+    keyring: &dyn KeyringStore,
+) -> Result<()> {
+    // 既存 identity を上書きすると元の author 鍵を失うため、空の db_path に限る。
+    if load_backend_marker(db_path)?.is_some() || load_secret_from_file(db_path)?.is_some() {
+        return Err(anyhow!(
+            "identity already exists for `{}`; installing an identity requires a fresh profile",
+            db_path.display()
+        ));
+    }
+    if mode == IdentityStorageMode::Auto
+        && let Ok(Some(_)) = load_secret_from_keyring(db_path, keyring)
+    {
+        return Err(anyhow!(
+            "identity already exists in keyring for `{}`; installing an identity requires a fresh profile",
+            db_path.display()
+        ));
+    }
+    persist_new_keys(db_path, mode, keys, keyring)
+}
+
+fn persist_new_keys(
+    db_path: &Path,
+    mode: IdentityStorageMode,
+    keys: &KukuriKeys,
+    keyring: &dyn KeyringStore,
+) -> Result<()> {
+    let encoded = keys.export_secret_hex();
     if mode == IdentityStorageMode::Auto
         && persist_secret_to_keyring(db_path, encoded.as_str(), keyring).is_ok()
     {
@@ -99,8 +141,7 @@ fn load_or_create_keys_with_keyring(
         persist_secret_to_file(db_path, encoded.as_str())?;
         write_backend_marker(db_path, BACKEND_FILE)?;
     }
-
-    Ok(keys)
+    Ok(())
 }
 
 fn load_keys_with_backend(
@@ -759,4 +800,32 @@ mod tests {
 
         assert_eq!(restored.export_secret_hex(), keys.export_secret_hex());
     }
+
+    #[test]
+    fn paired_keys_install_only_into_fresh_profile() {
+        clear_identity_env();
+        let dir = tempdir().expect("tempdir");
+        let db_path = dir.path().join("kukuri.db");
+        let keyring = FakeKeyringStore::default();
+        let paired = KukuriKeys::generate();
+
+        install_paired_keys_with_keyring(&db_path, IdentityStorageMode::Auto, &paired, &keyring)
+            .expect("install paired keys");
+        let restored =
+            load_or_create_keys_with_keyring(&db_path, IdentityStorageMode::Auto, &keyring)
+                .expect("reload paired keys");
+        assert_eq!(restored.export_secret_hex(), paired.export_secret_hex());
+
+        let error = install_paired_keys_with_keyring(
+            &db_path,
+            IdentityStorageMode::Auto,
+            &KukuriKeys::generate(),
+            &keyring,
+        )
+        .expect_err("existing identity must not be overwritten");
+        assert!(
+            error.to_string().contains("identity already exists"),
+            "unexpected error: {error}"
+        );
+    }
 }
@@ -29,35 +29,39 @@ fn emit<T: TS>(cfg: &ts_rs::Config, out: &mut String) {
 #[test]
 fn export_ipc_types() {
     use crate::{
-        AcceptCommunityNodeConsentsRequest, AuthorRequest, BookmarkCustomReactionRequest,
-        BookmarkPostRequest, CommunityNodeAdmissionRejection, CommunityNodeAdmissionRejectionCode,
-        CommunityNodeAuthState, CommunityNodeAuthorityScope, CommunityNodeCapabilityScope,
-        CommunityNodeConfig, CommunityNodeIndexQueryError, CommunityNodeIndexQueryRequest,
-        CommunityNodeIndexingRequest, CommunityNodeIndexingRequestError, CommunityNodeManifest,
-        CommunityNodeManifestFetch, CommunityNodeManifestFetchStatus, CommunityNodeNodeConfig,
-        CommunityNodeNodeStatus, CommunityNodeP2pBoundary, CommunityNodeRelationNeighborsRequest,
-        CommunityNodeReportAppeal, CommunityNodeReportError, CommunityNodeSessionPhase,
-        CommunityNodeTargetRequest, CommunityNodeTrustRelationError,
-        CommunityNodeUserAdvisoryRequest, CreateAttachmentRequest,
-        CreateCustomReactionAssetRequest, CreateGameRoomRequest, CreateLiveSessionRequest,
-        CreateMetaverseRoomRequest, CreatePostRequest, CreatePrivateChannelRequest,
-        CreateRepostRequest, CustomReactionCropRect, DeleteDirectMessageMessageRequest,
-        DirectMessageRequest, DiscoveryConfig, ExportChannelAccessTokenRequest,
-        ExportFriendOnlyGrantRequest, ExportFriendPlusShareRequest,
+        AcceptCommunityNodeConsentsRequest, AcceptDevicePairingRequest, AuthorRequest,
+        BookmarkCustomReactionRequest, BookmarkPostRequest, CommunityNodeAdmissionRejection,
+        CommunityNodeAdmissionRejectionCode, CommunityNodeAuthState, CommunityNodeAuthorityScope,
+        CommunityNodeCapabilityScope, CommunityNodeConfig, CommunityNodeIndexQueryError,
+        CommunityNodeIndexQueryRequest, CommunityNodeIndexingRequest,
+        CommunityNodeIndexingRequestError, CommunityNodeManifest, CommunityNodeManifestFetch,
+        CommunityNodeManifestFetchStatus, CommunityNodeNodeConfig, CommunityNodeNodeStatus,
+        CommunityNodeP2pBoundary, CommunityNodeRelationNeighborsRequest, CommunityNodeReportAppeal,
+        CommunityNodeReportError, CommunityNodeSessionPhase, CommunityNodeTargetRequest,
+        CommunityNodeTrustRelationError, CommunityNodeUserAdvisoryRequest, CreateAttachmentRequest,
+        CreateCustomReactionAssetRequest, CreateDevicePairingRequest, CreateGameRoomRequest,
+        CreateLiveSessionRequest, CreateMetaverseRoomRequest, CreatePostRequest,
+        CreatePrivateChannelRequest, CreateRepostRequest, CustomReactionCropRect,
+        DeleteDirectMessageMessageRequest, DirectMessageRequest, DiscoveryConfig,
+        ExportChannelAccessTokenRequest, ExportFriendOnlyGrantRequest,
+        ExportFriendPlusShareRequest, ExportIdentityBackupRequest,
         ExportPrivateChannelInviteRequest, FreezePrivateChannelRequest, GetBlobMediaRequest,
         GetBlobPreviewRequest, ImportChannelAccessTokenRequest, ImportFriendOnlyGrantRequest,
-        ImportFriendPlusShareRequest, ImportMetaverseRoomAssetRequest, ImportPeerTicketRequest,
-        ImportPrivateChannelInviteRequest, IndexEntryView, IndexQueryResponse, IndexScopeKind,
-        LeavePrivateChannelRequest, ListDirectMessageMessagesRequest, ListGameRoomsRequest,
-        ListJoinedPrivateChannelsRequest, ListLiveSessionsRequest, ListMetaverseRoomEventsRequest,
-        ListProfileTimelineRequest, ListRecentReactionsRequest, ListSocialConnectionsRequest,
-        ListThreadRequest, ListTimelineRequest, LiveSessionCommandRequest, NotificationIdRequest,
+        ImportFriendPlusShareRequest, ImportIdentityBackupRequest, ImportMetaverseRoomAssetRequest,
+        ImportPeerTicketRequest, ImportPrivateChannelInviteRequest, IndexEntryKind, IndexEntryView,
+        IndexProfileView, IndexQueryResponse, IndexScopeKind, IndexSessionKind, IndexSessionStatus,
+        IndexSessionView, IndexSortOrder, LeavePrivateChannelRequest,
+        ListDirectMessageMessagesRequest, ListGameRoomsRequest, ListJoinedPrivateChannelsRequest,
+        ListLiveSessionsRequest, ListMetaverseRoomEventsRequest, ListProfileTimelineRequest,
+        ListRecentReactionsRequest, ListSocialConnectionsRequest, ListThreadRequest,
+        ListTimelineRequest, LiveSessionCommandRequest, NotificationIdRequest,
         PreviewChannelAccessTokenRequest, PublishMetaverseRoomEventRequest, ReactionKeyRequest,
-        RelationNeighborsResponse, RelationOptoutResponse, RelationReadResponse,
-        RemoveBookmarkedCustomReactionRequest, RemoveBookmarkedPostRequest,
-        RotatePrivateChannelRequest, RuntimeEvent, SendDirectMessageRequest,
-        SetChannelGossipEnabledRequest, SetCommunityNodeConfigNode, SetCommunityNodeConfigRequest,
-        SetCommunityNodeInviteCodeRequest, SetDiscoverySeedsRequest, SetMyProfileRequest,
+        RebuildTopicProjectionsRequest, RecommendationReason, RelationNeighborsResponse,
+        RelationOptoutResponse, RelationReadResponse, RemoveBookmarkedCustomReactionRequest,
+        RemoveBookmarkedPostRequest, RotatePrivateChannelRequest, RuntimeEvent,
+        SendDirectMessageRequest, SetChannelGossipEnabledRequest, SetCommunityNodeConfigNode,
+        SetCommunityNodeConfigRequest, SetCommunityNodeInviteCodeRequest,
+        SetCommunityNodeModerationPolicyRequest, SetDiscoverySeedsRequest, SetMyProfileRequest,
         SetTopicGossipEnabledRequest, SubmitCommunityNodeReportRequest,
         SubmitCommunityNodeReportResult, SubmitCommunityNodeReportStatus,
         SubmitIndexingRequestResponse, ToggleReactionRequest, TrustUserReadResponse,
@@ -99,6 +103,7 @@ fn export_ipc_types() {
         BlobViewStatus,
         DeliveryState,
         NotificationKind,
+        CommunityModerationPolicy,
         ChannelAccessTokenKind,
         ProfileAssetView,
         AttachmentView,
@@ -113,6 +118,9 @@ fn export_ipc_types() {
         RepostSourceView,
         ContentObservationView,
         ContentProvenanceView,
+        ContentModerationSourceView,
+        ContentModerationView,
+        CommunityModerationPolicyView,
         PostView,
         BookmarkedPostView,
         AuthorSocialView,
@@ -124,6 +132,9 @@ fn export_ipc_types() {
         NotificationStatusView,
         TimelineView,
         DirectMessageTimelineView,
+        LinkedDeviceView,
+        DevicePairingView,
+        IdentityBackupImportView,
         JoinedPrivateChannelView,
         PrivateChannelEpochCapability,
         PrivateChannelCapability,
@@ -131,7 +142,10 @@ fn export_ipc_types() {
         ChannelAccessTokenPreview,
         TopicSyncStatus,
         DiscoveryStatus,
+        BlobDownloadQueueStatus,
         SyncStatus,
+        StorageUsageView,
+        BlobGarbageCollectionView,
         // metaverse / game / live
         LiveSessionStatus,
         LiveSessionView,
@@ -187,7 +201,14 @@ fn export_ipc_types() {
         CommunityNodeIndexingRequest,
         CommunityNodeIndexingRequestError,
         IndexScopeKind,
+        IndexSortOrder,
         IndexingRequestStatus,
+        RecommendationReason,
+        IndexEntryKind,
+        IndexProfileView,
+        IndexSessionKind,
+        IndexSessionStatus,
+        IndexSessionView,
         IndexEntryView,
         IndexQueryResponse,
         SubmitIndexingRequestResponse,
@@ -214,6 +235,7 @@ fn export_ipc_types() {
         SubmitCommunityNodeReportRequest,
         SubmitCommunityNodeReportStatus,
         SubmitCommunityNodeReportResult,
+        ContentEvent,
         RuntimeEvent,
         // 入力方向の request DTO(requests.rs。WP-B6)
         CreatePostRequest,
@@ -233,6 +255,7 @@ fn export_ipc_types() {
         ListProfileTimelineRequest,
         ImportPeerTicketRequest,
         UnsubscribeTopicRequest,
+        RebuildTopicProjectionsRequest,
         SetTopicGossipEnabledRequest,
         SetChannelGossipEnabledRequest,
         GetBlobPreviewRequest,
@@ -244,6 +267,10 @@ fn export_ipc_types() {
         ListDirectMessageMessagesRequest,
         SendDirectMessageRequest,
         DeleteDirectMessageMessageRequest,
+        CreateDevicePairingRequest,
+        AcceptDevicePairingRequest,
+        ExportIdentityBackupRequest,
+        ImportIdentityBackupRequest,
         SetMyProfileRequest,
         ListLiveSessionsRequest,
         CreateLiveSessionRequest,
@@ -274,6 +301,7 @@ fn export_ipc_types() {
         SetCommunityNodeConfigNode,
         SetCommunityNodeConfigRequest,
         SetCommunityNodeInviteCodeRequest,
+        SetCommunityNodeModerationPolicyRequest,
         CommunityNodeTargetRequest,
         AcceptCommunityNodeConsentsRequest,
         SetDiscoverySeedsRequest,
@@ -21,10 +21,12 @@ pub use community_node::{
     CommunityNodeManifestFetchStatus, CommunityNodeNodeConfig, CommunityNodeNodeStatus,
     CommunityNodeP2pBoundary, CommunityNodeRelationNeighborsRequest, CommunityNodeReportAppeal,
     CommunityNodeReportError, CommunityNodeSessionPhase, CommunityNodeTargetRequest,
-    CommunityNodeTrustRelationError, CommunityNodeUserAdvisoryRequest, IndexEntryView,
-    IndexQueryResponse, IndexScopeKind, RelationNeighborsResponse, RelationOptoutResponse,
-    RelationReadResponse, SetCommunityNodeConfigNode, SetCommunityNodeConfigRequest,
-    SetCommunityNodeInviteCodeRequest, SubmitCommunityNodeReportRequest,
+    CommunityNodeTrustRelationError, CommunityNodeUserAdvisoryRequest, IndexEntryKind,
+    IndexEntryView, IndexProfileView, IndexQueryResponse, IndexScopeKind, IndexSessionKind,
+    IndexSessionStatus, IndexSessionView, IndexSortOrder, RecommendationReason,
+    RelationNeighborsResponse, RelationOptoutResponse, RelationReadResponse,
+    SetCommunityNodeConfigNode, SetCommunityNodeConfigRequest, SetCommunityNodeInviteCodeRequest,
+    SetCommunityNodeModerationPolicyRequest, SubmitCommunityNodeReportRequest,
     SubmitCommunityNodeReportResult, SubmitCommunityNodeReportStatus,
     SubmitIndexingRequestResponse, TrustUserReadResponse,
 };
@@ -33,23 +35,25 @@ pub use discovery::{DiscoveryConfig, SetDiscoverySeedsRequest};
 pub use kukuri_store::StoreStartupError;
 pub use paths::resolve_db_path_from_env;
 pub use requests::{
-    AuthorRequest, BookmarkCustomReactionRequest, BookmarkPostRequest, CreateAttachmentRequest,
-    CreateCustomReactionAssetRequest, CreateGameRoomRequest, CreateLiveSessionRequest,
-    CreateMetaverseRoomRequest, CreatePostRequest, CreatePrivateChannelRequest,
-    CreateRepostRequest, CustomReactionCropRect, DeleteDirectMessageMessageRequest,
-    DirectMessageRequest, ExportChannelAccessTokenRequest, ExportFriendOnlyGrantRequest,
-    ExportFriendPlusShareRequest, ExportPrivateChannelInviteRequest, FreezePrivateChannelRequest,
-    GetBlobMediaRequest, GetBlobPreviewRequest, ImportChannelAccessTokenRequest,
-    ImportFriendOnlyGrantRequest, ImportFriendPlusShareRequest, ImportMetaverseRoomAssetRequest,
+    AcceptDevicePairingRequest, AuthorRequest, BookmarkCustomReactionRequest, BookmarkPostRequest,
+    CreateAttachmentRequest, CreateCustomReactionAssetRequest, CreateDevicePairingRequest,
+    CreateGameRoomRequest, CreateLiveSessionRequest, CreateMetaverseRoomRequest, CreatePostRequest,
+    CreatePrivateChannelRequest, CreateRepostRequest, CustomReactionCropRect,
+    DeleteDirectMessageMessageRequest, DirectMessageRequest, ExportChannelAccessTokenRequest,
+    ExportFriendOnlyGrantRequest, ExportFriendPlusShareRequest, ExportIdentityBackupRequest,
+    ExportPrivateChannelInviteRequest, FreezePrivateChannelRequest, GetBlobMediaRequest,
+    GetBlobPreviewRequest, ImportChannelAccessTokenRequest, ImportFriendOnlyGrantRequest,
+    ImportFriendPlusShareRequest, ImportIdentityBackupRequest, ImportMetaverseRoomAssetRequest,
     ImportPeerTicketRequest, ImportPrivateChannelInviteRequest, LeavePrivateChannelRequest,
     ListDirectMessageMessagesRequest, ListGameRoomsRequest, ListJoinedPrivateChannelsRequest,
     ListLiveSessionsRequest, ListMetaverseRoomEventsRequest, ListProfileTimelineRequest,
     ListRecentReactionsRequest, ListSocialConnectionsRequest, ListThreadRequest,
     ListTimelineRequest, LiveSessionCommandRequest, NotificationIdRequest,
     PreviewChannelAccessTokenRequest, PublishMetaverseRoomEventRequest, ReactionKeyRequest,
-    RemoveBookmarkedCustomReactionRequest, RemoveBookmarkedPostRequest,
-    RotatePrivateChannelRequest, SendDirectMessageRequest, SetChannelGossipEnabledRequest,
-    SetMyProfileRequest, SetTopicGossipEnabledRequest, ToggleReactionRequest,
-    UnsubscribeTopicRequest, UpdateGameRoomRequest, UpdateMetaverseRoomRequest,
+    RebuildTopicProjectionsRequest, RemoveBookmarkedCustomReactionRequest,
+    RemoveBookmarkedPostRequest, RotatePrivateChannelRequest, SendDirectMessageRequest,
+    SetChannelGossipEnabledRequest, SetMyProfileRequest, SetTopicGossipEnabledRequest,
+    ToggleReactionRequest, UnsubscribeTopicRequest, UpdateGameRoomRequest,
+    UpdateMetaverseRoomRequest,
 };
 pub use runtime::{DesktopRuntime, RuntimeEvent};
@@ -81,6 +81,22 @@ impl DesktopRuntime {
             .await
     }
 
+    pub async fn search_community_node_people(
+        &self,
+        request: CommunityNodeIndexQueryRequest,
+    ) -> std::result::Result<IndexQueryResponse, CommunityNodeIndexQueryError> {
+        self.query_community_node_index(IndexOperation::People, request)
+            .await
+    }
+
+    pub async fn list_community_node_live_now(
+        &self,
+        request: CommunityNodeIndexQueryRequest,
+    ) -> std::result::Result<IndexQueryResponse, CommunityNodeIndexQueryError> {
+        self.query_community_node_index(IndexOperation::Live, request)
+            .await
+    }
+
     pub async fn get_community_node_config(&self) -> Result<CommunityNodeConfig> {
         Ok(self.community_node_config.lock().await.clone())
     }
@@ -280,6 +296,7 @@ impl DesktopRuntime {
         request: CommunityNodeTargetRequest,
     ) -> Result<CommunityNodeNodeStatus> {
         let base_url = normalize_http_url(request.base_url.as_str())?;
+        self.logout_community_node_token(base_url.as_str()).await;
         delete_optional_secret(
             &self.db_path,
             self.identity_mode,
@@ -429,6 +446,25 @@ impl DesktopRuntime {
             .await
     }
 
+    /// 構成済み node の moderation event を timeline でどう扱うか(hide / blur / label / ignore)を
+    /// 設定する。event 自体は advisory のまま保存され、扱いは端末内 policy でのみ決まる。
+    pub async fn set_community_node_moderation_policy(
+        &self,
+        request: SetCommunityNodeModerationPolicyRequest,
+    ) -> Result<CommunityModerationPolicyView> {
+        let base_url = normalize_http_url(request.base_url.as_str())?;
+        self.require_community_node(base_url.as_str()).await?;
+        self.app_service
+            .set_community_moderation_policy(base_url.as_str(), request.policy)
+            .await
+    }
+
+    pub async fn list_community_node_moderation_policies(
+        &self,
+    ) -> Result<Vec<CommunityModerationPolicyView>> {
+        self.app_service.list_community_moderation_policies().await
+    }
+
     /// 解決済みの通報先 node へ通報を送信する（#310 の分散通報ルーティング）。
     /// 通報先は client が provenance + manifest から解決し、その report endpoint を渡す。
     pub async fn submit_community_node_report(
@@ -453,6 +489,18 @@ impl DesktopRuntime {
             handle.abort();
             let _ = tokio::time::timeout(std::time::Duration::from_secs(2), handle).await;
         }
+        if let Some(handle) = self.linked_device_sync_task.lock().await.take() {
+            handle.abort();
+            let _ = tokio::time::timeout(std::time::Duration::from_secs(2), handle).await;
+        }
+        if let Some(handle) = self.blob_download_task.lock().await.take() {
+            handle.abort();
+            let _ = tokio::time::timeout(std::time::Duration::from_secs(2), handle).await;
+        }
+        if let Some(handle) = self.blob_retention_task.lock().await.take() {
+            handle.abort();
+            let _ = tokio::time::timeout(std::time::Duration::from_secs(2), handle).await;
+        }
         self.app_service.shutdown().await;
         let _ = tokio::time::timeout(
             std::time::Duration::from_secs(15),
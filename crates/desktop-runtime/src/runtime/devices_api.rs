@@ -0,0 +1,173 @@
+use std::sync::Weak;
+use std::time::Duration;
+
+use tokio::time::MissedTickBehavior;
+
+use super::*;
+
+/// 他端末の state は device replica の remote fetch を伴うため、sync status の 3 秒 pull とは
+/// 分けて粗い間隔で取り込む。手動同期は `sync_linked_devices` で即時に行える。
+const LINKED_DEVICE_SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// identity backup の暗号文に入れる端末内 state。鍵は core 側の bundle が持つ。
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct IdentityBackupStateV1 {
+    #[serde(default)]
+    private_channel_capabilities: Vec<PrivateChannelCapability>,
+    #[serde(default)]
+    direct_messages: DirectMessageBackupV1,
+}
+
+impl DesktopRuntime {
+    /// pairing ticket を受け取った新端末側の初期化。runtime 起動前に呼び、
+    /// author 鍵と発行元が割り当てた device id を db_path の identity storage に保存する。
+    pub fn accept_device_pairing(
+        db_path: impl AsRef<Path>,
+        request: AcceptDevicePairingRequest,
+    ) -> Result<LinkedDeviceView> {
+        let db_path = db_path.as_ref();
+        let identity_mode = IdentityStorageMode::from_env();
+        if load_device_id(db_path, identity_mode)?.is_some() {
+            bail!("device id already exists; device pairing requires a fresh profile");
+        }
+        let pairing = open_device_pairing_ticket(
+            request.ticket.as_str(),
+            request.pairing_code.as_str(),
+            chrono::Utc::now().timestamp_millis(),
+        )?;
+        install_paired_keys(db_path, identity_mode, &pairing.keys)?;
+        persist_device_id(
+            db_path,
+            identity_mode,
+            pairing.authorization.device_id.as_str(),
+        )?;
+        Ok(LinkedDeviceView {
+            device_id: pairing.authorization.device_id,
+            device_label: pairing.authorization.device_label,
+            authorized_by_device_id: pairing.authorization.authorized_by_device_id,
+            authorized_at: pairing.authorization.authorized_at,
+            is_current_device: true,
+        })
+    }
+
+    /// author 鍵・private channel capability(friend grant 経由の参加を含む)・DM state を
+    /// passphrase で暗号化した backup bundle を返す。
+    pub async fn export_identity_backup(
+        &self,
+        request: ExportIdentityBackupRequest,
+    ) -> Result<String> {
+        let state = IdentityBackupStateV1 {
+            private_channel_capabilities: load_private_channel_capabilities(
+                &self.db_path,
+                self.identity_mode,
+            )?,
+            direct_messages: self.app_service.export_direct_message_backup().await?,
+        };
+        let keys = self.author_keys.as_ref().clone();
+        // argon2 の鍵導出は数百 ms かかるため async runtime の worker を塞がない。
+        tokio::task::spawn_blocking(move || {
+            seal_identity_backup(
+                &keys,
+                &state,
+                request.passphrase.as_str(),
+                chrono::Utc::now().timestamp_millis(),
+            )
+        })
+        .await
+        .context("identity backup task failed")?
+    }
+
+    /// identity backup を新しい profile へ取り込む。`accept_device_pairing` と同じく runtime 起動前に呼ぶ。
+    ///
+    /// capability は identity storage へ書き戻し、起動時の `restore_private_channel_capability`
+    /// で再水和する。DM state は起動時に store へ反映する。
+    pub fn import_identity_backup(
+        db_path: impl AsRef<Path>,
+        request: ImportIdentityBackupRequest,
+    ) -> Result<IdentityBackupImportView> {
+        Self::import_identity_backup_with_identity(
+            db_path.as_ref(),
+            IdentityStorageMode::from_env(),
+            request,
+        )
+    }
+
+    pub(crate) fn import_identity_backup_with_identity(
+        db_path: &Path,
+        identity_mode: IdentityStorageMode,
+        request: ImportIdentityBackupRequest,
+    ) -> Result<IdentityBackupImportView> {
+        if load_device_id(db_path, identity_mode)?.is_some() {
+            bail!("device id already exists; identity backup restore requires a fresh profile");
+        }
+        let backup: IdentityBackup<IdentityBackupStateV1> =
+            open_identity_backup(request.bundle.as_str(), request.passphrase.as_str())?;
+        let direct_messages = serde_json::to_string(&backup.state.direct_messages)
+            .context("failed to encode restored direct message state")?;
+        install_paired_keys(db_path, identity_mode, &backup.keys)?;
+        persist_private_channel_capabilities(
+            db_path,
+            identity_mode,
+            &backup.state.private_channel_capabilities,
+        )?;
+        persist_optional_secret(
+            db_path,
+            identity_mode,
+            IDENTITY_BACKUP_RESTORE_PURPOSE,
+            IDENTITY_BACKUP_RESTORE_DIRECT_MESSAGES_KEY,
+            direct_messages.as_str(),
+        )?;
+        Ok(IdentityBackupImportView {
+            author_pubkey: backup.keys.public_key().as_str().to_string(),
+            created_at: backup.created_at,
+            private_channel_count: backup.state.private_channel_capabilities.len(),
+            direct_message_count: backup.state.direct_messages.conversations.len()
+                + backup.state.direct_messages.group_conversations.len(),
+        })
+    }
+
+    pub async fn create_device_pairing(
+        &self,
+        request: CreateDevicePairingRequest,
+    ) -> Result<DevicePairingView> {
+        self.app_service
+            .create_device_pairing(request.device_label.as_str())
+            .await
+    }
+
+    pub async fn list_linked_devices(&self) -> Result<Vec<LinkedDeviceView>> {
+        self.app_service.list_linked_devices().await
+    }
+
+    pub async fn sync_linked_devices(&self) -> Result<usize> {
+        let applied = self.app_service.sync_linked_devices().await?;
+        if applied > 0 {
+            // 通知の既読は他端末由来でも変わるため、件数バッジを再取得させる。
+            self.emit_event(RuntimeEvent::NotificationStatusChanged);
+        }
+        Ok(applied)
+    }
+
+    pub async fn start_linked_device_sync(self: &Arc<Self>) {
+        let mut task = self.linked_device_sync_task.lock().await;
+        if task.as_ref().is_some_and(|handle| !handle.is_finished()) {
+            return;
+        }
+
+        let weak: Weak<Self> = Arc::downgrade(self);
+        *task = Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(LINKED_DEVICE_SYNC_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                let Some(runtime) = weak.upgrade() else {
+                    return;
+                };
+                if let Err(error) = runtime.sync_linked_devices().await {
+                    tracing::warn!(error = %error, "failed to sync linked device state");
+                }
+                drop(runtime);
+            }
+        }));
+    }
+}
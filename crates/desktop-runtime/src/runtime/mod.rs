@@ -7,21 +7,29 @@ use anyhow::{Context, Result, anyhow, bail};
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use kukuri_app_api::{
-    AppService, AuthorSocialView, BlobMediaPayload, BookmarkedCustomReactionView,
-    BookmarkedPostView, ChannelAccessTokenExport, ChannelAccessTokenPreview,
+    AppService, AuthorSocialView, BlobGarbageCollectionView, BlobMediaPayload,
+    BookmarkedCustomReactionView, BookmarkedPostView, ChannelAccessTokenExport,
+    ChannelAccessTokenPreview, CommunityModerationPolicyView, ContentEvent,
     CreateCustomReactionAssetInput, CreateGameRoomInput, CreateLiveSessionInput,
-    CreateMetaverseRoomInput, CustomReactionAssetView, DirectMessageConversationView,
-    DirectMessageStatusView, DirectMessageTimelineView, DirectMessageTopicStatusView, GameRoomView,
-    ImportMetaverseRoomAssetInput, JoinedPrivateChannelView, LiveSessionView,
+    CreateMetaverseRoomInput, CustomReactionAssetView, DevicePairingView, DirectMessageBackupV1,
+    DirectMessageConversationView, DirectMessageStatusView, DirectMessageTimelineView,
+    DirectMessageTopicStatusView, GameRoomView, IdentityBackupImportView,
+    ImportMetaverseRoomAssetInput, JoinedPrivateChannelView, LinkedDeviceView, LiveSessionView,
     MetaverseAssetRefView, MetaverseRoomEventView, NotificationStatusView, NotificationView,
     PrivateChannelCapability, ProfileInput, PublishMetaverseRoomEventInput, ReactionStateView,
-    RecentReactionView, ServiceHandles, SyncStatus, TimelineView, UpdateGameRoomInput,
-    UpdateMetaverseRoomInput,
+    RecentReactionView, ServiceHandles, StorageUsageView, SyncStatus, TimelineView,
+    UpdateGameRoomInput, UpdateMetaverseRoomInput,
+};
+use kukuri_blob_service::{
+    BlobDownloadScheduler, BlobDownloadSchedulerConfig, BlobRetentionConfig,
+    BlobRetentionScheduler, BlobService, RetainedBlobService,
 };
 use kukuri_cn_protocol::normalize_http_url;
 use kukuri_core::{
     BlobHash, CreatePrivateChannelInput, CustomReactionAssetSnapshotV1, FriendOnlyGrantPreview,
-    FriendPlusSharePreview, KukuriKeys, PrivateChannelInvitePreview, Profile, TopicId,
+    FriendPlusSharePreview, IdentityBackup, KukuriKeys, PrivateChannelInvitePreview, Profile,
+    TopicId, generate_device_id, open_device_pairing_ticket, open_identity_backup,
+    seal_identity_backup, validate_device_id,
 };
 use kukuri_docs_sync::{DocQuery, DocsSync};
 use kukuri_store::SqliteStore;
@@ -40,8 +48,8 @@ use crate::community_node::{
     CommunityNodeTargetRequest, CommunityNodeTrustRelationError, CommunityNodeUserAdvisoryRequest,
     IndexOperation, IndexQueryResponse, RelationNeighborsResponse, RelationOptoutResponse,
     RelationReadResponse, SetCommunityNodeConfigRequest, SetCommunityNodeInviteCodeRequest,
-    SubmitCommunityNodeReportRequest, SubmitCommunityNodeReportResult,
-    SubmitIndexingRequestResponse, TrustUserReadResponse,
+    SetCommunityNodeModerationPolicyRequest, SubmitCommunityNodeReportRequest,
+    SubmitCommunityNodeReportResult, SubmitIndexingRequestResponse, TrustUserReadResponse,
     community_node_consent_has_pending_update, community_node_seed_peers,
     default_preview_community_node_config, delete_community_node_invite_code,
     effective_seed_peer_apply_state, load_community_node_config_from_file,
@@ -54,14 +62,15 @@ use crate::discovery::{
     resolve_discovery_config_from_env, save_discovery_config,
 };
 use crate::identity::{
-    IdentityStorageMode, delete_optional_secret, load_optional_secret, load_or_create_keys,
-    persist_optional_secret,
+    IdentityStorageMode, delete_optional_secret, install_paired_keys, load_optional_secret,
+    load_or_create_keys, persist_optional_secret,
 };
 use crate::requests::*;
 use crate::stack::SharedIrohStack;
 
 mod community_node_api;
 mod content_profile_api;
+mod devices_api;
 mod notifications_messages_api;
 mod private_channels_game_api;
 mod sync_live_api;
@@ -71,6 +80,13 @@ pub(crate) const PRIVATE_CHANNEL_CAPABILITIES_PURPOSE: &str = "private-channel-c
 pub(crate) const PRIVATE_CHANNEL_CAPABILITIES_KEY: &str = "registry";
 pub(crate) const GOSSIP_SUBSCRIPTION_STATE_PURPOSE: &str = "gossip-subscription-state";
 pub(crate) const GOSSIP_SUBSCRIPTION_STATE_KEY: &str = "registry";
+pub(crate) const DEVICE_IDENTITY_PURPOSE: &str = "device-identity";
+pub(crate) const DEVICE_IDENTITY_KEY: &str = "device-id";
+/// identity backup から取り込み、次回起動時に store へ反映する DM state。
+pub(crate) const IDENTITY_BACKUP_RESTORE_PURPOSE: &str = "identity-backup-restore";
+pub(crate) const IDENTITY_BACKUP_RESTORE_DIRECT_MESSAGES_KEY: &str = "direct-messages";
+/// pin されていない blob の容量上限(MiB)。未設定・不正値なら既定値。
+pub(crate) const BLOB_CACHE_QUOTA_ENV: &str = "KUKURI_BLOB_CACHE_QUOTA_MB";
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
 #[cfg_attr(feature = "ts", derive(ts_rs::TS))]
@@ -82,6 +98,10 @@ pub enum RuntimeEvent {
         sync_status: Option<Box<SyncStatus>>,
         community_node_statuses: Option<Vec<CommunityNodeNodeStatus>>,
     },
+    // 購読経路で反映された投稿・reaction・DM などの変更。UI は該当範囲だけを取り直す。
+    ContentChanged {
+        event: ContentEvent,
+    },
 }
 
 pub struct DesktopRuntime {
@@ -100,6 +120,9 @@ pub struct DesktopRuntime {
     pub(crate) community_node_reconnect_guard: Arc<Mutex<()>>,
     pub(crate) community_node_scheduler_task: Mutex<Option<tokio::task::JoinHandle<()>>>,
     pub(crate) sync_status_observer_task: Mutex<Option<tokio::task::JoinHandle<()>>>,
+    pub(crate) linked_device_sync_task: Mutex<Option<tokio::task::JoinHandle<()>>>,
+    pub(crate) blob_download_task: Mutex<Option<tokio::task::JoinHandle<()>>>,
+    pub(crate) blob_retention_task: Mutex<Option<tokio::task::JoinHandle<()>>>,
     pub(crate) active_connectivity_urls: Arc<Mutex<Vec<String>>>,
     pub(crate) last_runtime_connectivity_assist_state:
         Arc<Mutex<Option<crate::community_node::RuntimeConnectivityAssistState>>>,
@@ -110,6 +133,17 @@ pub struct DesktopRuntime {
     event_sender: tokio::sync::broadcast::Sender<RuntimeEvent>,
 }
 
+fn blob_retention_config_from_env() -> BlobRetentionConfig {
+    let mut config = BlobRetentionConfig::default();
+    if let Some(quota_mb) = std::env::var(BLOB_CACHE_QUOTA_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+    {
+        config.cache_quota_bytes = quota_mb.saturating_mul(1024 * 1024);
+    }
+    config
+}
+
 fn load_private_channel_capabilities(
     db_path: &Path,
     mode: IdentityStorageMode,
@@ -182,6 +216,64 @@ fn persist_gossip_subscription_state(
     )
 }
 
+/// identity backup の取り込みで保留した DM state を反映し、反映後に保留分を消す。
+async fn restore_pending_direct_message_backup(
+    app_service: &AppService,
+    db_path: &Path,
+    mode: IdentityStorageMode,
+) -> Result<()> {
+    let Some(raw) = load_optional_secret(
+        db_path,
+        mode,
+        IDENTITY_BACKUP_RESTORE_PURPOSE,
+        IDENTITY_BACKUP_RESTORE_DIRECT_MESSAGES_KEY,
+    )?
+    else {
+        return Ok(());
+    };
+    let backup: DirectMessageBackupV1 =
+        serde_json::from_str(&raw).context("failed to decode pending direct message backup")?;
+    app_service.restore_direct_message_backup(backup).await?;
+    delete_optional_secret(
+        db_path,
+        mode,
+        IDENTITY_BACKUP_RESTORE_PURPOSE,
+        IDENTITY_BACKUP_RESTORE_DIRECT_MESSAGES_KEY,
+    )
+}
+
+fn load_device_id(db_path: &Path, mode: IdentityStorageMode) -> Result<Option<String>> {
+    let Some(device_id) =
+        load_optional_secret(db_path, mode, DEVICE_IDENTITY_PURPOSE, DEVICE_IDENTITY_KEY)?
+    else {
+        return Ok(None);
+    };
+    let device_id = device_id.trim().to_string();
+    validate_device_id(device_id.as_str()).context("persisted device id is invalid")?;
+    Ok(Some(device_id))
+}
+
+fn persist_device_id(db_path: &Path, mode: IdentityStorageMode, device_id: &str) -> Result<()> {
+    validate_device_id(device_id)?;
+    persist_optional_secret(
+        db_path,
+        mode,
+        DEVICE_IDENTITY_PURPOSE,
+        DEVICE_IDENTITY_KEY,
+        device_id,
+    )
+}
+
+/// db_path ごとの device id。pairing で参加した端末は発行元が割り当てた id を使う。
+fn load_or_create_device_id(db_path: &Path, mode: IdentityStorageMode) -> Result<String> {
+    if let Some(device_id) = load_device_id(db_path, mode)? {
+        return Ok(device_id);
+    }
+    let device_id = generate_device_id();
+    persist_device_id(db_path, mode, device_id.as_str())?;
+    Ok(device_id)
+}
+
 impl DesktopRuntime {
     pub async fn new(db_path: impl AsRef<Path>) -> Result<Self> {
         Self::new_with_config_and_identity_and_discovery(
@@ -264,16 +356,25 @@ impl DesktopRuntime {
         )
         .await?;
         let keys = load_or_create_keys(&db_path, identity_mode)?;
+        let device_id = load_or_create_device_id(&db_path, identity_mode)?;
         let author_keys = Arc::new(keys.clone());
+        let blob_retention = blob_retention_config_from_env();
+        // put / fetch / pin を blob_objects に記録し、retention の判断材料にする。
+        let blob_service: Arc<dyn BlobService> = Arc::new(RetainedBlobService::new(
+            iroh_stack.blob_service.clone(),
+            store.clone(),
+        ));
         let services = ServiceHandles::new(
             store.clone(),
             store.clone(),
             iroh_stack.transport.clone(),
             iroh_stack.transport.clone(),
             iroh_stack.docs_sync.clone(),
-            iroh_stack.blob_service.clone(),
+            blob_service.clone(),
             keys,
-        );
+        )
+        .with_local_device_id(device_id)
+        .with_blob_retention(blob_retention.clone());
         let app_service = AppService::from_handles(services);
         for capability in load_private_channel_capabilities(&db_path, identity_mode)? {
             app_service
@@ -299,6 +400,7 @@ impl DesktopRuntime {
             .await;
         app_service.warm_social_graph().await?;
         app_service.resume_direct_message_state().await?;
+        restore_pending_direct_message_backup(&app_service, &db_path, identity_mode).await?;
 
         let (event_sender, _) = tokio::sync::broadcast::channel(64);
         {
@@ -311,6 +413,31 @@ impl DesktopRuntime {
                 }
             });
         }
+        {
+            let mut content_events = app_service.subscribe_content_events();
+            let sender = event_sender.clone();
+            tokio::spawn(async move {
+                loop {
+                    match content_events.recv().await {
+                        Ok(event) => {
+                            let _ = sender.send(RuntimeEvent::ContentChanged { event });
+                        }
+                        // 取りこぼし分は UI 側の定期 refresh で追いつく。
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+        }
+        // hydration が積んだ未取得 blob を裏で取りに行く。前回途中だった job もここで再開する。
+        let blob_download_task = BlobDownloadScheduler::new(
+            store.clone(),
+            blob_service.clone(),
+            BlobDownloadSchedulerConfig::default(),
+        )
+        .spawn();
+        let blob_retention_task =
+            BlobRetentionScheduler::new(store.clone(), blob_service, blob_retention).spawn();
 
         Ok(Self {
             app_service,
@@ -330,6 +457,9 @@ impl DesktopRuntime {
             community_node_reconnect_guard: Arc::new(Mutex::new(())),
             community_node_scheduler_task: Mutex::new(None),
             sync_status_observer_task: Mutex::new(None),
+            linked_device_sync_task: Mutex::new(None),
+            blob_download_task: Mutex::new(Some(blob_download_task)),
+            blob_retention_task: Mutex::new(Some(blob_retention_task)),
             active_connectivity_urls: Arc::new(Mutex::new(relay_config.iroh_relay_urls.clone())),
             last_runtime_connectivity_assist_state: Arc::new(Mutex::new(Some(
                 initial_runtime_connectivity_state,
@@ -348,7 +478,7 @@ impl DesktopRuntime {
         let discovery_config = resolve_discovery_config_from_env(&db_path)?;
         let dht_options = match discovery_config.mode {
             DiscoveryMode::SeededDht => DhtDiscoveryOptions::seeded_dht(),
-            DiscoveryMode::StaticPeer => DhtDiscoveryOptions::disabled(),
+            DiscoveryMode::StaticPeer | DiscoveryMode::Lan => DhtDiscoveryOptions::disabled(),
         };
         Self::new_with_config_and_identity_and_discovery(
             &db_path,
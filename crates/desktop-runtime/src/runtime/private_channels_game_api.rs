@@ -276,6 +276,16 @@ impl DesktopRuntime {
             .await
     }
 
+    /// topic の projection を replica から全件作り直す(通常の hydration は差分だけ)。
+    pub async fn rebuild_topic_projections(
+        &self,
+        request: RebuildTopicProjectionsRequest,
+    ) -> Result<usize> {
+        self.app_service
+            .rebuild_topic_projections(request.topic.as_str())
+            .await
+    }
+
     pub async fn set_topic_gossip_enabled(
         &self,
         request: SetTopicGossipEnabledRequest,
@@ -7,6 +7,15 @@ impl DesktopRuntime {
         self.app_service.get_sync_status().await
     }
 
+    pub async fn get_storage_usage(&self) -> Result<StorageUsageView> {
+        self.app_service.get_storage_usage().await
+    }
+
+    /// 定期 GC(`BlobRetentionScheduler`)を待たずに cache を整理する。
+    pub async fn collect_blob_garbage(&self) -> Result<BlobGarbageCollectionView> {
+        self.app_service.collect_blob_garbage().await
+    }
+
     pub async fn has_topic_timeline_doc_index_entry(
         &self,
         topic: &str,
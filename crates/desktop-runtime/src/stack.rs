@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -6,16 +7,19 @@ use async_trait::async_trait;
 use kukuri_blob_service::{BlobService, BlobStatus, IrohBlobService, StoredBlob};
 use kukuri_core::{BlobHash, GossipHint, ReplicaId, TopicId};
 use kukuri_docs_sync::{
-    DocEventStream, DocFetchPolicy, DocOp, DocQuery, DocRecord, DocsSync, IrohDocsSync,
+    DocEventStream, DocFetchPolicy, DocOp, DocQuery, DocRecord, DocRecordsSince, DocsSync,
+    IrohDocsSync,
 };
 use kukuri_iroh_node::IrohDocsNode;
 use kukuri_transport::{
     ConnectMode, DhtDiscoveryOptions, DiscoveryMode, DiscoverySnapshot, HintStream, HintTransport,
-    IrohGossipTransport, PeerSnapshot, SeedPeer, Transport, TransportNetworkConfig,
-    TransportRelayConfig,
+    IrohGossipTransport, LanDiscovery, LanPeerEvent, PeerSnapshot, SeedPeer, Transport,
+    TransportNetworkConfig, TransportRelayConfig,
 };
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::{Mutex, RwLock};
-use tracing::info;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
 
 use crate::discovery::{DiscoveryConfig, normalize_seed_peers};
 
@@ -24,6 +28,79 @@ pub(crate) struct BoundIrohStack {
     pub(crate) transport: Arc<IrohGossipTransport>,
     pub(crate) docs_sync: Arc<IrohDocsSync>,
     pub(crate) blob_service: Arc<IrohBlobService>,
+    lan: Option<LanPeerForwarder>,
+}
+
+/// `DiscoveryMode::Lan` で mDNS が見つけたピアを transport / docs-sync / blob-service の
+/// 台帳へ配る。stack の作り直しごとに新しい endpoint で張り直す。
+struct LanPeerForwarder {
+    discovery: LanDiscovery,
+    task: JoinHandle<()>,
+}
+
+impl LanPeerForwarder {
+    async fn spawn(
+        node: &IrohDocsNode,
+        transport: Arc<IrohGossipTransport>,
+        docs_sync: Arc<IrohDocsSync>,
+        blob_service: Arc<IrohBlobService>,
+    ) -> Result<Self> {
+        let discovery = LanDiscovery::spawn(node.endpoint()).await?;
+        let mut events = discovery.subscribe();
+        let known = discovery.clone();
+        let task = tokio::spawn(async move {
+            for endpoint_addr in known.peers() {
+                forward_lan_peer_event(
+                    LanPeerEvent::Discovered(endpoint_addr),
+                    &transport,
+                    &docs_sync,
+                    &blob_service,
+                )
+                .await;
+            }
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        forward_lan_peer_event(event, &transport, &docs_sync, &blob_service).await
+                    }
+                    // 取りこぼしたら現在の発見済みピアを配り直す(台帳側は冪等)。
+                    Err(RecvError::Lagged(_)) => {
+                        for endpoint_addr in known.peers() {
+                            forward_lan_peer_event(
+                                LanPeerEvent::Discovered(endpoint_addr),
+                                &transport,
+                                &docs_sync,
+                                &blob_service,
+                            )
+                            .await;
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+        Ok(Self { discovery, task })
+    }
+
+    fn shutdown(&self) {
+        self.task.abort();
+        self.discovery.shutdown();
+    }
+}
+
+async fn forward_lan_peer_event(
+    event: LanPeerEvent,
+    transport: &IrohGossipTransport,
+    docs_sync: &IrohDocsSync,
+    blob_service: &IrohBlobService,
+) {
+    transport.apply_lan_peer_event(&event).await;
+    if let LanPeerEvent::Discovered(endpoint_addr) = event {
+        if let Err(error) = docs_sync.learn_peer_addr(endpoint_addr.clone()).await {
+            warn!(error = %error, "failed to apply lan peer to docs sync");
+        }
+        blob_service.learn_peer_addr(endpoint_addr).await;
+    }
 }
 
 /// ホットスワップ可能なサービスラッパーを 1 つ生成する。
@@ -126,6 +203,12 @@ reloadable_service! {
             query: DocQuery,
             policy: DocFetchPolicy,
         ) -> Result<Vec<DocRecord>>;
+        async fn query_replica_since(
+            replica_id: &ReplicaId,
+            query: DocQuery,
+            policy: DocFetchPolicy,
+            watermarks: &BTreeMap<String, u64>,
+        ) -> Result<DocRecordsSince>;
         async fn subscribe_replica(replica_id: &ReplicaId) -> Result<DocEventStream>;
         async fn import_peer_ticket(ticket: &str) -> Result<()>;
         async fn learn_peer(endpoint_id: &str) -> Result<()>;
@@ -143,6 +226,8 @@ reloadable_service! {
         async fn put_blob(data: Vec<u8>, mime: &str) -> Result<StoredBlob>;
         async fn fetch_blob(hash: &BlobHash) -> Result<Option<Vec<u8>>>;
         async fn pin_blob(hash: &BlobHash) -> Result<()>;
+        async fn unpin_blob(hash: &BlobHash) -> Result<()>;
+        async fn evict_blob(hash: &BlobHash) -> Result<bool>;
         async fn blob_status(hash: &BlobHash) -> Result<BlobStatus>;
         async fn import_peer_ticket(ticket: &str) -> Result<()>;
         async fn learn_peer(endpoint_id: &str) -> Result<()>;
@@ -409,15 +494,38 @@ impl BoundIrohStack {
             .set_seed_peers(effective_seed_peers.clone())
             .await?;
         blob_service.set_seed_peers(effective_seed_peers).await?;
+        let lan = if discovery_config.mode == DiscoveryMode::Lan {
+            // mDNS が使えない環境でも static peer だけで起動は続ける。
+            match LanPeerForwarder::spawn(
+                &node,
+                Arc::clone(&transport),
+                Arc::clone(&docs_sync),
+                Arc::clone(&blob_service),
+            )
+            .await
+            {
+                Ok(lan) => Some(lan),
+                Err(error) => {
+                    warn!(error = %error, "failed to start lan discovery");
+                    None
+                }
+            }
+        } else {
+            None
+        };
         Ok(Self {
             node,
             transport,
             docs_sync,
             blob_service,
+            lan,
         })
     }
 
     pub(crate) async fn shutdown(&self) {
+        if let Some(lan) = &self.lan {
+            lan.shutdown();
+        }
         self.transport.shutdown().await;
         self.docs_sync.shutdown().await;
         let _ = self.node.clone().shutdown().await;
@@ -57,6 +57,8 @@ async fn admission_verify(
         token_type: "Bearer".into(),
         expires_at: Utc::now().timestamp() + 3600,
         pubkey: "a".repeat(64),
+        refresh_token: None,
+        refresh_expires_at: None,
     })
     .into_response()
 }
@@ -308,6 +310,11 @@ async fn auth_verify_preserves_all_stable_admission_rejection_codes() {
             CommunityNodeAdmissionRejectionCode::NotAllowlisted,
         ),
         ("BANNED", CommunityNodeAdmissionRejectionCode::Banned),
+        ("SUSPENDED", CommunityNodeAdmissionRejectionCode::Suspended),
+        (
+            "VOUCH_REQUIRED",
+            CommunityNodeAdmissionRejectionCode::VouchRequired,
+        ),
     ];
 
     for (wire_code, expected) in cases {
@@ -2,8 +2,10 @@ use super::super::*;
 use axum::extract::Query;
 use axum::http::{Uri, header::RETRY_AFTER};
 use kukuri_cn_protocol::{
-    ApiErrorBody, IndexEntryView, IndexQueryParams, IndexQueryResponse, IndexScopeKind,
-    IndexingRequestStatus, SubmitIndexingRequestRequest, SubmitIndexingRequestResponse,
+    ApiErrorBody, IndexEntryKind, IndexEntryView, IndexProfileView, IndexQueryParams,
+    IndexQueryResponse, IndexScopeKind, IndexSessionKind, IndexSessionStatus, IndexSessionView,
+    IndexSortOrder, IndexingRequestStatus, SubmitIndexingRequestRequest,
+    SubmitIndexingRequestResponse,
 };
 
 type ForcedIndexError = (StatusCode, ApiErrorBody, Option<&'static str>);
@@ -126,15 +128,61 @@ async fn mock_index_query(
         }
         return response;
     }
-    Json(IndexQueryResponse {
-        entries: vec![IndexEntryView {
+    let author_pubkey = state.response_author_pubkey.lock().await.clone();
+    let entry = match path.as_str() {
+        "/v1/index/people" => IndexEntryView {
+            scope_kind: IndexScopeKind::PublicTopic,
+            scope_id: "rust".to_string(),
+            object_id: format!("profile:{author_pubkey}"),
+            author_pubkey,
+            text: "alice\nrust notes".to_string(),
+            created_at: 42,
+            reasons: Vec::new(),
+            entry_kind: IndexEntryKind::Profile,
+            profile: Some(IndexProfileView {
+                name: Some("alice".to_string()),
+                display_name: None,
+                about: Some("rust notes".to_string()),
+            }),
+            session: None,
+        },
+        "/v1/index/live" => IndexEntryView {
+            scope_kind: IndexScopeKind::PublicTopic,
+            scope_id: "rust".to_string(),
+            object_id: "room:room-1".to_string(),
+            author_pubkey,
+            text: "rust lobby".to_string(),
+            created_at: 42,
+            reasons: Vec::new(),
+            entry_kind: IndexEntryKind::Session,
+            profile: None,
+            session: Some(IndexSessionView {
+                session_kind: IndexSessionKind::GameRoom,
+                session_id: "room-1".to_string(),
+                topic_id: "rust".to_string(),
+                status: IndexSessionStatus::Running,
+                title: "rust lobby".to_string(),
+                description: None,
+                participant_count: Some(2),
+            }),
+        },
+        _ => IndexEntryView {
             scope_kind: IndexScopeKind::PublicTopic,
             scope_id: "rust".to_string(),
             object_id: state.response_object_id.lock().await.clone(),
-            author_pubkey: state.response_author_pubkey.lock().await.clone(),
+            author_pubkey,
             text: "hello\nderived-tag".to_string(),
             created_at: 42,
-        }],
+            reasons: Vec::new(),
+            entry_kind: IndexEntryKind::Post,
+            profile: None,
+            session: None,
+        },
+    };
+    Json(IndexQueryResponse {
+        entries: vec![entry],
+        // 先頭 page だけ続きがある体で cursor を返す(無限スクロールの往復確認用)。
+        next_cursor: params.cursor.is_none().then(|| "cursor-page-2".to_string()),
     })
     .into_response()
 }
@@ -199,6 +247,8 @@ async fn index_runtime(
         .route("/v1/index/search", get(mock_index_query))
         .route("/v1/index/discovery", get(mock_index_query))
         .route("/v1/index/recommendations", get(mock_index_query))
+        .route("/v1/index/people", get(mock_index_query))
+        .route("/v1/index/live", get(mock_index_query))
         .route("/v1/indexing/requests", post(mock_indexing_request))
         .route(
             "/v1/rendezvous/topics/heartbeat",
@@ -217,6 +267,8 @@ async fn index_runtime(
         &StoredCommunityNodeToken {
             access_token: "index-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist token");
@@ -241,6 +293,7 @@ fn scoped_request(base_url: &str) -> CommunityNodeIndexQueryRequest {
         scope_id: Some("rust".to_string()),
         topic_id: None,
         limit: Some(10),
+        ..CommunityNodeIndexQueryRequest::default()
     }
 }
 
@@ -281,6 +334,218 @@ async fn community_node_index_client_uses_session_and_preserves_query_contract()
     server.abort();
 }
 
+#[tokio::test]
+async fn community_node_people_search_returns_profiles_and_observes_only_the_author() {
+    use kukuri_store::ContentObservationStore;
+
+    let _resource = lock_test_resource(TestResource::CommunityNodeServer).await;
+    let (runtime, base_url, _managed, state, server, _dir) = index_runtime(None).await;
+    runtime
+        .set_my_profile(SetMyProfileRequest {
+            name: Some("alice".to_string()),
+            display_name: None,
+            about: Some("rust notes".to_string()),
+            picture: None,
+            picture_upload: None,
+            clear_picture: false,
+        })
+        .await
+        .expect("set profile");
+    let author_pubkey = runtime.author_keys.public_key_hex();
+    *state.response_author_pubkey.lock().await = author_pubkey.clone();
+    let people_request = CommunityNodeIndexQueryRequest {
+        query: Some("alice".to_string()),
+        scope_kind: None,
+        scope_id: None,
+        ..scoped_request(base_url.as_str())
+    };
+
+    let people = runtime
+        .search_community_node_people(people_request.clone())
+        .await
+        .expect("people search");
+    assert_eq!(people.entries[0].entry_kind, IndexEntryKind::Profile);
+    assert_eq!(
+        people.entries[0]
+            .profile
+            .as_ref()
+            .and_then(|profile| profile.name.as_deref()),
+        Some("alice")
+    );
+    let requests = state.requests.lock().await.clone();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].0, "/v1/index/people");
+    assert_eq!(requests[0].1.q.as_deref(), Some("alice"));
+    // profile entry の object_id は post ではないため、作者の profile だけを観測する。
+    assert!(
+        runtime
+            .store
+            .list_content_observations("post", people.entries[0].object_id.as_str())
+            .await
+            .expect("list post observations")
+            .is_empty()
+    );
+    assert_eq!(
+        runtime
+            .store
+            .list_content_observations("profile", author_pubkey.as_str())
+            .await
+            .expect("list profile observations")
+            .len(),
+        1
+    );
+
+    // 検索語の無い要求と private channel 指定は HTTP 前に拒否する。
+    for invalid in [
+        CommunityNodeIndexQueryRequest {
+            query: Some("  ".to_string()),
+            ..people_request.clone()
+        },
+        CommunityNodeIndexQueryRequest {
+            scope_kind: Some(IndexScopeKind::PrivateChannel),
+            scope_id: Some("channel-1".to_string()),
+            topic_id: Some("kukuri:topic:rust".to_string()),
+            ..people_request.clone()
+        },
+    ] {
+        let error = runtime
+            .search_community_node_people(invalid)
+            .await
+            .expect_err("invalid people query");
+        assert_eq!(error.code, "INVALID_INDEX_QUERY");
+    }
+    assert_eq!(state.requests.lock().await.len(), 1);
+    runtime.shutdown().await;
+    server.abort();
+}
+
+#[tokio::test]
+async fn community_node_live_now_lists_sessions_without_a_query() {
+    use kukuri_store::ContentObservationStore;
+
+    let _resource = lock_test_resource(TestResource::CommunityNodeServer).await;
+    let (runtime, base_url, _managed, state, server, _dir) = index_runtime(None).await;
+    runtime
+        .set_my_profile(SetMyProfileRequest {
+            name: Some("alice".to_string()),
+            display_name: None,
+            about: None,
+            picture: None,
+            picture_upload: None,
+            clear_picture: false,
+        })
+        .await
+        .expect("set profile");
+    let author_pubkey = runtime.author_keys.public_key_hex();
+    *state.response_author_pubkey.lock().await = author_pubkey.clone();
+
+    // live now は検索語なしで列挙できる。
+    let live = runtime
+        .list_community_node_live_now(CommunityNodeIndexQueryRequest {
+            query: None,
+            ..scoped_request(base_url.as_str())
+        })
+        .await
+        .expect("live now");
+    let entry = &live.entries[0];
+    assert_eq!(entry.entry_kind, IndexEntryKind::Session);
+    let session = entry.session.as_ref().expect("session view");
+    assert_eq!(session.session_kind, IndexSessionKind::GameRoom);
+    assert_eq!(session.status, IndexSessionStatus::Running);
+    assert_eq!(session.participant_count, Some(2));
+    let requests = state.requests.lock().await.clone();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].0, "/v1/index/live");
+    assert_eq!(requests[0].1.q, None);
+    // session は通報 subject ではないため、開催者の profile だけを観測する。
+    assert!(
+        runtime
+            .store
+            .list_content_observations("post", entry.object_id.as_str())
+            .await
+            .expect("list post observations")
+            .is_empty()
+    );
+    assert_eq!(
+        runtime
+            .store
+            .list_content_observations("profile", author_pubkey.as_str())
+            .await
+            .expect("list profile observations")
+            .len(),
+        1
+    );
+    runtime.shutdown().await;
+    server.abort();
+}
+
+#[tokio::test]
+async fn community_node_index_client_pages_with_cursor_and_filters() {
+    let _resource = lock_test_resource(TestResource::CommunityNodeServer).await;
+    let (runtime, base_url, _managed, state, server, _dir) = index_runtime(None).await;
+    let filtered = CommunityNodeIndexQueryRequest {
+        query: None,
+        author_pubkey: Some("author".to_string()),
+        since: Some(100),
+        until: Some(200),
+        order: Some(IndexSortOrder::Oldest),
+        ..scoped_request(base_url.as_str())
+    };
+
+    let first = runtime
+        .discover_community_node_index(filtered.clone())
+        .await
+        .expect("first page");
+    let next_cursor = first.next_cursor.expect("first page has a continuation");
+    let second = runtime
+        .discover_community_node_index(CommunityNodeIndexQueryRequest {
+            cursor: Some(next_cursor.clone()),
+            ..filtered
+        })
+        .await
+        .expect("second page");
+    assert_eq!(second.next_cursor, None);
+
+    let requests = state.requests.lock().await.clone();
+    assert_eq!(requests.len(), 2);
+    for (_, params) in &requests {
+        assert_eq!(params.author_pubkey.as_deref(), Some("author"));
+        assert_eq!(params.since, Some(100));
+        assert_eq!(params.until, Some(200));
+        assert_eq!(params.order.as_deref(), Some("oldest"));
+    }
+    assert_eq!(requests[0].1.cursor, None);
+    assert_eq!(requests[1].1.cursor.as_deref(), Some(next_cursor.as_str()));
+
+    // recommendation の順位には並び順を指定できず、逆転した期間とともに HTTP 前に拒否する。
+    for invalid in [
+        CommunityNodeIndexQueryRequest {
+            query: None,
+            scope_kind: None,
+            scope_id: None,
+            order: Some(IndexSortOrder::Newest),
+            ..scoped_request(base_url.as_str())
+        },
+        CommunityNodeIndexQueryRequest {
+            query: None,
+            scope_kind: None,
+            scope_id: None,
+            since: Some(200),
+            until: Some(100),
+            ..scoped_request(base_url.as_str())
+        },
+    ] {
+        let error = runtime
+            .recommend_community_node_index(invalid)
+            .await
+            .expect_err("invalid recommendation query");
+        assert_eq!(error.code, "INVALID_INDEX_QUERY");
+    }
+    assert_eq!(state.requests.lock().await.len(), 2);
+    runtime.shutdown().await;
+    server.abort();
+}
+
 #[tokio::test]
 async fn community_node_index_records_and_restores_existing_local_subjects() {
     use kukuri_store::ContentObservationStore;
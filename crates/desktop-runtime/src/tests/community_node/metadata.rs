@@ -46,6 +46,8 @@ async fn community_node_status_refresh_updates_bootstrap_seed_peers() {
         &StoredCommunityNodeToken {
             access_token: "fake-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist community-node token");
@@ -136,6 +138,8 @@ async fn community_node_session_maintenance_updates_bootstrap_seed_peers() {
         &StoredCommunityNodeToken {
             access_token: "fake-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist community-node token");
@@ -221,6 +225,8 @@ async fn community_node_metadata_refresh_heartbeats_before_bootstrap_sync_even_w
         &StoredCommunityNodeToken {
             access_token: "fake-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist community-node token");
@@ -352,6 +358,8 @@ async fn community_node_ready_transition_refreshes_bootstrap_metadata_before_nex
         &StoredCommunityNodeToken {
             access_token: "fake-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist community-node token");
@@ -460,6 +468,8 @@ async fn community_node_ready_transition_refreshes_bootstrap_metadata_only_once_
         &StoredCommunityNodeToken {
             access_token: "fake-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist community-node token");
@@ -535,6 +545,8 @@ async fn community_node_status_retries_bootstrap_metadata_when_seed_peers_are_em
         &StoredCommunityNodeToken {
             access_token: "fake-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist community-node token");
@@ -651,6 +663,8 @@ async fn refresh_community_node_metadata_refreshes_registration_before_bootstrap
         &StoredCommunityNodeToken {
             access_token: "fake-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist community-node token");
@@ -748,6 +762,8 @@ async fn refresh_community_node_metadata_requeues_heartbeat_when_runtime_connect
         &StoredCommunityNodeToken {
             access_token: "fake-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist community-node token");
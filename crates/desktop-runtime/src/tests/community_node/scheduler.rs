@@ -70,6 +70,8 @@ async fn session_scheduler_keeps_bootstrap_registration_alive_without_getter_pol
         &StoredCommunityNodeToken {
             access_token: "fake-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist community-node token");
@@ -191,6 +193,8 @@ async fn get_sync_status_is_read_only_for_community_node_session() {
         &StoredCommunityNodeToken {
             access_token: "fake-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist community-node token");
@@ -277,6 +281,8 @@ async fn session_scheduler_reauthenticates_near_expiry_token_without_getter_poll
         &StoredCommunityNodeToken {
             access_token: "near-expiry-token".into(),
             expires_at: Utc::now().timestamp() + 60,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist near-expiry token");
@@ -384,6 +390,8 @@ async fn topic_rendezvous_refresh_fires_between_bootstrap_heartbeats() {
         &StoredCommunityNodeToken {
             access_token: "fake-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist community-node token");
@@ -528,6 +536,8 @@ async fn private_channel_rendezvous_refresh_uses_only_the_current_epoch_secret()
         &StoredCommunityNodeToken {
             access_token: "fake-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("コミュニティノードの認証情報を保存できる");
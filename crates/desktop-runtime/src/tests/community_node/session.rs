@@ -251,6 +251,8 @@ async fn near_expiry_token_triggers_proactive_community_node_reauthentication()
         &StoredCommunityNodeToken {
             access_token: "near-expiry-token".into(),
             expires_at: Utc::now().timestamp() + 60,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist near-expiry token");
@@ -348,6 +350,8 @@ async fn consent_required_node_without_auto_approve_stays_pending() {
         &StoredCommunityNodeToken {
             access_token: "manual-consent-token".into(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist token");
@@ -424,6 +428,8 @@ async fn community_node_status_does_not_require_restart_when_connectivity_is_act
         &StoredCommunityNodeToken {
             access_token: "fake-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist community-node token");
@@ -530,6 +536,8 @@ async fn auto_approve_node_does_not_silently_reaccept_policy_update() {
         &StoredCommunityNodeToken {
             access_token: "update-pending-token".into(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist token");
@@ -586,3 +594,152 @@ async fn auto_approve_node_does_not_silently_reaccept_policy_update() {
     runtime.shutdown().await;
     server.abort();
 }
+
+async fn mock_refresh_token(
+    State(state): State<Arc<MockManagedCommunityNodeState>>,
+    Json(request): Json<kukuri_cn_protocol::AuthRefreshRequest>,
+) -> std::result::Result<Json<kukuri_cn_protocol::AuthVerifyResponse>, StatusCode> {
+    if request.refresh_token == "unavailable-refresh-token" {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    if request.refresh_token != "stored-refresh-token" || request.endpoint_id.is_none() {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    *state.current_token.lock().await = "refreshed-token".into();
+    Ok(Json(kukuri_cn_protocol::AuthVerifyResponse {
+        access_token: "refreshed-token".into(),
+        token_type: "Bearer".into(),
+        expires_at: Utc::now().timestamp() + 3600,
+        pubkey: "f".repeat(64),
+        refresh_token: Some("rotated-refresh-token".into()),
+        refresh_expires_at: Some(Utc::now().timestamp() + 86_400),
+    }))
+}
+
+async fn run_near_expiry_session_with_refresh_token(
+    refresh_token: &str,
+) -> (Arc<MockManagedCommunityNodeState>, StoredCommunityNodeToken) {
+    let dir = tempdir().expect("tempdir");
+    let db_path = dir.path().join("community-silent-refresh.db");
+    let runtime = DesktopRuntime::new_with_config_and_identity(
+        &db_path,
+        TransportNetworkConfig::loopback(),
+        IdentityStorageMode::FileOnly,
+    )
+    .await
+    .expect("runtime");
+
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind listener");
+    let base_url = format!("http://{}", listener.local_addr().expect("local addr"));
+    let state = Arc::new(MockManagedCommunityNodeState {
+        base_url: base_url.clone(),
+        seed_peers: vec![],
+        consent_accepted: Arc::new(AtomicBool::new(true)),
+        current_token: Arc::new(Mutex::new("near-expiry-token".into())),
+        challenge_hits: Arc::new(AtomicUsize::new(0)),
+        verify_hits: Arc::new(AtomicUsize::new(0)),
+        consent_status_hits: Arc::new(AtomicUsize::new(0)),
+        consent_accept_hits: Arc::new(AtomicUsize::new(0)),
+        heartbeat_hits: Arc::new(AtomicUsize::new(0)),
+        bootstrap_hits: Arc::new(AtomicUsize::new(0)),
+        simulate_pending_update: Arc::new(AtomicBool::new(false)),
+    });
+    let app = Router::new()
+        .route("/v1/auth/challenge", post(mock_managed_auth_challenge))
+        .route("/v1/auth/verify", post(mock_managed_auth_verify))
+        .route("/v1/auth/refresh", post(mock_refresh_token))
+        .route("/v1/consents/status", get(mock_managed_consent_status))
+        .route("/v1/consents", post(mock_managed_accept_consents))
+        .route(
+            "/v1/bootstrap/heartbeat",
+            post(mock_managed_bootstrap_heartbeat),
+        )
+        .route("/v1/bootstrap/nodes", get(mock_managed_bootstrap_nodes))
+        .with_state(state.clone());
+    let server = tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+
+    persist_community_node_token(
+        &db_path,
+        IdentityStorageMode::FileOnly,
+        base_url.as_str(),
+        &StoredCommunityNodeToken {
+            access_token: "near-expiry-token".into(),
+            expires_at: Utc::now().timestamp() + 60,
+            refresh_token: Some(refresh_token.into()),
+            refresh_expires_at: Some(Utc::now().timestamp() + 86_400),
+        },
+    )
+    .expect("persist near-expiry token");
+    *runtime.community_node_config.lock().await = CommunityNodeConfig {
+        nodes: vec![CommunityNodeNodeConfig {
+            base_url: base_url.clone(),
+            auto_approve: false,
+            resolved_urls: Some(
+                CommunityNodeResolvedUrls::new(base_url.clone(), Vec::new(), Vec::new())
+                    .expect("resolved urls"),
+            ),
+        }],
+    };
+
+    runtime.run_community_node_session_maintenance_once().await;
+    let statuses = runtime
+        .get_community_node_statuses()
+        .await
+        .expect("community node statuses");
+    assert_eq!(
+        statuses[0].session_phase,
+        crate::CommunityNodeSessionPhase::Ready
+    );
+    let stored = crate::community_node::load_community_node_token(
+        &db_path,
+        IdentityStorageMode::FileOnly,
+        base_url.as_str(),
+    )
+    .expect("load token")
+    .expect("stored token");
+
+    runtime.shutdown().await;
+    server.abort();
+    (state, stored)
+}
+
+#[tokio::test]
+async fn near_expiry_token_is_refreshed_silently_without_challenge() {
+    let _resource = lock_test_resource(TestResource::CommunityNodeServer).await;
+    let (state, stored) = run_near_expiry_session_with_refresh_token("stored-refresh-token").await;
+
+    assert_eq!(state.challenge_hits.load(Ordering::SeqCst), 0);
+    assert_eq!(state.verify_hits.load(Ordering::SeqCst), 0);
+    assert_eq!(state.heartbeat_hits.load(Ordering::SeqCst), 1);
+    assert_eq!(stored.access_token, "refreshed-token");
+    assert_eq!(
+        stored.refresh_token.as_deref(),
+        Some("rotated-refresh-token")
+    );
+}
+
+#[tokio::test]
+async fn rejected_refresh_token_falls_back_to_challenge() {
+    let _resource = lock_test_resource(TestResource::CommunityNodeServer).await;
+    let (state, stored) = run_near_expiry_session_with_refresh_token("revoked-refresh-token").await;
+
+    assert_eq!(state.challenge_hits.load(Ordering::SeqCst), 1);
+    assert_eq!(state.verify_hits.load(Ordering::SeqCst), 1);
+    assert_eq!(stored.access_token, "managed-token-1");
+    assert_eq!(stored.refresh_token, None);
+}
+
+#[tokio::test]
+async fn unavailable_refresh_endpoint_falls_back_to_challenge() {
+    let _resource = lock_test_resource(TestResource::CommunityNodeServer).await;
+    let (state, stored) =
+        run_near_expiry_session_with_refresh_token("unavailable-refresh-token").await;
+
+    assert_eq!(state.challenge_hits.load(Ordering::SeqCst), 1);
+    assert_eq!(state.verify_hits.load(Ordering::SeqCst), 1);
+    assert_eq!(stored.access_token, "managed-token-1");
+}
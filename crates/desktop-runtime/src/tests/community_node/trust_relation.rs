@@ -238,6 +238,8 @@ async fn trust_relation_runtime(
         &StoredCommunityNodeToken {
             access_token: "trust-token".to_string(),
             expires_at: Utc::now().timestamp() + 3600,
+            refresh_token: None,
+            refresh_expires_at: None,
         },
     )
     .expect("persist token");
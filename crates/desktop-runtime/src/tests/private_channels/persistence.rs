@@ -184,3 +184,100 @@ async fn owner_access_token_export_auto_rotate_survives_restart() {
         .await
         .expect("restarted runtime shutdown timeout");
 }
+
+/// identity backup を新しい profile へ取り込むと、同じ author 鍵と private channel capability が
+/// 起動時の restore で戻る。既存 profile への取り込みと誤った passphrase は拒否する。
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn identity_backup_restores_author_and_private_channels_on_fresh_profile() {
+    let _resource = lock_test_resource(TestResource::IrohNetwork).await;
+    let dir = tempdir().expect("tempdir");
+    let db = dir.path().join("identity-backup-source.db");
+    let runtime = DesktopRuntime::new_with_config_and_identity(
+        &db,
+        TransportNetworkConfig::loopback(),
+        IdentityStorageMode::FileOnly,
+    )
+    .await
+    .expect("runtime");
+    let topic = "kukuri:topic:desktop-identity-backup";
+    let channel = runtime
+        .create_private_channel(CreatePrivateChannelRequest {
+            topic: topic.into(),
+            label: "identity-backup".into(),
+            audience_kind: ChannelAudienceKind::InviteOnly,
+        })
+        .await
+        .expect("create private channel");
+    let author_pubkey = runtime.get_my_profile().await.expect("my profile").pubkey;
+    let passphrase = "backup passphrase for tests";
+    let bundle = runtime
+        .export_identity_backup(ExportIdentityBackupRequest {
+            passphrase: passphrase.into(),
+        })
+        .await
+        .expect("export identity backup");
+    timeout(runtime_shutdown_timeout(), runtime.shutdown())
+        .await
+        .expect("runtime shutdown timeout");
+    drop(runtime);
+
+    let error = DesktopRuntime::import_identity_backup_with_identity(
+        &db,
+        IdentityStorageMode::FileOnly,
+        ImportIdentityBackupRequest {
+            bundle: bundle.clone(),
+            passphrase: passphrase.into(),
+        },
+    )
+    .expect_err("existing profile must not be overwritten");
+    assert!(error.to_string().contains("fresh profile"));
+
+    let restored_db = dir.path().join("identity-backup-restored.db");
+    assert!(
+        DesktopRuntime::import_identity_backup_with_identity(
+            &restored_db,
+            IdentityStorageMode::FileOnly,
+            ImportIdentityBackupRequest {
+                bundle: bundle.clone(),
+                passphrase: "wrong passphrase value".into(),
+            },
+        )
+        .is_err()
+    );
+    let imported = DesktopRuntime::import_identity_backup_with_identity(
+        &restored_db,
+        IdentityStorageMode::FileOnly,
+        ImportIdentityBackupRequest {
+            bundle,
+            passphrase: passphrase.into(),
+        },
+    )
+    .expect("import identity backup");
+    assert_eq!(imported.author_pubkey, author_pubkey.as_str());
+    assert_eq!(imported.private_channel_count, 1);
+
+    let restored = DesktopRuntime::new_with_config_and_identity(
+        &restored_db,
+        TransportNetworkConfig::loopback(),
+        IdentityStorageMode::FileOnly,
+    )
+    .await
+    .expect("restored runtime");
+    assert_eq!(
+        restored.get_my_profile().await.expect("my profile").pubkey,
+        author_pubkey
+    );
+    let joined = restored
+        .list_joined_private_channels(ListJoinedPrivateChannelsRequest {
+            topic: topic.into(),
+        })
+        .await
+        .expect("list joined after restore");
+    assert_eq!(joined.len(), 1);
+    assert_eq!(joined[0].channel_id, channel.channel_id);
+    assert_eq!(joined[0].current_epoch_id, channel.current_epoch_id);
+
+    timeout(runtime_shutdown_timeout(), restored.shutdown())
+        .await
+        .expect("restored runtime shutdown timeout");
+}
@@ -271,6 +271,8 @@ pub(crate) async fn mock_managed_auth_verify(
         token_type: "Bearer".into(),
         expires_at: Utc::now().timestamp() + 3600,
         pubkey: "f".repeat(64),
+        refresh_token: None,
+        refresh_expires_at: None,
     })
 }
 
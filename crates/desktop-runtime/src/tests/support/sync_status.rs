@@ -437,5 +437,6 @@ pub(crate) fn sync_status_with_topic(
         discovery: Default::default(),
         gossip_disabled_topics: Vec::new(),
         gossip_disabled_channels: Vec::new(),
+        blob_downloads: Default::default(),
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -19,7 +19,7 @@ use tracing::info;
 use crate::access::parse_namespace_secret_hex;
 use crate::replicas::public_replica_secret;
 use crate::types::{
-    DocEvent, DocEventStream, DocFetchPolicy, DocOp, DocQuery, DocRecord, DocsSync,
+    DocEvent, DocEventStream, DocFetchPolicy, DocOp, DocQuery, DocRecord, DocRecordsSince, DocsSync,
 };
 use kukuri_iroh_node::{IrohDocsNode, remote_fetch};
 
@@ -94,6 +94,14 @@ impl IrohDocsSync {
         self.reapply_sync_peers().await
     }
 
+    /// アドレスが既に分かっているピア(mDNS の LAN 発見など)を learned 台帳へ入れる。
+    pub async fn learn_peer_addr(&self, endpoint_addr: EndpointAddr) -> Result<()> {
+        if self.peers.insert_learned_peer_addr(endpoint_addr).await {
+            self.reapply_sync_peers().await?;
+        }
+        Ok(())
+    }
+
     // 本体ループは remote_fetch(WP-B14)へ移設。characterization テスト専用に残す。
     #[cfg(test)]
     async fn connect_candidates(&self, imported_peer: &EndpointAddr) -> Vec<EndpointAddr> {
@@ -245,6 +253,54 @@ impl IrohDocsSync {
             }
         }
     }
+
+    /// watermark 以下の entry は本体を取得せず key だけ数える。
+    async fn query_entries(
+        &self,
+        replica_id: &ReplicaId,
+        query: DocQuery,
+        policy: DocFetchPolicy,
+        watermarks: &BTreeMap<String, u64>,
+    ) -> Result<DocRecordsSince> {
+        let doc = self.ensure_replica(replica_id).await?;
+        let query = match query {
+            DocQuery::Exact(key) => Query::key_exact(key).build(),
+            DocQuery::Prefix(prefix) => Query::key_prefix(prefix).build(),
+            DocQuery::All => Query::all().build(),
+        };
+        let stream = doc.get_many(query).await?;
+        tokio::pin!(stream);
+        let mut since = DocRecordsSince::default();
+        while let Some(entry) = stream.next().await {
+            let entry = entry?;
+            let key = String::from_utf8(entry.key().to_vec()).context("docs key is not utf8")?;
+            let author = entry.author().to_string();
+            let timestamp = entry.timestamp();
+            if watermarks
+                .get(author.as_str())
+                .is_some_and(|watermark| timestamp <= *watermark)
+            {
+                since.skipped_keys.push(key);
+                continue;
+            }
+            let content_hash = entry.content_hash().to_string();
+            let Some(value) = self
+                .fetch_entry_bytes(content_hash.as_str(), policy)
+                .await?
+            else {
+                continue;
+            };
+            since.records.push(DocRecord {
+                key,
+                value,
+                content_hash,
+                content_len: entry.content_len(),
+                author,
+                timestamp,
+            });
+        }
+        Ok(since)
+    }
 }
 
 #[async_trait]
@@ -320,33 +376,21 @@ impl DocsSync for IrohDocsSync {
         query: DocQuery,
         policy: DocFetchPolicy,
     ) -> Result<Vec<DocRecord>> {
-        let doc = self.ensure_replica(replica_id).await?;
-        let query = match query {
-            DocQuery::Exact(key) => Query::key_exact(key).build(),
-            DocQuery::Prefix(prefix) => Query::key_prefix(prefix).build(),
-            DocQuery::All => Query::all().build(),
-        };
-        let stream = doc.get_many(query).await?;
-        tokio::pin!(stream);
-        let mut records = Vec::new();
-        while let Some(entry) = stream.next().await {
-            let entry = entry?;
-            let key = String::from_utf8(entry.key().to_vec()).context("docs key is not utf8")?;
-            let content_hash = entry.content_hash().to_string();
-            let Some(value) = self
-                .fetch_entry_bytes(content_hash.as_str(), policy)
-                .await?
-            else {
-                continue;
-            };
-            records.push(DocRecord {
-                key,
-                value,
-                content_hash,
-                content_len: entry.content_len(),
-            });
-        }
-        Ok(records)
+        Ok(self
+            .query_entries(replica_id, query, policy, &BTreeMap::new())
+            .await?
+            .records)
+    }
+
+    async fn query_replica_since(
+        &self,
+        replica_id: &ReplicaId,
+        query: DocQuery,
+        policy: DocFetchPolicy,
+        watermarks: &BTreeMap<String, u64>,
+    ) -> Result<DocRecordsSince> {
+        self.query_entries(replica_id, query, policy, watermarks)
+            .await
     }
 
     async fn subscribe_replica(&self, replica_id: &ReplicaId) -> Result<DocEventStream> {
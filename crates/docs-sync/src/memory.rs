@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -14,16 +15,27 @@ use crate::types::{
     DocEvent, DocEventStream, DocFetchPolicy, DocOp, DocQuery, DocRecord, DocsSync,
 };
 
-type ReplicaRecords = HashMap<String, Vec<u8>>;
+/// memory 実装の entry はすべてこの author が書いたものとして扱う。
+const MEMORY_DOCS_AUTHOR: &str = "memory";
+
+/// 値と書き込み順の timestamp。
+type ReplicaRecords = HashMap<String, (Vec<u8>, u64)>;
 type MemoryReplicaMap = HashMap<String, ReplicaRecords>;
 
 #[derive(Clone, Default)]
 pub struct MemoryDocsSync {
     records: Arc<Mutex<MemoryReplicaMap>>,
+    clock: Arc<AtomicU64>,
     events: Arc<Mutex<HashMap<String, broadcast::Sender<DocEvent>>>>,
     private_replica_secrets: Arc<Mutex<HashMap<String, NamespaceSecret>>>,
 }
 
+impl MemoryDocsSync {
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed) + 1
+    }
+}
+
 #[async_trait]
 impl DocsSync for MemoryDocsSync {
     async fn open_replica(&self, replica_id: &ReplicaId) -> Result<()> {
@@ -48,7 +60,7 @@ impl DocsSync for MemoryDocsSync {
         match op {
             DocOp::SetJson { key, value } => {
                 let bytes = serde_json::to_vec(&value)?;
-                replica.insert(key.clone(), bytes.clone());
+                replica.insert(key.clone(), (bytes.clone(), self.tick()));
                 let _ = self
                     .events
                     .lock()
@@ -65,7 +77,7 @@ impl DocsSync for MemoryDocsSync {
             }
             DocOp::SetBytes { key, value } => {
                 let hash = value_hash(&value);
-                replica.insert(key.clone(), value);
+                replica.insert(key.clone(), (value, self.tick()));
                 let _ = self
                     .events
                     .lock()
@@ -106,11 +118,13 @@ impl DocsSync for MemoryDocsSync {
                 DocQuery::Prefix(prefix) => key.starts_with(prefix.as_str()),
                 DocQuery::All => true,
             })
-            .map(|(key, value)| DocRecord {
+            .map(|(key, (value, timestamp))| DocRecord {
                 content_hash: value_hash(&value),
                 content_len: value.len() as u64,
                 key,
                 value,
+                author: MEMORY_DOCS_AUTHOR.to_string(),
+                timestamp,
             })
             .collect::<Vec<_>>();
         rows.sort_by(|left, right| left.key.cmp(&right.key));
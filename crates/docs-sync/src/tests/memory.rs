@@ -1,4 +1,9 @@
-use crate::{DocsSync, MemoryDocsSync, private_channel_replica_id};
+use std::collections::BTreeMap;
+
+use crate::{
+    DocFetchPolicy, DocOp, DocQuery, DocsSync, MemoryDocsSync, private_channel_replica_id,
+    topic_replica_id,
+};
 
 #[tokio::test]
 async fn private_replica_requires_registered_capability() {
@@ -19,3 +24,48 @@ async fn private_replica_requires_registered_capability() {
         .await
         .expect("open replica after registration");
 }
+
+#[tokio::test]
+async fn query_since_skips_entries_at_or_below_author_watermark() {
+    let docs = MemoryDocsSync::default();
+    let replica = topic_replica_id("kukuri:topic:since");
+    for key in ["objects/a/state", "objects/b/state"] {
+        docs.apply_doc_op(
+            &replica,
+            DocOp::SetBytes {
+                key: key.to_string(),
+                value: key.as_bytes().to_vec(),
+            },
+        )
+        .await
+        .expect("write entry");
+    }
+    let records = docs
+        .query_replica(&replica, DocQuery::Prefix("objects/".into()))
+        .await
+        .expect("query all");
+    let first = records
+        .iter()
+        .find(|record| record.key == "objects/a/state")
+        .expect("first entry");
+
+    let watermarks = BTreeMap::from([(first.author.clone(), first.timestamp)]);
+    let since = docs
+        .query_replica_since(
+            &replica,
+            DocQuery::Prefix("objects/".into()),
+            DocFetchPolicy::LocalOnly,
+            &watermarks,
+        )
+        .await
+        .expect("query since");
+    assert_eq!(since.skipped_keys, vec!["objects/a/state".to_string()]);
+    assert_eq!(
+        since
+            .records
+            .iter()
+            .map(|record| record.key.as_str())
+            .collect::<Vec<_>>(),
+        vec!["objects/b/state"]
+    );
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::pin::Pin;
 
 use anyhow::Result;
@@ -43,6 +44,17 @@ pub struct DocRecord {
     pub value: Vec<u8>,
     pub content_hash: String,
     pub content_len: u64,
+    /// entry を書いた docs author。
+    pub author: String,
+    /// author が entry を書いた時刻。同じ author の中でだけ大小を比べられる。
+    pub timestamp: u64,
+}
+
+/// `query_replica_since` の結果。watermark 以下の entry は本体を取得せず key だけ返す。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DocRecordsSince {
+    pub records: Vec<DocRecord>,
+    pub skipped_keys: Vec<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -81,6 +93,31 @@ pub trait DocsSync: Send + Sync {
         self.query_replica_with_policy(replica_id, query, DocFetchPolicy::LocalThenRemote)
             .await
     }
+    /// `watermarks`(author ごとの timestamp)以下の entry を除いて返す。既定実装は全件を
+    /// 取得してから落とすので、本体の取得を省ける実装は上書きする。
+    async fn query_replica_since(
+        &self,
+        replica_id: &ReplicaId,
+        query: DocQuery,
+        policy: DocFetchPolicy,
+        watermarks: &BTreeMap<String, u64>,
+    ) -> Result<DocRecordsSince> {
+        let mut since = DocRecordsSince::default();
+        for record in self
+            .query_replica_with_policy(replica_id, query, policy)
+            .await?
+        {
+            if watermarks
+                .get(record.author.as_str())
+                .is_some_and(|watermark| record.timestamp <= *watermark)
+            {
+                since.skipped_keys.push(record.key);
+            } else {
+                since.records.push(record);
+            }
+        }
+        Ok(since)
+    }
     async fn subscribe_replica(&self, replica_id: &ReplicaId) -> Result<DocEventStream>;
     async fn import_peer_ticket(&self, ticket: &str) -> Result<()>;
     async fn learn_peer(&self, _endpoint_id: &str) -> Result<()> {
@@ -38,7 +38,8 @@ pub(crate) use kukuri_app_api::{
 pub(crate) use kukuri_cn_core::{JwtConfig, TestDatabase};
 pub(crate) use kukuri_cn_iroh_relay::{IrohRelayConfig, SpawnedIrohRelay};
 pub(crate) use kukuri_cn_user_api::{
-    UserApiConfig, app_router as user_api_app_router, build_state as build_user_api_state,
+    RendezvousBackendConfig, UserApiConfig, app_router as user_api_app_router,
+    build_state as build_user_api_state,
 };
 pub(crate) use kukuri_core::{
     ChannelAudienceKind, ChannelId, ChannelRef, CreatePrivateChannelInput, GameRoomStatus,
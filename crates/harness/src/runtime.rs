@@ -94,8 +94,10 @@ impl CommunityNodeStack {
         let user_api_state = build_user_api_state(&UserApiConfig {
             bind_addr: user_api_addr,
             database_url: database.database_url.clone(),
-            rendezvous_redis_url: community_node_rendezvous_redis_url(),
-            rendezvous_key_prefix: format!("cn:harness:{prefix}"),
+            rendezvous: RendezvousBackendConfig::Redis {
+                url: community_node_rendezvous_redis_url(),
+                key_prefix: format!("cn:harness:{prefix}"),
+            },
             base_url: base_url.clone(),
             public_base_url: base_url.clone(),
             connectivity_urls: vec![iroh_relay_url.clone()],
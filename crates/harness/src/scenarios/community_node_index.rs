@@ -9,8 +9,8 @@ use axum::{
 };
 use kukuri_cn_protocol::{
     AUTH_REQUIRED_CODE, ApiErrorBody, INDEX_QUERY_NOT_ACTIVATED_CODE,
-    INDEX_QUERY_NOT_CONFIGURED_CODE, IndexEntryView, IndexQueryParams, IndexQueryResponse,
-    IndexScopeKind,
+    INDEX_QUERY_NOT_CONFIGURED_CODE, IndexEntryKind, IndexEntryView, IndexQueryParams,
+    IndexQueryResponse, IndexScopeKind,
 };
 use kukuri_desktop_runtime::{CommunityNodeIndexQueryRequest, SetCommunityNodeConfigNode};
 use tokio::sync::Mutex;
@@ -126,7 +126,12 @@ async fn index_query(
             author_pubkey,
             text: format!("{operation} preview\nderived-tag"),
             created_at: 42,
+            reasons: Vec::new(),
+            entry_kind: IndexEntryKind::Post,
+            profile: None,
+            session: None,
         }],
+        next_cursor: None,
     })
     .into_response()
 }
@@ -165,6 +170,7 @@ fn request(base_url: &str) -> CommunityNodeIndexQueryRequest {
         scope_id: None,
         topic_id: None,
         limit: Some(20),
+        ..CommunityNodeIndexQueryRequest::default()
     }
 }
 
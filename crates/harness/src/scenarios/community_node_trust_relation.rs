@@ -14,11 +14,11 @@ use axum::{
 };
 use kukuri_cn_protocol::{
     AUTH_REQUIRED_CODE, ApiErrorBody, AppealStatus, Basis, CommunityNodeReportRequest,
-    CommunityNodeReportResponse, IndexEntryView, IndexQueryResponse, IndexScopeKind, Proximity,
-    ProximityBasisEntry, RELATION_NOT_FOUND_CODE, RelationNeighborsResponse,
-    RelationOptoutResponse, RelationReadResponse, RiskSignalTarget, SafetyCategory, Severity,
-    TRUST_READ_NOT_ACTIVATED_CODE, TRUST_READ_NOT_CONFIGURED_CODE, TrustBasisEntry,
-    TrustComponentKind, TrustReadView, TrustUserReadResponse, Visibility,
+    CommunityNodeReportResponse, IndexEntryKind, IndexEntryView, IndexQueryResponse,
+    IndexScopeKind, Proximity, ProximityBasisEntry, RELATION_NOT_FOUND_CODE,
+    RelationNeighborsResponse, RelationOptoutResponse, RelationReadResponse, RiskSignalTarget,
+    SafetyCategory, Severity, TRUST_READ_NOT_ACTIVATED_CODE, TRUST_READ_NOT_CONFIGURED_CODE,
+    TrustBasisEntry, TrustComponentKind, TrustReadView, TrustUserReadResponse, Visibility,
 };
 use kukuri_desktop_runtime::{
     CommunityNodeIndexQueryRequest, CommunityNodeRelationNeighborsRequest,
@@ -218,9 +218,17 @@ async fn index_search(State(state): State<ServerState>, headers: HeaderMap) -> R
             author_pubkey: "f".repeat(64),
             text: "distant community post".to_string(),
             created_at: 42,
+            reasons: Vec::new(),
+            entry_kind: IndexEntryKind::Post,
+            profile: None,
+            session: None,
         }]
     };
-    Json(IndexQueryResponse { entries }).into_response()
+    Json(IndexQueryResponse {
+        entries,
+        next_cursor: None,
+    })
+    .into_response()
 }
 
 async fn relation_user(AxumPath(target): AxumPath<String>, headers: HeaderMap) -> Response {
@@ -525,6 +533,7 @@ pub(crate) async fn run_community_node_trust_relation_client(
                             scope_id: Some(scope_id.clone()),
                             topic_id: None,
                             limit: Some(20),
+                            ..CommunityNodeIndexQueryRequest::default()
                         })
                         .await?;
                     anyhow::ensure!(
@@ -0,0 +1,78 @@
+//! blob store の GC(永続 node のみ)。
+//!
+//! iroh-blobs の GC は「保護集合に無い blob を消す」形でしか削除できない。kukuri は
+//! 明示的に手放した blob(`IrohDocsNode::evict_blob`)以外をすべて保護集合へ入れるので、
+//! docs の entry 本体や転送途中の blob が GC に巻き込まれることはない。evict の予約は
+//! メモリ上だけに持ち、GC 前に再起動した場合は blob がそのまま残る(次の retention で再判定)。
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex as StdMutex, OnceLock};
+use std::time::Duration;
+
+use iroh_blobs::Hash;
+use iroh_blobs::api::Store as BlobStore;
+use iroh_blobs::store::{GcConfig, ProtectCb, ProtectOutcome};
+use tracing::warn;
+
+const BLOB_GC_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// GC で消してよい blob(evict 予約)。
+#[derive(Clone, Debug, Default)]
+pub(crate) struct EvictedBlobs(Arc<StdMutex<HashSet<Hash>>>);
+
+impl EvictedBlobs {
+    pub(crate) fn insert(&self, hash: Hash) {
+        self.lock().insert(hash);
+    }
+
+    pub(crate) fn remove(&self, hash: &Hash) {
+        self.lock().remove(hash);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashSet<Hash>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// `store` は FsStore の load 後に埋める(GC config は load 前に渡す必要があるため)。
+/// 埋まる前の GC は中止する。
+pub(crate) fn blob_gc_config(store: Arc<OnceLock<BlobStore>>, evicted: EvictedBlobs) -> GcConfig {
+    GcConfig {
+        interval: BLOB_GC_INTERVAL,
+        add_protected: Some(protect_all_but_evicted(store, evicted)),
+    }
+}
+
+fn protect_all_but_evicted(store: Arc<OnceLock<BlobStore>>, evicted: EvictedBlobs) -> ProtectCb {
+    Arc::new(move |live| {
+        // callback の future は Sync が要るので、store への問い合わせは別 task に出す。
+        let listing = store
+            .get()
+            .cloned()
+            .map(|store| tokio::spawn(async move { store.blobs().list().hashes().await }));
+        let evicted = evicted.clone();
+        Box::pin(async move {
+            let Some(listing) = listing else {
+                return ProtectOutcome::Abort;
+            };
+            let hashes = match listing.await {
+                Ok(Ok(hashes)) => hashes.into_iter().collect::<HashSet<_>>(),
+                Ok(Err(error)) => {
+                    warn!(error = %error, "skipping blob gc: failed to list local blobs");
+                    return ProtectOutcome::Abort;
+                }
+                Err(error) => {
+                    warn!(error = %error, "skipping blob gc: blob listing task failed");
+                    return ProtectOutcome::Abort;
+                }
+            };
+            let mut evicted = evicted.lock();
+            // 前回までの GC で消えた分は予約から外す。
+            evicted.retain(|hash| hashes.contains(hash));
+            live.extend(hashes.into_iter().filter(|hash| !evicted.contains(hash)));
+            ProtectOutcome::Continue
+        })
+    })
+}
@@ -6,6 +6,7 @@
 //! かつては docs-sync が置き場所だったが、「docs-sync が基盤の持ち主」という歪みを
 //! 解消するため独立させた(挙動不変の移動)。
 
+mod blob_gc;
 mod node;
 pub mod remote_fetch;
 
@@ -1,7 +1,7 @@
 use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, RwLock as StdRwLock};
+use std::sync::{Arc, OnceLock, RwLock as StdRwLock};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result, anyhow, bail};
@@ -22,6 +22,8 @@ use serde::{Deserialize, Serialize};
 use tokio::time::timeout;
 use tracing::warn;
 
+use crate::blob_gc::{EvictedBlobs, blob_gc_config};
+
 #[cfg(test)]
 use iroh::tls::CaTlsConfig;
 
@@ -145,6 +147,7 @@ pub struct IrohDocsNode {
     router: Arc<Router>,
     docs: DocsApi,
     blobs: BlobStore,
+    evicted_blobs: EvictedBlobs,
     shutdown_started: AtomicBool,
 }
 
@@ -167,6 +170,7 @@ impl IrohDocsNode {
         let store = MemStore::new();
         Self::spawn(
             (*store).clone(),
+            EvictedBlobs::default(),
             None,
             TransportNetworkConfig::loopback(),
             DhtDiscoveryOptions::disabled(),
@@ -201,12 +205,17 @@ impl IrohDocsNode {
         let root = root.as_ref();
         std::fs::create_dir_all(root)
             .with_context(|| format!("failed to create docs root {}", root.display()))?;
-        let options = BlobStoreOptions::new(root);
+        let evicted_blobs = EvictedBlobs::default();
+        let gc_store = Arc::new(OnceLock::new());
+        let mut options = BlobStoreOptions::new(root);
+        options.gc = Some(blob_gc_config(gc_store.clone(), evicted_blobs.clone()));
         let store = iroh_blobs::store::fs::FsStore::load_with_opts(root.join("blobs.db"), options)
             .await
             .with_context(|| format!("failed to load blob store at {}", root.display()))?;
+        let _ = gc_store.set((*store).clone());
         Self::spawn(
             (*store).clone(),
+            evicted_blobs,
             Some(root.to_path_buf()),
             network_config,
             dht_options,
@@ -217,6 +226,7 @@ impl IrohDocsNode {
 
     async fn spawn(
         store: impl Into<BlobStore>,
+        evicted_blobs: EvictedBlobs,
         root: Option<PathBuf>,
         network_config: TransportNetworkConfig,
         dht_options: DhtDiscoveryOptions,
@@ -306,6 +316,7 @@ impl IrohDocsNode {
             router: Arc::new(router),
             docs: docs.api().clone(),
             blobs,
+            evicted_blobs,
             shutdown_started: AtomicBool::new(false),
         });
         if relay_config.connect_mode() == ConnectMode::DirectOrRelay {
@@ -401,6 +412,16 @@ impl IrohDocsNode {
         &self.blobs
     }
 
+    /// blob を次の GC で消すよう予約する(永続 node のみ。memory node では残り続ける)。
+    pub fn evict_blob(&self, hash: iroh_blobs::Hash) {
+        self.evicted_blobs.insert(hash);
+    }
+
+    /// evict の予約を取り消す。再び put / 取得された blob に使う。
+    pub fn retain_blob(&self, hash: &iroh_blobs::Hash) {
+        self.evicted_blobs.remove(hash);
+    }
+
     pub async fn shutdown(self: Arc<Self>) -> Result<()> {
         if self.shutdown_started.swap(true, Ordering::AcqRel) {
             return Ok(());
@@ -0,0 +1,2 @@
+ALTER TABLE object_index_cache
+  DROP COLUMN status;
@@ -0,0 +1,2 @@
+ALTER TABLE object_index_cache
+  ADD COLUMN status TEXT NOT NULL DEFAULT 'active';
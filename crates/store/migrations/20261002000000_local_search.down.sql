@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS local_search_fts;
+DROP INDEX IF EXISTS idx_local_search_documents_dm;
+DROP INDEX IF EXISTS idx_local_search_documents_created;
+DROP TABLE IF EXISTS local_search_documents;
@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS local_search_documents (
+    doc_id INTEGER PRIMARY KEY,
+    subject_kind TEXT NOT NULL,
+    subject_id TEXT NOT NULL,
+    topic_id TEXT,
+    channel_id TEXT,
+    dm_id TEXT,
+    author_pubkey TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    UNIQUE (subject_kind, subject_id),
+    CHECK (subject_kind IN ('post', 'direct_message'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_local_search_documents_created
+    ON local_search_documents (created_at DESC, subject_id DESC);
+
+CREATE INDEX IF NOT EXISTS idx_local_search_documents_dm
+    ON local_search_documents (dm_id);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS local_search_fts USING fts5(
+    body,
+    tokenize = 'trigram'
+);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_dm_skipped_message_keys_created_at;
+DROP TABLE IF EXISTS dm_skipped_message_keys;
+
+DROP TABLE IF EXISTS dm_sessions;
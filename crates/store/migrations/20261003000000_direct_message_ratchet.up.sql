@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS dm_sessions (
+    dm_id TEXT PRIMARY KEY,
+    peer_pubkey TEXT NOT NULL,
+    peer_frame_version INTEGER NOT NULL DEFAULT 1,
+    ratchet_state_json TEXT,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS dm_skipped_message_keys (
+    dm_id TEXT NOT NULL,
+    ratchet_pubkey TEXT NOT NULL,
+    message_number INTEGER NOT NULL,
+    message_key_hex TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (dm_id, ratchet_pubkey, message_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_dm_skipped_message_keys_created_at
+    ON dm_skipped_message_keys(dm_id, created_at DESC, ratchet_pubkey DESC, message_number DESC);
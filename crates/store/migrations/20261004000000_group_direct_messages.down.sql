@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS group_dm_acks;
+DROP TABLE IF EXISTS group_dm_outbox;
+DROP TABLE IF EXISTS group_dm_epochs;
+
+DROP INDEX IF EXISTS idx_group_dm_conversations_updated_at;
+DROP TABLE IF EXISTS group_dm_conversations;
@@ -0,0 +1,40 @@
+CREATE TABLE IF NOT EXISTS group_dm_conversations (
+    group_id TEXT PRIMARY KEY,
+    owner_pubkey TEXT NOT NULL,
+    title TEXT,
+    epoch INTEGER NOT NULL,
+    members_json TEXT NOT NULL,
+    removed_at INTEGER,
+    updated_at INTEGER NOT NULL,
+    last_message_at INTEGER,
+    last_message_id TEXT,
+    last_message_preview TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_group_dm_conversations_updated_at
+    ON group_dm_conversations(updated_at DESC, group_id DESC);
+
+CREATE TABLE IF NOT EXISTS group_dm_epochs (
+    group_id TEXT NOT NULL,
+    epoch INTEGER NOT NULL,
+    epoch_secret_hex TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (group_id, epoch)
+);
+
+CREATE TABLE IF NOT EXISTS group_dm_outbox (
+    group_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    frame_blob_hash TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    last_attempt_at INTEGER,
+    PRIMARY KEY (group_id, message_id)
+);
+
+CREATE TABLE IF NOT EXISTS group_dm_acks (
+    group_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    member_pubkey TEXT NOT NULL,
+    acked_at INTEGER NOT NULL,
+    PRIMARY KEY (group_id, message_id, member_pubkey)
+);
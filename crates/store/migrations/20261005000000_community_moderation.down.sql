@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS community_moderation_policies;
+
+DROP INDEX IF EXISTS idx_community_moderation_events_received_at;
+DROP INDEX IF EXISTS idx_community_moderation_events_target;
+DROP TABLE IF EXISTS community_moderation_events;
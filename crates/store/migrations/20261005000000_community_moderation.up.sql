@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS community_moderation_events (
+    node_base_url TEXT NOT NULL,
+    event_id TEXT NOT NULL,
+    issuer_node_id TEXT NOT NULL,
+    target_kind TEXT NOT NULL,
+    target_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    labels_json TEXT NOT NULL,
+    reason_code TEXT NOT NULL,
+    signed_event_json TEXT NOT NULL,
+    received_at INTEGER NOT NULL,
+    PRIMARY KEY (node_base_url, event_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_community_moderation_events_target
+    ON community_moderation_events (target_kind, target_id);
+
+CREATE INDEX IF NOT EXISTS idx_community_moderation_events_received_at
+    ON community_moderation_events (received_at ASC);
+
+CREATE TABLE IF NOT EXISTS community_moderation_policies (
+    node_base_url TEXT PRIMARY KEY,
+    policy TEXT NOT NULL,
+    updated_at INTEGER NOT NULL,
+    CHECK (policy IN ('hide', 'blur', 'label', 'ignore'))
+);
@@ -0,0 +1,22 @@
+DROP INDEX IF EXISTS idx_download_jobs_claim;
+
+ALTER TABLE download_jobs
+  DROP COLUMN updated_at;
+
+ALTER TABLE download_jobs
+  DROP COLUMN enqueued_at;
+
+ALTER TABLE download_jobs
+  DROP COLUMN last_error;
+
+ALTER TABLE download_jobs
+  DROP COLUMN next_attempt_at;
+
+ALTER TABLE download_jobs
+  DROP COLUMN attempts;
+
+ALTER TABLE download_jobs
+  DROP COLUMN priority;
+
+ALTER TABLE download_jobs
+  DROP COLUMN kind;
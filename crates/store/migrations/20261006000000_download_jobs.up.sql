@@ -0,0 +1,23 @@
+ALTER TABLE download_jobs
+  ADD COLUMN kind TEXT NOT NULL DEFAULT 'attachment';
+
+ALTER TABLE download_jobs
+  ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE download_jobs
+  ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE download_jobs
+  ADD COLUMN next_attempt_at INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE download_jobs
+  ADD COLUMN last_error TEXT;
+
+ALTER TABLE download_jobs
+  ADD COLUMN enqueued_at INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE download_jobs
+  ADD COLUMN updated_at INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_download_jobs_claim
+    ON download_jobs (status, priority DESC, next_attempt_at ASC);
@@ -0,0 +1,10 @@
+DROP INDEX IF EXISTS idx_blob_objects_lru;
+
+ALTER TABLE blob_objects
+  DROP COLUMN last_accessed_at;
+
+ALTER TABLE blob_objects
+  DROP COLUMN pinned;
+
+ALTER TABLE blob_objects
+  DROP COLUMN bytes;
@@ -0,0 +1,11 @@
+ALTER TABLE blob_objects
+  ADD COLUMN bytes INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE blob_objects
+  ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE blob_objects
+  ADD COLUMN last_accessed_at INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_blob_objects_lru
+    ON blob_objects (pinned, last_accessed_at ASC);
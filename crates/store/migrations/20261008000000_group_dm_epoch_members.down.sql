@@ -0,0 +1,2 @@
+ALTER TABLE group_dm_epochs
+  DROP COLUMN members_json;
@@ -0,0 +1,2 @@
+ALTER TABLE group_dm_epochs
+  ADD COLUMN members_json TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS replica_cursors;
+
+CREATE TABLE IF NOT EXISTS replica_cursors (
+    replica_id TEXT PRIMARY KEY,
+    cursor TEXT
+);
@@ -0,0 +1,8 @@
+DROP TABLE IF EXISTS replica_cursors;
+
+CREATE TABLE IF NOT EXISTS replica_cursors (
+    replica_id TEXT NOT NULL,
+    author_id TEXT NOT NULL,
+    watermark INTEGER NOT NULL,
+    PRIMARY KEY (replica_id, author_id)
+);
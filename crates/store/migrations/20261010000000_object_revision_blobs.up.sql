@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS object_revision_blobs (
+    object_id TEXT NOT NULL,
+    payload_ref_json TEXT NOT NULL,
+    attachments_json TEXT NOT NULL,
+    PRIMARY KEY (object_id, payload_ref_json, attachments_json)
+);
@@ -0,0 +1,5 @@
+ALTER TABLE dm_sessions
+  DROP COLUMN peer_prekey_pubkey;
+
+ALTER TABLE dm_sessions
+  DROP COLUMN local_prekey_secret_hex;
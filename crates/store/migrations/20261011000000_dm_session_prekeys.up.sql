@@ -0,0 +1,5 @@
+ALTER TABLE dm_sessions
+  ADD COLUMN local_prekey_secret_hex TEXT;
+
+ALTER TABLE dm_sessions
+  ADD COLUMN peer_prekey_pubkey TEXT;
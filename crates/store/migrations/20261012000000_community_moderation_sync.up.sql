@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS community_moderation_sync (
+    node_base_url TEXT PRIMARY KEY,
+    backfill_cursor TEXT,
+    updated_at INTEGER NOT NULL
+);
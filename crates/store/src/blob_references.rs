@@ -0,0 +1,64 @@
+//! projection が参照している blob の数え上げ(`BlobRetentionStore::blob_reference_counts`)。
+//!
+//! sqlite / memory の両実装が同じ規則で数えるよう、行ごとの blob 抽出をここへ集める。
+//! 1 行が同じ blob を複数回持っていても参照は 1 と数える。
+
+use std::collections::{BTreeSet, HashMap};
+
+use kukuri_core::{
+    AssetRef, DirectMessageAttachmentManifestV1, MetaverseRoomStateV1, ObjectStatus, PayloadRef,
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct BlobReferenceCounter {
+    counts: HashMap<String, u64>,
+}
+
+impl BlobReferenceCounter {
+    pub(crate) fn add_row<'a>(&mut self, hashes: impl IntoIterator<Item = &'a str>) {
+        let unique = hashes.into_iter().collect::<BTreeSet<_>>();
+        for hash in unique {
+            *self.counts.entry(hash.to_string()).or_default() += 1;
+        }
+    }
+
+    pub(crate) fn into_counts(self) -> HashMap<String, u64> {
+        self.counts
+    }
+}
+
+/// 取り消された post は本文・添付を参照しない。
+pub(crate) fn object_status_references_blobs(status: &ObjectStatus) -> bool {
+    matches!(status, ObjectStatus::Active | ObjectStatus::Edited)
+}
+
+pub(crate) fn post_blob_hashes<'a>(
+    payload_ref: &'a PayloadRef,
+    attachments: &'a [AssetRef],
+) -> impl Iterator<Item = &'a str> {
+    let payload = match payload_ref {
+        PayloadRef::BlobText { hash, .. } => Some(hash.as_str()),
+        PayloadRef::InlineText { .. } => None,
+    };
+    payload.into_iter().chain(
+        attachments
+            .iter()
+            .map(|attachment| attachment.hash.as_str()),
+    )
+}
+
+pub(crate) fn direct_message_attachment_hashes(
+    manifest: &DirectMessageAttachmentManifestV1,
+) -> impl Iterator<Item = &str> {
+    std::iter::once(manifest.original.hash.as_str())
+        .chain(manifest.poster.iter().map(|poster| poster.hash.as_str()))
+}
+
+pub(crate) fn metaverse_asset_hashes(
+    metaverse: &MetaverseRoomStateV1,
+) -> impl Iterator<Item = &str> {
+    metaverse
+        .asset_refs
+        .iter()
+        .map(|asset| asset.blob_hash.as_str())
+}
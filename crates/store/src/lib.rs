@@ -1,3 +1,4 @@
+mod blob_references;
 mod memory;
 mod models;
 mod pagination;
@@ -10,15 +11,21 @@ mod tests;
 
 pub use memory::MemoryStore;
 pub use models::{
-    AuthorRelationshipProjectionRow, BlobCacheStatus, BookmarkedCustomReactionRow,
-    BookmarkedPostRow, ContentObservationRow, DirectMessageConversationRow,
-    DirectMessageMessageRow, DirectMessageOutboxRow, DirectMessageTombstoneRow,
-    GameRoomProjectionRow, LiveSessionProjectionRow, MutedAuthorRow, NotificationKind,
-    NotificationRow, ObjectProjectionRow, Page, ReactionProjectionRow, TimelineCursor,
+    AuthorRelationshipProjectionRow, BlobCacheStatus, BlobRetentionRow,
+    BookmarkedCustomReactionRow, BookmarkedPostRow, CommunityModerationEventRow,
+    CommunityModerationPolicy, CommunityModerationPolicyRow, CommunityModerationSyncRow,
+    ContentObservationRow, DirectMessageConversationRow, DirectMessageMessageRow,
+    DirectMessageOutboxRow, DirectMessageSessionRow, DirectMessageSkippedKeyRow,
+    DirectMessageTombstoneRow, DownloadJobKind, DownloadJobRow, DownloadJobStatus,
+    DownloadQueueSummary, GameRoomProjectionRow, GroupDirectMessageConversationRow,
+    GroupDirectMessageEpochRow, GroupDirectMessageOutboxRow, LiveSessionProjectionRow,
+    LocalSearchDocumentRow, LocalSearchQuery, MutedAuthorRow, NotificationKind, NotificationRow,
+    ObjectProjectionRow, Page, ReactionProjectionRow, ReplicaCursor, TimelineCursor,
 };
 pub use sqlite::{SqliteStore, StoreStartupError};
 pub use traits::{
-    BlobCacheStore, ContentObservationStore, DirectMessageStore, LiveGameProjectionStore,
-    NotificationStore, ObjectProjectionStore, ProjectionStore, ReactionBookmarkStore,
+    BlobCacheStore, BlobRetentionStore, CommunityModerationStore, ContentObservationStore,
+    DirectMessageStore, DownloadJobStore, LiveGameProjectionStore, NotificationStore,
+    ObjectProjectionStore, ProjectionStore, ReactionBookmarkStore, ReplicaCursorStore,
     SocialProjectionStore, Store,
 };
@@ -0,0 +1,142 @@
+use super::*;
+use crate::blob_references::{
+    BlobReferenceCounter, direct_message_attachment_hashes, metaverse_asset_hashes,
+    object_status_references_blobs, post_blob_hashes,
+};
+
+/// sqlite の `blob_objects` の retention 列に相当する(status は `blob_statuses` 側)。
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct MemoryBlobUsage {
+    bytes: u64,
+    pinned: bool,
+    last_accessed_at: i64,
+}
+
+#[async_trait]
+impl BlobRetentionStore for MemoryStore {
+    async fn record_blob_access(
+        &self,
+        hash: &BlobHash,
+        bytes: Option<u64>,
+        now: i64,
+    ) -> Result<()> {
+        let mut statuses = self.blob_statuses.write().await;
+        let status = statuses
+            .entry(hash.as_str().to_string())
+            .or_insert(BlobCacheStatus::Available);
+        if *status != BlobCacheStatus::Pinned {
+            *status = BlobCacheStatus::Available;
+        }
+        let mut usage = self.blob_usage.write().await;
+        let entry = usage.entry(hash.as_str().to_string()).or_default();
+        if let Some(bytes) = bytes {
+            entry.bytes = bytes;
+        }
+        entry.last_accessed_at = entry.last_accessed_at.max(now);
+        Ok(())
+    }
+
+    async fn set_blob_pinned(&self, hash: &BlobHash, pinned: bool) -> Result<()> {
+        self.blob_statuses
+            .write()
+            .await
+            .entry(hash.as_str().to_string())
+            .or_insert(BlobCacheStatus::Available);
+        self.blob_usage
+            .write()
+            .await
+            .entry(hash.as_str().to_string())
+            .or_default()
+            .pinned = pinned;
+        Ok(())
+    }
+
+    async fn list_local_blobs(&self) -> Result<Vec<BlobRetentionRow>> {
+        let statuses = self.blob_statuses.read().await;
+        let usage = self.blob_usage.read().await;
+        let mut rows = statuses
+            .iter()
+            .filter(|(_, status)| {
+                matches!(status, BlobCacheStatus::Available | BlobCacheStatus::Pinned)
+            })
+            .map(|(hash, status)| {
+                let usage = usage.get(hash).copied().unwrap_or_default();
+                BlobRetentionRow {
+                    blob_hash: BlobHash::new(hash.clone()),
+                    status: status.clone(),
+                    bytes: usage.bytes,
+                    pinned: usage.pinned,
+                    last_accessed_at: usage.last_accessed_at,
+                }
+            })
+            .collect::<Vec<_>>();
+        rows.sort_by(|left, right| {
+            left.last_accessed_at
+                .cmp(&right.last_accessed_at)
+                .then_with(|| left.blob_hash.as_str().cmp(right.blob_hash.as_str()))
+        });
+        Ok(rows)
+    }
+
+    async fn blob_reference_counts(&self) -> Result<HashMap<String, u64>> {
+        let mut counter = BlobReferenceCounter::default();
+        let revision_blobs = self.object_revision_blobs.read().await;
+        for row in self.object_projection_rows.read().await.values() {
+            if object_status_references_blobs(&row.status) {
+                counter.add_row(post_blob_hashes(&row.payload_ref, &row.attachments));
+                for (payload_ref, attachments) in
+                    revision_blobs.get(&row.object_id).into_iter().flatten()
+                {
+                    counter.add_row(post_blob_hashes(payload_ref, attachments));
+                }
+            }
+        }
+        for row in self.bookmarked_posts.read().await.values() {
+            counter.add_row(post_blob_hashes(&row.payload_ref, &row.attachments));
+        }
+        for profile in self.profiles.read().await.values() {
+            if let Some(asset) = profile.picture_asset.as_ref() {
+                counter.add_row([asset.hash.as_str()]);
+            }
+        }
+        for row in self.reaction_projection_rows.read().await.values() {
+            if let Some(snapshot) = row.custom_asset_snapshot.as_ref()
+                && object_status_references_blobs(&row.status)
+            {
+                counter.add_row([snapshot.blob_hash.as_str()]);
+            }
+        }
+        for row in self.bookmarked_custom_reactions.read().await.values() {
+            counter.add_row([row.blob_hash.as_str()]);
+        }
+        for row in self.live_session_rows.read().await.values() {
+            counter.add_row([row.manifest_blob_hash.as_str()]);
+        }
+        for row in self.game_room_rows.read().await.values() {
+            counter.add_row(
+                std::iter::once(row.manifest_blob_hash.as_str())
+                    .chain(row.metaverse.iter().flat_map(metaverse_asset_hashes)),
+            );
+        }
+        for row in self.direct_message_rows.read().await.values() {
+            if let Some(manifest) = row.attachment_manifest.as_ref() {
+                counter.add_row(direct_message_attachment_hashes(manifest));
+            }
+        }
+        for row in self.direct_message_outbox_rows.read().await.values() {
+            counter.add_row([row.frame_blob_hash.as_str()]);
+        }
+        for row in self.group_direct_message_outbox_rows.read().await.values() {
+            counter.add_row([row.frame_blob_hash.as_str()]);
+        }
+        Ok(counter.into_counts())
+    }
+
+    async fn mark_blob_evicted(&self, hash: &BlobHash) -> Result<()> {
+        if let Some(status) = self.blob_statuses.write().await.get_mut(hash.as_str()) {
+            *status = BlobCacheStatus::Missing;
+        }
+        self.blob_usage.write().await.remove(hash.as_str());
+        Ok(())
+    }
+}
@@ -236,9 +236,224 @@ impl DirectMessageStore for MemoryStore {
             .write()
             .await
             .remove(&(dm_id.to_string(), message_id.to_string()));
+        self.remove_direct_message_search_documents(dm_id, Some(message_id))
+            .await;
         Ok(())
     }
 
+    async fn get_direct_message_session(
+        &self,
+        dm_id: &str,
+    ) -> Result<Option<DirectMessageSessionRow>> {
+        Ok(self
+            .direct_message_sessions
+            .read()
+            .await
+            .get(dm_id)
+            .cloned())
+    }
+
+    async fn put_direct_message_session(&self, row: DirectMessageSessionRow) -> Result<()> {
+        self.direct_message_sessions
+            .write()
+            .await
+            .insert(row.dm_id.clone(), row);
+        Ok(())
+    }
+
+    async fn put_direct_message_skipped_keys(
+        &self,
+        rows: Vec<DirectMessageSkippedKeyRow>,
+    ) -> Result<()> {
+        let mut keys = self.direct_message_skipped_keys.write().await;
+        for row in rows {
+            keys.entry((
+                row.dm_id.clone(),
+                row.ratchet_pubkey.clone(),
+                row.message_number,
+            ))
+            .or_insert(row);
+        }
+        Ok(())
+    }
+
+    async fn take_direct_message_skipped_key(
+        &self,
+        dm_id: &str,
+        ratchet_pubkey: &str,
+        message_number: u32,
+    ) -> Result<Option<String>> {
+        Ok(self
+            .direct_message_skipped_keys
+            .write()
+            .await
+            .remove(&(
+                dm_id.to_string(),
+                ratchet_pubkey.to_string(),
+                message_number,
+            ))
+            .map(|row| row.message_key_hex))
+    }
+
+    async fn prune_direct_message_skipped_keys(&self, dm_id: &str, max_keys: usize) -> Result<()> {
+        let mut keys = self.direct_message_skipped_keys.write().await;
+        let mut rows = keys
+            .values()
+            .filter(|row| row.dm_id == dm_id)
+            .map(|row| {
+                (
+                    row.created_at,
+                    row.ratchet_pubkey.clone(),
+                    row.message_number,
+                )
+            })
+            .collect::<Vec<_>>();
+        rows.sort_by(|left, right| right.cmp(left));
+        for (_, ratchet_pubkey, message_number) in rows.into_iter().skip(max_keys) {
+            keys.remove(&(dm_id.to_string(), ratchet_pubkey, message_number));
+        }
+        Ok(())
+    }
+
+    async fn upsert_group_direct_message_conversation(
+        &self,
+        row: GroupDirectMessageConversationRow,
+    ) -> Result<()> {
+        self.group_direct_message_conversations
+            .write()
+            .await
+            .insert(row.group_id.clone(), row);
+        Ok(())
+    }
+
+    async fn get_group_direct_message_conversation(
+        &self,
+        group_id: &str,
+    ) -> Result<Option<GroupDirectMessageConversationRow>> {
+        Ok(self
+            .group_direct_message_conversations
+            .read()
+            .await
+            .get(group_id)
+            .cloned())
+    }
+
+    async fn list_group_direct_message_conversations(
+        &self,
+    ) -> Result<Vec<GroupDirectMessageConversationRow>> {
+        let mut items = self
+            .group_direct_message_conversations
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        items.sort_by(|left, right| {
+            right
+                .updated_at
+                .cmp(&left.updated_at)
+                .then_with(|| right.group_id.cmp(&left.group_id))
+        });
+        Ok(items)
+    }
+
+    async fn put_group_direct_message_epoch(&self, row: GroupDirectMessageEpochRow) -> Result<()> {
+        self.group_direct_message_epochs
+            .write()
+            .await
+            .entry((row.group_id.clone(), row.epoch))
+            .or_insert(row);
+        Ok(())
+    }
+
+    async fn get_group_direct_message_epoch(
+        &self,
+        group_id: &str,
+        epoch: u64,
+    ) -> Result<Option<GroupDirectMessageEpochRow>> {
+        Ok(self
+            .group_direct_message_epochs
+            .read()
+            .await
+            .get(&(group_id.to_string(), epoch))
+            .cloned())
+    }
+
+    async fn put_group_direct_message_outbox(
+        &self,
+        row: GroupDirectMessageOutboxRow,
+    ) -> Result<()> {
+        self.group_direct_message_outbox_rows
+            .write()
+            .await
+            .insert((row.group_id.clone(), row.message_id.clone()), row);
+        Ok(())
+    }
+
+    async fn list_group_direct_message_outbox(
+        &self,
+        group_id: &str,
+    ) -> Result<Vec<GroupDirectMessageOutboxRow>> {
+        let mut items = self
+            .group_direct_message_outbox_rows
+            .read()
+            .await
+            .values()
+            .filter(|row| row.group_id == group_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        items.sort_by(|left, right| {
+            left.created_at
+                .cmp(&right.created_at)
+                .then_with(|| left.message_id.cmp(&right.message_id))
+        });
+        Ok(items)
+    }
+
+    async fn touch_group_direct_message_outbox_attempt(
+        &self,
+        group_id: &str,
+        message_id: &str,
+        attempted_at: i64,
+    ) -> Result<()> {
+        if let Some(row) = self
+            .group_direct_message_outbox_rows
+            .write()
+            .await
+            .get_mut(&(group_id.to_string(), message_id.to_string()))
+        {
+            row.last_attempt_at = Some(attempted_at);
+        }
+        Ok(())
+    }
+
+    async fn remove_group_direct_message_outbox(
+        &self,
+        group_id: &str,
+        message_id: &str,
+    ) -> Result<()> {
+        self.group_direct_message_outbox_rows
+            .write()
+            .await
+            .remove(&(group_id.to_string(), message_id.to_string()));
+        Ok(())
+    }
+
+    async fn put_group_direct_message_ack(
+        &self,
+        group_id: &str,
+        message_id: &str,
+        member_pubkey: &str,
+        acked_at: i64,
+    ) -> Result<Vec<String>> {
+        let mut acks = self.group_direct_message_acks.write().await;
+        let members = acks
+            .entry((group_id.to_string(), message_id.to_string()))
+            .or_default();
+        members.entry(member_pubkey.to_string()).or_insert(acked_at);
+        Ok(members.keys().cloned().collect())
+    }
+
     async fn clear_direct_message_local(&self, dm_id: &str) -> Result<()> {
         self.direct_message_rows
             .write()
@@ -248,10 +463,16 @@ impl DirectMessageStore for MemoryStore {
             .write()
             .await
             .retain(|(row_dm_id, _), _| row_dm_id != dm_id);
+        self.group_direct_message_outbox_rows
+            .write()
+            .await
+            .retain(|(group_id, _), _| group_id != dm_id);
         self.direct_message_conversations
             .write()
             .await
             .remove(dm_id);
+        self.remove_direct_message_search_documents(dm_id, None)
+            .await;
         Ok(())
     }
 }
@@ -0,0 +1,133 @@
+use super::*;
+
+#[async_trait]
+impl DownloadJobStore for MemoryStore {
+    async fn enqueue_download_job(
+        &self,
+        blob_hash: &BlobHash,
+        kind: DownloadJobKind,
+        priority: i64,
+        now: i64,
+    ) -> Result<bool> {
+        let mut jobs = self.download_jobs.write().await;
+        if let Some(job) = jobs.get_mut(blob_hash.as_str()) {
+            job.priority = job.priority.max(priority);
+            job.updated_at = now;
+            return Ok(false);
+        }
+        jobs.insert(
+            blob_hash.as_str().to_string(),
+            DownloadJobRow {
+                blob_hash: blob_hash.clone(),
+                kind,
+                status: DownloadJobStatus::Queued,
+                priority,
+                attempts: 0,
+                next_attempt_at: now,
+                last_error: None,
+                enqueued_at: now,
+                updated_at: now,
+            },
+        );
+        Ok(true)
+    }
+
+    async fn retry_download_job(&self, blob_hash: &BlobHash, now: i64) -> Result<bool> {
+        let mut jobs = self.download_jobs.write().await;
+        let Some(job) = jobs
+            .get_mut(blob_hash.as_str())
+            .filter(|job| job.status == DownloadJobStatus::Failed)
+        else {
+            return Ok(false);
+        };
+        job.status = DownloadJobStatus::Queued;
+        job.attempts = 0;
+        job.updated_at = now;
+        Ok(true)
+    }
+
+    async fn claim_download_jobs(&self, now: i64, limit: usize) -> Result<Vec<DownloadJobRow>> {
+        let mut jobs = self.download_jobs.write().await;
+        let mut due = jobs
+            .values()
+            .filter(|job| job.status == DownloadJobStatus::Queued && job.next_attempt_at <= now)
+            .map(|job| {
+                (
+                    -job.priority,
+                    job.enqueued_at,
+                    job.blob_hash.as_str().to_string(),
+                )
+            })
+            .collect::<Vec<_>>();
+        due.sort();
+        let mut claimed = Vec::new();
+        for (_, _, key) in due.into_iter().take(limit) {
+            if let Some(job) = jobs.get_mut(key.as_str()) {
+                job.status = DownloadJobStatus::Active;
+                job.updated_at = now;
+                claimed.push(job.clone());
+            }
+        }
+        Ok(claimed)
+    }
+
+    async fn complete_download_job(&self, blob_hash: &BlobHash) -> Result<()> {
+        self.download_jobs.write().await.remove(blob_hash.as_str());
+        Ok(())
+    }
+
+    async fn fail_download_job(
+        &self,
+        blob_hash: &BlobHash,
+        error: &str,
+        next_attempt_at: i64,
+        exhausted: bool,
+        now: i64,
+    ) -> Result<()> {
+        if let Some(job) = self.download_jobs.write().await.get_mut(blob_hash.as_str()) {
+            job.attempts = job.attempts.saturating_add(1);
+            job.last_error = Some(error.to_string());
+            job.next_attempt_at = next_attempt_at;
+            job.status = if exhausted {
+                DownloadJobStatus::Failed
+            } else {
+                DownloadJobStatus::Queued
+            };
+            job.updated_at = now;
+        }
+        Ok(())
+    }
+
+    async fn requeue_active_download_jobs(&self, now: i64) -> Result<usize> {
+        let mut requeued = 0usize;
+        for job in self.download_jobs.write().await.values_mut() {
+            if job.status == DownloadJobStatus::Active {
+                job.status = DownloadJobStatus::Queued;
+                job.updated_at = now;
+                requeued += 1;
+            }
+        }
+        Ok(requeued)
+    }
+
+    async fn get_download_job(&self, blob_hash: &BlobHash) -> Result<Option<DownloadJobRow>> {
+        Ok(self
+            .download_jobs
+            .read()
+            .await
+            .get(blob_hash.as_str())
+            .cloned())
+    }
+
+    async fn summarize_download_jobs(&self) -> Result<DownloadQueueSummary> {
+        let mut summary = DownloadQueueSummary::default();
+        for job in self.download_jobs.read().await.values() {
+            match job.status {
+                DownloadJobStatus::Queued => summary.queued += 1,
+                DownloadJobStatus::Active => summary.active += 1,
+                DownloadJobStatus::Failed => summary.failed += 1,
+            }
+        }
+        Ok(summary)
+    }
+}
@@ -4,25 +4,31 @@ use std::sync::Arc;
 use anyhow::Result;
 use async_trait::async_trait;
 use kukuri_core::{
-    BlobHash, EnvelopeId, FollowEdge, KukuriEnvelope, LiveSessionStatus, Profile, ReplicaId,
-    parse_follow_edge, parse_profile,
+    AssetRef, BlobHash, EnvelopeId, FollowEdge, KukuriEnvelope, LiveSessionStatus, PayloadRef,
+    Profile, ReplicaId, parse_follow_edge, parse_profile,
 };
 use tokio::sync::RwLock;
 
 use crate::models::{
-    AuthorRelationshipProjectionRow, BlobCacheStatus, BookmarkedCustomReactionRow,
-    BookmarkedPostRow, ContentObservationRow, DirectMessageConversationRow,
-    DirectMessageMessageRow, DirectMessageOutboxRow, DirectMessageTombstoneRow,
-    GameRoomProjectionRow, LiveSessionProjectionRow, MutedAuthorRow, NotificationRow,
-    ObjectProjectionRow, Page, ReactionProjectionRow, TimelineCursor,
+    AuthorRelationshipProjectionRow, BlobCacheStatus, BlobRetentionRow,
+    BookmarkedCustomReactionRow, BookmarkedPostRow, CommunityModerationEventRow,
+    CommunityModerationPolicyRow, CommunityModerationSyncRow, ContentObservationRow,
+    DirectMessageConversationRow, DirectMessageMessageRow, DirectMessageOutboxRow,
+    DirectMessageSessionRow, DirectMessageSkippedKeyRow, DirectMessageTombstoneRow,
+    DownloadJobKind, DownloadJobRow, DownloadJobStatus, DownloadQueueSummary,
+    GameRoomProjectionRow, GroupDirectMessageConversationRow, GroupDirectMessageEpochRow,
+    GroupDirectMessageOutboxRow, LiveSessionProjectionRow, LocalSearchDocumentRow,
+    LocalSearchQuery, MutedAuthorRow, NotificationRow, ObjectProjectionRow, Page,
+    ReactionProjectionRow, ReplicaCursor, TimelineCursor,
 };
 use crate::pagination::{
     apply_asc_cursor, apply_asc_projection_cursor, apply_desc_cursor,
     apply_desc_direct_message_cursor, apply_desc_projection_cursor,
 };
 use crate::traits::{
-    BlobCacheStore, ContentObservationStore, DirectMessageStore, LiveGameProjectionStore,
-    NotificationStore, ObjectProjectionStore, ReactionBookmarkStore, SocialProjectionStore, Store,
+    BlobCacheStore, BlobRetentionStore, CommunityModerationStore, ContentObservationStore,
+    DirectMessageStore, DownloadJobStore, LiveGameProjectionStore, NotificationStore,
+    ObjectProjectionStore, ReactionBookmarkStore, ReplicaCursorStore, SocialProjectionStore, Store,
 };
 
 /// sqlite の live_presence_cache 主キー ON CONFLICT(topic_id, channel_id, session_id,
@@ -34,9 +40,17 @@ type MemoryReactionProjectionRows = HashMap<(String, String, String), ReactionPr
 type MemoryDirectMessageRows = HashMap<(String, String), DirectMessageMessageRow>;
 type MemoryDirectMessageOutboxRows = HashMap<(String, String), DirectMessageOutboxRow>;
 type MemoryDirectMessageTombstones = HashMap<(String, String), DirectMessageTombstoneRow>;
+type MemoryDirectMessageSkippedKeys = HashMap<(String, String, u32), DirectMessageSkippedKeyRow>;
+type MemoryGroupDirectMessageEpochs = HashMap<(String, u64), GroupDirectMessageEpochRow>;
+type MemoryGroupDirectMessageOutboxRows = HashMap<(String, String), GroupDirectMessageOutboxRow>;
+type MemoryGroupDirectMessageAcks = HashMap<(String, String), BTreeMap<String, i64>>;
 type MemoryNotificationRows = HashMap<String, NotificationRow>;
 type MemoryContentObservationRows =
     HashMap<(String, String, String, String), ContentObservationRow>;
+type MemoryCommunityModerationEvents = HashMap<(String, String), CommunityModerationEventRow>;
+type MemoryLocalSearchRows = HashMap<(String, String), LocalSearchDocumentRow>;
+/// sqlite の object_revision_blobs と同義。object ごとの以前の版の本文と添付。
+type MemoryObjectRevisionBlobs = HashMap<EnvelopeId, Vec<(PayloadRef, Vec<AssetRef>)>>;
 
 #[derive(Clone, Default)]
 pub struct MemoryStore {
@@ -46,6 +60,7 @@ pub struct MemoryStore {
     profiles: Arc<RwLock<HashMap<String, Profile>>>,
     follow_edges: Arc<RwLock<HashMap<(String, String), FollowEdge>>>,
     object_projection_rows: Arc<RwLock<HashMap<EnvelopeId, ObjectProjectionRow>>>,
+    object_revision_blobs: Arc<RwLock<MemoryObjectRevisionBlobs>>,
     live_session_rows: Arc<RwLock<HashMap<String, LiveSessionProjectionRow>>>,
     game_room_rows: Arc<RwLock<HashMap<String, GameRoomProjectionRow>>>,
     author_relationship_rows:
@@ -53,6 +68,9 @@ pub struct MemoryStore {
     muted_authors: Arc<RwLock<HashMap<String, MutedAuthorRow>>>,
     live_presence: Arc<RwLock<HashMap<LivePresenceKey, LivePresenceValue>>>,
     blob_statuses: Arc<RwLock<HashMap<String, BlobCacheStatus>>>,
+    blob_usage: Arc<RwLock<HashMap<String, blob_retention::MemoryBlobUsage>>>,
+    download_jobs: Arc<RwLock<HashMap<String, DownloadJobRow>>>,
+    replica_cursors: Arc<RwLock<HashMap<String, ReplicaCursor>>>,
     reaction_projection_rows: Arc<RwLock<MemoryReactionProjectionRows>>,
     bookmarked_custom_reactions: Arc<RwLock<HashMap<String, BookmarkedCustomReactionRow>>>,
     bookmarked_posts: Arc<RwLock<HashMap<String, BookmarkedPostRow>>>,
@@ -60,17 +78,33 @@ pub struct MemoryStore {
     direct_message_rows: Arc<RwLock<MemoryDirectMessageRows>>,
     direct_message_outbox_rows: Arc<RwLock<MemoryDirectMessageOutboxRows>>,
     direct_message_tombstones: Arc<RwLock<MemoryDirectMessageTombstones>>,
+    direct_message_sessions: Arc<RwLock<HashMap<String, DirectMessageSessionRow>>>,
+    direct_message_skipped_keys: Arc<RwLock<MemoryDirectMessageSkippedKeys>>,
+    group_direct_message_conversations:
+        Arc<RwLock<HashMap<String, GroupDirectMessageConversationRow>>>,
+    group_direct_message_epochs: Arc<RwLock<MemoryGroupDirectMessageEpochs>>,
+    group_direct_message_outbox_rows: Arc<RwLock<MemoryGroupDirectMessageOutboxRows>>,
+    group_direct_message_acks: Arc<RwLock<MemoryGroupDirectMessageAcks>>,
     notification_rows: Arc<RwLock<MemoryNotificationRows>>,
     content_observation_rows: Arc<RwLock<MemoryContentObservationRows>>,
+    community_moderation_events: Arc<RwLock<MemoryCommunityModerationEvents>>,
+    community_moderation_policies: Arc<RwLock<HashMap<String, CommunityModerationPolicyRow>>>,
+    community_moderation_sync: Arc<RwLock<HashMap<String, CommunityModerationSyncRow>>>,
+    local_search_rows: Arc<RwLock<MemoryLocalSearchRows>>,
 }
 
+mod blob_retention;
 mod bookmarks;
 mod direct_messages;
+mod downloads;
 mod envelopes;
 mod live_game;
+mod moderation;
 mod notifications;
 mod observations;
 mod projections;
+mod replica_cursors;
+mod search;
 mod social;
 
 #[async_trait]
@@ -0,0 +1,99 @@
+use super::*;
+
+const MAX_COMMUNITY_MODERATION_EVENTS: usize = 4096;
+
+#[async_trait]
+impl CommunityModerationStore for MemoryStore {
+    async fn put_community_moderation_event(
+        &self,
+        row: CommunityModerationEventRow,
+    ) -> Result<bool> {
+        let key = (row.node_base_url.clone(), row.event_id.clone());
+        let mut events = self.community_moderation_events.write().await;
+        if events.contains_key(&key) {
+            return Ok(false);
+        }
+        events.insert(key, row);
+        if events.len() > MAX_COMMUNITY_MODERATION_EVENTS {
+            let mut oldest = events
+                .iter()
+                .map(|(key, event)| (key.clone(), event.received_at))
+                .collect::<Vec<_>>();
+            oldest.sort_by_key(|(_, received_at)| *received_at);
+            for (key, _) in oldest
+                .into_iter()
+                .take(events.len() - MAX_COMMUNITY_MODERATION_EVENTS)
+            {
+                events.remove(&key);
+            }
+        }
+        Ok(true)
+    }
+
+    async fn list_community_moderation_events_for_targets(
+        &self,
+        target_kind: &str,
+        target_ids: &[String],
+    ) -> Result<Vec<CommunityModerationEventRow>> {
+        let mut rows = self
+            .community_moderation_events
+            .read()
+            .await
+            .values()
+            .filter(|row| row.target_kind == target_kind && target_ids.contains(&row.target_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        rows.sort_by(|left, right| {
+            left.received_at
+                .cmp(&right.received_at)
+                .then_with(|| left.node_base_url.cmp(&right.node_base_url))
+                .then_with(|| left.event_id.cmp(&right.event_id))
+        });
+        Ok(rows)
+    }
+
+    async fn put_community_moderation_policy(
+        &self,
+        row: CommunityModerationPolicyRow,
+    ) -> Result<()> {
+        self.community_moderation_policies
+            .write()
+            .await
+            .insert(row.node_base_url.clone(), row);
+        Ok(())
+    }
+
+    async fn list_community_moderation_policies(
+        &self,
+    ) -> Result<Vec<CommunityModerationPolicyRow>> {
+        let mut rows = self
+            .community_moderation_policies
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        rows.sort_by(|left, right| left.node_base_url.cmp(&right.node_base_url));
+        Ok(rows)
+    }
+
+    async fn get_community_moderation_sync(
+        &self,
+        node_base_url: &str,
+    ) -> Result<Option<CommunityModerationSyncRow>> {
+        Ok(self
+            .community_moderation_sync
+            .read()
+            .await
+            .get(node_base_url)
+            .cloned())
+    }
+
+    async fn put_community_moderation_sync(&self, row: CommunityModerationSyncRow) -> Result<()> {
+        self.community_moderation_sync
+            .write()
+            .await
+            .insert(row.node_base_url.clone(), row);
+        Ok(())
+    }
+}
@@ -8,8 +8,18 @@ impl ObjectProjectionStore for MemoryStore {
 
     async fn put_object_projections(&self, rows: Vec<ObjectProjectionRow>) -> Result<()> {
         let mut projections = self.object_projection_rows.write().await;
+        let mut revision_blobs = self.object_revision_blobs.write().await;
         for row in rows {
-            projections.insert(row.object_id.clone(), row);
+            if let Some(previous) = projections.insert(row.object_id.clone(), row.clone())
+                && (previous.payload_ref != row.payload_ref
+                    || previous.attachments != row.attachments)
+            {
+                let history = revision_blobs.entry(row.object_id.clone()).or_default();
+                let version = (previous.payload_ref, previous.attachments);
+                if !history.contains(&version) {
+                    history.push(version);
+                }
+            }
         }
         Ok(())
     }
@@ -159,6 +169,7 @@ impl ObjectProjectionStore for MemoryStore {
         self.game_room_rows.write().await.clear();
         self.live_presence.write().await.clear();
         self.reaction_projection_rows.write().await.clear();
+        self.replica_cursors.write().await.clear();
         self.content_observation_rows
             .write()
             .await
@@ -166,6 +177,30 @@ impl ObjectProjectionStore for MemoryStore {
                 observation.subject_kind != "post"
                     || retained_object_ids.contains(observation.subject_id.as_str())
             });
+        self.local_search_rows.write().await.retain(|_, document| {
+            document.subject_kind != "post"
+                || retained_object_ids.contains(document.subject_id.as_str())
+        });
         Ok(())
     }
+
+    async fn put_local_search_documents(&self, rows: Vec<LocalSearchDocumentRow>) -> Result<()> {
+        self.put_local_search_documents_impl(rows).await
+    }
+
+    async fn remove_local_search_document(
+        &self,
+        subject_kind: &str,
+        subject_id: &str,
+    ) -> Result<()> {
+        self.remove_local_search_document_impl(subject_kind, subject_id)
+            .await
+    }
+
+    async fn search_local_documents(
+        &self,
+        query: &LocalSearchQuery,
+    ) -> Result<Vec<LocalSearchDocumentRow>> {
+        self.search_local_documents_impl(query).await
+    }
 }
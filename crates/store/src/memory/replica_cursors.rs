@@ -0,0 +1,36 @@
+use super::*;
+
+#[async_trait]
+impl ReplicaCursorStore for MemoryStore {
+    async fn get_replica_cursor(&self, replica_id: &ReplicaId) -> Result<Option<ReplicaCursor>> {
+        Ok(self
+            .replica_cursors
+            .read()
+            .await
+            .get(replica_id.as_str())
+            .cloned())
+    }
+
+    async fn put_replica_cursor(
+        &self,
+        replica_id: &ReplicaId,
+        cursor: &ReplicaCursor,
+    ) -> Result<()> {
+        self.replica_cursors
+            .write()
+            .await
+            .entry(replica_id.as_str().to_string())
+            .or_default()
+            .watermarks
+            .extend(cursor.watermarks.clone());
+        Ok(())
+    }
+
+    async fn clear_replica_cursor(&self, replica_id: &ReplicaId) -> Result<()> {
+        self.replica_cursors
+            .write()
+            .await
+            .remove(replica_id.as_str());
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use super::*;
+
+/// sqlite の trigram LIKE と同じく ASCII だけ大文字小文字を無視して部分一致させる。
+fn body_contains_term(body: &str, term: &str) -> bool {
+    body.to_ascii_lowercase()
+        .contains(term.to_ascii_lowercase().as_str())
+}
+
+impl MemoryStore {
+    pub(super) async fn put_local_search_documents_impl(
+        &self,
+        rows: Vec<LocalSearchDocumentRow>,
+    ) -> Result<()> {
+        let mut documents = self.local_search_rows.write().await;
+        for row in rows {
+            let key = (row.subject_kind.clone(), row.subject_id.clone());
+            if row.body.trim().is_empty() {
+                documents.remove(&key);
+            } else {
+                documents.insert(key, row);
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) async fn remove_local_search_document_impl(
+        &self,
+        subject_kind: &str,
+        subject_id: &str,
+    ) -> Result<()> {
+        self.local_search_rows
+            .write()
+            .await
+            .remove(&(subject_kind.to_string(), subject_id.to_string()));
+        Ok(())
+    }
+
+    /// DM 削除経路から呼ぶ。`message_id` が None なら会話全体の文書を消す。
+    pub(super) async fn remove_direct_message_search_documents(
+        &self,
+        dm_id: &str,
+        message_id: Option<&str>,
+    ) {
+        self.local_search_rows.write().await.retain(|_, row| {
+            row.subject_kind != "direct_message"
+                || row.dm_id.as_deref() != Some(dm_id)
+                || message_id.is_some_and(|message_id| row.subject_id != message_id)
+        });
+    }
+
+    pub(super) async fn search_local_documents_impl(
+        &self,
+        query: &LocalSearchQuery,
+    ) -> Result<Vec<LocalSearchDocumentRow>> {
+        let terms = query.terms();
+        if terms.is_empty() || query.limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut items = self
+            .local_search_rows
+            .read()
+            .await
+            .values()
+            .filter(|row| {
+                query.matches_filters(row)
+                    && terms
+                        .iter()
+                        .all(|term| body_contains_term(row.body.as_str(), term))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        items.sort_by(|left, right| {
+            right
+                .created_at
+                .cmp(&left.created_at)
+                .then_with(|| right.subject_id.cmp(&left.subject_id))
+        });
+        items.truncate(query.limit);
+        Ok(items)
+    }
+}
@@ -5,6 +5,7 @@ use kukuri_core::{
     RepostSourceSnapshotV1,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(ts_rs::TS))]
@@ -26,6 +27,83 @@ pub enum BlobCacheStatus {
     Missing,
     Available,
     Pinned,
+    /// download queue が転送中。
+    Downloading,
+    /// download queue が再試行上限に達した(再び要求されるまで取りに行かない)。
+    Failed,
+}
+
+/// download queue に積む blob の用途。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DownloadJobKind {
+    /// 投稿・コメントの本文 blob(`PayloadRef::BlobText`)。
+    Payload,
+    Attachment,
+    /// live session / game room の manifest blob。
+    Manifest,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DownloadJobStatus {
+    Queued,
+    Active,
+    Failed,
+}
+
+/// 未取得 blob の download job(`blob_hash` で一意)。取得できた job は行ごと消す。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadJobRow {
+    pub blob_hash: BlobHash,
+    pub kind: DownloadJobKind,
+    pub status: DownloadJobStatus,
+    /// 大きいほど先に取る。表示中の item は `enqueue` し直して引き上げる。
+    pub priority: i64,
+    pub attempts: u32,
+    /// この時刻(ms)までは claim しない(backoff)。
+    pub next_attempt_at: i64,
+    pub last_error: Option<String>,
+    pub enqueued_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadQueueSummary {
+    pub queued: usize,
+    pub active: usize,
+    pub failed: usize,
+}
+
+/// replica の projection 済み位置(`replica_cursors`)。docs author ごとに projection へ
+/// 反映済みの entry timestamp の上限を持ち、それ以下の entry は replica から取り直さない。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplicaCursor {
+    pub watermarks: BTreeMap<String, u64>,
+}
+
+impl ReplicaCursor {
+    pub fn is_current(&self, author: &str, timestamp: u64) -> bool {
+        self.watermarks
+            .get(author)
+            .is_some_and(|watermark| timestamp <= *watermark)
+    }
+
+    /// watermark は戻さない。
+    pub fn advance(&mut self, author: &str, timestamp: u64) {
+        let watermark = self.watermarks.entry(author.to_string()).or_default();
+        *watermark = (*watermark).max(timestamp);
+    }
+}
+
+/// ローカルに持っている blob の容量と保持区分(`blob_objects` の retention 列)。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobRetentionRow {
+    pub blob_hash: BlobHash,
+    pub status: BlobCacheStatus,
+    pub bytes: u64,
+    /// 自分が put した blob。unpin されるまで eviction しない。
+    pub pinned: bool,
+    /// LRU 用の最終参照時刻(ms)。
+    pub last_accessed_at: i64,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -42,6 +120,7 @@ pub struct ObjectProjectionRow {
     pub content: Option<String>,
     pub attachments: Vec<AssetRef>,
     pub repost_of: Option<RepostSourceSnapshotV1>,
+    pub status: ObjectStatus,
     pub source_replica_id: ReplicaId,
     pub source_key: String,
     pub source_envelope_id: EnvelopeId,
@@ -50,6 +129,70 @@ pub struct ObjectProjectionRow {
     pub projection_version: i64,
 }
 
+/// 端末内全文検索の文書 1 件(post 本文 / DM 平文)。
+///
+/// `created_at` は subject 種別をまたいだ日付 filter のため unix millis に正規化して持つ。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalSearchDocumentRow {
+    pub subject_kind: String,
+    pub subject_id: String,
+    pub topic_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub dm_id: Option<String>,
+    pub author_pubkey: String,
+    pub created_at: i64,
+    pub body: String,
+}
+
+/// 端末内全文検索の条件。`text` の空白区切り語をすべて含む文書を新しい順に返す。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalSearchQuery {
+    pub text: String,
+    /// 空なら全種別。
+    pub subject_kinds: BTreeSet<String>,
+    pub topic_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub author_pubkey: Option<String>,
+    /// 下限(含む、unix millis)。
+    pub created_after: Option<i64>,
+    /// 上限(含まない、unix millis)。
+    pub created_before: Option<i64>,
+    /// 続き読みの位置 `(created_at, subject_id)`。この位置より古い文書だけを返す(位置自体は含まない)。
+    pub before: Option<(i64, String)>,
+    pub limit: usize,
+}
+
+impl LocalSearchQuery {
+    pub(crate) fn terms(&self) -> Vec<&str> {
+        self.text.split_whitespace().collect()
+    }
+
+    pub(crate) fn matches_filters(&self, row: &LocalSearchDocumentRow) -> bool {
+        (self.subject_kinds.is_empty() || self.subject_kinds.contains(&row.subject_kind))
+            && self
+                .topic_id
+                .as_ref()
+                .is_none_or(|topic_id| row.topic_id.as_ref() == Some(topic_id))
+            && self
+                .channel_id
+                .as_ref()
+                .is_none_or(|channel_id| row.channel_id.as_ref() == Some(channel_id))
+            && self
+                .author_pubkey
+                .as_ref()
+                .is_none_or(|author_pubkey| &row.author_pubkey == author_pubkey)
+            && self
+                .created_after
+                .is_none_or(|created_after| row.created_at >= created_after)
+            && self
+                .created_before
+                .is_none_or(|created_before| row.created_at < created_before)
+            && self.before.as_ref().is_none_or(|(created_at, subject_id)| {
+                (row.created_at, row.subject_id.as_str()) < (*created_at, subject_id.as_str())
+            })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContentObservationRow {
     pub subject_kind: String,
@@ -59,6 +202,51 @@ pub struct ContentObservationRow {
     pub observed_at: i64,
 }
 
+/// community node の moderation event を端末内でどう扱うか(node ごとに利用者が選ぶ)。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "snake_case")]
+pub enum CommunityModerationPolicy {
+    Hide,
+    Blur,
+    /// 既定。advisory として label だけを表示する。
+    #[default]
+    Label,
+    Ignore,
+}
+
+/// issuer 鍵で検証済みの moderation event(node ごとに event id で一意)。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommunityModerationEventRow {
+    pub node_base_url: String,
+    pub event_id: String,
+    pub issuer_node_id: String,
+    pub target_kind: String,
+    pub target_id: String,
+    pub action: String,
+    pub labels: Vec<String>,
+    pub reason_code: String,
+    pub signed_event_json: String,
+    pub received_at: i64,
+}
+
+/// node ごとの moderation event pull の進捗。行が無い node はまだ一度も最後まで辿っていない。
+/// `backfill_cursor` があれば、その位置から古い側を最後まで辿り直す必要がある
+/// (前回の pull が途中で止まって取りこぼしがある)。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommunityModerationSyncRow {
+    pub node_base_url: String,
+    pub backfill_cursor: Option<String>,
+    pub updated_at: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommunityModerationPolicyRow {
+    pub node_base_url: String,
+    pub policy: CommunityModerationPolicy,
+    pub updated_at: i64,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ReactionProjectionRow {
     pub source_replica_id: ReplicaId,
@@ -211,6 +399,71 @@ pub struct DirectMessageTombstoneRow {
     pub deleted_at: i64,
 }
 
+/// DM の frame version 交渉結果と ratchet session。`ratchet_state_json` は
+/// `kukuri_core::DirectMessageRatchetStateV2` の JSON で、秘密鍵を含む。
+/// `local_prekey_secret_hex` は自分が広告中の session prekey の秘密鍵、
+/// `peer_prekey_pubkey` は相手が最後に広告した session prekey。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectMessageSessionRow {
+    pub dm_id: String,
+    pub peer_pubkey: String,
+    pub peer_frame_version: u8,
+    pub ratchet_state_json: Option<String>,
+    #[serde(default)]
+    pub local_prekey_secret_hex: Option<String>,
+    #[serde(default)]
+    pub peer_prekey_pubkey: Option<String>,
+    pub updated_at: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectMessageSkippedKeyRow {
+    pub dm_id: String,
+    pub ratchet_pubkey: String,
+    pub message_number: u32,
+    pub message_key_hex: String,
+    pub created_at: i64,
+}
+
+/// グループ DM の会話。`members` は owner を含む現在の epoch のメンバーで、
+/// メッセージ本体は `DirectMessageMessageRow`(`dm_id` = group_id)に入る。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupDirectMessageConversationRow {
+    pub group_id: String,
+    pub owner_pubkey: String,
+    pub title: Option<String>,
+    pub epoch: u64,
+    pub members: Vec<String>,
+    /// 自分がメンバーから外された時刻。以後は送受信しない。
+    pub removed_at: Option<i64>,
+    pub updated_at: i64,
+    pub last_message_at: Option<i64>,
+    pub last_message_id: Option<String>,
+    pub last_message_preview: Option<String>,
+}
+
+/// epoch ごとのグループ鍵。rekey 後も古い epoch の frame を読むために残す。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupDirectMessageEpochRow {
+    pub group_id: String,
+    pub epoch: u64,
+    pub epoch_secret_hex: String,
+    /// この epoch の鍵を配った時点のメンバー。送信者の検証は現在のメンバーではなくこれで行う。
+    /// 追加前の行では空。
+    #[serde(default)]
+    pub members: Vec<String>,
+    pub created_at: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupDirectMessageOutboxRow {
+    pub group_id: String,
+    pub message_id: String,
+    pub frame_blob_hash: BlobHash,
+    pub created_at: i64,
+    pub last_attempt_at: Option<i64>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(rename_all = "snake_case")]
@@ -221,6 +474,7 @@ pub enum NotificationKind {
     QuoteRepost,
     DirectMessage,
     Followed,
+    GroupDirectMessage,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -6,10 +6,14 @@ use kukuri_core::{
 use sqlx::Row;
 
 use crate::models::{
-    AuthorRelationshipProjectionRow, BookmarkedCustomReactionRow, BookmarkedPostRow,
+    AuthorRelationshipProjectionRow, BlobCacheStatus, BlobRetentionRow,
+    BookmarkedCustomReactionRow, BookmarkedPostRow, CommunityModerationEventRow,
+    CommunityModerationPolicy, CommunityModerationPolicyRow, CommunityModerationSyncRow,
     DirectMessageConversationRow, DirectMessageMessageRow, DirectMessageOutboxRow,
-    DirectMessageTombstoneRow, GameRoomProjectionRow, LiveSessionProjectionRow, MutedAuthorRow,
-    NotificationKind, NotificationRow, ObjectProjectionRow, ReactionProjectionRow,
+    DirectMessageSessionRow, DirectMessageTombstoneRow, DownloadJobKind, DownloadJobRow,
+    DownloadJobStatus, GameRoomProjectionRow, GroupDirectMessageConversationRow,
+    GroupDirectMessageEpochRow, GroupDirectMessageOutboxRow, LiveSessionProjectionRow,
+    MutedAuthorRow, NotificationKind, NotificationRow, ObjectProjectionRow, ReactionProjectionRow,
 };
 
 /// NULL 許容列の読み出し。sqlx-sqlite は NULL を `String` なら `Ok("")`、`i64` なら
@@ -70,6 +74,7 @@ pub(crate) fn row_to_object_projection(
             .filter(|value| !value.trim().is_empty())
             .map(|value| serde_json::from_str(value.as_str()))
             .transpose()?,
+        status: parse_object_status(row.get::<String, _>("status").as_str())?,
         source_replica_id: ReplicaId::new(row.get::<String, _>("source_replica_id")),
         source_key: row.get("source_key"),
         source_envelope_id: row.get::<String, _>("source_envelope_id").into(),
@@ -255,6 +260,67 @@ pub(crate) fn row_to_direct_message_outbox(
     })
 }
 
+pub(crate) fn row_to_direct_message_session(
+    row: sqlx::sqlite::SqliteRow,
+) -> Result<DirectMessageSessionRow> {
+    let peer_frame_version: i64 = row.get("peer_frame_version");
+    Ok(DirectMessageSessionRow {
+        dm_id: row.get("dm_id"),
+        peer_pubkey: row.get("peer_pubkey"),
+        peer_frame_version: u8::try_from(peer_frame_version)
+            .map_err(|_| anyhow::anyhow!("invalid dm peer_frame_version `{peer_frame_version}`"))?,
+        ratchet_state_json: opt_col(&row, "ratchet_state_json"),
+        local_prekey_secret_hex: opt_col(&row, "local_prekey_secret_hex"),
+        peer_prekey_pubkey: opt_col(&row, "peer_prekey_pubkey"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+pub(crate) fn row_to_group_direct_message_conversation(
+    row: sqlx::sqlite::SqliteRow,
+) -> Result<GroupDirectMessageConversationRow> {
+    let epoch: i64 = row.get("epoch");
+    Ok(GroupDirectMessageConversationRow {
+        group_id: row.get("group_id"),
+        owner_pubkey: row.get("owner_pubkey"),
+        title: opt_col(&row, "title"),
+        epoch: u64::try_from(epoch)
+            .map_err(|_| anyhow::anyhow!("invalid group dm epoch `{epoch}`"))?,
+        members: serde_json::from_str(row.get::<String, _>("members_json").as_str())?,
+        removed_at: opt_col(&row, "removed_at"),
+        updated_at: row.get("updated_at"),
+        last_message_at: opt_col(&row, "last_message_at"),
+        last_message_id: opt_col(&row, "last_message_id"),
+        last_message_preview: opt_col(&row, "last_message_preview"),
+    })
+}
+
+pub(crate) fn row_to_group_direct_message_epoch(
+    row: sqlx::sqlite::SqliteRow,
+) -> Result<GroupDirectMessageEpochRow> {
+    let epoch: i64 = row.get("epoch");
+    Ok(GroupDirectMessageEpochRow {
+        group_id: row.get("group_id"),
+        epoch: u64::try_from(epoch)
+            .map_err(|_| anyhow::anyhow!("invalid group dm epoch `{epoch}`"))?,
+        epoch_secret_hex: row.get("epoch_secret_hex"),
+        members: serde_json::from_str(row.get::<String, _>("members_json").as_str())?,
+        created_at: row.get("created_at"),
+    })
+}
+
+pub(crate) fn row_to_group_direct_message_outbox(
+    row: sqlx::sqlite::SqliteRow,
+) -> Result<GroupDirectMessageOutboxRow> {
+    Ok(GroupDirectMessageOutboxRow {
+        group_id: row.get("group_id"),
+        message_id: row.get("message_id"),
+        frame_blob_hash: BlobHash::new(row.get::<String, _>("frame_blob_hash")),
+        created_at: row.get("created_at"),
+        last_attempt_at: opt_col(&row, "last_attempt_at"),
+    })
+}
+
 pub(crate) fn row_to_direct_message_tombstone(
     row: sqlx::sqlite::SqliteRow,
 ) -> Result<DirectMessageTombstoneRow> {
@@ -415,6 +481,7 @@ pub(crate) fn notification_kind_name(kind: &NotificationKind) -> &'static str {
         NotificationKind::QuoteRepost => "quote_repost",
         NotificationKind::DirectMessage => "direct_message",
         NotificationKind::Followed => "followed",
+        NotificationKind::GroupDirectMessage => "group_direct_message",
     }
 }
 
@@ -426,10 +493,146 @@ pub(crate) fn parse_notification_kind(value: &str) -> Result<NotificationKind> {
         "quote_repost" => Ok(NotificationKind::QuoteRepost),
         "direct_message" => Ok(NotificationKind::DirectMessage),
         "followed" => Ok(NotificationKind::Followed),
+        "group_direct_message" => Ok(NotificationKind::GroupDirectMessage),
         _ => anyhow::bail!("unknown notification kind: {value}"),
     }
 }
 
+pub(crate) fn community_moderation_policy_name(policy: CommunityModerationPolicy) -> &'static str {
+    match policy {
+        CommunityModerationPolicy::Hide => "hide",
+        CommunityModerationPolicy::Blur => "blur",
+        CommunityModerationPolicy::Label => "label",
+        CommunityModerationPolicy::Ignore => "ignore",
+    }
+}
+
+pub(crate) fn parse_community_moderation_policy(value: &str) -> Result<CommunityModerationPolicy> {
+    match value {
+        "hide" => Ok(CommunityModerationPolicy::Hide),
+        "blur" => Ok(CommunityModerationPolicy::Blur),
+        "label" => Ok(CommunityModerationPolicy::Label),
+        "ignore" => Ok(CommunityModerationPolicy::Ignore),
+        _ => anyhow::bail!("unknown community moderation policy: {value}"),
+    }
+}
+
+pub(crate) fn row_to_community_moderation_event(
+    row: sqlx::sqlite::SqliteRow,
+) -> Result<CommunityModerationEventRow> {
+    Ok(CommunityModerationEventRow {
+        node_base_url: row.try_get("node_base_url")?,
+        event_id: row.try_get("event_id")?,
+        issuer_node_id: row.try_get("issuer_node_id")?,
+        target_kind: row.try_get("target_kind")?,
+        target_id: row.try_get("target_id")?,
+        action: row.try_get("action")?,
+        labels: serde_json::from_str(row.try_get::<String, _>("labels_json")?.as_str())?,
+        reason_code: row.try_get("reason_code")?,
+        signed_event_json: row.try_get("signed_event_json")?,
+        received_at: row.try_get("received_at")?,
+    })
+}
+
+pub(crate) fn row_to_community_moderation_policy(
+    row: sqlx::sqlite::SqliteRow,
+) -> Result<CommunityModerationPolicyRow> {
+    Ok(CommunityModerationPolicyRow {
+        node_base_url: row.try_get("node_base_url")?,
+        policy: parse_community_moderation_policy(row.try_get::<String, _>("policy")?.as_str())?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+pub(crate) fn row_to_community_moderation_sync(
+    row: sqlx::sqlite::SqliteRow,
+) -> Result<CommunityModerationSyncRow> {
+    Ok(CommunityModerationSyncRow {
+        node_base_url: row.try_get("node_base_url")?,
+        backfill_cursor: row.try_get("backfill_cursor")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+pub(crate) fn blob_cache_status_name(status: &BlobCacheStatus) -> &'static str {
+    match status {
+        BlobCacheStatus::Missing => "missing",
+        BlobCacheStatus::Available => "available",
+        BlobCacheStatus::Pinned => "pinned",
+        BlobCacheStatus::Downloading => "downloading",
+        BlobCacheStatus::Failed => "failed",
+    }
+}
+
+pub(crate) fn parse_blob_cache_status(value: &str) -> Result<BlobCacheStatus> {
+    match value {
+        "missing" => Ok(BlobCacheStatus::Missing),
+        "available" => Ok(BlobCacheStatus::Available),
+        "pinned" => Ok(BlobCacheStatus::Pinned),
+        "downloading" => Ok(BlobCacheStatus::Downloading),
+        "failed" => Ok(BlobCacheStatus::Failed),
+        _ => anyhow::bail!("unknown blob cache status: {value}"),
+    }
+}
+
+pub(crate) fn row_to_blob_retention(row: sqlx::sqlite::SqliteRow) -> Result<BlobRetentionRow> {
+    Ok(BlobRetentionRow {
+        blob_hash: BlobHash::new(row.try_get::<String, _>("blob_hash")?),
+        status: parse_blob_cache_status(row.try_get::<String, _>("status")?.as_str())?,
+        bytes: u64::try_from(row.try_get::<i64, _>("bytes")?)?,
+        pinned: row.try_get::<i64, _>("pinned")? != 0,
+        last_accessed_at: row.try_get("last_accessed_at")?,
+    })
+}
+
+pub(crate) fn download_job_kind_name(kind: DownloadJobKind) -> &'static str {
+    match kind {
+        DownloadJobKind::Payload => "payload",
+        DownloadJobKind::Attachment => "attachment",
+        DownloadJobKind::Manifest => "manifest",
+    }
+}
+
+pub(crate) fn parse_download_job_kind(value: &str) -> Result<DownloadJobKind> {
+    match value {
+        "payload" => Ok(DownloadJobKind::Payload),
+        "attachment" => Ok(DownloadJobKind::Attachment),
+        "manifest" => Ok(DownloadJobKind::Manifest),
+        _ => anyhow::bail!("unknown download job kind: {value}"),
+    }
+}
+
+pub(crate) fn download_job_status_name(status: DownloadJobStatus) -> &'static str {
+    match status {
+        DownloadJobStatus::Queued => "queued",
+        DownloadJobStatus::Active => "active",
+        DownloadJobStatus::Failed => "failed",
+    }
+}
+
+pub(crate) fn parse_download_job_status(value: &str) -> Result<DownloadJobStatus> {
+    match value {
+        "queued" => Ok(DownloadJobStatus::Queued),
+        "active" => Ok(DownloadJobStatus::Active),
+        "failed" => Ok(DownloadJobStatus::Failed),
+        _ => anyhow::bail!("unknown download job status: {value}"),
+    }
+}
+
+pub(crate) fn row_to_download_job(row: sqlx::sqlite::SqliteRow) -> Result<DownloadJobRow> {
+    Ok(DownloadJobRow {
+        blob_hash: BlobHash::new(row.try_get::<String, _>("blob_hash")?),
+        kind: parse_download_job_kind(row.try_get::<String, _>("kind")?.as_str())?,
+        status: parse_download_job_status(row.try_get::<String, _>("status")?.as_str())?,
+        priority: row.try_get("priority")?,
+        attempts: u32::try_from(row.try_get::<i64, _>("attempts")?)?,
+        next_attempt_at: row.try_get("next_attempt_at")?,
+        last_error: opt_col(&row, "last_error"),
+        enqueued_at: row.try_get("enqueued_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
 pub(crate) fn live_status_name(status: &LiveSessionStatus) -> &'static str {
     match status {
         LiveSessionStatus::Scheduled => "scheduled",
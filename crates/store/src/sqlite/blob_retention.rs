@@ -0,0 +1,226 @@
+use super::*;
+use crate::blob_references::{
+    BlobReferenceCounter, direct_message_attachment_hashes, metaverse_asset_hashes,
+    object_status_references_blobs, post_blob_hashes,
+};
+use crate::row_mapping::{parse_object_status, row_to_blob_retention};
+use kukuri_core::{
+    AssetRef, CustomReactionAssetSnapshotV1, DirectMessageAttachmentManifestV1,
+    MetaverseRoomStateV1, PayloadRef,
+};
+use std::collections::HashMap;
+
+#[async_trait]
+impl BlobRetentionStore for SqliteStore {
+    async fn record_blob_access(
+        &self,
+        hash: &BlobHash,
+        bytes: Option<u64>,
+        now: i64,
+    ) -> Result<()> {
+        let bytes = bytes.map(i64::try_from).transpose()?;
+        sqlx::query(
+            r#"
+            INSERT INTO blob_objects (blob_hash, status, bytes, pinned, last_accessed_at)
+            VALUES (?1, 'available', COALESCE(?2, 0), 0, ?3)
+            ON CONFLICT(blob_hash) DO UPDATE SET
+              status = CASE
+                WHEN blob_objects.status = 'pinned' THEN 'pinned'
+                ELSE 'available'
+              END,
+              bytes = COALESCE(?2, blob_objects.bytes),
+              last_accessed_at = MAX(blob_objects.last_accessed_at, excluded.last_accessed_at)
+            "#,
+        )
+        .bind(hash.as_str())
+        .bind(bytes)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_blob_pinned(&self, hash: &BlobHash, pinned: bool) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO blob_objects (blob_hash, status, pinned)
+            VALUES (?1, 'available', ?2)
+            ON CONFLICT(blob_hash) DO UPDATE SET pinned = excluded.pinned
+            "#,
+        )
+        .bind(hash.as_str())
+        .bind(i64::from(pinned))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_local_blobs(&self) -> Result<Vec<BlobRetentionRow>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT blob_hash, status, bytes, pinned, last_accessed_at
+            FROM blob_objects
+            WHERE status IN ('available', 'pinned')
+            ORDER BY last_accessed_at ASC, blob_hash ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(row_to_blob_retention).collect()
+    }
+
+    async fn blob_reference_counts(&self) -> Result<HashMap<String, u64>> {
+        let mut counter = BlobReferenceCounter::default();
+
+        for row in
+            sqlx::query("SELECT payload_ref_json, attachments_json, status FROM object_index_cache")
+                .fetch_all(&self.pool)
+                .await?
+        {
+            if !object_status_references_blobs(&parse_object_status(
+                row.try_get::<String, _>("status")?.as_str(),
+            )?) {
+                continue;
+            }
+            let payload_ref: PayloadRef =
+                serde_json::from_str(row.try_get::<String, _>("payload_ref_json")?.as_str())?;
+            let attachments: Vec<AssetRef> =
+                serde_json::from_str(row.try_get::<String, _>("attachments_json")?.as_str())?;
+            counter.add_row(post_blob_hashes(&payload_ref, &attachments));
+        }
+
+        // 編集前の版の本文と添付は、revision 履歴として表示される間は参照に数える。
+        for row in sqlx::query(
+            r#"
+            SELECT h.payload_ref_json, h.attachments_json, o.status
+            FROM object_revision_blobs h
+            JOIN object_index_cache o ON o.object_id = h.object_id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?
+        {
+            if !object_status_references_blobs(&parse_object_status(
+                row.try_get::<String, _>("status")?.as_str(),
+            )?) {
+                continue;
+            }
+            let payload_ref: PayloadRef =
+                serde_json::from_str(row.try_get::<String, _>("payload_ref_json")?.as_str())?;
+            let attachments: Vec<AssetRef> =
+                serde_json::from_str(row.try_get::<String, _>("attachments_json")?.as_str())?;
+            counter.add_row(post_blob_hashes(&payload_ref, &attachments));
+        }
+
+        for row in sqlx::query("SELECT payload_ref_json, attachments_json FROM bookmarked_posts")
+            .fetch_all(&self.pool)
+            .await?
+        {
+            let payload_ref: PayloadRef =
+                serde_json::from_str(row.try_get::<String, _>("payload_ref_json")?.as_str())?;
+            let attachments: Vec<AssetRef> =
+                serde_json::from_str(row.try_get::<String, _>("attachments_json")?.as_str())?;
+            counter.add_row(post_blob_hashes(&payload_ref, &attachments));
+        }
+
+        // profiles(自分の公開 profile)と profile_cache は同じ pubkey を二重に数えない。
+        for hash in sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT picture_blob_hash FROM (
+              SELECT pubkey, picture_blob_hash FROM profiles
+              UNION
+              SELECT pubkey, picture_blob_hash FROM profile_cache
+            )
+            WHERE picture_blob_hash IS NOT NULL
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?
+        {
+            counter.add_row([hash.as_str()]);
+        }
+
+        for row in sqlx::query(
+            r#"
+            SELECT custom_asset_snapshot_json, status
+            FROM reaction_cache
+            WHERE custom_asset_snapshot_json IS NOT NULL
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?
+        {
+            if !object_status_references_blobs(&parse_object_status(
+                row.try_get::<String, _>("status")?.as_str(),
+            )?) {
+                continue;
+            }
+            let snapshot: CustomReactionAssetSnapshotV1 = serde_json::from_str(
+                row.try_get::<String, _>("custom_asset_snapshot_json")?
+                    .as_str(),
+            )?;
+            counter.add_row([snapshot.blob_hash.as_str()]);
+        }
+
+        for query in [
+            "SELECT blob_hash FROM bookmarked_custom_reactions",
+            "SELECT manifest_blob_hash FROM live_session_cache",
+            "SELECT frame_blob_hash FROM dm_outbox",
+            "SELECT frame_blob_hash FROM group_dm_outbox",
+        ] {
+            for hash in sqlx::query_scalar::<_, String>(query)
+                .fetch_all(&self.pool)
+                .await?
+            {
+                counter.add_row([hash.as_str()]);
+            }
+        }
+
+        for row in sqlx::query("SELECT manifest_blob_hash, metaverse_json FROM game_room_cache")
+            .fetch_all(&self.pool)
+            .await?
+        {
+            let manifest_blob_hash = row.try_get::<String, _>("manifest_blob_hash")?;
+            let metaverse = row
+                .try_get::<Option<String>, _>("metaverse_json")?
+                .filter(|value| !value.trim().is_empty())
+                .map(|value| serde_json::from_str::<MetaverseRoomStateV1>(value.as_str()))
+                .transpose()?;
+            counter.add_row(
+                std::iter::once(manifest_blob_hash.as_str())
+                    .chain(metaverse.iter().flat_map(metaverse_asset_hashes)),
+            );
+        }
+
+        for manifest_json in sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT attachment_manifest_json
+            FROM dm_messages
+            WHERE attachment_manifest_json IS NOT NULL
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?
+        {
+            let manifest: DirectMessageAttachmentManifestV1 =
+                serde_json::from_str(manifest_json.as_str())?;
+            counter.add_row(direct_message_attachment_hashes(&manifest));
+        }
+
+        Ok(counter.into_counts())
+    }
+
+    async fn mark_blob_evicted(&self, hash: &BlobHash) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE blob_objects
+            SET status = 'missing', bytes = 0, pinned = 0
+            WHERE blob_hash = ?1
+            "#,
+        )
+        .bind(hash.as_str())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::row_mapping::blob_cache_status_name;
 
 #[async_trait]
 impl BlobCacheStore for SqliteStore {
@@ -11,11 +12,7 @@ impl BlobCacheStore for SqliteStore {
             "#,
         )
         .bind(hash.as_str())
-        .bind(match status {
-            BlobCacheStatus::Missing => "missing",
-            BlobCacheStatus::Available => "available",
-            BlobCacheStatus::Pinned => "pinned",
-        })
+        .bind(blob_cache_status_name(&status))
         .execute(&self.pool)
         .await?;
         Ok(())
@@ -36,11 +33,7 @@ impl BlobCacheStore for SqliteStore {
                 "#,
             )
             .bind(hash.as_str())
-            .bind(match status {
-                BlobCacheStatus::Missing => "missing",
-                BlobCacheStatus::Available => "available",
-                BlobCacheStatus::Pinned => "pinned",
-            })
+            .bind(blob_cache_status_name(&status))
             .execute(&mut *tx)
             .await?;
         }
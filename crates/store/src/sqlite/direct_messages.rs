@@ -377,9 +377,362 @@ impl DirectMessageStore for SqliteStore {
         .bind(message_id)
         .execute(&self.pool)
         .await?;
+        self.remove_direct_message_search_documents(dm_id, Some(message_id))
+            .await?;
         Ok(())
     }
 
+    async fn get_direct_message_session(
+        &self,
+        dm_id: &str,
+    ) -> Result<Option<DirectMessageSessionRow>> {
+        let row = sqlx::query(
+            r#"
+            SELECT dm_id, peer_pubkey, peer_frame_version, ratchet_state_json,
+                   local_prekey_secret_hex, peer_prekey_pubkey, updated_at
+            FROM dm_sessions
+            WHERE dm_id = ?1
+            "#,
+        )
+        .bind(dm_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        row.map(row_to_direct_message_session).transpose()
+    }
+
+    async fn put_direct_message_session(&self, row: DirectMessageSessionRow) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO dm_sessions (
+              dm_id, peer_pubkey, peer_frame_version, ratchet_state_json,
+              local_prekey_secret_hex, peer_prekey_pubkey, updated_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT(dm_id) DO UPDATE SET
+              peer_pubkey = excluded.peer_pubkey,
+              peer_frame_version = excluded.peer_frame_version,
+              ratchet_state_json = excluded.ratchet_state_json,
+              local_prekey_secret_hex = excluded.local_prekey_secret_hex,
+              peer_prekey_pubkey = excluded.peer_prekey_pubkey,
+              updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(row.dm_id.as_str())
+        .bind(row.peer_pubkey.as_str())
+        .bind(i64::from(row.peer_frame_version))
+        .bind(row.ratchet_state_json.as_deref())
+        .bind(row.local_prekey_secret_hex.as_deref())
+        .bind(row.peer_prekey_pubkey.as_deref())
+        .bind(row.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn put_direct_message_skipped_keys(
+        &self,
+        rows: Vec<DirectMessageSkippedKeyRow>,
+    ) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.pool.begin().await?;
+        for row in rows {
+            sqlx::query(
+                r#"
+                INSERT INTO dm_skipped_message_keys (
+                  dm_id, ratchet_pubkey, message_number, message_key_hex, created_at
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                ON CONFLICT(dm_id, ratchet_pubkey, message_number) DO NOTHING
+                "#,
+            )
+            .bind(row.dm_id.as_str())
+            .bind(row.ratchet_pubkey.as_str())
+            .bind(i64::from(row.message_number))
+            .bind(row.message_key_hex.as_str())
+            .bind(row.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn take_direct_message_skipped_key(
+        &self,
+        dm_id: &str,
+        ratchet_pubkey: &str,
+        message_number: u32,
+    ) -> Result<Option<String>> {
+        let key = sqlx::query_scalar::<_, String>(
+            r#"
+            DELETE FROM dm_skipped_message_keys
+            WHERE dm_id = ?1 AND ratchet_pubkey = ?2 AND message_number = ?3
+            RETURNING message_key_hex
+            "#,
+        )
+        .bind(dm_id)
+        .bind(ratchet_pubkey)
+        .bind(i64::from(message_number))
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(key)
+    }
+
+    async fn prune_direct_message_skipped_keys(&self, dm_id: &str, max_keys: usize) -> Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM dm_skipped_message_keys
+            WHERE dm_id = ?1
+              AND (ratchet_pubkey, message_number) NOT IN (
+                SELECT ratchet_pubkey, message_number
+                FROM dm_skipped_message_keys
+                WHERE dm_id = ?1
+                ORDER BY created_at DESC, ratchet_pubkey DESC, message_number DESC
+                LIMIT ?2
+              )
+            "#,
+        )
+        .bind(dm_id)
+        .bind(i64::try_from(max_keys).unwrap_or(i64::MAX))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn upsert_group_direct_message_conversation(
+        &self,
+        row: GroupDirectMessageConversationRow,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO group_dm_conversations (
+              group_id, owner_pubkey, title, epoch, members_json, removed_at, updated_at,
+              last_message_at, last_message_id, last_message_preview
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            ON CONFLICT(group_id) DO UPDATE SET
+              owner_pubkey = excluded.owner_pubkey,
+              title = excluded.title,
+              epoch = excluded.epoch,
+              members_json = excluded.members_json,
+              removed_at = excluded.removed_at,
+              updated_at = excluded.updated_at,
+              last_message_at = excluded.last_message_at,
+              last_message_id = excluded.last_message_id,
+              last_message_preview = excluded.last_message_preview
+            "#,
+        )
+        .bind(row.group_id.as_str())
+        .bind(row.owner_pubkey.as_str())
+        .bind(row.title.as_deref())
+        .bind(i64::try_from(row.epoch)?)
+        .bind(serde_json::to_string(&row.members)?)
+        .bind(row.removed_at)
+        .bind(row.updated_at)
+        .bind(row.last_message_at)
+        .bind(row.last_message_id.as_deref())
+        .bind(row.last_message_preview.as_deref())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_group_direct_message_conversation(
+        &self,
+        group_id: &str,
+    ) -> Result<Option<GroupDirectMessageConversationRow>> {
+        let row = sqlx::query(
+            r#"
+            SELECT group_id, owner_pubkey, title, epoch, members_json, removed_at, updated_at,
+                   last_message_at, last_message_id, last_message_preview
+            FROM group_dm_conversations
+            WHERE group_id = ?1
+            "#,
+        )
+        .bind(group_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        row.map(row_to_group_direct_message_conversation)
+            .transpose()
+    }
+
+    async fn list_group_direct_message_conversations(
+        &self,
+    ) -> Result<Vec<GroupDirectMessageConversationRow>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT group_id, owner_pubkey, title, epoch, members_json, removed_at, updated_at,
+                   last_message_at, last_message_id, last_message_preview
+            FROM group_dm_conversations
+            ORDER BY updated_at DESC, group_id DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter()
+            .map(row_to_group_direct_message_conversation)
+            .collect()
+    }
+
+    async fn put_group_direct_message_epoch(&self, row: GroupDirectMessageEpochRow) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO group_dm_epochs (group_id, epoch, epoch_secret_hex, members_json, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(group_id, epoch) DO NOTHING
+            "#,
+        )
+        .bind(row.group_id.as_str())
+        .bind(i64::try_from(row.epoch)?)
+        .bind(row.epoch_secret_hex.as_str())
+        .bind(serde_json::to_string(&row.members)?)
+        .bind(row.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_group_direct_message_epoch(
+        &self,
+        group_id: &str,
+        epoch: u64,
+    ) -> Result<Option<GroupDirectMessageEpochRow>> {
+        let row = sqlx::query(
+            r#"
+            SELECT group_id, epoch, epoch_secret_hex, members_json, created_at
+            FROM group_dm_epochs
+            WHERE group_id = ?1 AND epoch = ?2
+            "#,
+        )
+        .bind(group_id)
+        .bind(i64::try_from(epoch)?)
+        .fetch_optional(&self.pool)
+        .await?;
+        row.map(row_to_group_direct_message_epoch).transpose()
+    }
+
+    async fn put_group_direct_message_outbox(
+        &self,
+        row: GroupDirectMessageOutboxRow,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO group_dm_outbox (
+              group_id, message_id, frame_blob_hash, created_at, last_attempt_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(group_id, message_id) DO UPDATE SET
+              frame_blob_hash = excluded.frame_blob_hash,
+              created_at = excluded.created_at,
+              last_attempt_at = excluded.last_attempt_at
+            "#,
+        )
+        .bind(row.group_id.as_str())
+        .bind(row.message_id.as_str())
+        .bind(row.frame_blob_hash.as_str())
+        .bind(row.created_at)
+        .bind(row.last_attempt_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_group_direct_message_outbox(
+        &self,
+        group_id: &str,
+    ) -> Result<Vec<GroupDirectMessageOutboxRow>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT group_id, message_id, frame_blob_hash, created_at, last_attempt_at
+            FROM group_dm_outbox
+            WHERE group_id = ?1
+            ORDER BY created_at ASC, message_id ASC
+            "#,
+        )
+        .bind(group_id)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter()
+            .map(row_to_group_direct_message_outbox)
+            .collect()
+    }
+
+    async fn touch_group_direct_message_outbox_attempt(
+        &self,
+        group_id: &str,
+        message_id: &str,
+        attempted_at: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE group_dm_outbox
+            SET last_attempt_at = ?3
+            WHERE group_id = ?1 AND message_id = ?2
+            "#,
+        )
+        .bind(group_id)
+        .bind(message_id)
+        .bind(attempted_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn remove_group_direct_message_outbox(
+        &self,
+        group_id: &str,
+        message_id: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM group_dm_outbox
+            WHERE group_id = ?1 AND message_id = ?2
+            "#,
+        )
+        .bind(group_id)
+        .bind(message_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn put_group_direct_message_ack(
+        &self,
+        group_id: &str,
+        message_id: &str,
+        member_pubkey: &str,
+        acked_at: i64,
+    ) -> Result<Vec<String>> {
+        sqlx::query(
+            r#"
+            INSERT INTO group_dm_acks (group_id, message_id, member_pubkey, acked_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(group_id, message_id, member_pubkey) DO NOTHING
+            "#,
+        )
+        .bind(group_id)
+        .bind(message_id)
+        .bind(member_pubkey)
+        .bind(acked_at)
+        .execute(&self.pool)
+        .await?;
+        let members = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT member_pubkey
+            FROM group_dm_acks
+            WHERE group_id = ?1 AND message_id = ?2
+            ORDER BY member_pubkey ASC
+            "#,
+        )
+        .bind(group_id)
+        .bind(message_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(members)
+    }
+
     async fn clear_direct_message_local(&self, dm_id: &str) -> Result<()> {
         sqlx::query(
             r#"
@@ -399,6 +752,15 @@ impl DirectMessageStore for SqliteStore {
         .bind(dm_id)
         .execute(&self.pool)
         .await?;
+        sqlx::query(
+            r#"
+            DELETE FROM group_dm_outbox
+            WHERE group_id = ?1
+            "#,
+        )
+        .bind(dm_id)
+        .execute(&self.pool)
+        .await?;
         sqlx::query(
             r#"
             DELETE FROM dm_conversations
@@ -408,6 +770,8 @@ impl DirectMessageStore for SqliteStore {
         .bind(dm_id)
         .execute(&self.pool)
         .await?;
+        self.remove_direct_message_search_documents(dm_id, None)
+            .await?;
         Ok(())
     }
 }
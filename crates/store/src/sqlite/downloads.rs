@@ -0,0 +1,193 @@
+use super::*;
+use crate::row_mapping::{download_job_kind_name, download_job_status_name, row_to_download_job};
+
+#[async_trait]
+impl DownloadJobStore for SqliteStore {
+    async fn enqueue_download_job(
+        &self,
+        blob_hash: &BlobHash,
+        kind: DownloadJobKind,
+        priority: i64,
+        now: i64,
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        let exists = sqlx::query("SELECT 1 FROM download_jobs WHERE blob_hash = ?1")
+            .bind(blob_hash.as_str())
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some();
+        sqlx::query(
+            r#"
+            INSERT INTO download_jobs (
+              blob_hash, status, kind, priority, attempts, next_attempt_at, last_error,
+              enqueued_at, updated_at
+            )
+            VALUES (?1, 'queued', ?2, ?3, 0, ?4, NULL, ?4, ?4)
+            ON CONFLICT(blob_hash) DO UPDATE SET
+              priority = MAX(download_jobs.priority, excluded.priority),
+              updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(blob_hash.as_str())
+        .bind(download_job_kind_name(kind))
+        .bind(priority)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(!exists)
+    }
+
+    async fn retry_download_job(&self, blob_hash: &BlobHash, now: i64) -> Result<bool> {
+        let retried = sqlx::query(
+            r#"
+            UPDATE download_jobs
+            SET status = 'queued', attempts = 0, updated_at = ?2
+            WHERE blob_hash = ?1 AND status = 'failed'
+            "#,
+        )
+        .bind(blob_hash.as_str())
+        .bind(now)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        Ok(retried > 0)
+    }
+
+    async fn claim_download_jobs(&self, now: i64, limit: usize) -> Result<Vec<DownloadJobRow>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut tx = self.pool.begin().await?;
+        let rows = sqlx::query(
+            r#"
+            SELECT blob_hash, status, kind, priority, attempts, next_attempt_at, last_error,
+                   enqueued_at, updated_at
+            FROM download_jobs
+            WHERE status = 'queued' AND next_attempt_at <= ?1
+            ORDER BY priority DESC, enqueued_at ASC, blob_hash ASC
+            LIMIT ?2
+            "#,
+        )
+        .bind(now)
+        .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+        .fetch_all(&mut *tx)
+        .await?;
+        let mut jobs = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut job = row_to_download_job(row)?;
+            sqlx::query(
+                r#"
+                UPDATE download_jobs
+                SET status = ?2, updated_at = ?3
+                WHERE blob_hash = ?1
+                "#,
+            )
+            .bind(job.blob_hash.as_str())
+            .bind(download_job_status_name(DownloadJobStatus::Active))
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+            job.status = DownloadJobStatus::Active;
+            job.updated_at = now;
+            jobs.push(job);
+        }
+        tx.commit().await?;
+        Ok(jobs)
+    }
+
+    async fn complete_download_job(&self, blob_hash: &BlobHash) -> Result<()> {
+        sqlx::query("DELETE FROM download_jobs WHERE blob_hash = ?1")
+            .bind(blob_hash.as_str())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn fail_download_job(
+        &self,
+        blob_hash: &BlobHash,
+        error: &str,
+        next_attempt_at: i64,
+        exhausted: bool,
+        now: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE download_jobs
+            SET attempts = attempts + 1,
+                last_error = ?2,
+                next_attempt_at = ?3,
+                status = ?4,
+                updated_at = ?5
+            WHERE blob_hash = ?1
+            "#,
+        )
+        .bind(blob_hash.as_str())
+        .bind(error)
+        .bind(next_attempt_at)
+        .bind(download_job_status_name(if exhausted {
+            DownloadJobStatus::Failed
+        } else {
+            DownloadJobStatus::Queued
+        }))
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn requeue_active_download_jobs(&self, now: i64) -> Result<usize> {
+        let requeued = sqlx::query(
+            r#"
+            UPDATE download_jobs
+            SET status = 'queued', updated_at = ?1
+            WHERE status = 'active'
+            "#,
+        )
+        .bind(now)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        Ok(usize::try_from(requeued).unwrap_or(usize::MAX))
+    }
+
+    async fn get_download_job(&self, blob_hash: &BlobHash) -> Result<Option<DownloadJobRow>> {
+        sqlx::query(
+            r#"
+            SELECT blob_hash, status, kind, priority, attempts, next_attempt_at, last_error,
+                   enqueued_at, updated_at
+            FROM download_jobs
+            WHERE blob_hash = ?1
+            "#,
+        )
+        .bind(blob_hash.as_str())
+        .fetch_optional(&self.pool)
+        .await?
+        .map(row_to_download_job)
+        .transpose()
+    }
+
+    async fn summarize_download_jobs(&self) -> Result<DownloadQueueSummary> {
+        let rows = sqlx::query(
+            r#"
+            SELECT status, COUNT(*) AS count
+            FROM download_jobs
+            GROUP BY status
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let mut summary = DownloadQueueSummary::default();
+        for row in rows {
+            let count = usize::try_from(row.try_get::<i64, _>("count")?).unwrap_or(0);
+            match row.try_get::<String, _>("status")?.as_str() {
+                "queued" => summary.queued = count,
+                "active" => summary.active = count,
+                "failed" => summary.failed = count,
+                _ => {}
+            }
+        }
+        Ok(summary)
+    }
+}
@@ -11,11 +11,16 @@ use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use sqlx::{Pool, QueryBuilder, Row, Sqlite};
 
 use crate::models::{
-    AuthorRelationshipProjectionRow, BlobCacheStatus, BookmarkedCustomReactionRow,
-    BookmarkedPostRow, ContentObservationRow, DirectMessageConversationRow,
-    DirectMessageMessageRow, DirectMessageOutboxRow, DirectMessageTombstoneRow,
-    GameRoomProjectionRow, LiveSessionProjectionRow, MutedAuthorRow, NotificationRow,
-    ObjectProjectionRow, Page, ReactionProjectionRow, TimelineCursor,
+    AuthorRelationshipProjectionRow, BlobCacheStatus, BlobRetentionRow,
+    BookmarkedCustomReactionRow, BookmarkedPostRow, CommunityModerationEventRow,
+    CommunityModerationPolicyRow, CommunityModerationSyncRow, ContentObservationRow,
+    DirectMessageConversationRow, DirectMessageMessageRow, DirectMessageOutboxRow,
+    DirectMessageSessionRow, DirectMessageSkippedKeyRow, DirectMessageTombstoneRow,
+    DownloadJobKind, DownloadJobRow, DownloadJobStatus, DownloadQueueSummary,
+    GameRoomProjectionRow, GroupDirectMessageConversationRow, GroupDirectMessageEpochRow,
+    GroupDirectMessageOutboxRow, LiveSessionProjectionRow, LocalSearchDocumentRow,
+    LocalSearchQuery, MutedAuthorRow, NotificationRow, ObjectProjectionRow, Page,
+    ReactionProjectionRow, ReplicaCursor, TimelineCursor,
 };
 use crate::pagination::{
     direct_message_page_from_rows, envelope_page_from_rows, object_projection_page_from_rows,
@@ -25,23 +30,31 @@ use crate::row_mapping::{
     notification_kind_name, object_status_name, reaction_key_kind_name,
     row_to_author_relationship_projection, row_to_bookmarked_custom_reaction,
     row_to_bookmarked_post, row_to_direct_message_conversation, row_to_direct_message_message,
-    row_to_direct_message_outbox, row_to_direct_message_tombstone, row_to_envelope,
-    row_to_follow_edge, row_to_game_room_projection, row_to_live_session_projection,
-    row_to_muted_author, row_to_notification, row_to_object_projection, row_to_reaction_projection,
+    row_to_direct_message_outbox, row_to_direct_message_session, row_to_direct_message_tombstone,
+    row_to_envelope, row_to_follow_edge, row_to_game_room_projection,
+    row_to_group_direct_message_conversation, row_to_group_direct_message_epoch,
+    row_to_group_direct_message_outbox, row_to_live_session_projection, row_to_muted_author,
+    row_to_notification, row_to_object_projection, row_to_reaction_projection,
 };
 use crate::traits::{
-    BlobCacheStore, ContentObservationStore, DirectMessageStore, LiveGameProjectionStore,
-    NotificationStore, ObjectProjectionStore, ReactionBookmarkStore, SocialProjectionStore, Store,
+    BlobCacheStore, BlobRetentionStore, CommunityModerationStore, ContentObservationStore,
+    DirectMessageStore, DownloadJobStore, LiveGameProjectionStore, NotificationStore,
+    ObjectProjectionStore, ReactionBookmarkStore, ReplicaCursorStore, SocialProjectionStore, Store,
 };
 
+mod blob_retention;
 mod bookmarks;
 mod connection;
 mod direct_messages;
+mod downloads;
 mod envelopes;
 mod live_game;
+mod moderation;
 mod notifications;
 mod observations;
 mod projections;
+mod replica_cursors;
+mod search;
 mod social;
 
 pub use connection::StoreStartupError;
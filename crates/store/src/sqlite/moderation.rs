@@ -0,0 +1,158 @@
+use super::*;
+use crate::row_mapping::{
+    community_moderation_policy_name, row_to_community_moderation_event,
+    row_to_community_moderation_policy, row_to_community_moderation_sync,
+};
+
+const MAX_COMMUNITY_MODERATION_EVENTS: i64 = 4096;
+
+#[async_trait]
+impl CommunityModerationStore for SqliteStore {
+    async fn put_community_moderation_event(
+        &self,
+        row: CommunityModerationEventRow,
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO community_moderation_events (
+              node_base_url, event_id, issuer_node_id, target_kind, target_id, action,
+              labels_json, reason_code, signed_event_json, received_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            ON CONFLICT(node_base_url, event_id) DO NOTHING
+            "#,
+        )
+        .bind(row.node_base_url.as_str())
+        .bind(row.event_id.as_str())
+        .bind(row.issuer_node_id.as_str())
+        .bind(row.target_kind.as_str())
+        .bind(row.target_id.as_str())
+        .bind(row.action.as_str())
+        .bind(serde_json::to_string(&row.labels)?)
+        .bind(row.reason_code.as_str())
+        .bind(row.signed_event_json.as_str())
+        .bind(row.received_at)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+        sqlx::query(
+            r#"
+            DELETE FROM community_moderation_events
+            WHERE rowid IN (
+              SELECT rowid FROM community_moderation_events
+              ORDER BY received_at DESC, rowid DESC
+              LIMIT -1 OFFSET ?1
+            )
+            "#,
+        )
+        .bind(MAX_COMMUNITY_MODERATION_EVENTS)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    async fn list_community_moderation_events_for_targets(
+        &self,
+        target_kind: &str,
+        target_ids: &[String],
+    ) -> Result<Vec<CommunityModerationEventRow>> {
+        if target_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            r#"
+            SELECT node_base_url, event_id, issuer_node_id, target_kind, target_id, action,
+                   labels_json, reason_code, signed_event_json, received_at
+            FROM community_moderation_events
+            WHERE target_kind = "#,
+        );
+        builder.push_bind(target_kind);
+        builder.push(" AND target_id IN (");
+        let mut separated = builder.separated(", ");
+        for target_id in target_ids {
+            separated.push_bind(target_id.as_str());
+        }
+        separated.push_unseparated(")");
+        builder.push(" ORDER BY received_at ASC, node_base_url ASC, event_id ASC");
+        let rows = builder.build().fetch_all(&self.pool).await?;
+        rows.into_iter()
+            .map(row_to_community_moderation_event)
+            .collect()
+    }
+
+    async fn put_community_moderation_policy(
+        &self,
+        row: CommunityModerationPolicyRow,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO community_moderation_policies (node_base_url, policy, updated_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(node_base_url) DO UPDATE SET
+              policy = excluded.policy,
+              updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(row.node_base_url.as_str())
+        .bind(community_moderation_policy_name(row.policy))
+        .bind(row.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_community_moderation_policies(
+        &self,
+    ) -> Result<Vec<CommunityModerationPolicyRow>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT node_base_url, policy, updated_at
+            FROM community_moderation_policies
+            ORDER BY node_base_url ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter()
+            .map(row_to_community_moderation_policy)
+            .collect()
+    }
+
+    async fn get_community_moderation_sync(
+        &self,
+        node_base_url: &str,
+    ) -> Result<Option<CommunityModerationSyncRow>> {
+        let row = sqlx::query(
+            r#"
+            SELECT node_base_url, backfill_cursor, updated_at
+            FROM community_moderation_sync
+            WHERE node_base_url = ?1
+            "#,
+        )
+        .bind(node_base_url)
+        .fetch_optional(&self.pool)
+        .await?;
+        row.map(row_to_community_moderation_sync).transpose()
+    }
+
+    async fn put_community_moderation_sync(&self, row: CommunityModerationSyncRow) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO community_moderation_sync (node_base_url, backfill_cursor, updated_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(node_base_url) DO UPDATE SET
+              backfill_cursor = excluded.backfill_cursor,
+              updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(row.node_base_url.as_str())
+        .bind(row.backfill_cursor.as_deref())
+        .bind(row.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
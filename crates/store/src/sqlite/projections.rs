@@ -20,15 +20,32 @@ impl ObjectProjectionStore for SqliteStore {
                 .as_ref()
                 .map(serde_json::to_string)
                 .transpose()?;
+            // 本文や添付が変わる版は、以前の版を revision 履歴の参照として残す。
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO object_revision_blobs (
+                  object_id, payload_ref_json, attachments_json
+                )
+                SELECT object_id, payload_ref_json, attachments_json
+                FROM object_index_cache
+                WHERE object_id = ?1
+                  AND (payload_ref_json <> ?2 OR attachments_json <> ?3)
+                "#,
+            )
+            .bind(row.object_id.as_str())
+            .bind(payload_json.as_str())
+            .bind(attachments_json.as_str())
+            .execute(&mut *tx)
+            .await?;
             sqlx::query(
                 r#"
                 INSERT INTO object_index_cache (
                   object_id, topic_id, channel_id, author_pubkey, created_at, object_kind,
                   root_object_id, reply_to_object_id, payload_ref_json, content, attachments_json,
                   repost_of_json, source_replica_id, source_key, source_envelope_id,
-                  source_blob_hash, derived_at, projection_version
+                  source_blob_hash, derived_at, projection_version, status
                 )
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)
                 ON CONFLICT(object_id) DO UPDATE SET
                   topic_id = excluded.topic_id,
                   channel_id = excluded.channel_id,
@@ -46,7 +63,8 @@ impl ObjectProjectionStore for SqliteStore {
                   source_envelope_id = excluded.source_envelope_id,
                   source_blob_hash = excluded.source_blob_hash,
                   derived_at = excluded.derived_at,
-                  projection_version = excluded.projection_version
+                  projection_version = excluded.projection_version,
+                  status = excluded.status
                 "#,
             )
             .bind(row.object_id.as_str())
@@ -67,6 +85,7 @@ impl ObjectProjectionStore for SqliteStore {
             .bind(row.source_blob_hash.as_ref().map(BlobHash::as_str))
             .bind(row.derived_at)
             .bind(row.projection_version)
+            .bind(object_status_name(&row.status))
             .execute(&mut *tx)
             .await?;
 
@@ -109,7 +128,7 @@ impl ObjectProjectionStore for SqliteStore {
             SELECT object_id, topic_id, author_pubkey, created_at, object_kind, root_object_id,
                    reply_to_object_id, channel_id, payload_ref_json, content, attachments_json,
                    repost_of_json, source_replica_id, source_key, source_envelope_id,
-                   source_blob_hash, derived_at, projection_version
+                   source_blob_hash, derived_at, projection_version, status
             FROM object_index_cache
             WHERE object_id = ?1
             "#,
@@ -132,7 +151,7 @@ impl ObjectProjectionStore for SqliteStore {
             SELECT object_id, topic_id, author_pubkey, created_at, object_kind, root_object_id,
                    reply_to_object_id, channel_id, payload_ref_json, content, attachments_json,
                    repost_of_json, source_replica_id, source_key, source_envelope_id,
-                   source_blob_hash, derived_at, projection_version
+                   source_blob_hash, derived_at, projection_version, status
             FROM object_index_cache
             WHERE topic_id = ?1
               AND (
@@ -173,7 +192,7 @@ impl ObjectProjectionStore for SqliteStore {
             SELECT object_id, topic_id, author_pubkey, created_at, object_kind, root_object_id,
                    reply_to_object_id, channel_id, payload_ref_json, content, attachments_json,
                    repost_of_json, source_replica_id, source_key, source_envelope_id,
-                   source_blob_hash, derived_at, projection_version
+                   source_blob_hash, derived_at, projection_version, status
             FROM object_index_cache
             WHERE topic_id = "#,
         );
@@ -227,7 +246,7 @@ impl ObjectProjectionStore for SqliteStore {
                    oic.root_object_id, oic.reply_to_object_id, oic.channel_id,
                    oic.payload_ref_json, oic.content, oic.attachments_json, oic.repost_of_json,
                    oic.source_replica_id, oic.source_key, oic.source_envelope_id,
-                   oic.source_blob_hash, oic.derived_at, oic.projection_version
+                   oic.source_blob_hash, oic.derived_at, oic.projection_version, oic.status
             FROM object_thread_cache tc
             INNER JOIN object_index_cache oic ON oic.object_id = tc.object_id
             WHERE tc.topic_id = ?1
@@ -280,7 +299,7 @@ impl ObjectProjectionStore for SqliteStore {
                    oic.root_object_id, oic.reply_to_object_id, oic.channel_id,
                    oic.payload_ref_json, oic.content, oic.attachments_json, oic.repost_of_json,
                    oic.source_replica_id, oic.source_key, oic.source_envelope_id,
-                   oic.source_blob_hash, oic.derived_at, oic.projection_version
+                   oic.source_blob_hash, oic.derived_at, oic.projection_version, oic.status
             FROM object_thread_cache tc
             INNER JOIN object_index_cache oic ON oic.object_id = tc.object_id
             WHERE tc.topic_id = ?1
@@ -330,6 +349,9 @@ impl ObjectProjectionStore for SqliteStore {
         sqlx::query("DELETE FROM reaction_cache")
             .execute(&mut *tx)
             .await?;
+        sqlx::query("DELETE FROM replica_cursors")
+            .execute(&mut *tx)
+            .await?;
         tx.commit().await?;
         self.put_object_projections(rows).await?;
         sqlx::query(
@@ -344,6 +366,27 @@ impl ObjectProjectionStore for SqliteStore {
         )
         .execute(&self.pool)
         .await?;
+        self.prune_orphan_post_search_documents().await?;
         Ok(())
     }
+
+    async fn put_local_search_documents(&self, rows: Vec<LocalSearchDocumentRow>) -> Result<()> {
+        self.put_local_search_documents_impl(rows).await
+    }
+
+    async fn remove_local_search_document(
+        &self,
+        subject_kind: &str,
+        subject_id: &str,
+    ) -> Result<()> {
+        self.remove_local_search_document_impl(subject_kind, subject_id)
+            .await
+    }
+
+    async fn search_local_documents(
+        &self,
+        query: &LocalSearchQuery,
+    ) -> Result<Vec<LocalSearchDocumentRow>> {
+        self.search_local_documents_impl(query).await
+    }
 }
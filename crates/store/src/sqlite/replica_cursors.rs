@@ -0,0 +1,55 @@
+use super::*;
+
+#[async_trait]
+impl ReplicaCursorStore for SqliteStore {
+    async fn get_replica_cursor(&self, replica_id: &ReplicaId) -> Result<Option<ReplicaCursor>> {
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT author_id, watermark FROM replica_cursors WHERE replica_id = ?1",
+        )
+        .bind(replica_id.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+        if rows.is_empty() {
+            return Ok(None);
+        }
+        let mut cursor = ReplicaCursor::default();
+        for (author_id, watermark) in rows {
+            let watermark = u64::try_from(watermark)
+                .map_err(|_| anyhow::anyhow!("invalid replica cursor watermark `{watermark}`"))?;
+            cursor.watermarks.insert(author_id, watermark);
+        }
+        Ok(Some(cursor))
+    }
+
+    async fn put_replica_cursor(
+        &self,
+        replica_id: &ReplicaId,
+        cursor: &ReplicaCursor,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for (author_id, watermark) in &cursor.watermarks {
+            sqlx::query(
+                r#"
+                INSERT INTO replica_cursors (replica_id, author_id, watermark)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT(replica_id, author_id) DO UPDATE SET watermark = excluded.watermark
+                "#,
+            )
+            .bind(replica_id.as_str())
+            .bind(author_id.as_str())
+            .bind(i64::try_from(*watermark)?)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn clear_replica_cursor(&self, replica_id: &ReplicaId) -> Result<()> {
+        sqlx::query("DELETE FROM replica_cursors WHERE replica_id = ?1")
+            .bind(replica_id.as_str())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+use super::*;
+
+/// LIKE パターン内の `\` / `%` / `_` を literal として扱うためのエスケープ。
+fn escape_like_term(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len() + 2);
+    escaped.push('%');
+    for ch in term.chars() {
+        if matches!(ch, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped.push('%');
+    escaped
+}
+
+impl SqliteStore {
+    pub(super) async fn put_local_search_documents_impl(
+        &self,
+        rows: Vec<LocalSearchDocumentRow>,
+    ) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await?;
+        for row in rows {
+            let existing_doc_id = sqlx::query_scalar::<_, i64>(
+                r#"
+                SELECT doc_id FROM local_search_documents
+                WHERE subject_kind = ?1 AND subject_id = ?2
+                "#,
+            )
+            .bind(row.subject_kind.as_str())
+            .bind(row.subject_id.as_str())
+            .fetch_optional(&mut *tx)
+            .await?;
+            if let Some(doc_id) = existing_doc_id {
+                sqlx::query("DELETE FROM local_search_fts WHERE rowid = ?1")
+                    .bind(doc_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            if row.body.trim().is_empty() {
+                sqlx::query(
+                    r#"
+                    DELETE FROM local_search_documents
+                    WHERE subject_kind = ?1 AND subject_id = ?2
+                    "#,
+                )
+                .bind(row.subject_kind.as_str())
+                .bind(row.subject_id.as_str())
+                .execute(&mut *tx)
+                .await?;
+                continue;
+            }
+
+            let doc_id = sqlx::query_scalar::<_, i64>(
+                r#"
+                INSERT INTO local_search_documents (
+                  subject_kind, subject_id, topic_id, channel_id, dm_id, author_pubkey, created_at
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                ON CONFLICT(subject_kind, subject_id) DO UPDATE SET
+                  topic_id = excluded.topic_id,
+                  channel_id = excluded.channel_id,
+                  dm_id = excluded.dm_id,
+                  author_pubkey = excluded.author_pubkey,
+                  created_at = excluded.created_at
+                RETURNING doc_id
+                "#,
+            )
+            .bind(row.subject_kind.as_str())
+            .bind(row.subject_id.as_str())
+            .bind(row.topic_id.as_deref())
+            .bind(row.channel_id.as_deref())
+            .bind(row.dm_id.as_deref())
+            .bind(row.author_pubkey.as_str())
+            .bind(row.created_at)
+            .fetch_one(&mut *tx)
+            .await?;
+            sqlx::query("INSERT INTO local_search_fts (rowid, body) VALUES (?1, ?2)")
+                .bind(doc_id)
+                .bind(row.body.as_str())
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub(super) async fn remove_local_search_document_impl(
+        &self,
+        subject_kind: &str,
+        subject_id: &str,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            DELETE FROM local_search_fts
+            WHERE rowid IN (
+              SELECT doc_id FROM local_search_documents
+              WHERE subject_kind = ?1 AND subject_id = ?2
+            )
+            "#,
+        )
+        .bind(subject_kind)
+        .bind(subject_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            DELETE FROM local_search_documents
+            WHERE subject_kind = ?1 AND subject_id = ?2
+            "#,
+        )
+        .bind(subject_kind)
+        .bind(subject_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// DM 削除経路から呼ぶ。`message_id` が None なら会話全体の文書を消す。
+    pub(super) async fn remove_direct_message_search_documents(
+        &self,
+        dm_id: &str,
+        message_id: Option<&str>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            DELETE FROM local_search_fts
+            WHERE rowid IN (
+              SELECT doc_id FROM local_search_documents
+              WHERE subject_kind = 'direct_message'
+                AND dm_id = ?1
+                AND (?2 IS NULL OR subject_id = ?2)
+            )
+            "#,
+        )
+        .bind(dm_id)
+        .bind(message_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            DELETE FROM local_search_documents
+            WHERE subject_kind = 'direct_message'
+              AND dm_id = ?1
+              AND (?2 IS NULL OR subject_id = ?2)
+            "#,
+        )
+        .bind(dm_id)
+        .bind(message_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// projection rebuild 後に object_index_cache から消えた post の文書を落とす。
+    pub(super) async fn prune_orphan_post_search_documents(&self) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            DELETE FROM local_search_fts
+            WHERE rowid IN (
+              SELECT doc_id FROM local_search_documents
+              WHERE subject_kind = 'post'
+                AND NOT EXISTS (
+                  SELECT 1 FROM object_index_cache
+                  WHERE object_index_cache.object_id = local_search_documents.subject_id
+                )
+            )
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            DELETE FROM local_search_documents
+            WHERE subject_kind = 'post'
+              AND NOT EXISTS (
+                SELECT 1 FROM object_index_cache
+                WHERE object_index_cache.object_id = local_search_documents.subject_id
+              )
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub(super) async fn search_local_documents_impl(
+        &self,
+        query: &LocalSearchQuery,
+    ) -> Result<Vec<LocalSearchDocumentRow>> {
+        let terms = query.terms();
+        if terms.is_empty() || query.limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            r#"
+            SELECT d.subject_kind, d.subject_id, d.topic_id, d.channel_id, d.dm_id,
+                   d.author_pubkey, d.created_at, f.body
+            FROM local_search_fts f
+            INNER JOIN local_search_documents d ON d.doc_id = f.rowid
+            WHERE 1 = 1"#,
+        );
+        for term in terms {
+            builder.push(" AND f.body LIKE ");
+            builder.push_bind(escape_like_term(term));
+            builder.push(" ESCAPE '\\'");
+        }
+        if !query.subject_kinds.is_empty() {
+            builder.push(" AND d.subject_kind IN (");
+            let mut separated = builder.separated(", ");
+            for subject_kind in &query.subject_kinds {
+                separated.push_bind(subject_kind.as_str());
+            }
+            separated.push_unseparated(")");
+        }
+        if let Some(topic_id) = query.topic_id.as_deref() {
+            builder.push(" AND d.topic_id = ");
+            builder.push_bind(topic_id);
+        }
+        if let Some(channel_id) = query.channel_id.as_deref() {
+            builder.push(" AND d.channel_id = ");
+            builder.push_bind(channel_id);
+        }
+        if let Some(author_pubkey) = query.author_pubkey.as_deref() {
+            builder.push(" AND d.author_pubkey = ");
+            builder.push_bind(author_pubkey);
+        }
+        if let Some(created_after) = query.created_after {
+            builder.push(" AND d.created_at >= ");
+            builder.push_bind(created_after);
+        }
+        if let Some(created_before) = query.created_before {
+            builder.push(" AND d.created_at < ");
+            builder.push_bind(created_before);
+        }
+        if let Some((created_at, subject_id)) = query.before.as_ref() {
+            builder.push(" AND (d.created_at < ");
+            builder.push_bind(*created_at);
+            builder.push(" OR (d.created_at = ");
+            builder.push_bind(*created_at);
+            builder.push(" AND d.subject_id < ");
+            builder.push_bind(subject_id.as_str());
+            builder.push("))");
+        }
+        builder.push(" ORDER BY d.created_at DESC, d.subject_id DESC LIMIT ");
+        builder.push_bind(query.limit as i64);
+
+        let rows = builder.build().fetch_all(&self.pool).await?;
+        rows.into_iter()
+            .map(|row| {
+                Ok(LocalSearchDocumentRow {
+                    subject_kind: row.try_get("subject_kind")?,
+                    subject_id: row.try_get("subject_id")?,
+                    topic_id: row.try_get("topic_id")?,
+                    channel_id: row.try_get("channel_id")?,
+                    dm_id: row.try_get("dm_id")?,
+                    author_pubkey: row.try_get("author_pubkey")?,
+                    created_at: row.try_get("created_at")?,
+                    body: row.try_get("body")?,
+                })
+            })
+            .collect()
+    }
+}
@@ -95,6 +95,7 @@ fn parity_projection_row(
         content: Some(format!("content:{object_id}")),
         attachments: Vec::new(),
         repost_of: None,
+        status: ObjectStatus::Active,
         source_replica_id: ReplicaId::new(format!("topic::{topic_id}")),
         source_key: format!("objects/{object_id}/header"),
         source_envelope_id: EnvelopeId::from(object_id),
@@ -0,0 +1,166 @@
+use super::*;
+use kukuri_core::{AssetRef, AssetRole, Pubkey};
+
+fn hash(fill: char) -> BlobHash {
+    BlobHash::new(fill.to_string().repeat(64))
+}
+
+fn retention_projection_row(
+    object_id: &str,
+    status: ObjectStatus,
+    payload: char,
+) -> ObjectProjectionRow {
+    ObjectProjectionRow {
+        object_id: EnvelopeId::from(object_id),
+        topic_id: "kukuri:topic:retention".to_string(),
+        channel_id: "public".to_string(),
+        author_pubkey: "a".repeat(64),
+        created_at: 1,
+        object_kind: "post".to_string(),
+        root_object_id: None,
+        reply_to_object_id: None,
+        payload_ref: PayloadRef::BlobText {
+            hash: hash(payload),
+            mime: "text/plain".to_string(),
+            bytes: 4,
+        },
+        content: Some("body".to_string()),
+        attachments: vec![AssetRef {
+            hash: hash('b'),
+            mime: "image/png".to_string(),
+            bytes: 8,
+            role: AssetRole::ImageOriginal,
+        }],
+        repost_of: None,
+        status,
+        source_replica_id: ReplicaId::new("topic::retention"),
+        source_key: format!("objects/{object_id}/state"),
+        source_envelope_id: EnvelopeId::from(object_id),
+        source_blob_hash: None,
+        derived_at: 1,
+        projection_version: 2,
+    }
+}
+
+async fn blob_retention_scenario<S>(store: &S)
+where
+    S: BlobRetentionStore + BlobCacheStore + ObjectProjectionStore + SocialProjectionStore,
+{
+    store
+        .put_object_projection(retention_projection_row(
+            "post-active",
+            ObjectStatus::Active,
+            'a',
+        ))
+        .await
+        .unwrap();
+    store
+        .put_object_projection(retention_projection_row(
+            "post-deleted",
+            ObjectStatus::Tombstoned,
+            'c',
+        ))
+        .await
+        .unwrap();
+    store
+        .upsert_profile_cache(Profile {
+            pubkey: Pubkey::from("d".repeat(64)),
+            picture_asset: Some(AssetRef {
+                hash: hash('b'),
+                mime: "image/png".to_string(),
+                bytes: 8,
+                role: AssetRole::ProfileAvatar,
+            }),
+            updated_at: 1,
+            ..Profile::default()
+        })
+        .await
+        .unwrap();
+    let counts = store.blob_reference_counts().await.unwrap();
+    assert_eq!(counts.get(hash('a').as_str()), Some(&1));
+    assert_eq!(
+        counts.get(hash('b').as_str()),
+        Some(&2),
+        "生きている post の添付と profile 画像を 1 件ずつ数え、取り消し済み post の添付は数えない"
+    );
+    assert_eq!(counts.get(hash('c').as_str()), None);
+
+    store
+        .put_object_projection(retention_projection_row(
+            "post-edited",
+            ObjectStatus::Active,
+            'f',
+        ))
+        .await
+        .unwrap();
+    store
+        .put_object_projection(retention_projection_row(
+            "post-edited",
+            ObjectStatus::Edited,
+            'g',
+        ))
+        .await
+        .unwrap();
+    let counts = store.blob_reference_counts().await.unwrap();
+    assert_eq!(
+        counts.get(hash('f').as_str()),
+        Some(&1),
+        "編集前の版の本文も revision 履歴の参照として数える"
+    );
+    assert_eq!(counts.get(hash('g').as_str()), Some(&1));
+
+    store
+        .record_blob_access(&hash('a'), Some(10), 5)
+        .await
+        .unwrap();
+    store
+        .record_blob_access(&hash('b'), Some(20), 3)
+        .await
+        .unwrap();
+    store.record_blob_access(&hash('b'), None, 7).await.unwrap();
+    store.set_blob_pinned(&hash('a'), true).await.unwrap();
+    store
+        .mark_blob_status(&hash('e'), BlobCacheStatus::Missing)
+        .await
+        .unwrap();
+    let local = store.list_local_blobs().await.unwrap();
+    assert_eq!(
+        local
+            .iter()
+            .map(|row| (
+                row.blob_hash.as_str(),
+                row.bytes,
+                row.pinned,
+                row.last_accessed_at
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            (hash('a').as_str(), 10, true, 5),
+            (hash('b').as_str(), 20, false, 7),
+        ],
+        "未取得の blob は含めず、最終参照の古い順に並べる"
+    );
+
+    store.mark_blob_evicted(&hash('a')).await.unwrap();
+    let local = store.list_local_blobs().await.unwrap();
+    assert_eq!(local.len(), 1);
+    assert_eq!(local[0].blob_hash, hash('b'));
+
+    store
+        .record_blob_access(&hash('a'), Some(10), 9)
+        .await
+        .unwrap();
+    let refetched = store.list_local_blobs().await.unwrap();
+    assert!(
+        refetched
+            .iter()
+            .any(|row| row.blob_hash == hash('a') && !row.pinned),
+        "取り直した blob は pin されていない cache として戻る"
+    );
+}
+
+#[tokio::test]
+async fn blob_retention_tracks_usage_and_projection_references() {
+    blob_retention_scenario(&MemoryStore::default()).await;
+    blob_retention_scenario(&SqliteStore::connect_memory().await.unwrap()).await;
+}
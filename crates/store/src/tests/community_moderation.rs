@@ -0,0 +1,150 @@
+use super::*;
+
+fn moderation_event(
+    node_base_url: &str,
+    event_id: &str,
+    target_id: &str,
+) -> CommunityModerationEventRow {
+    CommunityModerationEventRow {
+        node_base_url: node_base_url.to_string(),
+        event_id: event_id.to_string(),
+        issuer_node_id: "b".repeat(64),
+        target_kind: "post".to_string(),
+        target_id: target_id.to_string(),
+        action: "risk_label".to_string(),
+        labels: vec!["spam".to_string()],
+        reason_code: "general_moderation".to_string(),
+        signed_event_json: format!(r#"{{"body":{{"id":"{event_id}"}}}}"#),
+        received_at: 10,
+    }
+}
+
+async fn community_moderation_scenario<S>(store: &S)
+where
+    S: CommunityModerationStore,
+{
+    let event = moderation_event("https://node-a.example", "event-1", "post-1");
+    assert!(
+        store
+            .put_community_moderation_event(event.clone())
+            .await
+            .unwrap()
+    );
+    let mut replayed = event.clone();
+    replayed.action = "exclude".to_string();
+    replayed.received_at = 20;
+    assert!(
+        !store
+            .put_community_moderation_event(replayed)
+            .await
+            .unwrap(),
+        "同じ node・event id の再受信は上書きしない"
+    );
+    let mut other_node = moderation_event("https://node-b.example", "event-1", "post-1");
+    other_node.received_at = 30;
+    assert!(
+        store
+            .put_community_moderation_event(other_node.clone())
+            .await
+            .unwrap()
+    );
+    store
+        .put_community_moderation_event(moderation_event(
+            "https://node-a.example",
+            "event-2",
+            "post-2",
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        store
+            .list_community_moderation_events_for_targets("post", &["post-1".to_string()])
+            .await
+            .unwrap(),
+        vec![event, other_node]
+    );
+    assert!(
+        store
+            .list_community_moderation_events_for_targets("user", &["post-1".to_string()])
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        store
+            .list_community_moderation_events_for_targets("post", &[])
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    for (policy, updated_at) in [
+        (CommunityModerationPolicy::Hide, 1),
+        (CommunityModerationPolicy::Blur, 2),
+    ] {
+        store
+            .put_community_moderation_policy(CommunityModerationPolicyRow {
+                node_base_url: "https://node-b.example".to_string(),
+                policy,
+                updated_at,
+            })
+            .await
+            .unwrap();
+    }
+    store
+        .put_community_moderation_policy(CommunityModerationPolicyRow {
+            node_base_url: "https://node-a.example".to_string(),
+            policy: CommunityModerationPolicy::Ignore,
+            updated_at: 3,
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        store.list_community_moderation_policies().await.unwrap(),
+        vec![
+            CommunityModerationPolicyRow {
+                node_base_url: "https://node-a.example".to_string(),
+                policy: CommunityModerationPolicy::Ignore,
+                updated_at: 3,
+            },
+            CommunityModerationPolicyRow {
+                node_base_url: "https://node-b.example".to_string(),
+                policy: CommunityModerationPolicy::Blur,
+                updated_at: 2,
+            },
+        ]
+    );
+
+    assert_eq!(
+        store
+            .get_community_moderation_sync("https://node-a.example")
+            .await
+            .unwrap(),
+        None
+    );
+    for backfill_cursor in [Some("1000:event-9".to_string()), None] {
+        let row = CommunityModerationSyncRow {
+            node_base_url: "https://node-a.example".to_string(),
+            backfill_cursor,
+            updated_at: 4,
+        };
+        store
+            .put_community_moderation_sync(row.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            store
+                .get_community_moderation_sync("https://node-a.example")
+                .await
+                .unwrap(),
+            Some(row)
+        );
+    }
+}
+
+#[tokio::test]
+async fn community_moderation_events_are_deduplicated_per_node_and_policies_upsert() {
+    community_moderation_scenario(&MemoryStore::default()).await;
+    community_moderation_scenario(&SqliteStore::connect_memory().await.unwrap()).await;
+}
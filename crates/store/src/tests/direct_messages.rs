@@ -129,3 +129,226 @@ async fn direct_message_local_delete_prevents_duplicate_reinsert() {
             .expect("has tombstone")
     );
 }
+
+async fn assert_direct_message_ratchet_storage(store: &dyn DirectMessageStore) {
+    let dm_id = "dm-ratchet";
+    assert!(
+        store
+            .get_direct_message_session(dm_id)
+            .await
+            .expect("get missing session")
+            .is_none()
+    );
+    let session = DirectMessageSessionRow {
+        dm_id: dm_id.into(),
+        peer_pubkey: "b".repeat(64),
+        peer_frame_version: 2,
+        ratchet_state_json: Some("{\"state\":1}".into()),
+        local_prekey_secret_hex: Some("d".repeat(64)),
+        peer_prekey_pubkey: Some("e".repeat(64)),
+        updated_at: 10,
+    };
+    store
+        .put_direct_message_session(session.clone())
+        .await
+        .expect("put session");
+    assert_eq!(
+        store
+            .get_direct_message_session(dm_id)
+            .await
+            .expect("get session"),
+        Some(session)
+    );
+
+    let skipped = (0..4_u32)
+        .map(|message_number| DirectMessageSkippedKeyRow {
+            dm_id: dm_id.into(),
+            ratchet_pubkey: "c".repeat(64),
+            message_number,
+            message_key_hex: format!("key-{message_number}"),
+            created_at: i64::from(message_number),
+        })
+        .collect::<Vec<_>>();
+    store
+        .put_direct_message_skipped_keys(skipped)
+        .await
+        .expect("put skipped keys");
+    let ratchet_pubkey = "c".repeat(64);
+    assert_eq!(
+        store
+            .take_direct_message_skipped_key(dm_id, ratchet_pubkey.as_str(), 1)
+            .await
+            .expect("take skipped key"),
+        Some("key-1".to_string())
+    );
+    assert_eq!(
+        store
+            .take_direct_message_skipped_key(dm_id, ratchet_pubkey.as_str(), 1)
+            .await
+            .expect("take consumed key"),
+        None,
+        "a skipped key must only be usable once"
+    );
+
+    store
+        .prune_direct_message_skipped_keys(dm_id, 1)
+        .await
+        .expect("prune skipped keys");
+    assert_eq!(
+        store
+            .take_direct_message_skipped_key(dm_id, ratchet_pubkey.as_str(), 0)
+            .await
+            .expect("take pruned key"),
+        None
+    );
+    assert_eq!(
+        store
+            .take_direct_message_skipped_key(dm_id, ratchet_pubkey.as_str(), 3)
+            .await
+            .expect("take newest key"),
+        Some("key-3".to_string())
+    );
+}
+
+#[tokio::test]
+async fn direct_message_ratchet_session_and_skipped_keys_roundtrip() {
+    let sqlite = SqliteStore::connect_memory().await.expect("sqlite store");
+    assert_direct_message_ratchet_storage(&sqlite).await;
+    assert_direct_message_ratchet_storage(&MemoryStore::default()).await;
+}
+
+async fn assert_group_direct_message_storage(store: &dyn DirectMessageStore) {
+    let group_id = "group-dm-storage";
+    let conversation = GroupDirectMessageConversationRow {
+        group_id: group_id.into(),
+        owner_pubkey: "a".repeat(64),
+        title: Some("team".into()),
+        epoch: 1,
+        members: vec!["a".repeat(64), "b".repeat(64), "c".repeat(64)],
+        removed_at: None,
+        updated_at: 10,
+        last_message_at: None,
+        last_message_id: None,
+        last_message_preview: None,
+    };
+    store
+        .upsert_group_direct_message_conversation(conversation.clone())
+        .await
+        .expect("put group conversation");
+    assert_eq!(
+        store
+            .get_group_direct_message_conversation(group_id)
+            .await
+            .expect("get group conversation"),
+        Some(conversation.clone())
+    );
+    assert_eq!(
+        store
+            .list_group_direct_message_conversations()
+            .await
+            .expect("list group conversations"),
+        vec![conversation]
+    );
+
+    let epoch = GroupDirectMessageEpochRow {
+        group_id: group_id.into(),
+        epoch: 1,
+        epoch_secret_hex: "secret-1".into(),
+        members: vec!["a".repeat(64), "b".repeat(64)],
+        created_at: 10,
+    };
+    store
+        .put_group_direct_message_epoch(epoch.clone())
+        .await
+        .expect("put epoch");
+    store
+        .put_group_direct_message_epoch(GroupDirectMessageEpochRow {
+            epoch_secret_hex: "secret-replaced".into(),
+            ..epoch.clone()
+        })
+        .await
+        .expect("put duplicate epoch");
+    assert_eq!(
+        store
+            .get_group_direct_message_epoch(group_id, 1)
+            .await
+            .expect("get epoch"),
+        Some(epoch),
+        "the first key received for an epoch must win"
+    );
+    assert!(
+        store
+            .get_group_direct_message_epoch(group_id, 2)
+            .await
+            .expect("get missing epoch")
+            .is_none()
+    );
+
+    for (message_id, created_at) in [("message-2", 20), ("message-1", 10)] {
+        store
+            .put_group_direct_message_outbox(GroupDirectMessageOutboxRow {
+                group_id: group_id.into(),
+                message_id: message_id.into(),
+                frame_blob_hash: BlobHash::new(format!("hash-{message_id}")),
+                created_at,
+                last_attempt_at: None,
+            })
+            .await
+            .expect("put group outbox");
+    }
+    store
+        .touch_group_direct_message_outbox_attempt(group_id, "message-1", 30)
+        .await
+        .expect("touch group outbox");
+    let outbox = store
+        .list_group_direct_message_outbox(group_id)
+        .await
+        .expect("list group outbox");
+    assert_eq!(
+        outbox
+            .iter()
+            .map(|row| (row.message_id.as_str(), row.last_attempt_at))
+            .collect::<Vec<_>>(),
+        vec![("message-1", Some(30)), ("message-2", None)]
+    );
+
+    let c_pubkey = "c".repeat(64);
+    let b_pubkey = "b".repeat(64);
+    assert_eq!(
+        store
+            .put_group_direct_message_ack(group_id, "message-1", c_pubkey.as_str(), 40)
+            .await
+            .expect("ack from c"),
+        vec![c_pubkey.clone()]
+    );
+    store
+        .put_group_direct_message_ack(group_id, "message-1", c_pubkey.as_str(), 41)
+        .await
+        .expect("duplicate ack from c");
+    assert_eq!(
+        store
+            .put_group_direct_message_ack(group_id, "message-1", b_pubkey.as_str(), 42)
+            .await
+            .expect("ack from b"),
+        vec![b_pubkey, c_pubkey]
+    );
+    store
+        .remove_group_direct_message_outbox(group_id, "message-1")
+        .await
+        .expect("remove group outbox");
+    assert_eq!(
+        store
+            .list_group_direct_message_outbox(group_id)
+            .await
+            .expect("list group outbox after removal")
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn group_direct_message_conversations_epochs_outbox_and_acks_roundtrip() {
+    let sqlite = SqliteStore::connect_memory().await.expect("sqlite store");
+    assert_group_direct_message_storage(&sqlite).await;
+    assert_group_direct_message_storage(&MemoryStore::default()).await;
+}
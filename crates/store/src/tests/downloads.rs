@@ -0,0 +1,135 @@
+use super::*;
+
+async fn download_queue_scenario<S>(store: &S)
+where
+    S: DownloadJobStore,
+{
+    let background = BlobHash::new("a".repeat(64));
+    let visible = BlobHash::new("b".repeat(64));
+    let manifest = BlobHash::new("c".repeat(64));
+    assert!(
+        store
+            .enqueue_download_job(&background, DownloadJobKind::Attachment, 0, 10)
+            .await
+            .unwrap()
+    );
+    assert!(
+        store
+            .enqueue_download_job(&visible, DownloadJobKind::Attachment, 0, 20)
+            .await
+            .unwrap()
+    );
+    assert!(
+        store
+            .enqueue_download_job(&manifest, DownloadJobKind::Manifest, 0, 30)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !store
+            .enqueue_download_job(&visible, DownloadJobKind::Attachment, 100, 40)
+            .await
+            .unwrap(),
+        "再 enqueue は priority を引き上げるだけ"
+    );
+
+    let claimed = store.claim_download_jobs(50, 2).await.unwrap();
+    assert_eq!(
+        claimed
+            .iter()
+            .map(|job| job.blob_hash.clone())
+            .collect::<Vec<_>>(),
+        vec![visible.clone(), background.clone()],
+        "priority 降順、同順位は enqueue 順"
+    );
+    assert!(
+        claimed
+            .iter()
+            .all(|job| job.status == DownloadJobStatus::Active)
+    );
+    assert_eq!(
+        store.summarize_download_jobs().await.unwrap(),
+        DownloadQueueSummary {
+            queued: 1,
+            active: 2,
+            failed: 0,
+        }
+    );
+
+    store.complete_download_job(&visible).await.unwrap();
+    assert!(store.get_download_job(&visible).await.unwrap().is_none());
+
+    store
+        .fail_download_job(&background, "timed out", 1_000, false, 60)
+        .await
+        .unwrap();
+    let retried = store.get_download_job(&background).await.unwrap().unwrap();
+    assert_eq!(retried.status, DownloadJobStatus::Queued);
+    assert_eq!(retried.attempts, 1);
+    assert_eq!(retried.next_attempt_at, 1_000);
+    assert_eq!(retried.last_error.as_deref(), Some("timed out"));
+    assert_eq!(
+        store
+            .claim_download_jobs(999, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|job| job.blob_hash)
+            .collect::<Vec<_>>(),
+        vec![manifest.clone()],
+        "backoff 中の job は claim しない"
+    );
+
+    // 再起動相当: 転送途中の job は queued に戻る。
+    assert_eq!(store.requeue_active_download_jobs(70).await.unwrap(), 1);
+    let requeued = store.get_download_job(&manifest).await.unwrap().unwrap();
+    assert_eq!(requeued.status, DownloadJobStatus::Queued);
+    assert_eq!(requeued.kind, DownloadJobKind::Manifest);
+
+    store
+        .fail_download_job(&manifest, "no peers", 5_000, true, 80)
+        .await
+        .unwrap();
+    assert_eq!(
+        store.summarize_download_jobs().await.unwrap(),
+        DownloadQueueSummary {
+            queued: 1,
+            active: 0,
+            failed: 1,
+        }
+    );
+    assert_eq!(store.claim_download_jobs(2_000, 10).await.unwrap().len(), 1);
+
+    assert!(
+        !store
+            .enqueue_download_job(&manifest, DownloadJobKind::Manifest, 100, 90)
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        store
+            .get_download_job(&manifest)
+            .await
+            .unwrap()
+            .unwrap()
+            .status,
+        DownloadJobStatus::Failed,
+        "enqueue し直しただけでは打ち切った job を再開しない"
+    );
+    assert!(store.retry_download_job(&manifest, 90).await.unwrap());
+    assert!(!store.retry_download_job(&manifest, 91).await.unwrap());
+    let revived = store.get_download_job(&manifest).await.unwrap().unwrap();
+    assert_eq!(revived.status, DownloadJobStatus::Queued);
+    assert_eq!(revived.attempts, 0);
+    assert_eq!(revived.priority, 100);
+    assert_eq!(
+        revived.next_attempt_at, 5_000,
+        "打ち切り時の cooldown は据え置く"
+    );
+}
+
+#[tokio::test]
+async fn download_jobs_claim_by_priority_back_off_and_resume() {
+    download_queue_scenario(&MemoryStore::default()).await;
+    download_queue_scenario(&SqliteStore::connect_memory().await.unwrap()).await;
+}
@@ -0,0 +1,205 @@
+use super::*;
+use crate::models::{LocalSearchDocumentRow, LocalSearchQuery};
+
+fn post_document(
+    subject_id: &str,
+    channel_id: &str,
+    created_at: i64,
+    body: &str,
+) -> LocalSearchDocumentRow {
+    LocalSearchDocumentRow {
+        subject_kind: "post".to_string(),
+        subject_id: subject_id.to_string(),
+        topic_id: Some("kukuri:topic:search".to_string()),
+        channel_id: Some(channel_id.to_string()),
+        dm_id: None,
+        author_pubkey: "a".repeat(64),
+        created_at,
+        body: body.to_string(),
+    }
+}
+
+fn direct_message_document(
+    dm_id: &str,
+    message_id: &str,
+    created_at: i64,
+    body: &str,
+) -> LocalSearchDocumentRow {
+    LocalSearchDocumentRow {
+        subject_kind: "direct_message".to_string(),
+        subject_id: message_id.to_string(),
+        topic_id: None,
+        channel_id: None,
+        dm_id: Some(dm_id.to_string()),
+        author_pubkey: "b".repeat(64),
+        created_at,
+        body: body.to_string(),
+    }
+}
+
+fn search_query(text: &str) -> LocalSearchQuery {
+    LocalSearchQuery {
+        text: text.to_string(),
+        limit: 20,
+        ..LocalSearchQuery::default()
+    }
+}
+
+async fn search_ids<S>(store: &S, query: LocalSearchQuery) -> Vec<String>
+where
+    S: ObjectProjectionStore,
+{
+    store
+        .search_local_documents(&query)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.subject_id)
+        .collect()
+}
+
+async fn local_search_scenario<S>(store: &S)
+where
+    S: ObjectProjectionStore,
+{
+    store
+        .put_local_search_documents(vec![
+            post_document("post-1", "public", 1_000, "Hello Kukuri world"),
+            post_document("post-2", "channel-a", 2_000, "kukuri private channel note"),
+            post_document("post-3", "public", 3_000, "100% literal_match"),
+            direct_message_document("dm-1", "message-1", 4_000, "secret kukuri plan"),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        search_ids(store, search_query("KUKURI")).await,
+        vec!["message-1", "post-2", "post-1"]
+    );
+    assert_eq!(
+        search_ids(store, search_query("kukuri world")).await,
+        vec!["post-1"]
+    );
+    assert!(search_ids(store, search_query("   ")).await.is_empty());
+    assert_eq!(
+        search_ids(store, search_query("0%")).await,
+        vec!["post-3"],
+        "LIKE wildcard は literal として扱う"
+    );
+    assert!(search_ids(store, search_query("l_teral")).await.is_empty());
+
+    let mut posts_only = search_query("kukuri");
+    posts_only.subject_kinds.insert("post".to_string());
+    assert_eq!(
+        search_ids(store, posts_only).await,
+        vec!["post-2", "post-1"]
+    );
+
+    let mut channel = search_query("kukuri");
+    channel.topic_id = Some("kukuri:topic:search".to_string());
+    channel.channel_id = Some("channel-a".to_string());
+    assert_eq!(search_ids(store, channel).await, vec!["post-2"]);
+
+    let mut author = search_query("kukuri");
+    author.author_pubkey = Some("b".repeat(64));
+    assert_eq!(search_ids(store, author).await, vec!["message-1"]);
+
+    let mut window = search_query("kukuri");
+    window.created_after = Some(1_000);
+    window.created_before = Some(4_000);
+    assert_eq!(search_ids(store, window).await, vec!["post-2", "post-1"]);
+
+    let mut limited = search_query("kukuri");
+    limited.limit = 1;
+    assert_eq!(search_ids(store, limited).await, vec!["message-1"]);
+
+    let mut continued = search_query("kukuri");
+    continued.before = Some((4_000, "message-1".to_string()));
+    assert_eq!(
+        search_ids(store, continued).await,
+        vec!["post-2", "post-1"],
+        "before の位置より古い文書だけを返す"
+    );
+
+    store
+        .put_local_search_documents(vec![post_document(
+            "post-1",
+            "public",
+            1_000,
+            "edited body",
+        )])
+        .await
+        .unwrap();
+    assert_eq!(
+        search_ids(store, search_query("kukuri world")).await,
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        search_ids(store, search_query("edited")).await,
+        vec!["post-1"]
+    );
+
+    store
+        .put_local_search_documents(vec![post_document("post-1", "public", 1_000, "")])
+        .await
+        .unwrap();
+    assert!(search_ids(store, search_query("edited")).await.is_empty());
+
+    store
+        .remove_local_search_document("post", "post-2")
+        .await
+        .unwrap();
+    assert_eq!(
+        search_ids(store, search_query("kukuri")).await,
+        vec!["message-1"]
+    );
+}
+
+async fn local_search_cascade_scenario<S>(store: &S)
+where
+    S: ObjectProjectionStore + DirectMessageStore,
+{
+    store
+        .put_local_search_documents(vec![
+            post_document("stale-post", "public", 1_000, "projection kukuri"),
+            direct_message_document("dm-1", "message-1", 2_000, "kukuri one"),
+            direct_message_document("dm-1", "message-2", 3_000, "kukuri two"),
+            direct_message_document("dm-2", "message-3", 4_000, "kukuri three"),
+        ])
+        .await
+        .unwrap();
+
+    store.rebuild_object_projections(Vec::new()).await.unwrap();
+    assert_eq!(
+        search_ids(store, search_query("kukuri")).await,
+        vec!["message-3", "message-2", "message-1"],
+        "rebuild で projection から消えた post 文書を落とす"
+    );
+
+    store
+        .delete_direct_message_message_local("dm-1", "message-2")
+        .await
+        .unwrap();
+    assert_eq!(
+        search_ids(store, search_query("kukuri")).await,
+        vec!["message-3", "message-1"]
+    );
+
+    store.clear_direct_message_local("dm-1").await.unwrap();
+    assert_eq!(
+        search_ids(store, search_query("kukuri")).await,
+        vec!["message-3"]
+    );
+}
+
+#[tokio::test]
+async fn local_search_matches_terms_with_filters_and_upserts() {
+    local_search_scenario(&MemoryStore::default()).await;
+    local_search_scenario(&SqliteStore::connect_memory().await.unwrap()).await;
+}
+
+#[tokio::test]
+async fn local_search_drops_documents_with_their_subjects() {
+    local_search_cascade_scenario(&MemoryStore::default()).await;
+    local_search_cascade_scenario(&SqliteStore::connect_memory().await.unwrap()).await;
+}
@@ -268,10 +268,10 @@ async fn connect_file_migrates_pre_metaverse_game_room_fixture() {
 // 後続 WP-H1(ProjectionStore 分割)の安全網として、
 // (1) 全世代に up/down が embed で揃うこと、
 // (2) 全適用 → undo(0) → 再適用でスキーマと migration 記録が完全復元されること
-// を固定する。期待値は観測した現挙動の生リテラル(世代数 17 など)。
+// を固定する。期待値は観測した現挙動の生リテラル(世代数 24 など)。
 // ---------------------------------------------------------------------------
 
-/// 全 17 世代に ReversibleUp / ReversibleDown が揃っていることを固定する(DB 不要)。
+/// 全 24 世代に ReversibleUp / ReversibleDown が揃っていることを固定する(DB 不要)。
 /// down が embed されていない世代は `Migrator::undo` に黙ってスキップされ、
 /// round-trip を静かに破壊するため、ここで欠落を即検出する。
 #[tokio::test]
@@ -297,8 +297,8 @@ async fn all_generations_have_paired_down() {
 
     assert_eq!(
         generations.len(),
-        17,
-        "store migrations must cover exactly 17 generations, found versions: {:?}",
+        24,
+        "store migrations must cover exactly 24 generations, found versions: {:?}",
         generations.keys().collect::<Vec<_>>()
     );
 
@@ -390,8 +390,8 @@ async fn full_migration_round_trip() {
     expected_versions.dedup();
     assert_eq!(
         applied_versions.len(),
-        17,
-        "round trip must restore all 17 migration generations"
+        24,
+        "round trip must restore all 24 migration generations"
     );
     assert_eq!(applied_versions, expected_versions);
 }
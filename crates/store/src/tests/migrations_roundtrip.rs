@@ -5,7 +5,7 @@
 //!
 //! - stepwise round-trip: 各世代 k について
 //!   「全適用 → undo(V[k-1]) → V[k-1] まで適用した別 DB とスキーマ一致
-//!   → run() 再適用 → 全適用スキーマと一致」を全 17 世代で固定する。
+//!   → run() 再適用 → 全適用スキーマと一致」を全 29 世代で固定する。
 //! - schema golden: 全適用後スキーマの正規化 dump を
 //!   `crates/store/fixtures/schema/store_schema_full.txt` と比較して固定する。
 //!
@@ -38,9 +38,9 @@ use std::path::PathBuf;
 
 use super::migrations::materialize_sqlite_fixture;
 
-/// 全 17 世代の up migration version(migrations/ ディレクトリのファイル名から
+/// 全 29 世代の up migration version(migrations/ ディレクトリのファイル名から
 /// 観測した生リテラル、昇順)。世代の追加・削除はここと golden の両方に現れる。
-const EXPECTED_VERSIONS: [i64; 17] = [
+const EXPECTED_VERSIONS: [i64; 29] = [
     20260310000000,
     20260312000000,
     20260315000000,
@@ -58,6 +58,18 @@ const EXPECTED_VERSIONS: [i64; 17] = [
     20260413000000,
     20260527000000,
     20260814000000,
+    20261001000000,
+    20261002000000,
+    20261003000000,
+    20261004000000,
+    20261005000000,
+    20261006000000,
+    20261007000000,
+    20261008000000,
+    20261009000000,
+    20261010000000,
+    20261011000000,
+    20261012000000,
 ];
 
 /// 各世代 k について「全適用 → undo(V[k-1]) → 中間世代スキーマと一致 →
@@ -74,7 +86,7 @@ async fn per_generation_stepwise_round_trip() {
     let versions = migrator_up_versions();
     assert_eq!(
         versions, EXPECTED_VERSIONS,
-        "embedded store migration generations drifted from the observed 17 versions"
+        "embedded store migration generations drifted from the observed 29 versions"
     );
 
     let full_snapshot = schema_snapshot(store.pool())
@@ -170,7 +182,7 @@ async fn fully_migrated_schema_matches_golden() {
     assert_eq!(
         migrator_up_versions(),
         EXPECTED_VERSIONS,
-        "embedded store migration generations drifted from the observed 17 versions"
+        "embedded store migration generations drifted from the observed 29 versions"
     );
     assert_eq!(
         applied_migration_versions(store.pool())
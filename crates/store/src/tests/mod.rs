@@ -14,11 +14,16 @@ use kukuri_core::{
 use tempfile::tempdir;
 
 mod backend_parity;
+mod blob_retention;
+mod community_moderation;
 mod content_observations;
 mod direct_messages;
+mod downloads;
+mod local_search;
 mod migrations;
 mod migrations_roundtrip;
 mod pagination;
+mod replica_cursors;
 mod row_mapping_edge;
 mod row_mapping_enums;
 mod row_mapping_roundtrip;
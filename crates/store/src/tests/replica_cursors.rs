@@ -0,0 +1,44 @@
+use super::*;
+
+async fn replica_cursor_scenario<S>(store: &S)
+where
+    S: ReplicaCursorStore + ObjectProjectionStore,
+{
+    let replica = ReplicaId::new("topic::kukuri:cursor");
+    assert!(store.get_replica_cursor(&replica).await.unwrap().is_none());
+
+    let mut cursor = ReplicaCursor::default();
+    cursor.advance("author-a", 10);
+    cursor.advance("author-b", 20);
+    cursor.advance("author-a", 5);
+    store.put_replica_cursor(&replica, &cursor).await.unwrap();
+    let stored = store.get_replica_cursor(&replica).await.unwrap().unwrap();
+    assert_eq!(stored, cursor);
+    assert!(stored.is_current("author-a", 10));
+    assert!(!stored.is_current("author-a", 11));
+    assert!(!stored.is_current("author-c", 1));
+
+    // 書き込んだ author の行だけが更新され、他の author の watermark は残る。
+    let mut update = ReplicaCursor::default();
+    update.advance("author-a", 30);
+    store.put_replica_cursor(&replica, &update).await.unwrap();
+    let stored = store.get_replica_cursor(&replica).await.unwrap().unwrap();
+    assert!(stored.is_current("author-a", 30));
+    assert!(stored.is_current("author-b", 20));
+
+    store.clear_replica_cursor(&replica).await.unwrap();
+    assert!(store.get_replica_cursor(&replica).await.unwrap().is_none());
+
+    store.put_replica_cursor(&replica, &cursor).await.unwrap();
+    store.rebuild_object_projections(Vec::new()).await.unwrap();
+    assert!(
+        store.get_replica_cursor(&replica).await.unwrap().is_none(),
+        "projection を作り直したら cursor も捨てる"
+    );
+}
+
+#[tokio::test]
+async fn replica_cursors_roundtrip_and_reset_on_rebuild() {
+    replica_cursor_scenario(&MemoryStore::default()).await;
+    replica_cursor_scenario(&SqliteStore::connect_memory().await.unwrap()).await;
+}
@@ -167,13 +167,14 @@ fn parse_reaction_key_kind_bails_on_unknown_values() {
 
 #[test]
 fn notification_kind_name_maps_all_variants() {
-    let cases: [(NotificationKind, &str); 6] = [
+    let cases: [(NotificationKind, &str); 7] = [
         (NotificationKind::Mention, "mention"),
         (NotificationKind::Reply, "reply"),
         (NotificationKind::Repost, "repost"),
         (NotificationKind::QuoteRepost, "quote_repost"),
         (NotificationKind::DirectMessage, "direct_message"),
         (NotificationKind::Followed, "followed"),
+        (NotificationKind::GroupDirectMessage, "group_direct_message"),
     ];
     for (variant, expected) in cases {
         assert_eq!(
@@ -186,13 +187,14 @@ fn notification_kind_name_maps_all_variants() {
 
 #[test]
 fn parse_notification_kind_maps_all_known_strings() {
-    let cases: [(&str, NotificationKind); 6] = [
+    let cases: [(&str, NotificationKind); 7] = [
         ("mention", NotificationKind::Mention),
         ("reply", NotificationKind::Reply),
         ("repost", NotificationKind::Repost),
         ("quote_repost", NotificationKind::QuoteRepost),
         ("direct_message", NotificationKind::DirectMessage),
         ("followed", NotificationKind::Followed),
+        ("group_direct_message", NotificationKind::GroupDirectMessage),
     ];
     for (input, expected) in cases {
         assert_eq!(
@@ -100,7 +100,7 @@ async fn envelope_roundtrip_preserves_all_columns() {
 }
 
 // ---------------------------------------------------------------------------
-// object_index_cache 全 18 列(row_to_object_projection)
+// object_index_cache 全 19 列(row_to_object_projection)
 // ---------------------------------------------------------------------------
 
 /// max fixture: 全 Option=Some・attachments 2 要素・repost_of の入れ子も全 Some。
@@ -149,6 +149,7 @@ fn object_projection_max() -> ObjectProjectionRow {
             reply_to_object_id: Some(EnvelopeId::from("src-parent")),
             root_id: Some(EnvelopeId::from("src-root")),
         }),
+        status: ObjectStatus::Tombstoned,
         source_replica_id: ReplicaId::new("topic::kukuri:topic:object-rt"),
         source_key: "objects/obj-max/header".into(),
         source_envelope_id: EnvelopeId::from("env-obj-max"),
@@ -175,6 +176,7 @@ fn object_projection_min() -> ObjectProjectionRow {
         content: None,
         attachments: Vec::new(),
         repost_of: None,
+        status: ObjectStatus::Active,
         source_replica_id: ReplicaId::new("topic::kukuri:topic:object-rt"),
         source_key: "objects/obj-min/header".into(),
         source_envelope_id: EnvelopeId::from("env-obj-min"),
@@ -185,7 +187,7 @@ fn object_projection_min() -> ObjectProjectionRow {
 }
 
 #[tokio::test]
-async fn object_projection_roundtrip_preserves_all_18_columns() {
+async fn object_projection_roundtrip_preserves_all_19_columns() {
     let store = SqliteStore::connect_memory().await.expect("sqlite store");
     let max = object_projection_max();
     let min = object_projection_min();
@@ -25,6 +25,7 @@ fn projection_row(
         content: Some(object_id.to_string()),
         attachments: Vec::new(),
         repost_of: None,
+        status: ObjectStatus::Active,
         source_replica_id: ReplicaId::new(format!("topic::{topic}")),
         source_key: format!("objects/{object_id}/header"),
         source_envelope_id: EnvelopeId::from(object_id),
@@ -308,6 +309,7 @@ async fn projection_rebuild_from_docs_blobs_only() {
             content: Some("root".into()),
             attachments: Vec::new(),
             repost_of: None,
+            status: ObjectStatus::Active,
             source_replica_id: ReplicaId::new(format!("topic::{topic}")),
             source_key: "objects/object-root/header".into(),
             source_envelope_id: root_id.clone(),
@@ -332,6 +334,7 @@ async fn projection_rebuild_from_docs_blobs_only() {
             content: Some("reply".into()),
             attachments: Vec::new(),
             repost_of: None,
+            status: ObjectStatus::Active,
             source_replica_id: ReplicaId::new(format!("topic::{topic}")),
             source_key: "objects/object-reply/header".into(),
             source_envelope_id: reply_id.clone(),
@@ -6,11 +6,16 @@ use async_trait::async_trait;
 use kukuri_core::{BlobHash, EnvelopeId, FollowEdge, KukuriEnvelope, Profile, ReplicaId};
 
 use crate::models::{
-    AuthorRelationshipProjectionRow, BlobCacheStatus, BookmarkedCustomReactionRow,
-    BookmarkedPostRow, ContentObservationRow, DirectMessageConversationRow,
-    DirectMessageMessageRow, DirectMessageOutboxRow, DirectMessageTombstoneRow,
-    GameRoomProjectionRow, LiveSessionProjectionRow, MutedAuthorRow, NotificationRow,
-    ObjectProjectionRow, Page, ReactionProjectionRow, TimelineCursor,
+    AuthorRelationshipProjectionRow, BlobCacheStatus, BlobRetentionRow,
+    BookmarkedCustomReactionRow, BookmarkedPostRow, CommunityModerationEventRow,
+    CommunityModerationPolicyRow, CommunityModerationSyncRow, ContentObservationRow,
+    DirectMessageConversationRow, DirectMessageMessageRow, DirectMessageOutboxRow,
+    DirectMessageSessionRow, DirectMessageSkippedKeyRow, DirectMessageTombstoneRow,
+    DownloadJobKind, DownloadJobRow, DownloadQueueSummary, GameRoomProjectionRow,
+    GroupDirectMessageConversationRow, GroupDirectMessageEpochRow, GroupDirectMessageOutboxRow,
+    LiveSessionProjectionRow, LocalSearchDocumentRow, LocalSearchQuery, MutedAuthorRow,
+    NotificationRow, ObjectProjectionRow, Page, ReactionProjectionRow, ReplicaCursor,
+    TimelineCursor,
 };
 
 pub(crate) const CONTENT_OBSERVATION_RETENTION_MS: i64 = 90 * 24 * 60 * 60 * 1000;
@@ -111,6 +116,18 @@ pub trait ObjectProjectionStore: Send + Sync {
         .await
     }
     async fn rebuild_object_projections(&self, rows: Vec<ObjectProjectionRow>) -> Result<()>;
+    /// 端末内全文検索の文書を upsert する。本文が空白のみの文書は削除として扱う。
+    async fn put_local_search_documents(&self, rows: Vec<LocalSearchDocumentRow>) -> Result<()>;
+    async fn remove_local_search_document(
+        &self,
+        subject_kind: &str,
+        subject_id: &str,
+    ) -> Result<()>;
+    /// 全語を含む文書を `created_at` の新しい順に最大 `limit` 件返す(ASCII は大文字小文字を区別しない)。
+    async fn search_local_documents(
+        &self,
+        query: &LocalSearchQuery,
+    ) -> Result<Vec<LocalSearchDocumentRow>>;
 }
 
 /// 内容をどのコミュニティノード経由で観測したかを保持する端末内記録。
@@ -136,6 +153,35 @@ pub trait ContentObservationStore: Send + Sync {
     ) -> Result<Vec<ContentObservationRow>>;
 }
 
+/// community node から pull して検証済みの moderation event と、node ごとの端末内 policy。
+///
+/// event は advisory であり、どう扱うか(hide / blur / label / ignore)は policy で決める。
+#[async_trait]
+pub trait CommunityModerationStore: Send + Sync {
+    /// 新規に保存した場合は true。同じ node・event id の再受信は上書きしない。
+    async fn put_community_moderation_event(
+        &self,
+        row: CommunityModerationEventRow,
+    ) -> Result<bool>;
+    /// `target_kind`(`post` / `user` 等)の対象 id 群に向けた event を受信順に返す。
+    async fn list_community_moderation_events_for_targets(
+        &self,
+        target_kind: &str,
+        target_ids: &[String],
+    ) -> Result<Vec<CommunityModerationEventRow>>;
+    async fn put_community_moderation_policy(
+        &self,
+        row: CommunityModerationPolicyRow,
+    ) -> Result<()>;
+    async fn list_community_moderation_policies(&self)
+    -> Result<Vec<CommunityModerationPolicyRow>>;
+    async fn get_community_moderation_sync(
+        &self,
+        node_base_url: &str,
+    ) -> Result<Option<CommunityModerationSyncRow>>;
+    async fn put_community_moderation_sync(&self, row: CommunityModerationSyncRow) -> Result<()>;
+}
+
 /// `put_object_projections` の既定動作: 1 件ずつ `put_object_projection` を呼ぶ。
 pub(crate) async fn put_object_projections_one_by_one<S>(
     store: &S,
@@ -284,6 +330,73 @@ where
     Ok(())
 }
 
+/// 未取得 blob の download queue(実装: sqlite/downloads.rs)。時刻はすべて ms。
+#[async_trait]
+pub trait DownloadJobStore: Send + Sync {
+    /// job を積む。既にあれば priority を引き上げるだけ(`Failed` は再開しない)。
+    /// 新しく積んだときだけ true。
+    async fn enqueue_download_job(
+        &self,
+        blob_hash: &BlobHash,
+        kind: DownloadJobKind,
+        priority: i64,
+        now: i64,
+    ) -> Result<bool>;
+    /// `Failed` の job を試行回数を戻して `Queued` に戻す。`next_attempt_at` は据え置くので
+    /// 打ち切り時の cooldown は守られる。戻したときだけ true。
+    async fn retry_download_job(&self, blob_hash: &BlobHash, now: i64) -> Result<bool>;
+    /// `next_attempt_at <= now` の `Queued` job を priority 順に最大 `limit` 件
+    /// `Active` にして返す。
+    async fn claim_download_jobs(&self, now: i64, limit: usize) -> Result<Vec<DownloadJobRow>>;
+    async fn complete_download_job(&self, blob_hash: &BlobHash) -> Result<()>;
+    /// 失敗を記録して `next_attempt_at` まで寝かせる。`exhausted` なら `Failed` で止める。
+    async fn fail_download_job(
+        &self,
+        blob_hash: &BlobHash,
+        error: &str,
+        next_attempt_at: i64,
+        exhausted: bool,
+        now: i64,
+    ) -> Result<()>;
+    /// 前回の起動で転送途中のまま残った `Active` job を `Queued` に戻す(起動時に 1 回)。
+    async fn requeue_active_download_jobs(&self, now: i64) -> Result<usize>;
+    async fn get_download_job(&self, blob_hash: &BlobHash) -> Result<Option<DownloadJobRow>>;
+    async fn summarize_download_jobs(&self) -> Result<DownloadQueueSummary>;
+}
+
+/// replica ごとの projection 済み位置(実装: sqlite/replica_cursors.rs)。
+/// `rebuild_object_projections` は projection と一緒に全 cursor を消す。
+#[async_trait]
+pub trait ReplicaCursorStore: Send + Sync {
+    /// author の行が 1 つも無ければ None(次の hydration が全件走査になる)。
+    async fn get_replica_cursor(&self, replica_id: &ReplicaId) -> Result<Option<ReplicaCursor>>;
+    /// `cursor` に含まれる author の行だけを書き換える。
+    async fn put_replica_cursor(
+        &self,
+        replica_id: &ReplicaId,
+        cursor: &ReplicaCursor,
+    ) -> Result<()>;
+    async fn clear_replica_cursor(&self, replica_id: &ReplicaId) -> Result<()>;
+}
+
+/// ローカル blob の容量・pin・最終参照時刻と、projection からの参照数
+/// (実装: sqlite/blob_retention.rs)。eviction の判断材料で、blob 本体は触らない。時刻は ms。
+#[async_trait]
+pub trait BlobRetentionStore: Send + Sync {
+    /// blob がローカルにあることを記録し、最終参照時刻を進める。`bytes` が `None` なら
+    /// 記録済みの容量を残す。
+    async fn record_blob_access(&self, hash: &BlobHash, bytes: Option<u64>, now: i64)
+    -> Result<()>;
+    async fn set_blob_pinned(&self, hash: &BlobHash, pinned: bool) -> Result<()>;
+    /// ローカルにある(`Available` / `Pinned`)blob を最終参照の古い順に返す。
+    async fn list_local_blobs(&self) -> Result<Vec<BlobRetentionRow>>;
+    /// blob ごとの参照数。post 本文・添付(取り消し済みを除く)、ブックマーク、profile 画像、
+    /// カスタムリアクション、live / game の manifest と metaverse asset、DM 添付と未送信 frame。
+    async fn blob_reference_counts(&self) -> Result<HashMap<String, u64>>;
+    /// 手放した blob を `Missing` に戻し、容量と pin を消す(必要になれば download queue が取り直す)。
+    async fn mark_blob_evicted(&self, hash: &BlobHash) -> Result<()>;
+}
+
 /// リアクション cache / カスタムリアクション / ブックマーク(実装: sqlite/bookmarks.rs)。
 #[async_trait]
 pub trait ReactionBookmarkStore: Send + Sync {
@@ -399,6 +512,67 @@ pub trait DirectMessageStore: Send + Sync {
         message_id: &str,
     ) -> Result<()>;
     async fn clear_direct_message_local(&self, dm_id: &str) -> Result<()>;
+    async fn get_direct_message_session(
+        &self,
+        dm_id: &str,
+    ) -> Result<Option<DirectMessageSessionRow>>;
+    async fn put_direct_message_session(&self, row: DirectMessageSessionRow) -> Result<()>;
+    async fn put_direct_message_skipped_keys(
+        &self,
+        rows: Vec<DirectMessageSkippedKeyRow>,
+    ) -> Result<()>;
+    /// 保存済みの skipped message key を取り出して削除する(1 key は 1 回だけ使う)。
+    async fn take_direct_message_skipped_key(
+        &self,
+        dm_id: &str,
+        ratchet_pubkey: &str,
+        message_number: u32,
+    ) -> Result<Option<String>>;
+    /// dm_id ごとに新しい順で `max_keys` 件を残し、古い skipped key を捨てる。
+    async fn prune_direct_message_skipped_keys(&self, dm_id: &str, max_keys: usize) -> Result<()>;
+    async fn upsert_group_direct_message_conversation(
+        &self,
+        row: GroupDirectMessageConversationRow,
+    ) -> Result<()>;
+    async fn get_group_direct_message_conversation(
+        &self,
+        group_id: &str,
+    ) -> Result<Option<GroupDirectMessageConversationRow>>;
+    async fn list_group_direct_message_conversations(
+        &self,
+    ) -> Result<Vec<GroupDirectMessageConversationRow>>;
+    /// 同じ (group_id, epoch) の鍵は上書きしない(最初に受け取った鍵を正とする)。
+    async fn put_group_direct_message_epoch(&self, row: GroupDirectMessageEpochRow) -> Result<()>;
+    async fn get_group_direct_message_epoch(
+        &self,
+        group_id: &str,
+        epoch: u64,
+    ) -> Result<Option<GroupDirectMessageEpochRow>>;
+    async fn put_group_direct_message_outbox(&self, row: GroupDirectMessageOutboxRow)
+    -> Result<()>;
+    async fn list_group_direct_message_outbox(
+        &self,
+        group_id: &str,
+    ) -> Result<Vec<GroupDirectMessageOutboxRow>>;
+    async fn touch_group_direct_message_outbox_attempt(
+        &self,
+        group_id: &str,
+        message_id: &str,
+        attempted_at: i64,
+    ) -> Result<()>;
+    async fn remove_group_direct_message_outbox(
+        &self,
+        group_id: &str,
+        message_id: &str,
+    ) -> Result<()>;
+    /// メンバーの ack を記録し、その message を ack 済みのメンバー(pubkey 順)を返す。
+    async fn put_group_direct_message_ack(
+        &self,
+        group_id: &str,
+        message_id: &str,
+        member_pubkey: &str,
+        acked_at: i64,
+    ) -> Result<Vec<String>>;
 }
 
 /// 通知(実装: sqlite/notifications.rs)。
@@ -418,9 +592,13 @@ pub trait NotificationStore: Send + Sync {
 pub trait ProjectionStore:
     ObjectProjectionStore
     + ContentObservationStore
+    + CommunityModerationStore
     + LiveGameProjectionStore
     + SocialProjectionStore
     + BlobCacheStore
+    + DownloadJobStore
+    + ReplicaCursorStore
+    + BlobRetentionStore
     + ReactionBookmarkStore
     + DirectMessageStore
     + NotificationStore
@@ -430,9 +608,13 @@ pub trait ProjectionStore:
 impl<T> ProjectionStore for T where
     T: ObjectProjectionStore
         + ContentObservationStore
+        + CommunityModerationStore
         + LiveGameProjectionStore
         + SocialProjectionStore
         + BlobCacheStore
+        + DownloadJobStore
+        + ReplicaCursorStore
+        + BlobRetentionStore
         + ReactionBookmarkStore
         + DirectMessageStore
         + NotificationStore
@@ -21,6 +21,8 @@ pub enum DiscoveryMode {
     #[default]
     StaticPeer,
     SeededDht,
+    /// static peer に加え、同一リンク上の kukuri endpoint を mDNS で広告・発見する(DHT なし)。
+    Lan,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -108,6 +110,8 @@ pub struct DiscoverySnapshot {
     pub configured_seed_peer_ids: Vec<String>,
     pub bootstrap_seed_peer_ids: Vec<String>,
     pub manual_ticket_peer_ids: Vec<String>,
+    #[serde(default)]
+    pub lan_peer_ids: Vec<String>,
     pub connected_peer_ids: Vec<String>,
     pub local_endpoint_id: String,
     pub last_discovery_error: Option<String>,
@@ -242,6 +242,7 @@ impl Transport for FakeTransport {
             configured_seed_peer_ids,
             bootstrap_seed_peer_ids,
             manual_ticket_peer_ids,
+            lan_peer_ids: Vec::new(),
             connected_peer_ids,
             local_endpoint_id: self.local_id.clone(),
             last_discovery_error: None,
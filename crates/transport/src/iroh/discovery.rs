@@ -11,6 +11,28 @@ impl IrohGossipTransport {
             .await;
     }
 
+    /// mDNS で見つけた LAN ピアを台帳と参加中 topic へ反映する。失効したピアは台帳からだけ外す
+    /// (張り済みの gossip 接続は切らない)。
+    pub async fn apply_lan_peer_event(&self, event: &LanPeerEvent) {
+        match event {
+            LanPeerEvent::Discovered(endpoint_addr) => {
+                self.discovery.add_endpoint_info(endpoint_addr.clone());
+                self.lan_peers
+                    .lock()
+                    .await
+                    .insert(endpoint_addr.id.to_string(), endpoint_addr.clone());
+                self.extend_active_topic_peers(vec![endpoint_addr.clone()], "lan-peer")
+                    .await;
+            }
+            LanPeerEvent::Expired(endpoint_id) => {
+                self.lan_peers
+                    .lock()
+                    .await
+                    .remove(endpoint_id.to_string().as_str());
+            }
+        }
+    }
+
     pub(crate) async fn transport_import_ticket_impl(&self, ticket: &str) -> Result<()> {
         let endpoint_addr = match parse_endpoint_ticket(ticket) {
             Ok(endpoint_addr) => endpoint_addr,
@@ -80,6 +102,13 @@ impl IrohGossipTransport {
             .keys()
             .cloned()
             .collect::<Vec<_>>();
+        let lan_peer_ids = self
+            .lan_peers
+            .lock()
+            .await
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
         Ok(DiscoverySnapshot {
             mode: self.discovery_mode.lock().await.clone(),
             connect_mode: self.connect_mode.lock().await.clone(),
@@ -93,6 +122,7 @@ impl IrohGossipTransport {
             configured_seed_peer_ids,
             bootstrap_seed_peer_ids,
             manual_ticket_peer_ids,
+            lan_peer_ids,
             connected_peer_ids: self.connected_peer_ids().await,
             local_endpoint_id: self.endpoint.id().to_string(),
             last_discovery_error: self.last_error.lock().await.clone(),
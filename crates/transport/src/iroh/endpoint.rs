@@ -76,6 +76,7 @@ impl IrohGossipTransport {
             configured_seed_peers: Arc::new(Mutex::new(BTreeMap::new())),
             bootstrap_seed_peers: Arc::new(Mutex::new(BTreeMap::new())),
             imported_peers: Arc::new(Mutex::new(BTreeMap::new())),
+            lan_peers: Arc::new(Mutex::new(BTreeMap::new())),
             subscribed_topics: Arc::new(Mutex::new(BTreeSet::new())),
             topic_states: Arc::new(Mutex::new(HashMap::new())),
             topic_warmups: Arc::new(TopicWarmupCoordinator::default()),
@@ -113,6 +114,7 @@ impl IrohGossipTransport {
             configured_seed_peers: Arc::new(Mutex::new(BTreeMap::new())),
             bootstrap_seed_peers: Arc::new(Mutex::new(BTreeMap::new())),
             imported_peers: Arc::new(Mutex::new(BTreeMap::new())),
+            lan_peers: Arc::new(Mutex::new(BTreeMap::new())),
             subscribed_topics: Arc::new(Mutex::new(BTreeSet::new())),
             topic_states: Arc::new(Mutex::new(HashMap::new())),
             topic_warmups: Arc::new(TopicWarmupCoordinator::default()),
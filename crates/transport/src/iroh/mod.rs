@@ -43,6 +43,7 @@ use crate::config::{
 };
 use crate::diagnostics::{peer_status_detail, topic_status_detail};
 use crate::discovery::prepare_endpoint_for_discovery;
+use crate::lan::LanPeerEvent;
 use crate::tickets::{
     encode_endpoint_ticket, endpoint_addr_with_relays, parse_endpoint_ticket, ticket_network_config,
 };
@@ -93,6 +94,7 @@ pub struct IrohGossipTransport {
     configured_seed_peers: Arc<Mutex<BTreeMap<String, EndpointAddr>>>,
     bootstrap_seed_peers: Arc<Mutex<BTreeMap<String, EndpointAddr>>>,
     imported_peers: Arc<Mutex<BTreeMap<String, EndpointAddr>>>,
+    lan_peers: Arc<Mutex<BTreeMap<String, EndpointAddr>>>,
     subscribed_topics: Arc<Mutex<BTreeSet<String>>>,
     topic_states: Arc<Mutex<HashMap<String, HintTopicState>>>,
     topic_warmups: Arc<TopicWarmupCoordinator>,
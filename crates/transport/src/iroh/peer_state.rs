@@ -108,6 +108,11 @@ impl IrohGossipTransport {
                 peers.push(peer.clone());
             }
         }
+        for peer in self.lan_peers.lock().await.values() {
+            if !peers.iter().any(|existing| existing.id == peer.id) {
+                peers.push(peer.clone());
+            }
+        }
         peers
     }
 
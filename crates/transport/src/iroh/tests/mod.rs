@@ -2,6 +2,7 @@ use super::*;
 
 use n0_mainline::{DhtBuilder, Testnet};
 
+use crate::lan::LanDiscovery;
 use crate::test_support::{
     HintRoundtripParticipant, format_peer_snapshot, wait_for_hint_roundtrip,
 };
@@ -30,6 +31,23 @@ async fn wait_for_endpoint_in_testnet(endpoint: &Endpoint, testnet: &Testnet) {
     .expect("resolve endpoint info from DHT");
 }
 
+async fn wait_for_lan_peer(lan: &LanDiscovery, peer_id: &str) -> EndpointAddr {
+    timeout(Duration::from_secs(20), async {
+        loop {
+            if let Some(peer) = lan
+                .peers()
+                .into_iter()
+                .find(|peer| peer.id.to_string() == peer_id)
+            {
+                return peer;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("lan peer was not discovered via mdns")
+}
+
 fn seed_peer_from_ticket(ticket: &str) -> SeedPeer {
     let (endpoint_id, addr_hint) = ticket.split_once('@').expect("ticket host");
     SeedPeer {
@@ -414,6 +432,75 @@ async fn transport_seeded_dht_can_connect_by_endpoint_id_without_ticket() {
     drop(connection);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn transport_lan_discovery_exchanges_hints_without_ticket() {
+    let transport_a = IrohGossipTransport::bind_local()
+        .await
+        .expect("transport a");
+    let transport_b = IrohGossipTransport::bind_local()
+        .await
+        .expect("transport b");
+    for transport in [&transport_a, &transport_b] {
+        transport
+            .configure_discovery(DiscoveryMode::Lan, false, Vec::new(), Vec::new())
+            .await
+            .expect("configure lan discovery");
+    }
+    let lan_a = LanDiscovery::spawn(&transport_a.endpoint)
+        .await
+        .expect("lan discovery a");
+    let lan_b = LanDiscovery::spawn(&transport_b.endpoint)
+        .await
+        .expect("lan discovery b");
+    let peer_id_a = transport_a.endpoint.id().to_string();
+    let peer_id_b = transport_b.endpoint.id().to_string();
+    let topic = TopicId::new("kukuri:topic:lan-discovery");
+    let (mut stream_a, mut stream_b) = tokio::try_join!(
+        transport_a.subscribe_hints(&topic),
+        transport_b.subscribe_hints(&topic)
+    )
+    .expect("subscribe both before lan discovery");
+
+    for (transport, lan, remote_peer_id) in [
+        (&transport_a, &lan_a, peer_id_b.as_str()),
+        (&transport_b, &lan_b, peer_id_a.as_str()),
+    ] {
+        let endpoint_addr = wait_for_lan_peer(lan, remote_peer_id).await;
+        transport
+            .apply_lan_peer_event(&LanPeerEvent::Discovered(endpoint_addr))
+            .await;
+    }
+    let discovery_a = transport_a.discovery().await.expect("discovery a");
+    assert_eq!(discovery_a.mode, DiscoveryMode::Lan);
+    assert_eq!(discovery_a.lan_peer_ids, vec![peer_id_b.clone()]);
+    assert!(discovery_a.manual_ticket_peer_ids.is_empty());
+
+    wait_for_hint_roundtrip(
+        HintRoundtripParticipant {
+            transport: &transport_a,
+            stream: &mut stream_a,
+            expected_source_peer: Some(peer_id_a.as_str()),
+        },
+        HintRoundtripParticipant {
+            transport: &transport_b,
+            stream: &mut stream_b,
+            expected_source_peer: Some(peer_id_b.as_str()),
+        },
+        &topic,
+        Duration::from_secs(10),
+        "lan-discovery",
+    )
+    .await;
+
+    transport_a
+        .apply_lan_peer_event(&LanPeerEvent::Expired(transport_b.endpoint.id()))
+        .await;
+    let discovery_a = transport_a.discovery().await.expect("discovery a");
+    assert!(discovery_a.lan_peer_ids.is_empty());
+    lan_a.shutdown();
+    lan_b.shutdown();
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn transport_custom_relay_static_peer_seed_peers_connect_without_ticket_import() {
     let (_relay_map, relay_url, _guard) = iroh::test_utils::run_relay_server()
@@ -0,0 +1,130 @@
+//! mDNS による LAN 内の kukuri endpoint 広告・発見(`DiscoveryMode::Lan`)。
+//!
+//! endpoint の address lookup に mDNS を足し、同一リンク上の kukuri endpoint を
+//! `LanPeerEvent` として配る。address lookup にも載るため、発見済みピアへの接続は
+//! endpoint id だけで解決できる。発見結果の配り先(gossip topic / docs-sync /
+//! blob-service のピア台帳)の配線は composition 側(desktop-runtime の stack)が持つ。
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex as StdMutex, RwLock as StdRwLock};
+
+use anyhow::{Context, Result, anyhow};
+use futures_util::StreamExt;
+use iroh::{Endpoint, EndpointAddr, EndpointId};
+use iroh_mdns_address_lookup::{DiscoveryEvent, MdnsAddressLookup};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+/// mDNS の service name(`_<name>._udp.local`)。iroh 既定の `irohv1` と分け、
+/// kukuri 以外の iroh endpoint を拾わないようにする。
+pub const LAN_DISCOVERY_SERVICE_NAME: &str = "kukuri";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LanPeerEvent {
+    Discovered(EndpointAddr),
+    Expired(EndpointId),
+}
+
+/// 1 endpoint 分の mDNS 広告・発見。clone は同じ購読を共有する。
+///
+/// address lookup は endpoint から外せないため、広告は endpoint の生存中続く。
+/// `shutdown` は発見イベントの配信だけを止める。
+#[derive(Clone)]
+pub struct LanDiscovery {
+    local_id: EndpointId,
+    peers: Arc<StdRwLock<BTreeMap<String, EndpointAddr>>>,
+    events: broadcast::Sender<LanPeerEvent>,
+    task: Arc<StdMutex<Option<JoinHandle<()>>>>,
+}
+
+impl LanDiscovery {
+    pub async fn spawn(endpoint: &Endpoint) -> Result<Self> {
+        let lookup = MdnsAddressLookup::builder()
+            .service_name(LAN_DISCOVERY_SERVICE_NAME)
+            .build(endpoint.id())
+            .context("failed to start mdns lan discovery")?;
+        endpoint
+            .address_lookup()
+            .map_err(|error| anyhow!("failed to register mdns lan discovery: {error}"))?
+            .add(lookup.clone());
+        let mut stream = lookup.subscribe().await;
+        let (events, _) = broadcast::channel(64);
+        let discovery = Self {
+            local_id: endpoint.id(),
+            peers: Arc::new(StdRwLock::new(BTreeMap::new())),
+            events,
+            task: Arc::new(StdMutex::new(None)),
+        };
+        let worker = discovery.clone();
+        // stream は endpoint が mDNS lookup を手放した時点で閉じ、task も終わる。
+        let task = tokio::spawn(async move {
+            while let Some(event) = stream.next().await {
+                worker.handle_event(event);
+            }
+            debug!("mdns lan discovery stream closed");
+        });
+        *discovery.task.lock().expect("lan discovery task poisoned") = Some(task);
+        info!(endpoint_id = %endpoint.id(), "mdns lan discovery started");
+        Ok(discovery)
+    }
+
+    /// 以後の発見・失効を購読する。既に見つかっているピアは `peers` で取る。
+    pub fn subscribe(&self) -> broadcast::Receiver<LanPeerEvent> {
+        self.events.subscribe()
+    }
+
+    pub fn peers(&self) -> Vec<EndpointAddr> {
+        self.peers
+            .read()
+            .expect("lan discovery peers poisoned")
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    pub fn shutdown(&self) {
+        if let Some(task) = self
+            .task
+            .lock()
+            .expect("lan discovery task poisoned")
+            .take()
+        {
+            task.abort();
+        }
+    }
+
+    fn handle_event(&self, event: DiscoveryEvent) {
+        match event {
+            DiscoveryEvent::Discovered { endpoint_info, .. } => {
+                let endpoint_addr = endpoint_info.into_endpoint_addr();
+                if endpoint_addr.id == self.local_id || endpoint_addr.is_empty() {
+                    return;
+                }
+                let key = endpoint_addr.id.to_string();
+                {
+                    let mut peers = self.peers.write().expect("lan discovery peers poisoned");
+                    if peers.get(key.as_str()) == Some(&endpoint_addr) {
+                        return;
+                    }
+                    peers.insert(key.clone(), endpoint_addr.clone());
+                }
+                debug!(peer_id = %key, "discovered lan peer via mdns");
+                let _ = self.events.send(LanPeerEvent::Discovered(endpoint_addr));
+            }
+            DiscoveryEvent::Expired { endpoint_id } => {
+                let removed = self
+                    .peers
+                    .write()
+                    .expect("lan discovery peers poisoned")
+                    .remove(endpoint_id.to_string().as_str())
+                    .is_some();
+                if removed {
+                    debug!(peer_id = %endpoint_id, "lan peer expired");
+                    let _ = self.events.send(LanPeerEvent::Expired(endpoint_id));
+                }
+            }
+            _ => {}
+        }
+    }
+}
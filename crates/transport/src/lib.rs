@@ -3,7 +3,7 @@
 //! ここに置くもの: gossip transport(`IrohGossipTransport`)と gossip hint 転送、
 //! endpoint 構築部品(bind / builder)、独自 ticket(`<endpoint_id>@<host:port>`)、
 //! ピア台帳(`peers` — docs-sync / blob-service が共有する接続候補とリトライ状態)、
-//! テスト用 `FakeTransport`。
+//! mDNS による LAN 内発見(`lan`)、テスト用 `FakeTransport`。
 //!
 //! ここに置かないもの: 実運用 iroh ノード全体(endpoint / gossip / router / blobs /
 //! docs)の所有権と composition は `kukuri-iroh-node`(WP-H2)。本 crate はそこへ
@@ -17,6 +17,7 @@ mod diagnostics;
 mod discovery;
 mod fake;
 mod iroh;
+mod lan;
 mod peers;
 #[cfg(test)]
 mod test_support;
@@ -27,6 +28,7 @@ pub use config::*;
 pub use discovery::*;
 pub use fake::*;
 pub use iroh::*;
+pub use lan::*;
 pub use peers::*;
 pub use tickets::*;
 pub use traits::*;